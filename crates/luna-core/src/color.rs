@@ -0,0 +1,671 @@
+//! Color parsing and manipulation utilities.
+//!
+//! This module provides functions for parsing color strings in various formats
+//! and converting between color spaces.
+
+use gpui::Hsla;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Parse a color string into an HSLA color.
+///
+/// Supports the following formats:
+/// - Hex colors: #RGB, #RGBA, #RRGGBB, #RRGGBBAA (with or without # prefix)
+/// - RGB/RGBA: rgb(r, g, b), rgba(r, g, b, a), rgb(r g b / a)
+/// - HSL/HSLA: hsl(h, s%, l%), hsla(h, s%, l%, a), hsl(h s% l% / a)
+/// - Named colors: black, white, red, etc.
+///
+/// # Examples
+///
+/// ```
+/// # use luna_core::color::parse_color;
+/// // Parse a hex color
+/// let red = parse_color("#ff0000").unwrap();
+///
+/// // Parse an RGB color
+/// let green = parse_color("rgb(0, 255, 0)").unwrap();
+///
+/// // Parse an HSLA color
+/// let blue = parse_color("hsla(240, 100%, 50%, 1.0)").unwrap();
+///
+/// // Parse a named color
+/// let black = parse_color("black").unwrap();
+/// ```
+pub fn parse_color(value: &str) -> Option<Hsla> {
+    let value = value.trim();
+    
+    // Handle transparent special case
+    if value.eq_ignore_ascii_case("transparent") {
+        return Some(Hsla { h: 0.0, s: 0.0, l: 0.0, a: 0.0 });
+    }
+    
+    // Try using the built-in functionality from gpui
+    if let Some(rgba) = parse_hex_color(value) {
+        return Some(rgba);
+    }
+    
+    // Handle RGB/RGBA format
+    if value.starts_with("rgb") {
+        return parse_rgb_color(value);
+    }
+    
+    // Handle HSL/HSLA format
+    if value.starts_with("hsl") {
+        return parse_hsla_color(value);
+    }
+    
+    // Handle named colors
+    match value.to_lowercase().as_str() {
+        "black" => Some(Hsla { h: 0.0, s: 0.0, l: 0.0, a: 1.0 }),
+        "white" => Some(Hsla { h: 0.0, s: 0.0, l: 1.0, a: 1.0 }),
+        "red" => Some(Hsla { h: 0.0, s: 1.0, l: 0.5, a: 1.0 }),
+        "green" => Some(Hsla { h: 0.33, s: 1.0, l: 0.5, a: 1.0 }),
+        "blue" => Some(Hsla { h: 0.67, s: 1.0, l: 0.5, a: 1.0 }),
+        "yellow" => Some(Hsla { h: 0.17, s: 1.0, l: 0.5, a: 1.0 }),
+        "cyan" => Some(Hsla { h: 0.5, s: 1.0, l: 0.5, a: 1.0 }),
+        "magenta" => Some(Hsla { h: 0.83, s: 1.0, l: 0.5, a: 1.0 }),
+        "gray" | "grey" => Some(Hsla { h: 0.0, s: 0.0, l: 0.5, a: 1.0 }),
+        _ => None,
+    }
+}
+
+/// Parse a color pasted from elsewhere, which may come as a whole CSS declaration
+///
+/// Hex, `rgb()` and `hsl()` values are read as [`parse_color`] reads them, with any
+/// property name in front and semicolon after left off, so copying
+/// `color: #3b82f6;` out of a stylesheet pastes the color.
+pub fn parse_pasted_color(text: &str) -> Option<Hsla> {
+    let text = text.trim().trim_end_matches(';');
+    let value = match text.split_once(':') {
+        Some((property, value)) if !property.contains('(') => value,
+        _ => text,
+    };
+    parse_color(value)
+}
+
+/// Parse a hex color into an Hsla
+fn parse_hex_color(value: &str) -> Option<Hsla> {
+    // Try using built-in TryFrom for Rgba first
+    if let Ok(rgba) = gpui::Rgba::try_from(value) {
+        return Some(rgba.into());
+    }
+    
+    // If that fails, try our own hex parsing implementation
+    let value = value.trim();
+    
+    // Normalize the hex string
+    let hex = if value.starts_with('#') {
+        &value[1..]
+    } else if !value.starts_with("rgb") && !value.starts_with("hsl") {
+        value
+    } else {
+        return None;
+    };
+    
+    // Parse based on the hex format
+    if hex.len() == 3 {
+        // Handle #RGB format
+        let r = u8::from_str_radix(&hex[0..1].repeat(2), 16).ok()? as f32 / 255.0;
+        let g = u8::from_str_radix(&hex[1..2].repeat(2), 16).ok()? as f32 / 255.0;
+        let b = u8::from_str_radix(&hex[2..3].repeat(2), 16).ok()? as f32 / 255.0;
+        
+        let rgba = gpui::Rgba { r, g, b, a: 1.0 };
+        return Some(rgba.into());
+    } else if hex.len() == 6 {
+        // Handle #RRGGBB format
+        let r = u8::from_str_radix(&hex[0..2], 16).ok()? as f32 / 255.0;
+        let g = u8::from_str_radix(&hex[2..4], 16).ok()? as f32 / 255.0;
+        let b = u8::from_str_radix(&hex[4..6], 16).ok()? as f32 / 255.0;
+        
+        let rgba = gpui::Rgba { r, g, b, a: 1.0 };
+        return Some(rgba.into());
+    } else if hex.len() == 8 {
+        // Handle #RRGGBBAA format
+        let r = u8::from_str_radix(&hex[0..2], 16).ok()? as f32 / 255.0;
+        let g = u8::from_str_radix(&hex[2..4], 16).ok()? as f32 / 255.0;
+        let b = u8::from_str_radix(&hex[4..6], 16).ok()? as f32 / 255.0;
+        let a = u8::from_str_radix(&hex[6..8], 16).ok()? as f32 / 255.0;
+        
+        let rgba = gpui::Rgba { r, g, b, a };
+        return Some(rgba.into());
+    }
+    
+    None
+}
+
+/// Parse RGB/RGBA color format
+fn parse_rgb_color(value: &str) -> Option<Hsla> {
+    let value = value.trim();
+    
+    // Get the components inside the parentheses
+    let components = if value.starts_with("rgba(") && value.ends_with(')') {
+        value.strip_prefix("rgba(")?.strip_suffix(")")?   
+    } else if value.starts_with("rgb(") && value.ends_with(')') {
+        value.strip_prefix("rgb(")?.strip_suffix(")")?   
+    } else {
+        return None;
+    };
+    
+    let parts = color_components(components);
+    
+    if parts.len() >= 3 {
+        // Parse RGB components
+        let r = parse_rgb_component(parts[0])?;
+        let g = parse_rgb_component(parts[1])?;
+        let b = parse_rgb_component(parts[2])?;
+        
+        // Parse alpha component if present
+        let a = if parts.len() > 3 {
+            parse_alpha(parts[3]).unwrap_or(1.0)
+        } else {
+            1.0
+        };
+        
+        let rgba = gpui::Rgba { r, g, b, a };
+        return Some(rgba.into());
+    }
+    
+    None
+}
+
+/// Parse HSL/HSLA color format
+fn parse_hsla_color(value: &str) -> Option<Hsla> {
+    let value = value.trim();
+    
+    // Get the components inside the parentheses, either function may leave out the
+    // alpha to be opaque
+    let content = value
+        .strip_prefix("hsla(")
+        .or_else(|| value.strip_prefix("hsl("))?
+        .strip_suffix(")")?;
+    let mut hsla_parts = color_components(content);
+    if hsla_parts.len() == 3 {
+        hsla_parts.push("1");
+    }
+    
+    // Need exactly 4 parts for hsla
+    if hsla_parts.len() == 4 {
+        // Try to parse the values
+        // h: hue (0-360 degrees)
+        // s: saturation (0-100%)
+        // l: lightness (0-100%)
+        // a: alpha (0-1)
+        if let Ok(h) = hsla_parts[0].trim().trim_end_matches("deg").parse::<f32>() {
+            // Parse saturation (remove % sign)
+            let s_str = hsla_parts[1].trim();
+            let s = if let Some(s_val) = s_str.strip_suffix("%") {
+                match s_val.parse::<f32>() {
+                    Ok(val) => val / 100.0, // Convert percentage to 0-1 range
+                    Err(_) => return None,
+                }
+            } else {
+                match s_str.parse::<f32>() {
+                    Ok(val) => val / 100.0, // Assume it's a percentage without %
+                    Err(_) => return None,
+                }
+            };
+            
+            // Parse lightness (remove % sign)
+            let l_str = hsla_parts[2].trim();
+            let l = if let Some(l_val) = l_str.strip_suffix("%") {
+                match l_val.parse::<f32>() {
+                    Ok(val) => val / 100.0, // Convert percentage to 0-1 range
+                    Err(_) => return None,
+                }
+            } else {
+                match l_str.parse::<f32>() {
+                    Ok(val) => val / 100.0, // Assume it's a percentage without %
+                    Err(_) => return None,
+                }
+            };
+            
+            // Parse alpha
+            if let Some(a) = parse_alpha(hsla_parts[3]) {
+                return Some(Hsla {
+                    h: (h / 360.0).clamp(0.0, 1.0), // Convert degrees to 0-1 range
+                    s: s.clamp(0.0, 1.0),
+                    l: l.clamp(0.0, 1.0),
+                    a: a.clamp(0.0, 1.0),
+                });
+            }
+        }
+    }
+    
+    None
+}
+
+/// Split the arguments of a color function
+///
+/// Handles both the comma separated syntax, `rgba(59, 130, 246, 0.5)`, and the space
+/// separated one with the alpha after a slash, `rgb(59 130 246 / 50%)`.
+fn color_components(content: &str) -> Vec<&str> {
+    if content.contains(',') {
+        content.split(',').collect()
+    } else {
+        content
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .filter(|part| !part.is_empty())
+            .collect()
+    }
+}
+
+/// Parse an alpha component, a number from 0 to 1 or a percentage
+fn parse_alpha(value: &str) -> Option<f32> {
+    let value = value.trim();
+    match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok().map(|v| v / 100.0),
+        None => value.parse::<f32>().ok(),
+    }
+}
+
+/// Parse a single RGB component which can be a number (0-255) or percentage
+fn parse_rgb_component(value: &str) -> Option<f32> {
+    let value = value.trim();
+    
+    if value.ends_with('%') {
+        // Handle percentage value
+        value[..value.len() - 1].parse::<f32>().ok().map(|v| v / 100.0)
+    } else {
+        // Handle numeric value (0-255)
+        value.parse::<u8>().ok().map(|v| v as f32 / 255.0)
+    }
+}
+
+/// How colors are written out for display and copying
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorFormat {
+    /// `#3b82f6`, with a trailing alpha byte for translucent colors
+    #[default]
+    Hex,
+    /// `rgb(59, 130, 246)` or `rgba(59, 130, 246, 0.5)`
+    Rgb,
+    /// `hsl(217, 91%, 60%)` or `hsla(217, 91%, 60%, 0.5)`
+    Hsl,
+}
+
+impl ColorFormat {
+    pub const ALL: [ColorFormat; 3] = [ColorFormat::Hex, ColorFormat::Rgb, ColorFormat::Hsl];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorFormat::Hex => "HEX",
+            ColorFormat::Rgb => "RGB",
+            ColorFormat::Hsl => "HSL",
+        }
+    }
+
+    /// Format a color so that [`parse_color`] reads it back
+    pub fn format(self, color: Hsla) -> String {
+        let opaque = color.a >= 1.0;
+        let alpha = format_alpha(color.a);
+
+        match self {
+            ColorFormat::Hex => {
+                let rgba = gpui::Rgba::from(color);
+                let mut hex = format!(
+                    "#{:02x}{:02x}{:02x}",
+                    channel_byte(rgba.r),
+                    channel_byte(rgba.g),
+                    channel_byte(rgba.b)
+                );
+                if !opaque {
+                    hex.push_str(&format!("{:02x}", channel_byte(rgba.a)));
+                }
+                hex
+            }
+            ColorFormat::Rgb => {
+                let rgba = gpui::Rgba::from(color);
+                let (r, g, b) = (
+                    channel_byte(rgba.r),
+                    channel_byte(rgba.g),
+                    channel_byte(rgba.b),
+                );
+                if opaque {
+                    format!("rgb({r}, {g}, {b})")
+                } else {
+                    format!("rgba({r}, {g}, {b}, {alpha})")
+                }
+            }
+            ColorFormat::Hsl => {
+                let h = (color.h * 360.0).round() as u32 % 360;
+                let s = (color.s * 100.0).round() as u32;
+                let l = (color.l * 100.0).round() as u32;
+                if opaque {
+                    format!("hsl({h}, {s}%, {l}%)")
+                } else {
+                    format!("hsla({h}, {s}%, {l}%, {alpha})")
+                }
+            }
+        }
+    }
+}
+
+fn channel_byte(channel: f32) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Alpha with at most two decimals and no trailing zeros
+fn format_alpha(alpha: f32) -> String {
+    let formatted = format!("{:.2}", alpha.clamp(0.0, 1.0));
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// Desaturate a color while keeping its perceived brightness.
+///
+/// HSL lightness treats a saturated yellow and a saturated blue as equally
+/// bright, which flattens hierarchy once hue is gone. Rec. 709 luma keeps the
+/// relative contrast between fills closer to what the eye saw in color.
+pub fn grayscale(color: Hsla) -> Hsla {
+    let rgba = gpui::Rgba::from(color);
+    let luma = 0.2126 * rgba.r + 0.7152 * rgba.g + 0.0722 * rgba.b;
+
+    Hsla {
+        h: 0.0,
+        s: 0.0,
+        l: luma.clamp(0.0, 1.0),
+        a: color.a,
+    }
+}
+
+/// A color as it might be shown with the OS asking for more contrast
+///
+/// Lightness is pushed away from the middle, so darks get darker and lights lighter,
+/// and translucent colors are made more opaque. Colors that are already black, white
+/// or opaque stay that way.
+pub fn increase_contrast(color: Hsla) -> Hsla {
+    const FACTOR: f32 = 0.6;
+    let l = if color.l < 0.5 {
+        color.l * FACTOR
+    } else {
+        1.0 - (1.0 - color.l) * FACTOR
+    };
+
+    Hsla {
+        l,
+        a: 1.0 - (1.0 - color.a) * FACTOR,
+        ..color
+    }
+}
+
+/// Number of colors kept in the recently used list
+pub const RECENT_COLOR_LIMIT: usize = 8;
+
+/// Whether two colors are identical once written out as 8-bit RGBA
+pub fn same_color(a: Hsla, b: Hsla) -> bool {
+    color_key(a) == color_key(b)
+}
+
+fn color_key(color: Hsla) -> [u8; 4] {
+    let rgba = gpui::Rgba::from(color);
+    [rgba.r, rgba.g, rgba.b, rgba.a].map(channel_byte)
+}
+
+/// Move `color` to the front of a recently used list, dropping the oldest colors once
+/// there are more than [`RECENT_COLOR_LIMIT`]
+pub fn push_recent_color(recent: &mut Vec<Hsla>, color: Hsla) {
+    recent.retain(|recent_color| !same_color(*recent_color, color));
+    recent.insert(0, color);
+    recent.truncate(RECENT_COLOR_LIMIT);
+}
+
+/// The distinct colors in `colors`, most used first
+///
+/// Colors that are the same at 8-bit precision are counted together, and ties keep
+/// the order the colors first appeared in. Fully transparent colors are skipped since
+/// they can't be told apart on a swatch.
+pub fn distinct_colors_by_usage(colors: impl IntoIterator<Item = Hsla>) -> Vec<Hsla> {
+    color_usage(colors)
+        .into_iter()
+        .map(|(color, _)| color)
+        .collect()
+}
+
+/// Like [`distinct_colors_by_usage`], with the number of times each color was used
+pub fn color_usage(colors: impl IntoIterator<Item = Hsla>) -> Vec<(Hsla, usize)> {
+    let mut counted: Vec<(Hsla, usize)> = Vec::new();
+    let mut index_by_key: HashMap<[u8; 4], usize> = HashMap::new();
+
+    for color in colors {
+        let key = color_key(color);
+        if key[3] == 0 {
+            continue;
+        }
+        match index_by_key.get(&key) {
+            Some(&index) => counted[index].1 += 1,
+            None => {
+                index_by_key.insert(key, counted.len());
+                counted.push((color, 1));
+            }
+        }
+    }
+
+    counted.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counted
+}
+
+/// Which color of an element picked colors are applied to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorTarget {
+    #[default]
+    Fill,
+    Stroke,
+}
+
+impl ColorTarget {
+    pub fn toggle(self) -> Self {
+        match self {
+            ColorTarget::Fill => ColorTarget::Stroke,
+            ColorTarget::Stroke => ColorTarget::Fill,
+        }
+    }
+}
+
+/// A color as hue, saturation and brightness, the way a color picker lays it out
+///
+/// Saturation runs across the picker's square and brightness up it. Every component is
+/// between 0 and 1. The hue is kept even where it can't be seen, so dragging through
+/// grays and back doesn't lose it the way a round trip through [`Hsla`] would.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsb {
+    pub hue: f32,
+    pub saturation: f32,
+    pub brightness: f32,
+    pub alpha: f32,
+}
+
+impl Hsb {
+    pub fn from_hsla(color: Hsla) -> Self {
+        let brightness = color.l + color.s * color.l.min(1.0 - color.l);
+        let saturation = if brightness > 0.0 {
+            2.0 * (1.0 - color.l / brightness)
+        } else {
+            0.0
+        };
+        Self {
+            hue: color.h,
+            saturation,
+            brightness,
+            alpha: color.a,
+        }
+    }
+
+    pub fn to_hsla(self) -> Hsla {
+        let l = self.brightness * (1.0 - self.saturation / 2.0);
+        let s = if l > 0.0 && l < 1.0 {
+            (self.brightness - l) / l.min(1.0 - l)
+        } else {
+            0.0
+        };
+        Hsla {
+            h: self.hue,
+            s,
+            l,
+            a: self.alpha,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{hsla, Rgba};
+
+    fn rgb(r: f32, g: f32, b: f32) -> Rgba {
+        Rgba { r, g, b, a: 1.0 }
+    }
+
+    #[test]
+    fn test_increase_contrast() {
+        let dark = increase_contrast(hsla(0.6, 0.5, 0.3, 0.5));
+        assert!(dark.l < 0.3 && dark.a > 0.5);
+        assert_eq!((dark.h, dark.s), (0.6, 0.5));
+
+        let light = increase_contrast(hsla(0.6, 0.5, 0.8, 1.0));
+        assert!(light.l > 0.8);
+        assert_eq!(light.a, 1.0);
+
+        assert_eq!(increase_contrast(Hsla::black()), Hsla::black());
+        assert_eq!(increase_contrast(Hsla::white()), Hsla::white());
+    }
+
+
+    #[test]
+    fn test_color_formats() {
+        let color = Hsla::from(rgb(59.0 / 255.0, 130.0 / 255.0, 246.0 / 255.0));
+
+        assert_eq!(ColorFormat::Hex.format(color), "#3b82f6");
+        assert_eq!(ColorFormat::Rgb.format(color), "rgb(59, 130, 246)");
+        assert_eq!(ColorFormat::Hsl.format(color), "hsl(217, 91%, 60%)");
+
+        let translucent = color.opacity(0.5);
+        assert_eq!(ColorFormat::Hex.format(translucent), "#3b82f680");
+        assert_eq!(
+            ColorFormat::Rgb.format(translucent),
+            "rgba(59, 130, 246, 0.5)"
+        );
+        assert_eq!(
+            ColorFormat::Hsl.format(translucent),
+            "hsla(217, 91%, 60%, 0.5)"
+        );
+    }
+
+    #[test]
+    fn test_formatted_colors_parse_back() {
+        let color = Hsla::from(rgb(0.8, 0.2, 0.4)).opacity(0.25);
+
+        for format in ColorFormat::ALL {
+            let parsed = parse_color(&format.format(color)).unwrap();
+            let (expected, parsed) = (Rgba::from(color), Rgba::from(parsed));
+            assert!((expected.r - parsed.r).abs() < 0.02, "{format:?}");
+            assert!((expected.g - parsed.g).abs() < 0.02, "{format:?}");
+            assert!((expected.a - parsed.a).abs() < 0.01, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_space_separated_color_functions() {
+        let expected = Rgba::from(parse_color("rgba(59, 130, 246, 0.5)").unwrap());
+
+        for value in [
+            "rgb(59 130 246 / 50%)",
+            "rgba(59 130 246 / 0.5)",
+            "hsl(217deg 91% 60% / 0.5)",
+            "#3b82f680",
+        ] {
+            let parsed = Rgba::from(parse_color(value).unwrap());
+            assert!((expected.r - parsed.r).abs() < 0.01, "{value}");
+            assert!((expected.b - parsed.b).abs() < 0.01, "{value}");
+            assert!((expected.a - parsed.a).abs() < 0.01, "{value}");
+        }
+        assert_eq!(parse_color("rgb(59 130 246)").unwrap().a, 1.0);
+    }
+
+    #[test]
+    fn test_parse_pasted_color() {
+        let blue = parse_color("#3b82f6");
+        assert_eq!(parse_pasted_color("  #3b82f6\n"), blue);
+        assert_eq!(parse_pasted_color("3b82f6"), blue);
+        let rgb = parse_pasted_color("rgb(59, 130, 246)").unwrap();
+        assert!(same_color(rgb, blue.unwrap()));
+        assert_eq!(parse_pasted_color("background-color: #3b82f6;"), blue);
+        assert!(parse_pasted_color("color: hsl(217, 91%, 60%);").is_some());
+        assert_eq!(parse_pasted_color("not a color"), None);
+    }
+
+    #[test]
+    fn test_recent_colors_move_to_front() {
+        let red = Hsla::from(rgb(1.0, 0.0, 0.0));
+        let blue = Hsla::from(rgb(0.0, 0.0, 1.0));
+        let mut recent = Vec::new();
+
+        push_recent_color(&mut recent, red);
+        push_recent_color(&mut recent, blue);
+        push_recent_color(&mut recent, red);
+        assert_eq!(recent.len(), 2);
+        assert!(same_color(recent[0], red));
+
+        for step in 0..RECENT_COLOR_LIMIT {
+            push_recent_color(&mut recent, Hsla::from(rgb(0.0, step as f32 / 10.0, 0.0)));
+        }
+        assert_eq!(recent.len(), RECENT_COLOR_LIMIT);
+        assert!(!recent.iter().any(|color| same_color(*color, red)));
+    }
+
+    #[test]
+    fn test_hsb_round_trip() {
+        let red = Hsb::from_hsla(Hsla::from(rgb(1.0, 0.0, 0.0)));
+        assert_eq!((red.saturation, red.brightness), (1.0, 1.0));
+        let gray = Hsb::from_hsla(Hsla::from(rgb(0.5, 0.5, 0.5)));
+        assert_eq!((gray.saturation, gray.brightness), (0.0, 0.5));
+        let black = Hsb::from_hsla(Hsla::from(rgb(0.0, 0.0, 0.0)));
+        assert_eq!((black.saturation, black.brightness), (0.0, 0.0));
+
+        for value in ["#3b82f6", "#3b82f680", "#ffffff", "#000000", "#7f1d1d"] {
+            let color = parse_color(value).unwrap();
+            let round_trip = Hsb::from_hsla(color).to_hsla();
+            assert!(same_color(color, round_trip), "{value}");
+        }
+
+        // A hue dragged down to black comes back once brightness does
+        let hsb = Hsb {
+            hue: 0.6,
+            saturation: 1.0,
+            brightness: 0.0,
+            alpha: 1.0,
+        };
+        assert_eq!(hsb.to_hsla().l, 0.0);
+        let blue = Hsb {
+            brightness: 1.0,
+            ..hsb
+        };
+        assert_eq!(blue.to_hsla().h, 0.6);
+    }
+
+    #[test]
+    fn test_distinct_colors_by_usage() {
+        let red = Hsla::from(rgb(1.0, 0.0, 0.0));
+        let blue = Hsla::from(rgb(0.0, 0.0, 1.0));
+        let green = Hsla::from(rgb(0.0, 1.0, 0.0));
+
+        let colors = distinct_colors_by_usage([
+            red,
+            blue,
+            green,
+            blue,
+            red.opacity(0.0),
+            // Rounding noise from a color that went through HSL and back
+            Hsla {
+                l: blue.l + 0.0001,
+                ..blue
+            },
+        ]);
+        assert_eq!(colors.len(), 3);
+        assert!(same_color(colors[0], blue));
+        assert!(same_color(colors[1], red));
+        assert!(same_color(colors[2], green));
+    }
+}
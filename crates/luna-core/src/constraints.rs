@@ -0,0 +1,797 @@
+//! # Constraint Expressions
+//!
+//! A lightweight parametric layer: a node property can be bound to an arithmetic
+//! expression over other properties, such as `parent.width * 0.5 - 16`, and is
+//! recomputed whenever the canvas changes.
+//!
+//! ## Syntax
+//!
+//! Expressions support numbers, `+ - * /`, unary minus and parentheses. A property
+//! reference is one of `x`, `y`, `width`, `height`, `corner_radius` or `border_width`,
+//! optionally prefixed by whose property it is:
+//!
+//! - `width` or `self.width`: the constrained node itself
+//! - `parent.width`: the node's parent
+//! - `#12.width`: the node with id 12
+//!
+//! Positions are relative to the parent, like the layout they are written to.
+//!
+//! ## Evaluation
+//!
+//! Every constraint writes one property slot, a `(NodeId, Property)` pair, and reads
+//! the slots its expression references. Constraints are evaluated in dependency order
+//! so a chain like `a.width = b.width` and `b.width = parent.width` settles in a single
+//! pass. A constraint that would make a slot depend on itself is rejected when it is
+//! set, since there is no order in which a cycle could be evaluated.
+
+use crate::node::NodeId;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, str::FromStr};
+
+/// A numeric node property that can be read or written by a constraint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Property {
+    X,
+    Y,
+    Width,
+    Height,
+    CornerRadius,
+    BorderWidth,
+}
+
+impl Property {
+    pub fn name(self) -> &'static str {
+        match self {
+            Property::X => "x",
+            Property::Y => "y",
+            Property::Width => "width",
+            Property::Height => "height",
+            Property::CornerRadius => "corner_radius",
+            Property::BorderWidth => "border_width",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "x" => Some(Property::X),
+            "y" => Some(Property::Y),
+            "width" => Some(Property::Width),
+            "height" => Some(Property::Height),
+            "corner_radius" => Some(Property::CornerRadius),
+            "border_width" => Some(Property::BorderWidth),
+            _ => None,
+        }
+    }
+}
+
+/// Whose property a reference reads, relative to the constrained node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subject {
+    This,
+    Parent,
+    Node(NodeId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reference {
+    pub subject: Subject,
+    pub property: Property,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// A parsed constraint expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f32),
+    Reference(Reference),
+    Negate(Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+}
+
+impl Expr {
+    /// Evaluate with `lookup` resolving references, `None` if any of them can't be
+    fn evaluate(&self, lookup: &impl Fn(Reference) -> Option<f32>) -> Option<f32> {
+        match self {
+            Expr::Number(value) => Some(*value),
+            Expr::Reference(reference) => lookup(*reference),
+            Expr::Negate(expr) => expr.evaluate(lookup).map(|value| -value),
+            Expr::Binary(lhs, op, rhs) => {
+                let lhs = lhs.evaluate(lookup)?;
+                let rhs = rhs.evaluate(lookup)?;
+                Some(match op {
+                    BinaryOp::Add => lhs + rhs,
+                    BinaryOp::Subtract => lhs - rhs,
+                    BinaryOp::Multiply => lhs * rhs,
+                    BinaryOp::Divide => lhs / rhs,
+                })
+            }
+        }
+    }
+
+    fn references(&self, references: &mut Vec<Reference>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Reference(reference) => references.push(*reference),
+            Expr::Negate(expr) => expr.references(references),
+            Expr::Binary(lhs, _, rhs) => {
+                lhs.references(references);
+                rhs.references(references);
+            }
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ConstraintError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+            end: source.len(),
+        };
+        let expr = parser.expression()?;
+        match parser.tokens.get(parser.position) {
+            None => Ok(expr),
+            Some((offset, token)) => Err(ConstraintError::Parse {
+                offset: *offset,
+                message: format!("unexpected {token}"),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstraintError {
+    /// The expression isn't valid syntax, `offset` is the byte offset of the problem
+    Parse { offset: usize, message: String },
+    /// A referenced node or its parent doesn't exist
+    UnresolvedReference(Reference),
+    /// The listed slots depend on each other in a loop
+    Cycle(Vec<(NodeId, Property)>),
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintError::Parse { offset, message } => {
+                write!(f, "{message} at position {offset}")
+            }
+            ConstraintError::UnresolvedReference(reference) => match reference.subject {
+                Subject::This => write!(f, "unknown node in {}", reference.property.name()),
+                Subject::Parent => write!(f, "node has no parent"),
+                Subject::Node(node_id) => write!(f, "no node with id {}", node_id.0),
+            },
+            ConstraintError::Cycle(slots) => {
+                let path = slots
+                    .iter()
+                    .map(|(node_id, property)| format!("#{}.{}", node_id.0, property.name()))
+                    .collect::<Vec<_>>()
+                    .join(" → ");
+                write!(f, "circular constraint: {path}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
+/// A property bound to an expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constraint {
+    pub node_id: NodeId,
+    pub property: Property,
+    pub expression: Expr,
+    /// The expression as the user wrote it, for display and editing
+    pub source: String,
+}
+
+/// Read and write access to the document that constraints are evaluated against
+pub trait ConstraintTarget {
+    fn parent(&self, node_id: NodeId) -> Option<NodeId>;
+    fn property(&self, node_id: NodeId, property: Property) -> Option<f32>;
+    fn set_property(&mut self, node_id: NodeId, property: Property, value: f32);
+}
+
+/// Every constraint in a document
+#[derive(Debug, Clone, Default)]
+pub struct ConstraintSet {
+    constraints: Vec<Constraint>,
+}
+
+impl ConstraintSet {
+    pub fn get(&self, node_id: NodeId, property: Property) -> Option<&Constraint> {
+        self.constraints
+            .iter()
+            .find(|constraint| constraint.node_id == node_id && constraint.property == property)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Constraint> {
+        self.constraints.iter()
+    }
+
+    /// Bind a property to an expression, replacing any existing constraint on it
+    ///
+    /// The set is left unchanged if the expression doesn't parse, references something
+    /// that doesn't exist, or would introduce a cycle.
+    pub fn set(
+        &mut self,
+        node_id: NodeId,
+        property: Property,
+        source: &str,
+        target: &impl ConstraintTarget,
+    ) -> Result<(), ConstraintError> {
+        let expression: Expr = source.parse()?;
+        let constraint = Constraint {
+            node_id,
+            property,
+            expression,
+            source: source.trim().to_string(),
+        };
+
+        for reference in constraint.references() {
+            if resolve(node_id, reference, target).is_none() {
+                return Err(ConstraintError::UnresolvedReference(reference));
+            }
+        }
+
+        let previous = self.remove(node_id, property);
+        self.constraints.push(constraint);
+        if let Err(err) = self.evaluation_order(target) {
+            self.constraints.pop();
+            self.constraints.extend(previous);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Remove the constraint on a property, returning it if there was one
+    pub fn remove(&mut self, node_id: NodeId, property: Property) -> Option<Constraint> {
+        let index = self.constraints.iter().position(|constraint| {
+            constraint.node_id == node_id && constraint.property == property
+        })?;
+        Some(self.constraints.remove(index))
+    }
+
+    /// Drop the constraints on a node that is being deleted
+    ///
+    /// Constraints on other nodes that reference it are kept, they stop applying until
+    /// the reference resolves again.
+    pub fn remove_node(&mut self, node_id: NodeId) {
+        self.constraints
+            .retain(|constraint| constraint.node_id != node_id);
+    }
+
+    /// Evaluate every constraint in dependency order and write the results
+    ///
+    /// Constraints with unresolved references or a non-finite result are skipped.
+    /// Returns the nodes that had a property change.
+    pub fn apply(
+        &self,
+        target: &mut impl ConstraintTarget,
+    ) -> Result<Vec<NodeId>, ConstraintError> {
+        let order = self.evaluation_order(target)?;
+
+        let mut changed = Vec::new();
+        for index in order {
+            let constraint = &self.constraints[index];
+            let value = constraint.expression.evaluate(&|reference| {
+                let (node_id, property) = resolve(constraint.node_id, reference, target)?;
+                target.property(node_id, property)
+            });
+            let Some(value) = value.filter(|value| value.is_finite()) else {
+                continue;
+            };
+
+            if target.property(constraint.node_id, constraint.property) != Some(value) {
+                target.set_property(constraint.node_id, constraint.property, value);
+                if !changed.contains(&constraint.node_id) {
+                    changed.push(constraint.node_id);
+                }
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Indices of the constraints ordered so each comes after the ones it reads from
+    fn evaluation_order(
+        &self,
+        target: &impl ConstraintTarget,
+    ) -> Result<Vec<usize>, ConstraintError> {
+        let slots: HashMap<(NodeId, Property), usize> = self
+            .constraints
+            .iter()
+            .enumerate()
+            .map(|(index, constraint)| ((constraint.node_id, constraint.property), index))
+            .collect();
+
+        // Edges only point at slots that are themselves constrained, plain property
+        // values are leaves that need no ordering
+        let dependencies: Vec<Vec<usize>> = self
+            .constraints
+            .iter()
+            .map(|constraint| {
+                constraint
+                    .references()
+                    .into_iter()
+                    .filter_map(|reference| resolve(constraint.node_id, reference, target))
+                    .filter_map(|slot| slots.get(&slot).copied())
+                    .collect()
+            })
+            .collect();
+
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            Unvisited,
+            InProgress,
+            Done,
+        }
+
+        fn visit(
+            index: usize,
+            dependencies: &[Vec<usize>],
+            marks: &mut [Mark],
+            stack: &mut Vec<usize>,
+            order: &mut Vec<usize>,
+        ) -> Result<(), Vec<usize>> {
+            match marks[index] {
+                Mark::Done => return Ok(()),
+                Mark::InProgress => {
+                    let start = stack.iter().position(|&i| i == index).unwrap_or(0);
+                    let mut cycle = stack[start..].to_vec();
+                    cycle.push(index);
+                    return Err(cycle);
+                }
+                Mark::Unvisited => {}
+            }
+
+            marks[index] = Mark::InProgress;
+            stack.push(index);
+            for &dependency in &dependencies[index] {
+                visit(dependency, dependencies, marks, stack, order)?;
+            }
+            stack.pop();
+            marks[index] = Mark::Done;
+            order.push(index);
+            Ok(())
+        }
+
+        let mut marks = vec![Mark::Unvisited; self.constraints.len()];
+        let mut order = Vec::with_capacity(self.constraints.len());
+        for index in 0..self.constraints.len() {
+            let mut stack = Vec::new();
+            visit(index, &dependencies, &mut marks, &mut stack, &mut order).map_err(|cycle| {
+                ConstraintError::Cycle(
+                    cycle
+                        .into_iter()
+                        .map(|i| (self.constraints[i].node_id, self.constraints[i].property))
+                        .collect(),
+                )
+            })?;
+        }
+
+        Ok(order)
+    }
+}
+
+impl Constraint {
+    fn references(&self) -> Vec<Reference> {
+        let mut references = Vec::new();
+        self.expression.references(&mut references);
+        references
+    }
+}
+
+/// The slot a reference points at when evaluated for `node_id`
+fn resolve(
+    node_id: NodeId,
+    reference: Reference,
+    target: &impl ConstraintTarget,
+) -> Option<(NodeId, Property)> {
+    let subject = match reference.subject {
+        Subject::This => node_id,
+        Subject::Parent => target.parent(node_id)?,
+        Subject::Node(other) => other,
+    };
+    // Probe the property so references to deleted nodes don't resolve
+    target.property(subject, reference.property)?;
+    Some((subject, reference.property))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f32),
+    Identifier(String),
+    NodeId(usize),
+    Operator(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "number {value}"),
+            Token::Identifier(name) => write!(f, "'{name}'"),
+            Token::NodeId(id) => write!(f, "'#{id}'"),
+            Token::Operator(op) => write!(f, "'{op}'"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, ConstraintError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some(&(offset, c)) = chars.peek() {
+        let take_while = |chars: &mut std::iter::Peekable<std::str::CharIndices>,
+                          predicate: fn(char) -> bool| {
+            let mut end = offset;
+            while let Some(&(index, c)) = chars.peek() {
+                if !predicate(c) {
+                    break;
+                }
+                end = index + c.len_utf8();
+                chars.next();
+            }
+            &source[offset..end]
+        };
+
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' && !follows_subject(&tokens) {
+            let text = take_while(&mut chars, |c| c.is_ascii_digit() || c == '.');
+            let value = text.parse().map_err(|_| ConstraintError::Parse {
+                offset,
+                message: format!("invalid number '{text}'"),
+            })?;
+            tokens.push((offset, Token::Number(value)));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let text = take_while(&mut chars, |c| c.is_ascii_alphanumeric() || c == '_');
+            tokens.push((offset, Token::Identifier(text.to_string())));
+        } else if c == '#' {
+            chars.next();
+            let text = take_while(&mut chars, |c| c.is_ascii_digit());
+            let id = text
+                .trim_start_matches('#')
+                .parse()
+                .map_err(|_| ConstraintError::Parse {
+                    offset,
+                    message: "expected a node id after '#'".to_string(),
+                })?;
+            tokens.push((offset, Token::NodeId(id)));
+        } else if "+-*/().".contains(c) {
+            chars.next();
+            tokens.push((offset, Token::Operator(c)));
+        } else {
+            return Err(ConstraintError::Parse {
+                offset,
+                message: format!("unexpected character '{c}'"),
+            });
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Whether a `.` at this point accesses a property rather than starting a number
+fn follows_subject(tokens: &[(usize, Token)]) -> bool {
+    matches!(
+        tokens.last(),
+        Some((_, Token::Identifier(_) | Token::NodeId(_)))
+    )
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    /// Offset reported for errors at the end of the input
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .map(|(_, token)| token.clone());
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Operator(op)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ConstraintError {
+        let offset = match self.tokens.get(self.position) {
+            Some((offset, _)) => *offset,
+            None => self.end,
+        };
+        ConstraintError::Parse {
+            offset,
+            message: message.into(),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expr, ConstraintError> {
+        let mut expr = self.term()?;
+        loop {
+            let op = if self.eat('+') {
+                BinaryOp::Add
+            } else if self.eat('-') {
+                BinaryOp::Subtract
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::Binary(Box::new(expr), op, Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, ConstraintError> {
+        let mut expr = self.unary()?;
+        loop {
+            let op = if self.eat('*') {
+                BinaryOp::Multiply
+            } else if self.eat('/') {
+                BinaryOp::Divide
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::Binary(Box::new(expr), op, Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ConstraintError> {
+        if self.eat('-') {
+            Ok(Expr::Negate(Box::new(self.unary()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, ConstraintError> {
+        if self.eat('(') {
+            let expr = self.expression()?;
+            if !self.eat(')') {
+                return Err(self.error("expected ')'"));
+            }
+            return Ok(expr);
+        }
+
+        let start = self.position;
+        let subject = match self.next() {
+            Some(Token::Number(value)) => return Ok(Expr::Number(value)),
+            Some(Token::NodeId(id)) => Some(Subject::Node(NodeId(id))),
+            Some(Token::Identifier(name)) if name == "parent" => Some(Subject::Parent),
+            Some(Token::Identifier(name)) if name == "self" => Some(Subject::This),
+            Some(Token::Identifier(_)) => None,
+            _ => {
+                self.position = start;
+                return Err(self.error("expected a number or property"));
+            }
+        };
+
+        let property_name = match subject {
+            Some(_) => {
+                if !self.eat('.') {
+                    return Err(self.error("expected '.' and a property name"));
+                }
+                match self.next() {
+                    Some(Token::Identifier(name)) => name,
+                    _ => {
+                        self.position -= 1;
+                        return Err(self.error("expected a property name"));
+                    }
+                }
+            }
+            None => match &self.tokens[start].1 {
+                Token::Identifier(name) => name.clone(),
+                _ => unreachable!(),
+            },
+        };
+
+        match Property::from_name(&property_name) {
+            Some(property) => Ok(Expr::Reference(Reference {
+                subject: subject.unwrap_or(Subject::This),
+                property,
+            })),
+            None => {
+                self.position -= 1;
+                Err(self.error(format!("unknown property '{property_name}'")))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Document {
+        parents: HashMap<NodeId, NodeId>,
+        values: HashMap<(NodeId, Property), f32>,
+    }
+
+    impl Document {
+        fn node(&mut self, id: usize, parent: Option<usize>, width: f32) -> NodeId {
+            let node_id = NodeId(id);
+            if let Some(parent) = parent {
+                self.parents.insert(node_id, NodeId(parent));
+            }
+            for property in [
+                Property::X,
+                Property::Y,
+                Property::Width,
+                Property::Height,
+                Property::CornerRadius,
+                Property::BorderWidth,
+            ] {
+                self.values.insert((node_id, property), 0.0);
+            }
+            self.values.insert((node_id, Property::Width), width);
+            node_id
+        }
+    }
+
+    impl ConstraintTarget for Document {
+        fn parent(&self, node_id: NodeId) -> Option<NodeId> {
+            self.parents.get(&node_id).copied()
+        }
+
+        fn property(&self, node_id: NodeId, property: Property) -> Option<f32> {
+            self.values.get(&(node_id, property)).copied()
+        }
+
+        fn set_property(&mut self, node_id: NodeId, property: Property, value: f32) {
+            self.values.insert((node_id, property), value);
+        }
+    }
+
+    fn reference(subject: Subject, property: Property) -> Box<Expr> {
+        Box::new(Expr::Reference(Reference { subject, property }))
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let expr: Expr = "parent.width * 0.5 - 16".parse().unwrap();
+        assert_eq!(
+            expr,
+            Expr::Binary(
+                Box::new(Expr::Binary(
+                    reference(Subject::Parent, Property::Width),
+                    BinaryOp::Multiply,
+                    Box::new(Expr::Number(0.5)),
+                )),
+                BinaryOp::Subtract,
+                Box::new(Expr::Number(16.0)),
+            )
+        );
+
+        let expr: Expr = "-(#3.height + height) / .5".parse().unwrap();
+        let Expr::Binary(lhs, BinaryOp::Divide, rhs) = expr else {
+            panic!("expected a division");
+        };
+        assert_eq!(*rhs, Expr::Number(0.5));
+        assert_eq!(
+            *lhs,
+            Expr::Negate(Box::new(Expr::Binary(
+                reference(Subject::Node(NodeId(3)), Property::Height),
+                BinaryOp::Add,
+                reference(Subject::This, Property::Height),
+            )))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let offset = |source: &str| match source.parse::<Expr>() {
+            Err(ConstraintError::Parse { offset, .. }) => offset,
+            other => panic!("expected a parse error, got {other:?}"),
+        };
+
+        assert_eq!(offset("width +"), 7);
+        assert_eq!(offset("parent.depth"), 7);
+        assert_eq!(offset("(width"), 6);
+        assert_eq!(offset("width 2"), 6);
+        assert_eq!(offset("width % 2"), 6);
+        assert_eq!(offset("#.width"), 0);
+    }
+
+    #[test]
+    fn test_apply_in_dependency_order() {
+        let mut document = Document::default();
+        let page = document.node(1, None, 400.0);
+        let card = document.node(2, Some(1), 100.0);
+        let badge = document.node(3, Some(2), 10.0);
+
+        let mut constraints = ConstraintSet::default();
+        // Added before the constraint it depends on, evaluation order must fix that
+        constraints
+            .set(badge, Property::Width, "parent.width / 4", &document)
+            .unwrap();
+        constraints
+            .set(card, Property::Width, "parent.width * 0.5 - 16", &document)
+            .unwrap();
+
+        let changed = constraints.apply(&mut document).unwrap();
+        assert_eq!(document.property(card, Property::Width), Some(184.0));
+        assert_eq!(document.property(badge, Property::Width), Some(46.0));
+        assert_eq!(changed.len(), 2);
+        assert!(!changed.contains(&page));
+
+        // Already settled, nothing to write
+        assert!(constraints.apply(&mut document).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cycles_are_rejected() {
+        let mut document = Document::default();
+        let a = document.node(1, None, 100.0);
+        let b = document.node(2, None, 100.0);
+
+        let mut constraints = ConstraintSet::default();
+        constraints
+            .set(a, Property::Width, "#2.width + 10", &document)
+            .unwrap();
+        constraints
+            .set(a, Property::Height, "width", &document)
+            .unwrap();
+
+        let err = constraints
+            .set(b, Property::Width, "#1.height", &document)
+            .unwrap_err();
+        assert!(matches!(err, ConstraintError::Cycle(ref slots) if slots.len() == 4));
+        assert!(constraints.get(b, Property::Width).is_none());
+
+        assert!(matches!(
+            constraints.set(a, Property::X, "x + 1", &document),
+            Err(ConstraintError::Cycle(_))
+        ));
+    }
+
+    #[test]
+    fn test_replacing_a_constraint_keeps_the_old_one_on_error() {
+        let mut document = Document::default();
+        let a = document.node(1, None, 100.0);
+
+        let mut constraints = ConstraintSet::default();
+        constraints
+            .set(a, Property::Height, "width / 2", &document)
+            .unwrap();
+
+        assert_eq!(
+            constraints.set(a, Property::Height, "parent.height", &document),
+            Err(ConstraintError::UnresolvedReference(Reference {
+                subject: Subject::Parent,
+                property: Property::Height,
+            }))
+        );
+        assert_eq!(
+            constraints.get(a, Property::Height).unwrap().source,
+            "width / 2"
+        );
+    }
+}
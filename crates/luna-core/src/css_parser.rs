@@ -0,0 +1,744 @@
+use crate::{
+    document_settings::DocumentSettings,
+    node::{
+        frame::{FrameNode, Overflow},
+        BlendMode, NodeCommon, NodeFactory, Shadow,
+    },
+};
+use gpui::{point, Corners, Hsla, Point};
+use smallvec::SmallVec;
+use std::collections::HashMap;
+
+/// Parses a CSS string and creates a FrameNode with the properties defined in the CSS.
+///
+/// # Arguments
+///
+/// * `css` - A string containing CSS declarations
+/// * `factory` - A NodeFactory to generate a unique ID for the new node
+///
+/// # Returns
+///
+/// * `Some(FrameNode)` if parsing was successful
+/// * `None` if there was a critical error during parsing
+///
+/// # Example
+///
+/// ```
+/// # use luna_core::{css_parser::parse_rectangle_from_css, node::NodeFactory};
+/// let css = r#"
+///     width: 100px;
+///     height: 50px;
+///     left: 10px;
+///     top: 20px;
+///     background-color: #ff0000;
+///     border-color: #000000;
+///     border-width: 2px;
+///     border-radius: 5px;
+/// "#;
+///
+/// let mut factory = NodeFactory::default();
+/// let rect = parse_rectangle_from_css(css, &mut factory).unwrap();
+/// ```
+pub fn parse_rectangle_from_css(css: &str, factory: &mut NodeFactory) -> Option<FrameNode> {
+    Some(frame_from_declarations(
+        &parse_css_declarations(css),
+        &DocumentSettings::default(),
+        factory,
+    ))
+}
+
+/// Create a FrameNode from parsed declarations, ignoring properties it has no use for
+fn frame_from_declarations(
+    properties: &HashMap<String, String>,
+    settings: &DocumentSettings,
+    factory: &mut NodeFactory,
+) -> FrameNode {
+    let mut rect = FrameNode::new(factory.next_id());
+    let length = |property: &str| {
+        properties
+            .get(property)
+            .and_then(|v| parse_length(v, settings))
+    };
+
+    // Apply properties to the rectangle
+    if let Some(width) = length("width") {
+        rect.layout_mut().width = width;
+    }
+
+    if let Some(height) = length("height") {
+        rect.layout_mut().height = height;
+    }
+
+    if let Some(x) = length("left") {
+        rect.layout_mut().x = x;
+    }
+
+    if let Some(y) = length("top") {
+        rect.layout_mut().y = y;
+    }
+
+    // `transform` moves the element without affecting layout, so the translation lands
+    // on top of the position. Percentages are of the element's own size.
+    if let Some(translation) = properties
+        .get("transform")
+        .map(|v| parse_translation(v, rect.layout().width, rect.layout().height, settings))
+    {
+        rect.layout_mut().x += translation.x;
+        rect.layout_mut().y += translation.y;
+    }
+
+    // Shorthands go first so the longhand properties can override them. Of everything
+    // `background` can hold, only its color is used.
+    if let Some(color) = properties.get("background").and_then(|v| {
+        split_top_level(v, char::is_whitespace)
+            .into_iter()
+            .find_map(parse_color)
+    }) {
+        rect.set_fill(Some(color));
+    }
+
+    if let Some(color) = properties
+        .get("background-color")
+        .and_then(|v| parse_color(v))
+    {
+        rect.set_fill(Some(color));
+    }
+
+    // `border: <width> <style> <color>` in any order, the style doesn't matter here
+    if let Some(border) = properties.get("border") {
+        let (mut color, mut width) = (rect.border_color(), rect.border_width());
+        for part in split_top_level(border, char::is_whitespace) {
+            if part == "none" {
+                width = 0.0;
+            } else if let Some(length) = parse_length(part, settings) {
+                width = length;
+            } else if let Some(parsed) = parse_color(part) {
+                color = Some(parsed);
+            }
+        }
+        rect.set_border(color, width);
+    }
+
+    if let Some(color) = properties.get("border-color").and_then(|v| parse_color(v)) {
+        rect.set_border(Some(color), rect.border_width());
+    }
+
+    if let Some(width) = length("border-width") {
+        rect.set_border(rect.border_color(), width);
+    }
+
+    // The shorthand sets every corner, the per-corner properties then override it
+    let mut radii = properties
+        .get("border-radius")
+        .and_then(|v| parse_border_radius(v, settings))
+        .unwrap_or_else(|| rect.corner_radii());
+    for (property, radius) in [
+        ("border-top-left-radius", &mut radii.top_left),
+        ("border-top-right-radius", &mut radii.top_right),
+        ("border-bottom-right-radius", &mut radii.bottom_right),
+        ("border-bottom-left-radius", &mut radii.bottom_left),
+    ] {
+        if let Some(value) = length(property) {
+            *radius = value;
+        }
+    }
+    rect.set_corner_radii(radii);
+
+    // Content that scrolls in the browser scrolls in preview
+    if let Some(overflow) = properties.get("overflow") {
+        rect.overflow = match overflow.trim() {
+            "scroll" | "auto" => Overflow::Scroll,
+            _ => Overflow::Visible,
+        };
+    }
+
+    // Opacity is a plain number or a percentage, both of which parse as lengths
+    if let Some(opacity) = length("opacity") {
+        rect.set_opacity(opacity);
+    }
+
+    if let Some(blend_mode) = properties
+        .get("mix-blend-mode")
+        .and_then(|v| BlendMode::from_css_name(v))
+    {
+        rect.set_blend_mode(blend_mode);
+    }
+
+    // Parse box-shadow
+    if let Some(shadow_str) = properties.get("box-shadow") {
+        if let Some(shadows) = parse_box_shadows(shadow_str, settings) {
+            rect.set_shadows(shadows);
+        }
+    }
+
+    rect
+}
+
+/// Parse CSS declarations into a map of property names to values
+///
+/// Values may span several lines, as long lists of shadows often do, so declarations
+/// end at semicolons rather than line breaks. Later declarations of a property win.
+fn parse_css_declarations(css: &str) -> HashMap<String, String> {
+    let mut properties = HashMap::new();
+    let css = strip_comments(css);
+
+    for declaration in split_top_level(&css, |c| c == ';') {
+        if let Some((property, value)) = declaration.split_once(':') {
+            let property = property.trim().to_lowercase();
+            let value = value.trim().trim_end_matches("!important");
+            // Collapse the line breaks and indentation of multi-line values
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            properties.insert(property, value);
+        }
+    }
+
+    properties
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut result = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        let comment = &rest[start + 2..];
+        rest = comment.find("*/").map_or("", |end| &comment[end + 2..]);
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Split `value` at separators outside of parentheses
+///
+/// Keeps function arguments together, so splitting `0 1px rgba(0, 0, 0, 0.3), 0 2px red`
+/// at commas gives two shadows rather than five pieces. Parts are trimmed and empty
+/// ones left out.
+fn split_top_level(value: &str, is_separator: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+
+    for (index, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if depth == 0 && is_separator(c) => {
+                parts.push(&value[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Add up the translations in a `transform` value
+///
+/// Other transform functions are skipped, rotations and scales have no equivalent on
+/// a frame. `width` and `height` resolve percentages, which are of the element's size.
+fn parse_translation(
+    value: &str,
+    width: f32,
+    height: f32,
+    settings: &DocumentSettings,
+) -> Point<f32> {
+    let length = |value: &str, extent: f32| match value.strip_suffix('%') {
+        Some(percent) => percent
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .map(|v| v / 100.0 * extent),
+        None => parse_length(value, settings),
+    };
+    let mut translation = point(0.0, 0.0);
+
+    for function in split_top_level(value, char::is_whitespace) {
+        let Some((name, arguments)) = function
+            .strip_suffix(')')
+            .and_then(|function| function.split_once('('))
+        else {
+            continue;
+        };
+        let arguments = split_top_level(arguments, |c| c == ',' || c.is_whitespace());
+        match (name.trim().to_lowercase().as_str(), arguments.as_slice()) {
+            ("translate", [x]) => translation.x += length(x, width).unwrap_or(0.0),
+            ("translate", [x, y]) => {
+                translation.x += length(x, width).unwrap_or(0.0);
+                translation.y += length(y, height).unwrap_or(0.0);
+            }
+            ("translatex", [x]) => translation.x += length(x, width).unwrap_or(0.0),
+            ("translatey", [y]) => translation.y += length(y, height).unwrap_or(0.0),
+            _ => {}
+        }
+    }
+
+    translation
+}
+
+/// Parse a CSS length value into a float
+///
+/// Handles units like 'px' and unitless numbers. Rem lengths are converted to pixels
+/// with the document's root font size.
+fn parse_length(value: &str, settings: &DocumentSettings) -> Option<f32> {
+    let value = value.trim();
+
+    // Handle pixel units (most common case)
+    if value.ends_with("px") {
+        value[..value.len() - 2].parse::<f32>().ok()
+    }
+    // Handle root-relative units, checked before `em` would be
+    else if let Some(rem) = value.strip_suffix("rem") {
+        rem.parse::<f32>()
+            .ok()
+            .map(|v| v * settings.pixels_per_rem())
+    }
+    // Handle percentage (convert to 0-1 range)
+    else if value.ends_with('%') {
+        value[..value.len() - 1]
+            .parse::<f32>()
+            .ok()
+            .map(|v| v / 100.0)
+    }
+    // Handle unitless values
+    else {
+        value.parse::<f32>().ok()
+    }
+}
+
+/// Parse a `border-radius` value of one to four radii
+///
+/// Radii go clockwise from the top-left corner, with missing ones copied from the
+/// opposite corner as in CSS. Elliptical corners (`8px / 4px`) use their horizontal
+/// radius.
+fn parse_border_radius(value: &str, settings: &DocumentSettings) -> Option<Corners<f32>> {
+    let horizontal = value.split('/').next()?;
+    let radii = horizontal
+        .split_whitespace()
+        .map(|v| parse_length(v, settings))
+        .collect::<Option<Vec<f32>>>()?;
+    let [top_left, top_right, bottom_right, bottom_left] = match radii[..] {
+        [all] => [all; 4],
+        [a, b] => [a, b, a, b],
+        [a, b, c] => [a, b, c, b],
+        [a, b, c, d] => [a, b, c, d],
+        _ => return None,
+    };
+    Some(Corners {
+        top_left,
+        top_right,
+        bottom_right,
+        bottom_left,
+    })
+}
+
+/// Parse a CSS color value into an Hsla
+///
+/// Supports:
+/// - Hex colors (#RGB, #RRGGBB)
+/// - RGB/RGBA format (rgb(r,g,b), rgba(r,g,b,a))
+/// - HSLA format (hsla(h,s%,l%,a))
+/// - Named colors (red, green, blue, transparent, etc.)
+fn parse_color(value: &str) -> Option<Hsla> {
+    crate::color::parse_color(value)
+}
+
+/// Parse CSS box-shadow value into a collection of Shadow objects
+///
+/// Supports multiple shadow definitions separated by commas.
+/// Each shadow includes offset-x, offset-y, blur-radius, spread-radius, and color.
+///
+/// Format: `[inset] <offset-x> <offset-y> [<blur-radius> [<spread-radius>]] [<color>]`
+///
+/// The color and `inset` can also come first, as CSS allows.
+///
+/// Example: `0px 4px 8px 2px rgba(0, 0, 0, 0.2)`
+fn parse_box_shadows(value: &str, settings: &DocumentSettings) -> Option<SmallVec<[Shadow; 1]>> {
+    let mut result = SmallVec::new();
+
+    // Split by commas to handle multiple shadow definitions, but not the commas inside
+    // color functions
+    for shadow_def in split_top_level(value, |c| c == ',') {
+        let mut inset = false;
+        let mut lengths = Vec::new();
+        let mut color = Hsla {
+            h: 0.0,
+            s: 0.0,
+            l: 0.0,
+            a: 0.32,
+        }; // Default semi-transparent black
+
+        for part in split_top_level(shadow_def, char::is_whitespace) {
+            if part.eq_ignore_ascii_case("inset") {
+                inset = true;
+            } else if let Some(length) = parse_length(part, settings) {
+                lengths.push(length);
+            } else if let Some(parsed_color) = parse_color(part) {
+                color = parsed_color;
+            }
+        }
+
+        // Need at least x and y offsets, blur and spread default to 0
+        let [x_offset, y_offset, ref rest @ ..] = lengths[..] else {
+            continue;
+        };
+        let blur_radius = rest.first().copied().unwrap_or(0.0);
+        let spread_radius = rest.get(1).copied().unwrap_or(0.0);
+
+        // Create the shadow
+        let shadow = Shadow {
+            color,
+            offset: point(x_offset, y_offset),
+            blur_radius,
+            spread_radius,
+            inset,
+        };
+
+        result.push(shadow);
+    }
+
+    if result.is_empty() {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+/// Parse a CSS file and extract multiple frame nodes
+///
+/// Each selector creates a separate FrameNode named after it, with the leading `.` or
+/// `#` of class and id selectors dropped. A rule listing several selectors applies to
+/// all of them, and rules repeating a selector add to its frame, later declarations
+/// winning as in the cascade. At-rules such as `@media` are skipped with their contents.
+/// Rem lengths are read against the root font size in `settings`.
+pub fn parse_frames_from_css_file(
+    css: &str,
+    settings: &DocumentSettings,
+    factory: &mut NodeFactory,
+) -> Vec<FrameNode> {
+    let css = strip_comments(css);
+    let mut rules: Vec<(&str, HashMap<String, String>)> = Vec::new();
+
+    for (selectors, block) in css_rules(&css) {
+        if selectors.starts_with('@') {
+            continue;
+        }
+
+        let declarations = parse_css_declarations(block);
+        for selector in split_top_level(selectors, |c| c == ',') {
+            match rules.iter_mut().find(|(existing, _)| *existing == selector) {
+                Some((_, properties)) => properties.extend(declarations.clone()),
+                None => rules.push((selector, declarations.clone())),
+            }
+        }
+    }
+
+    rules
+        .into_iter()
+        .map(|(selector, properties)| {
+            let mut frame = frame_from_declarations(&properties, settings, factory);
+            frame.set_name(Some(selector_name(selector)));
+            frame
+        })
+        .collect()
+}
+
+/// Split a stylesheet into its top-level rules, as selector text and block contents
+///
+/// Nested blocks stay inside their rule's contents. Statements without a block, like
+/// `@import`, are dropped from the selector text that follows them.
+fn css_rules(css: &str) -> Vec<(&str, &str)> {
+    let mut rules = Vec::new();
+    let mut depth = 0;
+    let (mut selector_start, mut block_start) = (0, 0);
+
+    for (index, c) in css.char_indices() {
+        match c {
+            '{' => {
+                if depth == 0 {
+                    block_start = index + 1;
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    let prelude = &css[selector_start..block_start - 1];
+                    let selectors = prelude.rsplit(';').next().unwrap_or_default().trim();
+                    rules.push((selectors, &css[block_start..index]));
+                    selector_start = index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    rules
+}
+
+/// A layer name for a selector, `button` for `.button` and `#button` alike
+///
+/// Compound selectors like `.card > .title` are kept whole.
+fn selector_name(selector: &str) -> String {
+    let name = selector.trim_start_matches(['.', '#']);
+    let simple = name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if simple && !name.is_empty() {
+        name.to_string()
+    } else {
+        selector.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ColorFormat;
+    use gpui::Rgba;
+
+    #[test]
+    fn test_parse_rectangle() {
+        let css = r#"
+            width: 100px;
+            height: 50px;
+            left: 10px;
+            top: 20px;
+            background-color: #ff0000;
+            border-color: #000000;
+            border-width: 2px;
+            border-radius: 5px;
+        "#;
+
+        let mut factory = NodeFactory::default();
+        let rect = parse_rectangle_from_css(css, &mut factory).unwrap();
+
+        assert_eq!(rect.layout().width, 100.0);
+        assert_eq!(rect.layout().height, 50.0);
+        assert_eq!(rect.layout().x, 10.0);
+        assert_eq!(rect.layout().y, 20.0);
+        assert_eq!(rect.border_width(), 2.0);
+        assert_eq!(rect.corner_radius(), 5.0);
+    }
+
+    #[test]
+    fn test_parse_border_radius() {
+        let mut factory = NodeFactory::default();
+
+        let rect = parse_rectangle_from_css("border-radius: 4px 8px;", &mut factory).unwrap();
+        assert_eq!(
+            rect.corner_radii(),
+            Corners {
+                top_left: 4.0,
+                top_right: 8.0,
+                bottom_right: 4.0,
+                bottom_left: 8.0,
+            }
+        );
+
+        let css = r#"
+            border-radius: 12px 0 6px / 2px;
+            border-bottom-left-radius: 1px;
+        "#;
+        let rect = parse_rectangle_from_css(css, &mut factory).unwrap();
+        assert_eq!(
+            rect.corner_radii(),
+            Corners {
+                top_left: 12.0,
+                top_right: 0.0,
+                bottom_right: 6.0,
+                bottom_left: 1.0,
+            }
+        );
+        assert_eq!(rect.corner_radius(), 12.0);
+
+        let rect = parse_rectangle_from_css("border-radius: 1px 2px 3px 4px 5px;", &mut factory);
+        assert_eq!(rect.unwrap().corner_radii(), Corners::all(0.0));
+    }
+
+    #[test]
+    fn test_parse_overflow() {
+        let mut factory = NodeFactory::default();
+
+        let rect = parse_rectangle_from_css("overflow: auto;", &mut factory).unwrap();
+        assert_eq!(rect.overflow, Overflow::Scroll);
+
+        let rect = parse_rectangle_from_css("width: 10px;", &mut factory).unwrap();
+        assert_eq!(rect.overflow, Overflow::Visible);
+    }
+
+    #[test]
+    fn test_parse_opacity_and_blend_mode() {
+        let mut factory = NodeFactory::default();
+
+        let css = "opacity: 0.4;\nmix-blend-mode: multiply;";
+        let rect = parse_rectangle_from_css(css, &mut factory).unwrap();
+        assert_eq!(rect.opacity(), 0.4);
+        assert_eq!(rect.blend_mode(), BlendMode::Multiply);
+
+        // Percentages work too, and modes Luna can't draw are left as normal
+        let css = "opacity: 150%;\nmix-blend-mode: hue;";
+        let rect = parse_rectangle_from_css(css, &mut factory).unwrap();
+        assert_eq!(rect.opacity(), 1.0);
+        assert_eq!(rect.blend_mode(), BlendMode::Normal);
+    }
+
+    #[test]
+    fn test_parse_shadows() {
+        let css = r#"
+            width: 100px;
+            height: 50px;
+            box-shadow: 0px 1px 1px rgba(0, 0, 0, 0.32), 0px 4px 6px rgba(0, 0, 0, 0.08);
+        "#;
+
+        let mut factory = NodeFactory::default();
+        let rect = parse_rectangle_from_css(css, &mut factory).unwrap();
+
+        // Verify shadows were parsed correctly
+        let shadows = rect.shadows();
+        assert_eq!(shadows.len(), 2);
+
+        // First shadow
+        assert_eq!(shadows[0].offset.x, 0.0);
+        assert_eq!(shadows[0].offset.y, 1.0);
+        assert_eq!(shadows[0].blur_radius, 1.0);
+        assert_eq!(shadows[0].spread_radius, 0.0);
+        assert!(shadows[0].color.a > 0.3 && shadows[0].color.a < 0.33); // Around 0.32
+
+        // Second shadow
+        assert_eq!(shadows[1].offset.x, 0.0);
+        assert_eq!(shadows[1].offset.y, 4.0);
+        assert_eq!(shadows[1].blur_radius, 6.0);
+        assert_eq!(shadows[1].spread_radius, 0.0);
+        assert!(shadows[1].color.a > 0.07 && shadows[1].color.a < 0.09); // Around 0.08
+    }
+
+    #[test]
+    fn test_parse_inset_shadows() {
+        let css = "box-shadow: inset 0px 2px 4px #000000, 0px 1px 2px #000000 inset, 0px 8px 16px #000000;";
+
+        let mut factory = NodeFactory::default();
+        let rect = parse_rectangle_from_css(css, &mut factory).unwrap();
+
+        let shadows = rect.shadows();
+        assert_eq!(shadows.len(), 3);
+        assert!(shadows[0].inset && shadows[1].inset && !shadows[2].inset);
+        assert_eq!(shadows[0].offset.y, 2.0);
+        assert_eq!(shadows[0].blur_radius, 4.0);
+        assert_eq!(shadows[1].blur_radius, 2.0);
+    }
+
+    #[test]
+    fn test_parse_multi_line_declarations_and_shorthands() {
+        let css = r#"
+            /* width: 10px; */
+            width: 82px;
+            background: url(noise.png) #292929;
+            border: 1px solid rgba(255, 255, 255, 0.5);
+            box-shadow:
+                0px 1px 1px rgba(0, 0, 0, 0.32),
+                rgba(255, 255, 255, 0.16) 0px -1px 0px;
+            transform: translate(4px, 50%) translateX(-1px);
+            height: 20px !important;
+        "#;
+
+        let mut factory = NodeFactory::default();
+        let rect = parse_rectangle_from_css(css, &mut factory).unwrap();
+
+        assert_eq!(rect.layout().width, 82.0);
+        assert_eq!(rect.layout().height, 20.0);
+        assert_eq!((rect.layout().x, rect.layout().y), (3.0, 10.0));
+        assert_eq!(rect.fill(), parse_color("#292929"));
+        assert_eq!(rect.border_width(), 1.0);
+        assert_eq!(rect.border_color().map(|color| color.a), Some(0.5));
+
+        // A leading color is fine too
+        let shadows = rect.shadows();
+        assert_eq!(shadows.len(), 2);
+        assert_eq!(shadows[1].offset.y, -1.0);
+        assert_eq!(shadows[1].color.l, 1.0);
+    }
+
+    #[test]
+    fn test_colors_round_trip_in_every_syntax() {
+        let color = parse_color("#3b82f680").unwrap();
+        let mut factory = NodeFactory::default();
+
+        let syntaxes = ColorFormat::ALL
+            .iter()
+            .map(|format| format.format(color))
+            .chain(["rgb(59 130 246 / 50%)".to_string()]);
+        for value in syntaxes {
+            let css = format!("background-color: {value};");
+            let fill = parse_rectangle_from_css(&css, &mut factory)
+                .unwrap()
+                .fill()
+                .unwrap();
+            let (expected, parsed) = (Rgba::from(color), Rgba::from(fill));
+            assert!((expected.r - parsed.r).abs() < 0.01, "{value}");
+            assert!((expected.g - parsed.g).abs() < 0.01, "{value}");
+            assert!((expected.b - parsed.b).abs() < 0.01, "{value}");
+            assert!((expected.a - parsed.a).abs() < 0.01, "{value}");
+        }
+    }
+
+    #[test]
+    fn test_parse_selector_lists_and_ids() {
+        let css = r#"
+        @import url("base.css");
+
+        .card, #hero {
+            width: 100px;
+            height: 40px;
+        }
+
+        @media (min-width: 600px) {
+            .card { width: 300px; }
+        }
+
+        .card {
+            height: 80px;
+        }
+
+        .card > .title { width: 10px; }
+        "#;
+
+        let mut factory = NodeFactory::default();
+        let frames = parse_frames_from_css_file(css, &DocumentSettings::default(), &mut factory);
+
+        let names: Vec<String> = frames.iter().map(|frame| frame.name()).collect();
+        assert_eq!(names, ["card", "hero", ".card > .title"]);
+        assert_eq!(frames[0].layout().width, 100.0);
+        assert_eq!(frames[0].layout().height, 80.0);
+        assert_eq!(frames[1].layout().height, 40.0);
+    }
+
+    #[test]
+    fn test_parse_multiple_rectangles() {
+        let css = r#"
+        .rect1 {
+            width: 100px;
+            height: 50px;
+            background-color: #ff0000;
+        }
+
+        .rect2 {
+            width: 200px;
+            height: 150px;
+            background-color: #00ff00;
+        }
+        "#;
+
+        let mut factory = NodeFactory::default();
+        let rects = parse_frames_from_css_file(css, &DocumentSettings::default(), &mut factory);
+
+        assert_eq!(rects.len(), 2);
+        assert_eq!(rects[0].layout().width, 100.0);
+        assert_eq!(rects[0].layout().height, 50.0);
+        assert_eq!(rects[1].layout().width, 200.0);
+        assert_eq!(rects[1].layout().height, 150.0);
+    }
+}
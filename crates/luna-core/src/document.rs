@@ -0,0 +1,887 @@
+//! # Documents
+//!
+//! A `.luna` file is a JSON document holding every node on the canvas along with its
+//! layout and style, the constraints between nodes, the document's settings, its guides
+//! and the viewport it was last looked at through. Colors are written as hex strings so
+//! a saved document reads and diffs well.
+//!
+//! Nodes are stored flat in the order the canvas keeps them, each with its layout
+//! relative to its parent and its children listed by id. Loading checks that those
+//! references form a tree before anything reaches the canvas.
+//!
+//! Every document records the format version it was written in. Older documents are
+//! upgraded one version at a time by the steps in [`MIGRATIONS`] as they're read, on the
+//! raw JSON so a step can see fields the current types no longer have. Documents from
+//! a newer version are refused rather than opened with whatever this build happens to
+//! understand, which would quietly drop the rest on the next save.
+//!
+//! A document is split into pages, each with nodes, constraints, guides and a viewport
+//! of its own. The first page is written where a single page document keeps them, so
+//! documents that never got a second page read the same as before pages existed. Node
+//! ids are unique across the whole document, which lets nodes move between pages.
+//!
+//! A document can be saved with a password, see [`crate::protection`]. Loading one
+//! without the right password fails with a
+//! [`ProtectionError`](crate::protection::ProtectionError) callers can ask for it on.
+
+use crate::{
+    color::{parse_color, ColorFormat},
+    constraints::Property,
+    document_settings::DocumentSettings,
+    guides::Guide,
+    layout::{AutoLayout, Pins},
+    library::{ComponentSource, LibraryLink, StyleLinks},
+    node::{
+        frame::{FrameNode, Overflow},
+        path::{Anchor, VectorPath},
+        BlendMode, NodeCommon, NodeId, NodeLayout, Shadow,
+    },
+    protection,
+};
+use anyhow::{bail, Context as _};
+use gpui::{point, Corners, Hsla, Point};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write as _,
+    path::{Path, PathBuf},
+};
+
+/// File extension of saved documents
+pub const DOCUMENT_EXTENSION: &str = "luna";
+
+/// Format version written to new documents, documents from newer versions are refused
+///
+/// Bump it whenever older builds would misread a document, adding the step that
+/// upgrades the previous version to [`MIGRATIONS`].
+pub const DOCUMENT_VERSION: u32 = 4;
+
+/// A step upgrading the JSON of a document by one format version
+pub type Migration = fn(&mut Map<String, Value>);
+
+/// Upgrade steps in order, the first takes version 1 to version 2
+pub const MIGRATIONS: [Migration; DOCUMENT_VERSION as usize - 1] =
+    [migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4];
+
+/// Version 2 can hold vector paths, which version 1 builds would draw as boxes and then
+/// lose on save. Nodes with per-corner radii no longer repeat the largest of them in
+/// `corner_radius`.
+pub fn migrate_v1_to_v2(document: &mut Map<String, Value>) {
+    let Some(nodes) = document.get_mut("nodes").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for node in nodes.iter_mut().filter_map(Value::as_object_mut) {
+        if node.contains_key("corner_radii") {
+            node.remove("corner_radius");
+        }
+    }
+}
+
+/// Version 3 documents can be password protected. Plain documents didn't change, but
+/// version 2 builds would read the nodes of a protected one as missing and open it empty.
+pub fn migrate_v2_to_v3(_document: &mut Map<String, Value>) {}
+
+/// Version 4 documents can have several pages. Single page documents didn't change, but
+/// version 3 builds would open only the first page and drop the rest on the next save.
+pub fn migrate_v3_to_v4(_document: &mut Map<String, Value>) {}
+
+/// What a page is called until it's renamed, by its position in the document
+pub fn default_page_name(index: usize) -> String {
+    format!("Page {}", index + 1)
+}
+
+/// The local path of a `file://` URL, which is how the OS hands over documents it
+/// wants opened. Percent-escapes are decoded, other kinds of URL give `None`.
+pub fn path_from_file_url(url: &str) -> Option<PathBuf> {
+    // Only local files, `file://localhost/` included
+    let path = url.strip_prefix("file://")?;
+    let path = path.strip_prefix("localhost").unwrap_or(path);
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedShadow {
+    pub color: String,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub blur_radius: f32,
+    pub spread_radius: f32,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inset: bool,
+}
+
+impl SavedShadow {
+    pub fn from_shadow(shadow: &Shadow) -> Self {
+        Self {
+            color: ColorFormat::Hex.format(shadow.color),
+            offset_x: shadow.offset.x,
+            offset_y: shadow.offset.y,
+            blur_radius: shadow.blur_radius,
+            spread_radius: shadow.spread_radius,
+            inset: shadow.inset,
+        }
+    }
+
+    /// The shadow, or `None` when its color can't be parsed
+    pub fn to_shadow(&self) -> Option<Shadow> {
+        Some(Shadow {
+            color: parse_color(&self.color)?,
+            offset: point(self.offset_x, self.offset_y),
+            blur_radius: self.blur_radius,
+            spread_radius: self.spread_radius,
+            inset: self.inset,
+        })
+    }
+}
+
+/// An anchor of a path, handles resting on it are left out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedAnchor {
+    pub x: f32,
+    pub y: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handle_in: Option<[f32; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handle_out: Option<[f32; 2]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPath {
+    pub anchors: Vec<SavedAnchor>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub closed: bool,
+}
+
+impl SavedPath {
+    pub fn from_path(path: &VectorPath) -> Self {
+        let handle = |anchor: &Anchor, handle: Point<f32>| {
+            (handle != anchor.position).then_some([handle.x, handle.y])
+        };
+        Self {
+            anchors: path
+                .anchors
+                .iter()
+                .map(|anchor| SavedAnchor {
+                    x: anchor.position.x,
+                    y: anchor.position.y,
+                    handle_in: handle(anchor, anchor.handle_in),
+                    handle_out: handle(anchor, anchor.handle_out),
+                })
+                .collect(),
+            closed: path.closed,
+        }
+    }
+
+    pub fn to_path(&self) -> VectorPath {
+        VectorPath {
+            anchors: self
+                .anchors
+                .iter()
+                .map(|anchor| {
+                    let position = point(anchor.x, anchor.y);
+                    let handle =
+                        |handle: Option<[f32; 2]>| handle.map_or(position, |[x, y]| point(x, y));
+                    Anchor {
+                        position,
+                        handle_in: handle(anchor.handle_in),
+                        handle_out: handle(anchor.handle_out),
+                    }
+                })
+                .collect(),
+            closed: self.closed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedNode {
+    pub id: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border_color: Option<String>,
+    #[serde(default)]
+    pub border_width: f32,
+    /// Left out when `corner_radii` is written
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub corner_radius: f32,
+    /// Top-left, top-right, bottom-right and bottom-left radii, only when they differ
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub corner_radii: Option<[f32; 4]>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shadows: Vec<SavedShadow>,
+    /// Left out for fully opaque nodes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f32>,
+    #[serde(default)]
+    pub blend_mode: BlendMode,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<usize>,
+    #[serde(default)]
+    pub overflow: Overflow,
+    /// Whether the node's children are hidden in the layer list
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub collapsed: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub snap_excluded: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<LibraryLink>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_source: Option<ComponentSource>,
+    #[serde(default, skip_serializing_if = "StyleLinks::is_empty")]
+    pub style_links: StyleLinks,
+    #[serde(default, skip_serializing_if = "Pins::is_default")]
+    pub pins: Pins,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_layout: Option<AutoLayout>,
+    /// In the node's own coordinates, stretched over its bounds when drawn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<SavedPath>,
+}
+
+fn is_zero(value: &f32) -> bool {
+    *value == 0.0
+}
+
+impl SavedNode {
+    pub fn from_frame(frame: &FrameNode) -> Self {
+        let hex = |color: Hsla| ColorFormat::Hex.format(color);
+        Self {
+            id: frame.id.0,
+            name: frame.name.clone(),
+            x: frame.layout.x,
+            y: frame.layout.y,
+            width: frame.layout.width,
+            height: frame.layout.height,
+            fill: frame.fill.map(hex),
+            border_color: frame.border_color.map(hex),
+            border_width: frame.border_width,
+            corner_radius: if frame.corner_radii.is_some() {
+                0.0
+            } else {
+                frame.corner_radius
+            },
+            corner_radii: frame.corner_radii.as_ref().map(|radii| {
+                [
+                    radii.top_left,
+                    radii.top_right,
+                    radii.bottom_right,
+                    radii.bottom_left,
+                ]
+            }),
+            shadows: frame.shadows.iter().map(SavedShadow::from_shadow).collect(),
+            opacity: (frame.opacity < 1.0).then_some(frame.opacity),
+            blend_mode: frame.blend_mode,
+            children: frame.children.iter().map(|child| child.0).collect(),
+            overflow: frame.overflow,
+            collapsed: frame.collapsed,
+            hidden: frame.hidden,
+            locked: frame.locked,
+            snap_excluded: frame.snap_excluded,
+            link: frame.link.clone(),
+            component: frame.component.clone(),
+            component_source: frame.component_source.clone(),
+            style_links: frame.style_links.clone(),
+            pins: frame.pins,
+            auto_layout: frame.auto_layout,
+            path: frame.path.as_ref().map(SavedPath::from_path),
+        }
+    }
+
+    pub fn to_frame(&self) -> anyhow::Result<FrameNode> {
+        let color = |value: &str| {
+            parse_color(value).with_context(|| format!("node {}: invalid color {value:?}", self.id))
+        };
+
+        let mut frame = FrameNode::new(NodeId::new(self.id));
+        frame.name = self.name.clone();
+        frame.layout = NodeLayout::new(self.x, self.y, self.width, self.height);
+        frame.fill = self.fill.as_deref().map(color).transpose()?;
+        frame.border_color = self.border_color.as_deref().map(color).transpose()?;
+        frame.border_width = self.border_width;
+        match self.corner_radii {
+            Some([top_left, top_right, bottom_right, bottom_left]) => {
+                frame.set_corner_radii(Corners {
+                    top_left,
+                    top_right,
+                    bottom_right,
+                    bottom_left,
+                })
+            }
+            None => frame.corner_radius = self.corner_radius,
+        }
+        frame.shadows = self
+            .shadows
+            .iter()
+            .map(|shadow| {
+                Ok(Shadow {
+                    color: color(&shadow.color)?,
+                    offset: point(shadow.offset_x, shadow.offset_y),
+                    blur_radius: shadow.blur_radius,
+                    spread_radius: shadow.spread_radius,
+                    inset: shadow.inset,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+        frame.set_opacity(self.opacity.unwrap_or(1.0));
+        frame.blend_mode = self.blend_mode;
+        frame.children = self.children.iter().copied().map(NodeId::new).collect();
+        frame.overflow = self.overflow;
+        frame.collapsed = self.collapsed;
+        frame.hidden = self.hidden;
+        frame.locked = self.locked;
+        frame.snap_excluded = self.snap_excluded;
+        frame.link = self.link.clone();
+        frame.component = self.component.clone();
+        frame.component_source = self.component_source.clone();
+        frame.style_links = self.style_links.clone();
+        frame.pins = self.pins;
+        frame.auto_layout = self.auto_layout;
+        frame.path = self.path.as_ref().map(SavedPath::to_path);
+        Ok(frame)
+    }
+}
+
+/// Canvas scroll position and zoom for a single document
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ViewportState {
+    pub scroll_x: f32,
+    pub scroll_y: f32,
+    pub zoom: f32,
+}
+
+/// A constraint, kept as the expression the user wrote
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedConstraint {
+    pub node: usize,
+    pub property: Property,
+    pub expression: String,
+}
+
+/// A page after the first, holding what the document itself holds for the first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPage {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<ViewportState>,
+    #[serde(default)]
+    pub nodes: Vec<SavedNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<SavedConstraint>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub guides: Vec<Guide>,
+}
+
+impl SavedPage {
+    /// An empty page
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            viewport: None,
+            nodes: Vec::new(),
+            constraints: Vec::new(),
+            guides: Vec::new(),
+        }
+    }
+
+    /// The page's nodes as frames, in the order they were saved
+    pub fn frames(&self) -> anyhow::Result<Vec<FrameNode>> {
+        self.nodes.iter().map(SavedNode::to_frame).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    pub version: u32,
+    #[serde(default)]
+    pub settings: DocumentSettings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<ViewportState>,
+    #[serde(default)]
+    pub nodes: Vec<SavedNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<SavedConstraint>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub guides: Vec<Guide>,
+    /// Name of the first page, `None` while it has the default one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_name: Option<String>,
+    /// Every page after the first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pages: Vec<SavedPage>,
+}
+
+impl Document {
+    /// A document made of `pages`, the first of them going where a single page
+    /// document keeps its nodes
+    pub fn from_pages(settings: DocumentSettings, pages: Vec<SavedPage>) -> Self {
+        let mut pages = pages.into_iter();
+        let first = pages
+            .next()
+            .unwrap_or_else(|| SavedPage::new(default_page_name(0)));
+        Self {
+            version: DOCUMENT_VERSION,
+            settings,
+            viewport: first.viewport,
+            nodes: first.nodes,
+            constraints: first.constraints,
+            guides: first.guides,
+            page_name: (first.name != default_page_name(0)).then_some(first.name),
+            pages: pages.collect(),
+        }
+    }
+
+    /// Every page of the document in order, the first included
+    pub fn into_pages(self) -> Vec<SavedPage> {
+        let first = SavedPage {
+            name: self.page_name.unwrap_or_else(|| default_page_name(0)),
+            viewport: self.viewport,
+            nodes: self.nodes,
+            constraints: self.constraints,
+            guides: self.guides,
+        };
+        std::iter::once(first).chain(self.pages).collect()
+    }
+
+    /// The page at `index` as a document of its own, for comparing it page by page
+    pub fn page(&self, index: usize) -> Option<Document> {
+        let page = self.clone().into_pages().into_iter().nth(index)?;
+        Some(Self::from_pages(self.settings, vec![page]))
+    }
+
+    /// Read the document at `path`, with the password it was protected with if it was
+    pub fn load(path: &Path, password: Option<&str>) -> anyhow::Result<Self> {
+        let contents =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let contents = if protection::is_protected(&contents) {
+            protection::open(&contents, password)
+                .with_context(|| format!("loading {}", path.display()))?
+        } else {
+            contents
+        };
+        Self::parse(&contents).with_context(|| format!("loading {}", path.display()))
+    }
+
+    /// Read a document from the contents of a `.luna` file, upgrading it to the
+    /// current format version
+    pub fn parse(contents: &str) -> anyhow::Result<Self> {
+        let mut json: Value = serde_json::from_str(contents)?;
+        let fields = json.as_object_mut().context("not a Luna document")?;
+        let version = fields
+            .get("version")
+            .and_then(Value::as_u64)
+            .context("missing format version")?;
+        if version > DOCUMENT_VERSION as u64 {
+            bail!(
+                "saved by a newer version of Luna (format version {version}, this build reads \
+                 up to {DOCUMENT_VERSION}), please upgrade Luna to open it"
+            );
+        }
+        if version == 0 {
+            bail!("unknown format version 0");
+        }
+        for migrate in &MIGRATIONS[version as usize - 1..] {
+            migrate(fields);
+        }
+        fields.insert("version".to_string(), DOCUMENT_VERSION.into());
+
+        let document: Self = serde_json::from_value(json)?;
+        document.validate()?;
+        Ok(document)
+    }
+
+    /// Write the document to `path`, encrypted with `password` when there is one
+    pub fn save(&self, path: &Path, password: Option<&str>) -> anyhow::Result<()> {
+        let mut contents = serde_json::to_string_pretty(self)?;
+        if let Some(password) = password {
+            contents = protection::seal(&contents, password, self.version)?;
+        }
+        write_atomically(path, contents.as_bytes())
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// The first page's nodes as frames, in the order they were saved
+    pub fn frames(&self) -> anyhow::Result<Vec<FrameNode>> {
+        self.nodes.iter().map(SavedNode::to_frame).collect()
+    }
+
+    /// Make sure node ids are unique across pages and children form a tree on each page
+    fn validate(&self) -> anyhow::Result<()> {
+        let mut ids = HashSet::new();
+        validate_page(&self.nodes, &self.constraints, &mut ids)?;
+        for page in &self.pages {
+            validate_page(&page.nodes, &page.constraints, &mut ids)
+                .with_context(|| format!("on page \"{}\"", page.name))?;
+        }
+        Ok(())
+    }
+}
+
+/// Replace the file at `path` without ever leaving it half written. The contents go to a
+/// temporary file beside it, which is flushed to disk and then renamed over the original,
+/// so a crash or full disk leaves the previous save in place.
+fn write_atomically(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(".{name}.{}.tmp", uuid::Uuid::new_v4()));
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&temp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        std::fs::rename(&temp, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Check the nodes of a single page, adding their ids to those of the pages before it
+fn validate_page(
+    nodes: &[SavedNode],
+    constraints: &[SavedConstraint],
+    ids: &mut HashSet<usize>,
+) -> anyhow::Result<()> {
+    let page_ids: HashSet<usize> = nodes.iter().map(|node| node.id).collect();
+    for node in nodes {
+        if !ids.insert(node.id) {
+            bail!("node {} appears more than once", node.id);
+        }
+    }
+
+    let mut parents = HashMap::new();
+    for node in nodes {
+        for &child in &node.children {
+            if !page_ids.contains(&child) {
+                bail!("node {} has a missing child {child}", node.id);
+            }
+            if parents.insert(child, node.id).is_some() {
+                bail!("node {child} has more than one parent");
+            }
+        }
+    }
+
+    // With a single parent each, walking up from any node has to reach the top
+    // level within as many steps as there are nodes
+    for node in nodes {
+        let mut current = node.id;
+        for _ in 0..=nodes.len() {
+            match parents.get(&current) {
+                Some(&parent) => current = parent,
+                None => break,
+            }
+        }
+        if parents.contains_key(&current) {
+            bail!("node {} is its own ancestor", node.id);
+        }
+    }
+
+    for constraint in constraints {
+        if !page_ids.contains(&constraint.node) {
+            bail!("constraint on missing node {}", constraint.node);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::same_color, layout::Pin};
+    use gpui::hsla;
+    use smallvec::smallvec;
+
+    fn node(id: usize, children: &[usize]) -> SavedNode {
+        let mut frame = FrameNode::with_rect(NodeId::new(id), 0.0, 0.0, 10.0, 10.0);
+        frame.children = children.iter().copied().map(NodeId::new).collect();
+        SavedNode::from_frame(&frame)
+    }
+
+    fn document(nodes: Vec<SavedNode>) -> Document {
+        Document {
+            version: DOCUMENT_VERSION,
+            settings: DocumentSettings::default(),
+            viewport: None,
+            nodes,
+            constraints: Vec::new(),
+            guides: Vec::new(),
+            page_name: None,
+            pages: Vec::new(),
+        }
+    }
+
+    fn parse(document: &Document) -> anyhow::Result<Document> {
+        Document::parse(&serde_json::to_string(document).unwrap())
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut frame = FrameNode::with_rect(NodeId::new(3), 10.0, 20.0, 300.0, 200.0);
+        frame.name = Some("Card".to_string());
+        frame.fill = Some(hsla(0.6, 0.8, 0.5, 1.0));
+        frame.border_color = None;
+        frame.corner_radius = 8.0;
+        frame.shadows = smallvec![
+            Shadow {
+                color: hsla(0.0, 0.0, 0.0, 0.25),
+                offset: point(0.0, 4.0),
+                blur_radius: 12.0,
+                spread_radius: 0.0,
+                inset: false,
+            },
+            Shadow {
+                color: hsla(0.0, 0.0, 1.0, 0.5),
+                offset: point(0.0, 1.0),
+                blur_radius: 0.0,
+                spread_radius: 0.0,
+                inset: true,
+            },
+        ];
+        frame.overflow = Overflow::Scroll;
+        frame.collapsed = true;
+        frame.locked = true;
+        frame.link = Some("https://example.com".to_string());
+        frame.auto_layout = Some(AutoLayout::default());
+        frame.children = vec![NodeId::new(4)];
+        let mut child = FrameNode::with_rect(NodeId::new(4), 5.0, 5.0, 40.0, 40.0);
+        child.hidden = true;
+        child.set_corner_radii(Corners {
+            top_left: 8.0,
+            top_right: 8.0,
+            bottom_right: 0.0,
+            bottom_left: 0.0,
+        });
+        child.opacity = 0.5;
+        child.blend_mode = BlendMode::Multiply;
+        child.pins.horizontal = Pin::Stretch;
+        let path = VectorPath {
+            anchors: vec![
+                Anchor::corner(point(0.0, 40.0)),
+                Anchor::smooth(point(20.0, 0.0), point(30.0, 0.0)),
+                Anchor::corner(point(40.0, 40.0)),
+            ],
+            closed: true,
+        };
+        child.path = Some(path.clone());
+
+        let mut saved = document(vec![
+            SavedNode::from_frame(&frame),
+            SavedNode::from_frame(&child),
+        ]);
+        saved.viewport = Some(ViewportState {
+            scroll_x: -120.0,
+            scroll_y: 40.0,
+            zoom: 2.0,
+        });
+        saved.constraints.push(SavedConstraint {
+            node: 4,
+            property: Property::Width,
+            expression: "parent.width / 2".to_string(),
+        });
+        saved.guides = vec![Guide::vertical(10.0), Guide::horizontal(220.0)];
+
+        let loaded = parse(&saved).unwrap();
+        assert_eq!(loaded, saved);
+
+        let frames = loaded.frames().unwrap();
+        let card = &frames[0];
+        assert_eq!(card.name.as_deref(), Some("Card"));
+        assert_eq!(card.layout.bounds(), frame.layout.bounds());
+        assert!(same_color(card.fill.unwrap(), frame.fill.unwrap()));
+        assert_eq!(card.border_color, None);
+        assert!(same_color(card.shadows[0].color, frame.shadows[0].color));
+        assert_eq!(card.shadows[0].offset, point(0.0, 4.0));
+        assert!(!card.shadows[0].inset && card.shadows[1].inset);
+        assert_eq!(card.overflow, Overflow::Scroll);
+        assert!(card.collapsed);
+        assert!(card.locked && !card.hidden);
+        assert!(frames[1].hidden && !frames[1].locked);
+        assert_eq!(card.corner_radii(), Corners::all(8.0));
+        assert_eq!(frames[1].corner_radii(), child.corner_radii());
+        assert_eq!(card.opacity, 1.0);
+        assert_eq!(frames[1].opacity, 0.5);
+        assert_eq!(frames[1].blend_mode, BlendMode::Multiply);
+        assert_eq!(card.link.as_deref(), Some("https://example.com"));
+        assert_eq!(card.children, vec![NodeId::new(4)]);
+        assert_eq!(card.auto_layout, Some(AutoLayout::default()));
+        assert_eq!(frames[1].auto_layout, None);
+        assert_eq!(frames[1].pins.horizontal, Pin::Stretch);
+        assert!(card.pins.is_default());
+        assert_eq!(frames[1].path, Some(path));
+        assert_eq!(card.path, None);
+        // Only the smooth anchor writes out its handles
+        let anchors = &saved.nodes[1].path.as_ref().unwrap().anchors;
+        assert_eq!(anchors[0].handle_out, None);
+        assert_eq!(anchors[1].handle_in, Some([10.0, 0.0]));
+    }
+
+    #[test]
+    fn test_minimal_document() {
+        let loaded = Document::parse(
+            r#"{ "version": 1, "nodes": [{ "id": 1, "x": 0, "y": 0, "width": 50, "height": 50 }] }"#,
+        )
+        .unwrap();
+        let frame = &loaded.frames().unwrap()[0];
+        assert_eq!(frame.fill, None);
+        assert_eq!(frame.overflow, Overflow::Visible);
+        assert_eq!(frame.opacity, 1.0);
+        assert_eq!(frame.blend_mode, BlendMode::Normal);
+        assert_eq!(loaded.settings, DocumentSettings::default());
+    }
+
+    #[test]
+    fn test_migrate_v1_to_v2() {
+        let v1 = r#"{
+            "version": 1,
+            "nodes": [
+                { "id": 1, "x": 0, "y": 0, "width": 50, "height": 50, "corner_radius": 8 },
+                {
+                    "id": 2, "x": 0, "y": 0, "width": 50, "height": 50,
+                    "corner_radius": 12, "corner_radii": [12, 0, 12, 0]
+                }
+            ]
+        }"#;
+        let mut json: Value = serde_json::from_str(v1).unwrap();
+        migrate_v1_to_v2(json.as_object_mut().unwrap());
+        assert_eq!(json["nodes"][0]["corner_radius"], 8);
+        assert_eq!(json["nodes"][1].get("corner_radius"), None);
+
+        // Reading an old document upgrades it without changing the design
+        let loaded = Document::parse(v1).unwrap();
+        assert_eq!(loaded.version, DOCUMENT_VERSION);
+        let frames = loaded.frames().unwrap();
+        assert_eq!(frames[0].corner_radii(), Corners::all(8.0));
+        assert_eq!(frames[1].corner_radius, 12.0);
+        assert_eq!(frames[1].corner_radii().top_right, 0.0);
+    }
+
+    #[test]
+    fn test_newer_documents_ask_for_an_upgrade() {
+        let newer = format!(r#"{{ "version": {}, "nodes": [] }}"#, DOCUMENT_VERSION + 1);
+        let err = Document::parse(&newer).unwrap_err().to_string();
+        assert!(err.contains("please upgrade"), "{err}");
+
+        assert!(Document::parse(r#"{ "version": 0, "nodes": [] }"#).is_err());
+        assert!(Document::parse(r#"{ "nodes": [] }"#).is_err());
+    }
+
+    #[test]
+    fn test_invalid_documents() {
+        let mut newer = document(Vec::new());
+        newer.version = DOCUMENT_VERSION + 1;
+        assert!(parse(&newer).is_err());
+
+        assert!(parse(&document(vec![node(1, &[]), node(1, &[])])).is_err());
+        assert!(parse(&document(vec![node(1, &[2])])).is_err());
+        assert!(parse(&document(vec![node(1, &[3]), node(2, &[3]), node(3, &[])])).is_err());
+        assert!(parse(&document(vec![node(1, &[2]), node(2, &[1])])).is_err());
+        assert!(parse(&document(vec![node(1, &[1])])).is_err());
+
+        let mut bad_color = node(1, &[]);
+        bad_color.fill = Some("not a color".to_string());
+        assert!(document(vec![bad_color]).frames().is_err());
+
+        assert!(parse(&document(vec![node(1, &[2]), node(2, &[])])).is_ok());
+    }
+
+    #[test]
+    fn test_pages() {
+        let mut second = SavedPage::new("Mobile".to_string());
+        second.nodes = vec![node(3, &[4]), node(4, &[])];
+        second.guides = vec![Guide::vertical(10.0)];
+        let saved = Document::from_pages(
+            DocumentSettings::default(),
+            vec![SavedPage::new(default_page_name(0)), second.clone()],
+        );
+        // The first page keeps its default name out of the file
+        assert_eq!(saved.page_name, None);
+
+        let loaded = parse(&saved).unwrap();
+        assert_eq!(loaded.pages, vec![second.clone()]);
+        let pages = loaded.clone().into_pages();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].name, "Page 1");
+        assert_eq!(pages[1], second);
+        assert_eq!(loaded.page(1).unwrap().nodes, second.nodes);
+        assert_eq!(loaded.page(2), None);
+
+        // Ids can't repeat from one page to the next, nor children reach across pages
+        let mut repeated = loaded.clone();
+        repeated.nodes = vec![node(3, &[])];
+        assert!(parse(&repeated).is_err());
+        let mut across = loaded;
+        across.nodes = vec![node(1, &[4])];
+        assert!(parse(&across).is_err());
+    }
+
+    #[test]
+    fn test_save_replaces_the_file_in_place() {
+        let dir = std::env::temp_dir().join(format!("luna-save-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("poster.luna");
+        std::fs::write(&path, "previous save").unwrap();
+
+        let saved = document(vec![node(1, &[])]);
+        saved.save(&path, None).unwrap();
+        assert_eq!(Document::load(&path, None).unwrap().nodes, saved.nodes);
+        // No temporary file is left beside the document
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_path_from_file_url() {
+        assert_eq!(
+            path_from_file_url("file:///Users/ada/Design%20System/caf%C3%A9.luna"),
+            Some(PathBuf::from("/Users/ada/Design System/café.luna"))
+        );
+        assert_eq!(
+            path_from_file_url("file://localhost/tmp/poster.luna"),
+            Some(PathBuf::from("/tmp/poster.luna"))
+        );
+        // A stray percent sign is kept as it is
+        assert_eq!(
+            path_from_file_url("file:///tmp/100%.luna"),
+            Some(PathBuf::from("/tmp/100%.luna"))
+        );
+        assert_eq!(path_from_file_url("https://example.com/poster.luna"), None);
+        assert_eq!(path_from_file_url("file://server/share/poster.luna"), None);
+    }
+}
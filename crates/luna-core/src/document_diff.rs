@@ -0,0 +1,321 @@
+//! # Document Diff
+//!
+//! Compares two versions of a document node by node. Nodes are matched by id, which a
+//! node keeps for as long as it exists, so a node that was moved, restyled or renamed
+//! shows up as changed rather than as removed and added again.
+//!
+//! Changes are described property by property as display text, the way the inspector
+//! would show each value, which is all a side-by-side comparison needs. Removed nodes
+//! keep their bounds from the older version so they can be drawn where they used to be.
+
+use crate::{
+    document::{Document, SavedNode},
+    node::NodeId,
+};
+use gpui::{Bounds, Point, Size};
+use std::collections::HashMap;
+
+/// A node that only exists in the older version
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemovedNode {
+    pub node_id: NodeId,
+    pub name: String,
+    /// In canvas coordinates, as they were in the older version
+    pub bounds: Bounds<f32>,
+}
+
+/// One property that differs between the two versions of a node
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyChange {
+    pub property: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeChange {
+    pub node_id: NodeId,
+    /// The node's name in the newer version
+    pub name: String,
+    pub properties: Vec<PropertyChange>,
+}
+
+/// How a newer version of a document differs from an older one
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentDiff {
+    /// Nodes only in the newer version, in its order
+    pub added: Vec<NodeId>,
+    /// Nodes only in the older version, in its order
+    pub removed: Vec<RemovedNode>,
+    /// Nodes in both versions that differ, in the newer version's order
+    pub changed: Vec<NodeChange>,
+}
+
+impl DocumentDiff {
+    /// Work out what changed going from `before` to `after`
+    pub fn between(before: &Document, after: &Document) -> Self {
+        let before_nodes: HashMap<usize, &SavedNode> =
+            before.nodes.iter().map(|node| (node.id, node)).collect();
+        let after_nodes: HashMap<usize, &SavedNode> =
+            after.nodes.iter().map(|node| (node.id, node)).collect();
+        let before_parents = parents(before);
+        let after_parents = parents(after);
+
+        let mut diff = Self::default();
+        for node in &after.nodes {
+            let Some(previous) = before_nodes.get(&node.id) else {
+                diff.added.push(NodeId::new(node.id));
+                continue;
+            };
+
+            let old_values = values(previous, before_parents.get(&node.id).copied(), before);
+            let new_values = values(node, after_parents.get(&node.id).copied(), after);
+            let properties: Vec<PropertyChange> = old_values
+                .into_iter()
+                .zip(new_values)
+                .filter(|((_, before), (_, after))| before != after)
+                .map(|((property, before), (_, after))| PropertyChange {
+                    property,
+                    before,
+                    after,
+                })
+                .collect();
+            if !properties.is_empty() {
+                diff.changed.push(NodeChange {
+                    node_id: NodeId::new(node.id),
+                    name: display_name(node),
+                    properties,
+                });
+            }
+        }
+
+        diff.removed = before
+            .nodes
+            .iter()
+            .filter(|node| !after_nodes.contains_key(&node.id))
+            .map(|node| RemovedNode {
+                node_id: NodeId::new(node.id),
+                name: display_name(node),
+                bounds: absolute_bounds(node, &before_parents, &before_nodes),
+            })
+            .collect();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    pub fn is_changed(&self, node_id: NodeId) -> bool {
+        self.changed.iter().any(|change| change.node_id == node_id)
+    }
+}
+
+/// Parent of every node that has one, by id
+fn parents(document: &Document) -> HashMap<usize, usize> {
+    document
+        .nodes
+        .iter()
+        .flat_map(|node| node.children.iter().map(move |&child| (child, node.id)))
+        .collect()
+}
+
+fn absolute_bounds(
+    node: &SavedNode,
+    parents: &HashMap<usize, usize>,
+    nodes: &HashMap<usize, &SavedNode>,
+) -> Bounds<f32> {
+    let mut origin = Point::new(node.x, node.y);
+    // Documents are validated to be trees when loaded, so this walk ends
+    let mut ancestor = parents.get(&node.id);
+    while let Some(parent) = ancestor.and_then(|id| nodes.get(id)) {
+        origin.x += parent.x;
+        origin.y += parent.y;
+        ancestor = parents.get(&parent.id);
+    }
+    Bounds {
+        origin,
+        size: Size::new(node.width, node.height),
+    }
+}
+
+fn display_name(node: &SavedNode) -> String {
+    node.name
+        .clone()
+        .unwrap_or_else(|| format!("Frame {}", node.id))
+}
+
+/// Everything compared about a node, as labelled display text
+///
+/// Whether a layer is collapsed in the layer list isn't part of the design, so it's
+/// left out.
+fn values(
+    node: &SavedNode,
+    parent: Option<usize>,
+    document: &Document,
+) -> Vec<(&'static str, String)> {
+    let none = || "None".to_string();
+    let yes_no = |value: bool| if value { "Yes" } else { "No" }.to_string();
+    let radius = match node.corner_radii {
+        Some(radii) => radii.map(|radius| radius.to_string()).join(" "),
+        None => node.corner_radius.to_string(),
+    };
+    let border = match (&node.border_color, node.border_width) {
+        (Some(color), width) if width > 0.0 => format!("{width} {color}"),
+        _ => none(),
+    };
+    let shadows = if node.shadows.is_empty() {
+        none()
+    } else {
+        node.shadows
+            .iter()
+            .map(|shadow| {
+                let inset = if shadow.inset { "inset " } else { "" };
+                format!(
+                    "{inset}{} {} {} {} {}",
+                    shadow.offset_x,
+                    shadow.offset_y,
+                    shadow.blur_radius,
+                    shadow.spread_radius,
+                    shadow.color
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let parent = parent
+        .and_then(|id| document.nodes.iter().find(|node| node.id == id))
+        .map_or_else(none, display_name);
+    let opacity = (node.opacity.unwrap_or(1.0) * 100.0).round();
+    let layer_order = if node.children.is_empty() {
+        none()
+    } else {
+        node.children
+            .iter()
+            .map(|child| child.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    vec![
+        ("Name", display_name(node)),
+        ("Parent", parent),
+        ("Position", format!("{}, {}", node.x, node.y)),
+        ("Size", format!("{} × {}", node.width, node.height)),
+        ("Fill", node.fill.clone().unwrap_or_else(none)),
+        ("Border", border),
+        ("Corner radius", radius),
+        ("Shadows", shadows),
+        ("Opacity", format!("{opacity}%")),
+        ("Blend mode", node.blend_mode.label().to_string()),
+        ("Overflow", format!("{:?}", node.overflow)),
+        ("Hidden", yes_no(node.hidden)),
+        ("Locked", yes_no(node.locked)),
+        ("Link", node.link.clone().unwrap_or_else(none)),
+        // Reordering children restacks them, which changes how the frame looks
+        ("Layer order", layer_order),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        document::DOCUMENT_VERSION,
+        document_settings::DocumentSettings,
+        node::{frame::FrameNode, BlendMode},
+    };
+
+    fn node(id: usize, x: f32, y: f32, children: &[usize]) -> SavedNode {
+        let mut frame = FrameNode::with_rect(NodeId::new(id), x, y, 10.0, 10.0);
+        frame.children = children.iter().copied().map(NodeId::new).collect();
+        SavedNode::from_frame(&frame)
+    }
+
+    fn document(nodes: Vec<SavedNode>) -> Document {
+        Document {
+            version: DOCUMENT_VERSION,
+            settings: DocumentSettings::default(),
+            viewport: None,
+            nodes,
+            constraints: Vec::new(),
+            guides: Vec::new(),
+            page_name: None,
+            pages: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_identical_documents_have_no_diff() {
+        let version = document(vec![node(1, 0.0, 0.0, &[2]), node(2, 5.0, 5.0, &[])]);
+        assert!(DocumentDiff::between(&version, &version.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_added_and_removed_nodes() {
+        let before = document(vec![node(1, 100.0, 50.0, &[2]), node(2, 5.0, 8.0, &[])]);
+        let after = document(vec![node(1, 100.0, 50.0, &[]), node(3, 0.0, 0.0, &[])]);
+
+        let diff = DocumentDiff::between(&before, &after);
+        assert_eq!(diff.added, [NodeId::new(3)]);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].node_id, NodeId::new(2));
+        assert_eq!(diff.removed[0].name, "Frame 2");
+        // Drawn where it was on the canvas, inside its parent
+        assert_eq!(diff.removed[0].bounds.origin, Point::new(105.0, 58.0));
+        // Losing a child changes the parent's layer order
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            diff.changed[0].properties,
+            [PropertyChange {
+                property: "Layer order",
+                before: "2".into(),
+                after: "None".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_changed_properties_side_by_side() {
+        let before = document(vec![node(1, 0.0, 0.0, &[]), node(2, 0.0, 0.0, &[])]);
+        let mut after = before.clone();
+        after.nodes[1].name = Some("Card".into());
+        after.nodes[1].width = 24.0;
+        after.nodes[1].fill = Some("#ff0000".into());
+        after.nodes[1].blend_mode = BlendMode::Multiply;
+        after.nodes[0].children = vec![2];
+
+        let diff = DocumentDiff::between(&before, &after);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert!(diff.is_changed(NodeId::new(2)));
+
+        let card = diff
+            .changed
+            .iter()
+            .find(|change| change.node_id == NodeId::new(2))
+            .unwrap();
+        assert_eq!(card.name, "Card");
+        let properties: Vec<(&str, &str, &str)> = card
+            .properties
+            .iter()
+            .map(|change| {
+                (
+                    change.property,
+                    change.before.as_str(),
+                    change.after.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            properties,
+            [
+                ("Name", "Frame 2", "Card"),
+                ("Parent", "None", "Frame 1"),
+                ("Size", "10 × 10", "24 × 10"),
+                ("Fill", "#ffffff", "#ff0000"),
+                ("Blend mode", "Normal", "Multiply"),
+            ]
+        );
+    }
+}
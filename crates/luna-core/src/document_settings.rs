@@ -0,0 +1,193 @@
+//! # Document Settings
+//!
+//! Preferences that belong to a document rather than to the app: the format colors
+//! are shown and copied in, and the unit used for dimensions. A design system specced
+//! in rem and hex can sit next to a marketing page measured in px, each displaying its
+//! values the way its developers will consume them.
+//!
+//! Settings mostly affect how values are written out. Nodes are always laid out in
+//! pixels and colors are always stored as `Hsla`. The exception is the grid, which
+//! belongs to the document because each one is designed against its own.
+//!
+//! A document can also be marked view only before it's handed to someone who should
+//! look rather than touch, like a developer implementing the design. It's a guard
+//! against accidental edits and not access control, anyone can switch it off again.
+
+use crate::{color::ColorFormat, grid::GridSettings};
+use gpui::Hsla;
+use serde::{Deserialize, Serialize};
+
+/// Root font size used to convert between px and rem when a document doesn't set one
+pub const DEFAULT_REM_BASE: f32 = 16.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DimensionUnit {
+    #[default]
+    Px,
+    Rem,
+}
+
+impl DimensionUnit {
+    pub const ALL: [DimensionUnit; 2] = [DimensionUnit::Px, DimensionUnit::Rem];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DimensionUnit::Px => "PX",
+            DimensionUnit::Rem => "REM",
+        }
+    }
+
+    pub fn suffix(self) -> &'static str {
+        match self {
+            DimensionUnit::Px => "px",
+            DimensionUnit::Rem => "rem",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentSettings {
+    pub color_format: ColorFormat,
+    pub dimension_unit: DimensionUnit,
+    /// Pixels per rem
+    pub rem_base: f32,
+    pub grid: GridSettings,
+    /// Open the document for navigating and exporting only
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub view_only: bool,
+}
+
+impl Default for DocumentSettings {
+    fn default() -> Self {
+        Self {
+            color_format: ColorFormat::default(),
+            dimension_unit: DimensionUnit::default(),
+            rem_base: DEFAULT_REM_BASE,
+            grid: GridSettings::default(),
+            view_only: false,
+        }
+    }
+}
+
+impl DocumentSettings {
+    pub fn format_color(&self, color: Hsla) -> String {
+        self.color_format.format(color)
+    }
+
+    /// Convert a pixel value into the document's unit
+    pub fn dimension(&self, pixels: f32) -> f32 {
+        match self.dimension_unit {
+            DimensionUnit::Px => pixels,
+            DimensionUnit::Rem => pixels / self.pixels_per_rem(),
+        }
+    }
+
+    /// Read a dimension typed in the document's unit back into pixels
+    ///
+    /// A px or rem suffix overrides the document's unit, so pasting "24px" into a rem
+    /// document still means 24 pixels.
+    pub fn parse_dimension(&self, text: &str) -> Option<f32> {
+        let text = text.trim();
+        let (number, unit) = if let Some(number) = text.strip_suffix("px") {
+            (number, DimensionUnit::Px)
+        } else if let Some(number) = text.strip_suffix("rem") {
+            (number, DimensionUnit::Rem)
+        } else {
+            (text, self.dimension_unit)
+        };
+
+        let value = number
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|v| v.is_finite())?;
+        Some(match unit {
+            DimensionUnit::Px => value,
+            DimensionUnit::Rem => value * self.pixels_per_rem(),
+        })
+    }
+
+    /// Pixels per rem, falling back to the default when the document's isn't usable
+    pub fn pixels_per_rem(&self) -> f32 {
+        if self.rem_base > 0.0 {
+            self.rem_base
+        } else {
+            DEFAULT_REM_BASE
+        }
+    }
+
+    /// Decimals worth showing for a dimension, rem values are fractions far more often
+    pub fn dimension_precision(&self) -> usize {
+        match self.dimension_unit {
+            DimensionUnit::Px => 1,
+            DimensionUnit::Rem => 3,
+        }
+    }
+
+    /// A pixel value in the document's unit, with its suffix and no trailing zeros
+    pub fn format_dimension(&self, pixels: f32) -> String {
+        let formatted = format!("{:.*}", self.dimension_precision(), self.dimension(pixels));
+        let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+        format!("{trimmed}{}", self.dimension_unit.suffix())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_dimension() {
+        let mut settings = DocumentSettings::default();
+        assert_eq!(settings.format_dimension(24.0), "24px");
+        assert_eq!(settings.format_dimension(12.26), "12.3px");
+
+        settings.dimension_unit = DimensionUnit::Rem;
+        assert_eq!(settings.format_dimension(24.0), "1.5rem");
+        assert_eq!(settings.format_dimension(14.0), "0.875rem");
+        assert_eq!(settings.format_dimension(160.0), "10rem");
+
+        settings.rem_base = 10.0;
+        assert_eq!(settings.format_dimension(24.0), "2.4rem");
+    }
+
+    #[test]
+    fn test_parse_dimension() {
+        let mut settings = DocumentSettings::default();
+        assert_eq!(settings.parse_dimension(" 24 "), Some(24.0));
+        assert_eq!(settings.parse_dimension("-8.5px"), Some(-8.5));
+        assert_eq!(settings.parse_dimension("1.5rem"), Some(24.0));
+        assert_eq!(settings.parse_dimension("wide"), None);
+        assert_eq!(settings.parse_dimension("inf"), None);
+
+        settings.dimension_unit = DimensionUnit::Rem;
+        settings.rem_base = 10.0;
+        assert_eq!(settings.parse_dimension("2.4"), Some(24.0));
+        assert_eq!(settings.parse_dimension("24px"), Some(24.0));
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let settings: DocumentSettings =
+            serde_json::from_str(r#"{ "dimension_unit": "rem" }"#).unwrap();
+
+        assert_eq!(settings.dimension_unit, DimensionUnit::Rem);
+        assert_eq!(settings.color_format, ColorFormat::Hex);
+        assert_eq!(settings.rem_base, DEFAULT_REM_BASE);
+        assert!(!settings.view_only);
+    }
+
+    #[test]
+    fn test_view_only_written_only_when_set() {
+        let mut settings = DocumentSettings::default();
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(!json.contains("view_only"));
+
+        settings.view_only = true;
+        let json = serde_json::to_string(&settings).unwrap();
+        let read: DocumentSettings = serde_json::from_str(&json).unwrap();
+        assert!(read.view_only);
+    }
+}
@@ -0,0 +1,162 @@
+//! # Export
+//!
+//! Turns document nodes into formats consumed outside of Luna. Exporters work from an
+//! [`ExportNode`] snapshot rather than wherever the nodes are kept, which keeps them
+//! plain functions from data to text that can be tested without a running app. Where
+//! the output ends up, the clipboard or a file, is up to the caller.
+
+use crate::node::{frame::FrameNode, path::VectorPath, BlendMode, NodeCommon, NodeId, Shadow};
+use gpui::{Bounds, Corners, Hsla, Point, Size};
+use smallvec::SmallVec;
+
+pub mod css;
+pub mod rust;
+pub mod svg;
+
+/// A node and its subtree, captured for export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportNode {
+    pub name: String,
+    /// Position relative to the parent node, or to the canvas for top-level nodes
+    pub bounds: Bounds<f32>,
+    pub fill: Option<Hsla>,
+    pub border_color: Option<Hsla>,
+    pub border_width: f32,
+    pub corner_radius: f32,
+    /// Radius of each corner when they differ, `corner_radius` is then the largest
+    pub corner_radii: Option<Corners<f32>>,
+    /// The node's own opacity, children carry theirs separately
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+    /// Drawn in order, the first on top
+    pub shadows: SmallVec<[Shadow; 1]>,
+    /// Drawn instead of the node's box, in the node's own coordinates
+    pub path: Option<VectorPath>,
+    /// Whether the node scrolls its children rather than cutting them off
+    pub scrollable: bool,
+    pub children: Vec<ExportNode>,
+}
+
+impl ExportNode {
+    /// Capture `node_id` and its visible descendants, looking nodes up through `get_node`
+    pub fn from_node<'a>(
+        node_id: NodeId,
+        get_node: &impl Fn(NodeId) -> Option<&'a FrameNode>,
+    ) -> Option<Self> {
+        let node = get_node(node_id)?;
+        let layout = node.layout();
+
+        Some(Self {
+            name: node.name(),
+            bounds: Bounds {
+                origin: Point::new(layout.x, layout.y),
+                size: Size::new(layout.width, layout.height),
+            },
+            fill: node.fill(),
+            border_color: node.border_color(),
+            border_width: node.border_width(),
+            corner_radius: node.corner_radius(),
+            corner_radii: node.corner_radii,
+            opacity: node.opacity(),
+            blend_mode: node.blend_mode(),
+            shadows: node.shadows(),
+            path: node.path.clone(),
+            scrollable: node.is_scrollable(),
+            children: node
+                .children()
+                .iter()
+                .filter(|&&child_id| get_node(child_id).is_some_and(|child| !child.hidden))
+                .filter_map(|&child_id| Self::from_node(child_id, get_node))
+                .collect(),
+        })
+    }
+
+    /// Space between this node's edges and the bounding box of its children
+    ///
+    /// Children that overflow an edge leave no padding on that side rather than a
+    /// negative one. `None` for nodes without children.
+    pub fn content_padding(&self) -> Option<Padding> {
+        let content = self
+            .children
+            .iter()
+            .map(|child| child.bounds)
+            .reduce(|a, b| a.union(&b))?;
+
+        Some(Padding {
+            top: content.origin.y.max(0.0),
+            right: (self.bounds.size.width - content.origin.x - content.size.width).max(0.0),
+            bottom: (self.bounds.size.height - content.origin.y - content.size.height).max(0.0),
+            left: content.origin.x.max(0.0),
+        })
+    }
+}
+
+/// Round to hundredths and drop trailing zeros, `-0` included
+fn number(value: f32) -> String {
+    format!("{}", (value * 100.0).round() / 100.0 + 0.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Padding {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(crate) fn node(name: &str, x: f32, y: f32, width: f32, height: f32) -> ExportNode {
+        ExportNode {
+            name: name.to_string(),
+            bounds: Bounds {
+                origin: Point::new(x, y),
+                size: Size::new(width, height),
+            },
+            fill: None,
+            border_color: None,
+            border_width: 0.0,
+            corner_radius: 0.0,
+            corner_radii: None,
+            opacity: 1.0,
+            blend_mode: BlendMode::Normal,
+            shadows: SmallVec::new(),
+            path: None,
+            scrollable: false,
+            children: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_content_padding() {
+        let mut card = node("Card", 0.0, 0.0, 200.0, 100.0);
+        assert_eq!(card.content_padding(), None);
+
+        card.children = vec![
+            node("Title", 16.0, 12.0, 100.0, 20.0),
+            node("Body", 16.0, 40.0, 168.0, 40.0),
+        ];
+        assert_eq!(
+            card.content_padding(),
+            Some(Padding {
+                top: 12.0,
+                right: 16.0,
+                bottom: 20.0,
+                left: 16.0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_overflowing_children_leave_no_padding() {
+        let mut card = node("Card", 0.0, 0.0, 100.0, 100.0);
+        card.children = vec![node("Bleed", -10.0, 20.0, 130.0, 20.0)];
+
+        let padding = card.content_padding().unwrap();
+        assert_eq!(padding.left, 0.0);
+        assert_eq!(padding.right, 0.0);
+        assert_eq!(padding.top, 20.0);
+    }
+}
@@ -0,0 +1,492 @@
+//! # CSS Export
+//!
+//! Writes frames back out as CSS, the counterpart of [`crate::css_parser`], so a
+//! stylesheet like `assets/css/buttons.css` can be loaded, reworked on the canvas and
+//! written out again.
+//!
+//! Every frame becomes a class rule named after its layer and absolutely positioned
+//! with `left` and `top`. Rules can't nest, so a frame's children follow it with rules
+//! of their own, positioned within it and marked with a comment naming it. Frames
+//! draw their border inside their edges, which `box-sizing: border-box` matches, and
+//! clip their children, which `overflow: hidden` does, unless they scroll them.
+//!
+//! When asked for through [`CssOptions`], the colors are given again as
+//! [`increase_contrast`] shows them, in a `prefers-contrast: more` block after the
+//! frames. It's the same adjustment preview simulates, so it's only written for designs
+//! that have been checked with it.
+//!
+//! Colors are written in the document's color format and lengths in its unit, px or rem
+//! against its root font size, both of which the parser reads back.
+
+use super::{
+    number,
+    rust::{module_name, unique_name},
+    ExportNode,
+};
+use crate::{
+    color::{increase_contrast, same_color},
+    document_settings::{DimensionUnit, DocumentSettings},
+    node::{BlendMode, Shadow},
+};
+use std::{collections::HashSet, fmt::Write as _};
+
+const INDENT: &str = "    ";
+
+/// What's written besides the frames' own rules
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CssOptions {
+    /// Follow the rules with the colors `prefers-contrast: more` should use
+    pub increased_contrast: bool,
+}
+
+/// A rule inside an `@media` block, the class it's for and its declarations
+type MediaRule = (String, Vec<(&'static str, String)>);
+
+/// Generate a stylesheet with a rule for each of the given frames and their children
+pub fn export(frames: &[ExportNode], settings: &DocumentSettings, options: CssOptions) -> String {
+    let mut out = String::new();
+    // Class names are global in CSS, so they're unique across the whole stylesheet
+    // rather than among siblings
+    let mut used_names = HashSet::new();
+    // Collected while the frames are written and put after them, so they win over the
+    // rules they adjust
+    let mut contrast_rules = options.increased_contrast.then(Vec::new);
+    for frame in frames {
+        write_rules(
+            &mut out,
+            frame,
+            None,
+            settings,
+            &mut used_names,
+            contrast_rules.as_mut(),
+        );
+    }
+
+    if let Some(contrast_rules) = contrast_rules {
+        write_media_block(&mut out, "prefers-contrast: more", contrast_rules);
+    }
+    out
+}
+
+fn write_rules(
+    out: &mut String,
+    node: &ExportNode,
+    parent: Option<&str>,
+    settings: &DocumentSettings,
+    used_names: &mut HashSet<String>,
+    mut contrast_rules: Option<&mut Vec<MediaRule>>,
+) {
+    let class = class_name(unique_name(module_name(&node.name), used_names));
+
+    let mut declarations = vec![
+        ("position", "absolute".to_string()),
+        ("left", length(node.bounds.origin.x, settings)),
+        ("top", length(node.bounds.origin.y, settings)),
+        ("width", length(node.bounds.size.width, settings)),
+        ("height", length(node.bounds.size.height, settings)),
+        ("box-sizing", "border-box".to_string()),
+    ];
+    if let Some(fill) = node.fill {
+        declarations.push(("background-color", settings.format_color(fill)));
+    }
+    if let Some(border_color) = node.border_color.filter(|_| node.border_width > 0.0) {
+        let border = format!(
+            "{} solid {}",
+            length(node.border_width, settings),
+            settings.format_color(border_color)
+        );
+        declarations.push(("border", border));
+    }
+    match node.corner_radii {
+        Some(radii) => {
+            let radii = [
+                radii.top_left,
+                radii.top_right,
+                radii.bottom_right,
+                radii.bottom_left,
+            ];
+            let radii: Vec<String> = radii
+                .into_iter()
+                .map(|radius| length(radius, settings))
+                .collect();
+            declarations.push(("border-radius", radii.join(" ")));
+        }
+        None if node.corner_radius > 0.0 => {
+            declarations.push(("border-radius", length(node.corner_radius, settings)));
+        }
+        None => {}
+    }
+    if node.opacity < 1.0 {
+        declarations.push(("opacity", number(node.opacity)));
+    }
+    if node.blend_mode != BlendMode::Normal {
+        declarations.push(("mix-blend-mode", node.blend_mode.css_name().to_string()));
+    }
+    if node.scrollable {
+        declarations.push(("overflow", "auto".to_string()));
+    } else if !node.children.is_empty() {
+        declarations.push(("overflow", "hidden".to_string()));
+    }
+    if let Some(contrast_rules) = contrast_rules.as_deref_mut() {
+        let contrast = contrast_declarations(node, settings);
+        if !contrast.is_empty() {
+            contrast_rules.push((class.clone(), contrast));
+        }
+    }
+
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    if let Some(parent) = parent {
+        let _ = writeln!(out, "/* Inside .{parent} */");
+    }
+    let _ = writeln!(out, ".{class} {{");
+    for (property, value) in declarations {
+        let _ = writeln!(out, "{INDENT}{property}: {value};");
+    }
+    // Several shadows get a line each, the way they're usually written by hand
+    match node.shadows.as_slice() {
+        [] => {}
+        [shadow] => {
+            let _ = writeln!(
+                out,
+                "{INDENT}box-shadow: {};",
+                shadow_value(shadow, settings)
+            );
+        }
+        shadows => {
+            let _ = writeln!(out, "{INDENT}box-shadow:");
+            let separator = format!(",\n{INDENT}{INDENT}");
+            let shadows: Vec<String> = shadows
+                .iter()
+                .map(|shadow| shadow_value(shadow, settings))
+                .collect();
+            let _ = writeln!(out, "{INDENT}{INDENT}{};", shadows.join(&separator));
+        }
+    }
+    out.push_str("}\n");
+
+    for child in &node.children {
+        write_rules(
+            out,
+            child,
+            Some(&class),
+            settings,
+            used_names,
+            contrast_rules.as_deref_mut(),
+        );
+    }
+}
+
+/// The colors of `node` that [`increase_contrast`] changes, as they'd be declared
+fn contrast_declarations(
+    node: &ExportNode,
+    settings: &DocumentSettings,
+) -> Vec<(&'static str, String)> {
+    let mut declarations = Vec::new();
+    if let Some(fill) = node.fill {
+        let contrasted = increase_contrast(fill);
+        if !same_color(fill, contrasted) {
+            declarations.push(("background-color", settings.format_color(contrasted)));
+        }
+    }
+    if let Some(border_color) = node.border_color.filter(|_| node.border_width > 0.0) {
+        let contrasted = increase_contrast(border_color);
+        if !same_color(border_color, contrasted) {
+            declarations.push(("border-color", settings.format_color(contrasted)));
+        }
+    }
+    let shadows: Vec<Shadow> = node
+        .shadows
+        .iter()
+        .map(|shadow| Shadow {
+            color: increase_contrast(shadow.color),
+            ..shadow.clone()
+        })
+        .collect();
+    let changed = shadows
+        .iter()
+        .zip(&node.shadows)
+        .any(|(contrasted, shadow)| !same_color(contrasted.color, shadow.color));
+    if changed {
+        let shadows: Vec<String> = shadows
+            .iter()
+            .map(|shadow| shadow_value(shadow, settings))
+            .collect();
+        declarations.push(("box-shadow", shadows.join(", ")));
+    }
+    declarations
+}
+
+/// An `@media` block around a rule for each class, left out when there are none
+fn write_media_block(out: &mut String, condition: &str, rules: Vec<MediaRule>) {
+    if rules.is_empty() {
+        return;
+    }
+
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    let _ = writeln!(out, "@media ({condition}) {{");
+    for (index, (class, declarations)) in rules.into_iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "{INDENT}.{class} {{");
+        for (property, value) in declarations {
+            let _ = writeln!(out, "{INDENT}{INDENT}{property}: {value};");
+        }
+        let _ = writeln!(out, "{INDENT}}}");
+    }
+    out.push_str("}\n");
+}
+
+/// Turn a layer name into a kebab-case class name
+fn class_name(module_name: String) -> String {
+    // Keywords get a trailing underscore as modules, which classes have no need for
+    module_name.trim_end_matches('_').replace('_', "-")
+}
+
+/// One shadow of a `box-shadow` value, leaving out the spread when there's none
+fn shadow_value(shadow: &Shadow, settings: &DocumentSettings) -> String {
+    let mut lengths = vec![shadow.offset.x, shadow.offset.y, shadow.blur_radius];
+    if shadow.spread_radius != 0.0 {
+        lengths.push(shadow.spread_radius);
+    }
+    let lengths: Vec<String> = lengths
+        .into_iter()
+        .map(|value| length(value, settings))
+        .collect();
+    let inset = if shadow.inset { "inset " } else { "" };
+    format!(
+        "{inset}{} {}",
+        lengths.join(" "),
+        settings.format_color(shadow.color)
+    )
+}
+
+/// A length in the document's unit
+fn length(pixels: f32, settings: &DocumentSettings) -> String {
+    match settings.dimension_unit {
+        DimensionUnit::Px => format!("{}px", number(pixels)),
+        // Rem values are fractions far more often, so they keep the extra decimal the
+        // inspector shows them with
+        DimensionUnit::Rem => settings.format_dimension(pixels),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::{parse_color, ColorFormat},
+        css_parser::parse_frames_from_css_file,
+        document_settings::DEFAULT_REM_BASE,
+        export::tests::node,
+        node::{NodeCommon, NodeFactory},
+    };
+    use gpui::{point, Corners, Hsla, Rgba};
+    use smallvec::smallvec;
+
+    fn assert_same_color(a: Option<Hsla>, b: Option<Hsla>) {
+        let (a, b) = (Rgba::from(a.unwrap()), Rgba::from(b.unwrap()));
+        assert!(
+            (a.r - b.r).abs() < 0.01 && (a.g - b.g).abs() < 0.01,
+            "{a:?} {b:?}"
+        );
+        assert!(
+            (a.b - b.b).abs() < 0.01 && (a.a - b.a).abs() < 0.01,
+            "{a:?} {b:?}"
+        );
+    }
+
+    #[test]
+    fn test_export_rules() {
+        let mut card = node("Card", 100.0, 40.0, 200.0, 120.0);
+        card.fill = Some(Hsla::white());
+        card.corner_radii = Some(Corners {
+            top_left: 8.0,
+            top_right: 8.0,
+            bottom_right: 0.0,
+            bottom_left: 0.0,
+        });
+        card.corner_radius = 8.0;
+        card.children = vec![
+            node("Title", 16.0, 12.5, 168.0, 20.0),
+            node("Card", 0.0, 0.0, 1.0, 1.0),
+        ];
+
+        let css = export(&[card], &DocumentSettings::default(), CssOptions::default());
+        assert!(css.starts_with(".card {\n    position: absolute;\n    left: 100px;\n"));
+        assert!(css.contains("    background-color: #ffffff;\n"));
+        assert!(css.contains("    border-radius: 8px 8px 0px 0px;\n"));
+        assert!(css.contains("    overflow: hidden;\n"));
+        assert!(css.contains("\n/* Inside .card */\n.title {\n"));
+        assert!(css.contains("    top: 12.5px;\n"));
+        // Class names are unique across the stylesheet, not just among siblings
+        assert!(css.contains(".card-2 {"));
+        assert!(!css.contains("border:"));
+    }
+
+    #[test]
+    fn test_export_shadows() {
+        let black = Hsla::black().opacity(0.32);
+        let mut button = node("Button", 0.0, 0.0, 82.0, 23.0);
+        button.shadows = smallvec![Shadow {
+            color: black,
+            offset: point(0.0, 1.0),
+            blur_radius: 1.0,
+            spread_radius: 0.0,
+            inset: false,
+        }];
+
+        let settings = DocumentSettings {
+            color_format: ColorFormat::Rgb,
+            ..Default::default()
+        };
+        let css = export(&[button.clone()], &settings, CssOptions::default());
+        assert!(css.contains("    box-shadow: 0px 1px 1px rgba(0, 0, 0, 0.32);\n"));
+
+        button.shadows.push(Shadow {
+            spread_radius: -2.0,
+            inset: true,
+            ..button.shadows[0].clone()
+        });
+        let css = export(&[button], &settings, CssOptions::default());
+        assert!(css.contains(
+            "    box-shadow:\n        0px 1px 1px rgba(0, 0, 0, 0.32),\n        inset 0px 1px 1px -2px rgba(0, 0, 0, 0.32);\n"
+        ));
+    }
+
+    #[test]
+    fn test_export_increased_contrast() {
+        let mut feed = node("Feed", 0.0, 0.0, 320.0, 480.0);
+        feed.scrollable = true;
+        feed.fill = Some(Hsla::white());
+        let mut caption = node("Caption", 16.0, 16.0, 200.0, 20.0);
+        caption.fill = parse_color("#808080");
+        feed.children = vec![caption];
+
+        let settings = DocumentSettings::default();
+        let css = export(&[feed.clone()], &settings, CssOptions::default());
+        assert!(css.contains("    overflow: auto;\n}\n"));
+        assert!(!css.contains("@media"));
+
+        let options = CssOptions {
+            increased_contrast: true,
+        };
+        let css = export(&[feed.clone()], &settings, options);
+        // White can't get any lighter, so only the caption is given again
+        let contrast = &css[css.find("@media (prefers-contrast: more) {").unwrap()..];
+        assert!(contrast.contains("    .caption {\n        background-color: #"));
+        assert!(!contrast.contains(".feed"));
+
+        // Nothing to adjust, so no block either
+        feed.children.clear();
+        let css = export(&[feed], &settings, options);
+        assert!(!css.contains("@media"));
+    }
+
+    #[test]
+    fn test_round_trip_through_the_parser() {
+        let mut button = node("Button Outer", 200.0, 200.0, 88.0, 29.0);
+        button.fill = parse_color("#292929");
+        button.border_color = parse_color("rgba(255, 255, 255, 0.03)");
+        button.border_width = 1.0;
+        button.corner_radius = 12.0;
+        button.opacity = 0.8;
+        button.blend_mode = BlendMode::Screen;
+        button.shadows = smallvec![
+            Shadow {
+                color: Hsla::black().opacity(0.32),
+                offset: point(0.0, 1.0),
+                blur_radius: 1.0,
+                spread_radius: 0.0,
+                inset: false,
+            },
+            Shadow {
+                color: Hsla::white().opacity(0.16),
+                offset: point(0.0, -1.0),
+                blur_radius: 0.0,
+                spread_radius: 2.0,
+                inset: true,
+            },
+        ];
+        let mut radii = node("Tab", 10.0, 20.0, 30.0, 40.0);
+        radii.corner_radii = Some(Corners {
+            top_left: 6.0,
+            top_right: 6.0,
+            bottom_right: 0.0,
+            bottom_left: 0.0,
+        });
+
+        // Every color format in px, then rem against the default and a custom root font
+        // size. Rem lengths are rounded to a thousandth, so they come back close to what
+        // was exported rather than exactly.
+        let mut all_settings: Vec<DocumentSettings> = ColorFormat::ALL
+            .into_iter()
+            .map(|color_format| DocumentSettings {
+                color_format,
+                ..Default::default()
+            })
+            .collect();
+        for rem_base in [DEFAULT_REM_BASE, 10.0] {
+            all_settings.push(DocumentSettings {
+                dimension_unit: DimensionUnit::Rem,
+                rem_base,
+                ..Default::default()
+            });
+        }
+        let assert_close = |a: f32, b: f32| assert!((a - b).abs() < 0.01, "{a} {b}");
+
+        for settings in all_settings {
+            let css = export(
+                &[button.clone(), radii.clone()],
+                &settings,
+                CssOptions::default(),
+            );
+            let frames = parse_frames_from_css_file(&css, &settings, &mut NodeFactory::default());
+            assert_eq!(frames.len(), 2, "{css}");
+
+            let parsed = &frames[0];
+            assert_eq!(parsed.name(), "button-outer");
+            let layout = parsed.layout();
+            assert_close(layout.x, 200.0);
+            assert_close(layout.y, 200.0);
+            assert_close(layout.width, 88.0);
+            assert_close(layout.height, 29.0);
+            assert_same_color(parsed.fill(), button.fill);
+            assert_same_color(parsed.border_color(), button.border_color);
+            assert_close(parsed.border_width(), 1.0);
+            assert_close(parsed.corner_radii().bottom_left, 12.0);
+            assert_eq!(parsed.opacity(), 0.8);
+            assert_eq!(parsed.blend_mode(), BlendMode::Screen);
+
+            let shadows = parsed.shadows();
+            assert_eq!(shadows.len(), 2);
+            for (parsed, original) in shadows.iter().zip(&button.shadows) {
+                assert_close(parsed.offset.x, original.offset.x);
+                assert_close(parsed.offset.y, original.offset.y);
+                assert_close(parsed.blur_radius, original.blur_radius);
+                assert_close(parsed.spread_radius, original.spread_radius);
+                assert_eq!(parsed.inset, original.inset);
+                assert_same_color(Some(parsed.color), Some(original.color));
+            }
+
+            let corners = frames[1].corner_radii();
+            assert_close(corners.top_right, 6.0);
+            assert_close(corners.bottom_right, 0.0);
+        }
+
+        // Lengths are written in the document's unit
+        let settings = DocumentSettings {
+            dimension_unit: DimensionUnit::Rem,
+            ..Default::default()
+        };
+        let css = export(&[button], &settings, CssOptions::default());
+        assert!(
+            css.contains("    left: 12.5rem;\n    top: 12.5rem;\n    width: 5.5rem;\n"),
+            "{css}"
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! # Rust Export
+//!
+//! Emits layout constants for gpui apps, so a design can be consumed as code instead of
+//! being measured and retyped by hand.
+//!
+//! Every exported frame becomes a module of `const`s named after the frame: its size,
+//! corner radius, border, colors as `Hsla` and the padding implied by its children.
+//! Corners that differ get a radius each. Translucent frames get an `OPACITY`, and
+//! frames that blend a `BLEND_MODE` holding its CSS name, since gpui only draws
+//! normally and whoever consumes the export has to apply it. Children become nested modules holding their
+//! offset within the parent. Colors are written as struct literals so they can be used
+//! in `const` context.
+//!
+//! Values are always emitted in pixels, since that is what gpui lays out in. The
+//! document's color format and unit show up as trailing comments, so the generated
+//! code can be matched against the inspector and the rest of the spec at a glance.
+
+use super::ExportNode;
+use crate::{
+    document_settings::{DimensionUnit, DocumentSettings},
+    node::BlendMode,
+};
+use gpui::Hsla;
+use std::{collections::HashSet, fmt::Write as _};
+
+const INDENT: &str = "    ";
+
+/// Generate a Rust source file describing the given frames
+pub fn export(frames: &[ExportNode], settings: &DocumentSettings) -> String {
+    let mut out = String::new();
+    out.push_str("// Generated by Luna, changes will be lost when the design is exported again.\n");
+
+    let mut used_names = HashSet::new();
+    for frame in frames {
+        out.push('\n');
+        write_node(&mut out, frame, settings, &mut used_names, 0);
+    }
+
+    out
+}
+
+fn write_node(
+    out: &mut String,
+    node: &ExportNode,
+    settings: &DocumentSettings,
+    used_names: &mut HashSet<String>,
+    depth: usize,
+) {
+    let indent = INDENT.repeat(depth);
+    let module_name = unique_name(module_name(&node.name), used_names);
+
+    let _ = writeln!(out, "{indent}/// {}", node.name);
+    let _ = writeln!(out, "{indent}pub mod {module_name} {{");
+
+    let mut constants = Constants::new(settings);
+    // Top-level positions are canvas coordinates, which mean nothing to a consumer
+    if depth > 0 {
+        constants.dimension("X", node.bounds.origin.x);
+        constants.dimension("Y", node.bounds.origin.y);
+    }
+    constants.dimension("WIDTH", node.bounds.size.width);
+    constants.dimension("HEIGHT", node.bounds.size.height);
+    match node.corner_radii {
+        Some(radii) => {
+            constants.dimension("CORNER_RADIUS_TOP_LEFT", radii.top_left);
+            constants.dimension("CORNER_RADIUS_TOP_RIGHT", radii.top_right);
+            constants.dimension("CORNER_RADIUS_BOTTOM_RIGHT", radii.bottom_right);
+            constants.dimension("CORNER_RADIUS_BOTTOM_LEFT", radii.bottom_left);
+        }
+        None if node.corner_radius > 0.0 => {
+            constants.dimension("CORNER_RADIUS", node.corner_radius);
+        }
+        None => {}
+    }
+    if let Some(fill) = node.fill {
+        constants.color("FILL", fill);
+    }
+    if let Some(border_color) = node.border_color.filter(|_| node.border_width > 0.0) {
+        constants.dimension("BORDER_WIDTH", node.border_width);
+        constants.color("BORDER_COLOR", border_color);
+    }
+    if node.opacity < 1.0 {
+        constants.opacity(node.opacity);
+    }
+    if node.blend_mode != BlendMode::Normal {
+        constants.blend_mode(node.blend_mode);
+    }
+    if let Some(padding) = node.content_padding() {
+        constants.dimension("PADDING_TOP", padding.top);
+        constants.dimension("PADDING_RIGHT", padding.right);
+        constants.dimension("PADDING_BOTTOM", padding.bottom);
+        constants.dimension("PADDING_LEFT", padding.left);
+    }
+
+    for (name, ty, value, comment) in constants.items {
+        let _ = write!(out, "{indent}{INDENT}pub const {name}: {ty} = {value};");
+        if let Some(comment) = comment {
+            let _ = write!(out, " // {comment}");
+        }
+        out.push('\n');
+    }
+
+    let mut child_names = HashSet::new();
+    for child in &node.children {
+        out.push('\n');
+        write_node(out, child, settings, &mut child_names, depth + 1);
+    }
+
+    let _ = writeln!(out, "{indent}}}");
+}
+
+/// The constants of one module, with comments in the document's units
+struct Constants<'a> {
+    settings: &'a DocumentSettings,
+    items: Vec<(&'static str, &'static str, String, Option<String>)>,
+}
+
+impl<'a> Constants<'a> {
+    fn new(settings: &'a DocumentSettings) -> Self {
+        Self {
+            settings,
+            items: Vec::new(),
+        }
+    }
+
+    fn dimension(&mut self, name: &'static str, pixels: f32) {
+        // A comment repeating the pixel value would only be noise
+        let comment = (self.settings.dimension_unit != DimensionUnit::Px)
+            .then(|| self.settings.format_dimension(pixels));
+        self.items.push((name, "f32", float(pixels), comment));
+    }
+
+    fn color(&mut self, name: &'static str, value: Hsla) {
+        let comment = Some(self.settings.format_color(value));
+        self.items.push((name, "gpui::Hsla", color(value), comment));
+    }
+
+    fn opacity(&mut self, opacity: f32) {
+        let comment = Some(format!("{}%", (opacity * 100.0).round()));
+        self.items.push(("OPACITY", "f32", float(opacity), comment));
+    }
+
+    fn blend_mode(&mut self, blend_mode: BlendMode) {
+        let name = format!("{:?}", blend_mode.css_name());
+        self.items.push(("BLEND_MODE", "&str", name, None));
+    }
+}
+
+/// Turn a layer name into a snake_case module name
+pub fn module_name(name: &str) -> String {
+    let mut ident = String::new();
+    let mut previous: Option<char> = None;
+
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            // Split camelCase words, but keep runs of capitals like "URL" together
+            let starts_word = c.is_ascii_uppercase()
+                && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit());
+            if starts_word && !ident.ends_with('_') {
+                ident.push('_');
+            }
+            ident.push(c.to_ascii_lowercase());
+        } else if !ident.is_empty() && !ident.ends_with('_') {
+            ident.push('_');
+        }
+        previous = Some(c);
+    }
+
+    let ident = ident.trim_end_matches('_').to_string();
+    if ident.is_empty() {
+        "node".to_string()
+    } else if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("node_{ident}")
+    } else if is_keyword(&ident) {
+        format!("{ident}_")
+    } else {
+        ident
+    }
+}
+
+/// Append a numeric suffix until `name` doesn't clash with a sibling
+pub(super) fn unique_name(name: String, used: &mut HashSet<String>) -> String {
+    let mut candidate = name.clone();
+    let mut suffix = 2;
+    while used.contains(&candidate) {
+        candidate = format!("{name}_{suffix}");
+        suffix += 1;
+    }
+    used.insert(candidate.clone());
+    candidate
+}
+
+fn is_keyword(ident: &str) -> bool {
+    matches!(
+        ident,
+        "as" | "async"
+            | "await"
+            | "break"
+            | "const"
+            | "continue"
+            | "crate"
+            | "dyn"
+            | "else"
+            | "enum"
+            | "extern"
+            | "false"
+            | "fn"
+            | "for"
+            | "if"
+            | "impl"
+            | "in"
+            | "let"
+            | "loop"
+            | "match"
+            | "mod"
+            | "move"
+            | "mut"
+            | "pub"
+            | "ref"
+            | "return"
+            | "self"
+            | "static"
+            | "struct"
+            | "super"
+            | "trait"
+            | "true"
+            | "type"
+            | "unsafe"
+            | "use"
+            | "where"
+            | "while"
+    )
+}
+
+/// Debug formatting always includes a decimal point, so the literal is an `f32`
+fn float(value: f32) -> String {
+    format!("{value:?}")
+}
+
+fn color(color: Hsla) -> String {
+    format!(
+        "gpui::Hsla {{ h: {}, s: {}, l: {}, a: {} }}",
+        float(color.h),
+        float(color.s),
+        float(color.l),
+        float(color.a)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::ColorFormat, export::tests::node};
+
+    #[test]
+    fn test_module_names() {
+        assert_eq!(module_name("Header Card"), "header_card");
+        assert_eq!(module_name("navBar"), "nav_bar");
+        assert_eq!(module_name("URL Input"), "url_input");
+        assert_eq!(module_name("Frame 12"), "frame_12");
+        assert_eq!(module_name("  -- Hero --  "), "hero");
+        assert_eq!(module_name("3 Columns"), "node_3_columns");
+        assert_eq!(module_name("Type"), "type_");
+        assert_eq!(module_name("✨"), "node");
+    }
+
+    #[test]
+    fn test_sibling_names_are_unique() {
+        let mut used = HashSet::new();
+        assert_eq!(unique_name("card".into(), &mut used), "card");
+        assert_eq!(unique_name("card".into(), &mut used), "card_2");
+        assert_eq!(unique_name("card".into(), &mut used), "card_3");
+    }
+
+    #[test]
+    fn test_export_frame() {
+        let mut card = node("Card", 400.0, 300.0, 200.0, 100.0);
+        card.corner_radius = 8.0;
+        card.fill = Some(Hsla::white());
+        card.children = vec![node("Title", 16.0, 12.0, 168.0, 20.0)];
+
+        let code = export(&[card], &DocumentSettings::default());
+        assert!(code.contains("pub mod card {"));
+        assert!(code.contains("    pub const WIDTH: f32 = 200.0;\n"));
+        assert!(code.contains("    pub const CORNER_RADIUS: f32 = 8.0;"));
+        assert!(code.contains(
+            "    pub const FILL: gpui::Hsla = gpui::Hsla { h: 0.0, s: 0.0, l: 1.0, a: 1.0 }; // #ffffff"
+        ));
+        assert!(code.contains("    pub const PADDING_LEFT: f32 = 16.0;"));
+        assert!(code.contains("        pub const X: f32 = 16.0;"));
+        // Canvas position of the frame itself is not exported
+        assert!(!code.contains("400.0"));
+        assert!(!code.contains("BORDER_WIDTH"));
+    }
+
+    #[test]
+    fn test_export_uses_document_units_in_comments() {
+        let mut card = node("Card", 0.0, 0.0, 320.0, 24.0);
+        card.fill = Some(Hsla::white());
+        let settings = DocumentSettings {
+            color_format: ColorFormat::Rgb,
+            dimension_unit: DimensionUnit::Rem,
+            ..Default::default()
+        };
+
+        let code = export(&[card], &settings);
+        assert!(code.contains("    pub const WIDTH: f32 = 320.0; // 20rem"));
+        assert!(code.contains("    pub const HEIGHT: f32 = 24.0; // 1.5rem"));
+        assert!(code.contains("l: 1.0, a: 1.0 }; // rgb(255, 255, 255)"));
+    }
+
+    #[test]
+    fn test_export_per_corner_radius() {
+        let mut tab = node("Tab", 0.0, 0.0, 120.0, 32.0);
+        tab.corner_radius = 8.0;
+        tab.corner_radii = Some(gpui::Corners {
+            top_left: 8.0,
+            top_right: 8.0,
+            bottom_right: 0.0,
+            bottom_left: 0.0,
+        });
+
+        let code = export(&[tab], &DocumentSettings::default());
+        assert!(code.contains("    pub const CORNER_RADIUS_TOP_LEFT: f32 = 8.0;"));
+        assert!(code.contains("    pub const CORNER_RADIUS_BOTTOM_RIGHT: f32 = 0.0;"));
+        assert!(!code.contains("CORNER_RADIUS:"));
+    }
+
+    #[test]
+    fn test_export_opacity_and_blend_mode() {
+        let mut card = node("Card", 0.0, 0.0, 200.0, 100.0);
+        let mut tint = node("Tint", 0.0, 0.0, 200.0, 100.0);
+        tint.opacity = 0.25;
+        tint.blend_mode = BlendMode::Multiply;
+        card.children = vec![tint];
+
+        let code = export(&[card], &DocumentSettings::default());
+        assert!(code.contains("        pub const OPACITY: f32 = 0.25; // 25%"));
+        assert!(code.contains("        pub const BLEND_MODE: &str = \"multiply\";"));
+        // Opaque, normally blended frames don't mention either
+        assert_eq!(code.matches("OPACITY").count(), 1);
+        assert_eq!(code.matches("BLEND_MODE").count(), 1);
+    }
+}
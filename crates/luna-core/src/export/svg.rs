@@ -0,0 +1,506 @@
+//! # SVG Export
+//!
+//! Writes frames out as a standalone SVG image. Every node becomes a `<g>` translated
+//! to its position within its parent, so the document's nesting carries over and a
+//! frame can be picked apart in any vector editor. Frames clip their children and draw
+//! their border inside their edge, the same as on the canvas.
+//!
+//! Path nodes are written as a `<path>` stretched over their bounds, filled when
+//! closed and stroked along the outline like the canvas draws them, without shadows.
+//!
+//! SVG has no box shadows, so shadows become filters built from primitives. Outer
+//! shadows grow, offset and blur the node's silhouette and are drawn beneath it. Inner
+//! shadows cut a blurred, offset silhouette out of the node and are drawn over its
+//! fill. A CSS blur radius is twice the standard deviation SVG blurs by.
+
+use super::{number, ExportNode};
+use crate::{
+    color::ColorFormat,
+    node::{path::VectorPath, BlendMode, Shadow},
+};
+use gpui::{Bounds, Corners, Hsla, Point, Size};
+use std::fmt::Write as _;
+
+const INDENT: &str = "  ";
+
+/// Generate an SVG image of the given frames, framed to fit them and their shadows
+pub fn export(frames: &[ExportNode]) -> String {
+    let area = frames
+        .iter()
+        .map(|frame| {
+            let reach = outer_reach(&frame.shadows);
+            Bounds {
+                origin: Point::new(frame.bounds.origin.x - reach, frame.bounds.origin.y - reach),
+                size: Size::new(
+                    frame.bounds.size.width + reach * 2.0,
+                    frame.bounds.size.height + reach * 2.0,
+                ),
+            }
+        })
+        .reduce(|a, b| a.union(&b))
+        .unwrap_or_default();
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        number(area.size.width),
+        number(area.size.height),
+        number(area.origin.x),
+        number(area.origin.y),
+        number(area.size.width),
+        number(area.size.height),
+    );
+    let mut ids = 0;
+    for frame in frames {
+        write_node(&mut out, frame, &mut ids, 1);
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+fn write_node(out: &mut String, node: &ExportNode, ids: &mut usize, depth: usize) {
+    let indent = INDENT.repeat(depth);
+    let inner = INDENT.repeat(depth + 1);
+    let size = node.bounds.size;
+    let radii = node
+        .corner_radii
+        .unwrap_or_else(|| Corners::all(node.corner_radius));
+    *ids += 1;
+    let id = *ids;
+
+    let _ = write!(
+        out,
+        r#"{indent}<g data-name="{}" transform="translate({} {})""#,
+        escape(&node.name),
+        number(node.bounds.origin.x),
+        number(node.bounds.origin.y),
+    );
+    if node.opacity < 1.0 {
+        let _ = write!(out, r#" opacity="{}""#, number(node.opacity));
+    }
+    if node.blend_mode != BlendMode::Normal {
+        let _ = write!(
+            out,
+            r#" style="mix-blend-mode: {}""#,
+            node.blend_mode.css_name()
+        );
+    }
+    out.push_str(">\n");
+
+    if let Some(path) = &node.path {
+        write_path(out, &inner, node, path);
+        let _ = writeln!(out, "{indent}</g>");
+        return;
+    }
+
+    let (inner_shadows, outer_shadows): (Vec<&Shadow>, Vec<&Shadow>) =
+        node.shadows.iter().partition(|shadow| shadow.inset);
+    if !outer_shadows.is_empty() {
+        let filter = format!("shadow-{id}");
+        let reach = outer_reach(&node.shadows);
+        let region = Bounds {
+            origin: Point::new(-reach, -reach),
+            size: Size::new(size.width + reach * 2.0, size.height + reach * 2.0),
+        };
+        write_shadow_filter(out, &inner, &filter, region, &outer_shadows);
+        let caster = format!(r##"fill="#000000" filter="url(#{filter})""##);
+        let _ = writeln!(out, "{inner}{}", shape(size, radii, 0.0, &caster));
+    }
+
+    if let Some(fill) = node.fill {
+        let _ = writeln!(
+            out,
+            "{inner}{}",
+            shape(size, radii, 0.0, &paint("fill", fill))
+        );
+    }
+
+    if !inner_shadows.is_empty() {
+        let filter = format!("inner-shadow-{id}");
+        let region = Bounds {
+            origin: Point::default(),
+            size,
+        };
+        write_shadow_filter(out, &inner, &filter, region, &inner_shadows);
+        let caster = format!(r##"fill="#000000" filter="url(#{filter})""##);
+        let _ = writeln!(out, "{inner}{}", shape(size, radii, 0.0, &caster));
+    }
+
+    if !node.children.is_empty() {
+        let clip = format!("clip-{id}");
+        let _ = writeln!(
+            out,
+            r#"{inner}<clipPath id="{clip}">{}</clipPath>"#,
+            shape(size, radii, 0.0, "")
+        );
+        let _ = writeln!(out, r#"{inner}<g clip-path="url(#{clip})">"#);
+        for child in &node.children {
+            write_node(out, child, ids, depth + 2);
+        }
+        let _ = writeln!(out, "{inner}</g>");
+    }
+
+    // Strokes straddle the outline, so it's pulled in by half the width to keep the
+    // border inside the node
+    if let Some(border_color) = node.border_color.filter(|_| node.border_width > 0.0) {
+        let attributes = format!(
+            r#"fill="none" {} stroke-width="{}""#,
+            paint("stroke", border_color),
+            number(node.border_width)
+        );
+        let stroke = shape(size, radii, node.border_width / 2.0, &attributes);
+        let _ = writeln!(out, "{inner}{stroke}");
+    }
+
+    let _ = writeln!(out, "{indent}</g>");
+}
+
+/// The fill and outline of a path node
+fn write_path(out: &mut String, indent: &str, node: &ExportNode, path: &VectorPath) {
+    let data = path_data(&path.fit(Bounds {
+        origin: Point::default(),
+        size: node.bounds.size,
+    }));
+    if let Some(fill) = node.fill.filter(|_| path.closed) {
+        let _ = writeln!(out, r#"{indent}<path d="{data}" {}/>"#, paint("fill", fill));
+    }
+    if let Some(border_color) = node.border_color.filter(|_| node.border_width > 0.0) {
+        let _ = writeln!(
+            out,
+            r#"{indent}<path d="{data}" fill="none" {} stroke-width="{}"/>"#,
+            paint("stroke", border_color),
+            number(node.border_width)
+        );
+    }
+}
+
+/// SVG path data for `path`, with lines for segments that don't curve
+fn path_data(path: &VectorPath) -> String {
+    let Some(first) = path.anchors.first() else {
+        return String::new();
+    };
+    let mut data = format!(
+        "M {} {}",
+        number(first.position.x),
+        number(first.position.y)
+    );
+    let closing = path.anchors.len() - 1;
+    for (index, [from, control_a, control_b, to]) in path.segments().enumerate() {
+        if control_a == from && control_b == to {
+            // `Z` draws the straight line back to the start on its own
+            if index < closing {
+                let _ = write!(data, " L {} {}", number(to.x), number(to.y));
+            }
+        } else {
+            let _ = write!(
+                data,
+                " C {} {} {} {} {} {}",
+                number(control_a.x),
+                number(control_a.y),
+                number(control_b.x),
+                number(control_b.y),
+                number(to.x),
+                number(to.y)
+            );
+        }
+    }
+    if path.closed {
+        data.push_str(" Z");
+    }
+    data
+}
+
+/// A filter drawing `shadows` cast by the silhouette of whatever it's applied to
+///
+/// Only the shadows come out of the filter, the silhouette itself is left out. The
+/// first shadow ends up on top, as in CSS.
+fn write_shadow_filter(
+    out: &mut String,
+    indent: &str,
+    id: &str,
+    region: Bounds<f32>,
+    shadows: &[&Shadow],
+) {
+    let primitive = format!("{indent}{INDENT}");
+    let _ = writeln!(
+        out,
+        r#"{indent}<filter id="{id}" filterUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}">"#,
+        number(region.origin.x),
+        number(region.origin.y),
+        number(region.size.width),
+        number(region.size.height),
+    );
+
+    for (index, shadow) in shadows.iter().enumerate() {
+        let mut silhouette = "SourceAlpha".to_string();
+        // Inner shadows spread by shrinking the part they leave lit
+        let spread = if shadow.inset {
+            -shadow.spread_radius
+        } else {
+            shadow.spread_radius
+        };
+        if spread != 0.0 {
+            let operator = if spread > 0.0 { "dilate" } else { "erode" };
+            let _ = writeln!(
+                out,
+                r#"{primitive}<feMorphology in="SourceAlpha" operator="{operator}" radius="{}" result="spread-{index}"/>"#,
+                number(spread.abs()),
+            );
+            silhouette = format!("spread-{index}");
+        }
+        let _ = writeln!(
+            out,
+            r#"{primitive}<feOffset in="{silhouette}" dx="{}" dy="{}" result="offset-{index}"/>"#,
+            number(shadow.offset.x),
+            number(shadow.offset.y),
+        );
+        let _ = writeln!(
+            out,
+            r#"{primitive}<feGaussianBlur in="offset-{index}" stdDeviation="{}" result="blur-{index}"/>"#,
+            number(shadow.blur_radius / 2.0),
+        );
+        let mut cast = format!("blur-{index}");
+        if shadow.inset {
+            let _ = writeln!(
+                out,
+                r#"{primitive}<feComposite in="SourceAlpha" in2="blur-{index}" operator="out" result="cut-{index}"/>"#,
+            );
+            cast = format!("cut-{index}");
+        }
+        let _ = writeln!(
+            out,
+            r#"{primitive}<feFlood {} result="color-{index}"/>"#,
+            paint("flood", shadow.color)
+        );
+        let _ = writeln!(
+            out,
+            r#"{primitive}<feComposite in="color-{index}" in2="{cast}" operator="in" result="shadow-{index}"/>"#,
+        );
+    }
+
+    let _ = writeln!(out, "{primitive}<feMerge>");
+    for index in (0..shadows.len()).rev() {
+        let _ = writeln!(
+            out,
+            r#"{primitive}{INDENT}<feMergeNode in="shadow-{index}"/>"#
+        );
+    }
+    let _ = writeln!(out, "{primitive}</feMerge>");
+    let _ = writeln!(out, "{indent}</filter>");
+}
+
+/// How far outer shadows can reach past a node's edges
+fn outer_reach(shadows: &[Shadow]) -> f32 {
+    shadows
+        .iter()
+        .filter(|shadow| !shadow.inset)
+        .map(|shadow| {
+            shadow.offset.x.abs().max(shadow.offset.y.abs())
+                + shadow.spread_radius.max(0.0)
+                + shadow.blur_radius * 2.0
+        })
+        .fold(0.0, f32::max)
+}
+
+/// A rectangle of `size` with `radii` corners, pulled in by `inset` on every side
+///
+/// Uniform corners make a `<rect>`, differing ones a `<path>` since `rx` can't vary.
+fn shape(size: Size<f32>, radii: Corners<f32>, inset: f32, attributes: &str) -> String {
+    let (width, height) = (
+        (size.width - inset * 2.0).max(0.0),
+        (size.height - inset * 2.0).max(0.0),
+    );
+    // Radii are capped the way they are when drawn, and shrink with the inset
+    let max_radius = width.min(height) / 2.0;
+    let radii = radii.map(|radius| (*radius - inset).clamp(0.0, max_radius));
+    let separator = if attributes.is_empty() { "" } else { " " };
+
+    if radii.top_left == radii.top_right
+        && radii.top_left == radii.bottom_right
+        && radii.top_left == radii.bottom_left
+    {
+        let mut rect = format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}""#,
+            number(inset),
+            number(inset),
+            number(width),
+            number(height)
+        );
+        if radii.top_left > 0.0 {
+            let _ = write!(rect, r#" rx="{}""#, number(radii.top_left));
+        }
+        return format!("{rect}{separator}{attributes}/>");
+    }
+
+    let (left, top) = (inset, inset);
+    let (right, bottom) = (inset + width, inset + height);
+    let corner = |radius: f32, x: f32, y: f32| {
+        format!(
+            "A {r} {r} 0 0 1 {} {}",
+            number(x),
+            number(y),
+            r = number(radius)
+        )
+    };
+    let path = [
+        format!("M {} {}", number(left + radii.top_left), number(top)),
+        format!("H {}", number(right - radii.top_right)),
+        corner(radii.top_right, right, top + radii.top_right),
+        format!("V {}", number(bottom - radii.bottom_right)),
+        corner(radii.bottom_right, right - radii.bottom_right, bottom),
+        format!("H {}", number(left + radii.bottom_left)),
+        corner(radii.bottom_left, left, bottom - radii.bottom_left),
+        format!("V {}", number(top + radii.top_left)),
+        corner(radii.top_left, left + radii.top_left, top),
+        "Z".to_string(),
+    ]
+    .join(" ");
+    format!(r#"<path d="{path}"{separator}{attributes}/>"#)
+}
+
+/// Color attributes for one of fill, stroke or flood, since SVG takes alpha separately
+fn paint(kind: &str, color: Hsla) -> String {
+    let color_attribute = if kind == "flood" { "flood-color" } else { kind };
+    let hex = ColorFormat::Hex.format(Hsla { a: 1.0, ..color });
+    let mut paint = format!(r#"{color_attribute}="{hex}""#);
+    if color.a < 1.0 {
+        let _ = write!(paint, r#" {kind}-opacity="{}""#, number(color.a));
+    }
+    paint
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{export::tests::node, node::path::Anchor};
+    use gpui::point;
+    use smallvec::smallvec;
+
+    fn shadow(y: f32, blur: f32, inset: bool) -> Shadow {
+        Shadow {
+            color: Hsla {
+                h: 0.0,
+                s: 0.0,
+                l: 0.0,
+                a: 0.25,
+            },
+            offset: point(0.0, y),
+            blur_radius: blur,
+            spread_radius: 0.0,
+            inset,
+        }
+    }
+
+    #[test]
+    fn test_export_nested_frames() {
+        let mut card = node("Card & Co", 100.0, 50.0, 200.0, 120.0);
+        card.fill = Some(Hsla::white());
+        card.corner_radius = 8.0;
+        let mut badge = node("Badge", 16.0, 12.0, 40.0, 20.0);
+        badge.border_color = Some(Hsla::black());
+        badge.border_width = 2.0;
+        badge.opacity = 0.5;
+        card.children = vec![badge];
+
+        let svg = export(&[card]);
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="120" viewBox="100 50 200 120">"#
+        ));
+        assert!(svg.contains(r#"<g data-name="Card &amp; Co" transform="translate(100 50)">"#));
+        assert!(
+            svg.contains(r##"<rect x="0" y="0" width="200" height="120" rx="8" fill="#ffffff"/>"##)
+        );
+        assert!(svg.contains(r#"<g clip-path="url(#clip-1)">"#));
+        assert!(svg.contains(r#"<g data-name="Badge" transform="translate(16 12)" opacity="0.5">"#));
+        // The border is drawn inside the badge's edge
+        assert!(svg.contains(
+            r##"<rect x="1" y="1" width="38" height="18" fill="none" stroke="#000000" stroke-width="2"/>"##
+        ));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_export_shadows_as_filters() {
+        let mut card = node("Card", 0.0, 0.0, 100.0, 100.0);
+        card.shadows = smallvec![shadow(4.0, 8.0, false), shadow(2.0, 4.0, true)];
+
+        let svg = export(&[card]);
+        // Outer shadows widen the image so they aren't cut off
+        assert!(svg.contains(r#"viewBox="-20 -20 140 140""#));
+        assert!(svg.contains(
+            r#"<filter id="shadow-1" filterUnits="userSpaceOnUse" x="-20" y="-20" width="140" height="140">"#
+        ));
+        assert!(svg.contains(r#"<feGaussianBlur in="offset-0" stdDeviation="4" result="blur-0"/>"#));
+        assert!(svg.contains(
+            r##"<feFlood flood-color="#000000" flood-opacity="0.25" result="color-0"/>"##
+        ));
+        assert!(svg.contains(r#"<filter id="inner-shadow-1" filterUnits="userSpaceOnUse" x="0" y="0" width="100" height="100">"#));
+        assert!(svg.contains(r#"operator="out" result="cut-0"/>"#));
+        assert!(svg.contains(r##"fill="#000000" filter="url(#inner-shadow-1)"/>"##));
+    }
+
+    #[test]
+    fn test_per_corner_shape() {
+        let radii = Corners {
+            top_left: 8.0,
+            top_right: 0.0,
+            bottom_right: 8.0,
+            bottom_left: 0.0,
+        };
+        assert_eq!(
+            shape(Size::new(40.0, 20.0), radii, 0.0, "fill=\"red\""),
+            r#"<path d="M 8 0 H 40 A 0 0 0 0 1 40 0 V 12 A 8 8 0 0 1 32 20 H 0 A 0 0 0 0 1 0 20 V 8 A 8 8 0 0 1 8 0 Z" fill="red"/>"#
+        );
+    }
+
+    #[test]
+    fn test_export_paths() {
+        let triangle = VectorPath {
+            anchors: vec![
+                Anchor::corner(point(0.0, 0.0)),
+                Anchor::corner(point(10.0, 0.0)),
+                Anchor::corner(point(0.0, 10.0)),
+            ],
+            closed: true,
+        };
+        let mut node = node("Triangle", 20.0, 20.0, 100.0, 50.0);
+        node.fill = Some(Hsla::white());
+        node.border_color = Some(Hsla::black());
+        node.border_width = 2.0;
+        node.path = Some(triangle);
+
+        // The path is stretched over the node, which draws no box of its own
+        let svg = export(&[node.clone()]);
+        assert!(svg.contains(r##"<path d="M 0 0 L 100 0 L 0 50 Z" fill="#ffffff"/>"##));
+        assert!(svg.contains(
+            r##"<path d="M 0 0 L 100 0 L 0 50 Z" fill="none" stroke="#000000" stroke-width="2"/>"##
+        ));
+        assert!(!svg.contains("<rect"));
+
+        // An open path has nothing to fill, and its curves keep their handles
+        node.path = Some(VectorPath {
+            anchors: vec![
+                Anchor {
+                    handle_out: point(5.0, 0.0),
+                    ..Anchor::corner(point(0.0, 0.0))
+                },
+                Anchor {
+                    handle_in: point(5.0, 10.0),
+                    ..Anchor::corner(point(10.0, 10.0))
+                },
+            ],
+            closed: false,
+        });
+        let svg = export(&[node]);
+        assert!(!svg.contains(r##"fill="#ffffff""##));
+        assert!(svg.contains(r#"<path d="M 0 0 C 50 0 50 50 100 50" fill="none""#));
+    }
+}
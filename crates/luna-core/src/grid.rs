@@ -0,0 +1,99 @@
+//! # Canvas Grid
+//!
+//! A square grid drawn behind the nodes, with major lines every `spacing` canvas units
+//! and lighter minor lines splitting each cell into `subdivisions`. The grid is painted
+//! in screen space so lines stay one pixel wide at any zoom, and levels that would be
+//! packed too tightly to read are dropped as the canvas zooms out.
+//!
+//! With snapping on, dragged and drawn nodes land on the finest grid line, and resize
+//! handles are pulled onto it as they are dragged.
+
+use serde::{Deserialize, Serialize};
+
+/// Grid lines closer together than this many screen pixels aren't drawn
+pub const MIN_LINE_GAP: f32 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridSettings {
+    pub visible: bool,
+    pub snap: bool,
+    /// Canvas units between major lines
+    pub spacing: f32,
+    /// Number of cells each major cell is split into by minor lines
+    pub subdivisions: u32,
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            snap: false,
+            spacing: 40.0,
+            subdivisions: 4,
+        }
+    }
+}
+
+impl GridSettings {
+    /// Spacings offered in the inspector, with their labels
+    pub const SPACING_PRESETS: [(f32, &str); 4] =
+        [(8.0, "8"), (10.0, "10"), (40.0, "40"), (100.0, "100")];
+    /// Subdivisions offered in the inspector, with their labels
+    pub const SUBDIVISION_PRESETS: [(u32, &str); 4] = [(1, "×1"), (2, "×2"), (4, "×4"), (5, "×5")];
+
+    /// Distance between the finest lines of the grid
+    pub fn step(&self) -> f32 {
+        self.spacing / self.subdivisions.max(1) as f32
+    }
+
+    /// Round a canvas coordinate to the nearest grid line, when snapping is on
+    pub fn snap(&self, value: f32) -> f32 {
+        let step = self.step();
+        if !self.snap || step <= 0.0 {
+            return value;
+        }
+        (value / step).round() * step
+    }
+}
+
+/// Canvas coordinates of the lines every `step` units between `start` and `end`
+pub fn line_positions(start: f32, end: f32, step: f32) -> impl Iterator<Item = f32> {
+    let (first, last) = if step > 0.0 {
+        ((start / step).ceil() as i64, (end / step).floor() as i64)
+    } else {
+        (1, 0)
+    };
+    (first..=last).map(move |index| index as f32 * step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap() {
+        let mut grid = GridSettings {
+            spacing: 40.0,
+            subdivisions: 4,
+            ..Default::default()
+        };
+        assert_eq!(grid.snap(13.0), 13.0);
+
+        grid.snap = true;
+        assert_eq!(grid.snap(13.0), 10.0);
+        assert_eq!(grid.snap(-16.0), -20.0);
+
+        grid.subdivisions = 0;
+        assert_eq!(grid.snap(13.0), 0.0);
+    }
+
+    #[test]
+    fn test_line_positions() {
+        let lines: Vec<f32> = line_positions(-25.0, 30.0, 10.0).collect();
+        assert_eq!(lines, vec![-20.0, -10.0, 0.0, 10.0, 20.0, 30.0]);
+
+        assert_eq!(line_positions(1.0, 9.0, 10.0).count(), 0);
+        assert_eq!(line_positions(0.0, 100.0, 0.0).count(), 0);
+    }
+}
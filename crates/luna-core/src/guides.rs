@@ -0,0 +1,223 @@
+//! # Guides
+//!
+//! Guides are lines running across the whole canvas at a fixed x or y, for lining
+//! nodes up by eye. They belong to the document and are saved with it, but they're
+//! editing chrome: exports, prints and preview mode leave them out.
+//!
+//! Guides are placed from the selection, one along each edge and one through each
+//! center line, so the next node can be lined up against the current one. They can
+//! also be dragged out of the rulers at the canvas edges, moved by dragging them, and
+//! dropped back on a ruler to remove them.
+//!
+//! The rulers count canvas units, labelled at round numbers spaced far enough apart
+//! on screen to stay readable at the current zoom.
+
+use gpui::{Bounds, Point};
+use serde::{Deserialize, Serialize};
+
+/// Guides closer together than this are treated as the same guide
+const SAME_GUIDE_DISTANCE: f32 = 0.01;
+
+/// Labels on a ruler are never closer together than this, in screen pixels
+pub const MIN_RULER_LABEL_GAP: f32 = 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuideAxis {
+    /// A vertical line, at a canvas x
+    Vertical,
+    /// A horizontal line, at a canvas y
+    Horizontal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Guide {
+    pub axis: GuideAxis,
+    /// Canvas x of a vertical guide, canvas y of a horizontal one
+    pub position: f32,
+}
+
+impl Guide {
+    pub fn vertical(x: f32) -> Self {
+        Self {
+            axis: GuideAxis::Vertical,
+            position: x,
+        }
+    }
+
+    pub fn horizontal(y: f32) -> Self {
+        Self {
+            axis: GuideAxis::Horizontal,
+            position: y,
+        }
+    }
+
+    /// The guide of `axis` running through `point`, in canvas coordinates
+    pub fn through(axis: GuideAxis, point: Point<f32>) -> Self {
+        match axis {
+            GuideAxis::Vertical => Self::vertical(point.x),
+            GuideAxis::Horizontal => Self::horizontal(point.y),
+        }
+    }
+
+    /// How far `point` is from the guide, across it
+    pub fn distance_to(&self, point: Point<f32>) -> f32 {
+        match self.axis {
+            GuideAxis::Vertical => (point.x - self.position).abs(),
+            GuideAxis::Horizontal => (point.y - self.position).abs(),
+        }
+    }
+
+    fn same_as(&self, other: &Guide) -> bool {
+        self.axis == other.axis && (self.position - other.position).abs() < SAME_GUIDE_DISTANCE
+    }
+}
+
+/// Guides along the edges and through the center lines of `bounds`, in canvas coordinates
+pub fn guides_around(bounds: Bounds<f32>) -> [Guide; 6] {
+    let center = bounds.center();
+    [
+        Guide::vertical(bounds.left()),
+        Guide::vertical(center.x),
+        Guide::vertical(bounds.right()),
+        Guide::horizontal(bounds.top()),
+        Guide::horizontal(center.y),
+        Guide::horizontal(bounds.bottom()),
+    ]
+}
+
+/// `guides` followed by whichever of `new` it doesn't already have
+pub fn with_guides(guides: &[Guide], new: impl IntoIterator<Item = Guide>) -> Vec<Guide> {
+    let mut guides = guides.to_vec();
+    for guide in new {
+        if !guides.iter().any(|existing| existing.same_as(&guide)) {
+            guides.push(guide);
+        }
+    }
+    guides
+}
+
+/// Index of the guide closest to `point` among those within `distance` of it
+pub fn guide_near(guides: &[Guide], point: Point<f32>, distance: f32) -> Option<usize> {
+    guides
+        .iter()
+        .enumerate()
+        .map(|(index, guide)| (index, guide.distance_to(point)))
+        .filter(|(_, to_guide)| *to_guide <= distance)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+/// Canvas units between labelled ticks on a ruler at `zoom`, the smallest of 1, 2 or 5
+/// times a power of ten that keeps labels [`MIN_RULER_LABEL_GAP`] apart, and how many
+/// parts the ticks between labels split that into
+pub fn ruler_step(zoom: f32) -> (f32, u32) {
+    let min_step = MIN_RULER_LABEL_GAP / zoom.max(f32::EPSILON);
+    let magnitude = 10f32.powf(min_step.log10().floor());
+    [(1.0, 5), (2.0, 4), (5.0, 5), (10.0, 5)]
+        .into_iter()
+        .map(|(mantissa, parts)| (mantissa * magnitude, parts))
+        .find(|(step, _)| *step >= min_step)
+        .unwrap_or((10.0 * magnitude, 5))
+}
+
+/// Label of the ruler tick at `position`, with as many decimals as `step` needs
+pub fn ruler_label(position: f32, step: f32) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    // Rounding can leave -0 at the origin
+    let position = if position.abs() < step / 2.0 {
+        0.0
+    } else {
+        position
+    };
+    format!("{position:.decimals$}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::Size;
+
+    #[test]
+    fn test_guides_around() {
+        let bounds = Bounds {
+            origin: Point::new(10.0, 20.0),
+            size: Size::new(100.0, 50.0),
+        };
+
+        assert_eq!(
+            guides_around(bounds),
+            [
+                Guide::vertical(10.0),
+                Guide::vertical(60.0),
+                Guide::vertical(110.0),
+                Guide::horizontal(20.0),
+                Guide::horizontal(45.0),
+                Guide::horizontal(70.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_guides_skips_existing() {
+        let existing = [Guide::vertical(10.0), Guide::horizontal(45.0)];
+        let guides = with_guides(
+            &existing,
+            [
+                Guide::vertical(10.001),
+                Guide::horizontal(10.0),
+                Guide::horizontal(45.0),
+                Guide::vertical(60.0),
+                Guide::vertical(60.0),
+            ],
+        );
+
+        assert_eq!(
+            guides,
+            vec![
+                Guide::vertical(10.0),
+                Guide::horizontal(45.0),
+                Guide::horizontal(10.0),
+                Guide::vertical(60.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_guide_near() {
+        let guides = [
+            Guide::vertical(10.0),
+            Guide::horizontal(20.0),
+            Guide::vertical(14.0),
+        ];
+
+        assert_eq!(guide_near(&guides, Point::new(13.0, 100.0), 4.0), Some(2));
+        assert_eq!(guide_near(&guides, Point::new(50.0, 18.0), 4.0), Some(1));
+        assert_eq!(guide_near(&guides, Point::new(50.0, 50.0), 4.0), None);
+        assert_eq!(
+            Guide::through(GuideAxis::Horizontal, Point::new(3.0, 7.0)),
+            Guide::horizontal(7.0)
+        );
+    }
+
+    #[test]
+    fn test_ruler_step() {
+        // Labels 60px apart need 60 units at 100%, rounded up to 100
+        assert_eq!(ruler_step(1.0), (100.0, 5));
+        assert_eq!(ruler_step(2.0), (50.0, 5));
+        assert_eq!(ruler_step(4.0), (20.0, 4));
+        assert_eq!(ruler_step(0.1), (1000.0, 5));
+        assert_eq!(ruler_step(60.0), (1.0, 5));
+
+        let (step, _) = ruler_step(200.0);
+        assert!((step - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ruler_label() {
+        assert_eq!(ruler_label(200.0, 100.0), "200");
+        assert_eq!(ruler_label(-1.5, 0.5), "-1.5");
+        assert_eq!(ruler_label(-0.00001, 0.5), "0.0");
+        assert_eq!(ruler_label(-0.00001, 100.0), "0");
+    }
+}
@@ -0,0 +1,168 @@
+//! # Interpolation
+//!
+//! Blending between two nodes, used by the blend tool to generate the steps between a
+//! pair of shapes: staircases, stacked shadows, color ramps and other patterns that
+//! would otherwise be drawn and adjusted one copy at a time.
+//!
+//! Geometry is interpolated linearly. Colors are mixed in Oklab rather than sRGB or
+//! HSL, so a ramp between two colors doesn't pass through muddy grays or swing around
+//! the hue wheel, and perceived lightness changes evenly from step to step.
+
+use crate::node::NodeCommon;
+use gpui::{Bounds, Hsla, Point, Rgba, Size};
+use palette::{IntoColor, LinSrgba, Mix, Oklaba, Srgba};
+
+/// Number of intermediate shapes created by a blend
+pub const DEFAULT_BLEND_STEPS: usize = 5;
+
+/// The properties of a node that a blend interpolates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlendKey {
+    pub bounds: Bounds<f32>,
+    pub corner_radius: f32,
+    pub fill: Option<Hsla>,
+    pub border_color: Option<Hsla>,
+    pub border_width: f32,
+}
+
+impl BlendKey {
+    /// Capture a node's blendable properties, positioned at `origin`
+    pub fn from_node(node: &impl NodeCommon, origin: Point<f32>) -> Self {
+        Self {
+            bounds: Bounds {
+                origin,
+                size: node.bounds().size,
+            },
+            corner_radius: node.corner_radius(),
+            fill: node.fill(),
+            border_color: node.border_color(),
+            border_width: node.border_width(),
+        }
+    }
+
+    /// The key `t` of the way from `self` to `other`
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            bounds: Bounds {
+                origin: Point::new(
+                    lerp(self.bounds.origin.x, other.bounds.origin.x, t),
+                    lerp(self.bounds.origin.y, other.bounds.origin.y, t),
+                ),
+                size: Size::new(
+                    lerp(self.bounds.size.width, other.bounds.size.width, t),
+                    lerp(self.bounds.size.height, other.bounds.size.height, t),
+                ),
+            },
+            corner_radius: lerp(self.corner_radius, other.corner_radius, t),
+            fill: lerp_optional_color(self.fill, other.fill, t),
+            border_color: lerp_optional_color(self.border_color, other.border_color, t),
+            border_width: lerp(self.border_width, other.border_width, t),
+        }
+    }
+}
+
+/// The `steps` keys evenly spaced between `from` and `to`, excluding both ends
+pub fn blend_steps(from: &BlendKey, to: &BlendKey, steps: usize) -> Vec<BlendKey> {
+    (1..=steps)
+        .map(|step| from.lerp(to, step as f32 / (steps + 1) as f32))
+        .collect()
+}
+
+pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Mix two colors in Oklab, including their alpha
+pub fn lerp_color(a: Hsla, b: Hsla, t: f32) -> Hsla {
+    let mixed = to_oklab(a).mix(to_oklab(b), t);
+    let linear: LinSrgba = mixed.into_color();
+    let srgb: Srgba = Srgba::from_linear(linear);
+
+    Rgba {
+        r: srgb.red.clamp(0.0, 1.0),
+        g: srgb.green.clamp(0.0, 1.0),
+        b: srgb.blue.clamp(0.0, 1.0),
+        a: srgb.alpha.clamp(0.0, 1.0),
+    }
+    .into()
+}
+
+/// Mix colors that may be missing, fading the one that is present in or out
+fn lerp_optional_color(a: Option<Hsla>, b: Option<Hsla>, t: f32) -> Option<Hsla> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(lerp_color(a, b, t)),
+        (Some(a), None) => Some(a.opacity(1.0 - t)),
+        (None, Some(b)) => Some(b.opacity(t)),
+        (None, None) => None,
+    }
+}
+
+fn to_oklab(color: Hsla) -> Oklaba {
+    let rgba = color.to_rgb();
+    Srgba::new(rgba.r, rgba.g, rgba.b, rgba.a)
+        .into_linear()
+        .into_color()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(x: f32, y: f32, width: f32, height: f32) -> BlendKey {
+        BlendKey {
+            bounds: Bounds {
+                origin: Point::new(x, y),
+                size: Size::new(width, height),
+            },
+            corner_radius: 0.0,
+            fill: None,
+            border_color: None,
+            border_width: 0.0,
+        }
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 0.01, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_blend_steps_exclude_ends() {
+        let from = key(0.0, 0.0, 100.0, 100.0);
+        let mut to = key(60.0, 120.0, 40.0, 100.0);
+        to.corner_radius = 20.0;
+
+        let steps = blend_steps(&from, &to, 2);
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].bounds.origin, Point::new(20.0, 40.0));
+        assert_eq!(steps[0].bounds.size, Size::new(80.0, 100.0));
+        assert_eq!(steps[1].bounds.origin, Point::new(40.0, 80.0));
+        assert_close(steps[1].corner_radius, 40.0 / 3.0);
+
+        assert!(blend_steps(&from, &to, 0).is_empty());
+    }
+
+    #[test]
+    fn test_lerp_color_endpoints_and_midpoint() {
+        let black = Hsla::black();
+        let white = Hsla::white();
+
+        assert_close(lerp_color(black, white, 0.0).l, 0.0);
+        assert_close(lerp_color(black, white, 1.0).l, 1.0);
+
+        // Halfway in perceived lightness sits darker than sRGB's 50% gray
+        let middle = lerp_color(black, white, 0.5);
+        assert_close(middle.s, 0.0);
+        assert!(middle.l > 0.35 && middle.l < 0.45);
+    }
+
+    #[test]
+    fn test_missing_fill_fades() {
+        let mut from = key(0.0, 0.0, 10.0, 10.0);
+        from.fill = Some(Hsla::white());
+        let to = key(0.0, 0.0, 10.0, 10.0);
+
+        let middle = from.lerp(&to, 0.25);
+        assert_close(middle.fill.unwrap().a, 0.75);
+        assert_eq!(from.lerp(&to, 0.5).border_color, None);
+    }
+}
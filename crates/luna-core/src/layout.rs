@@ -0,0 +1,302 @@
+//! # Layout Rules
+//!
+//! Frames can decide where their children go when the frame itself changes size, so a
+//! design can be checked at another screen size by resizing the frame it's drawn in.
+//!
+//! ## Pins
+//!
+//! Every node is pinned along each axis, which says what it keeps when its parent is
+//! resized:
+//!
+//! - `Start`: its distance to the parent's left or top edge, the default
+//! - `End`: its distance to the right or bottom edge
+//! - `Stretch`: its distance to both edges, growing and shrinking with the parent
+//! - `Center`: its offset from the parent's center
+//! - `Scale`: its position and size as a share of the parent's
+//!
+//! ## Auto Layout
+//!
+//! A frame with auto layout stacks its children in a row or column instead, in their
+//! layer order and with a fixed gap between them, inset from the frame's edges by its
+//! padding. Along the stack children keep their size. Across it their pin decides: they
+//! stretch to fill the frame, center in it, or line up with its far edge, and otherwise
+//! line up with its near edge. Hidden children take no room in the stack.
+//!
+//! Like constraints, layout rules are applied whenever the canvas changes, the canvas
+//! only has to feed the functions here the current layouts.
+
+use crate::node::NodeLayout;
+use gpui::Size;
+use serde::{Deserialize, Serialize};
+
+/// Which way something runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Axis {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+impl Axis {
+    pub const ALL: [Axis; 2] = [Axis::Horizontal, Axis::Vertical];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Axis::Horizontal => "Row",
+            Axis::Vertical => "Column",
+        }
+    }
+}
+
+/// What a node keeps along one axis when its parent is resized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pin {
+    #[default]
+    Start,
+    End,
+    Stretch,
+    Center,
+    Scale,
+}
+
+impl Pin {
+    pub const ALL: [Pin; 5] = [Pin::Start, Pin::End, Pin::Stretch, Pin::Center, Pin::Scale];
+
+    pub fn label(self, axis: Axis) -> &'static str {
+        match (self, axis) {
+            (Pin::Start, Axis::Horizontal) => "Left",
+            (Pin::Start, Axis::Vertical) => "Top",
+            (Pin::End, Axis::Horizontal) => "Right",
+            (Pin::End, Axis::Vertical) => "Bottom",
+            (Pin::Stretch, _) => "Both",
+            (Pin::Center, _) => "Center",
+            (Pin::Scale, _) => "Scale",
+        }
+    }
+
+    /// Move and resize the span from `start` to `start + length` for a parent going
+    /// from `from` to `to` long
+    fn apply(self, start: f32, length: f32, from: f32, to: f32) -> (f32, f32) {
+        let grown = to - from;
+        match self {
+            Pin::Start => (start, length),
+            Pin::End => (start + grown, length),
+            Pin::Stretch => (start, (length + grown).max(0.0)),
+            Pin::Center => (start + grown / 2.0, length),
+            // A parent with no size gives nothing to scale by
+            Pin::Scale if from <= 0.0 => (start, length),
+            Pin::Scale => {
+                let ratio = to / from;
+                (start * ratio, length * ratio)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Pins {
+    #[serde(default)]
+    pub horizontal: Pin,
+    #[serde(default)]
+    pub vertical: Pin,
+}
+
+impl Pins {
+    pub fn get(&self, axis: Axis) -> Pin {
+        match axis {
+            Axis::Horizontal => self.horizontal,
+            Axis::Vertical => self.vertical,
+        }
+    }
+
+    pub fn set(&mut self, axis: Axis, pin: Pin) {
+        match axis {
+            Axis::Horizontal => self.horizontal = pin,
+            Axis::Vertical => self.vertical = pin,
+        }
+    }
+
+    /// Pinned to the top left, which leaves the node where it is
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Stacking of a frame's children
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AutoLayout {
+    pub direction: Axis,
+    /// Space between neighboring children
+    #[serde(default)]
+    pub gap: f32,
+    /// Space between the children and every edge of the frame
+    #[serde(default)]
+    pub padding: f32,
+}
+
+impl Default for AutoLayout {
+    fn default() -> Self {
+        Self {
+            direction: Axis::Vertical,
+            gap: 8.0,
+            padding: 8.0,
+        }
+    }
+}
+
+/// Where a child pinned by `pins` goes when its parent is resized from `from` to `to`
+pub fn pin(layout: &NodeLayout, pins: Pins, from: Size<f32>, to: Size<f32>) -> NodeLayout {
+    let (x, width) = pins
+        .horizontal
+        .apply(layout.x, layout.width, from.width, to.width);
+    let (y, height) = pins
+        .vertical
+        .apply(layout.y, layout.height, from.height, to.height);
+    NodeLayout::new(x, y, width, height)
+}
+
+/// Layouts of a frame's children stacked by `auto_layout` in a frame of `frame_size`,
+/// in the order they're given
+pub fn stack(
+    auto_layout: &AutoLayout,
+    frame_size: Size<f32>,
+    children: &[(NodeLayout, Pins)],
+) -> Vec<NodeLayout> {
+    let padding = auto_layout.padding;
+    let cross_space = match auto_layout.direction {
+        Axis::Horizontal => frame_size.height,
+        Axis::Vertical => frame_size.width,
+    } - padding * 2.0;
+    let cross_axis = match auto_layout.direction {
+        Axis::Horizontal => Axis::Vertical,
+        Axis::Vertical => Axis::Horizontal,
+    };
+
+    let mut cursor = padding;
+    children
+        .iter()
+        .map(|(layout, pins)| {
+            let (length, cross_length) = match auto_layout.direction {
+                Axis::Horizontal => (layout.width, layout.height),
+                Axis::Vertical => (layout.height, layout.width),
+            };
+            let (cross_start, cross_length) = match pins.get(cross_axis) {
+                Pin::Stretch => (padding, cross_space.max(0.0)),
+                Pin::Center => (padding + (cross_space - cross_length) / 2.0, cross_length),
+                Pin::End => (padding + cross_space - cross_length, cross_length),
+                Pin::Start | Pin::Scale => (padding, cross_length),
+            };
+            let start = cursor;
+            cursor += length + auto_layout.gap;
+
+            match auto_layout.direction {
+                Axis::Horizontal => NodeLayout::new(start, cross_start, length, cross_length),
+                Axis::Vertical => NodeLayout::new(cross_start, start, cross_length, length),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pins(horizontal: Pin, vertical: Pin) -> Pins {
+        Pins {
+            horizontal,
+            vertical,
+        }
+    }
+
+    #[test]
+    fn test_pins_follow_parent_resize() {
+        let child = NodeLayout::new(10.0, 20.0, 30.0, 40.0);
+        let from = Size::new(100.0, 100.0);
+        let to = Size::new(200.0, 50.0);
+        let resized = |horizontal, vertical| pin(&child, pins(horizontal, vertical), from, to);
+
+        assert_eq!(resized(Pin::Start, Pin::Start), child);
+        assert_eq!(
+            resized(Pin::End, Pin::End),
+            NodeLayout::new(110.0, -30.0, 30.0, 40.0)
+        );
+        assert_eq!(
+            resized(Pin::Stretch, Pin::Center),
+            NodeLayout::new(10.0, -5.0, 130.0, 40.0)
+        );
+        assert_eq!(
+            resized(Pin::Scale, Pin::Scale),
+            NodeLayout::new(20.0, 10.0, 60.0, 20.0)
+        );
+
+        // Stretching never turns a node inside out
+        let shrunk = pin(
+            &child,
+            pins(Pin::Stretch, Pin::Stretch),
+            from,
+            Size::new(50.0, 50.0),
+        );
+        assert_eq!(shrunk, NodeLayout::new(10.0, 20.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_resizing_back_restores_pinned_layout() {
+        let child = NodeLayout::new(10.0, 20.0, 30.0, 40.0);
+        let from = Size::new(100.0, 80.0);
+        let to = Size::new(200.0, 160.0);
+        for pin_kind in Pin::ALL {
+            let both = pins(pin_kind, pin_kind);
+            let there = pin(&child, both, from, to);
+            assert_eq!(pin(&there, both, to, from), child, "{pin_kind:?}");
+        }
+    }
+
+    #[test]
+    fn test_stack_children() {
+        let column = AutoLayout {
+            direction: Axis::Vertical,
+            gap: 10.0,
+            padding: 5.0,
+        };
+        let children = [
+            (NodeLayout::new(50.0, 50.0, 40.0, 20.0), Pins::default()),
+            (
+                NodeLayout::new(0.0, 0.0, 40.0, 30.0),
+                pins(Pin::Stretch, Pin::Start),
+            ),
+            (
+                NodeLayout::new(0.0, 0.0, 40.0, 10.0),
+                pins(Pin::Center, Pin::Start),
+            ),
+            (
+                NodeLayout::new(0.0, 0.0, 40.0, 10.0),
+                pins(Pin::End, Pin::Start),
+            ),
+        ];
+        let stacked = stack(&column, Size::new(100.0, 200.0), &children);
+        assert_eq!(
+            stacked,
+            vec![
+                NodeLayout::new(5.0, 5.0, 40.0, 20.0),
+                NodeLayout::new(5.0, 35.0, 90.0, 30.0),
+                NodeLayout::new(30.0, 75.0, 40.0, 10.0),
+                NodeLayout::new(55.0, 95.0, 40.0, 10.0),
+            ]
+        );
+
+        let row = AutoLayout {
+            direction: Axis::Horizontal,
+            ..column
+        };
+        let stacked = stack(&row, Size::new(200.0, 50.0), &children[..2]);
+        assert_eq!(
+            stacked,
+            vec![
+                NodeLayout::new(5.0, 5.0, 40.0, 20.0),
+                NodeLayout::new(55.0, 5.0, 40.0, 30.0),
+            ]
+        );
+    }
+}
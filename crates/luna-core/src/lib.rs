@@ -0,0 +1,65 @@
+//! # Luna Core
+//!
+//! The parts of Luna that describe a design without editing it: the node data model,
+//! `.luna` documents, the scene graph, layout and constraints, the shared library's
+//! format, CSS import and the exporters. Undo, the clipboard, configuration and
+//! everything else that only makes sense inside the editor stay in the app.
+//!
+//! This is an internal workspace crate, not a published library. It isn't released
+//! on its own and its API changes whenever the editor needs it to. It builds against
+//! the same gpui checkout as the app, a sibling `zed` directory, and uses gpui's
+//! `Point`, `Size`, `Bounds` and `Hsla` so values pass between the model and the
+//! editor unconverted.
+//!
+//! ## Where to start
+//!
+//! - [`document::Document`] reads and writes `.luna` files, upgrading older formats
+//!   and optionally protecting them with a password. Loading checks that the nodes
+//!   form a tree before handing them over.
+//! - [`node::frame::FrameNode`] is the one kind of node, a box that can hold children
+//!   and take the shape of a [`node::path::VectorPath`]. Nodes are kept in a flat list
+//!   and refer to their parent and children by [`node::NodeId`].
+//! - [`scene_graph::SceneGraph`] tracks where nodes sit in the world once their
+//!   parents' positions are taken into account.
+//! - [`export::ExportNode`] snapshots a subtree for the CSS, Rust and SVG writers.
+//! - [`css_parser`] builds frames from CSS declarations.
+//!
+//! ```
+//! use luna_core::{
+//!     document::Document,
+//!     export::{svg, ExportNode},
+//! };
+//!
+//! let document = Document::parse(
+//!     r##"{
+//!         "version": 4,
+//!         "nodes": [{ "id": 1, "x": 0, "y": 0, "width": 120, "height": 40, "fill": "#3366ff" }]
+//!     }"##,
+//! )
+//! .unwrap();
+//! let frames = document.frames().unwrap();
+//! let button = ExportNode::from_node(frames[0].id, &|id| {
+//!     frames.iter().find(|frame| frame.id == id)
+//! })
+//! .unwrap();
+//! assert!(svg::export(&[button]).contains("<rect"));
+//! ```
+
+pub mod color;
+pub mod constraints;
+pub mod coordinates;
+pub mod css_parser;
+pub mod document;
+pub mod document_diff;
+pub mod document_settings;
+pub mod export;
+pub mod grid;
+pub mod guides;
+pub mod interpolation;
+pub mod layout;
+pub mod library;
+pub mod node;
+pub mod protection;
+pub mod scene_graph;
+pub mod snapping;
+pub mod statistics;
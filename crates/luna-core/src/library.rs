@@ -0,0 +1,810 @@
+//! # Shared Library
+//!
+//! The library holds components, styles and color tokens published from any document,
+//! ready to be inserted into any other. The app keeps one per user, a JSON file shared
+//! by every window.
+//!
+//! What's inserted from the library remembers the item it came from and the revision
+//! the item was at: a component instance on its root frame, a style or color token on
+//! every frame it was applied to. Publishing an item again bumps its revision, which is
+//! how a document finds out that items it uses have changed and offers to update them.
+//! A color token can also be given a new color from the library itself, which the
+//! document it's changed from takes on straight away.
+//!
+//! Every frame of a component instance also remembers which of the component's nodes
+//! it was copied from and the fill it was given. A fill changed on the instance is an
+//! override, kept when the instance is updated to a new revision of its component.
+//!
+//! Items are identified by a random id rather than by name, so renaming one or
+//! publishing two under the same name never mixes up what was inserted from them.
+
+use crate::{
+    color::{parse_color, ColorFormat, ColorTarget},
+    document::{SavedNode, SavedShadow},
+    node::{
+        frame::{instantiate, FrameNode, NodeStyle},
+        BlendMode, NodeId,
+    },
+};
+use anyhow::{bail, Context as _};
+use gpui::{Corners, Hsla, Point};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path};
+
+/// Format version written to new libraries, libraries from newer versions are refused
+pub const LIBRARY_VERSION: u32 = 1;
+
+pub const LIBRARY_FILE_NAME: &str = "library.json";
+
+/// Where something in a document came from in the library
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LibraryLink {
+    /// Id of the library item
+    pub item: String,
+    /// Revision of the item when it was inserted or last updated
+    pub revision: u32,
+}
+
+/// The node of a component an instance's frame was copied from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentSource {
+    /// Id of the node among the component's nodes
+    pub node: usize,
+    /// The fill the component gave the node, as hex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<String>,
+}
+
+impl ComponentSource {
+    /// Whether `frame` has a fill of its own rather than the component's
+    pub fn overrides_fill(&self, frame: &FrameNode) -> bool {
+        frame.fill.map(|fill| ColorFormat::Hex.format(fill)) != self.fill
+    }
+}
+
+/// The library style and color tokens a frame's style was applied from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StyleLinks {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<LibraryLink>,
+    /// Color token the fill was set from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<LibraryLink>,
+    /// Color token the border was set from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<LibraryLink>,
+}
+
+impl StyleLinks {
+    pub fn is_empty(&self) -> bool {
+        self.style.is_none() && self.fill.is_none() && self.border.is_none()
+    }
+}
+
+/// A published style, everything about how a frame looks that isn't structural
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedStyle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border_color: Option<String>,
+    #[serde(default)]
+    pub border_width: f32,
+    /// Top-left, top-right, bottom-right and bottom-left radii
+    #[serde(default)]
+    pub corner_radii: [f32; 4],
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shadows: Vec<SavedShadow>,
+    pub opacity: f32,
+    #[serde(default)]
+    pub blend_mode: BlendMode,
+}
+
+impl SavedStyle {
+    pub fn from_style(style: &NodeStyle) -> Self {
+        let hex = |color| ColorFormat::Hex.format(color);
+        let radii = style.corner_radii;
+        Self {
+            fill: style.fill.map(hex),
+            border_color: style.border_color.map(hex),
+            border_width: style.border_width,
+            corner_radii: [
+                radii.top_left,
+                radii.top_right,
+                radii.bottom_right,
+                radii.bottom_left,
+            ],
+            shadows: style.shadows.iter().map(SavedShadow::from_shadow).collect(),
+            opacity: style.opacity,
+            blend_mode: style.blend_mode,
+        }
+    }
+
+    /// Set everything the style covers on `style`, overflow is left as it is
+    fn apply_to(&self, style: &mut NodeStyle) -> anyhow::Result<()> {
+        let color = |value: &str| parse_color(value).with_context(|| invalid_color(value));
+        let [top_left, top_right, bottom_right, bottom_left] = self.corner_radii;
+
+        style.fill = self.fill.as_deref().map(color).transpose()?;
+        style.border_color = self.border_color.as_deref().map(color).transpose()?;
+        style.border_width = self.border_width;
+        style.corner_radii = Corners {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        };
+        style.shadows = self
+            .shadows
+            .iter()
+            .map(|shadow| {
+                shadow
+                    .to_shadow()
+                    .with_context(|| invalid_color(&shadow.color))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        style.opacity = self.opacity;
+        style.blend_mode = self.blend_mode;
+        Ok(())
+    }
+}
+
+fn invalid_color(value: &str) -> String {
+    format!("invalid color {value:?}")
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LibraryContent {
+    /// A frame and its descendants, the root first and at the origin
+    Component {
+        nodes: Vec<SavedNode>,
+    },
+    Style {
+        style: SavedStyle,
+    },
+    /// A named color, applied to fills or borders
+    ColorToken {
+        color: String,
+    },
+}
+
+impl LibraryContent {
+    pub fn kind(&self) -> LibraryKind {
+        match self {
+            LibraryContent::Component { .. } => LibraryKind::Component,
+            LibraryContent::Style { .. } => LibraryKind::Style,
+            LibraryContent::ColorToken { .. } => LibraryKind::ColorToken,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibraryKind {
+    Component,
+    Style,
+    ColorToken,
+}
+
+impl LibraryKind {
+    pub const ALL: [LibraryKind; 3] = [
+        LibraryKind::Component,
+        LibraryKind::Style,
+        LibraryKind::ColorToken,
+    ];
+
+    /// Heading for a list of items of this kind
+    pub fn plural_label(self) -> &'static str {
+        match self {
+            LibraryKind::Component => "Components",
+            LibraryKind::Style => "Styles",
+            LibraryKind::ColorToken => "Color Tokens",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryItem {
+    pub id: String,
+    pub name: String,
+    /// Bumped every time the item is published with different content
+    pub revision: u32,
+    #[serde(flatten)]
+    pub content: LibraryContent,
+}
+
+impl LibraryItem {
+    pub fn link(&self) -> LibraryLink {
+        LibraryLink {
+            item: self.id.clone(),
+            revision: self.revision,
+        }
+    }
+
+    /// A new instance of a component, its root at `origin` and linked to the item
+    ///
+    /// Every frame gets an id from `next_id`, except the root when `root_id` is given,
+    /// which is how an instance is replaced in place by an updated one.
+    pub fn instantiate(
+        &self,
+        origin: Point<f32>,
+        root_id: Option<NodeId>,
+        next_id: impl FnMut() -> NodeId,
+    ) -> anyhow::Result<Vec<FrameNode>> {
+        let LibraryContent::Component { nodes } = &self.content else {
+            bail!("{} isn't a component", self.name);
+        };
+        let frames = nodes
+            .iter()
+            .map(SavedNode::to_frame)
+            .collect::<anyhow::Result<Vec<_>>>()
+            .with_context(|| format!("loading component {}", self.name))?;
+
+        let mut copies = instantiate(&frames, origin, next_id);
+        for (copy, frame) in copies.iter_mut().zip(&frames) {
+            copy.component_source = Some(ComponentSource {
+                node: frame.id.0,
+                fill: frame.fill.map(|fill| ColorFormat::Hex.format(fill)),
+            });
+        }
+        let root = copies
+            .first_mut()
+            .with_context(|| format!("component {} is empty", self.name))?;
+        if let Some(root_id) = root_id {
+            root.id = root_id;
+        }
+        root.component = Some(self.link());
+        Ok(copies)
+    }
+
+    /// Apply a style or color token to `style` and link it to the item
+    ///
+    /// A style replaces any tokens applied before it. A token goes to the fill or the
+    /// border, whichever `target` is, giving a border-less frame a 1px border so the
+    /// color shows.
+    pub fn apply_to_style(&self, style: &mut NodeStyle, target: ColorTarget) -> anyhow::Result<()> {
+        match &self.content {
+            LibraryContent::Component { .. } => bail!("{} is a component", self.name),
+            LibraryContent::Style { style: saved } => {
+                saved.apply_to(style)?;
+                style.links = StyleLinks {
+                    style: Some(self.link()),
+                    ..Default::default()
+                };
+            }
+            LibraryContent::ColorToken { color } => {
+                let color = parse_color(color).with_context(|| invalid_color(color))?;
+                match target {
+                    ColorTarget::Fill => {
+                        style.fill = Some(color);
+                        style.links.fill = Some(self.link());
+                    }
+                    ColorTarget::Stroke => {
+                        style.border_color = Some(color);
+                        if style.border_width <= 0.0 {
+                            style.border_width = 1.0;
+                        }
+                        style.links.border = Some(self.link());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Which link of a frame an update is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkedAs {
+    Component,
+    Style,
+    Token(ColorTarget),
+}
+
+impl LinkedAs {
+    pub fn label(self) -> &'static str {
+        match self {
+            LinkedAs::Component => "Component",
+            LinkedAs::Style => "Style",
+            LinkedAs::Token(ColorTarget::Fill) => "Fill token",
+            LinkedAs::Token(ColorTarget::Stroke) => "Stroke token",
+        }
+    }
+
+    /// The kind of item that can be linked this way
+    pub fn kind(self) -> LibraryKind {
+        match self {
+            LinkedAs::Component => LibraryKind::Component,
+            LinkedAs::Style => LibraryKind::Style,
+            LinkedAs::Token(_) => LibraryKind::ColorToken,
+        }
+    }
+}
+
+/// A frame linked to a library item that has been published again since
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryUpdate {
+    pub node_id: NodeId,
+    pub linked_as: LinkedAs,
+    /// The revision the frame is linked to
+    pub revision: u32,
+    /// The item as it is now
+    pub item: LibraryItem,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Library {
+    pub version: u32,
+    pub items: Vec<LibraryItem>,
+}
+
+impl Default for Library {
+    fn default() -> Self {
+        Self {
+            version: LIBRARY_VERSION,
+            items: Vec::new(),
+        }
+    }
+}
+
+impl Library {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::parse(&contents).with_context(|| format!("loading {}", path.display()))
+    }
+
+    fn parse(contents: &str) -> anyhow::Result<Self> {
+        let library: Self = serde_json::from_str(contents)?;
+        if library.version > LIBRARY_VERSION {
+            bail!(
+                "library version {} is newer than this version of Luna supports",
+                library.version
+            );
+        }
+        Ok(library)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    pub fn item(&self, id: &str) -> Option<&LibraryItem> {
+        self.items.iter().find(|item| item.id == id)
+    }
+
+    /// Publish `content` under `name`, returning the link to the published item
+    ///
+    /// Publishing from something that was inserted from the library, `existing`,
+    /// updates that item when it's still around. Its revision only goes up when the
+    /// content changed, so publishing it again as it is doesn't flag every instance as
+    /// out of date. Anything else becomes a new item.
+    pub fn publish(
+        &mut self,
+        existing: Option<&LibraryLink>,
+        name: String,
+        content: LibraryContent,
+    ) -> LibraryLink {
+        let content = match content {
+            LibraryContent::Component { nodes } => LibraryContent::Component {
+                nodes: canonical_component(nodes),
+            },
+            content => content,
+        };
+
+        let existing =
+            existing.and_then(|link| self.items.iter_mut().find(|item| item.id == link.item));
+        match existing {
+            Some(item) => {
+                if item.content != content {
+                    item.content = content;
+                    item.revision += 1;
+                }
+                item.name = name;
+                item.link()
+            }
+            None => {
+                let item = LibraryItem {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name,
+                    revision: 1,
+                    content,
+                };
+                let link = item.link();
+                self.items.push(item);
+                link
+            }
+        }
+    }
+
+    /// Give the color token `id` a new color, returning the link to it
+    ///
+    /// It's published again under the same name, so the revision only goes up when
+    /// the color actually changed.
+    pub fn recolor_token(&mut self, id: &str, color: Hsla) -> anyhow::Result<LibraryLink> {
+        let item = self
+            .item(id)
+            .with_context(|| format!("no library item {id}"))?;
+        if item.content.kind() != LibraryKind::ColorToken {
+            bail!("{} isn't a color token", item.name);
+        }
+        let (link, name) = (item.link(), item.name.clone());
+        let content = LibraryContent::ColorToken {
+            color: ColorFormat::Hex.format(color),
+        };
+        Ok(self.publish(Some(&link), name, content))
+    }
+
+    /// Everything in `nodes` linked to an item that has a newer revision, in node order
+    ///
+    /// Links to items that were removed from the library, or that now hold a different
+    /// kind of item, are left alone.
+    pub fn updates_for(&self, nodes: &[FrameNode]) -> Vec<LibraryUpdate> {
+        let mut updates = Vec::new();
+        for node in nodes {
+            let links = [
+                (LinkedAs::Component, &node.component),
+                (LinkedAs::Style, &node.style_links.style),
+                (LinkedAs::Token(ColorTarget::Fill), &node.style_links.fill),
+                (
+                    LinkedAs::Token(ColorTarget::Stroke),
+                    &node.style_links.border,
+                ),
+            ];
+            for (linked_as, link) in links {
+                let Some(link) = link else {
+                    continue;
+                };
+                let Some(item) = self.item(&link.item) else {
+                    continue;
+                };
+                if item.content.kind() == linked_as.kind() && item.revision > link.revision {
+                    updates.push(LibraryUpdate {
+                        node_id: node.id,
+                        linked_as,
+                        revision: link.revision,
+                        item: item.clone(),
+                    });
+                }
+            }
+        }
+        updates
+    }
+}
+
+/// Give the frames of an updated instance, `instance`, the fills the instance it
+/// replaces had overridden, `previous` being the old instance's frames
+pub fn keep_overrides(previous: &[FrameNode], instance: &mut [FrameNode]) {
+    for frame in instance {
+        let Some(node) = frame.component_source.as_ref().map(|source| source.node) else {
+            continue;
+        };
+        let overridden = previous.iter().find(|previous| {
+            previous
+                .component_source
+                .as_ref()
+                .is_some_and(|source| source.node == node && source.overrides_fill(previous))
+        });
+        if let Some(previous) = overridden {
+            frame.fill = previous.fill;
+        }
+    }
+}
+
+/// A component's nodes as they're stored: the root first and at the origin, ids
+/// numbered from 1 in order and the root's own link left out
+///
+/// Instances are copies under fresh ids, so without renumbering every instance
+/// published back would look changed.
+fn canonical_component(mut nodes: Vec<SavedNode>) -> Vec<SavedNode> {
+    let ids: HashMap<usize, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (node.id, index + 1))
+        .collect();
+    for node in &mut nodes {
+        // Published from an instance, the nodes are the component's own now
+        node.component_source = None;
+        node.id = ids[&node.id];
+        node.children.retain(|child| ids.contains_key(child));
+        for child in &mut node.children {
+            *child = ids[child];
+        }
+    }
+    if let Some(root) = nodes.first_mut() {
+        root.x = 0.0;
+        root.y = 0.0;
+        root.component = None;
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::NodeCommon;
+
+    fn ids_from(first: usize) -> impl FnMut() -> NodeId {
+        let mut next = first;
+        move || {
+            next += 1;
+            NodeId::new(next - 1)
+        }
+    }
+
+    /// A card with a title, as published from a document where it sits at 40,40
+    fn card(root_id: usize, title_id: usize) -> Vec<SavedNode> {
+        let mut card = FrameNode::with_rect(NodeId::new(root_id), 40.0, 40.0, 200.0, 120.0);
+        card.name = Some("Card".into());
+        card.children = vec![NodeId::new(title_id)];
+        let title = FrameNode::with_rect(NodeId::new(title_id), 16.0, 16.0, 80.0, 20.0);
+        vec![SavedNode::from_frame(&card), SavedNode::from_frame(&title)]
+    }
+
+    fn token(color: &str) -> LibraryContent {
+        LibraryContent::ColorToken {
+            color: color.into(),
+        }
+    }
+
+    #[test]
+    fn test_publish_and_republish() {
+        let mut library = Library::default();
+        let first = library.publish(
+            None,
+            "Card".into(),
+            LibraryContent::Component { nodes: card(7, 9) },
+        );
+        assert_eq!(first.revision, 1);
+
+        // Stored at the origin with canonical ids, whichever document it came from
+        let LibraryContent::Component { nodes } = &library.items[0].content else {
+            panic!("not a component");
+        };
+        assert_eq!((nodes[0].id, nodes[0].children.clone()), (1, vec![2]));
+        assert_eq!((nodes[0].x, nodes[0].y), (0.0, 0.0));
+
+        // An unchanged instance with other ids publishes back as the same revision
+        let again = library.publish(
+            Some(&first),
+            "Card".into(),
+            LibraryContent::Component {
+                nodes: card(30, 31),
+            },
+        );
+        assert_eq!(again, first);
+
+        let mut changed = card(30, 31);
+        changed[1].width = 120.0;
+        let updated = library.publish(
+            Some(&first),
+            "Card".into(),
+            LibraryContent::Component { nodes: changed },
+        );
+        assert_eq!(
+            (updated.item.as_str(), updated.revision),
+            (first.item.as_str(), 2)
+        );
+        assert_eq!(library.items.len(), 1);
+
+        // A link to an item that's gone publishes a new one
+        library.items.clear();
+        let new = library.publish(Some(&first), "Brand".into(), token("#ff0000"));
+        assert_ne!(new.item, first.item);
+        assert_eq!(new.revision, 1);
+    }
+
+    #[test]
+    fn test_instantiate_component() {
+        let mut library = Library::default();
+        let link = library.publish(
+            None,
+            "Card".into(),
+            LibraryContent::Component { nodes: card(7, 9) },
+        );
+        let item = library.item(&link.item).unwrap();
+
+        let instance = item
+            .instantiate(Point::new(100.0, 50.0), None, ids_from(20))
+            .unwrap();
+        assert_eq!(instance[0].id, NodeId::new(20));
+        assert_eq!(instance[0].children, vec![NodeId::new(21)]);
+        assert_eq!((instance[0].layout.x, instance[0].layout.y), (100.0, 50.0));
+        assert_eq!(instance[0].component, Some(link.clone()));
+        assert_eq!(instance[1].component, None);
+
+        // Replacing an instance keeps the root's id
+        let replacement = item
+            .instantiate(Point::default(), Some(NodeId::new(5)), ids_from(30))
+            .unwrap();
+        assert_eq!(replacement[0].id, NodeId::new(5));
+        assert_eq!(replacement[0].children, vec![NodeId::new(31)]);
+    }
+
+    #[test]
+    fn test_instance_fill_overrides() {
+        let mut library = Library::default();
+        let link = library.publish(
+            None,
+            "Card".into(),
+            LibraryContent::Component { nodes: card(7, 9) },
+        );
+        let mut instance = library
+            .item(&link.item)
+            .unwrap()
+            .instantiate(Point::default(), None, ids_from(20))
+            .unwrap();
+        let sources: Vec<usize> = instance
+            .iter()
+            .map(|frame| frame.component_source.as_ref().unwrap().node)
+            .collect();
+        assert_eq!(sources, [1, 2]);
+        let red = parse_color("#ff0000");
+        instance[1].fill = red;
+
+        // Publishing the instance back doesn't make its sources part of the component
+        let mut changed: Vec<SavedNode> = instance.iter().map(SavedNode::from_frame).collect();
+        changed[0].fill = Some("#000000".into());
+        changed[1].fill = Some("#00ff00".into());
+        changed[1].width = 120.0;
+        let updated = library.publish(
+            Some(&link),
+            "Card".into(),
+            LibraryContent::Component { nodes: changed },
+        );
+        let item = library.item(&updated.item).unwrap();
+        let LibraryContent::Component { nodes } = &item.content else {
+            panic!("not a component");
+        };
+        assert!(nodes.iter().all(|node| node.component_source.is_none()));
+
+        // The title keeps the fill set on the instance, the rest follows the component
+        let mut replacement = item
+            .instantiate(Point::default(), Some(NodeId::new(20)), ids_from(30))
+            .unwrap();
+        keep_overrides(&instance, &mut replacement);
+        assert_eq!(replacement[0].fill, parse_color("#000000"));
+        assert_eq!(replacement[1].fill, red);
+        assert_eq!(replacement[1].layout.width, 120.0);
+
+        // A fill set back to the component's isn't an override anymore
+        instance[1].fill = Some(Hsla::white());
+        let mut replacement = item
+            .instantiate(Point::default(), Some(NodeId::new(20)), ids_from(30))
+            .unwrap();
+        keep_overrides(&instance, &mut replacement);
+        assert_eq!(replacement[1].fill, parse_color("#00ff00"));
+    }
+
+    #[test]
+    fn test_apply_styles_and_tokens() {
+        let mut library = Library::default();
+        let mut source = FrameNode::new(NodeId::new(1));
+        source.set_corner_radius(6.0);
+        source.fill = Some(Hsla::black());
+        let style = SavedStyle::from_style(&NodeStyle::of(&source));
+        let style_link = library.publish(None, "Dark".into(), LibraryContent::Style { style });
+        let token_link = library.publish(None, "Accent".into(), token("#ff0000"));
+
+        let mut target = FrameNode::new(NodeId::new(2));
+        target.border_width = 0.0;
+        let mut style = NodeStyle::of(&target);
+        let token = library.item(&token_link.item).unwrap();
+        token
+            .apply_to_style(&mut style, ColorTarget::Stroke)
+            .unwrap();
+        assert_eq!(style.border_color, parse_color("#ff0000"));
+        assert_eq!(style.border_width, 1.0);
+        assert_eq!(style.links.border, Some(token_link.clone()));
+
+        // A style takes over from the tokens applied before it
+        let dark = library.item(&style_link.item).unwrap();
+        dark.apply_to_style(&mut style, ColorTarget::Fill).unwrap();
+        assert_eq!(style.fill, Some(Hsla::black()));
+        assert_eq!(style.corner_radii, Corners::all(6.0));
+        assert_eq!(style.links.style, Some(style_link));
+        assert_eq!(style.links.border, None);
+    }
+
+    #[test]
+    fn test_updates_for_linked_nodes() {
+        let mut library = Library::default();
+        let component = library.publish(
+            None,
+            "Card".into(),
+            LibraryContent::Component { nodes: card(1, 2) },
+        );
+        let accent = library.publish(None, "Accent".into(), token("#ff0000"));
+
+        let mut instance = FrameNode::new(NodeId::new(10));
+        instance.component = Some(component.clone());
+        instance.style_links.border = Some(accent.clone());
+        let mut stale = FrameNode::new(NodeId::new(11));
+        // A component id used as a token isn't something an update can apply
+        stale.style_links.fill = Some(LibraryLink {
+            revision: 0,
+            ..component.clone()
+        });
+        stale.style_links.style = Some(LibraryLink {
+            item: "removed".into(),
+            revision: 0,
+        });
+        let nodes = vec![instance, stale];
+        assert!(library.updates_for(&nodes).is_empty());
+
+        library.publish(Some(&accent), "Accent".into(), token("#00ff00"));
+        let mut changed = card(1, 2);
+        changed[0].fill = Some("#000000".into());
+        library.publish(
+            Some(&component),
+            "Card".into(),
+            LibraryContent::Component { nodes: changed },
+        );
+
+        let updates: Vec<(NodeId, LinkedAs, u32, u32)> = library
+            .updates_for(&nodes)
+            .into_iter()
+            .map(|update| {
+                (
+                    update.node_id,
+                    update.linked_as,
+                    update.revision,
+                    update.item.revision,
+                )
+            })
+            .collect();
+        assert_eq!(
+            updates,
+            [
+                (NodeId::new(10), LinkedAs::Component, 1, 2),
+                (NodeId::new(10), LinkedAs::Token(ColorTarget::Stroke), 1, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recolor_token() {
+        let mut library = Library::default();
+        let accent = library.publish(None, "Accent".into(), token("#ff0000"));
+        let card = library.publish(
+            None,
+            "Card".into(),
+            LibraryContent::Component { nodes: card(1, 2) },
+        );
+        let mut node = FrameNode::new(NodeId::new(3));
+        node.style_links.fill = Some(accent.clone());
+
+        let green = parse_color("#00ff00").unwrap();
+        let recolored = library.recolor_token(&accent.item, green).unwrap();
+        assert_eq!(recolored.revision, 2);
+        assert_eq!(library.items[0].name, "Accent");
+        assert_eq!(library.items[0].content, token("#00ff00"));
+        let updates = library.updates_for(std::slice::from_ref(&node));
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].linked_as, LinkedAs::Token(ColorTarget::Fill));
+
+        // The same color again isn't a change
+        let again = library.recolor_token(&accent.item, green).unwrap();
+        assert_eq!(again, recolored);
+
+        assert!(library.recolor_token(&card.item, green).is_err());
+        assert!(library.recolor_token("removed", green).is_err());
+    }
+
+    #[test]
+    fn test_newer_library_is_refused() {
+        let json = format!(r#"{{"version": {}, "items": []}}"#, LIBRARY_VERSION + 1);
+        assert!(Library::parse(&json).is_err());
+
+        let mut library = Library::default();
+        library.publish(None, "Accent".into(), token("#ff0000"));
+        let json = serde_json::to_string(&library).unwrap();
+        assert!(json.contains(r#""kind":"color_token""#));
+        assert_eq!(Library::parse(&json).unwrap(), library);
+    }
+}
@@ -0,0 +1,465 @@
+//! # Node System
+//!
+//! This module defines the core data model for visual elements in Luna. The node system
+//! implements a type-safe approach to representing and manipulating canvas elements.
+//!
+//! ## Key Components
+//!
+//! - **NodeId**: Unique identifier for canvas nodes
+//! - **NodeType**: Enumeration of supported element types (Frame, etc.)
+//! - **NodeLayout**: Position and dimension properties shared by all nodes
+//! - **NodeCommon**: Trait defining shared behavior across node types
+//! - **BlendMode**: How a node's colors combine with the colors beneath it
+//!
+//! The node system focuses on managing the data model aspect of elements, while
+//! the scene graph handles spatial relationships and transformations. This separation
+//! allows for efficient data management independent of visual representation.
+
+use gpui::{point, Bounds, Corners, Hsla, Point, Rgba, Size};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+pub mod frame;
+pub mod path;
+
+/// A unique identifier for a canvas node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(pub usize);
+
+impl NodeId {
+    pub fn new(id: usize) -> Self {
+        NodeId(id)
+    }
+}
+
+impl std::fmt::Display for NodeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Node-{}", self.0)
+    }
+}
+
+/// Types of nodes that can be rendered on the canvas
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeType {
+    /// A frame that can contain other nodes
+    Frame,
+    /// A frame drawn as a vector path
+    Path,
+}
+
+/// Layout information for a node
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeLayout {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl NodeLayout {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn bounds(&self) -> Bounds<f32> {
+        Bounds {
+            origin: Point::new(self.x, self.y),
+            size: Size::new(self.width, self.height),
+        }
+    }
+}
+
+/// Layout information for a node
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shadow {
+    /// What color should the shadow have?
+    pub color: Hsla,
+    /// How should it be offset from its element?
+    pub offset: Point<f32>,
+    /// How much should the shadow be blurred?
+    pub blur_radius: f32,
+    /// How much should the shadow spread?
+    pub spread_radius: f32,
+    /// Is the shadow cast inward, inside the element's edges?
+    pub inset: bool,
+}
+
+impl From<gpui::BoxShadow> for Shadow {
+    fn from(value: gpui::BoxShadow) -> Self {
+        Shadow {
+            color: value.color,
+            offset: point(value.offset.x.0, value.offset.y.0),
+            blur_radius: value.blur_radius.0,
+            spread_radius: value.spread_radius.0,
+            inset: false,
+        }
+    }
+}
+
+/// How a node's colors combine with what is painted beneath it
+///
+/// These are the separable modes of CSS `mix-blend-mode`, and are exported as such.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 6] = [
+        BlendMode::Normal,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+        BlendMode::Overlay,
+        BlendMode::Darken,
+        BlendMode::Lighten,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BlendMode::Normal => "Normal",
+            BlendMode::Multiply => "Multiply",
+            BlendMode::Screen => "Screen",
+            BlendMode::Overlay => "Overlay",
+            BlendMode::Darken => "Darken",
+            BlendMode::Lighten => "Lighten",
+        }
+    }
+
+    /// The `mix-blend-mode` keyword for this mode
+    pub fn css_name(self) -> &'static str {
+        match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::Darken => "darken",
+            BlendMode::Lighten => "lighten",
+        }
+    }
+
+    pub fn from_css_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.css_name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The color `source` takes on when painted over `backdrop`, before alpha compositing
+    ///
+    /// Follows the compositing spec: the blended color only shows as far as the backdrop
+    /// is opaque, and `source` keeps its own alpha.
+    pub fn blend(self, backdrop: Hsla, source: Hsla) -> Hsla {
+        if self == BlendMode::Normal {
+            return source;
+        }
+
+        let (backdrop, source) = (backdrop.to_rgb(), source.to_rgb());
+        let coverage = backdrop.a;
+        let channel = |backdrop: f32, source: f32| {
+            let blended = match self {
+                BlendMode::Normal => source,
+                BlendMode::Multiply => backdrop * source,
+                BlendMode::Screen => screen(backdrop, source),
+                BlendMode::Overlay if backdrop <= 0.5 => 2.0 * backdrop * source,
+                BlendMode::Overlay => screen(2.0 * backdrop - 1.0, source),
+                BlendMode::Darken => backdrop.min(source),
+                BlendMode::Lighten => backdrop.max(source),
+            };
+            source + (blended - source) * coverage
+        };
+        Rgba {
+            r: channel(backdrop.r, source.r),
+            g: channel(backdrop.g, source.g),
+            b: channel(backdrop.b, source.b),
+            a: source.a,
+        }
+        .into()
+    }
+}
+
+fn screen(backdrop: f32, source: f32) -> f32 {
+    backdrop + source - backdrop * source
+}
+
+/// The radius shared by all four corners, `None` when they differ
+pub fn uniform_radius(radii: &Corners<f32>) -> Option<f32> {
+    let radius = radii.top_left;
+    (radii.top_right == radius && radii.bottom_right == radius && radii.bottom_left == radius)
+        .then_some(radius)
+}
+
+/// Whether `point` is inside `bounds` once its corners are rounded by `radii`
+///
+/// Radii are capped at half the shorter side, as they are when drawn, so a point in the
+/// part of a corner that was rounded off misses.
+pub fn rounded_rect_contains(bounds: Bounds<f32>, radii: &Corners<f32>, point: Point<f32>) -> bool {
+    if !bounds.contains(&point) {
+        return false;
+    }
+
+    let max_radius = bounds.size.width.min(bounds.size.height) / 2.0;
+    // Each corner along with the direction pointing into the bounds from it
+    let corners = [
+        (radii.top_left, bounds.origin, (1.0, 1.0)),
+        (radii.top_right, bounds.top_right(), (-1.0, 1.0)),
+        (radii.bottom_right, bounds.bottom_right(), (-1.0, -1.0)),
+        (radii.bottom_left, bounds.bottom_left(), (1.0, -1.0)),
+    ];
+    corners
+        .into_iter()
+        .all(|(radius, corner, (inward_x, inward_y))| {
+            let radius = radius.clamp(0.0, max_radius);
+            let center = Point::new(corner.x + inward_x * radius, corner.y + inward_y * radius);
+            let (offset_x, offset_y) = (point.x - center.x, point.y - center.y);
+            let beyond_center = offset_x * inward_x < 0.0 && offset_y * inward_y < 0.0;
+            !beyond_center || offset_x.hypot(offset_y) <= radius
+        })
+}
+
+/// Core trait defining the common interface for all canvas elements
+///
+/// This trait establishes a unified API for interacting with different node types,
+/// enforcing consistent behavior for essential operations like:
+/// - Identity and type determination
+/// - Layout manipulation and bounds calculation
+/// - Visual styling (fill, border, corner radius, opacity, blending)
+/// - Spatial queries (point containment)
+///
+/// By implementing this trait, node types gain consistent behavior while allowing
+/// type-specific customization. This enables polymorphic operations across
+/// heterogeneous collections of nodes.
+pub trait NodeCommon: std::fmt::Debug {
+    /// Get the node's ID
+    fn id(&self) -> NodeId;
+
+    /// Get the node type
+    fn node_type(&self) -> NodeType;
+
+    /// Get the display name, falling back to one derived from the type and id
+    fn name(&self) -> String;
+
+    /// Set a custom name, `None` restores the generated one
+    fn set_name(&mut self, name: Option<String>);
+
+    /// Get the layout for this node
+    fn layout(&self) -> &NodeLayout;
+
+    /// Get mutable access to the layout
+    fn layout_mut(&mut self) -> &mut NodeLayout;
+
+    /// Get the fill color
+    fn fill(&self) -> Option<Hsla>;
+
+    /// Set the fill color
+    fn set_fill(&mut self, color: Option<Hsla>);
+
+    /// Get the border color
+    fn border_color(&self) -> Option<Hsla>;
+
+    /// Get the border width
+    fn border_width(&self) -> f32;
+
+    /// Set the border properties
+    fn set_border(&mut self, color: Option<Hsla>, width: f32);
+
+    /// Get the corner radius, the largest one when the corners differ
+    fn corner_radius(&self) -> f32;
+
+    /// Set the same corner radius on all four corners
+    fn set_corner_radius(&mut self, radius: f32);
+
+    /// Get the radius of each corner
+    fn corner_radii(&self) -> Corners<f32>;
+
+    /// Set the radius of each corner
+    fn set_corner_radii(&mut self, radii: Corners<f32>);
+
+    fn shadows(&self) -> SmallVec<[Shadow; 1]>;
+
+    fn set_shadows(&mut self, shadows: SmallVec<[Shadow; 1]>);
+
+    /// Get the opacity, from 0 for invisible to 1 for opaque
+    ///
+    /// This is the node's own opacity, a node inside a translucent frame is drawn
+    /// fainter still.
+    fn opacity(&self) -> f32;
+
+    /// Set the opacity, clamped to the 0 to 1 range
+    fn set_opacity(&mut self, opacity: f32);
+
+    /// Get how the node's colors combine with what is beneath it
+    fn blend_mode(&self) -> BlendMode;
+
+    /// Set how the node's colors combine with what is beneath it
+    fn set_blend_mode(&mut self, blend_mode: BlendMode);
+
+    /// Check if a point is inside this node, leaving out rounded-off corners
+    fn contains_point(&self, point: &Point<f32>) -> bool {
+        rounded_rect_contains(self.layout().bounds(), &self.corner_radii(), *point)
+    }
+
+    /// Get the bounds of this node
+    fn bounds(&self) -> Bounds<f32> {
+        self.layout().bounds()
+    }
+}
+
+/// Factory for generating nodes with guaranteed unique identifiers
+///
+/// NodeFactory centralizes node creation and ID allocation, ensuring that:
+/// - Each node receives a unique NodeId
+/// - Node creation follows consistent initialization patterns
+/// - Factory methods encapsulate creation logic for different node types
+///
+/// This pattern allows the application to maintain referential integrity
+/// across the node system without exposing ID generation details.
+#[derive(Debug)]
+pub struct NodeFactory {
+    /// Internal counter for generating sequential node IDs
+    next_id: usize,
+}
+
+impl Default for NodeFactory {
+    fn default() -> Self {
+        Self { next_id: 1 }
+    }
+}
+
+impl NodeFactory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generate a new unique node ID
+    pub fn next_id(&mut self) -> NodeId {
+        let id = NodeId::new(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    /// Create a new frame node
+    pub fn create_frame(&mut self) -> frame::FrameNode {
+        frame::FrameNode::new(self.next_id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_id() {
+        let id = NodeId::new(42);
+        assert_eq!(id.0, 42);
+        assert_eq!(format!("{}", id), "Node-42");
+    }
+
+    #[test]
+    fn test_node_layout() {
+        let layout = NodeLayout::new(10.0, 20.0, 100.0, 200.0);
+        assert_eq!(layout.x, 10.0);
+        assert_eq!(layout.y, 20.0);
+        assert_eq!(layout.width, 100.0);
+        assert_eq!(layout.height, 200.0);
+
+        let bounds = layout.bounds();
+        assert_eq!(bounds.origin.x, 10.0);
+        assert_eq!(bounds.origin.y, 20.0);
+        assert_eq!(bounds.size.width, 100.0);
+        assert_eq!(bounds.size.height, 200.0);
+    }
+
+    #[test]
+    fn test_rounded_rect_contains() {
+        let bounds = NodeLayout::new(0.0, 0.0, 100.0, 50.0).bounds();
+        let radii = Corners {
+            top_left: 20.0,
+            top_right: 0.0,
+            bottom_right: 200.0,
+            bottom_left: 0.0,
+        };
+
+        // The very corner is cut away, a point well inside the arc isn't
+        assert!(!rounded_rect_contains(bounds, &radii, point(1.0, 1.0)));
+        assert!(rounded_rect_contains(bounds, &radii, point(8.0, 8.0)));
+        // Square corners reach all the way out
+        assert!(rounded_rect_contains(bounds, &radii, point(99.5, 0.5)));
+        assert!(rounded_rect_contains(bounds, &radii, point(0.5, 49.5)));
+        // Radii past half the height are capped at 25
+        assert!(!rounded_rect_contains(bounds, &radii, point(98.0, 48.0)));
+        assert!(rounded_rect_contains(bounds, &radii, point(80.0, 40.0)));
+        // Outside the bounds is always a miss
+        assert!(!rounded_rect_contains(bounds, &radii, point(50.0, 60.0)));
+    }
+
+    #[test]
+    fn test_blend_modes() {
+        let backdrop = Hsla::from(Rgba {
+            r: 0.5,
+            g: 0.2,
+            b: 1.0,
+            a: 1.0,
+        });
+        let source = Hsla::from(Rgba {
+            r: 0.5,
+            g: 0.8,
+            b: 0.0,
+            a: 0.6,
+        });
+        let channels_over = |backdrop: Hsla, mode: BlendMode| {
+            let color = mode.blend(backdrop, source).to_rgb();
+            [color.r, color.g, color.b, color.a].map(|c| (c * 100.0).round() / 100.0)
+        };
+        let channels = |mode| channels_over(backdrop, mode);
+
+        assert_eq!(channels(BlendMode::Normal), [0.5, 0.8, 0.0, 0.6]);
+        assert_eq!(channels(BlendMode::Multiply), [0.25, 0.16, 0.0, 0.6]);
+        assert_eq!(channels(BlendMode::Screen), [0.75, 0.84, 1.0, 0.6]);
+        assert_eq!(channels(BlendMode::Overlay), [0.5, 0.32, 1.0, 0.6]);
+        assert_eq!(channels(BlendMode::Darken), [0.5, 0.2, 0.0, 0.6]);
+        assert_eq!(channels(BlendMode::Lighten), [0.5, 0.8, 1.0, 0.6]);
+
+        // Over a clear backdrop there is nothing to blend with
+        assert_eq!(
+            channels_over(backdrop.opacity(0.0), BlendMode::Multiply),
+            [0.5, 0.8, 0.0, 0.6]
+        );
+    }
+
+    #[test]
+    fn test_blend_mode_css_names() {
+        for mode in BlendMode::ALL {
+            assert_eq!(BlendMode::from_css_name(mode.css_name()), Some(mode));
+        }
+        assert_eq!(
+            BlendMode::from_css_name(" Multiply "),
+            Some(BlendMode::Multiply)
+        );
+        assert_eq!(BlendMode::from_css_name("hue"), None);
+    }
+
+    #[test]
+    fn test_node_factory() {
+        let mut factory = NodeFactory::new();
+
+        let id1 = factory.next_id();
+        let id2 = factory.next_id();
+
+        assert_eq!(id1.0, 1);
+        assert_eq!(id2.0, 2);
+
+        let frame = factory.create_frame();
+        assert_eq!(frame.id().0, 3);
+    }
+}
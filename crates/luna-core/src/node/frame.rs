@@ -0,0 +1,567 @@
+//! # Frame Node Implementation
+//!
+//! Implements the FrameNode type, a fundamental container node that can hold child nodes.
+//! Frames are the core building blocks of the Luna canvas system, serving as containers
+//! for other visual elements with configurable styling properties.
+
+use crate::{
+    layout::{AutoLayout, Pins},
+    library::{ComponentSource, LibraryLink, StyleLinks},
+    node::{path::VectorPath, uniform_radius, BlendMode, NodeCommon, NodeId, NodeLayout, NodeType},
+};
+use gpui::{Corners, Hsla, Point, Size};
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use std::collections::{HashMap, HashSet};
+
+use super::Shadow;
+
+/// How a frame treats children that extend past its bounds
+///
+/// Children are always clipped to their frame on the canvas. `Scroll` additionally
+/// lets the content be scrolled within the frame while previewing, so a long page can
+/// be prototyped in a frame the size of the screen it will be shown on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Overflow {
+    #[default]
+    Visible,
+    Scroll,
+}
+
+impl Overflow {
+    pub const ALL: [Overflow; 2] = [Overflow::Visible, Overflow::Scroll];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Overflow::Visible => "Visible",
+            Overflow::Scroll => "Scroll",
+        }
+    }
+}
+
+/// Concrete implementation of a frame visual element
+///
+/// FrameNode represents a rectangular element that can contain children nodes with configurable:
+/// - Position and dimensions via NodeLayout
+/// - Fill color (optional)
+/// - Border properties (color and width)
+/// - Corner radius for rounded rectangles
+/// - Opacity and blend mode, which carry over to the children
+/// - Children nodes that are displayed inside and clipped to the frame bounds
+///
+/// As the fundamental building block in the canvas system, frames
+/// serve as the basis for many other visual elements and are optimized
+/// for efficient rendering and manipulation. Frames can contain other nodes as children,
+/// creating a hierarchy of elements.
+#[derive(Debug, Clone)]
+pub struct FrameNode {
+    pub id: NodeId,
+    /// User-assigned name, frames without one are listed as "Frame <id>"
+    pub name: Option<String>,
+    pub layout: NodeLayout,
+    pub fill: Option<Hsla>,
+    pub border_color: Option<Hsla>,
+    pub border_width: f32,
+    pub corner_radius: f32,
+    /// Radius of each corner when they differ, `corner_radius` is then the largest
+    pub corner_radii: Option<Corners<f32>>,
+    pub shadows: SmallVec<[Shadow; 1]>,
+    /// From 0 to 1, children are multiplied by it on top of their own
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+    pub children: Vec<NodeId>,
+    pub overflow: Overflow,
+    /// How far the content is scrolled, only non-zero for scroll frames in preview
+    pub scroll_offset: Point<f32>,
+    /// Whether the layer list hides this frame's children
+    pub collapsed: bool,
+    /// Left out of the canvas, exports and prints, children included
+    pub hidden: bool,
+    /// Can't be picked or dragged on the canvas, only selected from the layer list
+    pub locked: bool,
+    /// Left out of what dragged layers snap to, children included
+    pub snap_excluded: bool,
+    /// Address opened when the frame is clicked while previewing
+    pub link: Option<String>,
+    /// Library component this frame is an instance of, only set on the instance's root
+    pub component: Option<LibraryLink>,
+    /// The node of a library component this frame was copied from, on every frame of
+    /// an instance
+    pub component_source: Option<ComponentSource>,
+    /// Library style and color tokens the frame's style was applied from
+    pub style_links: StyleLinks,
+    /// What the frame keeps when its parent is resized
+    pub pins: Pins,
+    /// Stacking of the children, which then can't be placed by hand
+    pub auto_layout: Option<AutoLayout>,
+    /// Shape drawn instead of the frame's box, stretched over its bounds
+    pub path: Option<VectorPath>,
+}
+
+impl FrameNode {
+    pub fn new(id: NodeId) -> Self {
+        Self {
+            id,
+            name: None,
+            layout: NodeLayout::new(0.0, 0.0, 100.0, 100.0),
+            fill: Some(Hsla::white()),
+            border_color: Some(Hsla::black()),
+            border_width: 1.0,
+            corner_radius: 0.0,
+            corner_radii: None,
+            shadows: smallvec![],
+            opacity: 1.0,
+            blend_mode: BlendMode::default(),
+            children: Vec::new(),
+            overflow: Overflow::default(),
+            scroll_offset: Point::default(),
+            collapsed: false,
+            hidden: false,
+            locked: false,
+            snap_excluded: false,
+            link: None,
+            component: None,
+            component_source: None,
+            style_links: StyleLinks::default(),
+            pins: Pins::default(),
+            auto_layout: None,
+            path: None,
+        }
+    }
+
+    /// Create a frame with specific dimensions and position
+    pub fn with_rect(id: NodeId, x: f32, y: f32, width: f32, height: f32) -> Self {
+        let mut node = Self::new(id);
+        node.layout = NodeLayout::new(x, y, width, height);
+        node
+    }
+
+    /// Add a child node to this frame
+    ///
+    /// Returns true if the child was added (it wasn't already a child)
+    pub fn add_child(&mut self, child_id: NodeId) -> bool {
+        if !self.children.contains(&child_id) {
+            self.children.push(child_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Remove a child node from this frame
+    ///
+    /// Returns true if the child was removed (it was present)
+    pub fn remove_child(&mut self, child_id: NodeId) -> bool {
+        let len_before = self.children.len();
+        self.children.retain(|id| *id != child_id);
+        len_before != self.children.len()
+    }
+
+    /// Check if this frame contains a specific child
+    pub fn has_child(&self, child_id: NodeId) -> bool {
+        self.children.contains(&child_id)
+    }
+
+    /// Get a reference to the children of this frame
+    pub fn children(&self) -> &Vec<NodeId> {
+        &self.children
+    }
+
+    pub fn is_scrollable(&self) -> bool {
+        self.overflow == Overflow::Scroll
+    }
+}
+
+/// Clamp a scroll offset so the content can't be scrolled past its edges
+///
+/// `content_extent` is the far corner of the content in frame-local coordinates, content
+/// that fits inside the frame along an axis can't be scrolled along it at all.
+pub fn clamp_scroll_offset(
+    offset: Point<f32>,
+    frame_size: Size<f32>,
+    content_extent: Point<f32>,
+) -> Point<f32> {
+    let max_x = (content_extent.x - frame_size.width).max(0.0);
+    let max_y = (content_extent.y - frame_size.height).max(0.0);
+    Point::new(offset.x.clamp(0.0, max_x), offset.y.clamp(0.0, max_y))
+}
+
+/// Turn what was typed into a link field into an address that can be opened
+///
+/// Blank text clears the link. Addresses without a scheme are assumed to be web pages,
+/// so `example.com` opens as `https://example.com`.
+pub fn normalize_link(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else if text.contains("://") || text.starts_with("mailto:") {
+        Some(text.to_string())
+    } else {
+        Some(format!("https://{text}"))
+    }
+}
+
+impl NodeCommon for FrameNode {
+    fn id(&self) -> NodeId {
+        self.id
+    }
+
+    fn node_type(&self) -> NodeType {
+        if self.path.is_some() {
+            NodeType::Path
+        } else {
+            NodeType::Frame
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| match self.node_type() {
+            NodeType::Frame => format!("Frame {}", self.id.0),
+            NodeType::Path => format!("Path {}", self.id.0),
+        })
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    fn layout(&self) -> &NodeLayout {
+        &self.layout
+    }
+
+    fn layout_mut(&mut self) -> &mut NodeLayout {
+        &mut self.layout
+    }
+
+    fn fill(&self) -> Option<Hsla> {
+        self.fill
+    }
+
+    fn set_fill(&mut self, color: Option<Hsla>) {
+        self.fill = color;
+    }
+
+    fn border_color(&self) -> Option<Hsla> {
+        self.border_color
+    }
+
+    fn border_width(&self) -> f32 {
+        self.border_width
+    }
+
+    fn set_border(&mut self, color: Option<Hsla>, width: f32) {
+        self.border_color = color;
+        self.border_width = width;
+    }
+
+    fn corner_radius(&self) -> f32 {
+        self.corner_radius
+    }
+
+    fn set_corner_radius(&mut self, radius: f32) {
+        self.corner_radius = radius;
+        self.corner_radii = None;
+    }
+
+    fn corner_radii(&self) -> Corners<f32> {
+        self.corner_radii
+            .unwrap_or_else(|| Corners::all(self.corner_radius))
+    }
+
+    fn set_corner_radii(&mut self, radii: Corners<f32>) {
+        match uniform_radius(&radii) {
+            Some(radius) => self.set_corner_radius(radius),
+            None => {
+                self.corner_radius = radii
+                    .top_left
+                    .max(radii.top_right)
+                    .max(radii.bottom_right)
+                    .max(radii.bottom_left);
+                self.corner_radii = Some(radii);
+            }
+        }
+    }
+
+    fn shadows(&self) -> SmallVec<[Shadow; 1]> {
+        self.shadows.clone()
+    }
+
+    fn set_shadows(&mut self, shadows: SmallVec<[Shadow; 1]>) {
+        self.shadows = shadows
+    }
+
+    fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+}
+
+/// The visual properties of a node, everything but its geometry, name and children
+#[derive(Debug, Clone)]
+pub struct NodeStyle {
+    pub fill: Option<Hsla>,
+    pub border_color: Option<Hsla>,
+    pub border_width: f32,
+    pub corner_radii: Corners<f32>,
+    pub shadows: SmallVec<[Shadow; 1]>,
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+    pub overflow: Overflow,
+    /// Where in the library the style and colors were applied from
+    pub links: StyleLinks,
+}
+
+impl NodeStyle {
+    pub fn of(node: &FrameNode) -> Self {
+        Self {
+            fill: node.fill(),
+            border_color: node.border_color(),
+            border_width: node.border_width(),
+            corner_radii: node.corner_radii(),
+            shadows: node.shadows(),
+            opacity: node.opacity(),
+            blend_mode: node.blend_mode(),
+            overflow: node.overflow,
+            links: node.style_links.clone(),
+        }
+    }
+
+    pub fn apply_to(&self, node: &mut FrameNode) {
+        node.set_fill(self.fill);
+        node.set_border(self.border_color, self.border_width);
+        node.set_corner_radii(self.corner_radii);
+        node.set_shadows(self.shadows.clone());
+        node.set_opacity(self.opacity);
+        node.set_blend_mode(self.blend_mode);
+        if node.overflow != self.overflow {
+            node.overflow = self.overflow;
+            node.scroll_offset = Default::default();
+        }
+        node.style_links = self.links.clone();
+    }
+}
+
+/// Copies of `nodes` under ids from `next_id`, with the top-level ones moved by `offset`
+///
+/// Nodes keep their order. Children that aren't part of `nodes` are dropped from the
+/// copies.
+pub fn instantiate(
+    nodes: &[FrameNode],
+    offset: Point<f32>,
+    mut next_id: impl FnMut() -> NodeId,
+) -> Vec<FrameNode> {
+    let ids: HashMap<NodeId, NodeId> = nodes.iter().map(|node| (node.id, next_id())).collect();
+    let children: HashSet<NodeId> = nodes
+        .iter()
+        .flat_map(|node| node.children.iter().copied())
+        .collect();
+
+    nodes
+        .iter()
+        .map(|node| {
+            let mut copy = node.clone();
+            copy.id = ids[&node.id];
+            copy.children = node
+                .children
+                .iter()
+                .filter_map(|child| ids.get(child).copied())
+                .collect();
+            if !children.contains(&node.id) {
+                copy.layout.x += offset.x;
+                copy.layout.y += offset.y;
+            }
+            copy
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::point;
+
+    fn ids_from(first: usize) -> impl FnMut() -> NodeId {
+        let mut next = first;
+        move || {
+            next += 1;
+            NodeId::new(next - 1)
+        }
+    }
+
+    fn frames() -> Vec<FrameNode> {
+        let mut card = FrameNode::with_rect(NodeId::new(1), 100.0, 100.0, 200.0, 120.0);
+        card.children = vec![NodeId::new(2)];
+        let title = FrameNode::with_rect(NodeId::new(2), 16.0, 16.0, 80.0, 20.0);
+        vec![card, title]
+    }
+
+    #[test]
+    fn test_frame_node() {
+        let id = NodeId::new(2);
+        let frame = FrameNode::new(id);
+
+        assert_eq!(frame.node_type(), NodeType::Frame);
+        assert_eq!(frame.id(), id);
+        assert_eq!(frame.corner_radius(), 0.0);
+        assert_eq!(frame.opacity(), 1.0);
+        assert_eq!(frame.blend_mode(), BlendMode::Normal);
+        assert!(frame.children().is_empty());
+    }
+
+    #[test]
+    fn test_corner_radii() {
+        let mut frame = FrameNode::new(NodeId::new(3));
+        let radii = Corners {
+            top_left: 4.0,
+            top_right: 12.0,
+            bottom_right: 0.0,
+            bottom_left: 4.0,
+        };
+
+        frame.set_corner_radii(radii);
+        assert_eq!(frame.corner_radii(), radii);
+        assert_eq!(frame.corner_radius(), 12.0);
+
+        // Four equal corners, or a single radius, go back to being uniform
+        frame.set_corner_radii(Corners::all(6.0));
+        assert_eq!(frame.corner_radii, None);
+        assert_eq!(frame.corner_radius(), 6.0);
+
+        frame.set_corner_radii(radii);
+        frame.set_corner_radius(2.0);
+        assert_eq!(frame.corner_radii(), Corners::all(2.0));
+    }
+
+    #[test]
+    fn test_frame_name() {
+        let mut frame = FrameNode::new(NodeId::new(7));
+        assert_eq!(frame.name(), "Frame 7");
+
+        frame.set_name(Some("Header".to_string()));
+        assert_eq!(frame.name(), "Header");
+
+        frame.set_name(None);
+        assert_eq!(frame.name(), "Frame 7");
+
+        frame.path = Some(VectorPath::default());
+        assert_eq!(frame.node_type(), NodeType::Path);
+        assert_eq!(frame.name(), "Path 7");
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let id = NodeId::new(1);
+        let frame = FrameNode::with_rect(id, 10.0, 10.0, 100.0, 100.0);
+
+        // Test points inside and outside
+        let point_inside = Point::new(50.0, 50.0);
+        let point_outside = Point::new(200.0, 200.0);
+
+        assert!(frame.contains_point(&point_inside));
+        assert!(!frame.contains_point(&point_outside));
+    }
+
+    #[test]
+    fn test_frame_children() {
+        let parent_id = NodeId::new(1);
+        let child_id = NodeId::new(2);
+
+        let mut frame = FrameNode::new(parent_id);
+
+        // Initially no children
+        assert_eq!(frame.children().len(), 0);
+
+        // Add a child
+        frame.add_child(child_id);
+        assert_eq!(frame.children().len(), 1);
+        assert_eq!(frame.children()[0], child_id);
+
+        // Remove the child
+        frame.remove_child(child_id);
+        assert_eq!(frame.children().len(), 0);
+    }
+
+    #[test]
+    fn test_clamp_scroll_offset() {
+        let frame_size = Size::new(100.0, 200.0);
+        let content_extent = Point::new(80.0, 500.0);
+
+        // Content narrower than the frame doesn't scroll horizontally
+        let offset = clamp_scroll_offset(Point::new(40.0, 120.0), frame_size, content_extent);
+        assert_eq!(offset, Point::new(0.0, 120.0));
+
+        // Scrolling stops at the last of the content, and at the top
+        let offset = clamp_scroll_offset(Point::new(0.0, 900.0), frame_size, content_extent);
+        assert_eq!(offset, Point::new(0.0, 300.0));
+        let offset = clamp_scroll_offset(Point::new(0.0, -50.0), frame_size, content_extent);
+        assert_eq!(offset, Point::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_normalize_link() {
+        assert_eq!(normalize_link("   "), None);
+        assert_eq!(
+            normalize_link(" example.com/docs "),
+            Some("https://example.com/docs".to_string())
+        );
+        assert_eq!(
+            normalize_link("http://localhost:8080"),
+            Some("http://localhost:8080".to_string())
+        );
+        assert_eq!(
+            normalize_link("mailto:hello@example.com"),
+            Some("mailto:hello@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_node_style_round_trip() {
+        let mut node = FrameNode::new(NodeId::new(1));
+        node.overflow = Overflow::Scroll;
+        node.scroll_offset.y = 40.0;
+        let style = NodeStyle::of(&node);
+
+        node.set_fill(None);
+        node.set_corner_radius(12.0);
+        node.overflow = Overflow::Visible;
+        style.apply_to(&mut node);
+
+        assert_eq!(node.fill(), Some(Hsla::white()));
+        assert_eq!(node.corner_radius(), 0.0);
+        assert_eq!(node.overflow, Overflow::Scroll);
+        // Switching overflow back resets the scroll position like the inspector does
+        assert_eq!(node.scroll_offset.y, 0.0);
+    }
+
+    #[test]
+    fn test_instantiate_remaps_ids() {
+        let copies = instantiate(&frames(), point(10.0, 10.0), ids_from(50));
+
+        assert_eq!(copies[0].id, NodeId::new(50));
+        assert_eq!(copies[0].children, vec![NodeId::new(51)]);
+        assert_eq!(copies[1].id, NodeId::new(51));
+        // Only the top-level node moves, the child stays put within it
+        assert_eq!((copies[0].layout.x, copies[0].layout.y), (110.0, 110.0));
+        assert_eq!((copies[1].layout.x, copies[1].layout.y), (16.0, 16.0));
+    }
+
+    #[test]
+    fn test_missing_children_are_dropped() {
+        let mut card = frames().remove(0);
+        card.children.push(NodeId::new(9));
+        let copies = instantiate(&[card], Point::default(), ids_from(10));
+        assert!(copies[0].children.is_empty());
+    }
+}
@@ -0,0 +1,474 @@
+//! # Vector Paths
+//!
+//! A frame can take the shape of a vector path instead of its box, which is what the pen
+//! tool draws. The path runs through a list of anchors, each joined to the next by a
+//! cubic bezier curve. Every anchor has a handle on either side steering the curves into
+//! and out of it, and a handle resting on its anchor makes a sharp corner. A closed path
+//! runs from its last anchor back to the first.
+//!
+//! The frame's fill fills a closed path and its border strokes the outline. Paths are
+//! kept in the frame's own coordinates and stretched along with the frame, so resizing
+//! a path frame scales the path to the new size.
+//!
+//! While the pen draws, the segment it's about to add is measured by its length and its
+//! angle, which can also be typed to place the next anchor exactly.
+
+use crate::document_settings::DocumentSettings;
+use gpui::{Bounds, Point, Size};
+
+/// Straight pieces a curve is split into for measuring and hit testing
+const CURVE_STEPS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anchor {
+    pub position: Point<f32>,
+    /// Steers the curve coming into the anchor
+    pub handle_in: Point<f32>,
+    /// Steers the curve going out of the anchor
+    pub handle_out: Point<f32>,
+}
+
+impl Anchor {
+    /// A sharp corner, both handles resting on the anchor
+    pub fn corner(position: Point<f32>) -> Self {
+        Self {
+            position,
+            handle_in: position,
+            handle_out: position,
+        }
+    }
+
+    /// A smooth anchor, the curve coming in steered by `handle_out` mirrored around it
+    pub fn smooth(position: Point<f32>, handle_out: Point<f32>) -> Self {
+        Self {
+            position,
+            handle_in: Point::new(
+                position.x * 2.0 - handle_out.x,
+                position.y * 2.0 - handle_out.y,
+            ),
+            handle_out,
+        }
+    }
+
+    fn map(self, f: impl Fn(Point<f32>) -> Point<f32>) -> Self {
+        Self {
+            position: f(self.position),
+            handle_in: f(self.handle_in),
+            handle_out: f(self.handle_out),
+        }
+    }
+}
+
+/// A part of a path that can be dragged while editing it, by the index of its anchor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathHandle {
+    Anchor(usize),
+    In(usize),
+    Out(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VectorPath {
+    pub anchors: Vec<Anchor>,
+    pub closed: bool,
+}
+
+impl VectorPath {
+    /// The curves making up the path, each as its start, two handles and end
+    pub fn segments(&self) -> impl Iterator<Item = [Point<f32>; 4]> + '_ {
+        let len = self.anchors.len();
+        let count = if self.closed && len > 1 {
+            len
+        } else {
+            len.saturating_sub(1)
+        };
+        (0..count).map(move |index| {
+            let from = &self.anchors[index];
+            let to = &self.anchors[(index + 1) % len];
+            [from.position, from.handle_out, to.handle_in, to.position]
+        })
+    }
+
+    /// Points along the path close enough together to stand in for its curves
+    pub fn flatten(&self) -> Vec<Point<f32>> {
+        let mut points: Vec<Point<f32>> = self
+            .anchors
+            .first()
+            .map(|anchor| anchor.position)
+            .into_iter()
+            .collect();
+        for segment in self.segments() {
+            if is_straight(&segment) {
+                points.push(segment[3]);
+            } else {
+                points.extend(
+                    (1..=CURVE_STEPS)
+                        .map(|step| cubic_point(&segment, step as f32 / CURVE_STEPS as f32)),
+                );
+            }
+        }
+        points
+    }
+
+    /// The area the path covers, handles left out
+    pub fn bounds(&self) -> Bounds<f32> {
+        let points = self.flatten();
+        let Some(first) = points.first() else {
+            return Bounds::default();
+        };
+        let (mut min, mut max) = (*first, *first);
+        for point in &points {
+            min = Point::new(min.x.min(point.x), min.y.min(point.y));
+            max = Point::new(max.x.max(point.x), max.y.max(point.y));
+        }
+        Bounds {
+            origin: min,
+            size: Size::new(max.x - min.x, max.y - min.y),
+        }
+    }
+
+    /// The path stretched from its own bounds onto `bounds`
+    ///
+    /// A path that's flat along an axis, like a horizontal line, is only moved along it.
+    pub fn fit(&self, bounds: Bounds<f32>) -> VectorPath {
+        let own = self.bounds();
+        let scale = |from: f32, to: f32| if from > 0.0 { to / from } else { 1.0 };
+        let scale_x = scale(own.size.width, bounds.size.width);
+        let scale_y = scale(own.size.height, bounds.size.height);
+        self.map(|point| {
+            Point::new(
+                bounds.origin.x + (point.x - own.origin.x) * scale_x,
+                bounds.origin.y + (point.y - own.origin.y) * scale_y,
+            )
+        })
+    }
+
+    pub fn translate(&self, delta: Point<f32>) -> VectorPath {
+        self.map(|point| Point::new(point.x + delta.x, point.y + delta.y))
+    }
+
+    fn map(&self, f: impl Fn(Point<f32>) -> Point<f32>) -> VectorPath {
+        VectorPath {
+            anchors: self.anchors.iter().map(|anchor| anchor.map(&f)).collect(),
+            closed: self.closed,
+        }
+    }
+
+    /// Whether `point` is within `tolerance` of the outline, or inside a closed path
+    pub fn contains(&self, point: Point<f32>, tolerance: f32) -> bool {
+        let points = self.flatten();
+        let near_outline = match points.as_slice() {
+            [only] => distance(point, *only) <= tolerance,
+            points => points
+                .windows(2)
+                .any(|line| distance_to_line(point, line[0], line[1]) <= tolerance),
+        };
+        near_outline || (self.closed && encloses(&points, point))
+    }
+
+    /// The part of the path within `radius` of `point`
+    ///
+    /// Anchors come first, and handles resting on their anchor can't be picked apart
+    /// from it.
+    pub fn handle_at(&self, point: Point<f32>, radius: f32) -> Option<PathHandle> {
+        let near = |target: Point<f32>| distance(point, target) <= radius;
+        if let Some(index) = self.anchors.iter().position(|anchor| near(anchor.position)) {
+            return Some(PathHandle::Anchor(index));
+        }
+        self.anchors.iter().enumerate().find_map(|(index, anchor)| {
+            if anchor.handle_in != anchor.position && near(anchor.handle_in) {
+                Some(PathHandle::In(index))
+            } else if anchor.handle_out != anchor.position && near(anchor.handle_out) {
+                Some(PathHandle::Out(index))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Move part of the path to `to`, an anchor takes its handles along
+    pub fn move_handle(&mut self, handle: PathHandle, to: Point<f32>) {
+        match handle {
+            PathHandle::Anchor(index) => {
+                if let Some(anchor) = self.anchors.get_mut(index) {
+                    let delta = Point::new(to.x - anchor.position.x, to.y - anchor.position.y);
+                    *anchor = anchor.map(|point| Point::new(point.x + delta.x, point.y + delta.y));
+                }
+            }
+            PathHandle::In(index) => {
+                if let Some(anchor) = self.anchors.get_mut(index) {
+                    anchor.handle_in = to;
+                }
+            }
+            PathHandle::Out(index) => {
+                if let Some(anchor) = self.anchors.get_mut(index) {
+                    anchor.handle_out = to;
+                }
+            }
+        }
+    }
+}
+
+/// Length and angle of the straight segment from `from` to `to`
+///
+/// The angle is in degrees from 0 up to 360, counterclockwise from pointing right, the
+/// way it reads on screen even though canvas y grows downwards.
+pub fn measure_segment(from: Point<f32>, to: Point<f32>) -> (f32, f32) {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let angle = (-dy).atan2(dx).to_degrees().rem_euclid(360.0);
+    // Rounding can land just under 360, which reads better as 0
+    let angle = if angle >= 360.0 { 0.0 } else { angle };
+    (dx.hypot(dy), angle)
+}
+
+/// Where a straight segment leaving `from` ends, given its length and its angle as
+/// [`measure_segment`] gives them
+pub fn segment_end(from: Point<f32>, length: f32, angle: f32) -> Point<f32> {
+    let radians = angle.to_radians();
+    Point::new(
+        from.x + length * radians.cos(),
+        from.y - length * radians.sin(),
+    )
+}
+
+/// A segment typed while drawing with the pen, as its length alone or its length and
+/// angle apart by a comma, like `120` or `120, 45`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentEntry {
+    /// In pixels, typed in the document's unit
+    pub length: f32,
+    /// In degrees, `None` to keep pointing where the pointer does
+    pub angle: Option<f32>,
+}
+
+impl SegmentEntry {
+    pub fn parse(text: &str, settings: &DocumentSettings) -> Option<Self> {
+        let (length, angle) = match text.split_once(',') {
+            Some((length, angle)) => (length, Some(angle)),
+            None => (text, None),
+        };
+        let length = settings.parse_dimension(length)?;
+        let angle = match angle {
+            Some(angle) => Some(
+                angle
+                    .trim()
+                    .trim_end_matches('°')
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|angle| angle.is_finite())?,
+            ),
+            None => None,
+        };
+        Some(Self { length, angle })
+    }
+
+    /// Where the segment ends when drawn from `from`, towards `toward` unless it has an
+    /// angle of its own
+    pub fn end(&self, from: Point<f32>, toward: Point<f32>) -> Point<f32> {
+        let angle = self
+            .angle
+            .unwrap_or_else(|| measure_segment(from, toward).1);
+        segment_end(from, self.length, angle)
+    }
+}
+
+/// Whether both handles of a segment rest on its ends
+fn is_straight(segment: &[Point<f32>; 4]) -> bool {
+    segment[1] == segment[0] && segment[2] == segment[3]
+}
+
+fn cubic_point(segment: &[Point<f32>; 4], t: f32) -> Point<f32> {
+    let u = 1.0 - t;
+    let weights = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+    let mut point = Point::new(0.0, 0.0);
+    for (control, weight) in segment.iter().zip(weights) {
+        point.x += control.x * weight;
+        point.y += control.y * weight;
+    }
+    point
+}
+
+fn distance(a: Point<f32>, b: Point<f32>) -> f32 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+fn distance_to_line(point: Point<f32>, start: Point<f32>, end: Point<f32>) -> f32 {
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return distance(point, start);
+    }
+    let t =
+        (((point.x - start.x) * dx + (point.y - start.y) * dy) / length_squared).clamp(0.0, 1.0);
+    distance(point, Point::new(start.x + dx * t, start.y + dy * t))
+}
+
+/// Even-odd test of `point` against the polygon through `points`
+fn encloses(points: &[Point<f32>], point: Point<f32>) -> bool {
+    let mut inside = false;
+    let mut previous = match points.last() {
+        Some(last) => *last,
+        None => return false,
+    };
+    for current in points {
+        if (current.y > point.y) != (previous.y > point.y) {
+            let crossing = current.x
+                + (point.y - current.y) / (previous.y - current.y) * (previous.x - current.x);
+            if point.x < crossing {
+                inside = !inside;
+            }
+        }
+        previous = *current;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle() -> VectorPath {
+        VectorPath {
+            anchors: vec![
+                Anchor::corner(Point::new(0.0, 0.0)),
+                Anchor::corner(Point::new(100.0, 0.0)),
+                Anchor::corner(Point::new(0.0, 100.0)),
+            ],
+            closed: true,
+        }
+    }
+
+    #[test]
+    fn test_straight_and_curved_segments() {
+        let mut path = triangle();
+        assert_eq!(path.segments().count(), 3);
+        assert_eq!(path.flatten().len(), 4);
+
+        path.closed = false;
+        path.anchors[1] = Anchor::smooth(Point::new(100.0, 0.0), Point::new(100.0, 50.0));
+        assert_eq!(path.segments().count(), 2);
+        assert_eq!(path.flatten().len(), 1 + CURVE_STEPS * 2);
+        // The curve bulges past its anchors
+        let bounds = path.bounds();
+        assert!(bounds.origin.y < 0.0);
+        assert!(bounds.size.height > 100.0);
+    }
+
+    #[test]
+    fn test_fit_stretches_onto_bounds() {
+        let fitted = triangle().fit(Bounds {
+            origin: Point::new(10.0, 20.0),
+            size: Size::new(50.0, 200.0),
+        });
+        let positions: Vec<_> = fitted
+            .anchors
+            .iter()
+            .map(|anchor| anchor.position)
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                Point::new(10.0, 20.0),
+                Point::new(60.0, 20.0),
+                Point::new(10.0, 220.0),
+            ]
+        );
+
+        // A flat line keeps its length along the axis it has none on
+        let line = VectorPath {
+            anchors: vec![
+                Anchor::corner(Point::new(0.0, 5.0)),
+                Anchor::corner(Point::new(10.0, 5.0)),
+            ],
+            closed: false,
+        };
+        let moved = line.fit(Bounds {
+            origin: Point::new(0.0, 0.0),
+            size: Size::new(20.0, 0.0),
+        });
+        assert_eq!(moved.anchors[1].position, Point::new(20.0, 0.0));
+    }
+
+    #[test]
+    fn test_hit_testing() {
+        let mut path = triangle();
+        assert!(path.contains(Point::new(20.0, 20.0), 1.0));
+        assert!(!path.contains(Point::new(80.0, 80.0), 1.0));
+        // Open paths are only hit along the outline
+        path.closed = false;
+        assert!(!path.contains(Point::new(20.0, 20.0), 1.0));
+        assert!(path.contains(Point::new(50.0, 1.0), 2.0));
+
+        path.anchors[1] = Anchor::smooth(Point::new(100.0, 0.0), Point::new(120.0, 0.0));
+        assert_eq!(
+            path.handle_at(Point::new(101.0, 1.0), 4.0),
+            Some(PathHandle::Anchor(1))
+        );
+        assert_eq!(
+            path.handle_at(Point::new(80.0, 0.0), 4.0),
+            Some(PathHandle::In(1))
+        );
+        // Handles resting on their anchor can't be told apart from it
+        assert_eq!(
+            path.handle_at(Point::new(0.0, 0.0), 4.0),
+            Some(PathHandle::Anchor(0))
+        );
+        assert_eq!(path.handle_at(Point::new(50.0, 50.0), 4.0), None);
+    }
+
+    #[test]
+    fn test_moving_an_anchor_takes_its_handles_along() {
+        let mut path = triangle();
+        path.anchors[1] = Anchor::smooth(Point::new(100.0, 0.0), Point::new(120.0, 0.0));
+        path.move_handle(PathHandle::Anchor(1), Point::new(100.0, 10.0));
+        assert_eq!(
+            path.anchors[1],
+            Anchor::smooth(Point::new(100.0, 10.0), Point::new(120.0, 10.0))
+        );
+
+        path.move_handle(PathHandle::Out(1), Point::new(100.0, 40.0));
+        assert_eq!(path.anchors[1].handle_out, Point::new(100.0, 40.0));
+        assert_eq!(path.anchors[1].handle_in, Point::new(80.0, 10.0));
+    }
+
+    #[test]
+    fn test_measure_segment() {
+        let origin = Point::new(10.0, 10.0);
+        assert_eq!(measure_segment(origin, Point::new(20.0, 10.0)), (10.0, 0.0));
+        // Up on screen is 90 degrees, canvas y grows downwards
+        assert_eq!(measure_segment(origin, Point::new(10.0, 0.0)), (10.0, 90.0));
+        assert_eq!(
+            measure_segment(origin, Point::new(10.0, 20.0)),
+            (10.0, 270.0)
+        );
+
+        let end = segment_end(origin, 10.0, 90.0);
+        assert!((end.x - 10.0).abs() < 1e-4 && (end.y - 0.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_segment_entry() {
+        let settings = DocumentSettings::default();
+        assert_eq!(
+            SegmentEntry::parse("120", &settings),
+            Some(SegmentEntry {
+                length: 120.0,
+                angle: None
+            })
+        );
+        assert_eq!(
+            SegmentEntry::parse("120, 45°", &settings),
+            Some(SegmentEntry {
+                length: 120.0,
+                angle: Some(45.0)
+            })
+        );
+        assert_eq!(SegmentEntry::parse("", &settings), None);
+        assert_eq!(SegmentEntry::parse("120,", &settings), None);
+
+        // Without an angle the segment heads for the pointer
+        let entry = SegmentEntry::parse("50", &settings).unwrap();
+        let end = entry.end(Point::new(0.0, 0.0), Point::new(0.0, 10.0));
+        assert!(end.x.abs() < 1e-4 && (end.y - 50.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,269 @@
+//! # Password Protection
+//!
+//! A protected document is written as a small JSON envelope around the encrypted
+//! document. The key is derived from the password with scrypt and a random salt, and
+//! the document's JSON is sealed with XChaCha20-Poly1305, which also detects a wrong
+//! password or a damaged file rather than decrypting either into garbage.
+//!
+//! The envelope carries the same format version as a plain document, so builds that
+//! predate protection refuse it instead of opening it as an empty document. Everything
+//! needed to derive the key again is stored next to the ciphertext, and the password
+//! itself is never written anywhere.
+
+use anyhow::{bail, Context as _};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const SALT_LENGTH: usize = 16;
+
+/// The only cipher written so far, named in the envelope so another can follow
+const CIPHER: &str = "xchacha20poly1305";
+
+/// Why a protected document couldn't be opened, for callers that ask for a password
+/// and try again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtectionError {
+    PasswordRequired,
+    WrongPassword,
+}
+
+impl fmt::Display for ProtectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtectionError::PasswordRequired => write!(f, "the document is password protected"),
+            ProtectionError::WrongPassword => {
+                write!(f, "the password is wrong or the document is damaged")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProtectionError {}
+
+/// Cost of deriving a key, high enough that guessing passwords is slow
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct KeyParams {
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+impl Default for KeyParams {
+    /// The parameters scrypt recommends for interactive use, about 32 MiB of memory
+    fn default() -> Self {
+        Self {
+            log_n: 15,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+impl KeyParams {
+    /// The most expensive parameters a document may ask for, 1 GiB of memory at most
+    ///
+    /// They're read from the file before anything in it can be checked, so without a
+    /// limit a crafted document could make opening it exhaust memory or run for hours.
+    const MAX: KeyParams = KeyParams {
+        log_n: 20,
+        r: 8,
+        p: 4,
+    };
+
+    fn is_within_limits(&self) -> bool {
+        self.log_n <= Self::MAX.log_n && self.r <= Self::MAX.r && self.p <= Self::MAX.p
+    }
+
+    fn derive_key(&self, password: &str, salt: &[u8]) -> anyhow::Result<[u8; 32]> {
+        let params = scrypt::Params::new(self.log_n, self.r, self.p, 32)
+            .map_err(|err| anyhow::anyhow!("invalid key parameters: {err}"))?;
+        let mut key = [0; 32];
+        scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+            .map_err(|err| anyhow::anyhow!("deriving the key: {err}"))?;
+        Ok(key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Protection {
+    cipher: String,
+    #[serde(flatten)]
+    key: KeyParams,
+    salt: String,
+    nonce: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Envelope {
+    version: u32,
+    protection: Protection,
+    /// The encrypted document JSON
+    payload: String,
+}
+
+/// Whether `contents` is a protected document rather than a plain one
+pub fn is_protected(contents: &str) -> bool {
+    #[derive(Deserialize)]
+    struct Probe {
+        protection: Option<serde_json::Value>,
+    }
+    serde_json::from_str::<Probe>(contents).is_ok_and(|probe| probe.protection.is_some())
+}
+
+/// Encrypt the JSON of a document with `password`, tagging it with format `version`
+pub fn seal(contents: &str, password: &str, version: u32) -> anyhow::Result<String> {
+    seal_with(contents, password, version, KeyParams::default())
+}
+
+fn seal_with(
+    contents: &str,
+    password: &str,
+    version: u32,
+    key: KeyParams,
+) -> anyhow::Result<String> {
+    let mut salt = [0; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let cipher = XChaCha20Poly1305::new(&key.derive_key(password, &salt)?.into());
+    let payload = cipher
+        .encrypt(&nonce, contents.as_bytes())
+        .map_err(|_| anyhow::anyhow!("encrypting the document"))?;
+
+    let envelope = Envelope {
+        version,
+        protection: Protection {
+            cipher: CIPHER.to_string(),
+            key,
+            salt: to_hex(&salt),
+            nonce: to_hex(&nonce),
+        },
+        payload: to_hex(&payload),
+    };
+    Ok(serde_json::to_string_pretty(&envelope)?)
+}
+
+/// The JSON of the document sealed in `contents`, failing with a [`ProtectionError`]
+/// when the password is missing or doesn't open it
+pub fn open(contents: &str, password: Option<&str>) -> anyhow::Result<String> {
+    let envelope: Envelope = serde_json::from_str(contents).context("reading the envelope")?;
+    let protection = &envelope.protection;
+    if protection.cipher != CIPHER {
+        bail!("unsupported cipher {:?}", protection.cipher);
+    }
+    if !protection.key.is_within_limits() {
+        bail!("the document's key parameters are too expensive to derive");
+    }
+    let password = password.ok_or(ProtectionError::PasswordRequired)?;
+
+    let salt = from_hex(&protection.salt).context("invalid salt")?;
+    let nonce = from_hex(&protection.nonce)
+        .filter(|nonce| nonce.len() == 24)
+        .context("invalid nonce")?;
+    let payload = from_hex(&envelope.payload).context("invalid payload")?;
+    let cipher = XChaCha20Poly1305::new(&protection.key.derive_key(password, &salt)?.into());
+    let contents = cipher
+        .decrypt(XNonce::from_slice(&nonce), payload.as_slice())
+        .map_err(|_| ProtectionError::WrongPassword)?;
+    String::from_utf8(contents).context("the document isn't text")
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair)
+                .ok()
+                .filter(|pair| pair.len() == 2)?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap enough to keep the tests fast, never written by the app
+    const TEST_KEY: KeyParams = KeyParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+
+    #[test]
+    fn test_seal_and_open() {
+        let document = r#"{ "version": 3, "nodes": [] }"#;
+        let sealed = seal_with(document, "correct horse", 3, TEST_KEY).unwrap();
+        assert!(is_protected(&sealed));
+        assert!(!is_protected(document));
+        assert!(!sealed.contains("nodes"));
+
+        assert_eq!(open(&sealed, Some("correct horse")).unwrap(), document);
+        let error = |password| {
+            open(&sealed, password)
+                .unwrap_err()
+                .downcast::<ProtectionError>()
+                .unwrap()
+        };
+        assert_eq!(error(None), ProtectionError::PasswordRequired);
+        assert_eq!(
+            error(Some("battery staple")),
+            ProtectionError::WrongPassword
+        );
+
+        // Each save gets its own salt and nonce
+        let again = seal_with(document, "correct horse", 3, TEST_KEY).unwrap();
+        assert_ne!(again, sealed);
+    }
+
+    #[test]
+    fn test_damaged_payload() {
+        let sealed = seal_with("{}", "password", 3, TEST_KEY).unwrap();
+        let mut envelope: Envelope = serde_json::from_str(&sealed).unwrap();
+        let mut payload = from_hex(&envelope.payload).unwrap();
+        payload[0] ^= 1;
+        envelope.payload = to_hex(&payload);
+        let damaged = serde_json::to_string(&envelope).unwrap();
+        assert!(open(&damaged, Some("password")).is_err());
+    }
+
+    #[test]
+    fn test_expensive_key_params_are_refused() {
+        let sealed = seal_with("{}", "password", 3, TEST_KEY).unwrap();
+        let with_key = |key| {
+            let mut envelope: Envelope = serde_json::from_str(&sealed).unwrap();
+            envelope.protection.key = key;
+            serde_json::to_string(&envelope).unwrap()
+        };
+
+        for key in [
+            KeyParams {
+                log_n: 21,
+                ..TEST_KEY
+            },
+            KeyParams { r: 9, ..TEST_KEY },
+            KeyParams { p: 5, ..TEST_KEY },
+        ] {
+            let error = open(&with_key(key), Some("password")).unwrap_err();
+            assert!(error.downcast_ref::<ProtectionError>().is_none());
+            assert!(error.to_string().contains("key parameters"));
+        }
+        assert!(KeyParams::default().is_within_limits());
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(to_hex(&[0, 15, 255]), "000fff");
+        assert_eq!(from_hex("000fff"), Some(vec![0, 15, 255]));
+        assert_eq!(from_hex("0ff"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+}
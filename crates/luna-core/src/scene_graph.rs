@@ -181,6 +181,64 @@ impl SceneGraph {
         true
     }
 
+    /// Moves a node under `new_parent` at `index` among its children, keeping where it
+    /// is on the canvas
+    ///
+    /// Local bounds are relative to the parent, so they're recomputed from the node's
+    /// position before the move. `index` counts the new parent's other children and is
+    /// clamped to the end. Returns false, leaving the graph as it was, when either node
+    /// is missing, the node is the root, or the move would put it inside itself.
+    pub fn reparent(
+        &mut self,
+        node_id: SceneNodeId,
+        new_parent: SceneNodeId,
+        index: usize,
+    ) -> bool {
+        if node_id == self.root
+            || !self.nodes.contains_key(node_id)
+            || !self.nodes.contains_key(new_parent)
+            || self.is_ancestor(node_id, new_parent)
+        {
+            return false;
+        }
+
+        let position = self.position_in_root(node_id);
+        let parent_position = self.position_in_root(new_parent);
+
+        if let Some(old_parent_id) = self.nodes.get(node_id).and_then(|node| node.parent) {
+            if let Some(old_parent) = self.nodes.get_mut(old_parent_id) {
+                old_parent.children.retain(|&id| id != node_id);
+            }
+        }
+        if let Some(parent) = self.nodes.get_mut(new_parent) {
+            let index = index.min(parent.children.len());
+            parent.children.insert(index, node_id);
+        }
+        if let Some(node) = self.nodes.get_mut(node_id) {
+            node.parent = Some(new_parent);
+            node.local_bounds.origin = Point::new(
+                position.x - parent_position.x,
+                position.y - parent_position.y,
+            );
+        }
+
+        self.update_world_transform(node_id);
+        true
+    }
+
+    /// Where a node's local bounds start relative to the root, adding up the offsets
+    /// of the nodes it's nested in
+    fn position_in_root(&self, node_id: SceneNodeId) -> Point<f32> {
+        let mut position = Point::new(0.0, 0.0);
+        let mut current = self.nodes.get(node_id);
+        while let Some(node) = current {
+            position.x += node.local_bounds.origin.x;
+            position.y += node.local_bounds.origin.y;
+            current = node.parent.and_then(|parent| self.nodes.get(parent));
+        }
+        position
+    }
+
     /// Removes a node and all its children from the scene graph
     pub fn remove_node(&mut self, node_id: SceneNodeId) -> Option<NodeId> {
         // Can't remove the root node
@@ -601,6 +659,52 @@ mod tests {
         assert_eq!(graph.get_node(node3).unwrap().parent, Some(node2));
     }
 
+    #[test]
+    fn test_reparent_keeps_position() {
+        let mut graph = SceneGraph::new();
+        let bounds = |x, y| Bounds {
+            origin: Point::new(x, y),
+            size: Size::new(50.0, 50.0),
+        };
+
+        // root -> frame at (100, 100) -> card at (10, 20), and a sibling at (0, 0)
+        let frame = graph.create_node(None, None);
+        graph.set_local_bounds(frame, bounds(100.0, 100.0));
+        let card = graph.create_node(Some(frame), None);
+        graph.set_local_bounds(card, bounds(10.0, 20.0));
+        let sibling = graph.create_node(None, None);
+
+        // Out to the root, the card is still at (110, 120)
+        assert!(graph.reparent(card, graph.root(), 0));
+        assert_eq!(graph.get_local_bounds(card), Some(bounds(110.0, 120.0)));
+        assert_eq!(
+            graph.get_node(graph.root()).unwrap().children,
+            vec![card, frame, sibling]
+        );
+
+        // Back into the frame, with an index past the end of its children
+        assert!(graph.reparent(card, frame, 5));
+        assert_eq!(graph.get_local_bounds(card), Some(bounds(10.0, 20.0)));
+        assert_eq!(graph.get_node(frame).unwrap().children, vec![card]);
+        assert_eq!(
+            graph.get_node(graph.root()).unwrap().children,
+            vec![frame, sibling]
+        );
+    }
+
+    #[test]
+    fn test_reparent_refuses_cycles() {
+        let mut graph = SceneGraph::new();
+        let node1 = graph.create_node(None, None);
+        let node2 = graph.create_node(Some(node1), None);
+
+        assert!(!graph.reparent(node1, node2, 0));
+        assert!(!graph.reparent(node1, node1, 0));
+        assert!(!graph.reparent(graph.root(), node1, 0));
+        assert_eq!(graph.get_node(node1).unwrap().parent, Some(graph.root()));
+        assert_eq!(graph.get_node(node2).unwrap().parent, Some(node1));
+    }
+
     #[test]
     fn test_node_mapping() {
         let mut graph = SceneGraph::new();
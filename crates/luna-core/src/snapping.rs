@@ -0,0 +1,169 @@
+//! # Snapping to Layers
+//!
+//! While layers are dragged, their edges and center lines are pulled onto the edges and
+//! center lines of the layers around them once they come close enough. The document's
+//! guides pull on them the same way. Snapping to the grid lives with the grid, in
+//! [`grid`](crate::grid).
+//!
+//! Any layer can be left out of the targets, like a background that spans the whole
+//! page and would otherwise catch every drag across it.
+//!
+//! Anchors and handles dragged while a path is edited snap as single points, to whole
+//! pixels, to guides and to the path's other anchors. Each kind of target can be
+//! turned off for the session with [`PathSnapping`].
+
+use gpui::{Bounds, Point};
+
+use crate::guides::{Guide, GuideAxis};
+
+/// How close an edge has to come to a target to snap to it, in screen pixels
+pub const SNAP_DISTANCE: f32 = 6.0;
+
+/// What the anchors and handles of an edited path snap to, kept for the session only
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathSnapping {
+    /// Round to whole canvas units
+    pub pixels: bool,
+    pub guides: bool,
+    /// Line up with the other anchors of the same path
+    pub anchors: bool,
+}
+
+impl Default for PathSnapping {
+    fn default() -> Self {
+        Self {
+            pixels: true,
+            guides: true,
+            anchors: true,
+        }
+    }
+}
+
+/// Lines dragged edges can snap to, in canvas coordinates
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SnapTargets {
+    /// Canvas x of each vertical line
+    xs: Vec<f32>,
+    /// Canvas y of each horizontal line
+    ys: Vec<f32>,
+}
+
+impl SnapTargets {
+    /// Snap to the edges and the center lines of `bounds`
+    pub fn add_bounds(&mut self, bounds: Bounds<f32>) {
+        let center = bounds.center();
+        self.xs.extend([bounds.left(), center.x, bounds.right()]);
+        self.ys.extend([bounds.top(), center.y, bounds.bottom()]);
+    }
+
+    /// Snap to the line of each guide
+    pub fn add_guides(&mut self, guides: &[Guide]) {
+        for guide in guides {
+            match guide.axis {
+                GuideAxis::Vertical => self.xs.push(guide.position),
+                GuideAxis::Horizontal => self.ys.push(guide.position),
+            }
+        }
+    }
+
+    /// Snap to a vertical and a horizontal line through `point`
+    pub fn add_point(&mut self, point: Point<f32>) {
+        self.xs.push(point.x);
+        self.ys.push(point.y);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty() && self.ys.is_empty()
+    }
+
+    /// How far to move `bounds` so its edge or center line closest to a target lands
+    /// on it, each axis on its own and only for targets within `distance`
+    pub fn snap_offset(&self, bounds: Bounds<f32>, distance: f32) -> Point<f32> {
+        let center = bounds.center();
+        Point::new(
+            nearest_offset(
+                [bounds.left(), center.x, bounds.right()],
+                &self.xs,
+                distance,
+            ),
+            nearest_offset(
+                [bounds.top(), center.y, bounds.bottom()],
+                &self.ys,
+                distance,
+            ),
+        )
+    }
+
+    /// `point` moved onto the nearest target line within `distance`, each axis on its own
+    pub fn snap_point(&self, point: Point<f32>, distance: f32) -> Point<f32> {
+        Point::new(
+            point.x + nearest_offset([point.x], &self.xs, distance),
+            point.y + nearest_offset([point.y], &self.ys, distance),
+        )
+    }
+}
+
+fn nearest_offset<const N: usize>(edges: [f32; N], targets: &[f32], distance: f32) -> f32 {
+    edges
+        .iter()
+        .flat_map(|edge| targets.iter().map(move |target| target - edge))
+        .filter(|offset| offset.abs() <= distance)
+        .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::Size;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Bounds<f32> {
+        Bounds::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    #[test]
+    fn test_snap_offset() {
+        let mut targets = SnapTargets::default();
+        assert!(targets.is_empty());
+        targets.add_bounds(rect(0.0, 0.0, 100.0, 100.0));
+
+        // The left edge is 3 past the target's right edge, the top 2 below its top
+        let dragged = rect(103.0, 2.0, 40.0, 40.0);
+        assert_eq!(targets.snap_offset(dragged, 6.0), Point::new(-3.0, -2.0));
+
+        // Centers line up too, the closest pair wins
+        let dragged = rect(31.0, 200.0, 40.0, 40.0);
+        assert_eq!(targets.snap_offset(dragged, 6.0), Point::new(-1.0, 0.0));
+
+        // Nothing within reach leaves the bounds where they are
+        let dragged = rect(120.0, 120.0, 40.0, 40.0);
+        assert_eq!(targets.snap_offset(dragged, 6.0), Point::default());
+    }
+
+    #[test]
+    fn test_snap_to_guides() {
+        let mut targets = SnapTargets::default();
+        targets.add_guides(&[Guide::vertical(50.0), Guide::horizontal(-20.0)]);
+
+        // The right edge is 2 short of the vertical guide, the center 1 above the other
+        let dragged = rect(8.0, -41.0, 40.0, 40.0);
+        assert_eq!(targets.snap_offset(dragged, 6.0), Point::new(2.0, 1.0));
+    }
+
+    #[test]
+    fn test_snap_point() {
+        let mut targets = SnapTargets::default();
+        targets.add_point(Point::new(10.0, 20.0));
+        targets.add_guides(&[Guide::horizontal(50.0)]);
+
+        assert_eq!(
+            targets.snap_point(Point::new(12.0, 47.5), 3.0),
+            Point::new(10.0, 50.0)
+        );
+        // Each axis snaps on its own
+        assert_eq!(
+            targets.snap_point(Point::new(30.0, 21.0), 3.0),
+            Point::new(30.0, 20.0)
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! # Document Statistics
+//!
+//! A summary of what a document is made of, used by the document info panel to spot
+//! the parts of a large file that have grown unwieldy: how many nodes of each type
+//! there are, which colors carry the design, how deep the hierarchy goes and which
+//! frames hold the most content.
+
+use crate::{
+    color::color_usage,
+    node::{frame::FrameNode, NodeCommon, NodeId, NodeType},
+};
+use gpui::Hsla;
+use std::collections::HashMap;
+
+/// Number of colors listed as most used
+pub const TOP_COLOR_COUNT: usize = 8;
+/// Number of frames listed as heaviest
+pub const HEAVIEST_FRAME_COUNT: usize = 5;
+
+#[derive(Debug, Clone, Default)]
+pub struct DocumentStatistics {
+    pub node_count: usize,
+    /// Node count per type, most common first
+    pub counts_by_type: Vec<(NodeType, usize)>,
+    /// Fill and visible stroke colors with how often they're used, most used first
+    pub top_colors: Vec<(Hsla, usize)>,
+    /// The most deeply nested node and its depth, top-level nodes are at depth 0
+    pub deepest_node: Option<(NodeId, usize)>,
+    /// Frames with the most descendants, and how many they have
+    pub heaviest_frames: Vec<(NodeId, usize)>,
+}
+
+impl DocumentStatistics {
+    pub fn collect(nodes: &[FrameNode]) -> Self {
+        let mut counts_by_type: Vec<(NodeType, usize)> = Vec::new();
+        for node in nodes {
+            let node_type = node.node_type();
+            match counts_by_type.iter_mut().find(|(ty, _)| *ty == node_type) {
+                Some((_, count)) => *count += 1,
+                None => counts_by_type.push((node_type, 1)),
+            }
+        }
+        counts_by_type.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        let mut top_colors = color_usage(nodes.iter().flat_map(|node| {
+            let stroke = node.border_color().filter(|_| node.border_width() > 0.0);
+            [node.fill(), stroke].into_iter().flatten()
+        }));
+        top_colors.truncate(TOP_COLOR_COUNT);
+
+        let children: HashMap<NodeId, &[NodeId]> = nodes
+            .iter()
+            .map(|node| (node.id(), node.children().as_slice()))
+            .collect();
+        let parents: HashMap<NodeId, NodeId> = nodes
+            .iter()
+            .flat_map(|node| node.children().iter().map(|child| (*child, node.id())))
+            .collect();
+
+        let depth = |node_id: NodeId| {
+            std::iter::successors(parents.get(&node_id), |parent| parents.get(parent))
+                .take(nodes.len())
+                .count()
+        };
+        let deepest_node = nodes
+            .iter()
+            .map(|node| (node.id(), depth(node.id())))
+            .filter(|(_, depth)| *depth > 0)
+            // Reversed so the first of equally deep nodes wins
+            .rev()
+            .max_by_key(|(_, depth)| *depth);
+
+        let mut descendant_counts = HashMap::new();
+        let mut heaviest_frames: Vec<(NodeId, usize)> = nodes
+            .iter()
+            .map(|node| {
+                let count = count_descendants(node.id(), &children, &mut descendant_counts);
+                (node.id(), count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        heaviest_frames.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        heaviest_frames.truncate(HEAVIEST_FRAME_COUNT);
+
+        Self {
+            node_count: nodes.len(),
+            counts_by_type,
+            top_colors,
+            deepest_node,
+            heaviest_frames,
+        }
+    }
+}
+
+fn count_descendants(
+    node_id: NodeId,
+    children: &HashMap<NodeId, &[NodeId]>,
+    counts: &mut HashMap<NodeId, usize>,
+) -> usize {
+    if let Some(count) = counts.get(&node_id) {
+        return *count;
+    }
+
+    // Seed the entry so a malformed hierarchy with a cycle terminates
+    counts.insert(node_id, 0);
+    let count = children
+        .get(&node_id)
+        .copied()
+        .unwrap_or_default()
+        .iter()
+        .map(|child| 1 + count_descendants(*child, children, counts))
+        .sum();
+    counts.insert(node_id, count);
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_statistics() {
+        let mut page = FrameNode::new(NodeId::new(1));
+        let mut card = FrameNode::new(NodeId::new(2));
+        let mut title = FrameNode::new(NodeId::new(3));
+        let footer = FrameNode::new(NodeId::new(4));
+        let loose = FrameNode::new(NodeId::new(5));
+
+        page.add_child(card.id());
+        page.add_child(footer.id());
+        card.add_child(title.id());
+        title.set_border(title.border_color(), 0.0);
+
+        let nodes = vec![page, card, title, footer, loose];
+        let statistics = DocumentStatistics::collect(&nodes);
+
+        assert_eq!(statistics.node_count, 5);
+        assert_eq!(statistics.counts_by_type, vec![(NodeType::Frame, 5)]);
+        assert_eq!(statistics.deepest_node, Some((NodeId::new(3), 2)));
+        assert_eq!(
+            statistics.heaviest_frames,
+            vec![(NodeId::new(1), 3), (NodeId::new(2), 1)]
+        );
+
+        // Every node is filled white, and all but the borderless one stroked black
+        assert_eq!(statistics.top_colors.len(), 2);
+        assert_eq!(statistics.top_colors[0].1, 5);
+        assert_eq!(statistics.top_colors[1].1, 4);
+    }
+}
@@ -0,0 +1,106 @@
+//! # Viewport Animation
+//!
+//! Programmatic viewport changes, like revealing a node from quick open, glide to their
+//! destination instead of jumping there, so it stays clear where on the canvas the view
+//! moved to. The canvas samples the running animation once per frame and asks for
+//! another frame until it has settled.
+//!
+//! Scrolling and zooming by hand are never animated, they already follow the input.
+
+use gpui::{point, Point};
+use std::time::{Duration, Instant};
+
+/// How long a viewport transition takes
+pub const VIEWPORT_ANIMATION_DURATION: Duration = Duration::from_millis(200);
+
+/// Cubic ease-out, fast at the start and settling gently at the end
+pub fn ease_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// A transition of the scroll position and zoom from one viewport to another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewportAnimation {
+    from_scroll: Point<f32>,
+    from_zoom: f32,
+    to_scroll: Point<f32>,
+    to_zoom: f32,
+    started_at: Instant,
+    duration: Duration,
+}
+
+impl ViewportAnimation {
+    pub fn new(
+        (from_scroll, from_zoom): (Point<f32>, f32),
+        (to_scroll, to_zoom): (Point<f32>, f32),
+        started_at: Instant,
+    ) -> Self {
+        Self {
+            from_scroll,
+            from_zoom,
+            to_scroll,
+            to_zoom,
+            started_at,
+            duration: VIEWPORT_ANIMATION_DURATION,
+        }
+    }
+
+    pub fn target(&self) -> (Point<f32>, f32) {
+        (self.to_scroll, self.to_zoom)
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started_at) >= self.duration
+    }
+
+    /// Scroll position and zoom at `now`
+    ///
+    /// Zoom is interpolated geometrically, so zooming from 1× to 4× passes 2× halfway
+    /// through and every step looks like the same amount of zoom.
+    pub fn sample(&self, now: Instant) -> (Point<f32>, f32) {
+        let elapsed = now.saturating_duration_since(self.started_at);
+        let t = ease_out(elapsed.as_secs_f32() / self.duration.as_secs_f32());
+
+        let scroll = point(
+            self.from_scroll.x + (self.to_scroll.x - self.from_scroll.x) * t,
+            self.from_scroll.y + (self.to_scroll.y - self.from_scroll.y) * t,
+        );
+        let zoom = self.from_zoom * (self.to_zoom / self.from_zoom).powf(t);
+        (scroll, zoom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ease_out() {
+        assert_eq!(ease_out(0.0), 0.0);
+        assert_eq!(ease_out(1.0), 1.0);
+        assert_eq!(ease_out(2.0), 1.0);
+        // Most of the distance is covered early
+        assert!(ease_out(0.5) > 0.8);
+    }
+
+    #[test]
+    fn test_viewport_animation() {
+        let start = Instant::now();
+        let animation =
+            ViewportAnimation::new((point(0.0, 0.0), 1.0), (point(100.0, -50.0), 4.0), start);
+
+        assert_eq!(animation.sample(start), (point(0.0, 0.0), 1.0));
+        assert!(!animation.is_finished(start));
+
+        let (scroll, zoom) = animation.sample(start + VIEWPORT_ANIMATION_DURATION / 2);
+        assert!(scroll.x > 50.0 && scroll.x < 100.0);
+        assert!(zoom > 2.0 && zoom < 4.0);
+
+        let end = start + VIEWPORT_ANIMATION_DURATION;
+        assert!(animation.is_finished(end));
+        let (scroll, zoom) = animation.sample(end);
+        assert_eq!(scroll, point(100.0, -50.0));
+        assert!((zoom - 4.0).abs() < 1e-5);
+    }
+}
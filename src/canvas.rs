@@ -1,26 +1,93 @@
 #![allow(unused, dead_code)]
 
 use crate::{
-    interactivity::ActiveDrag,
-    node::{frame::FrameNode, NodeCommon, NodeId, NodeLayout, NodeType},
+    animation::ViewportAnimation,
+    clipboard::PASTE_OFFSET,
+    color::{distinct_colors_by_usage, ColorTarget},
+    constraints::{ConstraintError, ConstraintSet, ConstraintTarget, Property},
+    document::{default_page_name, Document, SavedConstraint, SavedNode, SavedPage, ViewportState},
+    document_diff::DocumentDiff,
+    grid::GridSettings,
+    guides::{guide_near, guides_around, with_guides, Guide},
+    history::{CanvasCommand, History, RemovedNode},
+    interactivity::{ActiveDrag, GuideDrag, ResizeOperation},
+    interpolation::{blend_steps, BlendKey},
+    layout::{self, AutoLayout, Axis, Pin},
+    library::{self, LibraryItem, LibraryUpdate, LinkedAs},
+    lint::LintWarning,
+    node::{
+        frame::{clamp_scroll_offset, instantiate, FrameNode, NodeStyle},
+        path::{Anchor, PathHandle, SegmentEntry, VectorPath},
+        rounded_rect_contains, NodeCommon, NodeId, NodeLayout, NodeType,
+    },
+    notifications::Notification,
+    pixel_preview::ExportScale,
+    power::{ActivePower, IDLE_FRAME_INTERVAL},
     scene_graph::{SceneGraph, SceneNodeId},
+    snapping::{SnapTargets, SNAP_DISTANCE},
+    stacking::{restack, Restack},
+    template::Template,
     theme::Theme,
+    ui::toasts::Toasts,
     AppState, Tool,
 };
 use gpui::{
     actions, canvas as gpui_canvas, div, hsla, point, prelude::*, px, size, Action, App, Bounds,
     Context, ContextEntry, DispatchPhase, Element, Entity, EntityInputHandler, FocusHandle,
-    Focusable, InputHandler, InteractiveElement, IntoElement, KeyContext, ParentElement, Pixels,
-    Point, Render, ScaledPixels, Size, Styled, TransformationMatrix, Window,
+    Focusable, Hsla, InputHandler, InteractiveElement, IntoElement, KeyContext, ParentElement,
+    Pixels, Point, Render, ScaledPixels, Size, Styled, TransformationMatrix, Window,
 };
 use std::{
     any::TypeId,
     cell::RefCell,
     collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
     rc::Rc,
+    time::Instant,
 };
 
-actions!(canvas, [ClearSelection]);
+actions!(
+    canvas,
+    [
+        BigNudgeDown,
+        BigNudgeLeft,
+        BigNudgeRight,
+        BigNudgeUp,
+        BringToFront,
+        ClearSelection,
+        Group,
+        LowerOne,
+        NudgeDown,
+        NudgeLeft,
+        NudgeRight,
+        NudgeUp,
+        RaiseOne,
+        Redo,
+        ResetZoom,
+        SelectNextSibling,
+        SelectPreviousSibling,
+        SendToBack,
+        ToggleHidden,
+        ToggleLocked,
+        Undo,
+        Ungroup,
+        ZoomIn,
+        ZoomOut
+    ]
+);
+
+/// Distance an arrow key moves the selection, in canvas units
+const NUDGE_DISTANCE: f32 = 1.0;
+/// Distance shift and an arrow key move the selection
+const BIG_NUDGE_DISTANCE: f32 = 10.0;
+/// Space kept between a revealed selection and the edge of the viewport, in screen pixels
+const REVEAL_MARGIN: f32 = 24.0;
+/// Closest the canvas zooms out and in
+const MIN_ZOOM: f32 = 0.1;
+const MAX_ZOOM: f32 = 10.0;
+/// Zoom levels zooming in and out from the keyboard steps through, the last one being
+/// as far in as the canvas goes
+const ZOOM_PRESETS: [f32; 8] = [MIN_ZOOM, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0, MAX_ZOOM];
 
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Default)]
 pub struct CanvasActionId(usize);
@@ -49,6 +116,99 @@ pub fn register_canvas_action<T: Action>(
     })
 }
 
+/// How nodes are styled when painted, independent of their stored properties
+///
+/// Render modes only change how `CanvasElement` paints, the document itself is
+/// never modified by switching modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderMode {
+    /// Nodes are painted with their own fills, borders and shadows
+    #[default]
+    Default,
+    /// Low-fidelity view for presenting structure without visual design:
+    /// fills are reduced to light grays, every node gets a neutral outline
+    /// and shadows are dropped
+    Wireframe,
+    /// Only a hairline around each node, no fills or shadows, and frames don't clip
+    /// their children, for finding nodes that are covered up or cut off
+    Outline,
+    /// Fills and borders are painted as the pixels an export at this scale would
+    /// fill in, so edges that fall between pixels show up soft
+    PixelPreview(ExportScale),
+}
+
+/// OS accessibility settings preview acts as if they were turned on, so a prototype
+/// can be checked against them without changing the system's
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SimulatedSettings {
+    /// Viewport changes jump rather than glide, like `prefers-reduced-motion`
+    pub reduced_motion: bool,
+    /// Colors are painted with more contrast, like `prefers-contrast: more`
+    pub increased_contrast: bool,
+}
+
+/// Where layers dropped in the layer list end up, relative to an existing node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerDrop {
+    /// Right before the node among its siblings, painted below it
+    Before(NodeId),
+    /// Right after the node among its siblings, painted above it
+    After(NodeId),
+    /// At the end of the node's children
+    Inside(NodeId),
+}
+
+impl LayerDrop {
+    pub fn node_id(self) -> NodeId {
+        match self {
+            LayerDrop::Before(node_id) | LayerDrop::After(node_id) | LayerDrop::Inside(node_id) => {
+                node_id
+            }
+        }
+    }
+}
+
+/// A page of the document, only the one on the canvas has its nodes out on it
+struct Page {
+    name: String,
+    /// Everything on the page while another page is on the canvas
+    stashed: Option<StashedPage>,
+}
+
+impl Page {
+    fn empty(name: String) -> Self {
+        Self {
+            name,
+            stashed: Some(StashedPage::default()),
+        }
+    }
+}
+
+/// A page put away while another is shown, along with how it was left
+#[derive(Default)]
+struct StashedPage {
+    /// Where the page was last viewed from, `None` for a page that's never been shown
+    viewport: Option<ViewportState>,
+    nodes: Vec<FrameNode>,
+    constraints: Vec<SavedConstraint>,
+    guides: Vec<Guide>,
+    /// Changes to the page's own nodes, which can only be undone on the page
+    history: History,
+    selection: HashSet<NodeId>,
+}
+
+/// What a hit test looked at, drawn by the hit test overlay
+#[derive(Debug, Clone, Default)]
+pub struct HitTestRecord {
+    /// The point tested, in canvas coordinates
+    pub point: Point<f32>,
+    /// Each node checked in the order it was checked, with its bounds in canvas
+    /// coordinates and whether the point fell inside its shape
+    pub tested: Vec<(NodeId, Bounds<f32>, bool)>,
+    /// The node the test found
+    pub result: Option<NodeId>,
+}
+
 /// A Canvas manages a collection of nodes that can be rendered and manipulated
 pub struct LunaCanvas {
     app_state: Entity<AppState>,
@@ -68,6 +228,30 @@ pub struct LunaCanvas {
     /// Currently hovered node (for hover effects)
     hovered_node: Option<NodeId>,
 
+    /// Selected nodes an inspector edit would change, while its field is hovered or typed into
+    highlighted_nodes: HashSet<NodeId>,
+
+    /// Nodes soloed from the layer list, everything outside them is hidden while any are
+    ///
+    /// Solo is a way of looking at the document rather than a change to it, so it isn't
+    /// undoable or saved.
+    soloed_nodes: HashSet<NodeId>,
+
+    /// How the document differs from another version of it, marked on the canvas while
+    /// the two are being compared
+    version_diff: Option<DocumentDiff>,
+
+    /// Every node's bounds and the last hit test are drawn over the canvas
+    debugging_hit_tests: bool,
+
+    /// What the last hit test checked, only recorded while hit tests are being debugged
+    ///
+    /// Hit tests only borrow the canvas, so the record is kept in a cell.
+    last_hit_test: RefCell<Option<HitTestRecord>>,
+
+    /// Space is held down, so dragging pans the viewport whatever the active tool
+    space_held: bool,
+
     /// The visible viewport of the canvas in canvas coordinates
     viewport: Bounds<f32>,
 
@@ -77,6 +261,12 @@ pub struct LunaCanvas {
     /// Zoom level of the canvas (1.0 = 100%)
     zoom: f32,
 
+    /// Transition to a new scroll position and zoom in progress, if any
+    viewport_animation: Option<ViewportAnimation>,
+
+    /// Pending redraw of the idle canvas in low power mode
+    idle_refresh: Option<gpui::Task<()>>,
+
     /// The full content bounds of all nodes
     content_bounds: Bounds<f32>,
 
@@ -102,6 +292,64 @@ pub struct LunaCanvas {
     /// Used to highlight frames that can become parents when dropping elements
     potential_parent_frame: Option<NodeId>,
 
+    /// Style resolution used when painting nodes
+    render_mode: RenderMode,
+
+    /// Whether the canvas is being interacted with as a prototype rather than edited
+    previewing: bool,
+
+    /// OS settings simulated while previewing, kept when preview is left
+    simulated_settings: SimulatedSettings,
+
+    /// Properties bound to expressions, re-evaluated whenever the canvas changes
+    constraints: ConstraintSet,
+
+    /// Size of every frame as of the last layout pass, which is how a resize is noticed
+    /// and the frame's children are reflowed
+    laid_out_sizes: HashMap<NodeId, Size<f32>>,
+
+    /// Lines across the canvas for lining nodes up, saved with the document
+    guides: Vec<Guide>,
+
+    /// Changes that can be undone and redone
+    history: History,
+
+    /// Pages of the document in order
+    pages: Vec<Page>,
+
+    /// Index in `pages` of the page on the canvas
+    current_page: usize,
+
+    /// Pages were added, renamed or deleted since the document was opened or saved,
+    /// which no page's history records
+    pages_edited: bool,
+
+    /// Where the document was opened from or last saved to, `None` until it's saved
+    document_path: Option<PathBuf>,
+
+    /// Password the document is encrypted with when it's saved, `None` saves it as is
+    document_password: Option<String>,
+
+    /// Parents and layouts of the selection when a drag started, recorded as one change
+    /// once it ends
+    pending_layout_change: Option<Vec<(NodeId, Option<NodeId>, NodeLayout)>>,
+
+    /// Path the pen tool is adding anchors to, it becomes a change once it's finished
+    pen_path: Option<NodeId>,
+    /// Where the pointer is while the pen draws, the end of the segment it would add
+    pen_pointer: Option<Point<f32>>,
+    /// Length and angle typed for the pen's next segment, see [`SegmentEntry`]
+    pen_entry: String,
+
+    /// Path node whose anchors and handles can be dragged
+    editing_path: Option<NodeId>,
+
+    /// How to undo the path edit being dragged, recorded once the drag ends
+    pending_path_change: Option<CanvasCommand>,
+
+    /// Styles of the selection as a color picker drag started, recorded once it ends
+    pending_color_change: Option<CanvasCommand>,
+
     theme: Theme,
 }
 
@@ -137,6 +385,8 @@ impl LunaCanvas {
             viewport,
             scroll_position: Point::new(0.0, 0.0), // Will be initialized with set_scroll_position below
             zoom: 1.0,
+            viewport_animation: None,
+            idle_refresh: None,
             content_bounds,
             next_id: 1,
             dirty: true,
@@ -146,8 +396,36 @@ impl LunaCanvas {
             active_element_draw: None,
             element_initial_positions: HashMap::new(),
             potential_parent_frame: None,
+            render_mode: RenderMode::default(),
+            previewing: false,
+            simulated_settings: SimulatedSettings::default(),
+            constraints: ConstraintSet::default(),
+            laid_out_sizes: HashMap::new(),
+            guides: Vec::new(),
+            history: History::default(),
+            pages: vec![Page {
+                name: default_page_name(0),
+                stashed: None,
+            }],
+            current_page: 0,
+            pages_edited: false,
+            document_path: None,
+            document_password: None,
+            pending_layout_change: None,
+            pen_path: None,
+            pen_pointer: None,
+            pen_entry: String::new(),
+            editing_path: None,
+            pending_path_change: None,
+            pending_color_change: None,
             theme: theme.clone(),
             hovered_node: None,
+            highlighted_nodes: HashSet::new(),
+            soloed_nodes: HashSet::new(),
+            version_diff: None,
+            debugging_hit_tests: false,
+            last_hit_test: RefCell::default(),
+            space_held: false,
         };
 
         // Initialize proper scroll position for centered coordinate system
@@ -164,7 +442,11 @@ impl LunaCanvas {
         if let Ok(css_content) = std::fs::read_to_string("assets/css/buttons.css") {
             // Use our CSS parser to create rectangle nodes
             let mut factory = crate::node::NodeFactory::default();
-            let frames = crate::css_parser::parse_frames_from_css_file(&css_content, &mut factory);
+            let frames = crate::css_parser::parse_frames_from_css_file(
+                &css_content,
+                &app_state_read.document_settings,
+                &mut factory,
+            );
 
             // Add all rectangles to the canvas
             for (index, mut rect) in frames.into_iter().enumerate() {
@@ -208,7 +490,6 @@ impl LunaCanvas {
     pub fn generate_id(&mut self) -> NodeId {
         let id = NodeId::new(self.next_id);
         self.next_id += 1;
-        println!("Generated new node ID: {}", id); // Debug logging
         id
     }
 
@@ -263,6 +544,58 @@ impl LunaCanvas {
         self.potential_parent_frame = frame_id;
     }
 
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+
+    pub fn set_render_mode(&mut self, render_mode: RenderMode, cx: &mut Context<Self>) {
+        if self.render_mode != render_mode {
+            self.render_mode = render_mode;
+            self.mark_dirty(cx);
+        }
+    }
+
+    pub fn is_previewing(&self) -> bool {
+        self.previewing
+    }
+
+    /// Enter or leave preview. Scroll positions are prototype state, so leaving
+    /// preview scrolls every frame back to the top.
+    pub fn set_previewing(&mut self, previewing: bool, cx: &mut Context<Self>) {
+        if self.previewing == previewing {
+            return;
+        }
+
+        self.previewing = previewing;
+        if !previewing {
+            for node in &mut self.nodes {
+                node.scroll_offset = Point::default();
+            }
+        }
+        self.hovered_node = None;
+        self.mark_dirty(cx);
+    }
+
+    pub fn simulated_settings(&self) -> SimulatedSettings {
+        self.simulated_settings
+    }
+
+    /// Change which OS settings preview simulates
+    pub fn set_simulated_settings(&mut self, settings: SimulatedSettings, cx: &mut Context<Self>) {
+        self.simulated_settings = settings;
+        self.mark_dirty(cx);
+    }
+
+    /// Whether reduced motion is being simulated right now, only the case in preview
+    fn simulates_reduced_motion(&self) -> bool {
+        self.previewing && self.simulated_settings.reduced_motion
+    }
+
+    /// Whether increased contrast is being simulated right now, only the case in preview
+    pub fn simulates_increased_contrast(&self) -> bool {
+        self.previewing && self.simulated_settings.increased_contrast
+    }
+
     pub fn hovered_node(&self) -> Option<NodeId> {
         self.hovered_node
     }
@@ -271,6 +604,26 @@ impl LunaCanvas {
         self.hovered_node = hovered_node;
     }
 
+    pub fn highlighted_nodes(&self) -> &HashSet<NodeId> {
+        &self.highlighted_nodes
+    }
+
+    /// Outline these selected nodes more strongly than the rest of the selection
+    pub fn set_highlighted_nodes(&mut self, nodes: HashSet<NodeId>, cx: &mut Context<Self>) {
+        if self.highlighted_nodes != nodes {
+            self.highlighted_nodes = nodes;
+            cx.notify();
+        }
+    }
+
+    pub fn space_held(&self) -> bool {
+        self.space_held
+    }
+
+    pub fn set_space_held(&mut self, space_held: bool) {
+        self.space_held = space_held;
+    }
+
     pub fn get_node(&self, node_id: NodeId) -> Option<&FrameNode> {
         self.nodes.iter().find(|n| n.id() == node_id)
     }
@@ -279,6 +632,20 @@ impl LunaCanvas {
         self.nodes.iter_mut().find(|n| n.id() == node_id)
     }
 
+    /// Move a window point onto the nearest grid line, when the document snaps to its grid
+    pub fn snap_window_point(&self, window_point: Point<f32>, cx: &App) -> Point<f32> {
+        let grid = self.app_state.read(cx).document_settings.grid;
+        if !grid.snap {
+            return window_point;
+        }
+
+        let canvas_point = self.window_to_canvas_point(window_point);
+        self.canvas_to_window_point(Point::new(
+            grid.snap(canvas_point.x),
+            grid.snap(canvas_point.y),
+        ))
+    }
+
     /// Convert a window-relative point to canvas-relative point
     /// With 0,0 at the center of the canvas
     pub fn window_to_canvas_point(&self, window_point: Point<f32>) -> Point<f32> {
@@ -423,19 +790,23 @@ impl LunaCanvas {
             child_layout.y = child_absolute_y - parent_y;
         }
 
-        // 5. Update scene graph - move child node to be under parent node
+        // 5. Update scene graph - move child node to the end of the parent's children
         let scene_updated = self.scene_graph.update(cx, |sg, _| {
             let parent_scene_id = sg.get_scene_node_id(parent_id);
             let child_scene_id = sg.get_scene_node_id(child_id);
 
             match (parent_scene_id, child_scene_id) {
-                (Some(parent_scene), Some(child_scene)) => sg.add_child(parent_scene, child_scene),
+                (Some(parent_scene), Some(child_scene)) => {
+                    sg.reparent(child_scene, parent_scene, usize::MAX)
+                }
                 _ => false,
             }
         });
 
+        // The layer list and inspector observe the canvas, so they follow the move
         if data_updated || scene_updated {
             self.dirty = true;
+            cx.notify();
         }
 
         data_updated && scene_updated
@@ -472,13 +843,14 @@ impl LunaCanvas {
                 let child_scene_id = sg.get_scene_node_id(child_id);
 
                 match child_scene_id {
-                    Some(child_scene) => sg.add_child(self.canvas_node, child_scene),
+                    Some(child_scene) => sg.reparent(child_scene, self.canvas_node, usize::MAX),
                     _ => false,
                 }
             });
 
             if data_updated || scene_updated {
                 self.dirty = true;
+                cx.notify();
             }
 
             data_updated && scene_updated
@@ -488,6 +860,178 @@ impl LunaCanvas {
         }
     }
 
+    /// Collapse or expand a frame in the layer list
+    pub fn set_layer_collapsed(
+        &mut self,
+        node_id: NodeId,
+        collapsed: bool,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(node) = self.get_node_mut(node_id) {
+            node.collapsed = collapsed;
+            cx.notify();
+        }
+    }
+
+    /// Move nodes next to or into another node, keeping their order and where they are
+    /// on the canvas
+    ///
+    /// Nodes that would end up inside themselves are left alone, as are nodes whose
+    /// ancestor is moved along with them. Returns false when nothing could be moved.
+    pub fn move_layers(
+        &mut self,
+        node_ids: &[NodeId],
+        drop: LayerDrop,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let target = drop.node_id();
+        if self.get_node(target).is_none() {
+            return false;
+        }
+        let new_parent = match drop {
+            LayerDrop::Inside(parent) => Some(parent),
+            LayerDrop::Before(sibling) | LayerDrop::After(sibling) => self.find_parent(sibling),
+        };
+
+        let moving: Vec<NodeId> = self
+            .nodes
+            .iter()
+            .map(|node| node.id())
+            .filter(|node_id| node_ids.contains(node_id) && *node_id != target)
+            .filter(|node_id| {
+                !new_parent.is_some_and(|parent| self.is_ancestor_of(*node_id, parent))
+            })
+            .collect();
+        let moving: Vec<NodeId> = moving
+            .iter()
+            .copied()
+            .filter(|node_id| {
+                !moving
+                    .iter()
+                    .any(|other| other != node_id && self.is_ancestor_of(*other, *node_id))
+            })
+            .collect();
+        if moving.is_empty() {
+            return false;
+        }
+
+        let old_parents: Vec<(NodeId, Option<NodeId>)> = moving
+            .iter()
+            .map(|&node_id| (node_id, self.find_parent(node_id)))
+            .collect();
+        let mut affected_parents: Vec<Option<NodeId>> = vec![new_parent];
+        for (_, parent) in &old_parents {
+            if !affected_parents.contains(parent) {
+                affected_parents.push(*parent);
+            }
+        }
+        let orders: Vec<CanvasCommand> = affected_parents
+            .into_iter()
+            .map(|parent| CanvasCommand::SetSiblingOrder {
+                parent,
+                order: self.sibling_order(parent),
+            })
+            .collect();
+        // Orders only apply to the nodes a parent holds at that point. Restoring them
+        // before and after reparenting means the second set puts the layers back when
+        // undoing, and the inverse of the first puts them in place again when redoing.
+        let mut undo = orders.clone();
+        undo.push(CanvasCommand::SetParents(old_parents));
+        undo.extend(orders);
+        self.history.record(CanvasCommand::Batch(undo));
+
+        for &node_id in &moving {
+            if self.find_parent(node_id) == new_parent {
+                continue;
+            }
+            self.remove_child_from_parent(node_id, cx);
+            if let Some(parent) = new_parent {
+                self.add_child_to_parent(parent, node_id, cx);
+            }
+            let bounds = self.get_node(node_id).map(|node| node.layout().bounds());
+            self.scene_graph.update(cx, |sg, _cx| {
+                if let (Some(scene_node_id), Some(bounds)) = (sg.get_scene_node_id(node_id), bounds)
+                {
+                    sg.set_local_bounds(scene_node_id, bounds);
+                }
+            });
+        }
+
+        let mut order: Vec<NodeId> = self
+            .sibling_order(new_parent)
+            .into_iter()
+            .filter(|node_id| !moving.contains(node_id))
+            .collect();
+        let index = match drop {
+            LayerDrop::Inside(_) => order.len(),
+            LayerDrop::Before(sibling) => order.iter().position(|id| *id == sibling).unwrap_or(0),
+            LayerDrop::After(sibling) => order
+                .iter()
+                .position(|id| *id == sibling)
+                .map_or(order.len(), |index| index + 1),
+        };
+        order.splice(index..index, moving.iter().copied());
+
+        self.set_sibling_order(new_parent, &order, cx);
+        self.mark_dirty(cx);
+        true
+    }
+
+    /// Children of `parent` in order, or the top-level nodes for `None`
+    ///
+    /// Children are ordered by their parent, top-level nodes by the node list.
+    fn sibling_order(&self, parent: Option<NodeId>) -> Vec<NodeId> {
+        match parent {
+            Some(parent) => self
+                .get_node(parent)
+                .map_or_else(Vec::new, |parent| parent.children().clone()),
+            None => self.top_level_nodes(),
+        }
+    }
+
+    /// Put the children of `parent`, or the top-level nodes for `None`, in a new order
+    ///
+    /// `order` has to hold the same nodes as [`Self::sibling_order`].
+    fn set_sibling_order(
+        &mut self,
+        parent: Option<NodeId>,
+        order: &[NodeId],
+        cx: &mut Context<Self>,
+    ) {
+        match parent.and_then(|parent| self.get_node_mut(parent)) {
+            Some(parent) => parent.children = order.to_vec(),
+            None => {
+                let mut top_level = order.iter();
+                let ids: Vec<NodeId> = self.nodes.iter().map(|node| node.id()).collect();
+                let mut reordered = Vec::with_capacity(self.nodes.len());
+                let mut nodes: HashMap<NodeId, FrameNode> =
+                    self.nodes.drain(..).map(|node| (node.id(), node)).collect();
+                for node_id in ids {
+                    // Top-level slots are refilled in the new order, nested nodes stay put
+                    let node_id = if order.contains(&node_id) {
+                        *top_level.next().unwrap()
+                    } else {
+                        node_id
+                    };
+                    reordered.extend(nodes.remove(&node_id));
+                }
+                self.nodes = reordered;
+            }
+        }
+
+        // Moving each sibling into its slot leaves the scene graph in the same order
+        let parent_scene_node = parent
+            .and_then(|parent| self.scene_graph.read(cx).get_scene_node_id(parent))
+            .unwrap_or(self.canvas_node);
+        self.scene_graph.update(cx, |sg, _cx| {
+            for (index, node_id) in order.iter().enumerate() {
+                if let Some(scene_node_id) = sg.get_scene_node_id(*node_id) {
+                    sg.reparent(scene_node_id, parent_scene_node, index);
+                }
+            }
+        });
+    }
+
     /// Find the parent node of a child node
     fn find_parent(&self, child_id: NodeId) -> Option<NodeId> {
         for node in &self.nodes {
@@ -559,410 +1103,3203 @@ impl LunaCanvas {
         (node_x, node_y)
     }
 
-    /// Remove a node from the canvas and update the scene graph
+    /// Absolute position of a node as painted in preview, shifted by the scroll
+    /// offsets of the frames it sits in
+    fn preview_position(&self, node_id: NodeId) -> Point<f32> {
+        let Some(node) = self.get_node(node_id) else {
+            return Point::default();
+        };
+
+        let local = Point::new(node.layout().x, node.layout().y);
+        match self.find_parent(node_id).and_then(|id| self.get_node(id)) {
+            Some(parent) => {
+                let parent_position = self.preview_position(parent.id());
+                Point::new(
+                    parent_position.x + local.x - parent.scroll_offset.x,
+                    parent_position.y + local.y - parent.scroll_offset.y,
+                )
+            }
+            None => local,
+        }
+    }
+
+    /// Scroll the innermost scroll frame under `canvas_point` by `delta`
     ///
-    /// This method removes the specified node and all its children recursively
-    /// from both the data model and the scene graph.
-    pub fn remove_node(
+    /// Returns false when there is no scroll frame there or it is already scrolled as
+    /// far as it goes, so the caller can pan the canvas instead.
+    pub fn scroll_frame_at(
         &mut self,
-        node_id: NodeId,
+        canvas_point: Point<f32>,
+        delta: Point<f32>,
         cx: &mut Context<Self>,
-    ) -> Option<crate::node::frame::FrameNode> {
-        // Remove from selection
-        self.selected_nodes.remove(&node_id);
+    ) -> bool {
+        if !self.previewing {
+            return false;
+        }
 
-        // Get a copy of this node's children first
-        let children = if let Some(node) = self.get_node(node_id) {
-            node.children().clone()
-        } else {
-            Vec::new()
+        let depth = |canvas: &Self, node_id: NodeId| {
+            std::iter::successors(canvas.find_parent(node_id), |id| canvas.find_parent(*id))
+                .count()
+        };
+        let target = self
+            .nodes
+            .iter()
+            .filter(|node| node.is_scrollable())
+            .filter(|node| {
+                Bounds {
+                    origin: self.preview_position(node.id()),
+                    size: node.bounds().size,
+                }
+                .contains(&canvas_point)
+            })
+            .max_by_key(|node| depth(self, node.id()))
+            .map(|node| node.id());
+        let Some(target) = target else {
+            return false;
         };
 
-        // Recursively remove all children first
-        for child_id in children {
-            self.remove_node(child_id, cx);
+        // Content extent in frame-local coordinates, from the far edges of the children
+        let node = self.get_node(target).unwrap();
+        let content_extent = node
+            .children()
+            .iter()
+            .filter_map(|child_id| self.get_node(*child_id))
+            .fold(Point::default(), |extent: Point<f32>, child| {
+                let layout = child.layout();
+                Point::new(
+                    extent.x.max(layout.x + layout.width),
+                    extent.y.max(layout.y + layout.height),
+                )
+            });
+        let offset = clamp_scroll_offset(
+            Point::new(
+                node.scroll_offset.x + delta.x,
+                node.scroll_offset.y + delta.y,
+            ),
+            node.bounds().size,
+            content_extent,
+        );
+        if offset == node.scroll_offset {
+            return false;
         }
 
-        // Remove from scene graph if it exists there
-        let scene_node_id = self
-            .scene_graph
-            .update(cx, |sg, _cx| sg.get_scene_node_id(node_id));
-        if let Some(scene_node_id) = scene_node_id {
-            self.scene_graph.update(cx, |sg, _cx| {
-                sg.remove_node(scene_node_id);
+        if let Some(node) = self.get_node_mut(target) {
+            node.scroll_offset = offset;
+        }
+        self.mark_dirty(cx);
+        true
+    }
+
+    /// The topmost node at `canvas_point`, following the order nodes are painted in
+    ///
+    /// Children are painted over their parent and clipped to it, so a point inside a
+    /// frame hits the topmost child under it, or the frame itself. Nodes in
+    /// `excluding` are skipped along with everything inside them.
+    pub fn node_at(&self, canvas_point: Point<f32>, excluding: &[NodeId]) -> Option<NodeId> {
+        let top_level = self.top_level_nodes();
+        let mut tested = self.debugging_hit_tests.then(Vec::new);
+        let result = self.topmost_at(
+            &top_level,
+            Point::default(),
+            canvas_point,
+            excluding,
+            &mut tested,
+        );
+        if let Some(tested) = tested {
+            *self.last_hit_test.borrow_mut() = Some(HitTestRecord {
+                point: canvas_point,
+                tested,
+                result,
             });
         }
+        result
+    }
 
-        // Find and remove the node from our vector
-        let position = self.nodes.iter().position(|node| node.id() == node_id);
-        let node = position.map(|idx| self.nodes.remove(idx));
+    fn topmost_at(
+        &self,
+        siblings: &[NodeId],
+        parent_origin: Point<f32>,
+        canvas_point: Point<f32>,
+        excluding: &[NodeId],
+        tested: &mut Option<Vec<(NodeId, Bounds<f32>, bool)>>,
+    ) -> Option<NodeId> {
+        for &node_id in siblings.iter().rev() {
+            let Some(node) = self.get_node(node_id) else {
+                continue;
+            };
+            // Hidden and locked frames can't be picked, and neither can anything in them
+            if excluding.contains(&node_id) || node.hidden || node.locked {
+                continue;
+            }
+            // Frames hidden by solo can't be picked either, but the soloed nodes inside
+            // them can
+            let hidden_by_solo = self.is_hidden_by_solo(node_id);
+            if hidden_by_solo && !self.contains_solo(node_id) {
+                continue;
+            }
+            let bounds = Bounds {
+                origin: Point::new(
+                    parent_origin.x + node.layout().x,
+                    parent_origin.y + node.layout().y,
+                ),
+                size: node.bounds().size,
+            };
+            let hit = match &node.path {
+                // Open paths and thin outlines get a few screen pixels of slack
+                Some(path) => {
+                    let tolerance = (node.border_width / 2.0).max(4.0 / self.zoom);
+                    path.fit(bounds).contains(canvas_point, tolerance)
+                }
+                None => rounded_rect_contains(bounds, &node.corner_radii(), canvas_point),
+            };
+            if let Some(tested) = tested {
+                tested.push((node_id, bounds, hit));
+            }
+            if hit {
+                let child = self.topmost_at(
+                    node.children(),
+                    bounds.origin,
+                    canvas_point,
+                    excluding,
+                    tested,
+                );
+                if child.is_some() || !hidden_by_solo {
+                    return child.or(Some(node_id));
+                }
+            }
+        }
+        None
+    }
 
-        // Mark canvas as dirty
-        self.dirty = true;
+    /// Whether a soloed node sits somewhere inside `node_id`
+    fn contains_solo(&self, node_id: NodeId) -> bool {
+        self.soloed_nodes.iter().any(|soloed| {
+            std::iter::successors(self.find_parent(*soloed), |id| self.find_parent(*id))
+                .any(|id| id == node_id)
+        })
+    }
 
-        node
+    /// The link to follow for a click at `canvas_point` in preview
+    ///
+    /// The topmost frame under the point decides, and a frame without a link of its
+    /// own passes the click on to the frames it sits in.
+    pub fn link_at(&self, canvas_point: Point<f32>) -> Option<String> {
+        // Children are clipped to their frames, so a hit only counts where every
+        // ancestor was hit as well
+        let hit = |node_id: NodeId| {
+            std::iter::successors(Some(node_id), |id| self.find_parent(*id)).all(|id| {
+                self.get_node(id).is_some_and(|node| {
+                    Bounds {
+                        origin: self.preview_position(id),
+                        size: node.bounds().size,
+                    }
+                    .contains(&canvas_point)
+                })
+            })
+        };
+        let depth = |node_id: NodeId| {
+            std::iter::successors(self.find_parent(node_id), |id| self.find_parent(*id)).count()
+        };
+        let target = self
+            .nodes
+            .iter()
+            .map(|node| node.id())
+            .filter(|node_id| hit(*node_id))
+            .max_by_key(|node_id| depth(*node_id))?;
+
+        std::iter::successors(Some(target), |id| self.find_parent(*id))
+            .find_map(|id| self.get_node(id)?.link.clone())
     }
 
-    /// Select a node
-    pub fn select_node(&mut self, node_id: NodeId) {
-        if self.nodes.iter().any(|node| node.id() == node_id) {
-            self.selected_nodes.insert(node_id);
-            self.dirty = true;
+    /// Set or clear the link of several nodes as one change
+    pub fn set_links(&mut self, node_ids: &[NodeId], link: Option<String>, cx: &mut Context<Self>) {
+        let links = node_ids
+            .iter()
+            .map(|node_id| (*node_id, link.clone()))
+            .collect();
+        self.execute(CanvasCommand::SetLinks(links), cx);
+    }
+
+    /// Show or hide several nodes as one change, nodes being hidden are deselected
+    pub fn set_hidden(&mut self, node_ids: &[NodeId], hidden: bool, cx: &mut Context<Self>) {
+        if hidden {
+            for node_id in node_ids {
+                self.deselect_node(*node_id);
+            }
         }
+        let changes = node_ids.iter().map(|node_id| (*node_id, hidden)).collect();
+        self.execute(CanvasCommand::SetHidden(changes), cx);
     }
 
-    /// Deselect a node
-    pub fn deselect_node(&mut self, node_id: NodeId) {
-        self.selected_nodes.remove(&node_id);
-        self.dirty = true;
+    /// Lock or unlock several nodes as one change
+    pub fn set_locked(&mut self, node_ids: &[NodeId], locked: bool, cx: &mut Context<Self>) {
+        let changes = node_ids.iter().map(|node_id| (*node_id, locked)).collect();
+        self.execute(CanvasCommand::SetLocked(changes), cx);
     }
 
-    /// Clear all selections
-    pub fn clear_selection(
+    /// Leave several nodes out of what dragged layers snap to as one change, or let
+    /// them be snapped to again
+    pub fn set_snap_excluded(
         &mut self,
-        _: &ClearSelection,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        node_ids: &[NodeId],
+        excluded: bool,
+        cx: &mut Context<Self>,
     ) {
-        self.selected_nodes.clear();
-        self.dirty = true;
+        let changes = node_ids
+            .iter()
+            .map(|node_id| (*node_id, excluded))
+            .collect();
+        self.execute(CanvasCommand::SetSnapExcluded(changes), cx);
     }
 
-    /// Toggle selection state of a node
-    pub fn toggle_node_selection(&mut self, node_id: NodeId) {
-        if self.selected_nodes.contains(&node_id) {
-            self.selected_nodes.remove(&node_id);
-        } else if self.nodes.iter().any(|node| node.id() == node_id) {
-            self.selected_nodes.insert(node_id);
+    /// Pin several nodes along one axis as one change
+    pub fn set_pins(&mut self, node_ids: &[NodeId], axis: Axis, pin: Pin, cx: &mut Context<Self>) {
+        let changes = node_ids
+            .iter()
+            .filter_map(|node_id| {
+                let mut pins = self.get_node(*node_id)?.pins;
+                pins.set(axis, pin);
+                Some((*node_id, pins))
+            })
+            .collect();
+        self.execute(CanvasCommand::SetPins(changes), cx);
+    }
+
+    /// Stack the children of several frames along `direction`, or stop stacking them
+    /// for `None`, as one change
+    ///
+    /// Frames that already stack their children keep their gap and padding.
+    pub fn set_auto_layout(
+        &mut self,
+        node_ids: &[NodeId],
+        direction: Option<Axis>,
+        cx: &mut Context<Self>,
+    ) {
+        let changes = node_ids
+            .iter()
+            .filter_map(|node_id| {
+                let current = self.get_node(*node_id)?.auto_layout;
+                let auto_layout = direction.map(|direction| AutoLayout {
+                    direction,
+                    ..current.unwrap_or_default()
+                });
+                Some((*node_id, auto_layout))
+            })
+            .collect();
+        // Stacking moves the children, undoing it puts them back where they were
+        let children: Vec<NodeId> = node_ids
+            .iter()
+            .filter_map(|node_id| self.get_node(*node_id))
+            .flat_map(|node| node.children.iter().copied())
+            .collect();
+        let command = CanvasCommand::Batch(vec![
+            self.layouts_of(children),
+            CanvasCommand::SetAutoLayout(changes),
+        ]);
+        self.execute(command, cx);
+    }
+
+    /// A path node's path in canvas coordinates, stretched over the node's bounds
+    pub fn absolute_path(&self, node_id: NodeId) -> Option<VectorPath> {
+        let path = self.get_node(node_id)?.path.as_ref()?;
+        Some(path.fit(self.absolute_bounds(node_id)?))
+    }
+
+    /// Give a path node `path`, in canvas coordinates, moving and resizing the node to
+    /// the area it covers
+    fn set_absolute_path(&mut self, node_id: NodeId, path: VectorPath, cx: &mut Context<Self>) {
+        let bounds = path.bounds();
+        let parent_origin = self
+            .find_parent(node_id)
+            .and_then(|parent_id| self.absolute_bounds(parent_id))
+            .map_or(Point::default(), |parent| parent.origin);
+        let Some(node) = self.get_node_mut(node_id) else {
+            return;
+        };
+        node.path = Some(path.translate(Point::new(-bounds.origin.x, -bounds.origin.y)));
+        *node.layout_mut() = NodeLayout::new(
+            bounds.origin.x - parent_origin.x,
+            bounds.origin.y - parent_origin.y,
+            bounds.size.width,
+            bounds.size.height,
+        );
+        self.sync_scene_bounds(vec![node_id], cx);
+        self.mark_dirty(cx);
+    }
+
+    /// The path the pen tool is drawing, if it's drawing one
+    pub fn pen_path(&self) -> Option<NodeId> {
+        self.pen_path
+    }
+
+    /// Start drawing a path at `position` with the pen tool, selecting it
+    pub fn begin_pen_path(
+        &mut self,
+        position: Point<f32>,
+        fill: Option<Hsla>,
+        border_color: Option<Hsla>,
+        cx: &mut Context<Self>,
+    ) -> NodeId {
+        let node_id = self.generate_id();
+        let mut node = FrameNode::with_rect(node_id, position.x, position.y, 0.0, 0.0);
+        node.set_fill(fill);
+        node.set_border(border_color, 1.0);
+        node.path = Some(VectorPath {
+            anchors: vec![Anchor::corner(Point::default())],
+            closed: false,
+        });
+        self.add_node(node, None, cx);
+        self.deselect_all_nodes(cx);
+        self.select_node(node_id);
+        self.pen_path = Some(node_id);
+        self.pen_pointer = Some(position);
+        self.pen_entry.clear();
+        self.mark_dirty(cx);
+        node_id
+    }
+
+    /// Add a corner at `position` to the path being drawn, or close the path when it's
+    /// within `radius` of the first anchor, returning whether the path was closed
+    pub fn add_pen_anchor(
+        &mut self,
+        position: Point<f32>,
+        radius: f32,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(node_id) = self.pen_path else {
+            return false;
+        };
+        let Some(mut path) = self.absolute_path(node_id) else {
+            // The path was deleted while it was being drawn
+            self.pen_path = None;
+            return false;
+        };
+        let closes = path.anchors.len() > 1
+            && path.handle_at(position, radius) == Some(PathHandle::Anchor(0));
+        if closes {
+            path.closed = true;
+        } else {
+            path.anchors.push(Anchor::corner(position));
+        }
+        // A click places the anchor over whatever was being typed
+        self.pen_entry.clear();
+        self.set_absolute_path(node_id, path, cx);
+        closes
+    }
+
+    /// Follow the pointer with the segment the pen would add next
+    pub fn set_pen_pointer(&mut self, position: Point<f32>, cx: &mut Context<Self>) {
+        if self.pen_path.is_some() && self.pen_pointer != Some(position) {
+            self.pen_pointer = Some(position);
+            self.mark_dirty(cx);
+        }
+    }
+
+    /// The segment the pen would add next, from the newest anchor to the typed length
+    /// and angle when they read as a segment, or else to the pointer
+    pub fn pen_segment(&self, cx: &App) -> Option<(Point<f32>, Point<f32>)> {
+        let path = self.absolute_path(self.pen_path?)?;
+        let from = path.anchors.last()?.position;
+        let pointer = self.pen_pointer?;
+        let settings = self.app_state.read(cx).document_settings;
+        let to = match SegmentEntry::parse(&self.pen_entry, &settings) {
+            Some(entry) => entry.end(from, pointer),
+            None => pointer,
+        };
+        Some((from, to))
+    }
+
+    /// What's been typed for the pen's next segment, empty when nothing has
+    pub fn pen_entry(&self) -> &str {
+        &self.pen_entry
+    }
+
+    /// Type into the length and angle of the pen's next segment
+    pub fn type_pen_entry(&mut self, text: &str, cx: &mut Context<Self>) {
+        if self.pen_path.is_some() {
+            self.pen_entry.push_str(text);
+            self.mark_dirty(cx);
+        }
+    }
+
+    /// Take back the last character typed for the pen's next segment, returning
+    /// whether there was one
+    pub fn erase_pen_entry(&mut self, cx: &mut Context<Self>) -> bool {
+        let erased = self.pen_entry.pop().is_some();
+        if erased {
+            self.mark_dirty(cx);
+        }
+        erased
+    }
+
+    /// Forget what was typed for the pen's next segment, returning whether anything was
+    pub fn clear_pen_entry(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.pen_entry.is_empty() {
+            return false;
+        }
+        self.pen_entry.clear();
+        self.mark_dirty(cx);
+        true
+    }
+
+    /// Add the typed segment to the path being drawn, returning whether it read as one
+    ///
+    /// The entry is kept when it doesn't, so it can be corrected.
+    pub fn commit_pen_entry(&mut self, cx: &mut Context<Self>) -> bool {
+        let settings = self.app_state.read(cx).document_settings;
+        if SegmentEntry::parse(&self.pen_entry, &settings).is_none() {
+            return false;
+        }
+        let Some((node_id, (_, to))) = self.pen_path.zip(self.pen_segment(cx)) else {
+            return false;
+        };
+        let Some(mut path) = self.absolute_path(node_id) else {
+            return false;
+        };
+        path.anchors.push(Anchor::corner(to));
+        self.set_absolute_path(node_id, path, cx);
+        self.pen_entry.clear();
+        true
+    }
+
+    /// Pull the handles of the newest anchor out to `position`, smoothing the curve
+    /// through it
+    pub fn drag_pen_handle(&mut self, position: Point<f32>, cx: &mut Context<Self>) {
+        let Some(node_id) = self.pen_path else {
+            return;
+        };
+        let Some(mut path) = self.absolute_path(node_id) else {
+            return;
+        };
+        if let Some(anchor) = path.anchors.last_mut() {
+            *anchor = Anchor::smooth(anchor.position, position);
+        }
+        self.set_absolute_path(node_id, path, cx);
+    }
+
+    /// Stop drawing with the pen, recording the path as one change
+    ///
+    /// A path that never got a second anchor has nothing to show and is thrown away.
+    pub fn finish_pen_path(&mut self, cx: &mut Context<Self>) {
+        let Some(node_id) = self.pen_path.take() else {
+            return;
+        };
+        self.pen_pointer = None;
+        self.pen_entry.clear();
+        let anchor_count = self
+            .get_node(node_id)
+            .and_then(|node| node.path.as_ref())
+            .map_or(0, |path| path.anchors.len());
+        if anchor_count < 2 {
+            self.remove_node(node_id, cx);
+        } else {
+            self.record_change(CanvasCommand::RemoveNodes(vec![node_id]));
+        }
+        self.mark_dirty(cx);
+    }
+
+    /// The path node whose anchors and handles are shown for dragging
+    pub fn editing_path(&self) -> Option<NodeId> {
+        self.editing_path
+    }
+
+    /// Show the anchors and handles of a path node for dragging
+    pub fn edit_path(&mut self, node_id: NodeId, cx: &mut Context<Self>) {
+        if self
+            .get_node(node_id)
+            .is_some_and(|node| node.path.is_some())
+        {
+            self.editing_path = Some(node_id);
+            self.mark_dirty(cx);
+        }
+    }
+
+    pub fn stop_editing_path(&mut self, cx: &mut Context<Self>) {
+        if self.editing_path.take().is_some() {
+            self.mark_dirty(cx);
+        }
+    }
+
+    /// The part of the edited path within `radius` of `position`
+    pub fn path_handle_at(&self, position: Point<f32>, radius: f32) -> Option<PathHandle> {
+        self.absolute_path(self.editing_path?)?
+            .handle_at(position, radius)
+    }
+
+    /// Remember the edited path as a drag of one of its parts starts
+    pub fn begin_path_edit(&mut self) {
+        let Some(node_id) = self.editing_path else {
+            return;
+        };
+        let Some(path) = self.get_node(node_id).map(|node| node.path.clone()) else {
+            return;
+        };
+        // Moving an anchor can move and resize the node along with the path
+        self.pending_path_change = Some(CanvasCommand::Batch(vec![
+            self.layouts_of([node_id]),
+            CanvasCommand::SetPaths(vec![(node_id, path)]),
+        ]));
+    }
+
+    /// Move part of the edited path to `position`, in canvas coordinates
+    pub fn move_path_handle(
+        &mut self,
+        handle: PathHandle,
+        position: Point<f32>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(node_id) = self.editing_path else {
+            return;
+        };
+        let Some(mut path) = self.absolute_path(node_id) else {
+            return;
+        };
+        path.move_handle(handle, position);
+        self.set_absolute_path(node_id, path, cx);
+    }
+
+    /// Where a dragged part of the edited path lands for the pointer at `window_point`,
+    /// in canvas coordinates
+    ///
+    /// Grid snapping goes first. Guides and the path's other anchors nearby then take
+    /// the point, each axis on its own, and an axis neither took is rounded to a whole
+    /// pixel. The app state's [`PathSnapping`](crate::snapping::PathSnapping) says which
+    /// of these apply.
+    pub fn snap_path_point(
+        &self,
+        window_point: Point<f32>,
+        handle: PathHandle,
+        cx: &App,
+    ) -> Point<f32> {
+        let position = self.window_to_canvas_point(self.snap_window_point(window_point, cx));
+        let snapping = self.app_state.read(cx).path_snapping;
+
+        let mut targets = SnapTargets::default();
+        if snapping.guides {
+            targets.add_guides(&self.guides);
+        }
+        if snapping.anchors {
+            let anchors = self
+                .editing_path
+                .and_then(|node_id| self.absolute_path(node_id))
+                .map(|path| path.anchors)
+                .unwrap_or_default();
+            for (index, anchor) in anchors.iter().enumerate() {
+                // A dragged anchor can't snap to itself, its handles can snap onto it
+                if handle != PathHandle::Anchor(index) {
+                    targets.add_point(anchor.position);
+                }
+            }
+        }
+
+        let snapped = targets.snap_point(position, SNAP_DISTANCE / self.zoom);
+        let round = |value: f32, snapped: f32| {
+            if snapped == value && snapping.pixels {
+                value.round()
+            } else {
+                snapped
+            }
+        };
+        Point::new(round(position.x, snapped.x), round(position.y, snapped.y))
+    }
+
+    /// Record the drag since [`Self::begin_path_edit`] as a single change
+    pub fn finish_path_edit(&mut self) {
+        if let Some(undo) = self.pending_path_change.take() {
+            self.history.record(undo);
+        }
+    }
+
+    /// Whether a node is hidden, either itself, by a frame it sits in, or by solo
+    pub fn is_layer_hidden(&self, node_id: NodeId) -> bool {
+        self.is_hidden_by_solo(node_id)
+            || std::iter::successors(Some(node_id), |id| self.find_parent(*id))
+                .any(|id| self.get_node(id).is_some_and(|node| node.hidden))
+    }
+
+    pub fn is_soloed(&self, node_id: NodeId) -> bool {
+        self.soloed_nodes.contains(&node_id)
+    }
+
+    /// Whether solo hides a node, because other nodes are soloed and it isn't inside one
+    ///
+    /// The frames a soloed node sits in are hidden too, leaving it on its own.
+    pub fn is_hidden_by_solo(&self, node_id: NodeId) -> bool {
+        // Soloed nodes that were since deleted don't count, or they'd hide everything
+        let solo_active = self
+            .soloed_nodes
+            .iter()
+            .any(|soloed| self.get_node(*soloed).is_some());
+        solo_active
+            && !std::iter::successors(Some(node_id), |id| self.find_parent(*id))
+                .any(|id| self.soloed_nodes.contains(&id))
+    }
+
+    /// Solo a node, or take it out of solo, any number of nodes can be soloed together
+    ///
+    /// Selected nodes that solo hides are deselected, as hidden nodes are.
+    pub fn toggle_solo(&mut self, node_id: NodeId, cx: &mut Context<Self>) {
+        if !self.soloed_nodes.remove(&node_id) {
+            self.soloed_nodes.insert(node_id);
+        }
+        let hidden: Vec<NodeId> = self
+            .selected_nodes
+            .iter()
+            .copied()
+            .filter(|id| self.is_hidden_by_solo(*id))
+            .collect();
+        for id in hidden {
+            self.deselect_node(id);
+        }
+        self.mark_dirty(cx);
+    }
+
+    pub fn version_diff(&self) -> Option<&DocumentDiff> {
+        self.version_diff.as_ref()
+    }
+
+    /// Mark the differences from another version of the document, `None` clears them
+    pub fn set_version_diff(&mut self, diff: Option<DocumentDiff>, cx: &mut Context<Self>) {
+        self.version_diff = diff;
+        self.mark_dirty(cx);
+    }
+
+    pub fn is_debugging_hit_tests(&self) -> bool {
+        self.debugging_hit_tests
+    }
+
+    /// Draw every node's bounds and what the last hit test checked over the canvas
+    pub fn set_debugging_hit_tests(&mut self, debugging: bool, cx: &mut Context<Self>) {
+        self.debugging_hit_tests = debugging;
+        self.last_hit_test.replace(None);
+        self.mark_dirty(cx);
+    }
+
+    /// What the last hit test checked, if it ran while hit tests were being debugged
+    pub fn last_hit_test(&self) -> Option<HitTestRecord> {
+        self.last_hit_test.borrow().clone()
+    }
+
+    /// Show everything again after soloing
+    pub fn clear_solo(&mut self, cx: &mut Context<Self>) {
+        if !self.soloed_nodes.is_empty() {
+            self.soloed_nodes.clear();
+            self.mark_dirty(cx);
+        }
+    }
+
+    /// Whether a node is locked, either itself or by a frame it sits in
+    pub fn is_layer_locked(&self, node_id: NodeId) -> bool {
+        std::iter::successors(Some(node_id), |id| self.find_parent(*id))
+            .any(|id| self.get_node(id).is_some_and(|node| node.locked))
+    }
+
+    /// Whether a node can be selected on the canvas, hidden and locked layers can only
+    /// be picked from the layer list
+    pub fn is_layer_selectable(&self, node_id: NodeId) -> bool {
+        !self.is_layer_hidden(node_id) && !self.is_layer_locked(node_id)
+    }
+
+    /// Remove a node from the canvas and update the scene graph
+    ///
+    /// This method removes the specified node and all its children recursively
+    /// from both the data model and the scene graph.
+    pub fn remove_node(
+        &mut self,
+        node_id: NodeId,
+        cx: &mut Context<Self>,
+    ) -> Option<crate::node::frame::FrameNode> {
+        // Remove from selection
+        self.selected_nodes.remove(&node_id);
+        self.constraints.remove_node(node_id);
+
+        // Get a copy of this node's children first
+        let children = if let Some(node) = self.get_node(node_id) {
+            node.children().clone()
+        } else {
+            Vec::new()
+        };
+
+        // Recursively remove all children first
+        for child_id in children {
+            self.remove_node(child_id, cx);
+        }
+
+        // Remove from scene graph if it exists there
+        let scene_node_id = self
+            .scene_graph
+            .update(cx, |sg, _cx| sg.get_scene_node_id(node_id));
+        if let Some(scene_node_id) = scene_node_id {
+            self.scene_graph.update(cx, |sg, _cx| {
+                sg.remove_node(scene_node_id);
+            });
+        }
+
+        // Find and remove the node from our vector
+        let position = self.nodes.iter().position(|node| node.id() == node_id);
+        let node = position.map(|idx| self.nodes.remove(idx));
+
+        // Mark canvas as dirty
+        self.dirty = true;
+
+        node
+    }
+
+    /// Select a node
+    pub fn select_node(&mut self, node_id: NodeId) {
+        if self.nodes.iter().any(|node| node.id() == node_id) {
+            self.selected_nodes.insert(node_id);
+            self.dirty = true;
+        }
+    }
+
+    /// Deselect a node
+    pub fn deselect_node(&mut self, node_id: NodeId) {
+        self.selected_nodes.remove(&node_id);
+        self.dirty = true;
+    }
+
+    /// Clear all selections
+    pub fn clear_selection(
+        &mut self,
+        _: &ClearSelection,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+        self.selected_nodes.clear();
+        self.dirty = true;
+    }
+
+    /// Select the sibling after the selected node, wrapping around to the first
+    pub fn select_next_sibling(
+        &mut self,
+        _: &SelectNextSibling,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.cycle_selection(true, cx);
+    }
+
+    /// Select the sibling before the selected node, wrapping around to the last
+    pub fn select_previous_sibling(
+        &mut self,
+        _: &SelectPreviousSibling,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.cycle_selection(false, cx);
+    }
+
+    /// Step the selection through the siblings of the first selected node
+    ///
+    /// With nothing selected this starts at the first (or last) top-level node. Hidden
+    /// and locked siblings are stepped over.
+    fn cycle_selection(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let current = self.top_level_selection().first().copied();
+        let mut siblings = match current.and_then(|node_id| self.find_parent(node_id)) {
+            Some(parent_id) => self
+                .get_node(parent_id)
+                .map(|parent| parent.children().clone())
+                .unwrap_or_default(),
+            None => self.top_level_nodes(),
+        };
+        siblings.retain(|node_id| Some(*node_id) == current || self.is_layer_selectable(*node_id));
+        if siblings.is_empty() {
+            return;
+        }
+
+        let index = current.and_then(|node_id| siblings.iter().position(|&id| id == node_id));
+        let next = match (index, forward) {
+            (Some(index), true) => (index + 1) % siblings.len(),
+            (Some(index), false) => (index + siblings.len() - 1) % siblings.len(),
+            (None, true) => 0,
+            (None, false) => siblings.len() - 1,
+        };
+
+        self.selected_nodes.clear();
+        self.selected_nodes.insert(siblings[next]);
+        self.reveal_selection(cx);
+        self.mark_dirty(cx);
+    }
+
+    pub fn nudge_up(&mut self, _: &NudgeUp, _window: &mut Window, cx: &mut Context<Self>) {
+        self.nudge_selection(point(0.0, -NUDGE_DISTANCE), cx);
+    }
+
+    pub fn nudge_down(&mut self, _: &NudgeDown, _window: &mut Window, cx: &mut Context<Self>) {
+        self.nudge_selection(point(0.0, NUDGE_DISTANCE), cx);
+    }
+
+    pub fn nudge_left(&mut self, _: &NudgeLeft, _window: &mut Window, cx: &mut Context<Self>) {
+        self.nudge_selection(point(-NUDGE_DISTANCE, 0.0), cx);
+    }
+
+    pub fn nudge_right(&mut self, _: &NudgeRight, _window: &mut Window, cx: &mut Context<Self>) {
+        self.nudge_selection(point(NUDGE_DISTANCE, 0.0), cx);
+    }
+
+    pub fn big_nudge_up(&mut self, _: &BigNudgeUp, _window: &mut Window, cx: &mut Context<Self>) {
+        self.nudge_selection(point(0.0, -BIG_NUDGE_DISTANCE), cx);
+    }
+
+    pub fn big_nudge_down(
+        &mut self,
+        _: &BigNudgeDown,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.nudge_selection(point(0.0, BIG_NUDGE_DISTANCE), cx);
+    }
+
+    pub fn big_nudge_left(
+        &mut self,
+        _: &BigNudgeLeft,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.nudge_selection(point(-BIG_NUDGE_DISTANCE, 0.0), cx);
+    }
+
+    pub fn big_nudge_right(
+        &mut self,
+        _: &BigNudgeRight,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.nudge_selection(point(BIG_NUDGE_DISTANCE, 0.0), cx);
+    }
+
+    /// Move the selection by `delta` from the keyboard
+    ///
+    /// Only nodes without a selected ancestor are moved, children follow their parent.
+    fn nudge_selection(&mut self, delta: Point<f32>, cx: &mut Context<Self>) {
+        let mut selection = self.top_level_selection();
+        selection.retain(|node_id| !self.is_layer_locked(*node_id));
+        if selection.is_empty() {
+            return;
+        }
+        let undo = self.layouts_of(selection.iter().copied());
+        self.history.record(undo);
+
+        for node_id in selection {
+            let Some(node) = self.get_node_mut(node_id) else {
+                continue;
+            };
+            let layout = node.layout_mut();
+            layout.x += delta.x;
+            layout.y += delta.y;
+
+            let bounds = node.layout().bounds();
+            self.scene_graph.update(cx, |sg, _cx| {
+                if let Some(scene_node_id) = sg.get_scene_node_id(node_id) {
+                    sg.set_local_bounds(scene_node_id, bounds);
+                }
+            });
+        }
+
+        self.reveal_selection(cx);
+        self.mark_dirty(cx);
+    }
+
+    pub fn bring_to_front(
+        &mut self,
+        _: &BringToFront,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.restack_selection(Restack::BringToFront, cx);
+    }
+
+    pub fn send_to_back(&mut self, _: &SendToBack, _window: &mut Window, cx: &mut Context<Self>) {
+        self.restack_selection(Restack::SendToBack, cx);
+    }
+
+    pub fn raise_one(&mut self, _: &RaiseOne, _window: &mut Window, cx: &mut Context<Self>) {
+        self.restack_selection(Restack::RaiseOne, cx);
+    }
+
+    pub fn lower_one(&mut self, _: &LowerOne, _window: &mut Window, cx: &mut Context<Self>) {
+        self.restack_selection(Restack::LowerOne, cx);
+    }
+
+    pub fn group(&mut self, _: &Group, _window: &mut Window, cx: &mut Context<Self>) {
+        self.group_selection(cx);
+    }
+
+    pub fn ungroup(&mut self, _: &Ungroup, _window: &mut Window, cx: &mut Context<Self>) {
+        self.ungroup_selection(cx);
+    }
+
+    pub fn toggle_hidden(
+        &mut self,
+        _: &ToggleHidden,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selection: Vec<NodeId> = self.selected_nodes.iter().copied().collect();
+        let hidden = selection
+            .iter()
+            .all(|node_id| self.get_node(*node_id).is_some_and(|node| node.hidden));
+        self.set_hidden(&selection, !hidden, cx);
+    }
+
+    pub fn toggle_locked(
+        &mut self,
+        _: &ToggleLocked,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selection: Vec<NodeId> = self.selected_nodes.iter().copied().collect();
+        let locked = selection
+            .iter()
+            .all(|node_id| self.get_node(*node_id).is_some_and(|node| node.locked));
+        self.set_locked(&selection, !locked, cx);
+    }
+
+    /// Whether [`Self::group_selection`] has anything to group
+    pub fn can_group_selection(&self) -> bool {
+        let roots = self.top_level_selection();
+        roots.first().is_some_and(|first| {
+            let parent = self.find_parent(*first);
+            roots
+                .iter()
+                .all(|node_id| self.find_parent(*node_id) == parent)
+        })
+    }
+
+    /// Whether [`Self::ungroup_selection`] has anything to ungroup
+    pub fn can_ungroup_selection(&self) -> bool {
+        !self.selected_groups().is_empty()
+    }
+
+    /// Selected frames holding other nodes, leaving out those inside another one
+    fn selected_groups(&self) -> Vec<NodeId> {
+        self.top_level_selection()
+            .into_iter()
+            .filter(|node_id| {
+                self.get_node(*node_id)
+                    .is_some_and(|node| !node.children().is_empty())
+            })
+            .collect()
+    }
+
+    /// Wrap the selection in a new frame fitted around it and select the frame, as one
+    /// change
+    ///
+    /// The frame takes the place of the topmost selected node and holds the selection
+    /// in its stacking order. Nodes in different frames aren't grouped together.
+    pub fn group_selection(&mut self, cx: &mut Context<Self>) -> Option<NodeId> {
+        if !self.can_group_selection() {
+            return None;
+        }
+        let roots = self.top_level_selection();
+        let parent = self.find_parent(roots[0]);
+        let bounds = self.selection_bounds()?;
+        let siblings = self.sibling_order(parent);
+        let members: Vec<NodeId> = siblings
+            .iter()
+            .copied()
+            .filter(|node_id| roots.contains(node_id))
+            .collect();
+        let topmost = *members.last()?;
+
+        let parent_origin = parent
+            .and_then(|parent| self.absolute_bounds(parent))
+            .map_or_else(Point::default, |bounds| bounds.origin);
+        let group_id = self.generate_id();
+        let mut group = FrameNode::with_rect(
+            group_id,
+            bounds.origin.x - parent_origin.x,
+            bounds.origin.y - parent_origin.y,
+            bounds.size.width,
+            bounds.size.height,
+        );
+        group.name = Some("Group".to_string());
+        group.set_fill(None);
+        group.set_border(None, 0.0);
+        let group_origin = point(group.layout.x, group.layout.y);
+
+        let index = self.nodes.iter().position(|node| node.id() == topmost)? + 1;
+        let child_index = siblings.iter().position(|node_id| *node_id == topmost)? + 1;
+        let layouts = members
+            .iter()
+            .filter_map(|&node_id| {
+                let mut layout = self.get_node(node_id)?.layout().clone();
+                layout.x -= group_origin.x;
+                layout.y -= group_origin.y;
+                Some((node_id, layout))
+            })
+            .collect();
+        let old_layouts = self.layouts_of(members.iter().copied());
+
+        // Reparenting works out positions from the layouts nodes have at the time, so
+        // the layouts are set outright on both sides to land exactly where they were
+        self.execute(
+            CanvasCommand::Batch(vec![
+                CanvasCommand::SetSiblingOrder {
+                    parent,
+                    order: siblings,
+                },
+                old_layouts,
+                CanvasCommand::InsertNodes {
+                    root: group_id,
+                    nodes: vec![RemovedNode { node: group, index }],
+                    parent: parent.map(|parent| (parent, child_index)),
+                },
+                CanvasCommand::SetParents(
+                    members
+                        .iter()
+                        .map(|node_id| (*node_id, Some(group_id)))
+                        .collect(),
+                ),
+                CanvasCommand::SetLayouts(layouts),
+            ]),
+            cx,
+        );
+        self.selected_nodes = HashSet::from([group_id]);
+        Some(group_id)
+    }
+
+    /// Move the children of every selected frame out in its place and remove the
+    /// frame, as one change
+    ///
+    /// The children are selected afterwards, keeping where they are on the canvas and
+    /// their stacking order.
+    pub fn ungroup_selection(&mut self, cx: &mut Context<Self>) {
+        let groups = self.selected_groups();
+        if groups.is_empty() {
+            return;
+        }
+
+        let mut undo = Vec::new();
+        let mut selection = HashSet::new();
+        for group_id in groups {
+            let Some(group) = self.get_node(group_id) else {
+                continue;
+            };
+            let children = group.children().clone();
+            let group_origin = point(group.layout().x, group.layout().y);
+            let parent = self.find_parent(group_id);
+            let siblings = self.sibling_order(parent);
+            let order: Vec<NodeId> = siblings
+                .iter()
+                .flat_map(|&node_id| {
+                    if node_id == group_id {
+                        children.clone()
+                    } else {
+                        vec![node_id]
+                    }
+                })
+                .collect();
+            let layouts = children
+                .iter()
+                .filter_map(|&node_id| {
+                    let mut layout = self.get_node(node_id)?.layout().clone();
+                    layout.x += group_origin.x;
+                    layout.y += group_origin.y;
+                    Some((node_id, layout))
+                })
+                .collect();
+
+            let command = CanvasCommand::Batch(vec![
+                CanvasCommand::SetSiblingOrder {
+                    parent,
+                    order: siblings,
+                },
+                self.layouts_of(children.iter().copied()),
+                CanvasCommand::SetParents(
+                    children.iter().map(|node_id| (*node_id, parent)).collect(),
+                ),
+                CanvasCommand::SetLayouts(layouts),
+                CanvasCommand::RemoveNodes(vec![group_id]),
+                CanvasCommand::SetSiblingOrder { parent, order },
+            ]);
+            undo.push(self.apply_command(command, cx));
+            selection.extend(children);
+        }
+
+        undo.reverse();
+        self.history.record(CanvasCommand::Batch(undo));
+        self.selected_nodes = selection;
+        self.mark_dirty(cx);
+    }
+
+    /// Move the selection within the stacking order of its siblings, as one change
+    ///
+    /// Selected nodes under different parents are restacked among their own siblings.
+    fn restack_selection(&mut self, restack_kind: Restack, cx: &mut Context<Self>) {
+        let selection = self.top_level_selection();
+        let mut parents: Vec<Option<NodeId>> = Vec::new();
+        for node_id in &selection {
+            let parent = self.find_parent(*node_id);
+            if !parents.contains(&parent) {
+                parents.push(parent);
+            }
+        }
+
+        let orders: Vec<CanvasCommand> = parents
+            .into_iter()
+            .filter_map(|parent| {
+                let current = self.sibling_order(parent);
+                let order = restack(&current, &selection, restack_kind);
+                (order != current).then_some(CanvasCommand::SetSiblingOrder { parent, order })
+            })
+            .collect();
+        if !orders.is_empty() {
+            self.execute(CanvasCommand::Batch(orders), cx);
+        }
+    }
+
+    /// Scroll just far enough to bring the selection back into view
+    ///
+    /// Does nothing when the selection is already visible or following the selection
+    /// is turned off.
+    pub fn reveal_selection(&mut self, cx: &mut Context<Self>) {
+        if !self.app_state.read(cx).follow_selection {
+            return;
+        }
+
+        let Some(target) = self
+            .selected_nodes
+            .clone()
+            .into_iter()
+            .filter_map(|node_id| {
+                let size = self.get_node(node_id)?.layout().bounds().size;
+                let (x, y) = self.get_absolute_position(node_id, cx);
+                Some(Bounds::new(point(x, y), size))
+            })
+            .reduce(|target, bounds| target.union(&bounds))
+        else {
+            return;
+        };
+
+        // Continue from where a running animation is headed, so quick repeated nudges
+        // don't each undo part of the previous scroll
+        let (scroll, zoom) = self
+            .viewport_animation
+            .map(|animation| animation.target())
+            .unwrap_or((self.scroll_position, self.zoom));
+        let visible_size = size(
+            self.viewport.size.width / zoom,
+            self.viewport.size.height / zoom,
+        );
+        let revealed = reveal_scroll(scroll, visible_size, target, REVEAL_MARGIN / zoom);
+        if revealed != scroll {
+            self.animate_viewport_to(revealed, zoom, cx);
+        }
+    }
+
+    /// Toggle selection state of a node
+    pub fn toggle_node_selection(&mut self, node_id: NodeId) {
+        if self.selected_nodes.contains(&node_id) {
+            self.selected_nodes.remove(&node_id);
+        } else if self.nodes.iter().any(|node| node.id() == node_id) {
+            self.selected_nodes.insert(node_id);
+        }
+        self.dirty = true;
+    }
+
+    /// Check if a node is selected
+    pub fn is_node_selected(&self, node_id: NodeId) -> bool {
+        self.selected_nodes.contains(&node_id)
+    }
+
+    /// Select every node that can be picked, leaving out hidden and locked layers
+    pub fn select_all_nodes(&mut self) {
+        let selectable: HashSet<NodeId> = self
+            .nodes
+            .iter()
+            .map(|node| node.id())
+            .filter(|node_id| self.is_layer_selectable(*node_id))
+            .collect();
+        // Check if all nodes are already selected to avoid unnecessary work
+        if self.selected_nodes == selectable {
+            return;
+        }
+
+        self.selected_nodes = selectable;
+        self.dirty = true;
+    }
+
+    /// Update the layout for the entire canvas
+    pub fn update_layout(&mut self) {
+        if !self.dirty {
+            return;
+        }
+
+        // Compute content bounds
+        self.update_content_bounds();
+
+        self.dirty = false;
+    }
+
+    /// Update the content bounds of the canvas
+    fn update_content_bounds(&mut self) {
+        let mut min_x = f32::MAX;
+        let mut min_y = f32::MAX;
+        let mut max_x = f32::MIN;
+        let mut max_y = f32::MIN;
+
+        // Find the bounds that contain all nodes
+        for node in &self.nodes {
+            let bounds = node.bounds();
+            min_x = min_x.min(bounds.origin.x);
+            min_y = min_y.min(bounds.origin.y);
+            max_x = max_x.max(bounds.origin.x + bounds.size.width);
+            max_y = max_y.max(bounds.origin.y + bounds.size.height);
+        }
+
+        // Update content bounds if we have nodes
+        if min_x != f32::MAX {
+            self.content_bounds = Bounds {
+                origin: Point::new(min_x, min_y),
+                size: Size::new(max_x - min_x, max_y - min_y),
+            };
+        }
+    }
+
+    /// Get nodes that are visible in the current viewport
+    pub fn visible_nodes(&self, cx: &mut App) -> Vec<&FrameNode> {
+        // Create viewport bounds in window coordinates
+        let viewport = Bounds {
+            origin: Point::new(0.0, 0.0),
+            size: self.viewport.size,
+        };
+
+        // Convert to gpui::Bounds
+        let gpui_viewport = gpui::Bounds {
+            origin: point(
+                gpui::Pixels(viewport.origin.x),
+                gpui::Pixels(viewport.origin.y),
+            ),
+            size: size(
+                gpui::Pixels(viewport.size.width),
+                gpui::Pixels(viewport.size.height),
+            ),
+        };
+
+        // Use scene graph to find visible nodes
+        let visible_node_ids = self.scene_graph.update(cx, |sg, _cx| {
+            let mut visible_ids = Vec::new();
+
+            // Start from canvas node children
+            if let Some(canvas_node) = sg.get_node(self.canvas_node) {
+                for &child_id in canvas_node.children() {
+                    self.collect_visible_nodes(child_id, gpui_viewport, sg, &mut visible_ids);
+                }
+            }
+
+            visible_ids
+        });
+
+        // Return references to visible nodes
+        self.nodes
+            .iter()
+            .filter(|node| visible_node_ids.contains(&node.id()))
+            .collect()
+    }
+
+    /// Helper method to recursively collect visible nodes
+    fn collect_visible_nodes(
+        &self,
+        node_id: SceneNodeId,
+        viewport: gpui::Bounds<gpui::Pixels>,
+        sg: &SceneGraph,
+        result: &mut Vec<NodeId>,
+    ) {
+        // TODO: Implement proper visibility checking
+        // For now, just add the node and its children to the result
+        if let Some(node) = sg.get_node(node_id) {
+            // If node has an associated data node, add it to results
+            if let Some(data_id) = node.data_node_id() {
+                result.push(data_id);
+            }
+
+            // Process all children
+            for &child_id in node.children() {
+                self.collect_visible_nodes(child_id, viewport, sg, result);
+            }
+        }
+    }
+
+    /// Helper function to check if two gpui::Bounds rectangles intersect
+    fn bounds_intersect_gpui(
+        a: &gpui::Bounds<gpui::Pixels>,
+        b: &gpui::Bounds<gpui::Pixels>,
+    ) -> bool {
+        // Check if one rectangle is to the left of the other
+        if a.origin.x.0 + a.size.width.0 < b.origin.x.0
+            || b.origin.x.0 + b.size.width.0 < a.origin.x.0
+        {
+            return false;
+        }
+
+        // Check if one rectangle is above the other
+        if a.origin.y.0 + a.size.height.0 < b.origin.y.0
+            || b.origin.y.0 + b.size.height.0 < a.origin.y.0
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// Nodes that aren't the child of any other node, in document order
+    pub fn top_level_nodes(&self) -> Vec<NodeId> {
+        self.nodes
+            .iter()
+            .map(|node| node.id())
+            .filter(|&node_id| self.find_parent(node_id).is_none())
+            .collect()
+    }
+
+    /// Selected nodes without a selected ancestor, in document order
+    ///
+    /// Operations that act on whole subtrees (printing, exporting, copying) use this so
+    /// a selected child isn't processed a second time alongside its selected parent.
+    pub fn top_level_selection(&self) -> Vec<NodeId> {
+        self.nodes
+            .iter()
+            .map(|node| node.id())
+            .filter(|node_id| self.selected_nodes.contains(node_id))
+            .filter(|&node_id| {
+                let mut ancestor = self.find_parent(node_id);
+                while let Some(ancestor_id) = ancestor {
+                    if self.selected_nodes.contains(&ancestor_id) {
+                        return false;
+                    }
+                    ancestor = self.find_parent(ancestor_id);
+                }
+                true
+            })
+            .collect()
+    }
+
+    /// Get all root nodes (all nodes since we removed hierarchy)
+    pub fn get_root_nodes(&self) -> Vec<NodeId> {
+        self.nodes.iter().map(|node| node.id()).collect()
+    }
+
+    /// Create a new node with the given type at a position
+    pub fn create_node(
+        &mut self,
+        _node_type: NodeType,
+        position: Point<f32>,
+        cx: &mut Context<Self>,
+    ) -> NodeId {
+        let id = self.generate_id();
+
+        // Create a rectangle node at the specified position
+        let mut rect = FrameNode::new(id);
+        *rect.layout_mut() = NodeLayout::new(position.x, position.y, 100.0, 100.0);
+
+        self.add_node(rect, None, cx)
+    }
+
+    /// Swap the fill and stroke colors of every selected node
+    pub fn swap_selected_fill_and_stroke(&mut self, cx: &mut Context<Self>) {
+        let undo = self.styles_of(self.selected_nodes.iter().copied());
+        self.history.record(undo);
+        for node_id in self.selected_nodes.clone() {
+            if let Some(node) = self.get_node(node_id) {
+                let (fill, stroke) = (node.fill(), node.border_color());
+                self.set_fill_and_stroke(node_id, stroke, fill);
+            }
+        }
+        self.mark_dirty(cx);
+    }
+
+    /// Give every selected node the same fill and stroke colors
+    pub fn set_selected_fill_and_stroke(
+        &mut self,
+        fill: Hsla,
+        stroke: Hsla,
+        cx: &mut Context<Self>,
+    ) {
+        let undo = self.styles_of(self.selected_nodes.iter().copied());
+        self.history.record(undo);
+        for node_id in self.selected_nodes.clone() {
+            self.set_fill_and_stroke(node_id, Some(fill), Some(stroke));
+        }
+        self.mark_dirty(cx);
+    }
+
+    /// Set the fill or the stroke of every selected node
+    pub fn set_selected_color(&mut self, target: ColorTarget, color: Hsla, cx: &mut Context<Self>) {
+        let undo = self.styles_of(self.selected_nodes.iter().copied());
+        self.history.record(undo);
+        self.preview_selected_color(target, color, cx);
+    }
+
+    /// Make `color` the current fill or stroke, whichever is targeted, and apply it to
+    /// the selection. This is how swatches and the eyedropper pick a color.
+    pub fn pick_color(&mut self, color: Hsla, cx: &mut Context<Self>) {
+        let target = self.app_state.update(cx, |state, cx| {
+            state.set_target_color(color);
+            state.note_recent_color(color);
+            cx.notify();
+            state.color_target
+        });
+        self.set_selected_color(target, color, cx);
+    }
+
+    /// Remember the styles of the selection as a color picker drag starts
+    pub fn begin_color_change(&mut self) {
+        self.pending_color_change = Some(self.styles_of(self.selected_nodes.iter().copied()));
+    }
+
+    /// Color the selection without recording a change, for colors shown live while
+    /// they're picked
+    pub fn preview_selected_color(
+        &mut self,
+        target: ColorTarget,
+        color: Hsla,
+        cx: &mut Context<Self>,
+    ) {
+        for node_id in self.selected_nodes.clone() {
+            if let Some(node) = self.get_node(node_id) {
+                let (fill, stroke) = match target {
+                    ColorTarget::Fill => (Some(color), node.border_color()),
+                    ColorTarget::Stroke => (node.fill(), Some(color)),
+                };
+                self.set_fill_and_stroke(node_id, fill, stroke);
+            }
+            // A color picked by hand replaces the library token it was set from
+            if let Some(node) = self.get_node_mut(node_id) {
+                match target {
+                    ColorTarget::Fill => node.style_links.fill = None,
+                    ColorTarget::Stroke => node.style_links.border = None,
+                }
+            }
+        }
+        self.mark_dirty(cx);
+    }
+
+    /// Record the colors picked since [`Self::begin_color_change`] as a single change
+    pub fn finish_color_change(&mut self) {
+        if let Some(undo) = self.pending_color_change.take() {
+            self.history.record(undo);
+        }
+    }
+
+    /// Every fill and visible stroke color in the document, most used first
+    pub fn document_colors(&self) -> Vec<Hsla> {
+        let colors = self.nodes.iter().flat_map(|node| {
+            let stroke = node.border_color().filter(|_| node.border_width() > 0.0);
+            [node.fill(), stroke]
+        });
+        distinct_colors_by_usage(colors.flatten())
+    }
+
+    /// Rename several nodes as one change, an empty name restores the generated one
+    pub fn rename_nodes(&mut self, names: &[(NodeId, String)], cx: &mut Context<Self>) {
+        let undo = names
+            .iter()
+            .filter_map(|(node_id, _)| Some((*node_id, self.get_node(*node_id)?.name.clone())))
+            .collect();
+        self.history.record(CanvasCommand::SetNames(undo));
+
+        for (node_id, name) in names {
+            if let Some(node) = self.get_node_mut(*node_id) {
+                node.set_name(Some(name.clone()).filter(|name| !name.trim().is_empty()));
+            }
+        }
+        self.mark_dirty(cx);
+    }
+
+    /// Fix the warnings that can be fixed automatically, returns how many were fixed
+    pub fn fix_lint_warnings(&mut self, warnings: &[LintWarning], cx: &mut Context<Self>) -> usize {
+        let warned_nodes = || warnings.iter().map(|warning| warning.node_id);
+        let undo = CanvasCommand::Batch(vec![
+            self.layouts_of(warned_nodes()),
+            self.styles_of(warned_nodes()),
+        ]);
+
+        let mut fixed = 0;
+        for warning in warnings {
+            let Some(node) = self.get_node_mut(warning.node_id) else {
+                continue;
+            };
+            if !warning.issue.fix(node) {
+                continue;
+            }
+
+            fixed += 1;
+            let bounds = node.layout().bounds();
+            self.scene_graph.update(cx, |sg, _cx| {
+                if let Some(scene_node_id) = sg.get_scene_node_id(warning.node_id) {
+                    sg.set_local_bounds(scene_node_id, bounds);
+                }
+            });
+        }
+
+        if fixed > 0 {
+            self.history.record(undo);
+            self.mark_dirty(cx);
+        }
+        fixed
+    }
+
+    /// Replace the whole document with a template's frames and center the first of them
+    pub fn load_template(&mut self, template: Template, cx: &mut Context<Self>) {
+        for node_id in self.top_level_nodes() {
+            self.remove_node(node_id, cx);
+        }
+        self.hovered_node = None;
+        // Template ids start over, so nothing from the previous document can come back
+        self.history.clear();
+        // A template starts a new document rather than editing the one on disk
+        self.document_path = None;
+        self.document_password = None;
+        self.guides.clear();
+        self.laid_out_sizes.clear();
+        // Templates are single page documents, whatever pages there were go with them
+        self.pages = vec![Page {
+            name: default_page_name(0),
+            stashed: None,
+        }];
+        self.current_page = 0;
+        self.pages_edited = false;
+
+        let frames = template.build();
+        let parents: HashMap<NodeId, NodeId> = frames
+            .iter()
+            .flat_map(|frame| frame.children.iter().map(|child| (*child, frame.id)))
+            .collect();
+        self.next_id = frames.iter().map(|frame| frame.id.0 + 1).max().unwrap_or(1);
+
+        for mut frame in frames {
+            let parent_id = parents.get(&frame.id).copied();
+            // Template layouts are relative to the parent, while add_node takes them in the
+            // parent's own coordinate space
+            if let Some(parent) = parent_id.and_then(|parent_id| self.get_node(parent_id)) {
+                frame.layout.x += parent.layout().x;
+                frame.layout.y += parent.layout().y;
+            }
+            // Children are linked back up as they're added
+            frame.children.clear();
+            self.add_node(frame, parent_id, cx);
+        }
+
+        if let Some(first) = self.top_level_nodes().first() {
+            self.center_on_node(*first, cx);
+        }
+        self.mark_dirty(cx);
+    }
+
+    pub fn document_path(&self) -> Option<&Path> {
+        self.document_path.as_deref()
+    }
+
+    /// The password the document is protected with
+    pub fn document_password(&self) -> Option<&str> {
+        self.document_password.as_deref()
+    }
+
+    /// Protect the document with `password` from its next save on, `None` removes the
+    /// protection
+    pub fn set_document_password(&mut self, password: Option<String>, cx: &mut Context<Self>) {
+        self.document_password = password;
+        cx.notify();
+    }
+
+    /// Whether the document was marked view only, which leaves it to navigation and export
+    pub fn is_view_only(&self, cx: &App) -> bool {
+        self.app_state.read(cx).document_settings.view_only
+    }
+
+    /// Whether the document has changed since it was opened or last saved
+    pub fn is_edited(&self) -> bool {
+        self.history.is_edited()
+            || self.pages_edited
+            || self
+                .stashed_pages()
+                .any(|stashed| stashed.history.is_edited())
+    }
+
+    /// The document as it would be written to disk
+    pub fn to_document(&self, cx: &App) -> Document {
+        let pages = self
+            .pages
+            .iter()
+            .map(|page| match &page.stashed {
+                Some(stashed) => SavedPage {
+                    name: page.name.clone(),
+                    viewport: stashed.viewport,
+                    nodes: stashed.nodes.iter().map(SavedNode::from_frame).collect(),
+                    constraints: stashed.constraints.clone(),
+                    guides: stashed.guides.clone(),
+                },
+                None => SavedPage {
+                    name: page.name.clone(),
+                    viewport: Some(self.viewport_state()),
+                    nodes: self.nodes.iter().map(SavedNode::from_frame).collect(),
+                    constraints: self.saved_constraints(),
+                    guides: self.guides.clone(),
+                },
+            })
+            .collect();
+        Document::from_pages(self.app_state.read(cx).document_settings, pages)
+    }
+
+    /// Where the canvas is scrolled to and how far it's zoomed in
+    fn viewport_state(&self) -> ViewportState {
+        ViewportState {
+            scroll_x: self.scroll_position.x,
+            scroll_y: self.scroll_position.y,
+            zoom: self.zoom,
+        }
+    }
+
+    /// The constraints on the page's nodes, as the expressions they were written as
+    fn saved_constraints(&self) -> Vec<SavedConstraint> {
+        self.constraints
+            .iter()
+            .map(|constraint| SavedConstraint {
+                node: constraint.node_id.0,
+                property: constraint.property,
+                expression: constraint.source.clone(),
+            })
+            .collect()
+    }
+
+    pub fn save_document(&mut self, path: PathBuf, cx: &mut Context<Self>) -> anyhow::Result<()> {
+        self.to_document(cx)
+            .save(&path, self.document_password.as_deref())?;
+        self.document_path = Some(path);
+        self.history.mark_saved();
+        for page in &mut self.pages {
+            if let Some(stashed) = &mut page.stashed {
+                stashed.history.mark_saved();
+            }
+        }
+        self.pages_edited = false;
+        cx.notify();
+        Ok(())
+    }
+
+    /// Replace everything on the canvas with a document opened from `path`, with the
+    /// password it was opened with if it's protected, and show its first page
+    pub fn load_document(
+        &mut self,
+        document: Document,
+        path: PathBuf,
+        password: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        // Convert every page up front so a bad color leaves the current document alone
+        let settings = document.settings;
+        let pages = document
+            .into_pages()
+            .into_iter()
+            .map(|page| -> anyhow::Result<Page> {
+                let stashed = StashedPage {
+                    viewport: page.viewport,
+                    nodes: page.frames()?,
+                    constraints: page.constraints,
+                    guides: page.guides,
+                    ..StashedPage::default()
+                };
+                Ok(Page {
+                    name: page.name,
+                    stashed: Some(stashed),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        for node_id in self.get_root_nodes() {
+            self.remove_node(node_id, cx);
+        }
+        self.hovered_node = None;
+        self.soloed_nodes.clear();
+        self.version_diff = None;
+        self.stop_viewport_animation();
+        self.history.clear();
+        self.constraints = ConstraintSet::default();
+        // Ids start over with the new document, a remembered size would read as a resize
+        self.laid_out_sizes.clear();
+
+        // Ids are unique across pages, so new nodes on any page can't take one
+        self.next_id = pages
+            .iter()
+            .flat_map(|page| page.stashed.iter().flat_map(|stashed| &stashed.nodes))
+            .map(|frame| frame.id.0 + 1)
+            .max()
+            .unwrap_or(1);
+        self.pages = pages;
+        self.current_page = 0;
+        self.pages_edited = false;
+
+        self.app_state.update(cx, |state, cx| {
+            state.document_settings = settings;
+            cx.notify();
+        });
+        if let Some(stashed) = self.pages[0].stashed.take() {
+            self.unstash_page(stashed, cx);
+        }
+
+        self.document_path = Some(path);
+        self.document_password = password;
+        self.mark_dirty(cx);
+        Ok(())
+    }
+
+    /// Names of the document's pages, in order
+    pub fn page_names(&self) -> impl Iterator<Item = &str> {
+        self.pages.iter().map(|page| page.name.as_str())
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Index of the page on the canvas among [`Self::page_names`]
+    pub fn current_page(&self) -> usize {
+        self.current_page
+    }
+
+    /// Whether the page at `index` has no nodes on it
+    pub fn is_page_empty(&self, index: usize) -> bool {
+        match self.pages.get(index).map(|page| &page.stashed) {
+            Some(Some(stashed)) => stashed.nodes.is_empty(),
+            Some(None) => self.nodes.is_empty(),
+            None => true,
+        }
+    }
+
+    /// Put the page on the canvas away and show the one at `index`, scrolled and zoomed
+    /// the way it was left
+    pub fn show_page(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index == self.current_page || index >= self.pages.len() {
+            return;
+        }
+        let stashed = self.stash_page(cx);
+        self.pages[self.current_page].stashed = Some(stashed);
+        self.current_page = index;
+        if let Some(stashed) = self.pages[index].stashed.take() {
+            self.unstash_page(stashed, cx);
+        }
+        cx.notify();
+    }
+
+    /// Add an empty page after the last one and show it
+    pub fn add_page(&mut self, cx: &mut Context<Self>) {
+        let index = self.pages.len();
+        self.pages.push(Page::empty(default_page_name(index)));
+        self.pages_edited = true;
+        self.show_page(index, cx);
+    }
+
+    pub fn rename_page(&mut self, index: usize, name: &str, cx: &mut Context<Self>) {
+        let name = name.trim();
+        let Some(page) = self.pages.get_mut(index) else {
+            return;
+        };
+        if name.is_empty() || page.name == name {
+            return;
+        }
+        page.name = name.to_string();
+        self.pages_edited = true;
+        cx.notify();
+    }
+
+    /// Delete the page at `index` with everything on it, which can't be undone
+    ///
+    /// The last page left stays, a document always has one.
+    pub fn remove_page(&mut self, index: usize, cx: &mut Context<Self>) {
+        if self.pages.len() < 2 || index >= self.pages.len() {
+            return;
+        }
+        if index == self.current_page {
+            let neighbor = if index + 1 < self.pages.len() {
+                index + 1
+            } else {
+                index - 1
+            };
+            self.show_page(neighbor, cx);
+        }
+        self.pages.remove(index);
+        if self.current_page > index {
+            self.current_page -= 1;
+        }
+        self.pages_edited = true;
+        cx.notify();
+    }
+
+    fn stashed_pages(&self) -> impl Iterator<Item = &StashedPage> {
+        self.pages.iter().filter_map(|page| page.stashed.as_ref())
+    }
+
+    /// Take the page on the canvas off it, with what it takes to show it again as it was
+    fn stash_page(&mut self, cx: &mut Context<Self>) -> StashedPage {
+        self.finish_pen_path(cx);
+        self.stop_editing_path(cx);
+
+        // Only the scene nodes go, the nodes are kept with their layouts as they are
+        for node_id in self.top_level_nodes() {
+            self.scene_graph.update(cx, |sg, _cx| {
+                if let Some(scene_node_id) = sg.get_scene_node_id(node_id) {
+                    sg.remove_node(scene_node_id);
+                }
+            });
+        }
+        let stashed = StashedPage {
+            viewport: Some(self.viewport_state()),
+            nodes: std::mem::take(&mut self.nodes),
+            constraints: self.saved_constraints(),
+            guides: std::mem::take(&mut self.guides),
+            history: std::mem::take(&mut self.history),
+            selection: std::mem::take(&mut self.selected_nodes),
+        };
+
+        self.constraints = ConstraintSet::default();
+        self.hovered_node = None;
+        self.highlighted_nodes.clear();
+        self.soloed_nodes.clear();
+        self.version_diff = None;
+        self.potential_parent_frame = None;
+        self.stop_viewport_animation();
+        stashed
+    }
+
+    /// Put a page back on the canvas, scrolled and zoomed the way it was left
+    fn unstash_page(&mut self, stashed: StashedPage, cx: &mut Context<Self>) {
+        let children: HashSet<NodeId> = stashed
+            .nodes
+            .iter()
+            .flat_map(|frame| frame.children.iter().copied())
+            .collect();
+        self.nodes = stashed.nodes;
+        // Saved layouts are already relative to their parent, so the nodes go in as they
+        // are and only need scene nodes built for them
+        for node_id in self.get_root_nodes() {
+            if !children.contains(&node_id) {
+                self.attach_scene_nodes(node_id, self.canvas_node, cx);
+            }
+        }
+
+        let mut skipped = Vec::new();
+        for constraint in stashed.constraints {
+            let node_id = NodeId::new(constraint.node);
+            if let Err(err) =
+                self.set_constraint(node_id, constraint.property, &constraint.expression, cx)
+            {
+                skipped.push(format!("{node_id}: {err}"));
+            }
+        }
+        if !skipped.is_empty() {
+            let message = format!("Skipped {} of the page's constraints", skipped.len());
+            Toasts::show(
+                Notification::error(message).with_detail(skipped.join("\n")),
+                cx,
+            );
+        }
+
+        self.guides = stashed.guides;
+        self.history = stashed.history;
+        self.selected_nodes = stashed.selection;
+        if let Some(viewport) = stashed.viewport {
+            self.set_zoom(viewport.zoom, cx);
+            self.set_scroll_position(point(viewport.scroll_x, viewport.scroll_y), cx);
+        }
+        self.mark_dirty(cx);
+    }
+
+    /// A stroke color on a node without a border would be invisible, so
+    /// such nodes get a 1px border along with the color
+    fn set_fill_and_stroke(&mut self, node_id: NodeId, fill: Option<Hsla>, stroke: Option<Hsla>) {
+        if let Some(node) = self.get_node_mut(node_id) {
+            let border_width = match stroke {
+                Some(_) if node.border_width() <= 0.0 => 1.0,
+                _ => node.border_width(),
+            };
+            node.set_fill(fill);
+            node.set_border(stroke, border_width);
+        }
+    }
+
+    /// Move selected nodes by a delta
+    pub fn move_selected_nodes(&mut self, delta: Point<f32>) {
+        for node in &mut self.nodes {
+            if self.selected_nodes.contains(&node.id()) {
+                let layout = node.layout_mut();
+                layout.x += delta.x;
+                layout.y += delta.y;
+            }
+        }
+
+        self.dirty = true;
+    }
+
+    /// Captures initial coordinates of all selected nodes in element_initial_positions
+    ///
+    /// This method should be called at the start of an element drag operation to establish
+    /// a reference point for relative transformations. The stored positions are used by
+    /// move_selected_nodes_with_drag to preserve element relationships during movement.
+    pub fn save_selected_nodes_positions(&mut self) {
+        self.element_initial_positions.clear();
+
+        // Locked layers stay put when dragged along with the rest of the selection
+        for node in &self.nodes {
+            if self.selected_nodes.contains(&node.id()) && !self.is_layer_locked(node.id()) {
+                let layout = node.layout();
+                self.element_initial_positions
+                    .insert(node.id(), Point::new(layout.x, layout.y));
+            }
+        }
+    }
+
+    /// Transforms selected elements by applying the provided delta to their initial positions
+    ///
+    /// This method operates on the captured initial positions, ensuring that multiple elements
+    /// maintain their relative spatial relationships during dragging. It also updates the
+    /// scene graph to reflect the visual changes.
+    ///
+    /// # Arguments
+    /// * `delta` - The transformation vector to apply to all selected elements
+    /// * `cx` - Context used for scene graph updates
+    pub fn move_selected_nodes_with_drag(&mut self, delta: Point<f32>, cx: &mut Context<Self>) {
+        let grid = self.app_state.read(cx).document_settings.grid;
+        let delta = if grid.snap {
+            self.snap_drag_delta(delta, &grid, cx)
+        } else {
+            delta
+        };
+        // Layers and guides nearby win over the grid, they're what the drag is lined up
+        // against
+        let mut targets = if self.app_state.read(cx).snap_to_layers {
+            self.layer_snap_targets()
+        } else {
+            SnapTargets::default()
+        };
+        targets.add_guides(&self.guides);
+        let delta = if targets.is_empty() {
+            delta
+        } else {
+            self.snap_drag_to(&targets, delta, cx)
+        };
+
+        for node in &mut self.nodes {
+            // Get the node ID first before any mutable borrows
+            let node_id = node.id();
+
+            if self.selected_nodes.contains(&node_id) {
+                if let Some(initial_pos) = self.element_initial_positions.get(&node_id) {
+                    // First, update the layout
+                    let layout = node.layout_mut();
+                    layout.x = initial_pos.x + delta.x;
+                    layout.y = initial_pos.y + delta.y;
+
+                    // Store values we need before releasing the mutable borrow
+                    let new_x = layout.x;
+                    let new_y = layout.y;
+                    let width = layout.width;
+                    let height = layout.height;
+
+                    // Update the scene graph bounds
+                    if let Some(scene_node_id) = self
+                        .scene_graph
+                        .update(cx, |sg, _cx| sg.get_scene_node_id(node_id))
+                    {
+                        self.scene_graph.update(cx, |sg, _cx| {
+                            sg.set_local_bounds(
+                                scene_node_id,
+                                Bounds {
+                                    origin: Point::new(new_x, new_y),
+                                    size: Size::new(width, height),
+                                },
+                            );
+                        });
+                    }
+                }
+            }
+        }
+
+        self.dirty = true;
+    }
+
+    /// Bounds of a node in canvas coordinates, its layout offset by those of its ancestors
+    pub fn absolute_bounds(&self, node_id: NodeId) -> Option<Bounds<f32>> {
+        let mut bounds = self.get_node(node_id)?.layout().bounds();
+        let mut ancestor = self.find_parent(node_id);
+        while let Some(ancestor_id) = ancestor {
+            let layout = self.get_node(ancestor_id)?.layout();
+            bounds.origin.x += layout.x;
+            bounds.origin.y += layout.y;
+            ancestor = self.find_parent(ancestor_id);
+        }
+        Some(bounds)
+    }
+
+    /// The area covered by the whole selection, in canvas coordinates
+    pub fn selection_bounds(&self) -> Option<Bounds<f32>> {
+        self.top_level_selection()
+            .into_iter()
+            .filter_map(|node_id| self.absolute_bounds(node_id))
+            .reduce(|union, bounds| union.union(&bounds))
+    }
+
+    /// Guides drawn across the canvas, in canvas coordinates
+    pub fn guides(&self) -> &[Guide] {
+        &self.guides
+    }
+
+    /// Put guides along the edges and center lines of the selection, as one change
+    ///
+    /// Returns how many were added, guides already in place aren't doubled up.
+    pub fn add_guides_from_selection(&mut self, cx: &mut Context<Self>) -> usize {
+        let Some(bounds) = self.selection_bounds() else {
+            return 0;
+        };
+        let guides = with_guides(&self.guides, guides_around(bounds));
+        let added = guides.len() - self.guides.len();
+        if added > 0 {
+            self.execute(CanvasCommand::SetGuides(guides), cx);
+        }
+        added
+    }
+
+    pub fn clear_guides(&mut self, cx: &mut Context<Self>) {
+        if !self.guides.is_empty() {
+            self.execute(CanvasCommand::SetGuides(Vec::new()), cx);
+        }
+    }
+
+    /// Index of the guide within `radius` of a canvas point, for picking one up
+    pub fn guide_at(&self, canvas_point: Point<f32>, radius: f32) -> Option<usize> {
+        if self.is_previewing() {
+            return None;
+        }
+        guide_near(&self.guides, canvas_point, radius)
+    }
+
+    /// Place a dragged guide where it was dropped, or take it away when it was dropped
+    /// back on a ruler, as one change
+    pub fn finish_guide_drag(&mut self, drag: &GuideDrag, remove: bool, cx: &mut Context<Self>) {
+        let mut guides = self.guides.clone();
+        if let Some(index) = drag.index.filter(|index| *index < guides.len()) {
+            guides.remove(index);
+        }
+        if !remove {
+            guides = with_guides(&guides, [drag.guide]);
+        }
+        if guides != self.guides {
+            self.execute(CanvasCommand::SetGuides(guides), cx);
+        }
+    }
+
+    /// Fit the nodes of a resize drag into the resized area after a drag of `delta`
+    ///
+    /// Nodes keep their place and proportions within the area, so a single node takes
+    /// on the resized bounds exactly. Children follow along since their layouts are
+    /// relative to the resized frame. With snapping on, the dragged corner lands on the
+    /// grid.
+    pub fn resize_nodes(
+        &mut self,
+        resize: &ResizeOperation,
+        delta: Point<f32>,
+        cx: &mut Context<Self>,
+    ) {
+        let grid = self.app_state.read(cx).document_settings.grid;
+        let delta = resize.snapped_delta(delta, |value| grid.snap(value));
+        let original = resize.original_bounds();
+        let resized = resize.resized_bounds(delta);
+        let scale_x = resized.size.width / original.size.width.max(f32::EPSILON);
+        let scale_y = resized.size.height / original.size.height.max(f32::EPSILON);
+
+        for (node_id, start) in &resize.nodes {
+            let parent_origin = self
+                .find_parent(*node_id)
+                .and_then(|parent_id| self.absolute_bounds(parent_id))
+                .map_or(Point::default(), |parent| parent.origin);
+            let x = resized.origin.x + (start.origin.x - original.origin.x) * scale_x;
+            let y = resized.origin.y + (start.origin.y - original.origin.y) * scale_y;
+            let Some(node) = self.get_node_mut(*node_id) else {
+                continue;
+            };
+            let layout = node.layout_mut();
+            layout.x = x - parent_origin.x;
+            layout.y = y - parent_origin.y;
+            layout.width = start.size.width * scale_x;
+            layout.height = start.size.height * scale_y;
+            let bounds = layout.bounds();
+
+            self.scene_graph.update(cx, |sg, _cx| {
+                if let Some(scene_node_id) = sg.get_scene_node_id(*node_id) {
+                    sg.set_local_bounds(scene_node_id, bounds);
+                }
+            });
+        }
+
+        self.mark_dirty(cx);
+    }
+
+    /// Adjust a drag so the top-left-most dragged node lands on the grid, the rest of
+    /// the selection moves by the same amount so its arrangement is kept
+    fn snap_drag_delta(
+        &self,
+        delta: Point<f32>,
+        grid: &GridSettings,
+        cx: &mut Context<Self>,
+    ) -> Point<f32> {
+        let anchor = self
+            .element_initial_positions
+            .iter()
+            .filter(|(node_id, _)| self.selected_nodes.contains(node_id))
+            .map(|(node_id, initial)| {
+                let (parent_x, parent_y) = self
+                    .find_parent(*node_id)
+                    .map(|parent_id| self.get_absolute_position(parent_id, cx))
+                    .unwrap_or((0.0, 0.0));
+                Point::new(parent_x + initial.x, parent_y + initial.y)
+            })
+            .min_by(|a, b| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+
+        match anchor {
+            Some(anchor) => Point::new(
+                grid.snap(anchor.x + delta.x) - anchor.x,
+                grid.snap(anchor.y + delta.y) - anchor.y,
+            ),
+            None => delta,
+        }
+    }
+
+    /// Adjust a drag so the edges or center lines of the dragged layers land on the
+    /// lines of `targets`, when they're close enough
+    fn snap_drag_to(
+        &self,
+        targets: &SnapTargets,
+        delta: Point<f32>,
+        cx: &mut Context<Self>,
+    ) -> Point<f32> {
+        let dragged = self
+            .element_initial_positions
+            .iter()
+            .filter(|(node_id, _)| self.selected_nodes.contains(node_id))
+            .filter_map(|(node_id, initial)| {
+                let size = self.get_node(*node_id)?.layout().bounds().size;
+                let (parent_x, parent_y) = self
+                    .find_parent(*node_id)
+                    .map(|parent_id| self.get_absolute_position(parent_id, cx))
+                    .unwrap_or((0.0, 0.0));
+                let origin = Point::new(
+                    parent_x + initial.x + delta.x,
+                    parent_y + initial.y + delta.y,
+                );
+                Some(Bounds::new(origin, size))
+            })
+            .reduce(|dragged, bounds| dragged.union(&bounds));
+        let Some(dragged) = dragged else {
+            return delta;
+        };
+
+        let offset = targets.snap_offset(dragged, SNAP_DISTANCE / self.zoom);
+        Point::new(delta.x + offset.x, delta.y + offset.y)
+    }
+
+    /// The edges of every layer a drag can snap to, leaving out the dragged layers and
+    /// anything hidden or excluded from snapping, along with its children
+    fn layer_snap_targets(&self) -> SnapTargets {
+        let mut targets = SnapTargets::default();
+        for node in &self.nodes {
+            let skipped =
+                std::iter::successors(Some(node.id), |id| self.find_parent(*id)).any(|id| {
+                    self.selected_nodes.contains(&id)
+                        || self
+                            .get_node(id)
+                            .is_some_and(|node| node.hidden || node.snap_excluded)
+                });
+            if skipped || self.is_hidden_by_solo(node.id) {
+                continue;
+            }
+            if let Some(bounds) = self.absolute_bounds(node.id) {
+                targets.add_bounds(bounds);
+            }
         }
+        targets
+    }
+
+    /// Set viewport bounds (when window resizes)
+    pub fn set_viewport(&mut self, viewport: Bounds<f32>) {
+        self.viewport = viewport;
+        self.dirty = true;
+    }
+
+    /// Set scroll position
+    pub fn set_scroll_position(&mut self, position: Point<f32>, cx: &mut Context<Self>) {
+        self.scroll_position = position;
+
+        self.scene_graph.update(cx, |sg, _cx| {
+            // Calculate viewport center for centered coordinate system
+            let center_x = self.viewport.size.width / 2.0;
+            let center_y = self.viewport.size.height / 2.0;
+            
+            // Use a single transformation matrix that combines all operations
+            // This ensures consistent transformation for all nodes
+            let transform = TransformationMatrix::unit()
+                // The order of operations: first translate to center, then scale, then apply scroll
+                .translate(point(
+                    Pixels(center_x).scale(1.0),
+                    Pixels(center_y).scale(1.0),
+                ))
+                .scale(size(self.zoom, self.zoom))
+                .translate(point(
+                    Pixels(-self.scroll_position.x).scale(1.0),
+                    Pixels(-self.scroll_position.y).scale(1.0),
+                ));
+
+            sg.set_local_transform(self.canvas_node, transform);
+        });
+
+        self.dirty = true;
+    }
+
+    /// Set zoom level
+    pub fn set_zoom(&mut self, zoom: f32, cx: &mut Context<Self>) {
+        self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+
+        // Update canvas root transform
+        self.scene_graph.update(cx, |sg, _cx| {
+            // Calculate viewport center for centered coordinate system
+            let center_x = self.viewport.size.width / 2.0;
+            let center_y = self.viewport.size.height / 2.0;
+            
+            // Use a single transformation matrix that combines all operations
+            // This ensures consistent transformation for all nodes
+            let transform = TransformationMatrix::unit()
+                // The order of operations: first translate to center, then scale, then apply scroll
+                .translate(point(
+                    Pixels(center_x).scale(1.0),
+                    Pixels(center_y).scale(1.0),
+                ))
+                .scale(size(self.zoom, self.zoom))
+                .translate(point(
+                    Pixels(-self.scroll_position.x).scale(1.0),
+                    Pixels(-self.scroll_position.y).scale(1.0),
+                ));
+
+            sg.set_local_transform(self.canvas_node, transform);
+        });
+
         self.dirty = true;
     }
 
-    /// Check if a node is selected
-    pub fn is_node_selected(&self, node_id: NodeId) -> bool {
-        self.selected_nodes.contains(&node_id)
+    pub fn zoom_in(&mut self, _: &ZoomIn, _window: &mut Window, cx: &mut Context<Self>) {
+        let (_, zoom) = self.viewport_target();
+        self.zoom_centered(step_zoom(zoom, true), cx);
     }
 
-    /// Select all root nodes in the canvas
-    pub fn select_all_nodes(&mut self) {
-        // Check if all nodes are already selected to avoid unnecessary work
-        if self.selected_nodes.len() == self.nodes.len() && !self.nodes.is_empty() {
+    pub fn zoom_out(&mut self, _: &ZoomOut, _window: &mut Window, cx: &mut Context<Self>) {
+        let (_, zoom) = self.viewport_target();
+        self.zoom_centered(step_zoom(zoom, false), cx);
+    }
+
+    pub fn reset_zoom(&mut self, _: &ResetZoom, _window: &mut Window, cx: &mut Context<Self>) {
+        self.zoom_centered(1.0, cx);
+    }
+
+    /// Glide to `zoom` with the selection in the middle of the viewport, or what's in
+    /// the middle now when nothing is selected
+    fn zoom_centered(&mut self, zoom: f32, cx: &mut Context<Self>) {
+        let (scroll, _) = self.viewport_target();
+        let center = self
+            .selection_bounds()
+            .map_or(scroll, |bounds| bounds.center());
+        self.animate_viewport_to(center, zoom, cx);
+    }
+
+    /// Scroll position and zoom the viewport is at, or headed to while it's animating,
+    /// so quick repeated steps build on each other
+    fn viewport_target(&self) -> (Point<f32>, f32) {
+        self.viewport_animation
+            .map(|animation| animation.target())
+            .unwrap_or((self.scroll_position, self.zoom))
+    }
+
+    /// Zoom while keeping the part of the canvas under `window_point` in place, the
+    /// way zooming with the cursor over the canvas feels like it should
+    pub fn zoom_around(&mut self, zoom: f32, window_point: Point<f32>, cx: &mut Context<Self>) {
+        let anchor = self.window_to_canvas_point(window_point);
+        self.set_zoom(zoom, cx);
+        let drifted = self.window_to_canvas_point(window_point);
+        self.set_scroll_position(
+            point(
+                self.scroll_position.x + anchor.x - drifted.x,
+                self.scroll_position.y + anchor.y - drifted.y,
+            ),
+            cx,
+        );
+    }
+
+    /// Get current zoom level
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+    
+    /// Get current scroll position
+    pub fn get_scroll_position(&self) -> Point<f32> {
+        self.scroll_position
+    }
+
+    /// Scroll so a node sits in the middle of the viewport, keeping the current zoom
+    pub fn center_on_node(&mut self, node_id: NodeId, cx: &mut Context<Self>) {
+        let Some(size) = self.get_node(node_id).map(|node| node.bounds().size) else {
+            return;
+        };
+
+        let (x, y) = self.get_absolute_position(node_id, cx);
+        let center = point(x + size.width / 2.0, y + size.height / 2.0);
+        self.animate_viewport_to(center, self.zoom, cx);
+    }
+
+    /// Glide to a new scroll position and zoom rather than jumping there
+    ///
+    /// Starts from wherever the viewport currently is, so retargeting a running
+    /// animation continues smoothly from its current frame. In low power mode, or
+    /// previewing with reduced motion simulated, the viewport jumps all the same.
+    pub fn animate_viewport_to(&mut self, scroll: Point<f32>, zoom: f32, cx: &mut Context<Self>) {
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        if scroll == self.scroll_position && zoom == self.zoom {
+            self.viewport_animation = None;
+            return;
+        }
+        if cx.low_power() || self.simulates_reduced_motion() {
+            self.viewport_animation = None;
+            self.set_zoom(zoom, cx);
+            self.set_scroll_position(scroll, cx);
+            cx.notify();
             return;
         }
 
-        self.selected_nodes.clear();
-        self.selected_nodes
-            .extend(self.nodes.iter().map(|node| node.id()));
-        self.dirty = true;
+        self.viewport_animation = Some(ViewportAnimation::new(
+            (self.scroll_position, self.zoom),
+            (scroll, zoom),
+            Instant::now(),
+        ));
+        cx.notify();
     }
 
-    /// Update the layout for the entire canvas
-    pub fn update_layout(&mut self) {
-        if !self.dirty {
+    /// Drop a running viewport animation where it is, for when the user takes over
+    pub fn stop_viewport_animation(&mut self) {
+        self.viewport_animation = None;
+    }
+
+    /// Move the viewport to the current frame of its animation, returns whether the
+    /// animation needs another frame
+    pub fn step_viewport_animation(&mut self, now: Instant, cx: &mut Context<Self>) -> bool {
+        let Some(animation) = self.viewport_animation else {
+            return false;
+        };
+
+        let finished = animation.is_finished(now);
+        let (scroll, zoom) = if finished {
+            self.viewport_animation = None;
+            animation.target()
+        } else {
+            animation.sample(now)
+        };
+        self.set_zoom(zoom, cx);
+        self.set_scroll_position(scroll, cx);
+        !finished
+    }
+
+    /// Copies of the selected subtrees in document order, the top-level ones with their
+    /// absolute layout so they can be placed anywhere
+    pub fn copy_selection(&self) -> Vec<FrameNode> {
+        let roots = self.top_level_selection();
+        self.nodes
+            .iter()
+            .filter(|node| {
+                roots
+                    .iter()
+                    .any(|&root| self.is_ancestor_of(root, node.id()))
+            })
+            .map(|node| {
+                let mut copy = node.clone();
+                if roots.contains(&node.id()) {
+                    if let Some(bounds) = self.absolute_bounds(node.id()) {
+                        copy.layout.x = bounds.origin.x;
+                        copy.layout.y = bounds.origin.y;
+                    }
+                }
+                copy
+            })
+            .collect()
+    }
+
+    /// Add copies made from [`Self::copy_selection`] to the top level and select them,
+    /// recorded as one change
+    pub fn insert_copies(&mut self, copies: Vec<FrameNode>, cx: &mut Context<Self>) {
+        if copies.is_empty() {
             return;
         }
 
-        // Compute content bounds
-        self.update_content_bounds();
+        let children: HashSet<NodeId> = copies
+            .iter()
+            .flat_map(|copy| copy.children.iter().copied())
+            .collect();
+        let roots: Vec<NodeId> = copies
+            .iter()
+            .map(|copy| copy.id)
+            .filter(|node_id| !children.contains(node_id))
+            .collect();
 
-        self.dirty = false;
+        self.nodes.extend(copies);
+        for &root in &roots {
+            self.attach_scene_nodes(root, self.canvas_node, cx);
+        }
+        self.selected_nodes = roots.iter().copied().collect();
+        self.history.record(CanvasCommand::RemoveNodes(roots));
+        self.mark_dirty(cx);
     }
 
-    /// Update the content bounds of the canvas
-    fn update_content_bounds(&mut self) {
-        let mut min_x = f32::MAX;
-        let mut min_y = f32::MAX;
-        let mut max_x = f32::MIN;
-        let mut max_y = f32::MIN;
+    /// Place a copy of the selection just next to it
+    pub fn duplicate_selection(&mut self, cx: &mut Context<Self>) {
+        let nodes = self.copy_selection();
+        let offset = point(PASTE_OFFSET, PASTE_OFFSET);
+        let copies = instantiate(&nodes, offset, || self.generate_id());
+        self.insert_copies(copies, cx);
+    }
 
-        // Find the bounds that contain all nodes
-        for node in &self.nodes {
-            let bounds = node.bounds();
-            min_x = min_x.min(bounds.origin.x);
-            min_y = min_y.min(bounds.origin.y);
-            max_x = max_x.max(bounds.origin.x + bounds.size.width);
-            max_y = max_y.max(bounds.origin.y + bounds.size.height);
+    /// Add an instance of a library component in the middle of the viewport and select it
+    pub fn insert_component(
+        &mut self,
+        item: &LibraryItem,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        let mut instance = item.instantiate(Point::default(), None, || self.generate_id())?;
+        let layout = &mut instance[0].layout;
+        layout.x = (self.scroll_position.x - layout.width / 2.0).round();
+        layout.y = (self.scroll_position.y - layout.height / 2.0).round();
+        self.insert_copies(instance, cx);
+        Ok(())
+    }
+
+    /// Apply a library style or color token to every selected node, tokens going to the
+    /// fill or the stroke depending on `target`
+    pub fn apply_library_item(
+        &mut self,
+        item: &LibraryItem,
+        target: ColorTarget,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        let styles = self
+            .selected_nodes
+            .iter()
+            .filter_map(|&node_id| {
+                let mut style = NodeStyle::of(self.get_node(node_id)?);
+                let applied = item.apply_to_style(&mut style, target);
+                Some(applied.map(|()| (node_id, style)))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        self.execute(CanvasCommand::SetStyles(styles), cx);
+        Ok(())
+    }
+
+    /// Bring nodes up to date with the library items they're linked to, as one change
+    ///
+    /// Component instances are rebuilt from the component where they are, keeping the
+    /// root's id so the instance stays selected and linked, and keeping the fills set
+    /// on the instance rather than the component. Styles and tokens are
+    /// applied again. Nothing changes when any of the updates can't be applied.
+    pub fn apply_library_updates(
+        &mut self,
+        updates: &[LibraryUpdate],
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        let mut commands = Vec::new();
+        let mut rebuilt = Vec::new();
+        for update in updates {
+            let root = update.node_id;
+            if update.linked_as != LinkedAs::Component {
+                continue;
+            }
+            let Some(node) = self.get_node(root) else {
+                continue;
+            };
+            let origin = point(node.layout.x, node.layout.y);
+            let index = self.nodes.iter().position(|node| node.id == root);
+            let parent = self.find_parent(root).and_then(|parent_id| {
+                let index = self
+                    .sibling_order(Some(parent_id))
+                    .iter()
+                    .position(|&child_id| child_id == root)?;
+                Some((parent_id, index))
+            });
+
+            let previous: Vec<FrameNode> = self
+                .nodes
+                .iter()
+                .filter(|node| self.is_ancestor_of(root, node.id))
+                .cloned()
+                .collect();
+            let mut instance = update
+                .item
+                .instantiate(origin, Some(root), || self.generate_id())?;
+            library::keep_overrides(&previous, &mut instance);
+            // Back where the old instance was in the node list, root first
+            let index = index.unwrap_or(self.nodes.len());
+            let nodes = instance
+                .into_iter()
+                .enumerate()
+                .map(|(offset, node)| RemovedNode {
+                    node,
+                    index: index + offset,
+                })
+                .collect();
+            commands.push(CanvasCommand::RemoveNodes(vec![root]));
+            commands.push(CanvasCommand::InsertNodes {
+                root,
+                nodes,
+                parent,
+            });
+            rebuilt.push(root);
         }
 
-        // Update content bounds if we have nodes
-        if min_x != f32::MAX {
-            self.content_bounds = Bounds {
-                origin: Point::new(min_x, min_y),
-                size: Size::new(max_x - min_x, max_y - min_y),
+        // Styles inside rebuilt instances come from the component now
+        let mut styles: Vec<(NodeId, NodeStyle)> = Vec::new();
+        for update in updates {
+            let node_id = update.node_id;
+            let target = match update.linked_as {
+                LinkedAs::Component => continue,
+                LinkedAs::Style => ColorTarget::Fill,
+                LinkedAs::Token(target) => target,
+            };
+            if rebuilt
+                .iter()
+                .any(|&root| self.is_ancestor_of(root, node_id))
+            {
+                continue;
+            }
+            let Some(node) = self.get_node(node_id) else {
+                continue;
             };
+            // A node can have a style and tokens to update, each goes on top of the last
+            let index = match styles.iter().position(|(id, _)| *id == node_id) {
+                Some(index) => index,
+                None => {
+                    styles.push((node_id, NodeStyle::of(node)));
+                    styles.len() - 1
+                }
+            };
+            update.item.apply_to_style(&mut styles[index].1, target)?;
         }
+        commands.push(CanvasCommand::SetStyles(styles));
+
+        self.execute(CanvasCommand::Batch(commands), cx);
+        Ok(())
     }
 
-    /// Get nodes that are visible in the current viewport
-    pub fn visible_nodes(&self, cx: &mut App) -> Vec<&FrameNode> {
-        // Create viewport bounds in window coordinates
-        let viewport = Bounds {
-            origin: Point::new(0.0, 0.0),
-            size: self.viewport.size,
+    /// Create `steps` nodes interpolated between two existing ones
+    ///
+    /// Steps are added to the shared parent of both nodes, or to the canvas when they
+    /// have different parents. Returns the ids of the new nodes, ordered from `from`
+    /// towards `to`.
+    pub fn blend_nodes(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        steps: usize,
+        cx: &mut Context<Self>,
+    ) -> Vec<NodeId> {
+        if self.get_node(from).is_none() || self.get_node(to).is_none() {
+            return Vec::new();
+        }
+
+        let parent_id = self
+            .find_parent(from)
+            .filter(|&parent| self.find_parent(to) == Some(parent));
+        // `add_node` positions children relative to their parent's layout origin
+        let parent_origin = parent_id
+            .and_then(|parent| self.get_node(parent))
+            .map(|parent| point(parent.layout().x, parent.layout().y))
+            .unwrap_or_default();
+
+        let key_for = |canvas: &Self, node_id: NodeId, cx: &mut Context<Self>| {
+            let node = canvas.get_node(node_id).unwrap();
+            let origin = if parent_id.is_some() {
+                point(
+                    node.layout().x + parent_origin.x,
+                    node.layout().y + parent_origin.y,
+                )
+            } else {
+                let (x, y) = canvas.get_absolute_position(node_id, cx);
+                point(x, y)
+            };
+            BlendKey::from_node(node, origin)
         };
+        let from_key = key_for(self, from, cx);
+        let to_key = key_for(self, to, cx);
+
+        let mut new_nodes = Vec::new();
+        for key in blend_steps(&from_key, &to_key, steps) {
+            let mut node = FrameNode::new(self.generate_id());
+            *node.layout_mut() = NodeLayout::new(
+                key.bounds.origin.x,
+                key.bounds.origin.y,
+                key.bounds.size.width,
+                key.bounds.size.height,
+            );
+            node.set_fill(key.fill);
+            node.set_border(key.border_color, key.border_width);
+            node.set_corner_radius(key.corner_radius);
+
+            new_nodes.push(self.add_node(node, parent_id, cx));
+        }
 
-        // Convert to gpui::Bounds
-        let gpui_viewport = gpui::Bounds {
-            origin: point(
-                gpui::Pixels(viewport.origin.x),
-                gpui::Pixels(viewport.origin.y),
-            ),
-            size: size(
-                gpui::Pixels(viewport.size.width),
-                gpui::Pixels(viewport.size.height),
-            ),
+        self.history
+            .record(CanvasCommand::RemoveNodes(new_nodes.clone()));
+        self.mark_dirty(cx);
+        new_nodes
+    }
+
+    /// Undo the latest change
+    pub fn undo(&mut self, _: &Undo, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(mut change) = self.history.take_undo() else {
+            return;
         };
+        change.command = self.apply_command(change.command, cx);
+        self.history.push_redo(change);
+        self.finish_history_step(cx);
+    }
 
-        // Use scene graph to find visible nodes
-        let visible_node_ids = self.scene_graph.update(cx, |sg, _cx| {
-            let mut visible_ids = Vec::new();
+    /// Redo the latest undone change
+    pub fn redo(&mut self, _: &Redo, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(mut change) = self.history.take_redo() else {
+            return;
+        };
+        change.command = self.apply_command(change.command, cx);
+        self.history.push_undo(change);
+        self.finish_history_step(cx);
+    }
 
-            // Start from canvas node children
-            if let Some(canvas_node) = sg.get_node(self.canvas_node) {
-                for &child_id in canvas_node.children() {
-                    self.collect_visible_nodes(child_id, gpui_viewport, sg, &mut visible_ids);
-                }
-            }
+    fn finish_history_step(&mut self, cx: &mut Context<Self>) {
+        // Nodes an undo removed can't stay selected or hovered
+        let nodes = &self.nodes;
+        self.selected_nodes
+            .retain(|node_id| nodes.iter().any(|node| node.id() == *node_id));
+        if self
+            .hovered_node
+            .is_some_and(|node_id| self.get_node(node_id).is_none())
+        {
+            self.hovered_node = None;
+        }
+        if self
+            .editing_path
+            .is_some_and(|node_id| self.get_node(node_id).is_none())
+        {
+            self.editing_path = None;
+        }
+        self.mark_dirty(cx);
+    }
 
-            visible_ids
-        });
+    /// Apply a change and record it so it can be undone
+    pub fn execute(&mut self, command: CanvasCommand, cx: &mut Context<Self>) {
+        let undo = self.apply_command(command, cx);
+        self.history.record(undo);
+        self.mark_dirty(cx);
+    }
 
-        // Return references to visible nodes
-        self.nodes
+    /// Record a change that was already made, by the command that reverts it
+    pub fn record_change(&mut self, undo: CanvasCommand) {
+        self.history.record(undo);
+    }
+
+    /// The current layouts of some nodes, for undoing a move or resize
+    pub fn layouts_of(&self, node_ids: impl IntoIterator<Item = NodeId>) -> CanvasCommand {
+        CanvasCommand::SetLayouts(
+            node_ids
+                .into_iter()
+                .filter_map(|node_id| Some((node_id, self.get_node(node_id)?.layout().clone())))
+                .collect(),
+        )
+    }
+
+    /// The current styles of some nodes, for undoing a style change
+    pub fn styles_of(&self, node_ids: impl IntoIterator<Item = NodeId>) -> CanvasCommand {
+        CanvasCommand::SetStyles(
+            node_ids
+                .into_iter()
+                .filter_map(|node_id| Some((node_id, NodeStyle::of(self.get_node(node_id)?))))
+                .collect(),
+        )
+    }
+
+    /// Remember the parents and layouts of the selection as a drag starts
+    pub fn begin_layout_change(&mut self) {
+        let before = self
+            .selected_nodes
             .iter()
-            .filter(|node| visible_node_ids.contains(&node.id()))
-            .collect()
+            .filter_map(|&node_id| {
+                let layout = self.get_node(node_id)?.layout().clone();
+                Some((node_id, self.find_parent(node_id), layout))
+            })
+            .collect();
+        self.pending_layout_change = Some(before);
     }
 
-    /// Helper method to recursively collect visible nodes
-    fn collect_visible_nodes(
-        &self,
-        node_id: SceneNodeId,
-        viewport: gpui::Bounds<gpui::Pixels>,
-        sg: &SceneGraph,
-        result: &mut Vec<NodeId>,
-    ) {
-        // TODO: Implement proper visibility checking
-        // For now, just add the node and its children to the result
-        if let Some(node) = sg.get_node(node_id) {
-            // If node has an associated data node, add it to results
-            if let Some(data_id) = node.data_node_id() {
-                result.push(data_id);
+    /// Record everything the drag since [`Self::begin_layout_change`] moved, resized or
+    /// dropped into another frame as a single change
+    pub fn finish_layout_change(&mut self) {
+        let Some(before) = self.pending_layout_change.take() else {
+            return;
+        };
+
+        let mut parents = Vec::new();
+        let mut layouts = Vec::new();
+        for (node_id, parent, layout) in before {
+            let Some(node) = self.get_node(node_id) else {
+                continue;
+            };
+            let moved = node.layout().bounds() != layout.bounds();
+            if self.find_parent(node_id) != parent {
+                parents.push((node_id, parent));
+                layouts.push((node_id, layout));
+            } else if moved {
+                layouts.push((node_id, layout));
+            }
+        }
+
+        // Undoing moves the nodes back to their parents first, so the layouts are
+        // relative to the right frame again
+        self.history.record(CanvasCommand::Batch(vec![
+            CanvasCommand::SetParents(parents),
+            CanvasCommand::SetLayouts(layouts),
+        ]));
+    }
+
+    /// Apply a command to the document, returning the command that reverts it
+    fn apply_command(&mut self, command: CanvasCommand, cx: &mut Context<Self>) -> CanvasCommand {
+        match command {
+            CanvasCommand::InsertNodes {
+                root,
+                nodes,
+                parent,
+            } => {
+                for removed in nodes {
+                    let index = removed.index.min(self.nodes.len());
+                    self.nodes.insert(index, removed.node);
+                }
+                let parent_scene_node = match parent {
+                    Some((parent_id, index)) => {
+                        if let Some(parent) = self.get_node_mut(parent_id) {
+                            let index = index.min(parent.children.len());
+                            parent.children.insert(index, root);
+                        }
+                        self.scene_graph
+                            .read(cx)
+                            .get_scene_node_id(parent_id)
+                            .unwrap_or(self.canvas_node)
+                    }
+                    None => self.canvas_node,
+                };
+                self.attach_scene_nodes(root, parent_scene_node, cx);
+                CanvasCommand::RemoveNodes(vec![root])
+            }
+            CanvasCommand::RemoveNodes(node_ids) => {
+                let mut undo = Vec::new();
+                for node_id in node_ids {
+                    if self.get_node(node_id).is_none() {
+                        continue;
+                    }
+
+                    let mut subtree = vec![node_id];
+                    let mut next = 0;
+                    while let Some(&subtree_node) = subtree.get(next) {
+                        let children = self.sibling_order(Some(subtree_node));
+                        subtree.extend(children);
+                        next += 1;
+                    }
+                    let nodes = self
+                        .nodes
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, node)| subtree.contains(&node.id()))
+                        .map(|(index, node)| RemovedNode {
+                            node: node.clone(),
+                            index,
+                        })
+                        .collect();
+                    let parent = self.find_parent(node_id).and_then(|parent_id| {
+                        let index = self
+                            .sibling_order(Some(parent_id))
+                            .iter()
+                            .position(|&child_id| child_id == node_id)?;
+                        Some((parent_id, index))
+                    });
+
+                    if let Some(parent) =
+                        parent.and_then(|(parent_id, _)| self.get_node_mut(parent_id))
+                    {
+                        parent.remove_child(node_id);
+                    }
+                    self.remove_node(node_id, cx);
+                    undo.push(CanvasCommand::InsertNodes {
+                        root: node_id,
+                        nodes,
+                        parent,
+                    });
+                }
+                // Nodes go back in the opposite order, so every index is as it was
+                undo.reverse();
+                CanvasCommand::Batch(undo)
+            }
+            CanvasCommand::SetLayouts(layouts) => {
+                let mut undo = Vec::new();
+                for (node_id, layout) in layouts {
+                    let Some(node) = self.get_node_mut(node_id) else {
+                        continue;
+                    };
+                    undo.push((node_id, std::mem::replace(node.layout_mut(), layout)));
+                    let bounds = node.layout().bounds();
+                    self.scene_graph.update(cx, |sg, _cx| {
+                        if let Some(scene_node_id) = sg.get_scene_node_id(node_id) {
+                            sg.set_local_bounds(scene_node_id, bounds);
+                        }
+                    });
+                }
+                CanvasCommand::SetLayouts(undo)
+            }
+            CanvasCommand::SetStyles(styles) => {
+                let mut undo = Vec::new();
+                for (node_id, style) in styles {
+                    if let Some(node) = self.get_node_mut(node_id) {
+                        undo.push((node_id, NodeStyle::of(node)));
+                        style.apply_to(node);
+                    }
+                }
+                CanvasCommand::SetStyles(undo)
+            }
+            CanvasCommand::SetNames(names) => {
+                let mut undo = Vec::new();
+                for (node_id, name) in names {
+                    if let Some(node) = self.get_node_mut(node_id) {
+                        undo.push((node_id, node.name.clone()));
+                        node.set_name(name);
+                    }
+                }
+                CanvasCommand::SetNames(undo)
+            }
+            CanvasCommand::SetLinks(links) => {
+                let mut undo = Vec::new();
+                for (node_id, link) in links {
+                    if let Some(node) = self.get_node_mut(node_id) {
+                        undo.push((node_id, std::mem::replace(&mut node.link, link)));
+                    }
+                }
+                CanvasCommand::SetLinks(undo)
+            }
+            CanvasCommand::SetComponents(components) => {
+                let mut undo = Vec::new();
+                for (node_id, component) in components {
+                    if let Some(node) = self.get_node_mut(node_id) {
+                        undo.push((node_id, std::mem::replace(&mut node.component, component)));
+                    }
+                }
+                CanvasCommand::SetComponents(undo)
+            }
+            CanvasCommand::SetHidden(hidden) => {
+                let mut undo = Vec::new();
+                for (node_id, hidden) in hidden {
+                    if let Some(node) = self.get_node_mut(node_id) {
+                        undo.push((node_id, std::mem::replace(&mut node.hidden, hidden)));
+                    }
+                }
+                CanvasCommand::SetHidden(undo)
+            }
+            CanvasCommand::SetLocked(locked) => {
+                let mut undo = Vec::new();
+                for (node_id, locked) in locked {
+                    if let Some(node) = self.get_node_mut(node_id) {
+                        undo.push((node_id, std::mem::replace(&mut node.locked, locked)));
+                    }
+                }
+                CanvasCommand::SetLocked(undo)
+            }
+            CanvasCommand::SetSnapExcluded(excluded) => {
+                let mut undo = Vec::new();
+                for (node_id, excluded) in excluded {
+                    if let Some(node) = self.get_node_mut(node_id) {
+                        undo.push((
+                            node_id,
+                            std::mem::replace(&mut node.snap_excluded, excluded),
+                        ));
+                    }
+                }
+                CanvasCommand::SetSnapExcluded(undo)
+            }
+            CanvasCommand::SetPins(pins) => {
+                let mut undo = Vec::new();
+                for (node_id, pins) in pins {
+                    if let Some(node) = self.get_node_mut(node_id) {
+                        undo.push((node_id, std::mem::replace(&mut node.pins, pins)));
+                    }
+                }
+                CanvasCommand::SetPins(undo)
+            }
+            CanvasCommand::SetAutoLayout(auto_layouts) => {
+                let mut undo = Vec::new();
+                for (node_id, auto_layout) in auto_layouts {
+                    if let Some(node) = self.get_node_mut(node_id) {
+                        let current = std::mem::replace(&mut node.auto_layout, auto_layout);
+                        undo.push((node_id, current));
+                    }
+                }
+                CanvasCommand::SetAutoLayout(undo)
+            }
+            CanvasCommand::SetPaths(paths) => {
+                let mut undo = Vec::new();
+                for (node_id, path) in paths {
+                    if let Some(node) = self.get_node_mut(node_id) {
+                        undo.push((node_id, std::mem::replace(&mut node.path, path)));
+                    }
+                }
+                CanvasCommand::SetPaths(undo)
+            }
+            CanvasCommand::SetGuides(guides) => {
+                CanvasCommand::SetGuides(std::mem::replace(&mut self.guides, guides))
+            }
+            CanvasCommand::SetParents(parents) => {
+                let mut undo = Vec::new();
+                for (node_id, parent) in parents {
+                    if self.get_node(node_id).is_none() {
+                        continue;
+                    }
+                    let current = self.find_parent(node_id);
+                    undo.push((node_id, current));
+                    if current == parent {
+                        continue;
+                    }
+
+                    self.remove_child_from_parent(node_id, cx);
+                    if let Some(parent) = parent {
+                        self.add_child_to_parent(parent, node_id, cx);
+                    }
+                    let bounds = self.get_node(node_id).map(|node| node.layout().bounds());
+                    self.scene_graph.update(cx, |sg, _cx| {
+                        if let (Some(scene_node_id), Some(bounds)) =
+                            (sg.get_scene_node_id(node_id), bounds)
+                        {
+                            sg.set_local_bounds(scene_node_id, bounds);
+                        }
+                    });
+                }
+                CanvasCommand::SetParents(undo)
+            }
+            CanvasCommand::SetSiblingOrder { parent, order } => {
+                let current = self.sibling_order(parent);
+                let same_nodes = current.len() == order.len()
+                    && order.iter().all(|node_id| current.contains(node_id));
+                if same_nodes {
+                    self.set_sibling_order(parent, &order, cx);
+                }
+                CanvasCommand::SetSiblingOrder {
+                    parent,
+                    order: current,
+                }
             }
-
-            // Process all children
-            for &child_id in node.children() {
-                self.collect_visible_nodes(child_id, viewport, sg, result);
+            CanvasCommand::Batch(commands) => {
+                let mut undo: Vec<CanvasCommand> = commands
+                    .into_iter()
+                    .map(|command| self.apply_command(command, cx))
+                    .collect();
+                undo.reverse();
+                CanvasCommand::Batch(undo)
             }
         }
     }
 
-    /// Helper function to check if two gpui::Bounds rectangles intersect
-    fn bounds_intersect_gpui(
-        a: &gpui::Bounds<gpui::Pixels>,
-        b: &gpui::Bounds<gpui::Pixels>,
-    ) -> bool {
-        // Check if one rectangle is to the left of the other
-        if a.origin.x.0 + a.size.width.0 < b.origin.x.0
-            || b.origin.x.0 + b.size.width.0 < a.origin.x.0
-        {
-            return false;
+    /// Create scene nodes for a node and its descendants, below `parent_scene_node`
+    fn attach_scene_nodes(
+        &mut self,
+        node_id: NodeId,
+        parent_scene_node: SceneNodeId,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(node) = self.get_node(node_id) else {
+            return;
+        };
+        let bounds = node.layout().bounds();
+        let children = node.children().clone();
+
+        let scene_node = self.scene_graph.update(cx, |sg, _cx| {
+            let scene_node = sg.create_node(Some(parent_scene_node), Some(node_id));
+            sg.set_local_bounds(scene_node, bounds);
+            scene_node
+        });
+        for child_id in children {
+            self.attach_scene_nodes(child_id, scene_node, cx);
         }
+    }
 
-        // Check if one rectangle is above the other
-        if a.origin.y.0 + a.size.height.0 < b.origin.y.0
-            || b.origin.y.0 + b.size.height.0 < a.origin.y.0
-        {
-            return false;
+    /// Check if the canvas is dirty and needs redrawing
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Redraw once a little time has passed, which is how an idle canvas keeps up to
+    /// date in low power mode instead of redrawing every frame
+    pub fn schedule_idle_refresh(&mut self, cx: &mut Context<Self>) {
+        if self.idle_refresh.is_some() {
+            return;
         }
+        let timer = cx.background_executor().timer(IDLE_FRAME_INTERVAL);
+        let this = cx.entity().downgrade();
+        let mut async_cx = cx.to_async();
+        self.idle_refresh = Some(cx.foreground_executor().spawn(async move {
+            timer.await;
+            this.update(&mut async_cx, |canvas, cx| {
+                canvas.idle_refresh = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
 
-        true
+    /// Mark the canvas as dirty (needing redraw)
+    pub fn mark_dirty(&mut self, cx: &mut Context<Self>) {
+        self.apply_constraints(cx);
+        self.apply_layout_rules(cx);
+        self.dirty = true;
+        cx.notify();
     }
 
-    /// Get all root nodes (all nodes since we removed hierarchy)
-    pub fn get_root_nodes(&self) -> Vec<NodeId> {
-        self.nodes.iter().map(|node| node.id()).collect()
+    pub fn constraints(&self) -> &ConstraintSet {
+        &self.constraints
     }
 
-    /// Create a new node with the given type at a position
-    pub fn create_node(
+    /// Bind a node property to an expression and apply it right away
+    pub fn set_constraint(
         &mut self,
-        _node_type: NodeType,
-        position: Point<f32>,
+        node_id: NodeId,
+        property: Property,
+        expression: &str,
         cx: &mut Context<Self>,
-    ) -> NodeId {
-        let id = self.generate_id();
-
-        // Create a rectangle node at the specified position
-        let mut rect = FrameNode::new(id);
-        *rect.layout_mut() = NodeLayout::new(position.x, position.y, 100.0, 100.0);
+    ) -> Result<(), ConstraintError> {
+        let mut constraints = std::mem::take(&mut self.constraints);
+        let result = constraints.set(node_id, property, expression, self);
+        self.constraints = constraints;
+        result?;
 
-        self.add_node(rect, None, cx)
+        self.mark_dirty(cx);
+        Ok(())
     }
 
-    /// Move selected nodes by a delta
-    pub fn move_selected_nodes(&mut self, delta: Point<f32>) {
-        for node in &mut self.nodes {
-            if self.selected_nodes.contains(&node.id()) {
-                let layout = node.layout_mut();
-                layout.x += delta.x;
-                layout.y += delta.y;
-            }
-        }
-
-        self.dirty = true;
+    /// Unbind a node property, leaving it at its last computed value
+    pub fn remove_constraint(&mut self, node_id: NodeId, property: Property) -> bool {
+        self.constraints.remove(node_id, property).is_some()
     }
 
-    /// Captures initial coordinates of all selected nodes in element_initial_positions
-    ///
-    /// This method should be called at the start of an element drag operation to establish
-    /// a reference point for relative transformations. The stored positions are used by
-    /// move_selected_nodes_with_drag to preserve element relationships during movement.
-    pub fn save_selected_nodes_positions(&mut self) {
-        self.element_initial_positions.clear();
+    /// Re-evaluate constraints and sync the scene graph for nodes that changed
+    fn apply_constraints(&mut self, cx: &mut Context<Self>) {
+        if self.constraints.is_empty() {
+            return;
+        }
 
-        for node in &self.nodes {
-            if self.selected_nodes.contains(&node.id()) {
-                let layout = node.layout();
-                self.element_initial_positions
-                    .insert(node.id(), Point::new(layout.x, layout.y));
+        let constraints = std::mem::take(&mut self.constraints);
+        let result = constraints.apply(self);
+        self.constraints = constraints;
+
+        let changed = match result {
+            Ok(changed) => changed,
+            // Reparenting can close a loop that didn't exist when the constraints were set
+            // Runs on every change, a repeat replaces the toast that's already up
+            Err(err) => {
+                let message = "Constraints stopped updating";
+                Toasts::show(Notification::error(message).with_detail(err), cx);
+                return;
             }
-        }
+        };
+
+        self.sync_scene_bounds(changed, cx);
     }
 
-    /// Transforms selected elements by applying the provided delta to their initial positions
-    ///
-    /// This method operates on the captured initial positions, ensuring that multiple elements
-    /// maintain their relative spatial relationships during dragging. It also updates the
-    /// scene graph to reflect the visual changes.
+    /// Reflow the children of frames resized since the last pass by their pins, and
+    /// restack the children of frames with auto layout
     ///
-    /// # Arguments
-    /// * `delta` - The transformation vector to apply to all selected elements
-    /// * `cx` - Context used for scene graph updates
-    pub fn move_selected_nodes_with_drag(&mut self, delta: Point<f32>, cx: &mut Context<Self>) {
-        for node in &mut self.nodes {
-            // Get the node ID first before any mutable borrows
-            let node_id = node.id();
-
-            if self.selected_nodes.contains(&node_id) {
-                if let Some(initial_pos) = self.element_initial_positions.get(&node_id) {
-                    // First, update the layout
-                    let layout = node.layout_mut();
-                    layout.x = initial_pos.x + delta.x;
-                    layout.y = initial_pos.y + delta.y;
-
-                    // Store values we need before releasing the mutable borrow
-                    let new_x = layout.x;
-                    let new_y = layout.y;
-                    let width = layout.width;
-                    let height = layout.height;
+    /// Frames are visited parents first, so a child stretched by its parent passes the
+    /// resize on to its own children in the same pass.
+    fn apply_layout_rules(&mut self, cx: &mut Context<Self>) {
+        let children: HashSet<NodeId> = self
+            .nodes
+            .iter()
+            .flat_map(|node| node.children.iter().copied())
+            .collect();
+        let mut pending: Vec<NodeId> = self
+            .nodes
+            .iter()
+            .map(|node| node.id())
+            .filter(|node_id| !children.contains(node_id))
+            .collect();
 
-                    // Update the scene graph bounds
-                    if let Some(scene_node_id) = self
-                        .scene_graph
-                        .update(cx, |sg, _cx| sg.get_scene_node_id(node_id))
-                    {
-                        self.scene_graph.update(cx, |sg, _cx| {
-                            sg.set_local_bounds(
-                                scene_node_id,
-                                Bounds {
-                                    origin: Point::new(new_x, new_y),
-                                    size: Size::new(width, height),
-                                },
-                            );
-                        });
+        let mut changed = Vec::new();
+        while let Some(node_id) = pending.pop() {
+            let Some(node) = self.get_node(node_id) else {
+                continue;
+            };
+            let size = node.layout().bounds().size;
+            let auto_layout = node.auto_layout;
+            let children = node.children.clone();
+            // Frames seen for the first time were just created or opened, not resized
+            let resized_from = self
+                .laid_out_sizes
+                .insert(node_id, size)
+                .filter(|from| *from != size);
+
+            if let Some(auto_layout) = auto_layout {
+                let (stacked, current): (Vec<NodeId>, Vec<_>) = children
+                    .iter()
+                    .filter_map(|child_id| self.get_node(*child_id))
+                    .filter(|child| !child.hidden)
+                    .map(|child| (child.id(), (child.layout().clone(), child.pins)))
+                    .unzip();
+                let layouts = layout::stack(&auto_layout, size, &current);
+                for ((child_id, (current, _)), layout) in stacked.iter().zip(&current).zip(layouts)
+                {
+                    if layout != *current {
+                        self.set_laid_out_layout(*child_id, layout, &mut changed);
+                    }
+                }
+            } else if let Some(from) = resized_from {
+                for child_id in &children {
+                    let Some(child) = self.get_node(*child_id) else {
+                        continue;
+                    };
+                    if !child.pins.is_default() {
+                        let layout = layout::pin(child.layout(), child.pins, from, size);
+                        self.set_laid_out_layout(*child_id, layout, &mut changed);
                     }
                 }
             }
+            pending.extend(children);
         }
-
-        self.dirty = true;
-    }
-
-    /// Set viewport bounds (when window resizes)
-    pub fn set_viewport(&mut self, viewport: Bounds<f32>) {
-        self.viewport = viewport;
-        self.dirty = true;
-    }
-
-    /// Set scroll position
-    pub fn set_scroll_position(&mut self, position: Point<f32>, cx: &mut Context<Self>) {
-        self.scroll_position = position;
-
-        self.scene_graph.update(cx, |sg, _cx| {
-            // Calculate viewport center for centered coordinate system
-            let center_x = self.viewport.size.width / 2.0;
-            let center_y = self.viewport.size.height / 2.0;
-            
-            // Use a single transformation matrix that combines all operations
-            // This ensures consistent transformation for all nodes
-            let transform = TransformationMatrix::unit()
-                // The order of operations: first translate to center, then scale, then apply scroll
-                .translate(point(
-                    Pixels(center_x).scale(1.0),
-                    Pixels(center_y).scale(1.0),
-                ))
-                .scale(size(self.zoom, self.zoom))
-                .translate(point(
-                    Pixels(-self.scroll_position.x).scale(1.0),
-                    Pixels(-self.scroll_position.y).scale(1.0),
-                ));
-
-            sg.set_local_transform(self.canvas_node, transform);
-        });
-
-        self.dirty = true;
-    }
-
-    /// Set zoom level
-    pub fn set_zoom(&mut self, zoom: f32, cx: &mut Context<Self>) {
-        self.zoom = zoom.max(0.1).min(10.0); // Limit zoom range
-
-        // Update canvas root transform
-        self.scene_graph.update(cx, |sg, _cx| {
-            // Calculate viewport center for centered coordinate system
-            let center_x = self.viewport.size.width / 2.0;
-            let center_y = self.viewport.size.height / 2.0;
-            
-            // Use a single transformation matrix that combines all operations
-            // This ensures consistent transformation for all nodes
-            let transform = TransformationMatrix::unit()
-                // The order of operations: first translate to center, then scale, then apply scroll
-                .translate(point(
-                    Pixels(center_x).scale(1.0),
-                    Pixels(center_y).scale(1.0),
-                ))
-                .scale(size(self.zoom, self.zoom))
-                .translate(point(
-                    Pixels(-self.scroll_position.x).scale(1.0),
-                    Pixels(-self.scroll_position.y).scale(1.0),
-                ));
-
-            sg.set_local_transform(self.canvas_node, transform);
-        });
-
-        self.dirty = true;
-    }
-
-    /// Get current zoom level
-    pub fn zoom(&self) -> f32 {
-        self.zoom
-    }
-    
-    /// Get current scroll position
-    pub fn get_scroll_position(&self) -> Point<f32> {
-        self.scroll_position
+        self.sync_scene_bounds(changed, cx);
     }
 
-    /// Check if the canvas is dirty and needs redrawing
-    pub fn is_dirty(&self) -> bool {
-        self.dirty
+    fn set_laid_out_layout(
+        &mut self,
+        node_id: NodeId,
+        layout: NodeLayout,
+        changed: &mut Vec<NodeId>,
+    ) {
+        if let Some(node) = self.get_node_mut(node_id) {
+            *node.layout_mut() = layout;
+            changed.push(node_id);
+        }
     }
 
-    /// Mark the canvas as dirty (needing redraw)
-    pub fn mark_dirty(&mut self, cx: &mut Context<Self>) {
-        self.dirty = true;
-        cx.notify();
+    /// Bring the scene graph in line with the layouts of nodes changed behind its back
+    fn sync_scene_bounds(&mut self, node_ids: Vec<NodeId>, cx: &mut Context<Self>) {
+        for node_id in node_ids {
+            let Some(bounds) = self.get_node(node_id).map(|node| node.layout().bounds()) else {
+                continue;
+            };
+            self.scene_graph.update(cx, |sg, _cx| {
+                if let Some(scene_node_id) = sg.get_scene_node_id(node_id) {
+                    sg.set_local_bounds(scene_node_id, bounds);
+                }
+            });
+        }
     }
 
     /// Get content bounds
@@ -972,6 +4309,7 @@ impl LunaCanvas {
 
     pub fn key_context(&self) -> KeyContext {
         let mut key_context = KeyContext::new_with_defaults();
+        key_context.add("Canvas");
         key_context.set("canvas", "Canvas");
         key_context
     }
@@ -1052,7 +4390,108 @@ impl LunaCanvas {
     }
 }
 
+impl Focusable for LunaCanvas {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl ConstraintTarget for LunaCanvas {
+    fn parent(&self, node_id: NodeId) -> Option<NodeId> {
+        self.find_parent(node_id)
+    }
+
+    fn property(&self, node_id: NodeId, property: Property) -> Option<f32> {
+        let node = self.get_node(node_id)?;
+        let layout = node.layout();
+        Some(match property {
+            Property::X => layout.x,
+            Property::Y => layout.y,
+            Property::Width => layout.width,
+            Property::Height => layout.height,
+            Property::CornerRadius => node.corner_radius(),
+            Property::BorderWidth => node.border_width(),
+        })
+    }
+
+    fn set_property(&mut self, node_id: NodeId, property: Property, value: f32) {
+        let Some(node) = self.get_node_mut(node_id) else {
+            return;
+        };
+        match property {
+            Property::X => node.layout_mut().x = value,
+            Property::Y => node.layout_mut().y = value,
+            Property::Width => node.layout_mut().width = value.max(0.0),
+            Property::Height => node.layout_mut().height = value.max(0.0),
+            Property::CornerRadius => node.set_corner_radius(value.max(0.0)),
+            Property::BorderWidth => {
+                let color = node.border_color();
+                node.set_border(color, value.max(0.0));
+            }
+        }
+    }
+}
+
+/// The next of the [`ZOOM_PRESETS`] past `zoom`, closer in or farther out, staying at
+/// the last one either way
+pub fn step_zoom(zoom: f32, zoom_in: bool) -> f32 {
+    // Zooms a hair off a preset, after zooming with the wheel, count as on it
+    const TOLERANCE: f32 = 1.001;
+    if zoom_in {
+        ZOOM_PRESETS
+            .into_iter()
+            .find(|preset| *preset > zoom * TOLERANCE)
+            .unwrap_or(MAX_ZOOM)
+    } else {
+        ZOOM_PRESETS
+            .into_iter()
+            .rev()
+            .find(|preset| *preset < zoom / TOLERANCE)
+            .unwrap_or(MIN_ZOOM)
+    }
+}
+
 /// Tests for AABB intersection between two bounds
+/// The scroll position closest to `scroll` that shows all of `target`
+///
+/// `visible_size` is the size of the viewport in canvas units and `margin` the space to
+/// keep around the target. A target that doesn't fit is aligned to its top left edge.
+pub fn reveal_scroll(
+    scroll: Point<f32>,
+    visible_size: Size<f32>,
+    target: Bounds<f32>,
+    margin: f32,
+) -> Point<f32> {
+    fn reveal_axis(scroll: f32, visible: f32, start: f32, end: f32, margin: f32) -> f32 {
+        let half = visible / 2.0;
+        let fits = end - start <= visible - margin * 2.0;
+        if !fits || start < scroll - half + margin {
+            start - margin + half
+        } else if end > scroll + half - margin {
+            end + margin - half
+        } else {
+            scroll
+        }
+    }
+
+    point(
+        reveal_axis(
+            scroll.x,
+            visible_size.width,
+            target.left(),
+            target.right(),
+            margin,
+        ),
+        reveal_axis(
+            scroll.y,
+            visible_size.height,
+            target.top(),
+            target.bottom(),
+            margin,
+        ),
+    )
+}
+
 fn bounds_intersect(a: &Bounds<f32>, b: &Bounds<f32>) -> bool {
     // Check if one rectangle is to the left of the other
     if a.origin.x + a.size.width < b.origin.x || b.origin.x + b.size.width < a.origin.x {
@@ -1071,6 +4510,19 @@ fn bounds_intersect(a: &Bounds<f32>, b: &Bounds<f32>) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_step_zoom() {
+        assert_eq!(step_zoom(1.0, true), 2.0);
+        assert_eq!(step_zoom(1.0, false), 0.5);
+        // Between presets, the next one either way
+        assert_eq!(step_zoom(0.7, true), 1.0);
+        assert_eq!(step_zoom(0.7, false), 0.5);
+        assert_eq!(step_zoom(1.0005, true), 2.0);
+        // Stepping stops at either end
+        assert_eq!(step_zoom(MAX_ZOOM, true), MAX_ZOOM);
+        assert_eq!(step_zoom(MIN_ZOOM, false), MIN_ZOOM);
+    }
+
     #[test]
     fn test_bounds_intersection() {
         // Overlapping bounds
@@ -1098,4 +4550,37 @@ mod tests {
         };
         assert!(!bounds_intersect(&a, &d));
     }
+    #[test]
+    fn test_reveal_scroll() {
+        let visible = Size::new(200.0, 100.0);
+        let scroll = Point::new(0.0, 0.0);
+        let bounds = |x, y, w, h| Bounds {
+            origin: Point::new(x, y),
+            size: Size::new(w, h),
+        };
+
+        // Already visible, nothing moves
+        assert_eq!(
+            reveal_scroll(scroll, visible, bounds(-20.0, -20.0, 40.0, 40.0), 10.0),
+            scroll
+        );
+
+        // Off the right edge, scroll just far enough to show it with the margin
+        assert_eq!(
+            reveal_scroll(scroll, visible, bounds(120.0, 0.0, 20.0, 20.0), 10.0),
+            Point::new(50.0, 0.0)
+        );
+
+        // Above the top edge
+        assert_eq!(
+            reveal_scroll(scroll, visible, bounds(0.0, -100.0, 20.0, 20.0), 10.0),
+            Point::new(0.0, -60.0)
+        );
+
+        // Too tall to fit, its top edge is shown
+        assert_eq!(
+            reveal_scroll(scroll, visible, bounds(0.0, 0.0, 20.0, 300.0), 10.0),
+            Point::new(0.0, 40.0)
+        );
+    }
 }
@@ -1,25 +1,53 @@
 use crate::{
-    canvas::{register_canvas_action, ClearSelection, LunaCanvas},
-    interactivity::{ActiveDrag, DragType, ResizeHandle, ResizeOperation},
-    node::{frame::FrameNode, NodeCommon, NodeId, NodeLayout, NodeType, Shadow},
+    canvas::{register_canvas_action, ClearSelection, LunaCanvas, RenderMode},
+    color::{grayscale, increase_contrast},
+    grid,
+    guides::{ruler_label, ruler_step, Guide, GuideAxis},
+    history::CanvasCommand,
+    interactivity::{ActiveDrag, DragType, GuideDrag, ResizeHandle, ResizeOperation},
+    node::{
+        frame::FrameNode, path::VectorPath, BlendMode, NodeCommon, NodeId, NodeLayout, NodeType,
+        Shadow,
+    },
+    overlay::{self, OverlayKind, LABEL_FONT_SIZE, LABEL_HEIGHT, LABEL_PADDING},
+    pixel_preview::{self, ExportScale},
+    power::ActivePower,
     scene_graph::SceneGraph,
     theme::{ActiveTheme, Theme},
     tools::{ActiveTool, GlobalTool},
     util::{round_to_pixel, rounded_point},
-    Tool,
+    ShowContextMenu, Tool,
 };
 use gpui::{
     hsla, prelude::*, px, relative, App, BorderStyle, ContentMask, DispatchPhase, ElementId,
-    Entity, Hitbox, Hsla, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Style,
-    TextStyle, TextStyleRefinement, TransformationMatrix, Window,
+    Entity, Focusable, Hitbox, Hsla, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    PathBuilder, Pixels, SharedString, Style, TextRun, TextStyle, TextStyleRefinement,
+    TransformationMatrix, Window,
 };
 use gpui::{point, Bounds, Point, Size};
 use smallvec::SmallVec;
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
+    time::Instant,
 };
 
+/// How strongly scrolling with cmd or ctrl zooms, per pixel scrolled. A notch of the
+/// wheel, 30px, zooms by about a third.
+const SCROLL_ZOOM_SPEED: f32 = 0.01;
+
+/// How close to a path's anchor or handle a click has to be to pick it, in screen pixels
+const PATH_HANDLE_RADIUS: f32 = 6.0;
+
+/// How close to a guide a click has to be to pick it up, in screen pixels
+const GUIDE_GRAB_RADIUS: f32 = 3.0;
+
+/// Thickness of the rulers along the canvas edges, in screen pixels
+const RULER_SIZE: f32 = 18.0;
+
+/// Size of the numbers on the rulers
+const RULER_FONT_SIZE: f32 = 9.0;
+
 /// Defines z-ordering for rendering layers with reserved index ranges
 ///
 /// Z-indices are allocated in blocks of 10,000 per layer:
@@ -117,13 +145,135 @@ fn bounds_intersect(a: &Bounds<f32>, b: &Bounds<f32>) -> bool {
     true
 }
 
+/// Paint a resize handle centered on each corner of a selection outline
+fn paint_corner_handles(selection_bounds: Bounds<Pixels>, color: Hsla, window: &mut Window) {
+    const HANDLE_SIZE: f32 = 7.0;
+    const HALF_HANDLE: f32 = HANDLE_SIZE / 2.0;
+
+    // Center handles on the selection outline
+    let corners = [
+        // Top-left
+        (
+            selection_bounds.origin.x - gpui::Pixels(HALF_HANDLE - 0.5),
+            selection_bounds.origin.y - gpui::Pixels(HALF_HANDLE - 0.5),
+        ),
+        // Top-right
+        (
+            selection_bounds.origin.x + selection_bounds.size.width
+                - gpui::Pixels(HALF_HANDLE + 0.5),
+            selection_bounds.origin.y - gpui::Pixels(HALF_HANDLE - 0.5),
+        ),
+        // Bottom-left
+        (
+            selection_bounds.origin.x - gpui::Pixels(HALF_HANDLE - 0.5),
+            selection_bounds.origin.y + selection_bounds.size.height
+                - gpui::Pixels(HALF_HANDLE + 0.5),
+        ),
+        // Bottom-right
+        (
+            selection_bounds.origin.x + selection_bounds.size.width
+                - gpui::Pixels(HALF_HANDLE + 0.5),
+            selection_bounds.origin.y + selection_bounds.size.height
+                - gpui::Pixels(HALF_HANDLE + 0.5),
+        ),
+    ];
+
+    for (x, y) in corners {
+        let handle_bounds = gpui::Bounds {
+            origin: gpui::Point::new(x, y),
+            size: gpui::Size::new(gpui::Pixels(HANDLE_SIZE), gpui::Pixels(HANDLE_SIZE)),
+        };
+
+        window.paint_quad(gpui::fill(handle_bounds, gpui::hsla(0.0, 0.0, 1.0, 1.0)));
+        window.paint_quad(gpui::outline(handle_bounds, color, BorderStyle::Solid));
+    }
+}
+
+/// Paint a shadow cast inward from the edges of `bounds`
+///
+/// gpui only casts shadows outward, so the shadow is cast by four strips around the
+/// area the shadow leaves lit, clipped to `bounds`. That area keeps square corners.
+fn paint_inset_shadow(bounds: Bounds<Pixels>, shadow: &Shadow, color: Hsla, window: &mut Window) {
+    let spread = px(shadow.spread_radius);
+    let lit = Bounds {
+        origin: point(
+            bounds.origin.x + px(shadow.offset.x) + spread,
+            bounds.origin.y + px(shadow.offset.y) + spread,
+        ),
+        size: Size::new(
+            (bounds.size.width - spread * 2.0).max(px(0.)),
+            (bounds.size.height - spread * 2.0).max(px(0.)),
+        ),
+    };
+    // Far enough out that the outer edges of the strips never blur into view
+    let reach = bounds.size.width + bounds.size.height + px(shadow.blur_radius * 2.0);
+    let (left, top, right, bottom) = (lit.left(), lit.top(), lit.right(), lit.bottom());
+    let strips = [
+        Bounds::from_corners(point(left - reach, top - reach), point(right + reach, top)),
+        Bounds::from_corners(
+            point(left - reach, bottom),
+            point(right + reach, bottom + reach),
+        ),
+        Bounds::from_corners(point(left - reach, top), point(left, bottom)),
+        Bounds::from_corners(point(right, top), point(right + reach, bottom)),
+    ];
+    let box_shadow = gpui::BoxShadow {
+        color,
+        offset: point(px(0.), px(0.)),
+        blur_radius: px(shadow.blur_radius),
+        spread_radius: px(0.),
+    };
+
+    window.with_content_mask(Some(ContentMask { bounds }), |window| {
+        for strip in strips {
+            window.paint_shadows(strip, gpui::Corners::default(), &[box_shadow.clone()]);
+        }
+    });
+}
+
+/// Paint `path` stretched over `bounds` on screen, filled or stroked as `builder` says
+fn paint_vector_path(
+    path: &VectorPath,
+    bounds: Bounds<Pixels>,
+    mut builder: PathBuilder,
+    color: Hsla,
+    window: &mut Window,
+) {
+    let path = path.fit(Bounds {
+        origin: point(bounds.origin.x.0, bounds.origin.y.0),
+        size: Size::new(bounds.size.width.0, bounds.size.height.0),
+    });
+    let Some(first) = path.anchors.first() else {
+        return;
+    };
+    let pixels = |position: Point<f32>| point(px(position.x), px(position.y));
+    builder.move_to(pixels(first.position));
+    // Straight segments are curves with their handles on their ends
+    for [_, control_a, control_b, to] in path.segments() {
+        builder.cubic_bezier_to(pixels(to), pixels(control_a), pixels(control_b));
+    }
+    if path.closed {
+        builder.close();
+    }
+    if let Ok(path) = builder.build() {
+        window.paint_path(path, color);
+    }
+}
+
 /// Detects if a point intersects with a resize handle on the node boundaries
-fn point_in_resize_handle(point: Point<f32>, node_bounds: &Bounds<f32>) -> Option<ResizeHandle> {
+///
+/// Handles are painted at a fixed size on screen, so their hit area shrinks in canvas
+/// units as the canvas is zoomed in.
+fn point_in_resize_handle(
+    point: Point<f32>,
+    node_bounds: &Bounds<f32>,
+    zoom: f32,
+) -> Option<ResizeHandle> {
     use ResizeHandle;
 
     // Define handle size and boundaries
-    const HANDLE_SIZE: f32 = 11.0; // Increased from 7.0 to provide a larger hit area
-    const HALF_HANDLE: f32 = HANDLE_SIZE / 2.0;
+    let handle_size = 11.0 / zoom; // Larger than the painted handle for an easier hit
+    let half_handle = handle_size / 2.0;
 
     // Create bounds for each corner handle
     let handles = [
@@ -131,10 +281,10 @@ fn point_in_resize_handle(point: Point<f32>, node_bounds: &Bounds<f32>) -> Optio
         (
             Bounds {
                 origin: Point::new(
-                    node_bounds.origin.x - HALF_HANDLE,
-                    node_bounds.origin.y - HALF_HANDLE,
+                    node_bounds.origin.x - half_handle,
+                    node_bounds.origin.y - half_handle,
                 ),
-                size: Size::new(HANDLE_SIZE, HANDLE_SIZE),
+                size: Size::new(handle_size, handle_size),
             },
             ResizeHandle::TopLeft,
         ),
@@ -142,10 +292,10 @@ fn point_in_resize_handle(point: Point<f32>, node_bounds: &Bounds<f32>) -> Optio
         (
             Bounds {
                 origin: Point::new(
-                    node_bounds.origin.x + node_bounds.size.width - HALF_HANDLE,
-                    node_bounds.origin.y - HALF_HANDLE,
+                    node_bounds.origin.x + node_bounds.size.width - half_handle,
+                    node_bounds.origin.y - half_handle,
                 ),
-                size: Size::new(HANDLE_SIZE, HANDLE_SIZE),
+                size: Size::new(handle_size, handle_size),
             },
             ResizeHandle::TopRight,
         ),
@@ -153,10 +303,10 @@ fn point_in_resize_handle(point: Point<f32>, node_bounds: &Bounds<f32>) -> Optio
         (
             Bounds {
                 origin: Point::new(
-                    node_bounds.origin.x - HALF_HANDLE,
-                    node_bounds.origin.y + node_bounds.size.height - HALF_HANDLE,
+                    node_bounds.origin.x - half_handle,
+                    node_bounds.origin.y + node_bounds.size.height - half_handle,
                 ),
-                size: Size::new(HANDLE_SIZE, HANDLE_SIZE),
+                size: Size::new(handle_size, handle_size),
             },
             ResizeHandle::BottomLeft,
         ),
@@ -164,10 +314,10 @@ fn point_in_resize_handle(point: Point<f32>, node_bounds: &Bounds<f32>) -> Optio
         (
             Bounds {
                 origin: Point::new(
-                    node_bounds.origin.x + node_bounds.size.width - HALF_HANDLE,
-                    node_bounds.origin.y + node_bounds.size.height - HALF_HANDLE,
+                    node_bounds.origin.x + node_bounds.size.width - half_handle,
+                    node_bounds.origin.y + node_bounds.size.height - half_handle,
                 ),
-                size: Size::new(HANDLE_SIZE, HANDLE_SIZE),
+                size: Size::new(handle_size, handle_size),
             },
             ResizeHandle::BottomRight,
         ),
@@ -216,6 +366,46 @@ impl Default for CanvasStyle {
 
 pub struct CanvasLayout {
     hitbox: Hitbox,
+    rulers: Option<Rulers>,
+}
+
+/// Where the rulers sit on screen, along the top and the left of the canvas
+#[derive(Clone, Copy)]
+struct Rulers {
+    top: Bounds<Pixels>,
+    left: Bounds<Pixels>,
+}
+
+impl Rulers {
+    /// Rulers along the top of `bounds` and down its left side, `left_inset` in from
+    /// its left edge
+    fn new(bounds: Bounds<Pixels>, left_inset: Pixels) -> Self {
+        let origin = point(bounds.origin.x + left_inset, bounds.origin.y);
+        Self {
+            top: Bounds {
+                origin,
+                size: Size::new(bounds.size.width - left_inset, px(RULER_SIZE)),
+            },
+            left: Bounds {
+                origin,
+                size: Size::new(px(RULER_SIZE), bounds.size.height),
+            },
+        }
+    }
+
+    fn contains(&self, position: Point<Pixels>) -> bool {
+        self.top.contains(&position) || self.left.contains(&position)
+    }
+
+    /// Axis of the guide pulled out of the ruler at `position`, horizontal ones come out
+    /// of the top ruler. The corner where the rulers meet has none.
+    fn guide_axis_at(&self, position: Point<Pixels>) -> Option<GuideAxis> {
+        match (self.top.contains(&position), self.left.contains(&position)) {
+            (true, false) => Some(GuideAxis::Horizontal),
+            (false, true) => Some(GuideAxis::Vertical),
+            _ => None,
+        }
+    }
 }
 
 /// CanvasElement uses  prefixes for identifying the role of methods within the canvas.
@@ -227,6 +417,8 @@ pub struct CanvasLayout {
 pub struct CanvasElement {
     canvas: Entity<LunaCanvas>,
     style: CanvasStyle,
+    /// How much of the canvas's left side panels cover, the rulers start past it
+    left_inset: Pixels,
 }
 
 impl CanvasElement {
@@ -240,9 +432,15 @@ impl CanvasElement {
         Self {
             canvas: canvas.clone(),
             style,
+            left_inset: px(0.),
         }
     }
 
+    pub fn with_left_inset(mut self, left_inset: Pixels) -> Self {
+        self.left_inset = left_inset;
+        self
+    }
+
     pub fn register_actions(&self, window: &mut Window, cx: &mut App) {
         let canvas = &self.canvas;
         canvas.update(cx, |canvas, cx| {
@@ -252,6 +450,34 @@ impl CanvasElement {
         });
 
         register_canvas_action(canvas, window, LunaCanvas::clear_selection);
+        register_canvas_action(canvas, window, LunaCanvas::select_next_sibling);
+        register_canvas_action(canvas, window, LunaCanvas::select_previous_sibling);
+        register_canvas_action(canvas, window, LunaCanvas::zoom_in);
+        register_canvas_action(canvas, window, LunaCanvas::zoom_out);
+        register_canvas_action(canvas, window, LunaCanvas::reset_zoom);
+
+        // Everything below changes the document
+        if canvas.read(cx).is_view_only(cx) {
+            return;
+        }
+        register_canvas_action(canvas, window, LunaCanvas::undo);
+        register_canvas_action(canvas, window, LunaCanvas::redo);
+        register_canvas_action(canvas, window, LunaCanvas::nudge_up);
+        register_canvas_action(canvas, window, LunaCanvas::nudge_down);
+        register_canvas_action(canvas, window, LunaCanvas::nudge_left);
+        register_canvas_action(canvas, window, LunaCanvas::nudge_right);
+        register_canvas_action(canvas, window, LunaCanvas::big_nudge_up);
+        register_canvas_action(canvas, window, LunaCanvas::big_nudge_down);
+        register_canvas_action(canvas, window, LunaCanvas::big_nudge_left);
+        register_canvas_action(canvas, window, LunaCanvas::big_nudge_right);
+        register_canvas_action(canvas, window, LunaCanvas::bring_to_front);
+        register_canvas_action(canvas, window, LunaCanvas::send_to_back);
+        register_canvas_action(canvas, window, LunaCanvas::raise_one);
+        register_canvas_action(canvas, window, LunaCanvas::lower_one);
+        register_canvas_action(canvas, window, LunaCanvas::group);
+        register_canvas_action(canvas, window, LunaCanvas::ungroup);
+        register_canvas_action(canvas, window, LunaCanvas::toggle_hidden);
+        register_canvas_action(canvas, window, LunaCanvas::toggle_locked);
     }
 
     // handle_mouse_down, etc
@@ -264,27 +490,28 @@ impl CanvasElement {
         // Convert window coordinate to canvas coordinate
         let canvas_point = canvas.window_to_canvas_point(window_point);
 
-        // Direct node testing with 1x1 selection point for hit detection
-        let select_point_bounds = Bounds {
-            origin: canvas_point,
-            size: Size::new(1.0, 1.0),
-        };
-
-        // Test each node to see if it contains this point
-        // Iterate in reverse order to match the painting order (last node is visually on top)
-        for node in canvas.nodes().iter().rev() {
-            let node_bounds = node.bounds();
-            if node_bounds.contains(&canvas_point) {
-                return Some(node.id());
-            }
-        }
+        // Hit testing walks the nodes in painting order, so the node on top wins
+        canvas.node_at(canvas_point, &[])
+    }
 
-        None
+    /// The frame nodes dropped at a canvas point would be moved into, paths can't hold
+    /// children
+    fn find_drop_parent(
+        canvas: &LunaCanvas,
+        canvas_point: Point<f32>,
+        excluding: &[NodeId],
+    ) -> Option<NodeId> {
+        canvas.node_at(canvas_point, excluding).filter(|node_id| {
+            canvas
+                .get_node(*node_id)
+                .is_some_and(|node| node.path.is_none())
+        })
     }
 
     fn handle_left_mouse_down(
         canvas: &mut LunaCanvas,
         event: &MouseDownEvent,
+        rulers: Option<Rulers>,
         window: &mut Window,
         cx: &mut Context<LunaCanvas>,
     ) {
@@ -295,49 +522,128 @@ impl CanvasElement {
         let position = event.position;
         let canvas_point = point(position.x.0, position.y.0);
 
+        // Guides are pulled out of the rulers with any tool, the rulers themselves are
+        // otherwise inert
+        if let Some(rulers) = rulers.filter(|rulers| rulers.contains(position)) {
+            if let Some(axis) = rulers
+                .guide_axis_at(position)
+                .filter(|_| !canvas.is_view_only(cx))
+            {
+                let guide = Guide::through(axis, canvas.window_to_canvas_point(canvas_point));
+                canvas.set_active_drag(ActiveDrag::new_guide(position, None, guide));
+                canvas.mark_dirty(cx);
+            }
+            cx.stop_propagation();
+            return;
+        }
+
         let active_tool = cx.active_tool().clone();
 
+        // Holding space borrows the hand tool for as long as it's held
+        if *active_tool == Tool::Hand || canvas.space_held() {
+            Self::begin_pan(canvas, position, cx);
+            return;
+        }
+
+        // A view only document can be selected in but not drawn on or dragged around
+        let view_only = canvas.is_view_only(cx);
+        if view_only && *active_tool != Tool::Selection {
+            return;
+        }
+
+        // Picking another tool finishes the path the pen was drawing
+        if *active_tool != Tool::Pen {
+            canvas.finish_pen_path(cx);
+        }
+
         match *active_tool {
             Tool::Selection => {
-                // First, check if we've clicked on a resize handle when only a single node is selected
-                if canvas.selected_nodes().len() == 1 {
-                    // Get the bounds of the selected node
-                    let selected_node_id = *canvas.selected_nodes().iter().next().unwrap();
-                    if let Some(node) = canvas.nodes().iter().find(|n| n.id() == selected_node_id) {
-                        let node_layout = node.layout();
-
-                        // Create node bounds to check for resize handle hits
-                        let node_bounds = Bounds {
-                            origin: Point::new(node_layout.x, node_layout.y),
-                            size: Size::new(node_layout.width, node_layout.height),
-                        };
+                // The anchors and handles of the edited path sit over everything else
+                let world_point = canvas.window_to_canvas_point(canvas_point);
+                let path_radius = PATH_HANDLE_RADIUS / canvas.zoom();
+                if let Some(handle) = canvas
+                    .path_handle_at(world_point, path_radius)
+                    .filter(|_| !view_only)
+                {
+                    canvas.begin_path_edit();
+                    canvas.set_active_drag(ActiveDrag::new_edit_path(position, handle));
+                    canvas.mark_dirty(cx);
+                    cx.stop_propagation();
+                    return;
+                }
 
-                        // Convert canvas point to world coordinates for hit detection
-                        let world_point = canvas.window_to_canvas_point(canvas_point);
-
-                        // Check if the point is within any resize handle
-                        if let Some(handle) = point_in_resize_handle(world_point, &node_bounds) {
-                            // Create a resize operation with the original node dimensions
-                            let resize_op = ResizeOperation::new(
-                                handle,
-                                node_layout.x,
-                                node_layout.y,
-                                node_layout.width,
-                                node_layout.height,
-                            );
-
-                            // Start a resize drag operation
-                            canvas.set_active_drag(ActiveDrag::new_resize(position, resize_op));
-                            canvas.mark_dirty(cx);
-                            cx.stop_propagation();
-                            return;
-                        }
+                // First, check if we've clicked on a corner of the selection to resize it,
+                // locked layers picked from the layer list and view only documents stay the
+                // size they are
+                let selection_locked = view_only
+                    || canvas
+                        .selected_nodes()
+                        .iter()
+                        .any(|node_id| canvas.is_layer_locked(*node_id));
+                if let Some(selection_bounds) =
+                    canvas.selection_bounds().filter(|_| !selection_locked)
+                {
+                    // Convert canvas point to world coordinates for hit detection
+                    let world_point = canvas.window_to_canvas_point(canvas_point);
+
+                    if let Some(handle) =
+                        point_in_resize_handle(world_point, &selection_bounds, canvas.zoom())
+                    {
+                        // Every selected subtree is scaled along with the selection
+                        let nodes = canvas
+                            .top_level_selection()
+                            .into_iter()
+                            .filter_map(|node_id| Some((node_id, canvas.absolute_bounds(node_id)?)))
+                            .collect();
+                        let resize_op = ResizeOperation::new(
+                            handle,
+                            selection_bounds.origin.x,
+                            selection_bounds.origin.y,
+                            selection_bounds.size.width,
+                            selection_bounds.size.height,
+                        )
+                        .with_nodes(nodes);
+
+                        // Start a resize drag operation
+                        canvas.begin_layout_change();
+                        canvas.set_active_drag(ActiveDrag::new_resize(position, resize_op));
+                        canvas.mark_dirty(cx);
+                        cx.stop_propagation();
+                        return;
                     }
                 }
 
+                // Guides lie over the nodes, so they're picked up before them
+                let guide_radius = GUIDE_GRAB_RADIUS / canvas.zoom();
+                if let Some(index) = canvas
+                    .guide_at(world_point, guide_radius)
+                    .filter(|_| !view_only)
+                {
+                    let guide = canvas.guides()[index];
+                    canvas.set_active_drag(ActiveDrag::new_guide(position, Some(index), guide));
+                    canvas.mark_dirty(cx);
+                    cx.stop_propagation();
+                    return;
+                }
+
                 // If we didn't hit a resize handle, proceed with normal selection behavior
                 // Attempt to find a node at the clicked point
-                if let Some(node_id) = Self::find_top_node_at_point(canvas, canvas_point, cx) {
+                let clicked_node = Self::find_top_node_at_point(canvas, canvas_point, cx);
+                if clicked_node != canvas.editing_path() {
+                    canvas.stop_editing_path(cx);
+                }
+                let clicked_path = clicked_node.filter(|node_id| {
+                    canvas
+                        .get_node(*node_id)
+                        .is_some_and(|node| node.path.is_some())
+                });
+                if let Some(node_id) = clicked_path.filter(|_| event.click_count == 2 && !view_only)
+                {
+                    // Double clicking a path shows its anchors and handles for dragging
+                    canvas.deselect_all_nodes(cx);
+                    canvas.select_node(node_id);
+                    canvas.edit_path(node_id, cx);
+                } else if let Some(node_id) = clicked_node {
                     // Check if we clicked on a node that's already selected
                     let already_selected = canvas.is_node_selected(node_id);
 
@@ -356,9 +662,10 @@ impl CanvasElement {
                     }
 
                     // If we clicked on a selected node, we should start dragging it
-                    if canvas.is_node_selected(node_id) {
+                    if canvas.is_node_selected(node_id) && !view_only {
                         // Save initial positions of all selected elements
                         canvas.save_selected_nodes_positions();
+                        canvas.begin_layout_change();
 
                         // Start a move elements drag operation
                         canvas.set_active_drag(ActiveDrag::new_move_elements(position));
@@ -387,15 +694,87 @@ impl CanvasElement {
                 canvas.set_active_element_draw((new_node_id, NodeType::Frame, active_drag));
                 canvas.mark_dirty(cx);
             }
+            Tool::Pen => {
+                let world_point =
+                    canvas.window_to_canvas_point(canvas.snap_window_point(canvas_point, cx));
+                let path_radius = PATH_HANDLE_RADIUS / canvas.zoom();
+                if event.click_count == 2 {
+                    // The first click of the double click placed the last anchor
+                    canvas.finish_pen_path(cx);
+                    cx.set_global(GlobalTool(Arc::new(Tool::Selection)));
+                } else if canvas.pen_path().is_none() {
+                    let app_state = canvas.app_state().read(cx);
+                    let fill = Some(app_state.current_background_color);
+                    let border_color = Some(app_state.current_border_color);
+                    canvas.begin_pen_path(world_point, fill, border_color, cx);
+                    canvas.set_active_drag(ActiveDrag::new_pen_handle(position));
+                } else if canvas.add_pen_anchor(world_point, path_radius, cx) {
+                    // Clicking the first anchor closed the path
+                    canvas.finish_pen_path(cx);
+                    cx.set_global(GlobalTool(Arc::new(Tool::Selection)));
+                } else {
+                    canvas.set_active_drag(ActiveDrag::new_pen_handle(position));
+                }
+                canvas.mark_dirty(cx);
+            }
+            Tool::Eyedropper => {
+                // Nodes without a fill have nothing to pick, the eyedropper stays out
+                let fill = Self::find_top_node_at_point(canvas, canvas_point, cx)
+                    .and_then(|node_id| canvas.get_node(node_id)?.fill());
+                if let Some(fill) = fill {
+                    canvas.pick_color(fill, cx);
+                    cx.set_global(GlobalTool(Arc::new(Tool::Selection)));
+                }
+            }
             _ => {}
         }
 
         cx.stop_propagation();
     }
 
+    /// Open the context menu for what was right-clicked
+    ///
+    /// A node outside the selection is selected on its own first, so the menu acts on
+    /// it. Right-clicking inside the selection keeps it, and empty canvas clears it.
+    fn handle_right_mouse_down(
+        canvas: &mut LunaCanvas,
+        event: &MouseDownEvent,
+        rulers: Option<Rulers>,
+        window: &mut Window,
+        cx: &mut Context<LunaCanvas>,
+    ) {
+        if window.default_prevented()
+            || rulers.is_some_and(|rulers| rulers.contains(event.position))
+            || canvas.active_drag().is_some()
+        {
+            return;
+        }
+
+        let position = point(event.position.x.0, event.position.y.0);
+        match Self::find_top_node_at_point(canvas, position, cx) {
+            Some(node_id) if canvas.is_node_selected(node_id) => {}
+            Some(node_id) => {
+                canvas.clear_selection(&ClearSelection, window, cx);
+                canvas.select_node(node_id);
+            }
+            None => canvas.clear_selection(&ClearSelection, window, cx),
+        }
+        canvas.mark_dirty(cx);
+        cx.stop_propagation();
+        window.dispatch_action(Box::new(ShowContextMenu), cx);
+    }
+
+    fn begin_pan(canvas: &mut LunaCanvas, position: Point<Pixels>, cx: &mut Context<LunaCanvas>) {
+        canvas.stop_viewport_animation();
+        let scroll_position = canvas.get_scroll_position();
+        canvas.set_active_drag(ActiveDrag::new_pan(position, scroll_position));
+        cx.stop_propagation();
+    }
+
     fn handle_left_mouse_up(
         canvas: &mut LunaCanvas,
         event: &MouseUpEvent,
+        rulers: Option<Rulers>,
         window: &mut Window,
         cx: &mut Context<LunaCanvas>,
     ) {
@@ -414,13 +793,25 @@ impl CanvasElement {
             match (node_type, active_tool) {
                 (NodeType::Frame, Tool::Frame) => {
                     // Calculate rectangle dimensions
-                    let start_pos = active_drag.start_position;
-                    let end_pos = active_drag.current_position;
+                    let start_pos = canvas.snap_window_point(
+                        point(
+                            active_drag.start_position.x.0,
+                            active_drag.start_position.y.0,
+                        ),
+                        cx,
+                    );
+                    let end_pos = canvas.snap_window_point(
+                        point(
+                            active_drag.current_position.x.0,
+                            active_drag.current_position.y.0,
+                        ),
+                        cx,
+                    );
 
-                    let min_x = start_pos.x.0.min(end_pos.x.0);
-                    let min_y = start_pos.y.0.min(end_pos.y.0);
-                    let width = (start_pos.x.0 - end_pos.x.0).abs();
-                    let height = (start_pos.y.0 - end_pos.y.0).abs();
+                    let min_x = start_pos.x.min(end_pos.x);
+                    let min_y = start_pos.y.min(end_pos.y);
+                    let width = (start_pos.x - end_pos.x).abs();
+                    let height = (start_pos.y - end_pos.y).abs();
 
                     // Only create a rectangle if it has meaningful dimensions
                     if width >= 2.0 && height >= 2.0 {
@@ -441,6 +832,7 @@ impl CanvasElement {
 
                         // Add the node to the canvas
                         let new_node_id = canvas.add_node(rect, None, cx);
+                        canvas.record_change(CanvasCommand::RemoveNodes(vec![new_node_id]));
 
                         // Clear any existing selection
                         canvas.deselect_all_nodes(cx);
@@ -480,12 +872,8 @@ impl CanvasElement {
                     }
 
                     // Get all the information we need from the potential parent before borrowing canvas mutably
-                    let parent_info = canvas
-                        .nodes()
-                        .iter()
-                        .rev() // Reverse to get top-to-bottom z-order
-                        .filter(|node| !selected_ids.contains(&node.id()))
-                        .find(|node| node.contains_point(&drop_point))
+                    let parent_info = Self::find_drop_parent(canvas, drop_point, &selected_ids)
+                        .and_then(|node_id| canvas.get_node(node_id))
                         .map(|parent_frame| ParentFrameInfo {
                             id: parent_frame.id(),
                             children: parent_frame.children().clone(),
@@ -537,7 +925,11 @@ impl CanvasElement {
                                         sg.get_scene_node_id(parent_info.id),
                                         sg.get_scene_node_id(node_id),
                                     ) {
-                                        // Update child bounds to be parent-relative
+                                        // Make child a child of parent in scene graph
+                                        sg.reparent(child_scene_id, parent_scene_id, usize::MAX);
+
+                                        // The layout was just made parent-relative, so it wins
+                                        // over what the scene graph worked out from the drag
                                         if let Some(child_node) = canvas.get_node(node_id) {
                                             let layout = child_node.layout();
                                             let bounds = Bounds {
@@ -546,9 +938,6 @@ impl CanvasElement {
                                             };
                                             sg.set_local_bounds(child_scene_id, bounds);
                                         }
-
-                                        // Make child a child of parent in scene graph
-                                        sg.add_child(parent_scene_id, child_scene_id);
                                     }
                                 });
                             }
@@ -569,9 +958,26 @@ impl CanvasElement {
                     // Finalize the resize operation - nothing special needed here
                     // The resize has already been applied to the node during drag
                 }
+                DragType::Pan { .. } => {
+                    // The viewport already moved along with the drag
+                }
+                DragType::PenHandle => {
+                    // The path goes on being drawn from the next click
+                }
+                DragType::EditPath(_) => {
+                    canvas.finish_path_edit();
+                }
+                DragType::Guide(drag) => {
+                    // Dropping a guide back on a ruler takes it away
+                    let remove = rulers.is_some_and(|rulers| rulers.contains(position));
+                    canvas.finish_guide_drag(&drag, remove, cx);
+                }
             }
         }
 
+        // A move or resize, including a drop into another frame, is undone as one change
+        canvas.finish_layout_change();
+
         // Reset the potential parent frame when drag ends
         canvas.set_potential_parent_frame(None);
         canvas.clear_active_drag();
@@ -634,6 +1040,7 @@ impl CanvasElement {
                             .iter()
                             .filter(|node| bounds_intersect(&selection_bounds, &node.bounds()))
                             .map(|node| node.id())
+                            .filter(|node_id| canvas.is_layer_selectable(*node_id))
                             .collect();
 
                         // Check if we want to add to existing selection (shift pressed)
@@ -669,13 +1076,8 @@ impl CanvasElement {
                             canvas.selected_nodes().iter().cloned().collect();
 
                         // Find potential parent frame at the current position
-                        let potential_parent = canvas
-                            .nodes()
-                            .iter()
-                            .rev() // Reverse to get top-to-bottom z-order
-                            .filter(|node| !selected_ids.contains(&node.id()))
-                            .find(|node| node.contains_point(&canvas_point))
-                            .map(|node| node.id());
+                        let potential_parent =
+                            Self::find_drop_parent(canvas, canvas_point, &selected_ids);
 
                         // Update the potential parent frame
                         canvas.set_potential_parent_frame(potential_parent);
@@ -688,290 +1090,54 @@ impl CanvasElement {
                     // Nothing to do here - handled in the rectangle drawing code below
                 }
                 DragType::Resize(mut resize_op) => {
-                    // Handle resize operation
-                    if canvas.selected_nodes().len() == 1 {
-                        // Get the zoom value before any mutable borrows
-                        let zoom = canvas.zoom();
-
-                        // Get the selected node
-                        let selected_node_id = *canvas.selected_nodes().iter().next().unwrap();
-                        if let Some(node) = canvas.get_node_mut(selected_node_id) {
-                            // Convert window delta to canvas delta
-                            let delta = Point::new(
-                                (position.x.0 - active_drag.start_position.x.0) / zoom,
-                                (position.y.0 - active_drag.start_position.y.0) / zoom,
-                            );
-
-                            // Check modifiers: shift for aspect ratio, option (alt) for resize from center
-                            let preserve_aspect_ratio = event.modifiers.shift;
-                            let resize_from_center = event.modifiers.alt;
-
-                            // Update resize config
-                            resize_op.config.preserve_aspect_ratio = preserve_aspect_ratio;
-                            resize_op.config.resize_from_center = resize_from_center;
-
-                            // Calculate new dimensions based on resize handle and modifiers
-                            let mut new_x = resize_op.original_x;
-                            let mut new_y = resize_op.original_y;
-                            let mut new_width = resize_op.original_width;
-                            let mut new_height = resize_op.original_height;
-
-                            // Calculate aspect ratio if needed
-                            let aspect_ratio = if preserve_aspect_ratio {
-                                resize_op.original_width / resize_op.original_height
-                            } else {
-                                0.0 // Not used when not preserving aspect ratio
-                            };
-
-                            // Adjust dimensions based on which handle is being dragged
-                            match resize_op.handle {
-                                ResizeHandle::TopLeft => {
-                                    // Width/height change is negative of delta for top-left
-                                    let width_delta = -delta.x;
-                                    let height_delta = -delta.y;
-
-                                    if preserve_aspect_ratio {
-                                        // Use whichever delta would make the shape larger
-                                        if width_delta.abs() / aspect_ratio > height_delta.abs() {
-                                            let adj_height = width_delta / aspect_ratio;
-                                            new_width = resize_op.original_width + width_delta;
-                                            new_height = resize_op.original_height + adj_height;
-                                            new_x = resize_op.original_x - width_delta;
-                                            new_y = resize_op.original_y - adj_height;
-                                        } else {
-                                            let adj_width = height_delta * aspect_ratio;
-                                            new_width = resize_op.original_width + adj_width;
-                                            new_height = resize_op.original_height + height_delta;
-                                            new_x = resize_op.original_x - adj_width;
-                                            new_y = resize_op.original_y - height_delta;
-                                        }
-                                    } else {
-                                        // Standard resize without aspect ratio constraint
-                                        new_width = resize_op.original_width + width_delta;
-                                        new_height = resize_op.original_height + height_delta;
-                                        new_x = resize_op.original_x - width_delta;
-                                        new_y = resize_op.original_y - height_delta;
-                                    }
-                                }
-                                ResizeHandle::TopRight => {
-                                    // Width change is positive, height change is negative
-                                    let width_delta = delta.x;
-                                    let height_delta = -delta.y;
-
-                                    if preserve_aspect_ratio {
-                                        if width_delta.abs() / aspect_ratio > height_delta.abs() {
-                                            let adj_height = width_delta / aspect_ratio;
-                                            new_width = resize_op.original_width + width_delta;
-                                            new_height = resize_op.original_height + adj_height;
-                                            new_y = resize_op.original_y - adj_height;
-                                        } else {
-                                            let adj_width = height_delta * aspect_ratio;
-                                            new_width = resize_op.original_width + adj_width;
-                                            new_height = resize_op.original_height + height_delta;
-                                            new_y = resize_op.original_y - height_delta;
-                                        }
-                                    } else {
-                                        new_width = resize_op.original_width + width_delta;
-                                        new_height = resize_op.original_height + height_delta;
-                                        new_y = resize_op.original_y - height_delta;
-                                    }
-                                }
-                                ResizeHandle::BottomLeft => {
-                                    // Width change is negative, height change is positive
-                                    let width_delta = -delta.x;
-                                    let height_delta = delta.y;
-
-                                    if preserve_aspect_ratio {
-                                        if width_delta.abs() / aspect_ratio > height_delta.abs() {
-                                            let adj_height = width_delta / aspect_ratio;
-                                            new_width = resize_op.original_width + width_delta;
-                                            new_height = resize_op.original_height + adj_height;
-                                            new_x = resize_op.original_x - width_delta;
-                                        } else {
-                                            let adj_width = height_delta * aspect_ratio;
-                                            new_width = resize_op.original_width + adj_width;
-                                            new_height = resize_op.original_height + height_delta;
-                                            new_x = resize_op.original_x - adj_width;
-                                        }
-                                    } else {
-                                        new_width = resize_op.original_width + width_delta;
-                                        new_height = resize_op.original_height + height_delta;
-                                        new_x = resize_op.original_x - width_delta;
-                                    }
-                                }
-                                ResizeHandle::BottomRight => {
-                                    let width_delta = delta.x;
-                                    let height_delta = delta.y;
-
-                                    if preserve_aspect_ratio {
-                                        if width_delta.abs() / aspect_ratio > height_delta.abs() {
-                                            let adj_height = width_delta / aspect_ratio;
-                                            new_width = resize_op.original_width + width_delta;
-                                            new_height = resize_op.original_height + adj_height;
-                                        } else {
-                                            let adj_width = height_delta * aspect_ratio;
-                                            new_width = resize_op.original_width + adj_width;
-                                            new_height = resize_op.original_height + height_delta;
-                                        }
-                                    } else {
-                                        new_width = resize_op.original_width + width_delta;
-                                        new_height = resize_op.original_height + height_delta;
-                                    }
-                                }
-                            }
-
-                            // If resize from center is enabled, adjust position to keep center fixed
-                            if resize_from_center {
-                                let orig_center_x =
-                                    resize_op.original_x + resize_op.original_width / 2.0;
-                                let orig_center_y =
-                                    resize_op.original_y + resize_op.original_height / 2.0;
-                                new_x = orig_center_x - new_width / 2.0;
-                                new_y = orig_center_y - new_height / 2.0;
-                            }
-
-                            // Calculate the correct position and dimensions for each handle type
-                            match resize_op.handle {
-                                ResizeHandle::TopLeft => {
-                                    // Handle horizontal resizing (left edge)
-                                    if new_width < 0.0 {
-                                        // Crossed right edge - fixed point switches to left
-                                        new_width = -new_width;
-                                        // Left edge is now at original right edge + the overflow
-                                        new_x = resize_op.original_x + resize_op.original_width;
-                                    } else {
-                                        // Normal case - right edge stays fixed
-                                        new_x = resize_op.original_x + resize_op.original_width
-                                            - new_width;
-                                    }
-
-                                    // Handle vertical resizing (top edge)
-                                    if new_height < 0.0 {
-                                        // Crossed bottom edge - fixed point switches to top
-                                        new_height = -new_height;
-                                        // Top edge is now at original bottom edge + the overflow
-                                        new_y = resize_op.original_y + resize_op.original_height;
-                                    } else {
-                                        // Normal case - bottom edge stays fixed
-                                        new_y = resize_op.original_y + resize_op.original_height
-                                            - new_height;
-                                    }
-                                }
-                                ResizeHandle::TopRight => {
-                                    // Handle horizontal resizing (right edge)
-                                    if new_width < 0.0 {
-                                        // Crossed left edge - fixed point switches to right
-                                        new_width = -new_width;
-                                        // Keep the original x, width grows to the left
-                                        new_x = resize_op.original_x - new_width;
-                                    } else {
-                                        // Normal case - left edge stays fixed at original x
-                                        new_x = resize_op.original_x;
-                                    }
-
-                                    // Handle vertical resizing (top edge)
-                                    if new_height < 0.0 {
-                                        // Crossed bottom edge - fixed point switches to top
-                                        new_height = -new_height;
-                                        // Top edge is now at original bottom edge + the overflow
-                                        new_y = resize_op.original_y + resize_op.original_height;
-                                    } else {
-                                        // Normal case - bottom edge stays fixed
-                                        new_y = resize_op.original_y + resize_op.original_height
-                                            - new_height;
-                                    }
-                                }
-                                ResizeHandle::BottomLeft => {
-                                    // Handle horizontal resizing (left edge)
-                                    if new_width < 0.0 {
-                                        // Crossed right edge - fixed point switches to left
-                                        new_width = -new_width;
-                                        // Left edge is now at original right edge + the overflow
-                                        new_x = resize_op.original_x + resize_op.original_width;
-                                    } else {
-                                        // Normal case - right edge stays fixed
-                                        new_x = resize_op.original_x + resize_op.original_width
-                                            - new_width;
-                                    }
-
-                                    // Handle vertical resizing (bottom edge)
-                                    if new_height < 0.0 {
-                                        // Crossed top edge - fixed point switches to bottom
-                                        new_height = -new_height;
-                                        // Keep original y, height grows upward
-                                        new_y = resize_op.original_y - new_height;
-                                    } else {
-                                        // Normal case - top edge stays fixed at original y
-                                        new_y = resize_op.original_y;
-                                    }
-                                }
-                                ResizeHandle::BottomRight => {
-                                    // Handle horizontal resizing (right edge)
-                                    if new_width < 0.0 {
-                                        // Crossed left edge - fixed point switches to right
-                                        new_width = -new_width;
-                                        // Keep the original x, width grows to the left
-                                        new_x = resize_op.original_x - new_width;
-                                    } else {
-                                        // Normal case - left edge stays fixed at original x
-                                        new_x = resize_op.original_x;
-                                    }
+                    // Convert window delta to canvas delta
+                    let zoom = canvas.zoom();
+                    let delta = Point::new(
+                        (position.x.0 - active_drag.start_position.x.0) / zoom,
+                        (position.y.0 - active_drag.start_position.y.0) / zoom,
+                    );
 
-                                    // Handle vertical resizing (bottom edge)
-                                    if new_height < 0.0 {
-                                        // Crossed top edge - fixed point switches to bottom
-                                        new_height = -new_height;
-                                        // Keep original y, height grows upward
-                                        new_y = resize_op.original_y - new_height;
-                                    } else {
-                                        // Normal case - top edge stays fixed at original y
-                                        new_y = resize_op.original_y;
-                                    }
-                                }
-                            }
+                    // Check modifiers: shift for aspect ratio, option (alt) for resize from center
+                    resize_op.config.preserve_aspect_ratio = event.modifiers.shift;
+                    resize_op.config.resize_from_center = event.modifiers.alt;
+                    canvas.resize_nodes(&resize_op, delta, cx);
 
-                            // Ensure minimum dimensions (very small but positive)
-                            if new_width > 0.1 && new_height > 0.1 {
-                                // Update node dimensions
-                                let layout = node.layout_mut();
-                                layout.x = new_x;
-                                layout.y = new_y;
-                                layout.width = new_width;
-                                layout.height = new_height;
-
-                                // Update scene graph
-                                if let Some(scene_node_id) = canvas
-                                    .scene_graph()
-                                    .update(cx, |sg, _cx| sg.get_scene_node_id(selected_node_id))
-                                {
-                                    canvas.scene_graph().update(cx, |sg, _cx| {
-                                        sg.set_local_bounds(
-                                            scene_node_id,
-                                            Bounds {
-                                                origin: Point::new(new_x, new_y),
-                                                size: Size::new(new_width, new_height),
-                                            },
-                                        );
-                                    });
-
-                                    // Update child node layouts to reflect parent's resize
-                                    canvas.update_child_layouts_after_parent_resize(
-                                        selected_node_id,
-                                        cx,
-                                    );
-                                }
-                            }
-
-                            // Update the resize operation in the drag
-                            let updated_drag = ActiveDrag {
-                                start_position: active_drag.start_position,
-                                current_position: position,
-                                drag_type: DragType::Resize(resize_op),
-                            };
-                            canvas.set_active_drag(updated_drag);
-                        }
+                    // Update the resize operation in the drag
+                    canvas.set_active_drag(ActiveDrag {
+                        start_position: active_drag.start_position,
+                        current_position: position,
+                        drag_type: DragType::Resize(resize_op),
+                    });
+                }
+                DragType::Pan { .. } => {
+                    if let Some(scroll_position) = new_drag.pan_scroll_position(canvas.zoom()) {
+                        canvas.set_scroll_position(scroll_position, cx);
+                    }
+                }
+                DragType::PenHandle => {
+                    // A click that wobbles a little still places a corner
+                    let start = active_drag.start_position;
+                    let moved = (position.x.0 - start.x.0)
+                        .abs()
+                        .max((position.y.0 - start.y.0).abs());
+                    if moved >= 2.0 {
+                        let handle = canvas.window_to_canvas_point(canvas_point);
+                        canvas.drag_pen_handle(handle, cx);
                     }
                 }
+                DragType::EditPath(handle) => {
+                    let to = canvas.snap_path_point(canvas_point, handle, cx);
+                    canvas.move_path_handle(handle, to, cx);
+                }
+                DragType::Guide(drag) => {
+                    let to =
+                        canvas.window_to_canvas_point(canvas.snap_window_point(canvas_point, cx));
+                    let guide = Guide::through(drag.guide.axis, to);
+                    canvas.set_active_drag(ActiveDrag {
+                        drag_type: DragType::Guide(GuideDrag { guide, ..drag }),
+                        ..new_drag
+                    });
+                }
             }
 
             canvas.mark_dirty(cx);
@@ -1003,11 +1169,25 @@ impl CanvasElement {
         let position = event.position;
         let canvas_point = point(position.x.0, position.y.0);
 
-        // Find node under cursor for hover effect
-        let hovered = Self::find_top_node_at_point(canvas, canvas_point, cx);
+        if canvas.pen_path().is_some() {
+            let world_point =
+                canvas.window_to_canvas_point(canvas.snap_window_point(canvas_point, cx));
+            canvas.set_pen_pointer(world_point, cx);
+        }
 
-        // Only update and redraw if hover state changed
-        if canvas.hovered_node() != hovered {
+        // Find node under cursor for hover effect, which low power mode goes without.
+        // Drags go without it too, it would cost a second hit test on every move on top
+        // of the one looking for a frame to drop into
+        let dragging = canvas.active_drag().is_some() || canvas.active_element_draw().is_some();
+        let hovered = if cx.low_power() || dragging {
+            None
+        } else {
+            Self::find_top_node_at_point(canvas, canvas_point, cx)
+        };
+
+        // Only update and redraw if hover state changed, or the hit test overlay has a
+        // new query to show
+        if canvas.hovered_node() != hovered || canvas.is_debugging_hit_tests() {
             canvas.set_hovered_node(hovered);
             canvas.mark_dirty(cx);
         }
@@ -1074,10 +1254,15 @@ impl CanvasElement {
         window: &mut Window,
         cx: &App,
     ) {
-        // Get the raw cursor positions directly from the drag event
-        // These are in absolute window coordinates where the mouse is positioned
-        let start_pos = active_drag.start_position;
-        let current_pos = active_drag.current_position;
+        // Get the cursor positions from the drag event, snapped to the grid when
+        // snapping is on. These are in absolute window coordinates
+        let canvas_read = self.canvas.read(cx);
+        let snap = |position: Point<Pixels>| {
+            let snapped = canvas_read.snap_window_point(point(position.x.0, position.y.0), cx);
+            point(px(snapped.x), px(snapped.y))
+        };
+        let start_pos = snap(active_drag.start_position);
+        let current_pos = snap(active_drag.current_position);
 
         // Calculate rectangle bounds in window coordinates
         // Don't round yet to avoid accumulating rounding errors
@@ -1095,7 +1280,6 @@ impl CanvasElement {
         };
 
         // Read canvas and app_state separately to avoid multiple borrows
-        let canvas_read = self.canvas.read(cx);
         let app_state_entity = canvas_read.app_state().clone();
 
         let app_state = app_state_entity.read(cx);
@@ -1109,6 +1293,95 @@ impl CanvasElement {
         window.request_animation_frame();
     }
 
+    /// Paint labels and badges that keep their size regardless of zoom.
+    ///
+    /// Drawn last so they stay legible on top of nodes and selection outlines.
+    fn paint_overlays(&self, layout: &CanvasLayout, window: &mut Window, cx: &mut App) {
+        let theme = cx.theme().clone();
+        let (overlays, zoom) = self.canvas.update(cx, |canvas, cx| {
+            let overlays = overlay::collect_overlays(canvas, cx)
+                .into_iter()
+                .map(|label| {
+                    let anchor = canvas.canvas_to_window_point(label.anchor);
+                    (label, anchor)
+                })
+                .collect::<Vec<_>>();
+            (overlays, canvas.zoom())
+        });
+
+        let font = window.text_style().font();
+        let font_size = px(LABEL_FONT_SIZE);
+        let shape = |window: &mut Window, text: SharedString, color: Hsla| {
+            let run = TextRun {
+                len: text.len(),
+                font: font.clone(),
+                color,
+                background_color: None,
+                underline: None,
+                strikethrough: None,
+            };
+            window
+                .text_system()
+                .shape_line(text, font_size, &[run])
+                .ok()
+        };
+
+        window.paint_layer(layout.hitbox.bounds, |window| {
+            for (label, anchor) in overlays {
+                let (text_color, background) = match label.kind {
+                    OverlayKind::FrameTitle { selected: true } => {
+                        (theme.tokens.active_border, None)
+                    }
+                    OverlayKind::FrameTitle { selected: false } => (theme.tokens.subtext0, None),
+                    OverlayKind::DimensionBadge => {
+                        (theme.tokens.background, Some(theme.tokens.active_border))
+                    }
+                };
+
+                let Some(mut line) = shape(window, label.text.clone(), text_color) else {
+                    continue;
+                };
+                if let Some(max_width) = label.max_width {
+                    let max_width = max_width * zoom;
+                    if line.width.0 > max_width {
+                        let Some(text) =
+                            overlay::truncate_to_width(&label.text, line.width.0, max_width)
+                        else {
+                            continue;
+                        };
+                        let Some(truncated) = shape(window, text.into(), text_color) else {
+                            continue;
+                        };
+                        line = truncated;
+                    }
+                }
+
+                let padding = if background.is_some() {
+                    LABEL_PADDING
+                } else {
+                    0.0
+                };
+                let bounds = label.layout(
+                    anchor,
+                    Size::new(line.width.0 + padding * 2.0, LABEL_HEIGHT),
+                );
+                let bounds = Bounds {
+                    origin: rounded_point(px(bounds.origin.x), px(bounds.origin.y)),
+                    size: Size::new(px(bounds.size.width), px(bounds.size.height)),
+                };
+                if !bounds.intersects(&layout.hitbox.bounds) {
+                    continue;
+                }
+
+                if let Some(background) = background {
+                    window.paint_quad(gpui::fill(bounds, background).corner_radii(px(2.)));
+                }
+                let text_origin = point(bounds.origin.x + px(padding), bounds.origin.y);
+                line.paint(text_origin, px(LABEL_HEIGHT), window, cx).ok();
+            }
+        });
+    }
+
     /// Paint the background layer of the canvas.
     ///
     /// Everything on this layer has the same draw order.
@@ -1123,6 +1396,466 @@ impl CanvasElement {
         });
     }
 
+    /// Paint the document's grid in screen space, under the nodes.
+    ///
+    /// Levels of the grid whose lines would sit closer than a few pixels apart are
+    /// skipped, minor lines first, so zooming out never turns the grid into a wash.
+    fn paint_grid(&self, layout: &CanvasLayout, window: &mut Window, cx: &mut App) {
+        let canvas = self.canvas.read(cx);
+        let grid = canvas.app_state().read(cx).document_settings.grid;
+        if !grid.visible || canvas.is_previewing() {
+            return;
+        }
+
+        let line_color = cx.theme().tokens.overlay0;
+        let zoom = canvas.zoom();
+        let bounds = layout.hitbox.bounds;
+        let top_left = canvas.window_to_canvas_point(point(bounds.origin.x.0, bounds.origin.y.0));
+        let bottom_right =
+            canvas.window_to_canvas_point(point(bounds.right().0, bounds.bottom().0));
+
+        // Minor lines aren't repeated where a major line will be drawn over them
+        let is_major = |position: f32| {
+            let nearest_major = (position / grid.spacing).round() * grid.spacing;
+            (position - nearest_major).abs() < grid.step() * 0.01
+        };
+        let mut levels = vec![(grid.spacing, line_color.opacity(0.35), false)];
+        if grid.subdivisions > 1 {
+            levels.insert(0, (grid.step(), line_color.opacity(0.15), true));
+        }
+
+        window.paint_layer(bounds, |window| {
+            for (step, color, minor) in levels {
+                if step * zoom < grid::MIN_LINE_GAP {
+                    continue;
+                }
+
+                for x in grid::line_positions(top_left.x, bottom_right.x, step) {
+                    if minor && is_major(x) {
+                        continue;
+                    }
+                    let window_x = canvas.canvas_to_window_point(point(x, 0.0)).x.round();
+                    let line = Bounds {
+                        origin: point(px(window_x), bounds.origin.y),
+                        size: Size::new(px(1.0), bounds.size.height),
+                    };
+                    window.paint_quad(gpui::fill(line, color));
+                }
+
+                for y in grid::line_positions(top_left.y, bottom_right.y, step) {
+                    if minor && is_major(y) {
+                        continue;
+                    }
+                    let window_y = canvas.canvas_to_window_point(point(0.0, y)).y.round();
+                    let line = Bounds {
+                        origin: point(bounds.origin.x, px(window_y)),
+                        size: Size::new(bounds.size.width, px(1.0)),
+                    };
+                    window.paint_quad(gpui::fill(line, color));
+                }
+            }
+        });
+    }
+
+    /// Paint the document's guides across the whole canvas, over the nodes. A guide
+    /// being dragged is drawn where it's been dragged to.
+    fn paint_guides(&self, layout: &CanvasLayout, window: &mut Window, cx: &mut App) {
+        let canvas = self.canvas.read(cx);
+        let dragged = canvas.active_drag().and_then(|drag| match drag.drag_type {
+            DragType::Guide(drag) => Some(drag),
+            _ => None,
+        });
+        if (canvas.guides().is_empty() && dragged.is_none()) || canvas.is_previewing() {
+            return;
+        }
+
+        let moved = dragged.as_ref().and_then(|drag| drag.index);
+        let guides = canvas
+            .guides()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| moved != Some(*index))
+            .map(|(_, guide)| *guide)
+            .chain(dragged.map(|drag| drag.guide));
+        let color = cx.theme().palette.pink;
+        let bounds = layout.hitbox.bounds;
+        window.paint_layer(bounds, |window| {
+            for guide in guides {
+                let line = match guide.axis {
+                    GuideAxis::Vertical => {
+                        let x = canvas.canvas_to_window_point(point(guide.position, 0.0)).x;
+                        Bounds {
+                            origin: point(px(x.round()), bounds.origin.y),
+                            size: Size::new(px(1.0), bounds.size.height),
+                        }
+                    }
+                    GuideAxis::Horizontal => {
+                        let y = canvas.canvas_to_window_point(point(0.0, guide.position)).y;
+                        Bounds {
+                            origin: point(bounds.origin.x, px(y.round())),
+                            size: Size::new(bounds.size.width, px(1.0)),
+                        }
+                    }
+                };
+                window.paint_quad(gpui::fill(line, color));
+            }
+        });
+    }
+
+    /// Paint the rulers over everything else on the canvas, counting canvas units at
+    /// the current zoom. Numbers on the left ruler run down it a character at a time.
+    fn paint_rulers(&self, layout: &CanvasLayout, window: &mut Window, cx: &mut App) {
+        let Some(rulers) = layout.rulers else {
+            return;
+        };
+
+        let theme = cx.theme().clone();
+        let canvas = self.canvas.read(cx);
+        let (step, parts) = ruler_step(canvas.zoom());
+        let tick_step = step / parts as f32;
+        let is_labelled = |position: f32| {
+            let nearest = (position / step).round() * step;
+            (position - nearest).abs() < tick_step * 0.01
+        };
+        let tick_length = |labelled: bool| {
+            if labelled {
+                RULER_SIZE
+            } else {
+                RULER_SIZE / 4.0
+            }
+        };
+
+        // Ticks start past the corner where the rulers meet
+        let top_left =
+            canvas.window_to_canvas_point(point(rulers.left.right().0, rulers.top.bottom().0));
+        let bottom_right =
+            canvas.window_to_canvas_point(point(rulers.top.right().0, rulers.left.bottom().0));
+        let columns = grid::line_positions(top_left.x, bottom_right.x, tick_step)
+            .map(|x| (x, canvas.canvas_to_window_point(point(x, 0.0)).x.round()))
+            .collect::<Vec<_>>();
+        let rows = grid::line_positions(top_left.y, bottom_right.y, tick_step)
+            .map(|y| (y, canvas.canvas_to_window_point(point(0.0, y)).y.round()))
+            .collect::<Vec<_>>();
+
+        let font = window.text_style().font();
+        let font_size = px(RULER_FONT_SIZE);
+        let line_height = px(RULER_FONT_SIZE + 1.0);
+        let shape = |window: &mut Window, text: SharedString| {
+            let run = TextRun {
+                len: text.len(),
+                font: font.clone(),
+                color: theme.tokens.subtext0,
+                background_color: None,
+                underline: None,
+                strikethrough: None,
+            };
+            window
+                .text_system()
+                .shape_line(text, font_size, &[run])
+                .ok()
+        };
+
+        window.paint_layer(layout.hitbox.bounds, |window| {
+            let background = theme.tokens.background_secondary;
+            let border = theme.tokens.inactive_border;
+            let tick = theme.tokens.subtext0.opacity(0.6);
+            for ruler in [rulers.top, rulers.left] {
+                window.paint_quad(gpui::fill(ruler, background));
+            }
+
+            let top_mask = ContentMask { bounds: rulers.top };
+            window.with_content_mask(Some(top_mask), |window| {
+                let bottom = rulers.top.bottom();
+                for &(x, window_x) in &columns {
+                    let labelled = is_labelled(x);
+                    let length = tick_length(labelled);
+                    window.paint_quad(gpui::fill(
+                        Bounds {
+                            origin: point(px(window_x), bottom - px(length)),
+                            size: Size::new(px(1.0), px(length)),
+                        },
+                        tick,
+                    ));
+                    if labelled {
+                        if let Some(line) = shape(window, ruler_label(x, step).into()) {
+                            let origin = point(px(window_x + 3.0), rulers.top.origin.y);
+                            line.paint(origin, line_height, window, cx).ok();
+                        }
+                    }
+                }
+            });
+
+            let left_mask = ContentMask {
+                bounds: rulers.left,
+            };
+            window.with_content_mask(Some(left_mask), |window| {
+                let right = rulers.left.right();
+                for &(y, window_y) in &rows {
+                    let labelled = is_labelled(y);
+                    let length = tick_length(labelled);
+                    window.paint_quad(gpui::fill(
+                        Bounds {
+                            origin: point(right - px(length), px(window_y)),
+                            size: Size::new(px(length), px(1.0)),
+                        },
+                        tick,
+                    ));
+                    if !labelled {
+                        continue;
+                    }
+                    let mut origin = point(rulers.left.origin.x + px(3.0), px(window_y + 2.));
+                    for character in ruler_label(y, step).chars() {
+                        if let Some(line) = shape(window, character.to_string().into()) {
+                            line.paint(origin, line_height, window, cx).ok();
+                        }
+                        origin.y += line_height;
+                    }
+                }
+            });
+
+            // Edges between the rulers and the canvas
+            window.paint_quad(gpui::fill(
+                Bounds {
+                    origin: point(rulers.top.origin.x, rulers.top.bottom()),
+                    size: Size::new(rulers.top.size.width, px(1.0)),
+                },
+                border,
+            ));
+            window.paint_quad(gpui::fill(
+                Bounds {
+                    origin: point(rulers.left.right(), rulers.left.origin.y),
+                    size: Size::new(px(1.0), rulers.left.size.height),
+                },
+                border,
+            ));
+        });
+    }
+
+    /// Show the anchors of the path being drawn or edited as squares, and the handles
+    /// steering its curves as dots joined to their anchor. The pen also gets a line to
+    /// where its next anchor would go.
+    fn paint_path_handles(&self, layout: &CanvasLayout, window: &mut Window, cx: &mut App) {
+        const ANCHOR_SIZE: f32 = 7.0;
+        const HANDLE_SIZE: f32 = 5.0;
+
+        let canvas = self.canvas.read(cx);
+        let path = canvas
+            .pen_path()
+            .or(canvas.editing_path())
+            .and_then(|node_id| canvas.absolute_path(node_id));
+        let Some(path) = path.filter(|_| !canvas.is_previewing()) else {
+            return;
+        };
+        let pen_segment = canvas
+            .pen_segment(cx)
+            .filter(|_| canvas.active_drag().is_none());
+
+        let theme = cx.theme();
+        let (color, background) = (theme.tokens.active_border, theme.tokens.background);
+        let to_window = |canvas_point: Point<f32>| {
+            let window_point = canvas.canvas_to_window_point(canvas_point);
+            point(px(window_point.x), px(window_point.y))
+        };
+        let centered = |center: Point<Pixels>, size: f32| Bounds {
+            origin: point(center.x - px(size / 2.0), center.y - px(size / 2.0)),
+            size: Size::new(px(size), px(size)),
+        };
+
+        window.paint_layer(layout.hitbox.bounds, |window| {
+            if let Some((from, to)) = pen_segment {
+                let mut line = PathBuilder::stroke(px(1.0));
+                line.move_to(to_window(from));
+                line.line_to(to_window(to));
+                if let Ok(line) = line.build() {
+                    window.paint_path(line, color.opacity(0.5));
+                }
+            }
+            for anchor in &path.anchors {
+                let position = to_window(anchor.position);
+                for handle in [anchor.handle_in, anchor.handle_out] {
+                    if handle == anchor.position {
+                        continue;
+                    }
+                    let handle = to_window(handle);
+                    let mut line = PathBuilder::stroke(px(1.0));
+                    line.move_to(position);
+                    line.line_to(handle);
+                    if let Ok(line) = line.build() {
+                        window.paint_path(line, color);
+                    }
+                    window.paint_quad(
+                        gpui::fill(centered(handle, HANDLE_SIZE), color)
+                            .corner_radii(px(HANDLE_SIZE / 2.0)),
+                    );
+                }
+                window.paint_quad(
+                    gpui::fill(centered(position, ANCHOR_SIZE), background)
+                        .border_widths(px(1.0))
+                        .border_color(color),
+                );
+            }
+        });
+    }
+
+    /// Mark how the document differs from the version it's compared with, over the
+    /// nodes: added nodes are outlined in green and changed ones in yellow, removed
+    /// ones are ghosted in red where they used to be.
+    fn paint_version_diff(&self, layout: &CanvasLayout, window: &mut Window, cx: &mut App) {
+        let canvas = self.canvas.read(cx);
+        let Some(diff) = canvas.version_diff() else {
+            return;
+        };
+        if canvas.is_previewing() {
+            return;
+        }
+
+        let palette = &cx.theme().palette;
+        let zoom = canvas.zoom();
+        let to_window = |bounds: Bounds<f32>| {
+            let origin = canvas.canvas_to_window_point(bounds.origin);
+            Bounds {
+                origin: point(px(origin.x), px(origin.y)),
+                size: Size::new(px(bounds.size.width * zoom), px(bounds.size.height * zoom)),
+            }
+        };
+
+        window.paint_layer(layout.hitbox.bounds, |window| {
+            for removed in &diff.removed {
+                let bounds = to_window(removed.bounds);
+                window.paint_quad(gpui::fill(bounds, palette.red.opacity(0.12)));
+                window.paint_quad(gpui::outline(bounds, palette.red, BorderStyle::Dashed));
+            }
+
+            let added = diff.added.iter().map(|node_id| (*node_id, palette.green));
+            let changed = diff
+                .changed
+                .iter()
+                .map(|change| (change.node_id, palette.yellow));
+            for (node_id, color) in added.chain(changed) {
+                if canvas.is_layer_hidden(node_id) {
+                    continue;
+                }
+                if let Some(bounds) = canvas.absolute_bounds(node_id) {
+                    let bounds = to_window(bounds);
+                    window.paint_quad(gpui::outline(bounds, color, BorderStyle::Solid));
+                }
+            }
+        });
+    }
+
+    /// Every node's bounds and what the last hit test checked, while debugging hit tests
+    ///
+    /// Nodes the test looked at are outlined green where the point fell inside them and
+    /// peach where it didn't, and the node it found is filled in.
+    fn paint_hit_test_debug(&self, layout: &CanvasLayout, window: &mut Window, cx: &mut App) {
+        let canvas = self.canvas.read(cx);
+        if !canvas.is_debugging_hit_tests() {
+            return;
+        }
+
+        let palette = cx.theme().palette.clone();
+        let zoom = canvas.zoom();
+        let to_window = |bounds: Bounds<f32>| {
+            let origin = canvas.canvas_to_window_point(bounds.origin);
+            Bounds {
+                origin: point(px(origin.x), px(origin.y)),
+                size: Size::new(px(bounds.size.width * zoom), px(bounds.size.height * zoom)),
+            }
+        };
+        let all_bounds = canvas
+            .nodes()
+            .iter()
+            .filter(|node| !canvas.is_layer_hidden(node.id()))
+            .filter_map(|node| canvas.absolute_bounds(node.id()))
+            .map(to_window)
+            .collect::<Vec<_>>();
+        let record = canvas.last_hit_test();
+        let tested = record
+            .iter()
+            .flat_map(|record| &record.tested)
+            .map(|(node_id, bounds, hit)| (*node_id, to_window(*bounds), *hit))
+            .collect::<Vec<_>>();
+        let query = record.as_ref().map(|record| {
+            let position = canvas.canvas_to_window_point(record.point);
+            let found = record
+                .result
+                .and_then(|node_id| canvas.get_node(node_id))
+                .map_or_else(|| "nothing".to_string(), |node| node.name());
+            let label = format!("{} tested, hit {found}", record.tested.len());
+            (point(px(position.x), px(position.y)), record.result, label)
+        });
+
+        let font_size = px(11.0);
+        let label = query.as_ref().and_then(|(_, _, label)| {
+            let text = SharedString::from(label.clone());
+            let run = TextRun {
+                len: text.len(),
+                font: window.text_style().font(),
+                color: palette.text,
+                background_color: None,
+                underline: None,
+                strikethrough: None,
+            };
+            window
+                .text_system()
+                .shape_line(text, font_size, &[run])
+                .ok()
+        });
+
+        window.paint_layer(layout.hitbox.bounds, |window| {
+            for bounds in all_bounds {
+                window.paint_quad(gpui::outline(
+                    bounds,
+                    palette.sky.opacity(0.5),
+                    BorderStyle::Solid,
+                ));
+            }
+
+            let Some((position, result, _)) = query else {
+                return;
+            };
+            for (node_id, bounds, hit) in tested {
+                if Some(node_id) == result {
+                    window.paint_quad(gpui::fill(bounds, palette.green.opacity(0.15)));
+                }
+                let color = if hit { palette.green } else { palette.peach };
+                window.paint_quad(gpui::outline(bounds, color, BorderStyle::Solid));
+            }
+
+            // A crosshair where the test looked, labelled with what it found
+            let arm = px(6.0);
+            window.paint_quad(gpui::fill(
+                Bounds {
+                    origin: point(position.x - arm, position.y),
+                    size: Size::new(arm * 2.0, px(1.0)),
+                },
+                palette.red,
+            ));
+            window.paint_quad(gpui::fill(
+                Bounds {
+                    origin: point(position.x, position.y - arm),
+                    size: Size::new(px(1.0), arm * 2.0),
+                },
+                palette.red,
+            ));
+            if let Some(line) = label {
+                let origin = point(position.x + arm + px(4.0), position.y + arm);
+                let background = Bounds {
+                    origin,
+                    size: Size::new(line.width + px(8.0), font_size + px(4.0)),
+                };
+                window.paint_quad(gpui::fill(background, palette.base.opacity(0.85)));
+                line.paint(
+                    point(origin.x + px(4.0), origin.y + px(2.0)),
+                    font_size,
+                    window,
+                    cx,
+                )
+                .ok();
+            }
+        });
+    }
+
     /// Register mouse listeners like click, hover and drag events.
     ///
     /// Despite not being visually "painted", mouse listeners are registered
@@ -1158,10 +1891,36 @@ impl CanvasElement {
                             }
                         };
 
+                        // Cmd or ctrl turns the wheel into zoom, which is also how some
+                        // platforms deliver a trackpad pinch
+                        if event.modifiers.platform || event.modifiers.control {
+                            canvas.stop_viewport_animation();
+                            let zoom = canvas.zoom() * (delta.y.0 * SCROLL_ZOOM_SPEED).exp();
+                            let cursor = point(event.position.x.0, event.position.y.0);
+                            canvas.zoom_around(zoom, cursor, cx);
+                            cx.stop_propagation();
+                            return;
+                        }
+
                         // Invert delta for natural feeling panning
                         let inverted_delta =
                             gpui::Point::new(gpui::Pixels(-delta.x.0), gpui::Pixels(-delta.y.0));
 
+                        // In preview, scroll frames take the wheel before the canvas does
+                        let canvas_point = canvas
+                            .window_to_canvas_point(point(event.position.x.0, event.position.y.0));
+                        let frame_delta = point(
+                            inverted_delta.x.0 / canvas.zoom(),
+                            inverted_delta.y.0 / canvas.zoom(),
+                        );
+                        if canvas.scroll_frame_at(canvas_point, frame_delta, cx) {
+                            cx.stop_propagation();
+                            return;
+                        }
+
+                        // Scrolling by hand takes over from any viewport transition
+                        canvas.stop_viewport_animation();
+
                         // Get current canvas position through getter
                         let current_position = canvas.get_scroll_position();
 
@@ -1181,19 +1940,39 @@ impl CanvasElement {
     }
 
     fn paint_mouse_listeners(&mut self, layout: &CanvasLayout, window: &mut Window, cx: &mut App) {
+        let rulers = layout.rulers;
         window.on_mouse_event({
             let canvas = self.canvas.clone();
             move |event: &MouseDownEvent, phase, window, cx| {
-                if phase == DispatchPhase::Bubble {
-                    match event.button {
-                        MouseButton::Left => canvas.update(cx, |canvas, cx| {
-                            Self::handle_left_mouse_down(canvas, event, window, cx);
-                        }),
-                        MouseButton::Right => canvas.update(cx, |canvas, cx| {
-                            // todo
-                        }),
-                        _ => {}
+                if phase != DispatchPhase::Bubble {
+                    return;
+                }
+
+                // Previewed prototypes follow links instead of editing
+                if canvas.read(cx).is_previewing() {
+                    if event.button == MouseButton::Left && !window.default_prevented() {
+                        let position = point(event.position.x.0, event.position.y.0);
+                        let canvas = canvas.read(cx);
+                        if let Some(link) = canvas.link_at(canvas.window_to_canvas_point(position))
+                        {
+                            cx.open_url(&link);
+                        }
                     }
+                    return;
+                }
+
+                match event.button {
+                    MouseButton::Left => canvas.update(cx, |canvas, cx| {
+                        Self::handle_left_mouse_down(canvas, event, rulers, window, cx);
+                    }),
+                    // The middle button pans with any tool
+                    MouseButton::Middle => canvas.update(cx, |canvas, cx| {
+                        Self::begin_pan(canvas, event.position, cx);
+                    }),
+                    MouseButton::Right => canvas.update(cx, |canvas, cx| {
+                        Self::handle_right_mouse_down(canvas, event, rulers, window, cx);
+                    }),
+                    _ => {}
                 }
             }
         });
@@ -1201,10 +1980,19 @@ impl CanvasElement {
         window.on_mouse_event({
             let canvas = self.canvas.clone();
             move |event: &MouseUpEvent, phase, window, cx| {
-                if phase == DispatchPhase::Bubble {
+                if phase == DispatchPhase::Bubble && !canvas.read(cx).is_previewing() {
                     match event.button {
                         MouseButton::Left => canvas.update(cx, |canvas, cx| {
-                            Self::handle_left_mouse_up(canvas, event, window, cx)
+                            Self::handle_left_mouse_up(canvas, event, rulers, window, cx)
+                        }),
+                        MouseButton::Middle => canvas.update(cx, |canvas, cx| {
+                            let panning = canvas
+                                .active_drag()
+                                .is_some_and(|drag| matches!(drag.drag_type, DragType::Pan { .. }));
+                            if panning {
+                                canvas.clear_active_drag();
+                                cx.stop_propagation();
+                            }
                         }),
                         MouseButton::Right => canvas.update(cx, |canvas, cx| {
                             // todo
@@ -1218,7 +2006,7 @@ impl CanvasElement {
         window.on_mouse_event({
             let canvas = self.canvas.clone();
             move |event: &MouseMoveEvent, phase, window, cx| {
-                if phase == DispatchPhase::Bubble {
+                if phase == DispatchPhase::Bubble && !canvas.read(cx).is_previewing() {
                     canvas.update(cx, |canvas, cx| {
                         if event.pressed_button == Some(MouseButton::Left)
                             || event.pressed_button == Some(MouseButton::Middle)
@@ -1245,9 +2033,91 @@ impl CanvasElement {
             fill_color: Option<Hsla>,
             border_color: Option<Hsla>,
             border_width: f32,
-            corner_radius: f32,
+            corner_radii: gpui::Corners<f32>,
             shadows: SmallVec<[Shadow; 1]>,
+            opacity: f32,
+            blend_mode: BlendMode,
             children: Vec<NodeId>,
+            /// How far the children are scrolled, in screen pixels
+            scroll_offset: gpui::Point<Pixels>,
+            /// Whether children are cut off at the node's bounds
+            clip_children: bool,
+            /// Drawn instead of the node's box, stretched over its bounds
+            path: Option<VectorPath>,
+        }
+
+        /// The pixel grid of an export of the top-level frame being painted, on screen
+        #[derive(Clone, Copy)]
+        struct PixelGrid {
+            /// Where the frame's origin, the corner of its first pixel, is on screen
+            origin: gpui::Point<Pixels>,
+            zoom: f32,
+            scale: ExportScale,
+        }
+
+        impl PixelGrid {
+            /// Screen bounds in canvas units, relative to the grid's origin
+            fn to_local(&self, bounds: gpui::Bounds<Pixels>) -> Bounds<f32> {
+                Bounds {
+                    origin: Point::new(
+                        (bounds.origin.x - self.origin.x).0 / self.zoom,
+                        (bounds.origin.y - self.origin.y).0 / self.zoom,
+                    ),
+                    size: Size::new(
+                        bounds.size.width.0 / self.zoom,
+                        bounds.size.height.0 / self.zoom,
+                    ),
+                }
+            }
+
+            /// Paint every export pixel `local` touches as a flat block, faded by how
+            /// much of it is covered
+            fn paint(&self, local: Bounds<f32>, color: Hsla, window: &mut Window) {
+                for cell in pixel_preview::rasterize(local, self.scale) {
+                    let bounds = gpui::Bounds {
+                        origin: gpui::Point::new(
+                            self.origin.x + px(cell.bounds.origin.x * self.zoom),
+                            self.origin.y + px(cell.bounds.origin.y * self.zoom),
+                        ),
+                        size: gpui::Size::new(
+                            px(cell.bounds.size.width * self.zoom),
+                            px(cell.bounds.size.height * self.zoom),
+                        ),
+                    };
+                    window.paint_quad(gpui::fill(bounds, color.opacity(cell.coverage)));
+                }
+            }
+
+            /// Paint a border `width` canvas units wide along the inside of `local`
+            fn paint_border(
+                &self,
+                local: Bounds<f32>,
+                width: f32,
+                color: Hsla,
+                window: &mut Window,
+            ) {
+                let Bounds { origin, size } = local;
+                let width = width.min(size.width / 2.0).min(size.height / 2.0);
+                let inner_height = size.height - width * 2.0;
+                let edges = [
+                    (origin, Size::new(size.width, width)),
+                    (
+                        Point::new(origin.x, origin.y + size.height - width),
+                        Size::new(size.width, width),
+                    ),
+                    (
+                        Point::new(origin.x, origin.y + width),
+                        Size::new(width, inner_height),
+                    ),
+                    (
+                        Point::new(origin.x + size.width - width, origin.y + width),
+                        Size::new(width, inner_height),
+                    ),
+                ];
+                for (origin, size) in edges {
+                    self.paint(Bounds { origin, size }, color, window);
+                }
+            }
         }
 
         // Helper function to organize nodes into a hierarchy
@@ -1289,59 +2159,150 @@ impl CanvasElement {
         }
 
         // Get all the data we need in one place
-        let (nodes_to_render, selected_node_ids, hovered_node, potential_parent_frame, active_drag) =
-            canvas.update(cx, |canvas, cx| {
-                let visible_nodes = canvas.visible_nodes(cx);
-                let scene_graph = canvas.scene_graph().read(cx);
-                let selected_nodes = canvas.selected_nodes().clone();
-                let theme = cx.theme().clone();
-                let hovered_node = canvas.hovered_node().clone();
-
-                // Collect all node rendering information into owned structures
-                let mut nodes_to_render = Vec::new();
-
-                for node in visible_nodes {
-                    let node_id = node.id();
-
-                    if let Some(scene_node_id) = scene_graph.get_scene_node_id(node_id) {
-                        if let Some(world_bounds) = scene_graph.get_world_bounds(scene_node_id) {
-                            nodes_to_render.push(NodeRenderInfo {
-                                node_id,
-                                bounds: gpui::Bounds {
-                                    origin: gpui::Point::new(
-                                        gpui::Pixels(world_bounds.origin.x),
-                                        gpui::Pixels(world_bounds.origin.y),
-                                    ),
-                                    size: gpui::Size::new(
-                                        gpui::Pixels(world_bounds.size.width),
-                                        gpui::Pixels(world_bounds.size.height),
-                                    ),
-                                },
-                                fill_color: node.fill(),
-                                border_color: node.border_color(),
-                                border_width: node.border_width(),
-                                corner_radius: node.corner_radius(),
-                                shadows: node.shadows(),
-                                children: node.children().clone(),
-                            });
-                        }
+        let (
+            nodes_to_render,
+            selected_node_ids,
+            highlighted_node_ids,
+            hovered_node,
+            potential_parent_frame,
+            active_drag,
+            pixel_scale,
+        ) = canvas.update(cx, |canvas, cx| {
+            let visible_nodes = canvas.visible_nodes(cx);
+            let scene_graph = canvas.scene_graph().read(cx);
+            let selected_nodes = canvas.selected_nodes().clone();
+            let theme = cx.theme().clone();
+            let hovered_node = canvas.hovered_node().clone();
+            let zoom = canvas.zoom();
+
+            // Collect all node rendering information into owned structures
+            let mut nodes_to_render = Vec::new();
+
+            for node in visible_nodes {
+                let node_id = node.id();
+                if canvas.is_layer_hidden(node_id) {
+                    continue;
+                }
+
+                if let Some(scene_node_id) = scene_graph.get_scene_node_id(node_id) {
+                    if let Some(world_bounds) = scene_graph.get_world_bounds(scene_node_id) {
+                        nodes_to_render.push(NodeRenderInfo {
+                            node_id,
+                            bounds: gpui::Bounds {
+                                origin: gpui::Point::new(
+                                    gpui::Pixels(world_bounds.origin.x),
+                                    gpui::Pixels(world_bounds.origin.y),
+                                ),
+                                size: gpui::Size::new(
+                                    gpui::Pixels(world_bounds.size.width),
+                                    gpui::Pixels(world_bounds.size.height),
+                                ),
+                            },
+                            fill_color: node.fill(),
+                            border_color: node.border_color(),
+                            border_width: node.border_width(),
+                            corner_radii: node.corner_radii(),
+                            // Shadows are cast by boxes, a path would sit on its box's shadow
+                            shadows: match node.path {
+                                Some(_) => SmallVec::new(),
+                                None => node.shadows(),
+                            },
+                            opacity: node.opacity(),
+                            blend_mode: node.blend_mode(),
+                            children: node.children().clone(),
+                            scroll_offset: gpui::Point::new(
+                                gpui::Pixels(node.scroll_offset.x * zoom),
+                                gpui::Pixels(node.scroll_offset.y * zoom),
+                            ),
+                            clip_children: true,
+                            path: node.path.clone(),
+                        });
                     }
                 }
+            }
 
-                (
-                    nodes_to_render,
-                    selected_nodes,
-                    hovered_node,
-                    canvas.potential_parent_frame(),
-                    canvas.active_drag(),
-                )
-            });
+            // Style resolution pass: render modes restyle the collected render
+            // info rather than the nodes, so switching modes never touches the
+            // document
+            match canvas.render_mode() {
+                RenderMode::Default | RenderMode::PixelPreview(_) => {}
+                RenderMode::Wireframe => {
+                    let outline_color = hsla(0.0, 0.0, 0.45, 1.0);
+                    for node_info in &mut nodes_to_render {
+                        // Compress fills into a light band so nesting still reads
+                        // as stacked tones without competing with the outlines
+                        node_info.fill_color = node_info.fill_color.map(|fill| {
+                            let gray = grayscale(fill);
+                            hsla(0.0, 0.0, 0.78 + gray.l * 0.18, fill.a)
+                        });
+                        node_info.border_color = Some(outline_color);
+                        node_info.border_width = node_info.border_width.max(1.0);
+                        node_info.shadows.clear();
+                        // Faded or blended nodes would hide the structure
+                        node_info.opacity = 1.0;
+                        node_info.blend_mode = BlendMode::Normal;
+                    }
+                }
+                RenderMode::Outline => {
+                    for node_info in &mut nodes_to_render {
+                        node_info.fill_color = None;
+                        node_info.border_color = Some(theme.tokens.text);
+                        node_info.border_width = 1.0;
+                        node_info.shadows.clear();
+                        node_info.opacity = 1.0;
+                        node_info.blend_mode = BlendMode::Normal;
+                        // Content a frame cuts off is part of what this mode is for
+                        node_info.clip_children = false;
+                    }
+                }
+            }
+
+            // Simulated OS settings apply on top of the render mode, as the real ones would
+            if canvas.simulates_increased_contrast() {
+                for node_info in &mut nodes_to_render {
+                    node_info.fill_color = node_info.fill_color.map(increase_contrast);
+                    node_info.border_color = node_info.border_color.map(increase_contrast);
+                    for shadow in &mut node_info.shadows {
+                        shadow.color = increase_contrast(shadow.color);
+                    }
+                }
+            }
+
+            // Previewing shows the design as it will be used, without editing chrome
+            let selected_nodes = if canvas.is_previewing() {
+                HashSet::new()
+            } else {
+                selected_nodes
+            };
+
+            (
+                nodes_to_render,
+                selected_nodes,
+                canvas.highlighted_nodes().clone(),
+                hovered_node,
+                canvas.potential_parent_frame(),
+                canvas.active_drag(),
+                match canvas.render_mode() {
+                    RenderMode::PixelPreview(scale) => Some((scale, zoom)),
+                    _ => None,
+                },
+            )
+        });
 
         window.paint_layer(layout.hitbox.bounds, |window| {
             // Organize nodes into a hierarchy
             let (root_nodes, children_map) = organize_nodes_hierarchically(&nodes_to_render);
 
             // Recursive function to paint a node and its children
+            //
+            // gpui has no group opacity or blend modes, so both are applied to the
+            // colors directly: `parent_opacity` is what the ancestors multiply down to,
+            // and `backdrop` is the parent's fill, the one color a blend mode can be
+            // worked out against. Nodes over their siblings or the bare canvas blend
+            // as if normal.
+            //
+            // With a `pixel_grid` the fill and border are painted as the pixels of an
+            // export instead, square cornered.
             fn paint_node_recursively(
                 node_info: &NodeRenderInfo,
                 children_map: &HashMap<NodeId, Vec<NodeRenderInfo>>,
@@ -1350,9 +2311,19 @@ impl CanvasElement {
                 potential_parent_frame: &Option<NodeId>,
                 has_active_drag: bool,
                 parent_transform: Option<TransformationMatrix>,
+                parent_opacity: f32,
+                backdrop: Option<Hsla>,
+                pixel_grid: Option<PixelGrid>,
                 theme: &Theme,
                 window: &mut gpui::Window,
             ) {
+                let opacity = parent_opacity * node_info.opacity;
+                let blend = |color: Hsla| match backdrop {
+                    Some(backdrop) => node_info.blend_mode.blend(backdrop, color),
+                    None => color,
+                };
+                let fill_color = node_info.fill_color.map(blend);
+
                 // Get coordinates in parent space
                 let (frame_x, frame_y) = (node_info.bounds.origin.x.0, node_info.bounds.origin.y.0);
                 let (frame_width, frame_height) = (
@@ -1387,20 +2358,23 @@ impl CanvasElement {
                 };
 
                 // Create a transformation matrix for children
-                // This creates a new coordinate system relative to this frame
+                // This creates a new coordinate system relative to this frame,
+                // shifted by the frame's scroll offset
                 let child_transform = TransformationMatrix::unit()
                     .compose(parent_transform.unwrap_or_else(TransformationMatrix::unit))
                     .translate(point(
-                        gpui::Pixels(frame_x).scale(1.0),
-                        gpui::Pixels(frame_y).scale(1.0),
+                        (gpui::Pixels(frame_x) - node_info.scroll_offset.x).scale(1.0),
+                        (gpui::Pixels(frame_y) - node_info.scroll_offset.y).scale(1.0),
                     ));
 
                 // FIRST: Paint any shadows behind the node
-                // Shadows need to be rendered before the node itself
-                if !node_info.shadows.is_empty() {
+                // Shadows need to be rendered before the node itself, inset ones are
+                // painted over the fill further down
+                let (inset_shadows, outer_shadows): (Vec<&Shadow>, Vec<&Shadow>) =
+                    node_info.shadows.iter().partition(|shadow| shadow.inset);
+                if !outer_shadows.is_empty() {
                     // Convert our Shadow types to gpui::BoxShadow types
-                    let box_shadows: Vec<gpui::BoxShadow> = node_info
-                        .shadows
+                    let box_shadows: Vec<gpui::BoxShadow> = outer_shadows
                         .iter()
                         .map(|shadow| gpui::BoxShadow {
                             offset: gpui::Point::new(
@@ -1409,38 +2383,63 @@ impl CanvasElement {
                             ),
                             blur_radius: gpui::Pixels(shadow.blur_radius),
                             spread_radius: gpui::Pixels(shadow.spread_radius),
-                            color: shadow.color,
+                            color: shadow.color.opacity(opacity),
                         })
                         .collect();
 
                     // Use the dedicated shadow rendering function
                     window.paint_shadows(
                         transformed_bounds,
-                        gpui::Corners::all(gpui::Pixels(node_info.corner_radius)),
+                        node_info.corner_radii.map(|radius| gpui::Pixels(*radius)),
                         &box_shadows,
                     );
                 }
 
                 // SECOND: Paint the node itself (background and frame)
-                // Paint the fill if it exists
-                if let Some(fill_color) = node_info.fill_color {
+                // Paint the fill if it exists, only a closed path has an inside to fill
+                if let Some(path) = &node_info.path {
+                    if let Some(fill_color) = fill_color.filter(|_| path.closed) {
+                        paint_vector_path(
+                            path,
+                            transformed_bounds,
+                            PathBuilder::fill(),
+                            fill_color.opacity(opacity),
+                            window,
+                        );
+                    }
+                } else if let (Some(fill_color), Some(grid)) = (fill_color, pixel_grid) {
+                    grid.paint(
+                        grid.to_local(transformed_bounds),
+                        fill_color.opacity(opacity),
+                        window,
+                    );
+                } else if let Some(fill_color) = fill_color {
                     window.paint_quad(gpui::PaintQuad {
                         bounds: transformed_bounds,
-                        corner_radii: (node_info.corner_radius).into(),
-                        background: fill_color.into(),
+                        corner_radii: node_info.corner_radii.map(|radius| gpui::Pixels(*radius)),
+                        background: fill_color.opacity(opacity).into(),
                         border_widths: (0.).into(),
                         border_color: gpui::transparent_black().into(),
                         border_style: BorderStyle::Solid,
                     });
                 }
 
+                for shadow in inset_shadows {
+                    paint_inset_shadow(
+                        transformed_bounds,
+                        shadow,
+                        shadow.color.opacity(opacity),
+                        window,
+                    );
+                }
+
                 // SECOND: Paint all children (if any) with clipping and proper transformation
                 // We paint children AFTER the parent's fill but BEFORE the parent's border
                 // This ensures children appear on top of the parent's background
                 if let Some(children) = children_map.get(&node_info.node_id) {
                     // Create a mask for children to clip them to the frame bounds
                     window.with_content_mask(
-                        Some(ContentMask {
+                        node_info.clip_children.then_some(ContentMask {
                             bounds: transformed_bounds,
                         }),
                         |window| {
@@ -1453,6 +2452,9 @@ impl CanvasElement {
                                     potential_parent_frame,
                                     has_active_drag,
                                     Some(child_transform),
+                                    opacity,
+                                    fill_color.or(backdrop),
+                                    pixel_grid,
                                     theme,
                                     window,
                                 );
@@ -1462,10 +2464,30 @@ impl CanvasElement {
                 }
 
                 // THIRD: Paint the border if it exists (after children, so it's on top)
-                if let Some(border_color) = node_info.border_color {
+                let border_color = node_info
+                    .border_color
+                    .map(|border_color| blend(border_color).opacity(opacity));
+                if let (Some(border_color), Some(path)) = (border_color, &node_info.path) {
+                    if node_info.border_width > 0.0 {
+                        paint_vector_path(
+                            path,
+                            transformed_bounds,
+                            PathBuilder::stroke(px(node_info.border_width)),
+                            border_color,
+                            window,
+                        );
+                    }
+                } else if let (Some(border_color), Some(grid)) = (border_color, pixel_grid) {
+                    grid.paint_border(
+                        grid.to_local(transformed_bounds),
+                        node_info.border_width,
+                        border_color,
+                        window,
+                    );
+                } else if let Some(border_color) = border_color {
                     window.paint_quad(gpui::PaintQuad {
                         bounds: transformed_bounds,
-                        corner_radii: (node_info.corner_radius).into(),
+                        corner_radii: node_info.corner_radii.map(|radius| gpui::Pixels(*radius)),
                         background: gpui::transparent_black().into(),
                         border_widths: (node_info.border_width).into(),
                         border_color: border_color.into(),
@@ -1551,6 +2573,14 @@ impl CanvasElement {
                     &potential_parent_frame,
                     has_active_drag,
                     None, // No parent transform for root nodes
+                    1.0,
+                    None,
+                    // Each top-level frame is exported on its own, pixels counted from its corner
+                    pixel_scale.map(|(scale, zoom)| PixelGrid {
+                        origin: node_info.bounds.origin,
+                        zoom,
+                        scale,
+                    }),
                     &theme,
                     window,
                 );
@@ -1653,13 +2683,22 @@ impl CanvasElement {
                         ),
                     };
 
-                    // Reduce outline opacity to 20% when multiple elements are selected
-                    let selection_color = if selected_node_ids.len() > 1 {
+                    // Reduce outline opacity to 20% when multiple elements are selected,
+                    // except for the ones the hovered inspector field would change
+                    let highlighted = highlighted_node_ids.contains(&node_info.node_id);
+                    let selection_color = if selected_node_ids.len() > 1 && !highlighted {
                         theme.tokens.active_border.opacity(0.2)
                     } else {
                         theme.tokens.active_border
                     };
 
+                    if highlighted {
+                        window.paint_quad(gpui::fill(
+                            selection_bounds,
+                            theme.tokens.active_border.opacity(0.08),
+                        ));
+                    }
+
                     window.paint_quad(gpui::outline(
                         selection_bounds,
                         selection_color,
@@ -1668,56 +2707,7 @@ impl CanvasElement {
 
                     // Only draw resize handles if this is the only selected node
                     if selected_node_ids.len() == 1 {
-                        const HANDLE_SIZE: f32 = 7.0;
-                        const HALF_HANDLE: f32 = HANDLE_SIZE / 2.0;
-
-                        // Center handles on the selection outline
-                        let corners = [
-                            // Top-left
-                            (
-                                selection_bounds.origin.x - gpui::Pixels(HALF_HANDLE - 0.5),
-                                selection_bounds.origin.y - gpui::Pixels(HALF_HANDLE - 0.5),
-                            ),
-                            // Top-right
-                            (
-                                selection_bounds.origin.x + selection_bounds.size.width
-                                    - gpui::Pixels(HALF_HANDLE + 0.5),
-                                selection_bounds.origin.y - gpui::Pixels(HALF_HANDLE - 0.5),
-                            ),
-                            // Bottom-left
-                            (
-                                selection_bounds.origin.x - gpui::Pixels(HALF_HANDLE - 0.5),
-                                selection_bounds.origin.y + selection_bounds.size.height
-                                    - gpui::Pixels(HALF_HANDLE + 0.5),
-                            ),
-                            // Bottom-right
-                            (
-                                selection_bounds.origin.x + selection_bounds.size.width
-                                    - gpui::Pixels(HALF_HANDLE + 0.5),
-                                selection_bounds.origin.y + selection_bounds.size.height
-                                    - gpui::Pixels(HALF_HANDLE + 0.5),
-                            ),
-                        ];
-
-                        for (x, y) in corners {
-                            let handle_bounds = gpui::Bounds {
-                                origin: gpui::Point::new(x, y),
-                                size: gpui::Size::new(
-                                    gpui::Pixels(HANDLE_SIZE),
-                                    gpui::Pixels(HANDLE_SIZE),
-                                ),
-                            };
-
-                            window.paint_quad(gpui::fill(
-                                handle_bounds,
-                                gpui::hsla(0.0, 0.0, 1.0, 1.0),
-                            ));
-                            window.paint_quad(gpui::outline(
-                                handle_bounds,
-                                selection_color,
-                                BorderStyle::Solid,
-                            ));
-                        }
+                        paint_corner_handles(selection_bounds, selection_color, window);
                     }
                 }
             }
@@ -1762,10 +2752,21 @@ impl CanvasElement {
                         theme.tokens.active_border,
                         BorderStyle::Solid,
                     ));
+                    // A group is resized as a whole from the corners of its outline
+                    paint_corner_handles(
+                        group_selection_bounds,
+                        theme.tokens.active_border,
+                        window,
+                    );
                 }
             }
 
-            window.request_animation_frame();
+            if cx.low_power() {
+                self.canvas
+                    .update(cx, |canvas, cx| canvas.schedule_idle_refresh(cx));
+            } else {
+                window.request_animation_frame();
+            }
         });
     }
 }
@@ -1811,14 +2812,25 @@ impl Element for CanvasElement {
         window: &mut gpui::Window,
         cx: &mut gpui::App,
     ) -> Self::PrepaintState {
+        // Advance a viewport transition before anything reads the canvas transform
+        let animating = self.canvas.update(cx, |canvas, cx| {
+            canvas.step_viewport_animation(Instant::now(), cx)
+        });
+        if animating {
+            window.request_animation_frame();
+        }
+
         // set up canvas styles
         let text_style = TextStyleRefinement {
             font_size: Some(self.style.text.font_size),
             line_height: Some(self.style.text.line_height),
             ..Default::default()
         };
-        // let focus_handle = self.focus_handle(cx);
-        // window.set_focus_handle(&focus_handle, cx);
+        // The canvas shares the workspace's focus handle and claims it after the
+        // workspace div does, so the actions it registers while painting sit on the
+        // focus path, with the workspace's own actions still reachable above it
+        let focus_handle = self.canvas.read(cx).focus_handle(cx);
+        window.set_focus_handle(&focus_handle, cx);
 
         window.with_text_style(Some(text_style), |window| {
             window.with_content_mask(Some(ContentMask { bounds }), |window| {
@@ -1838,7 +2850,13 @@ impl Element for CanvasElement {
                     // dragging goes in here
                 }
 
-                CanvasLayout { hitbox }
+                let rulers = {
+                    let canvas = self.canvas.read(cx);
+                    let shown = canvas.app_state().read(cx).show_rulers;
+                    (shown && !canvas.is_previewing()).then(|| Rulers::new(bounds, self.left_inset))
+                };
+
+                CanvasLayout { hitbox, rulers }
             })
         })
     }
@@ -1858,6 +2876,9 @@ impl Element for CanvasElement {
         let key_context = self.canvas.update(cx, |canvas, cx| canvas.key_context());
 
         window.set_key_context(key_context);
+        // The canvas paints straight into the workspace, so its actions are heard
+        // wherever focus is in the window
+        self.register_actions(window, cx);
 
         let text_style = TextStyleRefinement {
             font_size: Some(self.style.text.font_size),
@@ -1872,7 +2893,12 @@ impl Element for CanvasElement {
                 self.paint_mouse_listeners(layout, window, cx);
                 self.paint_scroll_wheel_listener(layout, window, cx);
                 self.paint_canvas_background(layout, window, cx);
+                self.paint_grid(layout, window, cx);
                 self.paint_nodes(layout, window, cx);
+                self.paint_path_handles(layout, window, cx);
+                self.paint_guides(layout, window, cx);
+                self.paint_version_diff(layout, window, cx);
+                self.paint_hit_test_debug(layout, window, cx);
 
                 // Read canvas once to get all needed data
                 let canvas_read = canvas_clone.read(cx);
@@ -1893,6 +2919,9 @@ impl Element for CanvasElement {
                         _ => {}
                     }
                 }
+
+                self.paint_overlays(layout, window, cx);
+                self.paint_rulers(layout, window, cx);
             });
         })
     }
@@ -0,0 +1,88 @@
+//! # Node Clipboard
+//!
+//! Copying keeps full copies of the selected subtrees in memory, each top-level copy
+//! with its absolute layout so it can be pasted regardless of where it came from.
+//! Pasting instantiates the copies again under fresh ids, with children remapped to
+//! match, so the same clipboard can be pasted any number of times.
+//!
+//! Every paste after a copy lands a little further down and to the right, leaving the
+//! previous one visible underneath. Cut nodes are gone from the canvas, so the first
+//! paste after a cut puts them back exactly where they were.
+
+use crate::node::{
+    frame::{instantiate, FrameNode},
+    NodeId,
+};
+use gpui::point;
+
+/// Distance between a pasted or duplicated copy and the nodes it was copied from
+pub const PASTE_OFFSET: f32 = 10.0;
+
+#[derive(Debug, Clone, Default)]
+pub struct NodeClipboard {
+    /// Copied subtrees in document order, top-level nodes with absolute layouts
+    nodes: Vec<FrameNode>,
+    /// Offset steps the next paste is shifted by
+    next_step: usize,
+}
+
+impl NodeClipboard {
+    pub fn copied(nodes: Vec<FrameNode>) -> Self {
+        Self {
+            nodes,
+            next_step: 1,
+        }
+    }
+
+    pub fn cut(nodes: Vec<FrameNode>) -> Self {
+        Self {
+            nodes,
+            next_step: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Copies of the clipboard contents under ids from `next_id`, one step further
+    /// along than the previous paste
+    pub fn paste(&mut self, next_id: impl FnMut() -> NodeId) -> Vec<FrameNode> {
+        let distance = PASTE_OFFSET * self.next_step as f32;
+        self.next_step += 1;
+        instantiate(&self.nodes, point(distance, distance), next_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids_from(first: usize) -> impl FnMut() -> NodeId {
+        let mut next = first;
+        move || {
+            next += 1;
+            NodeId::new(next - 1)
+        }
+    }
+
+    fn frames() -> Vec<FrameNode> {
+        let mut card = FrameNode::with_rect(NodeId::new(1), 100.0, 100.0, 200.0, 120.0);
+        card.children = vec![NodeId::new(2)];
+        let title = FrameNode::with_rect(NodeId::new(2), 16.0, 16.0, 80.0, 20.0);
+        vec![card, title]
+    }
+
+    #[test]
+    fn test_repeated_pastes_cascade() {
+        let mut next_id = ids_from(10);
+        let mut clipboard = NodeClipboard::copied(frames());
+        assert_eq!(clipboard.paste(&mut next_id)[0].layout.x, 110.0);
+        assert_eq!(clipboard.paste(&mut next_id)[0].layout.x, 120.0);
+
+        let mut clipboard = NodeClipboard::cut(frames());
+        assert_eq!(clipboard.paste(&mut next_id)[0].layout.x, 100.0);
+        assert_eq!(clipboard.paste(&mut next_id)[0].layout.x, 110.0);
+        assert!(NodeClipboard::default().is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+//! # Fuzzy Matching
+//!
+//! Subsequence matching for finders such as quick-open. A candidate matches when every
+//! character of the query appears in it in order, ignoring case. Among the possible
+//! alignments the best scoring one is kept, where matches on word starts and runs of
+//! consecutive characters are worth more and gaps between matches cost a little. That is
+//! what lets "hd" rank "Header Dark" above "Shadow".
+
+const SCORE_MATCH: i32 = 16;
+const BONUS_WORD_START: i32 = 12;
+const BONUS_CONSECUTIVE: i32 = 12;
+const PENALTY_GAP_START: i32 = 3;
+const PENALTY_GAP_EXTENSION: i32 = 1;
+
+/// A candidate that matched the query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Index of the candidate in the list that was searched
+    pub candidate_index: usize,
+    pub score: i32,
+    /// Char indices of the matched characters within the candidate, for highlighting
+    pub positions: Vec<usize>,
+}
+
+/// Score a single candidate, `None` if the query is not a subsequence of it
+pub fn score(query: &str, candidate: &str) -> Option<(i32, Vec<usize>)> {
+    let query: Vec<char> = query.chars().map(fold_case).collect();
+    let chars: Vec<char> = candidate.chars().collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    if query.len() > chars.len() {
+        return None;
+    }
+
+    let bonus = |index: usize| {
+        let is_word_start = match index.checked_sub(1).map(|prev| chars[prev]) {
+            None => true,
+            Some(prev) => {
+                !prev.is_alphanumeric() || (prev.is_lowercase() && chars[index].is_uppercase())
+            }
+        };
+        if is_word_start {
+            BONUS_WORD_START
+        } else {
+            0
+        }
+    };
+
+    // best[i][j]: best score with query[i] matched at chars[j], with a back pointer to
+    // where query[i - 1] was matched. Names are short, so the quadratic inner scan is fine.
+    let mut best: Vec<Vec<Option<(i32, usize)>>> = vec![vec![None; chars.len()]; query.len()];
+
+    for (i, &query_char) in query.iter().enumerate() {
+        for j in i..chars.len() {
+            if fold_case(chars[j]) != query_char {
+                continue;
+            }
+
+            let base = SCORE_MATCH + bonus(j);
+            best[i][j] = if i == 0 {
+                Some((base - PENALTY_GAP_EXTENSION * j as i32, 0))
+            } else {
+                (i - 1..j)
+                    .filter_map(|k| {
+                        let (prev_score, _) = best[i - 1][k]?;
+                        let transition = if k + 1 == j {
+                            BONUS_CONSECUTIVE
+                        } else {
+                            -PENALTY_GAP_START - PENALTY_GAP_EXTENSION * (j - k - 2) as i32
+                        };
+                        Some((prev_score + base + transition, k))
+                    })
+                    .max_by_key(|&(score, k)| (score, std::cmp::Reverse(k)))
+            };
+        }
+    }
+
+    let last = query.len() - 1;
+    let (mut end, (total, _)) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, entry)| entry.map(|entry| (j, entry)))
+        .max_by_key(|&(j, (score, _))| (score, std::cmp::Reverse(j)))?;
+
+    let mut positions = vec![0; query.len()];
+    for i in (0..query.len()).rev() {
+        positions[i] = end;
+        end = best[i][end].map(|(_, prev)| prev).unwrap_or_default();
+    }
+
+    Some((total, positions))
+}
+
+/// Match a query against every candidate, best matches first
+///
+/// Equal scores keep the candidates' original order, and an empty query matches
+/// everything in order.
+pub fn match_candidates<'a>(
+    query: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<FuzzyMatch> {
+    let query = query.trim();
+    let mut matches: Vec<FuzzyMatch> = candidates
+        .into_iter()
+        .enumerate()
+        .filter_map(|(candidate_index, candidate)| {
+            let (score, positions) = score(query, candidate)?;
+            Some(FuzzyMatch {
+                candidate_index,
+                score,
+                positions,
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| b.score.cmp(&a.score));
+    matches
+}
+
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_must_be_a_subsequence() {
+        assert!(score("hdr", "Header").is_some());
+        assert!(score("rdh", "Header").is_none());
+        assert!(score("headers", "Header").is_none());
+    }
+
+    #[test]
+    fn test_matching_ignores_case() {
+        let (_, positions) = score("HEAD", "header").unwrap();
+        assert_eq!(positions, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_prefers_word_starts() {
+        let (_, positions) = score("hd", "Header Dark").unwrap();
+        assert_eq!(positions, vec![0, 7]);
+
+        let (_, positions) = score("nb", "navBar").unwrap();
+        assert_eq!(positions, vec![0, 3]);
+    }
+
+    #[test]
+    fn test_ranks_better_matches_first() {
+        let candidates = ["Shadow", "Header Dark", "Card"];
+        let matches = match_candidates("hd", candidates);
+
+        let order: Vec<_> = matches
+            .iter()
+            .map(|m| candidates[m.candidate_index])
+            .collect();
+        assert_eq!(order, vec!["Header Dark", "Shadow"]);
+    }
+
+    #[test]
+    fn test_consecutive_runs_beat_scattered_characters() {
+        let (contiguous, _) = score("card", "Card").unwrap();
+        let (scattered, _) = score("card", "Clear a red dot").unwrap();
+        assert!(contiguous > scattered);
+    }
+
+    #[test]
+    fn test_empty_query_keeps_order() {
+        let matches = match_candidates("  ", ["Frame 2", "Frame 1"]);
+        let indices: Vec<_> = matches.iter().map(|m| m.candidate_index).collect();
+        assert_eq!(indices, vec![0, 1]);
+    }
+}
@@ -0,0 +1,267 @@
+//! # Undo History
+//!
+//! Every change made to the document through the canvas is recorded as the command
+//! that reverts it. Applying a command hands back its own inverse, so undoing a change
+//! yields the command that redoes it and the other way around. The canvas only has to
+//! know how to apply each kind of command, the stacks here never look inside them.
+//!
+//! Changes to how the document is viewed — selection, scroll, zoom, collapsed layers —
+//! aren't part of the history.
+
+use crate::{
+    guides::Guide,
+    layout::{AutoLayout, Pins},
+    library::LibraryLink,
+    node::{
+        frame::{FrameNode, NodeStyle},
+        path::VectorPath,
+        NodeId, NodeLayout,
+    },
+};
+
+/// Most changes kept for undo, the oldest are dropped first
+pub const HISTORY_LIMIT: usize = 200;
+
+/// A removed node and where it sat in the canvas node list
+#[derive(Debug, Clone)]
+pub struct RemovedNode {
+    pub node: FrameNode,
+    pub index: usize,
+}
+
+/// A change to the document that the canvas knows how to apply and invert
+#[derive(Debug, Clone)]
+pub enum CanvasCommand {
+    /// Put a removed subtree back where it was
+    ///
+    /// `nodes` holds the root and all of its descendants ordered by their index in the
+    /// node list, `parent` the root's parent and its index among the parent's children.
+    InsertNodes {
+        root: NodeId,
+        nodes: Vec<RemovedNode>,
+        parent: Option<(NodeId, usize)>,
+    },
+    /// Remove nodes along with their descendants
+    RemoveNodes(Vec<NodeId>),
+    /// Move and resize nodes, layouts are relative to each node's parent
+    SetLayouts(Vec<(NodeId, NodeLayout)>),
+    SetStyles(Vec<(NodeId, NodeStyle)>),
+    SetNames(Vec<(NodeId, Option<String>)>),
+    SetLinks(Vec<(NodeId, Option<String>)>),
+    /// Link nodes to the library components they're instances of
+    SetComponents(Vec<(NodeId, Option<LibraryLink>)>),
+    SetHidden(Vec<(NodeId, bool)>),
+    SetLocked(Vec<(NodeId, bool)>),
+    /// Leave nodes out of what dragged layers snap to, or let them be snapped to again
+    SetSnapExcluded(Vec<(NodeId, bool)>),
+    SetPins(Vec<(NodeId, Pins)>),
+    SetAutoLayout(Vec<(NodeId, Option<AutoLayout>)>),
+    /// Change the vector paths nodes are drawn as, `None` draws them as their box
+    SetPaths(Vec<(NodeId, Option<VectorPath>)>),
+    /// Replace every guide on the canvas
+    SetGuides(Vec<Guide>),
+    /// Move nodes into another parent, or to the top level for `None`, keeping where
+    /// they are on the canvas
+    SetParents(Vec<(NodeId, Option<NodeId>)>),
+    /// Reorder the children of a parent, or the top-level nodes for `None`
+    SetSiblingOrder {
+        parent: Option<NodeId>,
+        order: Vec<NodeId>,
+    },
+    /// Several commands applied in order as a single change
+    Batch(Vec<CanvasCommand>),
+}
+
+impl CanvasCommand {
+    /// Whether applying the command would leave the document as it is
+    pub fn is_empty(&self) -> bool {
+        match self {
+            CanvasCommand::InsertNodes { nodes, .. } => nodes.is_empty(),
+            CanvasCommand::RemoveNodes(node_ids) => node_ids.is_empty(),
+            CanvasCommand::SetLayouts(layouts) => layouts.is_empty(),
+            CanvasCommand::SetStyles(styles) => styles.is_empty(),
+            CanvasCommand::SetNames(names) => names.is_empty(),
+            CanvasCommand::SetLinks(links) => links.is_empty(),
+            CanvasCommand::SetComponents(components) => components.is_empty(),
+            CanvasCommand::SetHidden(hidden) => hidden.is_empty(),
+            CanvasCommand::SetLocked(locked) => locked.is_empty(),
+            CanvasCommand::SetSnapExcluded(excluded) => excluded.is_empty(),
+            CanvasCommand::SetPins(pins) => pins.is_empty(),
+            CanvasCommand::SetAutoLayout(auto_layouts) => auto_layouts.is_empty(),
+            CanvasCommand::SetPaths(paths) => paths.is_empty(),
+            // Even an empty list replaces the guides there are
+            CanvasCommand::SetGuides(_) => false,
+            CanvasCommand::SetParents(parents) => parents.is_empty(),
+            CanvasCommand::SetSiblingOrder { order, .. } => order.is_empty(),
+            CanvasCommand::Batch(commands) => commands.iter().all(CanvasCommand::is_empty),
+        }
+    }
+}
+
+/// A change on the undo or redo stack
+#[derive(Debug)]
+pub struct Change {
+    /// Identifies the change across undo and redo, so the history can tell when the
+    /// document is back to how it was saved
+    id: u64,
+    /// Reverts the change on the undo stack, applies it again on the redo stack
+    pub command: CanvasCommand,
+}
+
+/// The undo and redo stacks of a document
+#[derive(Debug, Default)]
+pub struct History {
+    undo_stack: Vec<Change>,
+    redo_stack: Vec<Change>,
+    last_id: u64,
+    /// The latest change when the document was last saved, 0 before any change
+    saved: u64,
+}
+
+impl History {
+    /// Record a new change by the command that undoes it
+    ///
+    /// A new change forks the history, whatever could be redone is dropped.
+    pub fn record(&mut self, undo: CanvasCommand) {
+        if undo.is_empty() {
+            return;
+        }
+        self.last_id += 1;
+        self.push_undo(Change {
+            id: self.last_id,
+            command: undo,
+        });
+        self.redo_stack.clear();
+    }
+
+    /// The latest change, its command reverted goes to [`Self::push_redo`]
+    pub fn take_undo(&mut self) -> Option<Change> {
+        self.undo_stack.pop()
+    }
+
+    /// The latest undone change, its command applied again goes to [`Self::push_undo`]
+    pub fn take_redo(&mut self) -> Option<Change> {
+        self.redo_stack.pop()
+    }
+
+    pub fn push_undo(&mut self, change: Change) {
+        self.undo_stack.push(change);
+        if self.undo_stack.len() > HISTORY_LIMIT {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    pub fn push_redo(&mut self, change: Change) {
+        self.redo_stack.push(change);
+    }
+
+    fn current(&self) -> u64 {
+        self.undo_stack.last().map_or(0, |change| change.id)
+    }
+
+    /// Remember the current state as the one on disk
+    pub fn mark_saved(&mut self) {
+        self.saved = self.current();
+    }
+
+    /// Whether the document differs from how it was last saved
+    pub fn is_edited(&self) -> bool {
+        self.current() != self.saved
+    }
+
+    /// Forget every change, the document as it is counts as saved
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.saved = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(name: &str) -> CanvasCommand {
+        CanvasCommand::SetNames(vec![(NodeId::new(1), Some(name.to_string()))])
+    }
+
+    fn name_of(change: Change) -> Option<String> {
+        match change.command {
+            CanvasCommand::SetNames(names) => names[0].1.clone(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_undo_and_redo_stacks() {
+        let mut history = History::default();
+        assert!(history.take_undo().is_none());
+
+        history.record(names("first"));
+        history.record(names("second"));
+        // Commands that wouldn't change anything aren't worth an undo step
+        let nothing = CanvasCommand::Batch(vec![CanvasCommand::RemoveNodes(Vec::new())]);
+        history.record(nothing);
+
+        let mut change = history.take_undo().unwrap();
+        change.command = names("redo second");
+        history.push_redo(change);
+        assert_eq!(history.redo_stack.len(), 1);
+
+        // Redoing keeps the rest of the redo stack, a new change drops it
+        let mut change = history.take_redo().unwrap();
+        change.command = names("undo second");
+        history.push_undo(change);
+        history.record(names("third"));
+        assert!(history.take_redo().is_none());
+        assert_eq!(name_of(history.take_undo().unwrap()), Some("third".into()));
+        assert_eq!(
+            name_of(history.take_undo().unwrap()),
+            Some("undo second".into())
+        );
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut history = History::default();
+        for index in 0..HISTORY_LIMIT + 10 {
+            history.record(names(&index.to_string()));
+        }
+
+        let mut count = 0;
+        let mut oldest = None;
+        while let Some(change) = history.take_undo() {
+            count += 1;
+            oldest = name_of(change);
+        }
+        assert_eq!(count, HISTORY_LIMIT);
+        assert_eq!(oldest, Some("10".into()));
+    }
+
+    #[test]
+    fn test_edited_since_save() {
+        let mut history = History::default();
+        assert!(!history.is_edited());
+
+        history.record(names("first"));
+        history.mark_saved();
+        history.record(names("second"));
+        assert!(history.is_edited());
+
+        // Undoing back to the saved state leaves nothing unsaved
+        let change = history.take_undo().unwrap();
+        history.push_redo(change);
+        assert!(!history.is_edited());
+        let change = history.take_undo().unwrap();
+        history.push_redo(change);
+        assert!(history.is_edited());
+
+        // Redoing returns to it as well
+        let change = history.take_redo().unwrap();
+        history.push_undo(change);
+        assert!(!history.is_edited());
+
+        history.clear();
+        assert!(!history.is_edited());
+    }
+}
@@ -1,4 +1,11 @@
-use gpui::{Pixels, Point};
+use crate::{
+    guides::Guide,
+    node::{path::PathHandle, NodeId},
+};
+use gpui::{point, size, Bounds, Pixels, Point};
+
+/// Smallest width or height a resize drag can shrink an area to, in canvas units
+pub const MIN_RESIZE_SIZE: f32 = 1.0;
 
 /// The type of dragging operation being performed
 #[derive(Clone, Debug, PartialEq)]
@@ -11,6 +18,22 @@ pub enum DragType {
     CreateElement,
     /// Dragging to resize an element
     Resize(ResizeOperation),
+    /// Dragging the viewport around, from where it was scrolled to at the start
+    Pan { start_scroll: Point<f32> },
+    /// Pulling out the handles of the anchor the pen tool just placed
+    PenHandle,
+    /// Dragging an anchor or handle of the path being edited
+    EditPath(PathHandle),
+    /// Dragging a guide out of a ruler, or one already placed
+    Guide(GuideDrag),
+}
+
+/// A guide being dragged, and where it's been dragged to so far
+#[derive(Clone, Debug, PartialEq)]
+pub struct GuideDrag {
+    /// Index of the guide being moved, `None` for a new one pulled out of a ruler
+    pub index: Option<usize>,
+    pub guide: Guide,
 }
 
 /// Represents a drag operation in progress with start and current points
@@ -59,6 +82,56 @@ impl ActiveDrag {
         }
     }
 
+    /// Creates a new pan drag operation
+    pub fn new_pan(start: Point<Pixels>, start_scroll: Point<f32>) -> Self {
+        Self {
+            start_position: start,
+            current_position: start,
+            drag_type: DragType::Pan { start_scroll },
+        }
+    }
+
+    /// Creates a new drag pulling handles out of a newly placed pen anchor
+    pub fn new_pen_handle(start: Point<Pixels>) -> Self {
+        Self {
+            start_position: start,
+            current_position: start,
+            drag_type: DragType::PenHandle,
+        }
+    }
+
+    /// Creates a new drag of part of the edited path
+    pub fn new_edit_path(start: Point<Pixels>, handle: PathHandle) -> Self {
+        Self {
+            start_position: start,
+            current_position: start,
+            drag_type: DragType::EditPath(handle),
+        }
+    }
+
+    /// Creates a new drag of a guide, `index` being the guide's own when it's already
+    /// placed
+    pub fn new_guide(start: Point<Pixels>, index: Option<usize>, guide: Guide) -> Self {
+        Self {
+            start_position: start,
+            current_position: start,
+            drag_type: DragType::Guide(GuideDrag { index, guide }),
+        }
+    }
+
+    /// Scroll position that keeps the canvas under the cursor while panning at `zoom`,
+    /// `None` for other drags
+    pub fn pan_scroll_position(&self, zoom: f32) -> Option<Point<f32>> {
+        let DragType::Pan { start_scroll } = self.drag_type else {
+            return None;
+        };
+        let delta = self.delta();
+        Some(point(
+            start_scroll.x - delta.x / zoom,
+            start_scroll.y - delta.y / zoom,
+        ))
+    }
+
     /// Gets the delta (change) between the current position and the start position
     pub fn delta(&self) -> Point<f32> {
         Point::new(
@@ -142,6 +215,8 @@ pub struct ResizeOperation {
     pub original_y: f32,
     /// Configuration for the resize operation
     pub config: ResizeConfig,
+    /// The nodes being resized and their absolute bounds before the resize
+    pub nodes: Vec<(NodeId, Bounds<f32>)>,
 }
 
 impl ResizeOperation {
@@ -154,9 +229,110 @@ impl ResizeOperation {
             original_x: x,
             original_y: y,
             config: ResizeConfig::default(),
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Sets the nodes that are scaled along with the resized area
+    pub fn with_nodes(mut self, nodes: Vec<(NodeId, Bounds<f32>)>) -> Self {
+        self.nodes = nodes;
+        self
+    }
+
+    pub fn original_bounds(&self) -> Bounds<f32> {
+        Bounds {
+            origin: point(self.original_x, self.original_y),
+            size: size(self.original_width, self.original_height),
+        }
+    }
+
+    /// The resized area once the handle has been dragged by `delta` canvas units
+    ///
+    /// The corner opposite the handle stays in place, or the center when resizing from
+    /// the center. Dragging past it flips the area over rather than collapsing it.
+    pub fn resized_bounds(&self, delta: Point<f32>) -> Bounds<f32> {
+        let original = self.original_bounds();
+        let direction_x = if self.handle.is_left() { -1.0 } else { 1.0 };
+        let direction_y = if self.handle.is_top() { -1.0 } else { 1.0 };
+        // Both sides move when resizing from the center
+        let factor = if self.config.resize_from_center {
+            2.0
+        } else {
+            1.0
+        };
+
+        // Signed sizes, negative once the handle crosses the fixed point
+        let mut width = original.size.width + delta.x * direction_x * factor;
+        let mut height = original.size.height + delta.y * direction_y * factor;
+        if self.config.preserve_aspect_ratio
+            && original.size.width > 0.0
+            && original.size.height > 0.0
+        {
+            // Follow whichever axis the pointer has moved further along
+            let scale_x = width / original.size.width;
+            let scale_y = height / original.size.height;
+            let scale = if (scale_x - 1.0).abs() > (scale_y - 1.0).abs() {
+                scale_x
+            } else {
+                scale_y
+            };
+            width = original.size.width * scale;
+            height = original.size.height * scale;
+        }
+        let width = at_least_min_size(width);
+        let height = at_least_min_size(height);
+
+        let (x, y) = if self.config.resize_from_center {
+            (
+                original.center().x - width / 2.0,
+                original.center().y - height / 2.0,
+            )
+        } else {
+            (
+                if self.handle.is_left() {
+                    original.right() - width
+                } else {
+                    original.left()
+                },
+                if self.handle.is_top() {
+                    original.bottom() - height
+                } else {
+                    original.top()
+                },
+            )
+        };
+
+        Bounds {
+            origin: point(x.min(x + width), y.min(y + height)),
+            size: size(width.abs(), height.abs()),
         }
     }
 
+    /// Adjust a drag so the dragged corner lands where `snap` puts it
+    ///
+    /// `snap` rounds a canvas coordinate, usually onto the grid. With the opposite
+    /// corner on the grid as well, the resized width and height come out as whole
+    /// grid steps.
+    pub fn snapped_delta(&self, delta: Point<f32>, snap: impl Fn(f32) -> f32) -> Point<f32> {
+        let original = self.original_bounds();
+        let corner = point(
+            if self.handle.is_left() {
+                original.left()
+            } else {
+                original.right()
+            },
+            if self.handle.is_top() {
+                original.top()
+            } else {
+                original.bottom()
+            },
+        );
+        point(
+            snap(corner.x + delta.x) - corner.x,
+            snap(corner.y + delta.y) - corner.y,
+        )
+    }
+
     /// Sets whether to preserve aspect ratio
     pub fn with_preserve_aspect_ratio(mut self, preserve: bool) -> Self {
         self.config.preserve_aspect_ratio = preserve;
@@ -169,3 +345,124 @@ impl ResizeOperation {
         self
     }
 }
+
+fn at_least_min_size(signed_size: f32) -> f32 {
+    if signed_size.abs() < MIN_RESIZE_SIZE {
+        MIN_RESIZE_SIZE.copysign(signed_size)
+    } else {
+        signed_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::px;
+
+    fn resize(handle: ResizeHandle) -> ResizeOperation {
+        ResizeOperation::new(handle, 100.0, 100.0, 200.0, 100.0)
+    }
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Bounds<f32> {
+        Bounds {
+            origin: point(x, y),
+            size: size(width, height),
+        }
+    }
+
+    #[test]
+    fn test_resize_keeps_opposite_corner() {
+        let bottom_right = resize(ResizeHandle::BottomRight);
+        assert_eq!(
+            bottom_right.resized_bounds(point(50.0, 20.0)),
+            rect(100.0, 100.0, 250.0, 120.0)
+        );
+
+        let top_left = resize(ResizeHandle::TopLeft);
+        assert_eq!(
+            top_left.resized_bounds(point(50.0, 20.0)),
+            rect(150.0, 120.0, 150.0, 80.0)
+        );
+    }
+
+    #[test]
+    fn test_resize_flips_past_fixed_corner() {
+        let top_right = resize(ResizeHandle::TopRight);
+        // The right edge crosses over the left one, the top edge over the bottom one
+        assert_eq!(
+            top_right.resized_bounds(point(-250.0, 130.0)),
+            rect(50.0, 200.0, 50.0, 30.0)
+        );
+        // Collapsing entirely still leaves something to grab
+        assert_eq!(
+            top_right.resized_bounds(point(-200.0, 0.0)).size.width,
+            MIN_RESIZE_SIZE
+        );
+    }
+
+    #[test]
+    fn test_resize_preserving_aspect_ratio() {
+        let bottom_right = resize(ResizeHandle::BottomRight).with_preserve_aspect_ratio(true);
+        // Width grew by 50%, which wins over the smaller change in height
+        assert_eq!(
+            bottom_right.resized_bounds(point(100.0, 10.0)),
+            rect(100.0, 100.0, 300.0, 150.0)
+        );
+
+        let top_left = resize(ResizeHandle::TopLeft).with_preserve_aspect_ratio(true);
+        assert_eq!(
+            top_left.resized_bounds(point(0.0, 50.0)),
+            rect(200.0, 150.0, 100.0, 50.0)
+        );
+    }
+
+    #[test]
+    fn test_resize_from_center() {
+        let bottom_left = resize(ResizeHandle::BottomLeft).with_resize_from_center(true);
+        assert_eq!(
+            bottom_left.resized_bounds(point(-10.0, 10.0)),
+            rect(90.0, 90.0, 220.0, 120.0)
+        );
+
+        let both = resize(ResizeHandle::TopRight)
+            .with_resize_from_center(true)
+            .with_preserve_aspect_ratio(true);
+        assert_eq!(
+            both.resized_bounds(point(50.0, 0.0)),
+            rect(50.0, 75.0, 300.0, 150.0)
+        );
+    }
+
+    #[test]
+    fn test_snapped_delta() {
+        let snap = |value: f32| (value / 10.0).round() * 10.0;
+
+        // The bottom-right corner starts at 300,200 and is pulled onto 340,210
+        let bottom_right = resize(ResizeHandle::BottomRight);
+        assert_eq!(
+            bottom_right.snapped_delta(point(37.0, 12.0), snap),
+            point(40.0, 10.0)
+        );
+
+        // The top-left corner starts at 100,100
+        let top_left = resize(ResizeHandle::TopLeft);
+        assert_eq!(
+            top_left.snapped_delta(point(-6.0, 4.0), snap),
+            point(-10.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_pan_scroll_position() {
+        let mut drag = ActiveDrag::new_pan(point(px(400.0), px(300.0)), point(50.0, -20.0));
+        drag.current_position = point(px(440.0), px(280.0));
+
+        // At 2x the canvas moves half as far as the cursor, and the opposite way the
+        // scroll position does
+        assert_eq!(drag.pan_scroll_position(2.0), Some(point(30.0, -10.0)));
+        assert_eq!(
+            ActiveDrag::new_selection(point(px(0.0), px(0.0))).pan_scroll_position(1.0),
+            None
+        );
+    }
+}
@@ -1,25 +1,180 @@
 use gpui::{App, KeyBinding};
 
 use crate::{
-    Cancel, Copy, Cut, Delete, FrameTool, HandTool, Paste, RectangleTool, SelectAll, SelectionTool,
+    canvas::{
+        BigNudgeDown, BigNudgeLeft, BigNudgeRight, BigNudgeUp, BringToFront, LowerOne, NudgeDown,
+        NudgeLeft, NudgeRight, NudgeUp, RaiseOne, Redo, ResetZoom, SelectNextSibling,
+        SelectPreviousSibling, SendToBack, ToggleHidden, ToggleLocked, Undo, ZoomIn, ZoomOut,
+    },
+    ui::{
+        batch_rename, color_picker, context_menu, design_lint, document_info, inspector,
+        library_browser, library_review, link_editor, page_list, password_prompt, quick_open,
+        shortcuts, template_gallery, version_diff,
+    },
+    Blend, Cancel, Copy, Cut, Delete, Duplicate, EditLink, EyedropperTool, FrameTool, GoToNode,
+    HandTool, NewFromTemplate, Open, Paste, PenTool, RectangleTool, RenameSelection,
+    ResetCurrentColors, Save, SaveAs, SelectAll, SelectionTool, ShowColorPicker, ShowDesignLint,
+    ShowDocumentInfo, ShowShortcuts, SwapCurrentColors, ToggleColorTarget, ToggleFullScreen,
+    ToggleGrid, ToggleOutline, TogglePreview, ToggleRulers, ToggleSnapToGrid, ToggleUI,
+    ToggleWireframe,
 };
 
 pub fn init_keymap(cx: &mut App) {
     cx.bind_keys([
-        KeyBinding::new("h", HandTool, None),
-        KeyBinding::new("a", SelectionTool, None),
-        KeyBinding::new("r", RectangleTool, None),
-        KeyBinding::new("f", FrameTool, None),
         KeyBinding::new("escape", Cancel, None),
         KeyBinding::new("cmd-a", SelectAll, None),
         KeyBinding::new("cmd-v", Paste, None),
         KeyBinding::new("cmd-c", Copy, None),
         KeyBinding::new("cmd-x", Cut, None),
+        KeyBinding::new("cmd-d", Duplicate, None),
+        KeyBinding::new("cmd-y", ToggleOutline, None),
+        KeyBinding::new("cmd-'", ToggleGrid, None),
+        KeyBinding::new("shift-cmd-'", ToggleSnapToGrid, None),
+        KeyBinding::new("cmd-\\", ToggleUI, None),
+        KeyBinding::new("ctrl-cmd-f", ToggleFullScreen, None),
+        KeyBinding::new("cmd-p", GoToNode, None),
+        KeyBinding::new("cmd-i", ShowDocumentInfo, None),
+        KeyBinding::new("shift-cmd-l", ShowDesignLint, None),
+        KeyBinding::new("alt-cmd-b", Blend, None),
+        KeyBinding::new("cmd-r", RenameSelection, None),
+        KeyBinding::new("cmd-k", EditLink, None),
+        KeyBinding::new("shift-cmd-n", NewFromTemplate, None),
+        KeyBinding::new("cmd-o", Open, None),
+        KeyBinding::new("cmd-s", Save, None),
+        KeyBinding::new("shift-cmd-s", SaveAs, None),
+        KeyBinding::new("cmd-z", Undo, None),
+        KeyBinding::new("shift-cmd-z", Redo, None),
         // Canvas
-        KeyBinding::new("delete", Delete, None),
-        KeyBinding::new("backspace", Delete, None),
+        //
+        // Keys that type text are only bound while the canvas has focus, otherwise they
+        // would be taken from the inspector fields and page names being typed into
+        KeyBinding::new("h", HandTool, Some("Canvas")),
+        KeyBinding::new("a", SelectionTool, Some("Canvas")),
+        KeyBinding::new("r", RectangleTool, Some("Canvas")),
+        KeyBinding::new("f", FrameTool, Some("Canvas")),
+        KeyBinding::new("p", PenTool, Some("Canvas")),
+        KeyBinding::new("i", EyedropperTool, Some("Canvas")),
+        KeyBinding::new("shift-c", ShowColorPicker, Some("Canvas")),
+        KeyBinding::new("shift-x", SwapCurrentColors, Some("Canvas")),
+        KeyBinding::new("d", ResetCurrentColors, Some("Canvas")),
+        KeyBinding::new("x", ToggleColorTarget, Some("Canvas")),
+        KeyBinding::new("shift-w", ToggleWireframe, Some("Canvas")),
+        KeyBinding::new("shift-p", TogglePreview, Some("Canvas")),
+        KeyBinding::new("shift-r", ToggleRulers, Some("Canvas")),
+        KeyBinding::new("shift-/", ShowShortcuts, Some("Canvas")),
+        KeyBinding::new("delete", Delete, Some("Canvas")),
+        KeyBinding::new("backspace", Delete, Some("Canvas")),
+        KeyBinding::new("tab", SelectNextSibling, Some("Canvas")),
+        KeyBinding::new("shift-tab", SelectPreviousSibling, Some("Canvas")),
+        KeyBinding::new("up", NudgeUp, Some("Canvas")),
+        KeyBinding::new("down", NudgeDown, Some("Canvas")),
+        KeyBinding::new("left", NudgeLeft, Some("Canvas")),
+        KeyBinding::new("right", NudgeRight, Some("Canvas")),
+        KeyBinding::new("shift-up", BigNudgeUp, Some("Canvas")),
+        KeyBinding::new("shift-down", BigNudgeDown, Some("Canvas")),
+        KeyBinding::new("shift-left", BigNudgeLeft, Some("Canvas")),
+        KeyBinding::new("shift-right", BigNudgeRight, Some("Canvas")),
+        KeyBinding::new("cmd-]", RaiseOne, None),
+        KeyBinding::new("cmd-[", LowerOne, None),
+        KeyBinding::new("alt-cmd-]", BringToFront, None),
+        KeyBinding::new("alt-cmd-[", SendToBack, None),
+        KeyBinding::new("shift-cmd-h", ToggleHidden, None),
+        KeyBinding::new("alt-cmd-l", ToggleLocked, None),
+        KeyBinding::new("cmd-=", ZoomIn, None),
+        KeyBinding::new("cmd-+", ZoomIn, None),
+        KeyBinding::new("cmd--", ZoomOut, None),
+        KeyBinding::new("cmd-0", ResetZoom, None),
         // Layer List
         KeyBinding::new("delete", Delete, Some("LayerList")),
         KeyBinding::new("backspace", Delete, Some("LayerList")),
+        // Quick Open
+        KeyBinding::new("cmd-p", quick_open::Dismiss, Some("QuickOpen")),
+        KeyBinding::new("escape", quick_open::Dismiss, Some("QuickOpen")),
+        KeyBinding::new("enter", quick_open::Confirm, Some("QuickOpen")),
+        KeyBinding::new("up", quick_open::SelectPrevious, Some("QuickOpen")),
+        KeyBinding::new("down", quick_open::SelectNext, Some("QuickOpen")),
+        // Document Info
+        KeyBinding::new("cmd-i", document_info::Dismiss, Some("DocumentInfo")),
+        KeyBinding::new("escape", document_info::Dismiss, Some("DocumentInfo")),
+        // Design Lint
+        KeyBinding::new("shift-cmd-l", design_lint::Dismiss, Some("DesignLint")),
+        KeyBinding::new("escape", design_lint::Dismiss, Some("DesignLint")),
+        KeyBinding::new("cmd-enter", design_lint::FixAll, Some("DesignLint")),
+        // Library Browser
+        KeyBinding::new("escape", library_browser::Dismiss, Some("LibraryBrowser")),
+        KeyBinding::new("enter", library_browser::Confirm, Some("LibraryBrowser")),
+        KeyBinding::new("cmd-v", library_browser::Paste, Some("LibraryBrowser")),
+        KeyBinding::new("escape", library_review::Dismiss, Some("LibraryReview")),
+        KeyBinding::new("cmd-enter", library_review::Confirm, Some("LibraryReview")),
+        // Version Diff
+        KeyBinding::new("escape", version_diff::Dismiss, Some("VersionDiff")),
+        // Batch Rename
+        KeyBinding::new("escape", batch_rename::Dismiss, Some("BatchRename")),
+        KeyBinding::new("enter", batch_rename::Confirm, Some("BatchRename")),
+        KeyBinding::new("tab", batch_rename::NextField, Some("BatchRename")),
+        KeyBinding::new(
+            "shift-tab",
+            batch_rename::PreviousField,
+            Some("BatchRename"),
+        ),
+        // Color Picker
+        KeyBinding::new("escape", color_picker::Dismiss, Some("ColorPicker")),
+        KeyBinding::new("enter", color_picker::Confirm, Some("ColorPicker")),
+        KeyBinding::new("cmd-v", color_picker::Paste, Some("ColorPicker")),
+        KeyBinding::new("tab", color_picker::NextControl, Some("ColorPicker")),
+        KeyBinding::new(
+            "shift-tab",
+            color_picker::PreviousControl,
+            Some("ColorPicker"),
+        ),
+        KeyBinding::new("up", color_picker::StepUp, Some("ColorPicker")),
+        KeyBinding::new("down", color_picker::StepDown, Some("ColorPicker")),
+        KeyBinding::new("left", color_picker::StepLeft, Some("ColorPicker")),
+        KeyBinding::new("right", color_picker::StepRight, Some("ColorPicker")),
+        KeyBinding::new("shift-up", color_picker::BigStepUp, Some("ColorPicker")),
+        KeyBinding::new("shift-down", color_picker::BigStepDown, Some("ColorPicker")),
+        KeyBinding::new("shift-left", color_picker::BigStepLeft, Some("ColorPicker")),
+        KeyBinding::new(
+            "shift-right",
+            color_picker::BigStepRight,
+            Some("ColorPicker"),
+        ),
+        // Context Menu
+        KeyBinding::new("escape", context_menu::Dismiss, Some("ContextMenu")),
+        KeyBinding::new("enter", context_menu::Confirm, Some("ContextMenu")),
+        KeyBinding::new("up", context_menu::SelectPrevious, Some("ContextMenu")),
+        KeyBinding::new("down", context_menu::SelectNext, Some("ContextMenu")),
+        // Link Editor
+        KeyBinding::new("escape", link_editor::Dismiss, Some("LinkEditor")),
+        KeyBinding::new("enter", link_editor::Confirm, Some("LinkEditor")),
+        KeyBinding::new("cmd-v", link_editor::Paste, Some("LinkEditor")),
+        // Password Prompt
+        KeyBinding::new("escape", password_prompt::Dismiss, Some("PasswordPrompt")),
+        KeyBinding::new("enter", password_prompt::Confirm, Some("PasswordPrompt")),
+        KeyBinding::new("cmd-v", password_prompt::Paste, Some("PasswordPrompt")),
+        // Page list
+        KeyBinding::new("escape", page_list::Dismiss, Some("PageList")),
+        KeyBinding::new("enter", page_list::Confirm, Some("PageList")),
+        // Inspector fields
+        KeyBinding::new("escape", inspector::Dismiss, Some("Inspector")),
+        KeyBinding::new("enter", inspector::Confirm, Some("Inspector")),
+        KeyBinding::new("cmd-c", inspector::CopyValue, Some("Inspector")),
+        KeyBinding::new("cmd-v", inspector::PasteValue, Some("Inspector")),
+        // Shortcuts
+        KeyBinding::new("shift-/", shortcuts::Dismiss, Some("Shortcuts")),
+        KeyBinding::new("escape", shortcuts::Dismiss, Some("Shortcuts")),
+        // Template Gallery
+        KeyBinding::new("escape", template_gallery::Dismiss, Some("TemplateGallery")),
+        KeyBinding::new("enter", template_gallery::Confirm, Some("TemplateGallery")),
+        KeyBinding::new(
+            "right",
+            template_gallery::SelectNext,
+            Some("TemplateGallery"),
+        ),
+        KeyBinding::new(
+            "left",
+            template_gallery::SelectPrevious,
+            Some("TemplateGallery"),
+        ),
     ]);
 }
@@ -0,0 +1,231 @@
+//! # Library Repository
+//!
+//! The shared library can live in a git repository instead of the config directory,
+//! which is how a team shares one design system without running a server. The library
+//! file sits at the top of the repository's working tree. Publishing to it commits the
+//! change and pushes it when the repository has a remote, and pulling brings in what
+//! others published, after which documents are offered the updates as usual.
+//!
+//! Git is run as a command, using whatever identity, credentials and remotes the user
+//! has set up for the repository. It's never left waiting on a credential prompt, so
+//! credentials have to come from a helper or an SSH agent.
+
+use crate::{
+    library::{Library, LIBRARY_FILE_NAME},
+    util::config_dir,
+};
+use anyhow::{bail, Context as _};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+const REPOSITORY_FILE_NAME: &str = "library_repository.json";
+
+/// Which repository holds the library, kept in the config directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct RepositorySetting {
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryRepository {
+    /// Top of the working tree
+    path: PathBuf,
+}
+
+impl LibraryRepository {
+    fn setting_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(REPOSITORY_FILE_NAME))
+    }
+
+    /// The repository the library is kept in, `None` while it's kept in the config
+    /// directory
+    pub fn configured() -> Option<Self> {
+        let contents = std::fs::read_to_string(Self::setting_path()?).ok()?;
+        let setting: RepositorySetting = serde_json::from_str(&contents).ok()?;
+        setting.path.map(|path| Self { path })
+    }
+
+    /// Keep the library in `repository` from now on, or in the config directory again
+    /// for `None`
+    pub fn configure(repository: Option<&Self>) -> anyhow::Result<()> {
+        let path = Self::setting_path().context("no config directory available")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let setting = RepositorySetting {
+            path: repository.map(|repository| repository.path.clone()),
+        };
+        std::fs::write(&path, serde_json::to_string_pretty(&setting)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// The repository `path` is in, which can be any folder inside its working tree
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let top_level = git(path, &["rev-parse", "--show-toplevel"])
+            .with_context(|| format!("{} isn't in a git repository", path.display()))?;
+        Ok(Self {
+            path: PathBuf::from(top_level),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Where the library file is in the working tree
+    pub fn library_path(&self, file_name: &str) -> PathBuf {
+        self.path.join(file_name)
+    }
+
+    /// Commit the library file if it changed, returning whether there was a commit
+    pub fn commit(&self, file_name: &str, message: &str) -> anyhow::Result<bool> {
+        self.git(&["add", "--", file_name])?;
+        // Only the library file is committed, whatever else is staged stays staged
+        if self
+            .git(&["diff", "--cached", "--quiet", "--", file_name])
+            .is_ok()
+        {
+            return Ok(false);
+        }
+        self.git(&["commit", "--quiet", "--message", message, "--", file_name])?;
+        Ok(true)
+    }
+
+    /// Push to the remote, returning whether there is one to push to
+    pub fn push(&self) -> anyhow::Result<bool> {
+        if self.git(&["remote"])?.is_empty() {
+            return Ok(false);
+        }
+        self.git(&["push", "--quiet"])?;
+        Ok(true)
+    }
+
+    /// Bring in what others published
+    ///
+    /// Only fast-forwards, a library that was published to on both sides is left for
+    /// the user to merge with git, rather than ending up with conflict markers in it.
+    pub fn pull(&self) -> anyhow::Result<()> {
+        self.git(&["pull", "--quiet", "--ff-only"])?;
+        Ok(())
+    }
+
+    fn git(&self, args: &[&str]) -> anyhow::Result<String> {
+        git(&self.path, args)
+    }
+}
+
+/// Where the user's library is kept, in the library repository when there is one and
+/// otherwise in the config directory
+pub fn user_library_path() -> Option<PathBuf> {
+    match LibraryRepository::configured() {
+        Some(repository) => Some(repository.library_path(LIBRARY_FILE_NAME)),
+        None => config_dir().map(|dir| dir.join(LIBRARY_FILE_NAME)),
+    }
+}
+
+/// The user's library, empty until something is published to it
+pub fn load_user_library() -> anyhow::Result<Library> {
+    match user_library_path().filter(|path| path.exists()) {
+        Some(path) => Library::load(&path),
+        None => Ok(Library::default()),
+    }
+}
+
+pub fn save_user_library(library: &Library) -> anyhow::Result<()> {
+    let path = user_library_path().context("no config directory available")?;
+    library.save(&path)
+}
+
+/// Run git in `directory`, returning what it printed with surrounding whitespace
+/// trimmed, or what it complained about when it fails
+///
+/// Git must not stop to ask for credentials, there's no terminal to answer it in and
+/// the app would wait on it forever. A remote that needs them fails instead, with
+/// git's own message.
+fn git(directory: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .output()
+        .context("running git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match stderr.trim() {
+            "" => bail!("git {} exited with {}", args[0], output.status),
+            message => bail!("git {}: {message}", args[0]),
+        }
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A bare repository standing in for the team's remote, with two clones of it
+    struct Remote {
+        dir: PathBuf,
+    }
+
+    impl Remote {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("luna-library-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            git(&dir, &["init", "--quiet", "--bare", "remote.git"]).unwrap();
+            Self { dir }
+        }
+
+        fn clone(&self, name: &str) -> LibraryRepository {
+            git(&self.dir, &["clone", "--quiet", "remote.git", name]).unwrap();
+            let repository = LibraryRepository::open(&self.dir.join(name)).unwrap();
+            repository.git(&["config", "user.name", name]).unwrap();
+            repository
+                .git(&["config", "user.email", &format!("{name}@example.com")])
+                .unwrap();
+            repository
+        }
+    }
+
+    impl Drop for Remote {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    #[test]
+    fn test_publish_and_pull() {
+        let remote = Remote::new();
+        let publisher = remote.clone("publisher");
+        let consumer = remote.clone("consumer");
+
+        std::fs::write(publisher.library_path("library.json"), "v1").unwrap();
+        assert!(publisher.commit("library.json", "Publish Button").unwrap());
+        assert!(publisher.push().unwrap());
+        // Nothing changed, nothing to commit
+        assert!(!publisher.commit("library.json", "Publish Button").unwrap());
+
+        consumer.pull().unwrap();
+        let library = std::fs::read_to_string(consumer.library_path("library.json")).unwrap();
+        assert_eq!(library, "v1");
+
+        std::fs::write(publisher.library_path("library.json"), "v2").unwrap();
+        publisher.commit("library.json", "Update Button").unwrap();
+        publisher.push().unwrap();
+        consumer.pull().unwrap();
+        let library = std::fs::read_to_string(consumer.library_path("library.json")).unwrap();
+        assert_eq!(library, "v2");
+    }
+
+    #[test]
+    fn test_folders_outside_a_repository_are_refused() {
+        let remote = Remote::new();
+        assert!(LibraryRepository::open(&remote.dir).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+//! # Design Lint
+//!
+//! Checks a document for the small inconsistencies that creep into a design file as it
+//! grows: nodes sitting between pixels, layers still carrying their generated names, and
+//! colors that are almost, but not quite, a color used elsewhere. Most of these can be
+//! fixed mechanically, so each warning knows whether it can fix itself.
+
+use crate::{
+    color::{color_usage, same_color, ColorTarget},
+    node::{frame::FrameNode, NodeCommon, NodeId},
+};
+use gpui::Hsla;
+
+/// Largest difference in any 8-bit RGBA channel for two colors to count as near misses
+const NEAR_COLOR_TOLERANCE: f32 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LintIssue {
+    /// The node's position or size isn't a whole number of pixels
+    OffPixelGrid,
+    /// The node still has the name it was created with
+    DefaultName,
+    /// A fill or stroke is a near miss of a color the document uses more often
+    NearDuplicateColor {
+        target: ColorTarget,
+        color: Hsla,
+        closest: Hsla,
+    },
+}
+
+impl LintIssue {
+    pub fn title(&self) -> &'static str {
+        match self {
+            LintIssue::OffPixelGrid => "Off pixel grid",
+            LintIssue::DefaultName => "Unnamed layer",
+            LintIssue::NearDuplicateColor {
+                target: ColorTarget::Fill,
+                ..
+            } => "Stray fill color",
+            LintIssue::NearDuplicateColor {
+                target: ColorTarget::Stroke,
+                ..
+            } => "Stray stroke color",
+        }
+    }
+
+    pub fn can_fix(&self) -> bool {
+        !matches!(self, LintIssue::DefaultName)
+    }
+
+    /// Fix the issue on `node`, returns false when it has to be fixed by hand
+    pub fn fix(&self, node: &mut FrameNode) -> bool {
+        match *self {
+            LintIssue::OffPixelGrid => {
+                let layout = node.layout_mut();
+                layout.x = layout.x.round();
+                layout.y = layout.y.round();
+                layout.width = layout.width.round().max(1.0);
+                layout.height = layout.height.round().max(1.0);
+                true
+            }
+            LintIssue::DefaultName => false,
+            LintIssue::NearDuplicateColor {
+                target, closest, ..
+            } => {
+                match target {
+                    ColorTarget::Fill => node.set_fill(Some(closest)),
+                    ColorTarget::Stroke => node.set_border(Some(closest), node.border_width()),
+                }
+                true
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LintWarning {
+    pub node_id: NodeId,
+    pub issue: LintIssue,
+}
+
+/// Check every node in the document, warnings are grouped by node in document order
+pub fn lint(nodes: &[FrameNode]) -> Vec<LintWarning> {
+    let usage = color_usage(nodes.iter().flat_map(|node| {
+        let stroke = node.border_color().filter(|_| node.border_width() > 0.0);
+        [node.fill(), stroke].into_iter().flatten()
+    }));
+    // A color is a near miss when a more used color is close to it, ties don't count
+    // since neither color is clearly the intended one
+    let closest = |color: Hsla| {
+        let uses = usage
+            .iter()
+            .find(|(used, _)| same_color(*used, color))
+            .map_or(0, |(_, uses)| *uses);
+        usage
+            .iter()
+            .take_while(|(_, closest_uses)| *closest_uses > uses)
+            .find(|(used, _)| is_near_color(*used, color))
+            .map(|(used, _)| *used)
+    };
+
+    let mut warnings = Vec::new();
+    for node in nodes {
+        let mut warn = |issue| {
+            warnings.push(LintWarning {
+                node_id: node.id(),
+                issue,
+            })
+        };
+
+        let layout = node.layout();
+        if [layout.x, layout.y, layout.width, layout.height]
+            .iter()
+            .any(|value| value.fract() != 0.0)
+        {
+            warn(LintIssue::OffPixelGrid);
+        }
+
+        if node.name.is_none() {
+            warn(LintIssue::DefaultName);
+        }
+
+        let stroke = node.border_color().filter(|_| node.border_width() > 0.0);
+        for (target, color) in [
+            (ColorTarget::Fill, node.fill()),
+            (ColorTarget::Stroke, stroke),
+        ] {
+            let Some(color) = color else {
+                continue;
+            };
+            if let Some(closest) = closest(color) {
+                warn(LintIssue::NearDuplicateColor {
+                    target,
+                    color,
+                    closest,
+                });
+            }
+        }
+    }
+    warnings
+}
+
+/// Whether two distinct colors are close enough that one is likely a mistyped version
+/// of the other
+fn is_near_color(a: Hsla, b: Hsla) -> bool {
+    let (rgba_a, rgba_b) = (gpui::Rgba::from(a), gpui::Rgba::from(b));
+    let difference = [
+        rgba_a.r - rgba_b.r,
+        rgba_a.g - rgba_b.g,
+        rgba_a.b - rgba_b.b,
+        rgba_a.a - rgba_b.a,
+    ]
+    .into_iter()
+    .map(|channel| (channel * 255.0).abs())
+    .fold(0.0, f32::max);
+    !same_color(a, b) && difference <= NEAR_COLOR_TOLERANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(hex: u32) -> Hsla {
+        gpui::Rgba {
+            r: ((hex >> 16) & 0xff) as f32 / 255.0,
+            g: ((hex >> 8) & 0xff) as f32 / 255.0,
+            b: (hex & 0xff) as f32 / 255.0,
+            a: 1.0,
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_lint() {
+        let mut nodes: Vec<FrameNode> = (1..=4)
+            .map(|id| {
+                let mut node = FrameNode::new(NodeId::new(id));
+                node.set_name(Some(format!("Card {id}")));
+                node.set_fill(Some(rgb(0x3366ff)));
+                node.set_border(None, 0.0);
+                node
+            })
+            .collect();
+        nodes[1].layout_mut().x = 10.5;
+        nodes[2].set_name(None);
+        nodes[3].set_fill(Some(rgb(0x3367fd)));
+
+        let warnings = lint(&nodes);
+        let issues: Vec<(usize, &'static str)> = warnings
+            .iter()
+            .map(|warning| (warning.node_id.0, warning.issue.title()))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                (2, "Off pixel grid"),
+                (3, "Unnamed layer"),
+                (4, "Stray fill color")
+            ]
+        );
+
+        for warning in warnings {
+            let node = &mut nodes[warning.node_id.0 - 1];
+            assert_eq!(warning.issue.fix(node), warning.issue.can_fix());
+        }
+        assert_eq!(nodes[1].layout().x, 11.0);
+        assert!(same_color(nodes[3].fill().unwrap(), rgb(0x3366ff)));
+        assert_eq!(lint(&nodes).len(), 1);
+    }
+
+    #[test]
+    fn test_near_colors_need_a_clear_favorite() {
+        let mut nodes = vec![
+            FrameNode::new(NodeId::new(1)),
+            FrameNode::new(NodeId::new(2)),
+        ];
+        for node in &mut nodes {
+            node.set_name(Some("Swatch".into()));
+            node.set_border(None, 0.0);
+        }
+        nodes[0].set_fill(Some(rgb(0x808080)));
+        nodes[1].set_fill(Some(rgb(0x828282)));
+        assert!(lint(&nodes).is_empty());
+
+        assert!(is_near_color(rgb(0x808080), rgb(0x868080)));
+        assert!(!is_near_color(rgb(0x808080), rgb(0x878080)));
+        assert!(!is_near_color(rgb(0x808080), rgb(0x808080)));
+    }
+}
@@ -18,53 +18,194 @@
 //! for efficient spatial operations and a component-based architecture for the UI.
 
 use assets::Assets;
-use canvas::LunaCanvas;
+use canvas::{
+    BringToFront, Group, LowerOne, LunaCanvas, RaiseOne, Redo, RenderMode, ResetZoom, SendToBack,
+    ToggleHidden, ToggleLocked, Undo, Ungroup, ZoomIn, ZoomOut,
+};
 use canvas_element::CanvasElement;
+use clipboard::NodeClipboard;
+use color::{ColorFormat, ColorTarget};
+use document::{Document, SavedNode, ViewportState, DOCUMENT_EXTENSION};
+use document_diff::DocumentDiff;
+use document_settings::DocumentSettings;
+use export::{css::CssOptions, ExportNode};
 use gpui::{
-    actions, div, point, prelude::*, px, App, Application, Entity, FocusHandle, Focusable, Hsla,
-    IntoElement, Menu, MenuItem, TitlebarOptions, Window, WindowBackgroundAppearance,
-    WindowOptions,
+    actions, div, point, prelude::*, px, size, AnyView, App, Application, AsyncApp, Bounds,
+    ClipboardItem, Entity, FocusHandle, Focusable, Hsla, IntoElement, KeyDownEvent, KeyUpEvent,
+    Keystroke, Menu, MenuItem, PathPromptOptions, PromptLevel, SharedString, Subscription, Task,
+    TitlebarOptions, Window, WindowBackgroundAppearance, WindowBounds, WindowHandle, WindowOptions,
 };
+use grid::GridSettings;
+use history::CanvasCommand;
 use keymap::init_keymap;
+use library::{
+    LibraryContent, LibraryItem, LibraryLink, LibraryUpdate, SavedStyle, StyleLinks,
+    LIBRARY_FILE_NAME,
+};
+use library_repository::{
+    load_user_library, save_user_library, user_library_path, LibraryRepository,
+};
+use node::{
+    frame::{FrameNode, NodeStyle},
+    NodeCommon, NodeId,
+};
+use notifications::Notification;
+use pixel_preview::ExportScale;
+use power::GlobalPower;
+use print::{PageSetup, PrintPage};
+use profile::{Profile, PROFILE_VERSION};
+use protection::ProtectionError;
 use scene_graph::SceneGraph;
-use std::{path::PathBuf, sync::Arc};
-use theme::{ActiveTheme, GlobalTheme, Theme};
+use snapping::PathSnapping;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+    time::SystemTime,
+};
+use theme::{ActiveTheme, GlobalTheme, Theme, ThemeVariant};
 use tools::{ActiveTool, GlobalTool, Tool};
-use ui::{inspector::Inspector, sidebar::Sidebar};
+use ui::{
+    batch_rename::{BatchRename, BatchRenameEvent},
+    color_picker::{ColorPicker, ColorPickerEvent},
+    context_menu::{ContextMenu, ContextMenuEntry, ContextMenuEvent},
+    design_lint::{DesignLint, DesignLintEvent},
+    detached_panel::{DetachedPanel, PanelKind},
+    document_info::{DocumentInfo, DocumentInfoEvent},
+    frame_navigator::FrameNavigator,
+    inspector::{Inspector, InspectorEvent},
+    library_browser::{LibraryBrowser, LibraryBrowserEvent},
+    library_review::{LibraryReview, LibraryReviewEvent},
+    link_editor::{LinkEditor, LinkEditorEvent},
+    password_prompt::{PasswordPrompt, PasswordPromptEvent, PasswordPurpose},
+    quick_open::{QuickOpen, QuickOpenEvent},
+    shortcuts::{Shortcuts, ShortcutsEvent},
+    sidebar::Sidebar,
+    template_gallery::{TemplateGallery, TemplateGalleryEvent},
+    toasts::Toasts,
+    version_diff::{VersionDiff, VersionDiffEvent},
+};
+use window_state::{PanelState, SavedWindowBounds, WindowState};
+
+// The document model, scene graph and exporters live in `luna-core`, brought in here so
+// the app reaches them through the same `crate::` paths as its own modules
+use luna_core::{
+    color, constraints, css_parser, document, document_diff, document_settings, export, grid,
+    guides, interpolation, layout, library, node, protection, scene_graph, snapping, statistics,
+};
 
+mod animation;
 mod assets;
 mod canvas;
 mod canvas_element;
-mod color;
-mod coordinates;
-mod css_parser;
+mod clipboard;
+mod fuzzy;
+mod history;
 mod interactivity;
 mod keymap;
-mod node;
-mod scene_graph;
-mod scene_node;
+mod library_repository;
+mod lint;
+mod notifications;
+mod overlay;
+mod pixel_preview;
+mod power;
+mod print;
+mod profile;
+mod rename;
+mod stacking;
+mod template;
 mod theme;
+mod thumbnail;
 mod tools;
 mod ui;
 mod util;
+mod window_state;
 
 actions!(
     luna,
     [
+        AddGuidesFromSelection,
+        Blend,
         Cancel,
+        ClearGuides,
+        CompareWithSaved,
         Copy,
+        CopyAsCss,
+        CopyAsRust,
+        CopyAsSvg,
         Cut,
         Delete,
+        DeletePage,
+        DetachInspector,
+        DetachLayers,
+        Duplicate,
+        EditLink,
+        ExitSolo,
+        EyedropperTool,
+        ExportCss,
+        ExportProfile,
         FrameTool,
+        GoToNode,
         HandTool,
+        ImportProfile,
+        NewFromTemplate,
+        NewPage,
+        NextPage,
+        Open,
         Paste,
+        PenTool,
+        PreviousPage,
+        Print,
+        ProtectWithPassword,
+        PublishColorToken,
+        PublishComponent,
+        PublishStyle,
+        PullLibrary,
         Quit,
         RectangleTool,
+        RenamePage,
+        RenameSelection,
         ResetCurrentColors,
+        Save,
+        SaveAs,
         SelectAll,
         SelectionTool,
+        ShowColorPicker,
+        ShowContextMenu,
+        ShowDesignLint,
+        ShowDocumentInfo,
+        ShowLibrary,
+        ShowShortcuts,
         SwapCurrentColors,
+        ToggleColorTarget,
+        ToggleFollowSelection,
+        ToggleFrameNavigator,
+        ToggleFullScreen,
+        ToggleGrid,
+        ToggleHitTestDebug,
+        ToggleLowPowerMode,
+        ToggleOutline,
+        TogglePixelPreview1x,
+        TogglePixelPreview2x,
+        TogglePreview,
+        ToggleRestoreSession,
+        ToggleRulers,
+        ToggleSimulateIncreasedContrast,
+        ToggleSimulateReducedMotion,
+        ToggleSnapAnchorsToAnchors,
+        ToggleSnapAnchorsToGuides,
+        ToggleSnapAnchorsToPixels,
+        ToggleSnapExclusion,
+        ToggleSnapToGrid,
+        ToggleSnapToLayers,
         ToggleUI,
+        ToggleViewOnly,
+        ToggleWireframe,
+        UpdateFromLibrary,
+        UseLibraryRepository,
+        UseLocalLibrary,
     ]
 );
 
@@ -81,6 +222,57 @@ pub struct AppState {
     pub current_border_color: Hsla,
     /// Current background color for new elements
     pub current_background_color: Hsla,
+    /// How the open document displays colors and dimensions
+    pub document_settings: DocumentSettings,
+    /// Colors most recently applied to elements, newest first
+    pub recent_colors: Vec<Hsla>,
+    /// Whether swatches and other color pickers change the fill or the stroke
+    pub color_target: ColorTarget,
+    /// Whether the viewport scrolls to keep a selection moved from the keyboard in view
+    pub follow_selection: bool,
+    /// Whether dragged layers snap to the edges and centers of the layers around them
+    pub snap_to_layers: bool,
+    /// Whether rulers run along the top and left of the canvas, for dragging out guides
+    pub show_rulers: bool,
+    /// What anchors and handles snap to while a path is edited, reset with each launch
+    pub path_snapping: PathSnapping,
+}
+
+impl AppState {
+    /// Default background and border colors for new elements
+    pub fn default_colors(theme: &Theme) -> (Hsla, Hsla) {
+        (theme.tokens.surface0, theme.tokens.overlay0)
+    }
+
+    /// The current color in the slot picked colors go to
+    pub fn target_color(&self) -> Hsla {
+        match self.color_target {
+            ColorTarget::Fill => self.current_background_color,
+            ColorTarget::Stroke => self.current_border_color,
+        }
+    }
+
+    pub fn set_target_color(&mut self, color: Hsla) {
+        match self.color_target {
+            ColorTarget::Fill => self.current_background_color = color,
+            ColorTarget::Stroke => self.current_border_color = color,
+        }
+    }
+
+    pub fn note_recent_color(&mut self, color: Hsla) {
+        color::push_recent_color(&mut self.recent_colors, color);
+    }
+
+    pub fn swap_current_colors(&mut self) {
+        std::mem::swap(
+            &mut self.current_background_color,
+            &mut self.current_border_color,
+        );
+    }
+
+    pub fn reset_current_colors(&mut self, theme: &Theme) {
+        (self.current_background_color, self.current_border_color) = Self::default_colors(theme);
+    }
 }
 
 /// Main application component that orchestrates the Luna design application
@@ -104,22 +296,192 @@ struct Luna {
     scene_graph: Entity<SceneGraph>,
     /// Inspector panel for element properties and tools
     inspector: Entity<Inspector>,
+    /// Passes the nodes an inspector edit would change on to the canvas
+    _inspector_subscription: Subscription,
     /// Sidebar for additional tools and controls
     sidebar: Entity<Sidebar>,
+    sidebar_visible: bool,
+    inspector_visible: bool,
+    frame_navigator: Entity<FrameNavigator>,
+    navigator_visible: bool,
+    /// Panel visibility to return to when focus mode is exited, `Some` while it is active
+    focus_mode_restore: Option<(bool, bool)>,
+    /// Panels popped out into their own windows
+    detached_panels: HashMap<PanelKind, WindowHandle<DetachedPanel>>,
+    /// The open quick-open finder and the subscription to its events
+    quick_open: Option<(Entity<QuickOpen>, Subscription)>,
+    /// The open document info panel and the subscription to its events
+    document_info: Option<(Entity<DocumentInfo>, Subscription)>,
+    /// The open design lint panel and the subscription to its events
+    design_lint: Option<(Entity<DesignLint>, Subscription)>,
+    /// The open batch rename dialog and the subscription to its events
+    batch_rename: Option<(Entity<BatchRename>, Subscription)>,
+    link_editor: Option<(Entity<LinkEditor>, Subscription)>,
+    /// The open color picker and the subscription to its events
+    color_picker: Option<(Entity<ColorPicker>, Subscription)>,
+    /// The open context menu and the subscription to its events
+    context_menu: Option<(Entity<ContextMenu>, Subscription)>,
+    /// The open password prompt and the subscription to its events
+    password_prompt: Option<(Entity<PasswordPrompt>, Subscription)>,
+    /// The open keyboard shortcut sheet and the subscription to its events
+    shortcuts: Option<(Entity<Shortcuts>, Subscription)>,
+    /// The open template gallery and the subscription to its events
+    template_gallery: Option<(Entity<TemplateGallery>, Subscription)>,
+    /// The open comparison with the saved version and the subscription to its events
+    version_diff: Option<(Entity<VersionDiff>, Subscription)>,
+    /// The open library browser and the subscription to its events
+    library_browser: Option<(Entity<LibraryBrowser>, Subscription)>,
+    /// The open review of library updates and the subscription to its events
+    library_review: Option<(Entity<LibraryReview>, Subscription)>,
+    /// When the library file had last changed as of the last check for updates to the
+    /// document's library items
+    library_checked: Option<SystemTime>,
+    /// The app's toasts, shown over this window
+    toasts: Entity<Toasts>,
+    /// Persisted window layout, kept current so it can be written out on close or quit
+    window_state: WindowState,
+    /// Nodes last copied or cut, ready to be pasted
+    clipboard: NodeClipboard,
 }
 
 impl Luna {
-    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
-        let app_state = cx.new(|cx| AppState {
-            current_border_color: cx.theme().tokens.overlay0,
-            current_background_color: cx.theme().tokens.surface0,
+    pub fn new(window_state: WindowState, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let document_settings = window_state.document_settings();
+        let (current_background_color, current_border_color) = AppState::default_colors(cx.theme());
+        let app_state = cx.new(|_| AppState {
+            current_border_color,
+            current_background_color,
+            document_settings,
+            recent_colors: Vec::new(),
+            color_target: ColorTarget::default(),
+            follow_selection: window_state.follow_selection,
+            snap_to_layers: window_state.snap_to_layers,
+            show_rulers: window_state.show_rulers,
+            path_snapping: PathSnapping::default(),
         });
-        let focus_handle = cx.focus_handle();
         let scene_graph = cx.new(|_| SceneGraph::new());
         let theme = Theme::default();
         let canvas = cx.new(|cx| LunaCanvas::new(&app_state, &scene_graph, &theme, window, cx));
-        let inspector = cx.new(|_| Inspector::new(app_state.clone(), canvas.clone()));
-        let sidebar = cx.new(|cx| Sidebar::new(canvas.clone(), cx));
+        // Focusing the workspace focuses the canvas, which is where canvas actions are
+        // registered, see `CanvasElement::register_actions`
+        let focus_handle = canvas.read(cx).focus_handle(cx);
+        let inspector = cx.new(|cx| {
+            Inspector::new(
+                app_state.clone(),
+                canvas.clone(),
+                focus_handle.clone(),
+                window,
+                cx,
+            )
+        });
+        let _inspector_subscription =
+            cx.subscribe_in(&inspector, window, Self::handle_inspector_event);
+        let sidebar = cx.new(|cx| Sidebar::new(canvas.clone(), focus_handle.clone(), window, cx));
+        let frame_navigator = cx.new(|cx| FrameNavigator::new(canvas.clone(), cx));
+
+        let panels = window_state.panels.clone();
+        sidebar.update(cx, |sidebar, cx| {
+            sidebar.set_width(panels.sidebar_width, cx)
+        });
+        inspector.update(cx, |inspector, cx| {
+            inspector.set_width(panels.inspector_width, cx)
+        });
+        // Reopen the document the window was left on, starting an untitled one instead
+        // if it was moved or can no longer be read
+        let mut window_state = window_state;
+        if let Some(path) = window_state.document.clone() {
+            let result = Document::load(&path, None).and_then(|document| {
+                canvas.update(cx, |canvas, cx| {
+                    canvas.load_document(document, path.clone(), None, cx)
+                })
+            });
+            match result {
+                Ok(()) => Self::restore_selection(&canvas, &window_state, cx),
+                // Ask for the password once the window is up, it stays untitled if the
+                // prompt is dismissed
+                Err(err) if err.downcast_ref::<ProtectionError>().is_some() => {
+                    window_state.document = None;
+                    cx.defer_in(window, move |luna, window, cx| {
+                        luna.open_path(path, None, window, cx)
+                    });
+                }
+                Err(err) => {
+                    Toasts::show(
+                        Notification::error(format!("Couldn't reopen {}", path.display()))
+                            .with_detail(format!("{err:#}")),
+                        cx,
+                    );
+                    window_state.document = None;
+                }
+            }
+        }
+        if let Some(viewport) = window_state.viewport() {
+            canvas.update(cx, |canvas, cx| {
+                canvas.set_zoom(viewport.zoom, cx);
+                canvas.set_scroll_position(point(viewport.scroll_x, viewport.scroll_y), cx);
+            });
+        }
+
+        // Track the window frame as it changes, the platform window may already be
+        // gone by the time we get to persist it
+        cx.observe_window_bounds(window, |this, window, _cx| {
+            this.window_state.bounds = Some(SavedWindowBounds::from_window_bounds(
+                window.window_bounds(),
+            ));
+        })
+        .detach();
+
+        // The key up is missed when space is let go of in another app
+        cx.observe_window_activation(window, |this, window, cx| {
+            if !window.is_window_active() {
+                this.set_space_held(false, cx);
+            } else {
+                // The library may have been published to from another window since
+                this.check_library_updates(cx);
+            }
+        })
+        .detach();
+
+        // Closing with unsaved changes waits on the save prompt, which closes the window
+        // itself once the changes are saved or discarded
+        let this = cx.entity().downgrade();
+        window.on_window_should_close(cx, move |window, cx| {
+            this.update(cx, |this, cx| {
+                if !this.canvas.read(cx).is_edited() {
+                    this.save_window_state_on_exit(cx);
+                    return true;
+                }
+                let confirm = this.confirm_discard(window, cx);
+                let this = cx.entity().downgrade();
+                let handle = window.window_handle();
+                let mut async_cx = cx.to_async();
+                cx.foreground_executor()
+                    .spawn(async move {
+                        if !confirm.await {
+                            return;
+                        }
+                        handle
+                            .update(&mut async_cx, |_, window, cx| {
+                                this.update(cx, |this, cx| this.save_window_state_on_exit(cx))
+                                    .ok();
+                                window.remove_window();
+                            })
+                            .ok();
+                    })
+                    .detach();
+                false
+            })
+            .unwrap_or(true)
+        });
+        cx.on_app_quit(|this, cx| {
+            this.save_window_state_on_exit(cx);
+            async {}
+        })
+        .detach();
+
+        window_state.bounds = Some(SavedWindowBounds::from_window_bounds(
+            window.window_bounds(),
+        ));
 
         Luna {
             app_state,
@@ -127,7 +489,166 @@ impl Luna {
             scene_graph,
             focus_handle,
             inspector,
+            _inspector_subscription,
             sidebar,
+            sidebar_visible: panels.sidebar_visible,
+            inspector_visible: panels.inspector_visible,
+            frame_navigator,
+            navigator_visible: panels.navigator_visible,
+            focus_mode_restore: None,
+            detached_panels: HashMap::new(),
+            quick_open: None,
+            document_info: None,
+            design_lint: None,
+            batch_rename: None,
+            link_editor: None,
+            color_picker: None,
+            context_menu: None,
+            password_prompt: None,
+            shortcuts: None,
+            template_gallery: None,
+            version_diff: None,
+            library_browser: None,
+            library_review: None,
+            library_checked: None,
+            toasts: Toasts::global(cx),
+            window_state,
+            clipboard: NodeClipboard::default(),
+        }
+    }
+
+    /// Capture the current panel layout, viewport and selection and write the window
+    /// state to disk
+    fn save_window_state(&mut self, cx: &App) -> anyhow::Result<()> {
+        let canvas = self.canvas.read(cx);
+        let scroll_position = canvas.get_scroll_position();
+        let viewport = ViewportState {
+            scroll_x: scroll_position.x,
+            scroll_y: scroll_position.y,
+            zoom: canvas.zoom(),
+        };
+        let selection = canvas
+            .selected_nodes()
+            .iter()
+            .map(|node_id| node_id.0)
+            .collect();
+
+        self.window_state.set_viewport(viewport);
+        self.window_state.set_selection(selection);
+        self.window_state.panels = self.panel_state(cx);
+        self.window_state
+            .set_document_settings(self.app_state.read(cx).document_settings);
+        self.window_state.follow_selection = self.app_state.read(cx).follow_selection;
+        self.window_state.snap_to_layers = self.app_state.read(cx).snap_to_layers;
+        self.window_state.show_rulers = self.app_state.read(cx).show_rulers;
+        // Shared by every window, so whichever closes last doesn't undo a change
+        self.window_state.low_power = cx.global::<GlobalPower>().0.setting;
+        self.window_state.save()
+    }
+
+    /// Save the window state on behalf of a user action, reporting a failure in a toast.
+    /// Returns whether it was saved.
+    fn save_window_state_or_notify(&mut self, cx: &mut App) -> bool {
+        let result = self.save_window_state(cx);
+        if let Err(err) = &result {
+            Toasts::show(
+                Notification::error("Couldn't save the window state")
+                    .with_detail(format!("{err:#}")),
+                cx,
+            );
+        }
+        result.is_ok()
+    }
+
+    /// Save the window state as the window closes or the app quits, when there's nowhere
+    /// left to show a toast
+    fn save_window_state_on_exit(&mut self, cx: &App) {
+        if let Err(err) = self.save_window_state(cx) {
+            eprintln!("Failed to save window state: {err:#}");
+        }
+    }
+
+    /// Select what was selected when the document was last closed, skipping nodes
+    /// that have since been removed
+    fn restore_selection(canvas: &Entity<LunaCanvas>, window_state: &WindowState, cx: &mut App) {
+        let selection = window_state.selection();
+        if selection.is_empty() {
+            return;
+        }
+        canvas.update(cx, |canvas, cx| {
+            for node_id in selection {
+                canvas.select_node(NodeId::new(node_id));
+            }
+            canvas.mark_dirty(cx);
+        });
+    }
+
+    fn panel_state(&self, cx: &App) -> PanelState {
+        // Focus mode is transient, persist the layout the user will return to
+        let (sidebar_visible, inspector_visible) = self
+            .focus_mode_restore
+            .unwrap_or((self.sidebar_visible, self.inspector_visible));
+        PanelState {
+            sidebar_visible,
+            sidebar_width: self.sidebar.read(cx).width(),
+            inspector_visible,
+            inspector_width: self.inspector.read(cx).width(),
+            navigator_visible: self.navigator_visible,
+        }
+    }
+
+    /// Holding space over the canvas pans it on drag, like the hand tool
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let keystroke = &event.keystroke;
+        if keystroke.key == "space" && !keystroke.modifiers.modified() {
+            self.set_space_held(true, cx);
+            cx.stop_propagation();
+        } else if self.canvas.read(cx).pen_path().is_some() {
+            self.type_pen_segment(keystroke, cx);
+        }
+    }
+
+    /// Type the length and angle of the pen's next segment, and place it with enter
+    fn type_pen_segment(&mut self, keystroke: &Keystroke, cx: &mut Context<Self>) {
+        if keystroke.modifiers.platform || keystroke.modifiers.control {
+            return;
+        }
+        let typed = keystroke.key_char.as_deref().filter(|text| {
+            text.chars()
+                .all(|c| c.is_ascii_digit() || ".,-".contains(c))
+        });
+        let handled = self.canvas.update(cx, |canvas, cx| {
+            if keystroke.key == "enter" {
+                canvas.commit_pen_entry(cx)
+            } else if let Some(text) = typed {
+                canvas.type_pen_entry(text, cx);
+                true
+            } else {
+                false
+            }
+        });
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    fn handle_key_up(&mut self, event: &KeyUpEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if event.keystroke.key == "space" {
+            self.set_space_held(false, cx);
+        }
+    }
+
+    fn set_space_held(&mut self, space_held: bool, cx: &mut Context<Self>) {
+        if self.canvas.read(cx).space_held() != space_held {
+            self.canvas
+                .update(cx, |canvas, _cx| canvas.set_space_held(space_held));
+            // Redraw so the cursor follows
+            cx.notify();
         }
     }
 
@@ -161,6 +682,21 @@ impl Luna {
         cx.notify();
     }
 
+    fn activate_pen_tool(&mut self, _: &PenTool, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.set_global(GlobalTool(Arc::new(Tool::Pen)));
+        cx.notify();
+    }
+
+    fn activate_eyedropper_tool(
+        &mut self,
+        _: &EyedropperTool,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.set_global(GlobalTool(Arc::new(Tool::Eyedropper)));
+        cx.notify();
+    }
+
     fn select_all_nodes(&mut self, _: &SelectAll, _window: &mut Window, cx: &mut Context<Self>) {
         self.canvas.update(cx, |canvas, _| {
             canvas.select_all_nodes();
@@ -170,137 +706,2633 @@ impl Luna {
 
     fn delete_selected_nodes(&mut self, _: &Delete, _window: &mut Window, cx: &mut Context<Self>) {
         self.canvas.update(cx, |canvas, cx| {
-            let selected_nodes = canvas
-                .get_root_nodes()
-                .into_iter()
-                .filter(|&node_id| canvas.is_node_selected(node_id))
-                .collect::<Vec<_>>();
-
-            for node_id in selected_nodes {
-                canvas.remove_node(node_id, cx);
+            // Backspace takes back what was typed for the pen before the path itself
+            if canvas.erase_pen_entry(cx) {
+                return;
             }
-            canvas.mark_dirty(cx);
+            // Selected children go along with their selected ancestors
+            let selected_nodes = canvas.top_level_selection();
+            canvas.execute(CanvasCommand::RemoveNodes(selected_nodes), cx);
         });
     }
 
-    fn handle_cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
-        let active_tool = *cx.active_tool().clone();
+    fn copy(&mut self, _: &Copy, _window: &mut Window, cx: &mut Context<Self>) {
+        let nodes = self.canvas.read(cx).copy_selection();
+        if !nodes.is_empty() {
+            self.clipboard = NodeClipboard::copied(nodes);
+        }
+    }
 
-        if active_tool == Tool::Selection {
-            self.canvas.update(cx, |canvas, cx| {
-                canvas.deselect_all_nodes(cx);
-                canvas.mark_dirty(cx);
-            });
-        } else {
-            cx.dispatch_action(&SelectionTool);
+    fn cut(&mut self, _: &Cut, _window: &mut Window, cx: &mut Context<Self>) {
+        let nodes = self.canvas.read(cx).copy_selection();
+        if nodes.is_empty() {
+            return;
         }
+        self.clipboard = NodeClipboard::cut(nodes);
+        self.canvas.update(cx, |canvas, cx| {
+            let selected_nodes = canvas.top_level_selection();
+            canvas.execute(CanvasCommand::RemoveNodes(selected_nodes), cx);
+        });
     }
-}
 
-impl Render for Luna {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let theme = Theme::get_global(cx);
+    fn paste(&mut self, _: &Paste, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.clipboard.is_empty() {
+            return;
+        }
+        self.canvas.update(cx, |canvas, cx| {
+            let copies = self.clipboard.paste(|| canvas.generate_id());
+            canvas.insert_copies(copies, cx);
+        });
+    }
 
-        div()
-            .id("Luna")
-            .key_context("luna")
-            .track_focus(&self.focus_handle(cx))
-            .absolute()
-            .top_0()
-            .left_0()
-            .size_full()
-            .flex()
-            .font_family("Berkeley Mono")
-            .text_xs()
-            .bg(theme.tokens.background)
-            .text_color(theme.tokens.text)
-            .border_1()
-            .border_color(gpui::white().alpha(0.08))
-            .rounded(px(16.))
-            .overflow_hidden()
-            .on_key_down(|event, _, _| {
-                dbg!(event.keystroke.clone());
+    fn duplicate(&mut self, _: &Duplicate, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas
+            .update(cx, |canvas, cx| canvas.duplicate_selection(cx));
+    }
+
+    fn print(&mut self, _: &Print, window: &mut Window, cx: &mut Context<Self>) {
+        let canvas = self.canvas.read(cx);
+
+        // Print the selected frames, or every top-level frame once the user has confirmed
+        // the page count
+        let mut frames = canvas.top_level_selection();
+        let whole_page = frames.is_empty();
+        if whole_page {
+            frames = canvas.top_level_nodes();
+        }
+
+        let setup = PageSetup::default();
+        let pages: Vec<_> = frames
+            .into_iter()
+            .filter_map(|frame_id| PrintPage::for_frame(canvas, frame_id, &setup))
+            .collect();
+        if pages.is_empty() {
+            return;
+        }
+        let postscript = print::render_postscript(&pages);
+
+        if !whole_page {
+            if let Err(err) = print::send_to_printer(&postscript) {
+                Toasts::show(Notification::error("Couldn't print").with_detail(err), cx);
+            }
+            return;
+        }
+
+        let count = pages.len();
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &if count == 1 {
+                "Print 1 page?".to_string()
+            } else {
+                format!("Print {count} pages?")
+            },
+            Some("Nothing is selected, so every frame on this page will be printed."),
+            &["Print", "Cancel"],
+            cx,
+        );
+        let this = cx.entity().downgrade();
+        let mut async_cx = cx.to_async();
+        cx.foreground_executor()
+            .spawn(async move {
+                if let Ok(0) = answer.await {
+                    if let Err(err) = print::send_to_printer(&postscript) {
+                        this.update(&mut async_cx, |_, cx| {
+                            Toasts::show(
+                                Notification::error("Couldn't print").with_detail(err),
+                                cx,
+                            );
+                        })
+                        .ok();
+                    }
+                }
             })
-            .map(|div| match *cx.active_tool().clone() {
-                Tool::Hand => div.cursor_grab(),
-                Tool::Frame | Tool::Line | Tool::TextCursor => div.cursor_crosshair(),
-                _ => div.cursor_default(),
+            .detach();
+    }
+
+    fn selected_export_nodes(&self, cx: &App) -> Vec<ExportNode> {
+        let canvas = self.canvas.read(cx);
+        canvas
+            .top_level_selection()
+            .into_iter()
+            .filter_map(|node_id| ExportNode::from_node(node_id, &|id| canvas.get_node(id)))
+            .collect()
+    }
+
+    /// Contrast rules are only written while preview simulates increased contrast, the
+    /// design has been checked with it then
+    fn css_options(&self, cx: &App) -> CssOptions {
+        CssOptions {
+            increased_contrast: self.canvas.read(cx).simulates_increased_contrast(),
+        }
+    }
+
+    /// Copy the selected frames to the clipboard as CSS rules
+    fn copy_as_css(&mut self, _: &CopyAsCss, _window: &mut Window, cx: &mut Context<Self>) {
+        let frames = self.selected_export_nodes(cx);
+        if frames.is_empty() {
+            return;
+        }
+
+        let settings = self.app_state.read(cx).document_settings;
+        let css = export::css::export(&frames, &settings, self.css_options(cx));
+        cx.write_to_clipboard(ClipboardItem::new_string(css));
+    }
+
+    /// Write every top-level frame of the document to a stylesheet, the way
+    /// `assets/css/buttons.css` is read in
+    fn export_css(&mut self, _: &ExportCss, _window: &mut Window, cx: &mut Context<Self>) {
+        let canvas = self.canvas.read(cx);
+        let frames: Vec<_> = canvas
+            .top_level_nodes()
+            .into_iter()
+            .filter(|node_id| canvas.get_node(*node_id).is_some_and(|node| !node.hidden))
+            .filter_map(|node_id| ExportNode::from_node(node_id, &|id| canvas.get_node(id)))
+            .collect();
+        let settings = self.app_state.read(cx).document_settings;
+        let css = export::css::export(&frames, &settings, self.css_options(cx));
+
+        let path = cx.prompt_for_new_path(&self.save_directory(cx));
+        let async_cx = cx.to_async();
+        cx.foreground_executor()
+            .spawn(async move {
+                let Ok(Ok(Some(path))) = path.await else {
+                    return;
+                };
+                let notification = match std::fs::write(&path, css) {
+                    Ok(()) => Notification::info(format!("Exported CSS to {}", path.display())),
+                    Err(err) => {
+                        Notification::error("Couldn't export CSS").with_detail(err.to_string())
+                    }
+                };
+                async_cx.update(|cx| Toasts::show(notification, cx)).ok();
             })
-            .on_action(cx.listener(Self::activate_hand_tool))
-            .on_action(cx.listener(Self::activate_selection_tool))
-            .on_action(cx.listener(Self::activate_rectangle_tool))
-            .on_action(cx.listener(Self::activate_frame_tool))
-            .on_action(cx.listener(Self::select_all_nodes))
-            .on_action(cx.listener(Self::delete_selected_nodes))
-            .on_action(cx.listener(Self::handle_cancel))
-            .child(CanvasElement::new(&self.canvas, &self.scene_graph, cx))
-            .child(self.inspector.clone())
-            .child(self.sidebar.clone())
+            .detach();
     }
-}
 
-impl Focusable for Luna {
-    fn focus_handle(&self, _cx: &App) -> FocusHandle {
-        self.focus_handle.clone()
+    /// Copy the selected frames to the clipboard as Rust layout constants
+    fn copy_as_rust(&mut self, _: &CopyAsRust, _window: &mut Window, cx: &mut Context<Self>) {
+        let frames = self.selected_export_nodes(cx);
+        if frames.is_empty() {
+            return;
+        }
+
+        let settings = self.app_state.read(cx).document_settings;
+        let code = export::rust::export(&frames, &settings);
+        cx.write_to_clipboard(ClipboardItem::new_string(code));
     }
-}
 
-fn init_globals(cx: &mut App) {
-    cx.set_global(GlobalTheme(Arc::new(Theme::default())));
-    cx.set_global(GlobalTool(Arc::new(Tool::default())));
-}
+    /// Copy the selected frames to the clipboard as an SVG image
+    fn copy_as_svg(&mut self, _: &CopyAsSvg, _window: &mut Window, cx: &mut Context<Self>) {
+        let frames = self.selected_export_nodes(cx);
+        if frames.is_empty() {
+            return;
+        }
 
-/// Application entry point
-///
-/// Initializes the GPUI application, sets up global state, defines menus,
-/// and opens the main application window. This function is the starting point
-/// for the entire Luna application.
-fn main() {
-    Application::new()
-        .with_assets(Assets {
-            base: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets"),
-        })
-        .run(|cx: &mut App| {
-            cx.on_action(quit);
-            cx.set_menus(vec![Menu {
-                name: "Luna".into(),
-                items: vec![MenuItem::action("Quit", Quit)],
-            }]);
-
-            init_keymap(cx);
-            init_globals(cx);
-
-            let window = cx
-                .open_window(
-                    WindowOptions {
-                        titlebar: Some(TitlebarOptions {
-                            title: Some("Luna".into()),
-                            appears_transparent: true,
-                            traffic_light_position: Some(point(px(8.0), px(8.0))),
-                        }),
-                        window_background: WindowBackgroundAppearance::Transparent,
-                        ..Default::default()
-                    },
-                    |window, cx| cx.new(|cx| Luna::new(window, cx)),
-                )
-                .unwrap();
-
-            cx.on_keyboard_layout_change({
-                move |cx| {
-                    window.update(cx, |_, _, cx| cx.notify()).ok();
+        cx.write_to_clipboard(ClipboardItem::new_string(export::svg::export(&frames)));
+    }
+
+    fn open(&mut self, _: &Open, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+        let this = cx.entity().downgrade();
+        let handle = window.window_handle();
+        let mut async_cx = cx.to_async();
+        cx.foreground_executor()
+            .spawn(async move {
+                let Ok(Ok(Some(paths))) = paths.await else {
+                    return;
+                };
+                let Some(path) = paths.into_iter().next() else {
+                    return;
+                };
+                handle
+                    .update(&mut async_cx, |_, window, cx| {
+                        this.update(cx, |luna, cx| luna.open_path(path, None, window, cx))
+                    })
+                    .ok();
+            })
+            .detach();
+    }
+
+    /// Open the document at `path` in place of the current one, once the current one's
+    /// unsaved changes are saved or discarded. A protected document asks for its
+    /// password and comes back here with it.
+    fn open_path(
+        &mut self,
+        path: PathBuf,
+        password: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Read the file first, there's no point asking about changes for a file that
+        // can't be opened anyway
+        let document = match Document::load(&path, password.as_deref()) {
+            Ok(document) => document,
+            Err(err) if err.downcast_ref::<ProtectionError>().is_some() => {
+                let error = password.is_some().then(|| "Wrong password".into());
+                self.show_password_prompt(PasswordPurpose::Open(path), error, window, cx);
+                return;
+            }
+            Err(err) => {
+                Toasts::show(
+                    Notification::error(format!("Couldn't open {}", path.display()))
+                        .with_detail(format!("{err:#}"))
+                        .with_action("Open Another…", Open),
+                    cx,
+                );
+                return;
+            }
+        };
+
+        let confirm = self.confirm_discard(window, cx);
+        let this = cx.entity().downgrade();
+        let mut async_cx = cx.to_async();
+        cx.foreground_executor()
+            .spawn(async move {
+                if confirm.await {
+                    this.update(&mut async_cx, |luna, cx| {
+                        luna.open_document(document, path, password, cx)
+                    })
+                    .ok();
                 }
             })
             .detach();
+    }
 
-            window
-                .update(cx, |view, window, cx| {
-                    window.focus(&view.focus_handle(cx));
-                    cx.activate(true);
-                })
-                .unwrap();
+    fn open_document(
+        &mut self,
+        document: Document,
+        path: PathBuf,
+        password: Option<String>,
+        cx: &mut Context<Self>,
+    ) {
+        // Remember where the outgoing document was viewed from before switching
+        self.save_window_state_or_notify(cx);
+        let result = self.canvas.update(cx, |canvas, cx| {
+            canvas.load_document(document, path.clone(), password, cx)
         });
-}
+        match result {
+            Ok(()) => {
+                self.window_state.document = Some(path);
+                Self::restore_selection(&self.canvas, &self.window_state, cx);
+                self.put_away_editing_tools(cx);
+                self.library_checked = None;
+                self.check_library_updates(cx);
+            }
+            Err(err) => Toasts::show(
+                Notification::error(format!("Couldn't open {}", path.display()))
+                    .with_detail(format!("{err:#}")),
+                cx,
+            ),
+        }
+        cx.notify();
+    }
 
-fn quit(_: &Quit, cx: &mut App) {
-    cx.quit();
+    /// Write the document back to where it came from, asking for a path if it has none
+    fn save(&mut self, _: &Save, window: &mut Window, cx: &mut Context<Self>) {
+        match self.canvas.read(cx).document_path() {
+            Some(path) => {
+                self.save_document(path.to_path_buf(), cx);
+            }
+            None => self.save_as(&SaveAs, window, cx),
+        }
+    }
+
+    fn save_as(&mut self, _: &SaveAs, _window: &mut Window, cx: &mut Context<Self>) {
+        let path = cx.prompt_for_new_path(&self.save_directory(cx));
+        let this = cx.entity().downgrade();
+        let mut async_cx = cx.to_async();
+        cx.foreground_executor()
+            .spawn(async move {
+                let Ok(Ok(Some(path))) = path.await else {
+                    return;
+                };
+                this.update(&mut async_cx, |luna, cx| luna.save_document(path, cx))
+                    .ok();
+            })
+            .detach();
+    }
+
+    /// Ask for a password to protect the document with, or to change or remove the one
+    /// it has. The document is saved as soon as the new password is confirmed, so the
+    /// copy on disk never lags behind what the menu says.
+    fn protect_with_password(
+        &mut self,
+        _: &ProtectWithPassword,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.password_prompt.is_some() {
+            return;
+        }
+        let protected = self.canvas.read(cx).document_password().is_some();
+        self.show_password_prompt(PasswordPurpose::Protect { protected }, None, window, cx);
+    }
+
+    fn show_password_prompt(
+        &mut self,
+        purpose: PasswordPurpose,
+        error: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let prompt = cx.new(|cx| PasswordPrompt::new(purpose, error, cx));
+        let subscription = cx.subscribe_in(&prompt, window, Self::handle_password_prompt_event);
+        window.focus(&prompt.focus_handle(cx));
+        self.password_prompt = Some((prompt, subscription));
+        cx.notify();
+    }
+
+    fn handle_password_prompt_event(
+        &mut self,
+        prompt: &Entity<PasswordPrompt>,
+        event: &PasswordPromptEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let purpose = prompt.read(cx).purpose().clone();
+        self.password_prompt = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+
+        let PasswordPromptEvent::Confirmed(password) = event else {
+            return;
+        };
+        match purpose {
+            PasswordPurpose::Open(path) => {
+                self.open_path(path, Some(password.clone()), window, cx);
+            }
+            PasswordPurpose::Protect { .. } if password.is_empty() => {
+                self.set_document_password(None, window, cx);
+            }
+            PasswordPurpose::Protect { .. } => {
+                let purpose = PasswordPurpose::Repeat(password.clone());
+                self.show_password_prompt(purpose, None, window, cx);
+            }
+            PasswordPurpose::Repeat(first) if first == *password => {
+                self.set_document_password(Some(first), window, cx);
+            }
+            // Start over rather than guess which of the two was mistyped
+            PasswordPurpose::Repeat(_) => {
+                let protected = self.canvas.read(cx).document_password().is_some();
+                self.show_password_prompt(
+                    PasswordPurpose::Protect { protected },
+                    Some("The passwords didn't match".into()),
+                    window,
+                    cx,
+                );
+            }
+        }
+    }
+
+    fn set_document_password(
+        &mut self,
+        password: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.canvas
+            .update(cx, |canvas, cx| canvas.set_document_password(password, cx));
+        self.save(&Save, window, cx);
+    }
+
+    /// Where the save prompt starts out, next to the document if it has been saved before
+    fn save_directory(&self, cx: &App) -> PathBuf {
+        self.canvas
+            .read(cx)
+            .document_path()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::var_os("HOME").map(PathBuf::from))
+            .unwrap_or_default()
+    }
+
+    /// Offer to save unsaved changes before the document is closed. Resolves to whether
+    /// closing can go ahead, false when the prompt or the save is cancelled or the save
+    /// fails.
+    fn confirm_discard(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Task<bool> {
+        let canvas = self.canvas.read(cx);
+        if !canvas.is_edited() {
+            return Task::ready(true);
+        }
+
+        let path = canvas.document_path().map(Path::to_path_buf);
+        let name = path
+            .as_deref()
+            .and_then(Path::file_stem)
+            .map_or("Untitled".into(), |name| name.to_string_lossy());
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &format!("Save changes to “{name}” before closing?"),
+            Some("Your changes will be lost if you don't save them."),
+            &["Save", "Don't Save", "Cancel"],
+            cx,
+        );
+        let this = cx.entity().downgrade();
+        let mut async_cx = cx.to_async();
+        cx.foreground_executor().spawn(async move {
+            match answer.await {
+                Ok(0) => {}
+                Ok(1) => return true,
+                _ => return false,
+            }
+            let path = match path {
+                Some(path) => path,
+                None => {
+                    let Ok(prompt) = this.update(&mut async_cx, |this, cx| {
+                        cx.prompt_for_new_path(&this.save_directory(cx))
+                    }) else {
+                        return false;
+                    };
+                    let Ok(Ok(Some(path))) = prompt.await else {
+                        return false;
+                    };
+                    path
+                }
+            };
+            this.update(&mut async_cx, |this, cx| this.save_document(path, cx))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Returns whether the document was saved, failures are reported as they happen
+    fn save_document(&mut self, mut path: PathBuf, cx: &mut Context<Self>) -> bool {
+        if path.extension().is_none() {
+            path.set_extension(DOCUMENT_EXTENSION);
+        }
+        let result = self
+            .canvas
+            .update(cx, |canvas, cx| canvas.save_document(path.clone(), cx));
+        match result {
+            Ok(()) => {
+                self.window_state.document = Some(path);
+                true
+            }
+            // Most often the folder went away or isn't writable, somewhere else may work
+            Err(err) => {
+                Toasts::show(
+                    Notification::error(format!("Couldn't save {}", path.display()))
+                        .with_detail(format!("{err:#}"))
+                        .with_action("Save As…", SaveAs),
+                    cx,
+                );
+                false
+            }
+        }
+    }
+
+    /// Save the current theme, key bindings, panels and document settings to a file
+    fn export_profile(&mut self, _: &ExportProfile, _window: &mut Window, cx: &mut Context<Self>) {
+        let profile = Profile {
+            version: PROFILE_VERSION,
+            theme: ThemeVariant::of(cx.theme()).unwrap_or_default(),
+            key_bindings: Profile::capture_key_bindings(&cx.key_bindings().borrow()),
+            panels: self.panel_state(cx),
+            document_settings: DocumentSettings {
+                view_only: false,
+                ..self.app_state.read(cx).document_settings
+            },
+        };
+
+        let directory = std::env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_default();
+        let path = cx.prompt_for_new_path(&directory);
+        let async_cx = cx.to_async();
+        cx.foreground_executor()
+            .spawn(async move {
+                let Ok(Ok(Some(path))) = path.await else {
+                    return;
+                };
+                let notification = match profile.save(&path) {
+                    Ok(()) => Notification::info(format!(
+                        "Exported settings profile to {}",
+                        path.display()
+                    )),
+                    Err(err) => Notification::error("Couldn't export settings profile")
+                        .with_detail(format!("{err:#}")),
+                };
+                async_cx.update(|cx| Toasts::show(notification, cx)).ok();
+            })
+            .detach();
+    }
+
+    fn import_profile(&mut self, _: &ImportProfile, _window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+        let this = cx.entity().downgrade();
+        let mut async_cx = cx.to_async();
+        cx.foreground_executor()
+            .spawn(async move {
+                let Ok(Ok(Some(paths))) = paths.await else {
+                    return;
+                };
+                let Some(path) = paths.first() else {
+                    return;
+                };
+                match Profile::load(path) {
+                    Ok(profile) => {
+                        this.update(&mut async_cx, |luna, cx| luna.apply_profile(profile, cx))
+                            .ok();
+                    }
+                    Err(err) => {
+                        async_cx
+                            .update(|cx| {
+                                Toasts::show(
+                                    Notification::error("Couldn't import settings profile")
+                                        .with_detail(format!("{err:#}")),
+                                    cx,
+                                )
+                            })
+                            .ok();
+                    }
+                }
+            })
+            .detach();
+    }
+
+    fn apply_profile(&mut self, profile: Profile, cx: &mut Context<Self>) {
+        profile.apply_to_app(cx);
+
+        let panels = &profile.panels;
+        self.focus_mode_restore = None;
+        self.sidebar_visible = panels.sidebar_visible;
+        self.inspector_visible = panels.inspector_visible;
+        self.navigator_visible = panels.navigator_visible;
+        self.sidebar.update(cx, |sidebar, cx| {
+            sidebar.set_width(panels.sidebar_width, cx)
+        });
+        self.inspector.update(cx, |inspector, cx| {
+            inspector.set_width(panels.inspector_width, cx)
+        });
+        self.app_state.update(cx, |state, cx| {
+            // Whether the document is view only is up to the document, not the profile
+            state.document_settings = DocumentSettings {
+                view_only: state.document_settings.view_only,
+                ..profile.document_settings
+            };
+            cx.notify();
+        });
+        self.canvas.update(cx, |canvas, cx| canvas.mark_dirty(cx));
+
+        // The profile is applied either way, it just won't be there at the next launch
+        if let Err(err) = profile.save_as_active() {
+            Toasts::show(
+                Notification::error("Couldn't keep settings profile for next launch")
+                    .with_detail(format!("{err:#}")),
+                cx,
+            );
+        }
+        cx.notify();
+    }
+
+    /// Fill the space between the two selected nodes with interpolated steps
+    fn blend(&mut self, _: &Blend, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            if let [from, to] = canvas.top_level_selection()[..] {
+                canvas.blend_nodes(from, to, interpolation::DEFAULT_BLEND_STEPS, cx);
+            }
+        });
+    }
+
+    /// Swap fill and stroke, on the selection and for new elements
+    fn swap_current_colors(
+        &mut self,
+        _: &SwapCurrentColors,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.app_state.update(cx, |state, cx| {
+            state.swap_current_colors();
+            cx.notify();
+        });
+        self.canvas
+            .update(cx, |canvas, cx| canvas.swap_selected_fill_and_stroke(cx));
+    }
+
+    /// Go back to the default fill and stroke, on the selection and for new elements
+    fn reset_current_colors(
+        &mut self,
+        _: &ResetCurrentColors,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let theme = cx.theme().clone();
+        let (fill, stroke) = AppState::default_colors(&theme);
+        self.app_state.update(cx, |state, cx| {
+            state.reset_current_colors(&theme);
+            cx.notify();
+        });
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.set_selected_fill_and_stroke(fill, stroke, cx)
+        });
+    }
+
+    /// Toggle focus mode, hiding every panel so only the canvas remains
+    fn toggle_ui(&mut self, _: &ToggleUI, _window: &mut Window, cx: &mut Context<Self>) {
+        match self.focus_mode_restore.take() {
+            Some((sidebar_visible, inspector_visible)) => {
+                self.sidebar_visible = sidebar_visible;
+                self.inspector_visible = inspector_visible;
+            }
+            None => {
+                self.focus_mode_restore = Some((self.sidebar_visible, self.inspector_visible));
+                self.sidebar_visible = false;
+                self.inspector_visible = false;
+            }
+        }
+        cx.notify();
+    }
+
+    fn detach_layers(&mut self, _: &DetachLayers, _window: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_detached_panel(PanelKind::Layers, cx);
+    }
+
+    fn detach_inspector(
+        &mut self,
+        _: &DetachInspector,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_detached_panel(PanelKind::Inspector, cx);
+    }
+
+    fn handle_inspector_event(
+        &mut self,
+        _: &Entity<Inspector>,
+        event: &InspectorEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let InspectorEvent::Preview(nodes) = event;
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.set_highlighted_nodes(nodes.clone(), cx)
+        });
+    }
+
+    /// Pop a panel out into its own window, or dock it back if it already is
+    fn toggle_detached_panel(&mut self, kind: PanelKind, cx: &mut Context<Self>) {
+        if let Some(handle) = self.detached_panels.remove(&kind) {
+            handle
+                .update(cx, |_, window, _| window.remove_window())
+                .ok();
+            self.dock_panel(kind, cx);
+            return;
+        }
+
+        let content: AnyView = match kind {
+            PanelKind::Layers => self.sidebar.read(cx).layer_list().clone().into(),
+            PanelKind::Inspector => self.inspector.clone().into(),
+        };
+
+        let options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(kind.default_width()), px(640.)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some(kind.title().into()),
+                appears_transparent: true,
+                traffic_light_position: Some(point(px(8.0), px(8.0))),
+            }),
+            ..Default::default()
+        };
+
+        let luna = cx.entity().downgrade();
+        let handle = cx.open_window(options, |window, cx| {
+            // Closing the panel window docks the panel again
+            window.on_window_should_close(cx, move |_window, cx| {
+                luna.update(cx, |luna, cx| {
+                    luna.detached_panels.remove(&kind);
+                    luna.dock_panel(kind, cx);
+                })
+                .ok();
+                true
+            });
+            cx.new(|_| DetachedPanel::new(kind, content))
+        });
+
+        match handle {
+            Ok(handle) => {
+                self.detached_panels.insert(kind, handle);
+                if kind == PanelKind::Layers {
+                    self.sidebar
+                        .update(cx, |sidebar, cx| sidebar.set_layers_detached(true, cx));
+                }
+                cx.notify();
+            }
+            Err(err) => Toasts::show(
+                Notification::error(format!("Couldn't open {} window", kind.title()))
+                    .with_detail(format!("{err:#}")),
+                cx,
+            ),
+        }
+    }
+
+    fn dock_panel(&mut self, kind: PanelKind, cx: &mut Context<Self>) {
+        if kind == PanelKind::Layers {
+            self.sidebar
+                .update(cx, |sidebar, cx| sidebar.set_layers_detached(false, cx));
+        }
+        cx.notify();
+    }
+
+    fn toggle_full_screen(
+        &mut self,
+        _: &ToggleFullScreen,
+        window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+        window.toggle_fullscreen();
+    }
+
+    fn toggle_color_target(
+        &mut self,
+        _: &ToggleColorTarget,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.app_state.update(cx, |state, cx| {
+            state.color_target = state.color_target.toggle();
+            cx.notify();
+        });
+    }
+
+    fn toggle_grid(&mut self, _: &ToggleGrid, _window: &mut Window, cx: &mut Context<Self>) {
+        self.update_grid(cx, |grid| grid.visible = !grid.visible);
+    }
+
+    fn toggle_snap_to_grid(
+        &mut self,
+        _: &ToggleSnapToGrid,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_grid(cx, |grid| grid.snap = !grid.snap);
+    }
+
+    fn add_guides_from_selection(
+        &mut self,
+        _: &AddGuidesFromSelection,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.canvas
+            .update(cx, |canvas, cx| canvas.add_guides_from_selection(cx));
+    }
+
+    fn clear_guides(&mut self, _: &ClearGuides, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| canvas.clear_guides(cx));
+    }
+
+    fn exit_solo(&mut self, _: &ExitSolo, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| canvas.clear_solo(cx));
+    }
+
+    fn toggle_follow_selection(
+        &mut self,
+        _: &ToggleFollowSelection,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.app_state.update(cx, |state, cx| {
+            state.follow_selection = !state.follow_selection;
+            cx.notify();
+        });
+    }
+
+    fn toggle_snap_to_layers(
+        &mut self,
+        _: &ToggleSnapToLayers,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.app_state.update(cx, |state, cx| {
+            state.snap_to_layers = !state.snap_to_layers;
+            cx.notify();
+        });
+    }
+
+    fn toggle_snap_anchors_to_pixels(
+        &mut self,
+        _: &ToggleSnapAnchorsToPixels,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_path_snapping(cx, |snapping| snapping.pixels = !snapping.pixels);
+    }
+
+    fn toggle_snap_anchors_to_guides(
+        &mut self,
+        _: &ToggleSnapAnchorsToGuides,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_path_snapping(cx, |snapping| snapping.guides = !snapping.guides);
+    }
+
+    fn toggle_snap_anchors_to_anchors(
+        &mut self,
+        _: &ToggleSnapAnchorsToAnchors,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_path_snapping(cx, |snapping| snapping.anchors = !snapping.anchors);
+    }
+
+    fn update_path_snapping(
+        &mut self,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut PathSnapping),
+    ) {
+        self.app_state.update(cx, |state, cx| {
+            update(&mut state.path_snapping);
+            cx.notify();
+        });
+    }
+
+    fn toggle_rulers(&mut self, _: &ToggleRulers, _window: &mut Window, cx: &mut Context<Self>) {
+        self.app_state.update(cx, |state, cx| {
+            state.show_rulers = !state.show_rulers;
+            cx.notify();
+        });
+    }
+
+    /// Leave the selected layers out of what dragged layers snap to, or let them be
+    /// snapped to again when they all already are left out
+    fn toggle_snap_exclusion(
+        &mut self,
+        _: &ToggleSnapExclusion,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.canvas.update(cx, |canvas, cx| {
+            let selected: Vec<NodeId> = canvas.selected_nodes().iter().copied().collect();
+            if selected.is_empty() {
+                return;
+            }
+            let excluded = !selected.iter().all(|node_id| {
+                canvas
+                    .get_node(*node_id)
+                    .is_some_and(|node| node.snap_excluded)
+            });
+            canvas.set_snap_excluded(&selected, excluded, cx);
+        });
+    }
+
+    fn toggle_restore_session(
+        &mut self,
+        _: &ToggleRestoreSession,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.window_state.restore_session = !self.window_state.restore_session;
+        if !self.save_window_state_or_notify(cx) {
+            return;
+        }
+        let message = if self.window_state.restore_session {
+            "The open document will be reopened at launch"
+        } else {
+            "Luna will start with an untitled document"
+        };
+        Toasts::show(Notification::info(message), cx);
+    }
+
+    /// Switch to the next low power setting: on battery, always, never
+    fn toggle_low_power_mode(
+        &mut self,
+        _: &ToggleLowPowerMode,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let setting = cx.global::<GlobalPower>().0.setting.next();
+        power::set_setting(setting, cx);
+        if self.save_window_state_or_notify(cx) {
+            Toasts::show(Notification::info(setting.description()), cx);
+        }
+    }
+
+    /// Mark the document view only, or make it editable again
+    ///
+    /// Like the other document settings it's written with the next save, so a view only
+    /// document is marked before it's shared rather than when it's opened.
+    fn toggle_view_only(
+        &mut self,
+        _: &ToggleViewOnly,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let view_only = self.app_state.update(cx, |state, cx| {
+            state.document_settings.view_only = !state.document_settings.view_only;
+            cx.notify();
+            state.document_settings.view_only
+        });
+        if view_only {
+            if self.color_picker.take().is_some() {
+                window.focus(&self.focus_handle);
+            }
+            self.put_away_editing_tools(cx);
+        }
+        Toasts::show(
+            Notification::info(if view_only {
+                "The document is view only"
+            } else {
+                "The document can be edited again"
+            }),
+            cx,
+        );
+        cx.notify();
+    }
+
+    /// Leave whatever editing was under way once the document turns out to be view only
+    fn put_away_editing_tools(&mut self, cx: &mut Context<Self>) {
+        if !self.canvas.read(cx).is_view_only(cx) {
+            return;
+        }
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.finish_pen_path(cx);
+            canvas.stop_editing_path(cx);
+        });
+        if !matches!(*cx.active_tool().clone(), Tool::Selection | Tool::Hand) {
+            cx.set_global(GlobalTool(Arc::new(Tool::Selection)));
+        }
+        // The inspector edits, so it's docked back where view only hides it
+        if self.detached_panels.contains_key(&PanelKind::Inspector) {
+            self.toggle_detached_panel(PanelKind::Inspector, cx);
+        }
+    }
+
+    fn toggle_frame_navigator(
+        &mut self,
+        _: &ToggleFrameNavigator,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.navigator_visible = !self.navigator_visible;
+        cx.notify();
+    }
+
+    fn update_grid(&mut self, cx: &mut Context<Self>, update: impl FnOnce(&mut GridSettings)) {
+        self.app_state.update(cx, |state, cx| {
+            update(&mut state.document_settings.grid);
+            cx.notify();
+        });
+        self.canvas.update(cx, |canvas, cx| canvas.mark_dirty(cx));
+    }
+
+    /// Switch to `render_mode`, or back to the default one if it's already on
+    fn toggle_render_mode(&mut self, render_mode: RenderMode, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            let render_mode = if canvas.render_mode() == render_mode {
+                RenderMode::Default
+            } else {
+                render_mode
+            };
+            canvas.set_render_mode(render_mode, cx);
+        });
+    }
+
+    fn toggle_wireframe(
+        &mut self,
+        _: &ToggleWireframe,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_render_mode(RenderMode::Wireframe, cx);
+    }
+
+    fn toggle_outline(&mut self, _: &ToggleOutline, _window: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_render_mode(RenderMode::Outline, cx);
+    }
+
+    fn toggle_pixel_preview_1x(
+        &mut self,
+        _: &TogglePixelPreview1x,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_render_mode(RenderMode::PixelPreview(ExportScale::X1), cx);
+    }
+
+    fn toggle_pixel_preview_2x(
+        &mut self,
+        _: &TogglePixelPreview2x,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_render_mode(RenderMode::PixelPreview(ExportScale::X2), cx);
+    }
+
+    fn toggle_preview(&mut self, _: &TogglePreview, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            let previewing = !canvas.is_previewing();
+            canvas.set_previewing(previewing, cx);
+        });
+    }
+
+    /// Preview as if the OS asked for less motion, to check the prototype still reads
+    /// without animation
+    fn toggle_simulate_reduced_motion(
+        &mut self,
+        _: &ToggleSimulateReducedMotion,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.canvas.update(cx, |canvas, cx| {
+            let mut settings = canvas.simulated_settings();
+            settings.reduced_motion = !settings.reduced_motion;
+            canvas.set_simulated_settings(settings, cx);
+        });
+    }
+
+    /// Preview as if the OS asked for more contrast, to check the design still reads
+    fn toggle_simulate_increased_contrast(
+        &mut self,
+        _: &ToggleSimulateIncreasedContrast,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.canvas.update(cx, |canvas, cx| {
+            let mut settings = canvas.simulated_settings();
+            settings.increased_contrast = !settings.increased_contrast;
+            canvas.set_simulated_settings(settings, cx);
+        });
+    }
+
+    /// Draw node bounds and the last hit test over the canvas, for tracking down clicks
+    /// that pick the wrong node
+    fn toggle_hit_test_debug(
+        &mut self,
+        _: &ToggleHitTestDebug,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.canvas.update(cx, |canvas, cx| {
+            let debugging = !canvas.is_debugging_hit_tests();
+            canvas.set_debugging_hit_tests(debugging, cx);
+        });
+    }
+
+    fn go_to_node(&mut self, _: &GoToNode, window: &mut Window, cx: &mut Context<Self>) {
+        if self.quick_open.is_some() {
+            return;
+        }
+
+        let quick_open = cx.new(|cx| QuickOpen::new(&self.canvas, cx));
+        let subscription = cx.subscribe_in(&quick_open, window, Self::handle_quick_open_event);
+        window.focus(&quick_open.focus_handle(cx));
+        self.quick_open = Some((quick_open, subscription));
+        cx.notify();
+    }
+
+    fn new_page(&mut self, _: &NewPage, _window: &mut Window, cx: &mut Context<Self>) {
+        self.close_version_diff(cx);
+        self.canvas.update(cx, |canvas, cx| canvas.add_page(cx));
+    }
+
+    fn next_page(&mut self, _: &NextPage, _window: &mut Window, cx: &mut Context<Self>) {
+        let canvas = self.canvas.read(cx);
+        let next = canvas.current_page() + 1;
+        if next < canvas.page_count() {
+            self.close_version_diff(cx);
+            self.canvas
+                .update(cx, |canvas, cx| canvas.show_page(next, cx));
+        }
+    }
+
+    fn previous_page(&mut self, _: &PreviousPage, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(previous) = self.canvas.read(cx).current_page().checked_sub(1) else {
+            return;
+        };
+        self.close_version_diff(cx);
+        self.canvas
+            .update(cx, |canvas, cx| canvas.show_page(previous, cx));
+    }
+
+    fn rename_page(&mut self, _: &RenamePage, window: &mut Window, cx: &mut Context<Self>) {
+        // The name is typed into the page list, which needs to be showing
+        self.sidebar_visible = true;
+        let index = self.canvas.read(cx).current_page();
+        let page_list = self.sidebar.read(cx).page_list().clone();
+        page_list.update(cx, |page_list, cx| {
+            page_list.start_renaming(index, window, cx)
+        });
+        cx.notify();
+    }
+
+    /// Delete the page on the canvas, asking first when there's anything on it since
+    /// deleting a page can't be undone
+    fn delete_page(&mut self, _: &DeletePage, window: &mut Window, cx: &mut Context<Self>) {
+        let canvas = self.canvas.read(cx);
+        if canvas.page_count() < 2 {
+            Toasts::show(Notification::info("A document always keeps one page"), cx);
+            return;
+        }
+        let index = canvas.current_page();
+        let Some(name) = canvas.page_names().nth(index).map(str::to_string) else {
+            return;
+        };
+        if canvas.is_page_empty(index) {
+            self.close_version_diff(cx);
+            self.canvas
+                .update(cx, |canvas, cx| canvas.remove_page(index, cx));
+            return;
+        }
+
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &format!("Delete “{name}” and everything on it?"),
+            Some("Deleting a page can't be undone."),
+            &["Delete", "Cancel"],
+            cx,
+        );
+        let this = cx.entity().downgrade();
+        let mut async_cx = cx.to_async();
+        cx.foreground_executor()
+            .spawn(async move {
+                if let Ok(0) = answer.await {
+                    this.update(&mut async_cx, |this, cx| {
+                        this.close_version_diff(cx);
+                        this.canvas
+                            .update(cx, |canvas, cx| canvas.remove_page(index, cx));
+                    })
+                    .ok();
+                }
+            })
+            .detach();
+    }
+
+    fn handle_quick_open_event(
+        &mut self,
+        _: &Entity<QuickOpen>,
+        event: &QuickOpenEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let QuickOpenEvent::Confirmed(node_id) = *event {
+            self.reveal_node(node_id, cx);
+        }
+
+        self.quick_open = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn show_document_info(
+        &mut self,
+        _: &ShowDocumentInfo,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.document_info.is_some() {
+            return;
+        }
+
+        let settings = self.app_state.read(cx).document_settings;
+        let document_info = cx.new(|cx| DocumentInfo::new(&self.canvas, settings, cx));
+        let subscription =
+            cx.subscribe_in(&document_info, window, Self::handle_document_info_event);
+        window.focus(&document_info.focus_handle(cx));
+        self.document_info = Some((document_info, subscription));
+        cx.notify();
+    }
+
+    fn handle_document_info_event(
+        &mut self,
+        _: &Entity<DocumentInfo>,
+        event: &DocumentInfoEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let DocumentInfoEvent::Reveal(node_id) = *event {
+            self.reveal_node(node_id, cx);
+        }
+
+        self.document_info = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Compare the document with the version last saved to disk, marking the
+    /// differences on the canvas and listing them in a panel
+    fn compare_with_saved(
+        &mut self,
+        _: &CompareWithSaved,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.version_diff.is_some() {
+            return;
+        }
+
+        let canvas = self.canvas.read(cx);
+        let Some(path) = canvas.document_path().map(Path::to_path_buf) else {
+            Toasts::show(
+                Notification::error("There's no saved version to compare with")
+                    .with_detail("The document hasn't been saved yet"),
+                cx,
+            );
+            return;
+        };
+        let saved = match Document::load(&path, canvas.document_password()) {
+            Ok(saved) => saved,
+            Err(err) => {
+                Toasts::show(
+                    Notification::error("Couldn't read the saved version")
+                        .with_detail(format!("{err:#}")),
+                    cx,
+                );
+                return;
+            }
+        };
+        // Only the page on the canvas is compared, the panel can only reveal its nodes
+        let index = canvas.current_page();
+        let saved = saved
+            .page(index)
+            .unwrap_or_else(|| Document::from_pages(saved.settings, Vec::new()));
+        let current = canvas
+            .to_document(cx)
+            .page(index)
+            .expect("the page on the canvas is in its document");
+        let diff = DocumentDiff::between(&saved, &current);
+        if diff.is_empty() {
+            Toasts::show(
+                Notification::info("No changes since the document was saved"),
+                cx,
+            );
+            return;
+        }
+
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.set_version_diff(Some(diff.clone()), cx)
+        });
+        let version_diff = cx.new(|cx| VersionDiff::new(diff, &self.canvas, cx));
+        let subscription = cx.subscribe_in(&version_diff, window, Self::handle_version_diff_event);
+        window.focus(&version_diff.focus_handle(cx));
+        self.version_diff = Some((version_diff, subscription));
+        cx.notify();
+    }
+
+    fn handle_version_diff_event(
+        &mut self,
+        _: &Entity<VersionDiff>,
+        event: &VersionDiffEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match *event {
+            // The panel stays open to go through the rest of the changes
+            VersionDiffEvent::Reveal(node_id) => self.reveal_node(node_id, cx),
+            VersionDiffEvent::Dismissed => {
+                self.canvas
+                    .update(cx, |canvas, cx| canvas.set_version_diff(None, cx));
+                self.version_diff = None;
+                window.focus(&self.focus_handle);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Close the list of changes since the last save, whose nodes are on the page
+    /// being put away
+    fn close_version_diff(&mut self, cx: &mut Context<Self>) {
+        if self.version_diff.take().is_some() {
+            self.canvas
+                .update(cx, |canvas, cx| canvas.set_version_diff(None, cx));
+            cx.notify();
+        }
+    }
+
+    fn show_library(&mut self, _: &ShowLibrary, window: &mut Window, cx: &mut Context<Self>) {
+        if self.library_browser.is_some() {
+            return;
+        }
+
+        let library = match load_user_library() {
+            Ok(library) => library,
+            Err(err) => {
+                Toasts::show(
+                    Notification::error("Couldn't read the library")
+                        .with_detail(format!("{err:#}")),
+                    cx,
+                );
+                return;
+            }
+        };
+        let library_browser = cx.new(|cx| LibraryBrowser::new(library, cx));
+        let subscription =
+            cx.subscribe_in(&library_browser, window, Self::handle_library_browser_event);
+        window.focus(&library_browser.focus_handle(cx));
+        self.library_browser = Some((library_browser, subscription));
+        cx.notify();
+    }
+
+    fn handle_library_browser_event(
+        &mut self,
+        _: &Entity<LibraryBrowser>,
+        event: &LibraryBrowserEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let LibraryBrowserEvent::Recolor(item, color) = event {
+            self.recolor_token(item, *color, cx);
+        } else if let LibraryBrowserEvent::Insert(item) = event {
+            let target = self.app_state.read(cx).color_target;
+            let result = match item.content {
+                LibraryContent::Component { .. } => self
+                    .canvas
+                    .update(cx, |canvas, cx| canvas.insert_component(item, cx)),
+                _ if self.canvas.read(cx).selected_nodes().is_empty() => {
+                    let message = format!("Select the layers to apply {} to", item.name);
+                    Toasts::show(Notification::error(message), cx);
+                    Ok(())
+                }
+                _ => self
+                    .canvas
+                    .update(cx, |canvas, cx| canvas.apply_library_item(item, target, cx)),
+            };
+            if let Err(err) = result {
+                Toasts::show(
+                    Notification::error(format!("Couldn't insert {}", item.name))
+                        .with_detail(format!("{err:#}")),
+                    cx,
+                );
+            }
+        }
+
+        self.library_browser = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Give a color token a new color and update every layer of the document using it
+    ///
+    /// Other documents find out about the new revision when they're next activated, like
+    /// with anything else published.
+    fn recolor_token(&mut self, item: &LibraryItem, color: Hsla, cx: &mut Context<Self>) {
+        let result = load_user_library().and_then(|mut library| {
+            library.recolor_token(&item.id, color)?;
+            save_user_library(&library)?;
+            Ok(library)
+        });
+        let library = match result {
+            Ok(library) => library,
+            Err(err) => {
+                Toasts::show(
+                    Notification::error(format!("Couldn't change {}", item.name))
+                        .with_detail(format!("{err:#}")),
+                    cx,
+                );
+                return;
+            }
+        };
+        self.share_library(format!("Change {}", item.name), cx);
+
+        let updates: Vec<LibraryUpdate> = library
+            .updates_for(self.canvas.read(cx).nodes())
+            .into_iter()
+            .filter(|update| update.item.id == item.id)
+            .collect();
+        let layers: HashSet<NodeId> = updates.iter().map(|update| update.node_id).collect();
+        let result = if updates.is_empty() {
+            Ok(())
+        } else {
+            self.canvas
+                .update(cx, |canvas, cx| canvas.apply_library_updates(&updates, cx))
+        };
+        let notification = match result {
+            Ok(()) => Notification::info(match layers.len() {
+                0 => format!("Changed {}", item.name),
+                1 => format!("Changed {} on 1 layer", item.name),
+                count => format!("Changed {} on {count} layers", item.name),
+            }),
+            Err(err) => Notification::error(format!("Couldn't update layers using {}", item.name))
+                .with_detail(format!("{err:#}")),
+        };
+        Toasts::show(notification, cx);
+    }
+
+    /// The layer to publish from, `None` after telling the user when there isn't
+    /// exactly one selected
+    fn layer_to_publish(&self, cx: &mut Context<Self>) -> Option<FrameNode> {
+        let canvas = self.canvas.read(cx);
+        let selected: Vec<NodeId> = canvas.selected_nodes().iter().copied().collect();
+        let node = match selected[..] {
+            [node_id] => canvas.get_node(node_id).cloned(),
+            _ => None,
+        };
+        if node.is_none() {
+            Toasts::show(Notification::error("Select one layer to publish"), cx);
+        }
+        node
+    }
+
+    /// Publish to the user's library, returning the link to the published item
+    fn publish_to_library(
+        &mut self,
+        existing: Option<&LibraryLink>,
+        name: String,
+        content: LibraryContent,
+        cx: &mut Context<Self>,
+    ) -> Option<LibraryLink> {
+        let result = load_user_library().and_then(|mut library| {
+            let link = library.publish(existing, name.clone(), content);
+            save_user_library(&library)?;
+            Ok(link)
+        });
+        match result {
+            Ok(link) => {
+                let (message, commit_message) =
+                    if existing.is_some_and(|existing| existing.item == link.item) {
+                        (
+                            format!("Updated {name} in the library"),
+                            format!("Update {name}"),
+                        )
+                    } else {
+                        (
+                            format!("Published {name} to the library"),
+                            format!("Publish {name}"),
+                        )
+                    };
+                Toasts::show(Notification::info(message), cx);
+                self.share_library(commit_message, cx);
+                Some(link)
+            }
+            Err(err) => {
+                Toasts::show(
+                    Notification::error(format!("Couldn't publish {name}"))
+                        .with_detail(format!("{err:#}")),
+                    cx,
+                );
+                None
+            }
+        }
+    }
+
+    /// Commit and push a change to the library when it's kept in a git repository
+    ///
+    /// Pushing goes over the network, so git runs in the background. The change is
+    /// already saved in the working tree, failing only keeps it from the rest of the
+    /// team.
+    fn share_library(&self, message: String, cx: &mut Context<Self>) {
+        let Some(repository) = LibraryRepository::configured() else {
+            return;
+        };
+        let shared = cx.background_executor().spawn(async move {
+            if repository.commit(LIBRARY_FILE_NAME, &message)? {
+                repository.push()?;
+            }
+            anyhow::Ok(())
+        });
+        let async_cx = cx.to_async();
+        cx.foreground_executor()
+            .spawn(async move {
+                if let Err(err) = shared.await {
+                    let notification = Notification::error("Couldn't share the library change")
+                        .with_detail(format!("{err:#}"));
+                    async_cx.update(|cx| Toasts::show(notification, cx)).ok();
+                }
+            })
+            .detach();
+    }
+
+    /// Publish the selected layer and its children as a component, the layer becoming
+    /// its first instance. Publishing an instance updates its component.
+    fn publish_component(
+        &mut self,
+        _: &PublishComponent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(root) = self.layer_to_publish(cx) else {
+            return;
+        };
+        let mut nodes = self.canvas.read(cx).copy_selection();
+        // Components are stored root first, wherever it is in the node list
+        if let Some(index) = nodes.iter().position(|node| node.id == root.id) {
+            let root = nodes.remove(index);
+            nodes.insert(0, root);
+        }
+        let content = LibraryContent::Component {
+            nodes: nodes.iter().map(SavedNode::from_frame).collect(),
+        };
+
+        let existing = root.component.as_ref();
+        if let Some(link) = self.publish_to_library(existing, root.name(), content, cx) {
+            let components = vec![(root.id, Some(link))];
+            self.canvas.update(cx, |canvas, cx| {
+                canvas.execute(CanvasCommand::SetComponents(components), cx)
+            });
+            self.check_library_updates(cx);
+        }
+    }
+
+    /// Publish the style of the selected layer, which is then linked to it
+    fn publish_style(&mut self, _: &PublishStyle, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(node) = self.layer_to_publish(cx) else {
+            return;
+        };
+        let mut style = NodeStyle::of(&node);
+        let content = LibraryContent::Style {
+            style: SavedStyle::from_style(&style),
+        };
+
+        let existing = node.style_links.style.as_ref();
+        if let Some(link) = self.publish_to_library(existing, node.name(), content, cx) {
+            style.links = StyleLinks {
+                style: Some(link),
+                ..Default::default()
+            };
+            self.canvas.update(cx, |canvas, cx| {
+                canvas.execute(CanvasCommand::SetStyles(vec![(node.id, style)]), cx)
+            });
+            self.check_library_updates(cx);
+        }
+    }
+
+    /// Publish the fill or the stroke color of the selected layer, whichever colors
+    /// are applied to, as a color token
+    fn publish_color_token(
+        &mut self,
+        _: &PublishColorToken,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(node) = self.layer_to_publish(cx) else {
+            return;
+        };
+        let target = self.app_state.read(cx).color_target;
+        let (color, existing, kind) = match target {
+            ColorTarget::Fill => (node.fill, &node.style_links.fill, "Fill"),
+            ColorTarget::Stroke => (node.border_color, &node.style_links.border, "Stroke"),
+        };
+        let Some(color) = color else {
+            let message = format!("{} has no {} color", node.name(), kind.to_lowercase());
+            Toasts::show(Notification::error(message), cx);
+            return;
+        };
+        let content = LibraryContent::ColorToken {
+            color: ColorFormat::Hex.format(color),
+        };
+
+        let name = format!("{} {kind}", node.name());
+        if let Some(link) = self.publish_to_library(existing.as_ref(), name, content, cx) {
+            let mut style = NodeStyle::of(&node);
+            match target {
+                ColorTarget::Fill => style.links.fill = Some(link),
+                ColorTarget::Stroke => style.links.border = Some(link),
+            }
+            self.canvas.update(cx, |canvas, cx| {
+                canvas.execute(CanvasCommand::SetStyles(vec![(node.id, style)]), cx)
+            });
+            self.check_library_updates(cx);
+        }
+    }
+
+    /// Tell the user when library items the document uses have been published again
+    ///
+    /// The library is only read when it changed on disk since the last check, so the
+    /// same updates aren't brought up every time the window is activated.
+    fn check_library_updates(&mut self, cx: &mut Context<Self>) {
+        let modified = user_library_path()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .and_then(|metadata| metadata.modified().ok());
+        if modified.is_none() || modified == self.library_checked {
+            return;
+        }
+        self.library_checked = modified;
+
+        let library = match load_user_library() {
+            Ok(library) => library,
+            Err(err) => {
+                Toasts::show(
+                    Notification::error("Couldn't read the library")
+                        .with_detail(format!("{err:#}")),
+                    cx,
+                );
+                return;
+            }
+        };
+        let updates = library.updates_for(self.canvas.read(cx).nodes());
+        let layers: HashSet<NodeId> = updates.iter().map(|update| update.node_id).collect();
+        let message = match layers.len() {
+            0 => return,
+            1 => "A layer uses library items that have changed".to_string(),
+            count => format!("{count} layers use library items that have changed"),
+        };
+        Toasts::show(
+            Notification::info(message).with_action("Review", UpdateFromLibrary),
+            cx,
+        );
+    }
+
+    /// Open the review of the updates to library items the document uses
+    fn update_from_library(
+        &mut self,
+        _: &UpdateFromLibrary,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.library_review.is_some() {
+            return;
+        }
+
+        let updates = match load_user_library() {
+            Ok(library) => library.updates_for(self.canvas.read(cx).nodes()),
+            Err(err) => {
+                Toasts::show(
+                    Notification::error("Couldn't read the library")
+                        .with_detail(format!("{err:#}")),
+                    cx,
+                );
+                return;
+            }
+        };
+        if updates.is_empty() {
+            Toasts::show(
+                Notification::info("Everything from the library is up to date"),
+                cx,
+            );
+            return;
+        }
+
+        let library_review = cx.new(|cx| LibraryReview::new(updates, &self.canvas, cx));
+        let subscription =
+            cx.subscribe_in(&library_review, window, Self::handle_library_review_event);
+        window.focus(&library_review.focus_handle(cx));
+        self.library_review = Some((library_review, subscription));
+        cx.notify();
+    }
+
+    fn handle_library_review_event(
+        &mut self,
+        _: &Entity<LibraryReview>,
+        event: &LibraryReviewEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let LibraryReviewEvent::Apply(updates) = event {
+            let result = if updates.is_empty() {
+                Ok(())
+            } else {
+                self.canvas
+                    .update(cx, |canvas, cx| canvas.apply_library_updates(updates, cx))
+            };
+            if let Err(err) = result {
+                Toasts::show(
+                    Notification::error("Couldn't update from the library")
+                        .with_detail(format!("{err:#}")),
+                    cx,
+                );
+            }
+        }
+
+        self.library_review = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Pull what others published to the library repository, then offer the updates
+    fn pull_library(&mut self, _: &PullLibrary, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repository) = LibraryRepository::configured() else {
+            Toasts::show(
+                Notification::error("The library isn't kept in a git repository")
+                    .with_action("Use Git Repository…", UseLibraryRepository),
+                cx,
+            );
+            return;
+        };
+
+        let pulled = cx.background_executor().spawn(async move {
+            let library_path = repository.library_path(LIBRARY_FILE_NAME);
+            let modified = || {
+                std::fs::metadata(&library_path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+            };
+            let before = modified();
+            repository.pull()?;
+            anyhow::Ok(modified() != before)
+        });
+        let this = cx.entity().downgrade();
+        let mut async_cx = cx.to_async();
+        cx.foreground_executor()
+            .spawn(async move {
+                let result = pulled.await;
+                this.update(&mut async_cx, |luna, cx| match result {
+                    Ok(true) => {
+                        Toasts::show(Notification::info("Pulled library updates"), cx);
+                        luna.check_library_updates(cx);
+                    }
+                    Ok(false) => {
+                        Toasts::show(Notification::info("The library is up to date"), cx);
+                    }
+                    Err(err) => {
+                        Toasts::show(
+                            Notification::error("Couldn't pull library updates")
+                                .with_detail(format!("{err:#}")),
+                            cx,
+                        );
+                    }
+                })
+                .ok();
+            })
+            .detach();
+    }
+
+    /// Keep the library in a git repository the user picks, shared with whoever else
+    /// uses it
+    fn use_library_repository(
+        &mut self,
+        _: &UseLibraryRepository,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+        });
+        let this = cx.entity().downgrade();
+        let mut async_cx = cx.to_async();
+        cx.foreground_executor()
+            .spawn(async move {
+                let Ok(Ok(Some(paths))) = paths.await else {
+                    return;
+                };
+                let Some(path) = paths.first() else {
+                    return;
+                };
+                let result = LibraryRepository::open(path).and_then(|repository| {
+                    LibraryRepository::configure(Some(&repository))?;
+                    Ok(repository)
+                });
+                this.update(&mut async_cx, |luna, cx| {
+                    let notification = match result {
+                        Ok(repository) => Notification::info(format!(
+                            "The library is now kept in {}",
+                            repository.path().display()
+                        )),
+                        Err(err) => Notification::error("Couldn't use the repository")
+                            .with_detail(format!("{err:#}")),
+                    };
+                    Toasts::show(notification, cx);
+                    luna.library_checked = None;
+                    luna.check_library_updates(cx);
+                })
+                .ok();
+            })
+            .detach();
+    }
+
+    /// Go back to keeping the library in the config directory
+    fn use_local_library(
+        &mut self,
+        _: &UseLocalLibrary,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if LibraryRepository::configured().is_none() {
+            return;
+        }
+        let notification = match LibraryRepository::configure(None) {
+            Ok(()) => Notification::info("The library is now kept on this computer only"),
+            Err(err) => Notification::error("Couldn't stop using the repository")
+                .with_detail(format!("{err:#}")),
+        };
+        Toasts::show(notification, cx);
+        self.library_checked = None;
+        self.check_library_updates(cx);
+    }
+
+    fn show_design_lint(
+        &mut self,
+        _: &ShowDesignLint,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.design_lint.is_some() {
+            return;
+        }
+
+        let design_lint = cx.new(|cx| DesignLint::new(&self.canvas, cx));
+        let subscription = cx.subscribe_in(&design_lint, window, Self::handle_design_lint_event);
+        window.focus(&design_lint.focus_handle(cx));
+        self.design_lint = Some((design_lint, subscription));
+        cx.notify();
+    }
+
+    fn handle_design_lint_event(
+        &mut self,
+        _: &Entity<DesignLint>,
+        event: &DesignLintEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let DesignLintEvent::Reveal(node_id) = *event {
+            self.reveal_node(node_id, cx);
+        }
+
+        self.design_lint = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn rename_selection(
+        &mut self,
+        _: &RenameSelection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.batch_rename.is_some() || self.canvas.read(cx).selected_nodes().is_empty() {
+            return;
+        }
+
+        let batch_rename = cx.new(|cx| BatchRename::new(&self.canvas, cx));
+        let subscription = cx.subscribe_in(&batch_rename, window, Self::handle_batch_rename_event);
+        window.focus(&batch_rename.focus_handle(cx));
+        self.batch_rename = Some((batch_rename, subscription));
+        cx.notify();
+    }
+
+    fn handle_batch_rename_event(
+        &mut self,
+        _: &Entity<BatchRename>,
+        event: &BatchRenameEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let BatchRenameEvent::Confirmed(names) = event {
+            self.canvas
+                .update(cx, |canvas, cx| canvas.rename_nodes(names, cx));
+        }
+
+        self.batch_rename = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Open the color picker where the pointer is, next to the swatch or field it was
+    /// opened from
+    fn show_color_picker(
+        &mut self,
+        _: &ShowColorPicker,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.color_picker.is_some() {
+            return;
+        }
+
+        let position = window.mouse_position();
+        let color_picker = cx
+            .new(|cx| ColorPicker::new(self.app_state.clone(), self.canvas.clone(), position, cx));
+        let subscription = cx.subscribe_in(&color_picker, window, Self::handle_color_picker_event);
+        window.focus(&color_picker.focus_handle(cx));
+        self.color_picker = Some((color_picker, subscription));
+        cx.notify();
+    }
+
+    fn handle_color_picker_event(
+        &mut self,
+        _: &Entity<ColorPicker>,
+        event: &ColorPickerEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ColorPickerEvent::Dismissed = event;
+        self.color_picker = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Open the context menu where the pointer is, with what can be done to the selection
+    fn show_context_menu(
+        &mut self,
+        _: &ShowContextMenu,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let canvas = self.canvas.read(cx);
+        let editable = !canvas.is_view_only(cx);
+        let selection: Vec<&FrameNode> = canvas
+            .selected_nodes()
+            .iter()
+            .filter_map(|node_id| canvas.get_node(*node_id))
+            .collect();
+        let selected = !selection.is_empty();
+        let locked = selected && selection.iter().all(|node| node.locked);
+        let hidden = selected && selection.iter().all(|node| node.hidden);
+
+        let edits = editable && selected;
+        let entries = vec![
+            ContextMenuEntry::action("Cut", Cut).enabled(edits),
+            ContextMenuEntry::action("Copy", Copy).enabled(selected),
+            ContextMenuEntry::action("Paste", Paste).enabled(editable),
+            ContextMenuEntry::action("Duplicate", Duplicate).enabled(edits),
+            ContextMenuEntry::action("Delete", Delete).enabled(edits),
+            ContextMenuEntry::Separator,
+            ContextMenuEntry::action("Bring to Front", BringToFront).enabled(edits),
+            ContextMenuEntry::action("Send to Back", SendToBack).enabled(edits),
+            ContextMenuEntry::Separator,
+            ContextMenuEntry::action("Group", Group)
+                .enabled(editable && canvas.can_group_selection()),
+            ContextMenuEntry::action("Ungroup", Ungroup)
+                .enabled(editable && canvas.can_ungroup_selection()),
+            ContextMenuEntry::Separator,
+            ContextMenuEntry::action(if locked { "Unlock" } else { "Lock" }, ToggleLocked)
+                .enabled(edits),
+            ContextMenuEntry::action(if hidden { "Show" } else { "Hide" }, ToggleHidden)
+                .enabled(edits),
+        ];
+
+        let position = window.mouse_position();
+        let return_focus = self.focus_handle.clone();
+        let context_menu = cx.new(|cx| ContextMenu::new(position, entries, return_focus, cx));
+        let subscription = cx.subscribe_in(&context_menu, window, Self::handle_context_menu_event);
+        window.focus(&context_menu.focus_handle(cx));
+        self.context_menu = Some((context_menu, subscription));
+        cx.notify();
+    }
+
+    fn handle_context_menu_event(
+        &mut self,
+        _: &Entity<ContextMenu>,
+        event: &ContextMenuEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ContextMenuEvent::Dismissed = event;
+        self.context_menu = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn edit_link(&mut self, _: &EditLink, window: &mut Window, cx: &mut Context<Self>) {
+        if self.link_editor.is_some() || self.canvas.read(cx).selected_nodes().is_empty() {
+            return;
+        }
+
+        let link_editor = cx.new(|cx| LinkEditor::new(&self.canvas, cx));
+        let subscription = cx.subscribe_in(&link_editor, window, Self::handle_link_editor_event);
+        window.focus(&link_editor.focus_handle(cx));
+        self.link_editor = Some((link_editor, subscription));
+        cx.notify();
+    }
+
+    fn handle_link_editor_event(
+        &mut self,
+        _: &Entity<LinkEditor>,
+        event: &LinkEditorEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let LinkEditorEvent::Confirmed(link) = event {
+            self.canvas.update(cx, |canvas, cx| {
+                let selection = canvas.top_level_selection();
+                canvas.set_links(&selection, link.clone(), cx);
+            });
+        }
+
+        self.link_editor = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn show_shortcuts(&mut self, _: &ShowShortcuts, window: &mut Window, cx: &mut Context<Self>) {
+        if self.shortcuts.is_some() {
+            return;
+        }
+
+        let shortcuts = cx.new(Shortcuts::new);
+        let subscription = cx.subscribe_in(&shortcuts, window, Self::handle_shortcuts_event);
+        window.focus(&shortcuts.focus_handle(cx));
+        self.shortcuts = Some((shortcuts, subscription));
+        cx.notify();
+    }
+
+    fn handle_shortcuts_event(
+        &mut self,
+        _: &Entity<Shortcuts>,
+        _: &ShortcutsEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.shortcuts = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn new_from_template(
+        &mut self,
+        _: &NewFromTemplate,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_template_gallery(false, window, cx);
+    }
+
+    /// Open the template gallery, `onboarding` when it greets a first launch
+    fn show_template_gallery(
+        &mut self,
+        onboarding: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.template_gallery.is_some() {
+            return;
+        }
+
+        let gallery = cx.new(|cx| TemplateGallery::new(onboarding, cx));
+        let subscription = cx.subscribe_in(&gallery, window, Self::handle_template_gallery_event);
+        window.focus(&gallery.focus_handle(cx));
+        self.template_gallery = Some((gallery, subscription));
+        cx.notify();
+    }
+
+    fn handle_template_gallery_event(
+        &mut self,
+        _: &Entity<TemplateGallery>,
+        event: &TemplateGalleryEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let TemplateGalleryEvent::Confirmed(template) = event {
+            self.save_window_state_or_notify(cx);
+            self.canvas
+                .update(cx, |canvas, cx| canvas.load_template(*template, cx));
+            self.window_state.document = None;
+        }
+
+        self.template_gallery = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Select a node on its own and bring it to the center of the viewport
+    fn reveal_node(&mut self, node_id: NodeId, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.deselect_all_nodes(cx);
+            canvas.select_node(node_id);
+            canvas.center_on_node(node_id, cx);
+        });
+    }
+
+    fn handle_cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        let active_tool = *cx.active_tool().clone();
+
+        let canvas = self.canvas.read(cx);
+        if canvas.is_previewing() {
+            self.canvas
+                .update(cx, |canvas, cx| canvas.set_previewing(false, cx));
+        } else if !canvas.pen_entry().is_empty() {
+            self.canvas
+                .update(cx, |canvas, cx| canvas.clear_pen_entry(cx));
+        } else if canvas.pen_path().is_some() {
+            // The pen stays out for the next path
+            self.canvas
+                .update(cx, |canvas, cx| canvas.finish_pen_path(cx));
+        } else if canvas.editing_path().is_some() {
+            self.canvas
+                .update(cx, |canvas, cx| canvas.stop_editing_path(cx));
+        } else if active_tool == Tool::Selection {
+            self.canvas.update(cx, |canvas, cx| {
+                canvas.deselect_all_nodes(cx);
+                canvas.mark_dirty(cx);
+            });
+        } else {
+            cx.dispatch_action(&SelectionTool);
+        }
+    }
+}
+
+impl Render for Luna {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+        let view_only = self.canvas.read(cx).is_view_only(cx);
+        // The sidebar sits over the canvas, the rulers start past it
+        let ruler_inset = if self.sidebar_visible {
+            self.sidebar.read(cx).width() + 1.0
+        } else {
+            0.0
+        };
+
+        let workspace = div()
+            .id("Luna")
+            .key_context("luna")
+            .track_focus(&self.focus_handle(cx))
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .font_family("Berkeley Mono")
+            .text_xs()
+            .bg(theme.tokens.background)
+            .text_color(theme.tokens.text)
+            .border_1()
+            .border_color(gpui::white().alpha(0.08))
+            .rounded(px(16.))
+            .overflow_hidden()
+            .on_key_down(cx.listener(Self::handle_key_down))
+            .on_key_up(cx.listener(Self::handle_key_up))
+            .map(|div| match *cx.active_tool().clone() {
+                _ if self.canvas.read(cx).space_held() => div.cursor_grab(),
+                Tool::Hand => div.cursor_grab(),
+                Tool::Frame | Tool::Line | Tool::TextCursor | Tool::Eyedropper => {
+                    div.cursor_crosshair()
+                }
+                _ => div.cursor_default(),
+            })
+            .on_action(cx.listener(Self::activate_hand_tool))
+            .on_action(cx.listener(Self::activate_selection_tool))
+            .on_action(cx.listener(Self::select_all_nodes))
+            .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::handle_cancel))
+            .on_action(cx.listener(Self::toggle_wireframe))
+            .on_action(cx.listener(Self::toggle_outline))
+            .on_action(cx.listener(Self::toggle_pixel_preview_1x))
+            .on_action(cx.listener(Self::toggle_pixel_preview_2x))
+            .on_action(cx.listener(Self::toggle_preview))
+            .on_action(cx.listener(Self::toggle_simulate_reduced_motion))
+            .on_action(cx.listener(Self::toggle_simulate_increased_contrast))
+            .on_action(cx.listener(Self::toggle_hit_test_debug))
+            .on_action(cx.listener(Self::show_context_menu))
+            .on_action(cx.listener(Self::toggle_grid))
+            .on_action(cx.listener(Self::exit_solo))
+            .on_action(cx.listener(Self::toggle_follow_selection))
+            .on_action(cx.listener(Self::toggle_rulers))
+            .on_action(cx.listener(Self::toggle_restore_session))
+            .on_action(cx.listener(Self::toggle_low_power_mode))
+            .on_action(cx.listener(Self::toggle_frame_navigator))
+            .on_action(cx.listener(Self::print))
+            .on_action(cx.listener(Self::copy_as_css))
+            .on_action(cx.listener(Self::export_css))
+            .on_action(cx.listener(Self::copy_as_rust))
+            .on_action(cx.listener(Self::copy_as_svg))
+            .on_action(cx.listener(Self::toggle_ui))
+            .on_action(cx.listener(Self::toggle_full_screen))
+            .on_action(cx.listener(Self::detach_layers))
+            .on_action(cx.listener(Self::go_to_node))
+            .on_action(cx.listener(Self::next_page))
+            .on_action(cx.listener(Self::previous_page))
+            .on_action(cx.listener(Self::show_document_info))
+            .on_action(cx.listener(Self::compare_with_saved))
+            .on_action(cx.listener(Self::pull_library))
+            .on_action(cx.listener(Self::use_library_repository))
+            .on_action(cx.listener(Self::use_local_library))
+            .on_action(cx.listener(Self::show_shortcuts))
+            .on_action(cx.listener(Self::new_from_template))
+            .on_action(cx.listener(Self::open))
+            .on_action(cx.listener(Self::save))
+            .on_action(cx.listener(Self::save_as))
+            .on_action(cx.listener(Self::export_profile))
+            .on_action(cx.listener(Self::import_profile))
+            .on_action(cx.listener(Self::toggle_view_only))
+            // Without a handler the editing actions' menu items are greyed out too
+            .when(!view_only, |div| {
+                div.on_action(cx.listener(Self::activate_rectangle_tool))
+                    .on_action(cx.listener(Self::activate_frame_tool))
+                    .on_action(cx.listener(Self::activate_pen_tool))
+                    .on_action(cx.listener(Self::activate_eyedropper_tool))
+                    .on_action(cx.listener(Self::show_color_picker))
+                    .on_action(cx.listener(Self::delete_selected_nodes))
+                    .on_action(cx.listener(Self::cut))
+                    .on_action(cx.listener(Self::paste))
+                    .on_action(cx.listener(Self::duplicate))
+                    .on_action(cx.listener(Self::add_guides_from_selection))
+                    .on_action(cx.listener(Self::clear_guides))
+                    .on_action(cx.listener(Self::toggle_snap_to_grid))
+                    .on_action(cx.listener(Self::toggle_snap_to_layers))
+                    .on_action(cx.listener(Self::toggle_snap_exclusion))
+                    .on_action(cx.listener(Self::toggle_snap_anchors_to_pixels))
+                    .on_action(cx.listener(Self::toggle_snap_anchors_to_guides))
+                    .on_action(cx.listener(Self::toggle_snap_anchors_to_anchors))
+                    .on_action(cx.listener(Self::blend))
+                    .on_action(cx.listener(Self::swap_current_colors))
+                    .on_action(cx.listener(Self::reset_current_colors))
+                    .on_action(cx.listener(Self::toggle_color_target))
+                    .on_action(cx.listener(Self::detach_inspector))
+                    .on_action(cx.listener(Self::show_design_lint))
+                    .on_action(cx.listener(Self::show_library))
+                    .on_action(cx.listener(Self::publish_component))
+                    .on_action(cx.listener(Self::publish_style))
+                    .on_action(cx.listener(Self::publish_color_token))
+                    .on_action(cx.listener(Self::update_from_library))
+                    .on_action(cx.listener(Self::rename_selection))
+                    .on_action(cx.listener(Self::edit_link))
+                    .on_action(cx.listener(Self::protect_with_password))
+                    .on_action(cx.listener(Self::new_page))
+                    .on_action(cx.listener(Self::rename_page))
+                    .on_action(cx.listener(Self::delete_page))
+            })
+            .child(
+                CanvasElement::new(&self.canvas, &self.scene_graph, cx)
+                    .with_left_inset(px(ruler_inset)),
+            )
+            // Focus mode hides the navigator along with the panels
+            .when(
+                self.navigator_visible && self.focus_mode_restore.is_none(),
+                |div| div.child(self.frame_navigator.clone()),
+            )
+            .when(
+                self.inspector_visible
+                    && !view_only
+                    && !self.detached_panels.contains_key(&PanelKind::Inspector),
+                |div| div.child(self.inspector.clone()),
+            )
+            .when(self.sidebar_visible, |div| div.child(self.sidebar.clone()));
+
+        // The finder, dialogs and document panels are siblings of the workspace rather than
+        // children, so while one has focus the workspace's single-letter shortcuts have no
+        // handler and reach it as text
+        div()
+            .size_full()
+            .child(workspace)
+            .when_some(self.quick_open.as_ref(), |div, (quick_open, _)| {
+                div.child(quick_open.clone())
+            })
+            .when_some(self.document_info.as_ref(), |div, (document_info, _)| {
+                div.child(document_info.clone())
+            })
+            .when_some(self.design_lint.as_ref(), |div, (design_lint, _)| {
+                div.child(design_lint.clone())
+            })
+            .when_some(self.version_diff.as_ref(), |div, (version_diff, _)| {
+                div.child(version_diff.clone())
+            })
+            .when_some(self.library_browser.as_ref(), |div, (browser, _)| {
+                div.child(browser.clone())
+            })
+            .when_some(self.library_review.as_ref(), |div, (review, _)| {
+                div.child(review.clone())
+            })
+            .when_some(self.batch_rename.as_ref(), |div, (batch_rename, _)| {
+                div.child(batch_rename.clone())
+            })
+            .when_some(self.link_editor.as_ref(), |div, (link_editor, _)| {
+                div.child(link_editor.clone())
+            })
+            .when_some(self.password_prompt.as_ref(), |div, (prompt, _)| {
+                div.child(prompt.clone())
+            })
+            .when_some(self.color_picker.as_ref(), |div, (color_picker, _)| {
+                div.child(color_picker.clone())
+            })
+            .when_some(self.context_menu.as_ref(), |div, (context_menu, _)| {
+                div.child(context_menu.clone())
+            })
+            .when_some(self.shortcuts.as_ref(), |div, (shortcuts, _)| {
+                div.child(shortcuts.clone())
+            })
+            .when_some(self.template_gallery.as_ref(), |div, (gallery, _)| {
+                div.child(gallery.clone())
+            })
+            .child(self.toasts.clone())
+    }
+}
+
+impl Focusable for Luna {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+fn init_globals(cx: &mut App) {
+    cx.set_global(GlobalTheme(Arc::new(Theme::default())));
+    cx.set_global(GlobalTool(Arc::new(Tool::default())));
+    Toasts::init(cx);
+}
+
+/// Application entry point
+///
+/// Initializes the GPUI application, sets up global state, defines menus,
+/// and opens the main application window. This function is the starting point
+/// for the entire Luna application.
+fn main() {
+    let application = Application::new().with_assets(Assets {
+        base: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets"),
+    });
+
+    let open_requests = Rc::new(RefCell::new(OpenRequests::default()));
+    application.on_open_urls({
+        let open_requests = open_requests.clone();
+        move |urls| {
+            let paths = urls
+                .iter()
+                .filter_map(|url| document::path_from_file_url(url))
+                .collect();
+            open_requests.borrow_mut().push(paths);
+        }
+    });
+
+    application.run(move |cx: &mut App| {
+        cx.on_action(quit);
+        cx.set_menus(vec![
+            Menu {
+                name: "Luna".into(),
+                items: vec![
+                    MenuItem::action("Reopen Last Document at Launch", ToggleRestoreSession),
+                    MenuItem::action("Switch Low Power Mode", ToggleLowPowerMode),
+                    MenuItem::separator(),
+                    MenuItem::action("Quit", Quit),
+                ],
+            },
+            Menu {
+                name: "File".into(),
+                items: vec![
+                    MenuItem::action("New from Template…", NewFromTemplate),
+                    MenuItem::action("Open…", Open),
+                    MenuItem::separator(),
+                    MenuItem::action("Save", Save),
+                    MenuItem::action("Save As…", SaveAs),
+                    MenuItem::action("Password…", ProtectWithPassword),
+                    MenuItem::action("View Only", ToggleViewOnly),
+                    MenuItem::separator(),
+                    MenuItem::action("Print…", Print),
+                    MenuItem::separator(),
+                    MenuItem::action("Export CSS…", ExportCss),
+                    MenuItem::action("Copy as CSS", CopyAsCss),
+                    MenuItem::action("Copy as Rust", CopyAsRust),
+                    MenuItem::action("Copy as SVG", CopyAsSvg),
+                    MenuItem::separator(),
+                    MenuItem::action("Import Settings Profile…", ImportProfile),
+                    MenuItem::action("Export Settings Profile…", ExportProfile),
+                ],
+            },
+            Menu {
+                name: "Edit".into(),
+                items: vec![
+                    MenuItem::action("Undo", Undo),
+                    MenuItem::action("Redo", Redo),
+                    MenuItem::separator(),
+                    MenuItem::action("Cut", Cut),
+                    MenuItem::action("Copy", Copy),
+                    MenuItem::action("Paste", Paste),
+                    MenuItem::action("Duplicate", Duplicate),
+                ],
+            },
+            Menu {
+                name: "Object".into(),
+                items: vec![
+                    MenuItem::action("Blend", Blend),
+                    MenuItem::action("Rename…", RenameSelection),
+                    MenuItem::action("Link…", EditLink),
+                    MenuItem::action("Exclude from Snapping", ToggleSnapExclusion),
+                    MenuItem::separator(),
+                    MenuItem::action("Bring to Front", BringToFront),
+                    MenuItem::action("Bring Forward", RaiseOne),
+                    MenuItem::action("Send Backward", LowerOne),
+                    MenuItem::action("Send to Back", SendToBack),
+                    MenuItem::separator(),
+                    MenuItem::action("Group", Group),
+                    MenuItem::action("Ungroup", Ungroup),
+                    MenuItem::separator(),
+                    MenuItem::action("Lock/Unlock", ToggleLocked),
+                    MenuItem::action("Hide/Show", ToggleHidden),
+                    MenuItem::separator(),
+                    MenuItem::action("Swap Fill and Stroke", SwapCurrentColors),
+                    MenuItem::action("Default Fill and Stroke", ResetCurrentColors),
+                    MenuItem::action("Toggle Fill/Stroke Target", ToggleColorTarget),
+                ],
+            },
+            Menu {
+                name: "Go".into(),
+                items: vec![
+                    MenuItem::action("Go to Node…", GoToNode),
+                    MenuItem::separator(),
+                    MenuItem::action("Next Page", NextPage),
+                    MenuItem::action("Previous Page", PreviousPage),
+                ],
+            },
+            Menu {
+                name: "Page".into(),
+                items: vec![
+                    MenuItem::action("New Page", NewPage),
+                    MenuItem::action("Rename Page", RenamePage),
+                    MenuItem::action("Delete Page…", DeletePage),
+                ],
+            },
+            Menu {
+                name: "View".into(),
+                items: vec![
+                    MenuItem::action("Zoom In", ZoomIn),
+                    MenuItem::action("Zoom Out", ZoomOut),
+                    MenuItem::action("Actual Size", ResetZoom),
+                    MenuItem::separator(),
+                    MenuItem::action("Focus Mode", ToggleUI),
+                    MenuItem::action("Wireframe", ToggleWireframe),
+                    MenuItem::action("Outline", ToggleOutline),
+                    MenuItem::action("Pixel Preview @1x", TogglePixelPreview1x),
+                    MenuItem::action("Pixel Preview @2x", TogglePixelPreview2x),
+                    MenuItem::action("Preview", TogglePreview),
+                    MenuItem::action("Simulate Reduced Motion", ToggleSimulateReducedMotion),
+                    MenuItem::action(
+                        "Simulate Increased Contrast",
+                        ToggleSimulateIncreasedContrast,
+                    ),
+                    MenuItem::action("Exit Solo", ExitSolo),
+                    MenuItem::action("Document Info…", ShowDocumentInfo),
+                    MenuItem::action("Design Lint…", ShowDesignLint),
+                    MenuItem::action("Compare with Saved Version…", CompareWithSaved),
+                    MenuItem::separator(),
+                    MenuItem::action("Grid", ToggleGrid),
+                    MenuItem::action("Snap to Grid", ToggleSnapToGrid),
+                    MenuItem::action("Snap to Layers", ToggleSnapToLayers),
+                    MenuItem::action("Snap Anchors to Pixels", ToggleSnapAnchorsToPixels),
+                    MenuItem::action("Snap Anchors to Guides", ToggleSnapAnchorsToGuides),
+                    MenuItem::action("Snap Anchors to Other Anchors", ToggleSnapAnchorsToAnchors),
+                    MenuItem::action("Rulers", ToggleRulers),
+                    MenuItem::action("Add Guides Around Selection", AddGuidesFromSelection),
+                    MenuItem::action("Clear Guides", ClearGuides),
+                    MenuItem::action("Follow Selection", ToggleFollowSelection),
+                    MenuItem::action("Frame Navigator", ToggleFrameNavigator),
+                    MenuItem::action("Show Hit Testing", ToggleHitTestDebug),
+                    MenuItem::separator(),
+                    MenuItem::action("Pop Out Layers", DetachLayers),
+                    MenuItem::action("Pop Out Inspector", DetachInspector),
+                    MenuItem::separator(),
+                    MenuItem::action("Toggle Full Screen", ToggleFullScreen),
+                ],
+            },
+            Menu {
+                name: "Library".into(),
+                items: vec![
+                    MenuItem::action("Browse Library…", ShowLibrary),
+                    MenuItem::separator(),
+                    MenuItem::action("Publish as Component", PublishComponent),
+                    MenuItem::action("Publish Style", PublishStyle),
+                    MenuItem::action("Publish Color Token", PublishColorToken),
+                    MenuItem::separator(),
+                    MenuItem::action("Review Library Updates…", UpdateFromLibrary),
+                    MenuItem::action("Pull Library Updates", PullLibrary),
+                    MenuItem::separator(),
+                    MenuItem::action("Use Git Repository…", UseLibraryRepository),
+                    MenuItem::action("Use Local Library", UseLocalLibrary),
+                ],
+            },
+            Menu {
+                name: "Help".into(),
+                items: vec![MenuItem::action("Keyboard Shortcuts", ShowShortcuts)],
+            },
+        ]);
+
+        init_keymap(cx);
+        init_globals(cx);
+        if let Some(profile) = Profile::load_active(cx) {
+            profile.apply_to_app(cx);
+        }
+
+        // A document passed on the command line or opened from the OS before launch
+        // finished takes the place of the one the window was left on
+        let first_launch = !WindowState::exists();
+        let mut window_state = WindowState::load();
+        if !window_state.restore_session {
+            window_state.document = None;
+        }
+        power::init(window_state.low_power, cx);
+        // Older macOS versions pass a `-psn_` process id to apps started from Finder
+        let launch_path = std::env::args_os()
+            .nth(1)
+            .filter(|arg| !arg.to_string_lossy().starts_with('-'))
+            .map(PathBuf::from)
+            .or_else(|| open_requests.borrow_mut().pending.drain(..).next());
+        // The template gallery would only be in the way of a document opened on purpose
+        let show_gallery = first_launch && launch_path.is_none();
+        if let Some(path) = launch_path {
+            window_state.document = Some(std::path::absolute(&path).unwrap_or(path));
+        }
+        open_requests.borrow_mut().app = Some(cx.to_async());
+
+        let window = open_main_window(window_state, cx);
+        cx.on_keyboard_layout_change({
+            move |cx| {
+                window.update(cx, |_, _, cx| cx.notify()).ok();
+            }
+        })
+        .detach();
+
+        if show_gallery {
+            window
+                .update(cx, |view, window, cx| {
+                    view.show_template_gallery(true, window, cx)
+                })
+                .ok();
+        }
+    });
+}
+
+/// Documents the OS asked to have opened, by double clicking one or using Open With
+#[derive(Default)]
+struct OpenRequests {
+    /// Set once the app has launched, until then requests wait in `pending`
+    app: Option<AsyncApp>,
+    pending: Vec<PathBuf>,
+}
+
+impl OpenRequests {
+    fn push(&mut self, mut paths: Vec<PathBuf>) {
+        let Some(app) = self.app.clone() else {
+            self.pending.append(&mut paths);
+            return;
+        };
+        // The OS calls in from outside of any update, but opening waits for the next
+        // turn of the event loop all the same rather than rely on that
+        app.foreground_executor()
+            .spawn({
+                let app = app.clone();
+                async move {
+                    app.update(|cx| open_paths(paths, cx)).ok();
+                }
+            })
+            .detach();
+    }
+}
+
+/// Open documents in the main window, opening it again if it was closed. A window holds
+/// one document, so only the first of several is opened.
+fn open_paths(paths: Vec<PathBuf>, cx: &mut App) {
+    let Some(path) = paths.into_iter().next() else {
+        return;
+    };
+    let window = cx
+        .windows()
+        .into_iter()
+        .find_map(|window| window.downcast::<Luna>());
+    match window {
+        Some(window) => {
+            window
+                .update(cx, |luna, window, cx| {
+                    window.activate_window();
+                    luna.open_path(path, None, window, cx);
+                })
+                .ok();
+        }
+        None => {
+            let mut window_state = WindowState::load();
+            window_state.document = Some(path);
+            open_main_window(window_state, cx);
+        }
+    }
+}
+
+fn open_main_window(window_state: WindowState, cx: &mut App) -> WindowHandle<Luna> {
+    let window = cx
+        .open_window(
+            WindowOptions {
+                window_bounds: window_state.bounds.map(|bounds| bounds.to_window_bounds()),
+                titlebar: Some(TitlebarOptions {
+                    title: Some("Luna".into()),
+                    appears_transparent: true,
+                    traffic_light_position: Some(point(px(8.0), px(8.0))),
+                }),
+                window_background: WindowBackgroundAppearance::Transparent,
+                ..Default::default()
+            },
+            |window, cx| cx.new(|cx| Luna::new(window_state, window, cx)),
+        )
+        .unwrap();
+
+    window
+        .update(cx, |view, window, cx| {
+            window.focus(&view.focus_handle(cx));
+            cx.activate(true);
+        })
+        .unwrap();
+    window
+}
+
+/// Quit once every window's unsaved changes are saved or discarded, staying open if
+/// any of the prompts is cancelled
+fn quit(_: &Quit, cx: &mut App) {
+    let windows: Vec<_> = cx
+        .windows()
+        .into_iter()
+        .filter_map(|window| window.downcast::<Luna>())
+        .collect();
+    let mut async_cx = cx.to_async();
+    cx.foreground_executor()
+        .spawn(async move {
+            for window in windows {
+                let confirm = window.update(&mut async_cx, |luna, window, cx| {
+                    window.activate_window();
+                    luna.confirm_discard(window, cx)
+                });
+                // A window that went away in the meantime has nothing left to save
+                if let Ok(confirm) = confirm {
+                    if !confirm.await {
+                        return;
+                    }
+                }
+            }
+            async_cx.update(|cx| cx.quit()).ok();
+        })
+        .detach();
 }
@@ -0,0 +1,219 @@
+//! # Notifications
+//!
+//! Messages shown to the user as toasts over the canvas, for things that happen
+//! outside of what they're looking at: a document that failed to open, a print job
+//! that couldn't be sent, settings that were only partly applied.
+//!
+//! Informational messages fade after a few seconds and errors stay a little longer.
+//! Errors that offer an action stay until the user either takes it or dismisses them.
+//! Posting a message that is already showing brings it back to the front instead of
+//! stacking a copy, so a failure that keeps repeating shows up once.
+
+use gpui::Action;
+use std::time::{Duration, Instant};
+
+/// Most toasts shown at once, older ones are dropped to make room
+pub const MAX_NOTIFICATIONS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Info,
+    Error,
+}
+
+impl NotificationLevel {
+    /// How long a notification without an action stays up
+    fn lifetime(self) -> Duration {
+        match self {
+            NotificationLevel::Info => Duration::from_secs(4),
+            NotificationLevel::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+/// A button on a notification that dispatches an action
+pub struct NotificationAction {
+    pub label: String,
+    pub action: Box<dyn Action>,
+}
+
+impl Clone for NotificationAction {
+    fn clone(&self) -> Self {
+        Self {
+            label: self.label.clone(),
+            action: self.action.boxed_clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Notification {
+    pub level: NotificationLevel,
+    pub message: String,
+    /// Underlying error or other specifics, shown in smaller text below the message
+    pub detail: Option<String>,
+    pub action: Option<NotificationAction>,
+}
+
+impl Notification {
+    pub fn info(message: impl Into<String>) -> Self {
+        Self {
+            level: NotificationLevel::Info,
+            message: message.into(),
+            detail: None,
+            action: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            level: NotificationLevel::Error,
+            ..Self::info(message)
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl ToString) -> Self {
+        self.detail = Some(detail.to_string());
+        self
+    }
+
+    pub fn with_action(mut self, label: impl Into<String>, action: impl Action) -> Self {
+        self.action = Some(NotificationAction {
+            label: label.into(),
+            action: Box::new(action),
+        });
+        self
+    }
+
+    fn expires_at(&self, now: Instant) -> Option<Instant> {
+        match (self.level, &self.action) {
+            (NotificationLevel::Error, Some(_)) => None,
+            (level, _) => Some(now + level.lifetime()),
+        }
+    }
+}
+
+struct Entry {
+    id: usize,
+    notification: Notification,
+    expires_at: Option<Instant>,
+}
+
+/// The notifications currently showing, oldest first
+#[derive(Default)]
+pub struct Notifications {
+    entries: Vec<Entry>,
+    next_id: usize,
+}
+
+impl Notifications {
+    /// Show a notification, returning the id it can be dismissed by
+    pub fn push(&mut self, notification: Notification, now: Instant) -> usize {
+        self.entries.retain(|entry| {
+            entry.notification.level != notification.level
+                || entry.notification.message != notification.message
+        });
+        if self.entries.len() >= MAX_NOTIFICATIONS {
+            self.entries.remove(0);
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(Entry {
+            id,
+            expires_at: notification.expires_at(now),
+            notification,
+        });
+        id
+    }
+
+    pub fn dismiss(&mut self, id: usize) -> Option<Notification> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        Some(self.entries.remove(index).notification)
+    }
+
+    /// Drop the notifications whose time is up, returning whether any were
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let count = self.entries.len();
+        self.entries
+            .retain(|entry| entry.expires_at.is_none_or(|expires_at| expires_at > now));
+        self.entries.len() != count
+    }
+
+    /// When the next notification is due to go away
+    pub fn next_expiry(&self) -> Option<Instant> {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.expires_at)
+            .min()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Notification)> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.id, &entry.notification))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(notifications: &Notifications) -> Vec<&str> {
+        notifications
+            .iter()
+            .map(|(_, notification)| notification.message.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_repeated_messages_show_once() {
+        let now = Instant::now();
+        let mut notifications = Notifications::default();
+        notifications.push(Notification::error("Failed to print"), now);
+        notifications.push(Notification::info("Saved"), now);
+        notifications.push(Notification::error("Failed to print"), now);
+        assert_eq!(messages(&notifications), vec!["Saved", "Failed to print"]);
+
+        for message in ["a", "b", "c"] {
+            notifications.push(Notification::info(message), now);
+        }
+        assert_eq!(messages(&notifications), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_expiry() {
+        let now = Instant::now();
+        let mut notifications = Notifications::default();
+        notifications.push(Notification::info("Saved"), now);
+        notifications.push(Notification::error("Failed to save"), now);
+        assert_eq!(
+            notifications.next_expiry(),
+            Some(now + Duration::from_secs(4))
+        );
+
+        assert!(!notifications.expire(now + Duration::from_secs(1)));
+        assert!(notifications.expire(now + Duration::from_secs(5)));
+        assert_eq!(messages(&notifications), vec!["Failed to save"]);
+        assert!(notifications.expire(now + Duration::from_secs(8)));
+        assert!(notifications.is_empty());
+        assert_eq!(notifications.next_expiry(), None);
+    }
+
+    #[test]
+    fn test_dismiss() {
+        let mut notifications = Notifications::default();
+        let id = notifications.push(Notification::info("Saved"), Instant::now());
+        assert_eq!(
+            notifications
+                .dismiss(id)
+                .map(|notification| notification.message),
+            Some("Saved".to_string())
+        );
+        assert!(notifications.dismiss(id).is_none());
+    }
+}
@@ -0,0 +1,266 @@
+//! # Screen-Space Overlays
+//!
+//! Annotations drawn over the canvas at a constant on-screen size, such as frame titles,
+//! dimension badges and the length and angle of the segment the pen is drawing. They describe the document rather than being part of it, so
+//! they are never stored on nodes, printed or exported.
+//!
+//! Each overlay is anchored to a point in canvas space but laid out in window space:
+//! zooming moves a label along with its node while the text stays readable. Collecting
+//! overlays only reads the canvas, `CanvasElement` shapes and paints the result on top
+//! of the nodes.
+
+use crate::{
+    canvas::LunaCanvas,
+    node::{path::measure_segment, NodeCommon, NodeId},
+};
+use gpui::{Bounds, Context, Point, SharedString, Size};
+
+/// Distance between a label and its anchor, in screen pixels
+pub const LABEL_GAP: f32 = 4.0;
+/// Horizontal padding inside labels that draw a background
+pub const LABEL_PADDING: f32 = 4.0;
+/// Height of a single line label, in screen pixels
+pub const LABEL_HEIGHT: f32 = 16.0;
+/// Font size for overlay text, independent of the canvas zoom
+pub const LABEL_FONT_SIZE: f32 = 11.0;
+/// How far labels following the pointer keep from it, so the cursor doesn't cover them
+pub const POINTER_CLEARANCE: f32 = 12.0;
+
+/// Where a label sits relative to its anchor point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayPlacement {
+    /// Bottom-left corner just above the anchor
+    AboveStart,
+    /// Horizontally centered just below the anchor
+    BelowCenter,
+    /// Top-left corner below and to the right of the anchor, clear of the pointer on it
+    BesidePointer,
+}
+
+/// What an overlay annotates, which decides how it is styled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayKind {
+    FrameTitle { selected: bool },
+    DimensionBadge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlayLabel {
+    pub kind: OverlayKind,
+    pub text: SharedString,
+    /// Anchor point in canvas coordinates
+    pub anchor: Point<f32>,
+    pub placement: OverlayPlacement,
+    /// Widest the label may be in canvas units, so the limit follows the zoom while the
+    /// text does not. Longer text is truncated.
+    pub max_width: Option<f32>,
+}
+
+impl OverlayLabel {
+    /// Screen-space bounds for a label of the given size anchored at a window point
+    pub fn layout(&self, anchor: Point<f32>, size: Size<f32>) -> Bounds<f32> {
+        let origin = match self.placement {
+            OverlayPlacement::AboveStart => {
+                Point::new(anchor.x, anchor.y - LABEL_GAP - size.height)
+            }
+            OverlayPlacement::BelowCenter => {
+                Point::new(anchor.x - size.width / 2.0, anchor.y + LABEL_GAP)
+            }
+            OverlayPlacement::BesidePointer => {
+                Point::new(anchor.x + POINTER_CLEARANCE, anchor.y + POINTER_CLEARANCE)
+            }
+        };
+
+        Bounds { origin, size }
+    }
+}
+
+/// Gather every overlay for the current state of the canvas
+///
+/// Top-level frames get their name above the top-left corner, and the selection gets
+/// a badge with its size centered underneath it. While the pen draws, the pointer gets
+/// the length and angle of the segment it would add, or what's been typed for them.
+pub fn collect_overlays(canvas: &LunaCanvas, cx: &mut Context<LunaCanvas>) -> Vec<OverlayLabel> {
+    let mut overlays = Vec::new();
+
+    for node_id in canvas.top_level_nodes() {
+        let Some(node) = canvas.get_node(node_id) else {
+            continue;
+        };
+        let bounds = node.bounds();
+        overlays.push(OverlayLabel {
+            kind: OverlayKind::FrameTitle {
+                selected: canvas.is_node_selected(node_id),
+            },
+            text: node.name().into(),
+            anchor: bounds.origin,
+            placement: OverlayPlacement::AboveStart,
+            max_width: Some(bounds.size.width),
+        });
+    }
+
+    if let Some(selection) = selection_bounds(canvas, &canvas.top_level_selection(), cx) {
+        overlays.push(OverlayLabel {
+            kind: OverlayKind::DimensionBadge,
+            text: format!(
+                "{} × {}",
+                format_dimension(selection.size.width),
+                format_dimension(selection.size.height)
+            )
+            .into(),
+            anchor: Point::new(
+                selection.origin.x + selection.size.width / 2.0,
+                selection.origin.y + selection.size.height,
+            ),
+            placement: OverlayPlacement::BelowCenter,
+            max_width: None,
+        });
+    }
+
+    // Dragging out the handles of the newest anchor measures no segment
+    let pen_segment = canvas
+        .pen_segment(cx)
+        .filter(|_| canvas.active_drag().is_none());
+    if let Some((from, to)) = pen_segment {
+        let text = if canvas.pen_entry().is_empty() {
+            let (length, angle) = measure_segment(from, to);
+            let settings = canvas.app_state().read(cx).document_settings;
+            format!(
+                "{}  {}°",
+                settings.format_dimension(length),
+                format_dimension(angle)
+            )
+        } else {
+            format!("{}|", canvas.pen_entry())
+        };
+        overlays.push(OverlayLabel {
+            kind: OverlayKind::DimensionBadge,
+            text: text.into(),
+            anchor: to,
+            placement: OverlayPlacement::BesidePointer,
+            max_width: None,
+        });
+    }
+
+    overlays
+}
+
+/// Canvas-space bounding box of the given nodes
+fn selection_bounds(
+    canvas: &LunaCanvas,
+    node_ids: &[NodeId],
+    cx: &mut Context<LunaCanvas>,
+) -> Option<Bounds<f32>> {
+    node_ids
+        .iter()
+        .filter_map(|&node_id| {
+            let size = canvas.get_node(node_id)?.bounds().size;
+            let (x, y) = canvas.get_absolute_position(node_id, cx);
+            Some(Bounds {
+                origin: Point::new(x, y),
+                size,
+            })
+        })
+        .reduce(|a, b| a.union(&b))
+}
+
+/// Format a size for display, with at most two decimals and no trailing zeros
+pub fn format_dimension(value: f32) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    let formatted = format!("{rounded:.2}");
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// Shorten text to fit `max_width`, given the width it measured at
+///
+/// Overlays use a monospace font, so the number of characters that fit scales linearly
+/// with the width. Returns `None` when not even a single character and the ellipsis fit.
+pub fn truncate_to_width(text: &str, measured_width: f32, max_width: f32) -> Option<String> {
+    if measured_width <= max_width {
+        return Some(text.to_string());
+    }
+
+    let char_count = text.chars().count();
+    if char_count == 0 {
+        return Some(String::new());
+    }
+
+    let char_width = measured_width / char_count as f32;
+    // Leave room for the ellipsis itself
+    let fitting = (max_width / char_width).floor() as usize;
+    if fitting < 2 {
+        return None;
+    }
+
+    let mut truncated: String = text.chars().take(fitting - 1).collect();
+    truncated.push('…');
+    Some(truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(placement: OverlayPlacement) -> OverlayLabel {
+        OverlayLabel {
+            kind: OverlayKind::DimensionBadge,
+            text: "100 × 50".into(),
+            anchor: Point::new(0.0, 0.0),
+            placement,
+            max_width: None,
+        }
+    }
+
+    #[test]
+    fn test_layout_above_start() {
+        let bounds = label(OverlayPlacement::AboveStart)
+            .layout(Point::new(10.0, 100.0), Size::new(60.0, 16.0));
+
+        assert_eq!(bounds.origin, Point::new(10.0, 100.0 - LABEL_GAP - 16.0));
+        assert_eq!(bounds.size, Size::new(60.0, 16.0));
+    }
+
+    #[test]
+    fn test_layout_below_center() {
+        let bounds = label(OverlayPlacement::BelowCenter)
+            .layout(Point::new(100.0, 200.0), Size::new(40.0, 16.0));
+
+        assert_eq!(bounds.origin, Point::new(80.0, 200.0 + LABEL_GAP));
+    }
+
+    #[test]
+    fn test_layout_beside_pointer() {
+        let bounds = label(OverlayPlacement::BesidePointer)
+            .layout(Point::new(100.0, 200.0), Size::new(40.0, 16.0));
+
+        assert_eq!(
+            bounds.origin,
+            Point::new(100.0 + POINTER_CLEARANCE, 200.0 + POINTER_CLEARANCE)
+        );
+    }
+
+    #[test]
+    fn test_format_dimension() {
+        assert_eq!(format_dimension(100.0), "100");
+        assert_eq!(format_dimension(12.5), "12.5");
+        assert_eq!(format_dimension(33.333), "33.33");
+        assert_eq!(format_dimension(0.004), "0");
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(
+            truncate_to_width("Header", 36.0, 100.0).as_deref(),
+            Some("Header")
+        );
+        // 6px per character, 4 fit: three characters and the ellipsis
+        assert_eq!(
+            truncate_to_width("Navigation", 60.0, 24.0).as_deref(),
+            Some("Nav…")
+        );
+        assert_eq!(truncate_to_width("Navigation", 60.0, 8.0), None);
+    }
+}
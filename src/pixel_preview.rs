@@ -0,0 +1,194 @@
+//! # Pixel Preview
+//!
+//! Shows how nodes will land on the pixels of an exported image. An export at a given
+//! scale turns every canvas unit into that many pixels, counted from the top-level
+//! frame's origin, and an edge that falls between two pixels ends up as a pixel only
+//! partly filled in: the soft, blurry edge icon designers want to catch before exporting.
+//!
+//! The preview works it out per axis. Along each one a shape covers some pixels fully
+//! and, at edges off the grid, one pixel partly. Painting every covered pixel as a flat
+//! block, faded by its coverage, reproduces the export's anti-aliasing the way a
+//! nearest-neighbor zoom would show it.
+
+use gpui::{Bounds, Point, Size};
+use smallvec::SmallVec;
+
+/// Edges closer to a pixel boundary than this, in pixels, count as on it
+const ALIGNED_DISTANCE: f32 = 0.001;
+
+/// Pixel density of an export, the number of pixels per canvas unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportScale {
+    X1,
+    X2,
+}
+
+impl ExportScale {
+    pub fn factor(self) -> f32 {
+        match self {
+            ExportScale::X1 => 1.0,
+            ExportScale::X2 => 2.0,
+        }
+    }
+}
+
+/// A run of pixels along one axis, all covered by the same amount
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PixelSpan {
+    /// First pixel boundary of the run
+    pub start: f32,
+    /// Last pixel boundary of the run
+    pub end: f32,
+    /// How much of each pixel is covered, from 0 to 1
+    pub coverage: f32,
+}
+
+/// A block of pixels covered by a shape, all by the same amount
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PixelCell {
+    /// In canvas units, relative to the grid's origin
+    pub bounds: Bounds<f32>,
+    pub coverage: f32,
+}
+
+/// The pixels a shape running from `start` to `end` touches, both measured in pixels
+///
+/// Partly covered pixels at either edge come first and last, the fully covered run
+/// between them in the middle.
+pub fn rasterize_span(start: f32, end: f32) -> SmallVec<[PixelSpan; 3]> {
+    let mut spans = SmallVec::new();
+    let first = (start + ALIGNED_DISTANCE).floor();
+    let last = (end - ALIGNED_DISTANCE).ceil();
+    if last <= first {
+        return spans;
+    }
+
+    // Thinner than a pixel, and within one
+    if last - first == 1.0 {
+        spans.push(PixelSpan {
+            start: first,
+            end: last,
+            coverage: (end - start).min(1.0),
+        });
+        return spans;
+    }
+
+    let (mut inner_start, mut inner_end) = (first, last);
+    if start - first > ALIGNED_DISTANCE {
+        inner_start = first + 1.0;
+        spans.push(PixelSpan {
+            start: first,
+            end: inner_start,
+            coverage: inner_start - start,
+        });
+    }
+    let trailing = (last - end > ALIGNED_DISTANCE).then(|| {
+        inner_end = last - 1.0;
+        PixelSpan {
+            start: inner_end,
+            end: last,
+            coverage: end - inner_end,
+        }
+    });
+    if inner_end > inner_start {
+        spans.push(PixelSpan {
+            start: inner_start,
+            end: inner_end,
+            coverage: 1.0,
+        });
+    }
+    spans.extend(trailing);
+    spans
+}
+
+/// The pixels `bounds` covers in an export at `scale`, with the grid starting at 0,0
+pub fn rasterize(bounds: Bounds<f32>, scale: ExportScale) -> Vec<PixelCell> {
+    let scale = scale.factor();
+    let columns = rasterize_span(
+        bounds.origin.x * scale,
+        (bounds.origin.x + bounds.size.width) * scale,
+    );
+    let rows = rasterize_span(
+        bounds.origin.y * scale,
+        (bounds.origin.y + bounds.size.height) * scale,
+    );
+
+    rows.iter()
+        .flat_map(|row| {
+            columns.iter().map(move |column| PixelCell {
+                bounds: Bounds {
+                    origin: Point::new(column.start / scale, row.start / scale),
+                    size: Size::new(
+                        (column.end - column.start) / scale,
+                        (row.end - row.start) / scale,
+                    ),
+                },
+                coverage: column.coverage * row.coverage,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(start: f32, end: f32, coverage: f32) -> PixelSpan {
+        PixelSpan {
+            start,
+            end,
+            coverage,
+        }
+    }
+
+    #[test]
+    fn test_aligned_span_is_fully_covered() {
+        assert_eq!(rasterize_span(2.0, 10.0).as_slice(), [span(2.0, 10.0, 1.0)]);
+        // Float noise doesn't make an edge soft
+        assert_eq!(
+            rasterize_span(1.9999, 10.0004).as_slice(),
+            [span(2.0, 10.0, 1.0)]
+        );
+        assert!(rasterize_span(4.0, 4.0).is_empty());
+    }
+
+    #[test]
+    fn test_edges_between_pixels_are_partly_covered() {
+        assert_eq!(
+            rasterize_span(2.5, 10.25).as_slice(),
+            [
+                span(2.0, 3.0, 0.5),
+                span(3.0, 10.0, 1.0),
+                span(10.0, 11.0, 0.25)
+            ]
+        );
+        // Two half pixels with nothing fully covered between them
+        assert_eq!(
+            rasterize_span(0.5, 1.5).as_slice(),
+            [span(0.0, 1.0, 0.5), span(1.0, 2.0, 0.5)]
+        );
+        // A hairline inside a single pixel
+        assert_eq!(rasterize_span(3.25, 3.5).as_slice(), [span(3.0, 4.0, 0.25)]);
+    }
+
+    #[test]
+    fn test_rasterize_at_export_scale() {
+        let bounds = Bounds {
+            origin: Point::new(0.5, 0.0),
+            size: Size::new(2.0, 1.0),
+        };
+
+        // Half a unit is a whole pixel at 2x, so nothing is blurry
+        let cells = rasterize(bounds, ExportScale::X2);
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[0].bounds, bounds);
+        assert_eq!(cells[0].coverage, 1.0);
+
+        // At 1x both vertical edges fall halfway through a pixel
+        let cells = rasterize(bounds, ExportScale::X1);
+        let coverage: Vec<f32> = cells.iter().map(|cell| cell.coverage).collect();
+        assert_eq!(coverage, [0.5, 1.0, 0.5]);
+        assert_eq!(cells[0].bounds.origin, Point::new(0.0, 0.0));
+        assert_eq!(cells[2].bounds.origin, Point::new(2.0, 0.0));
+    }
+}
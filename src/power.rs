@@ -0,0 +1,242 @@
+//! # Low Power Mode
+//!
+//! Luna can go easy on the battery at the cost of some polish. In low power mode the
+//! canvas stops redrawing every frame while nothing happens, refreshing a few times a
+//! second instead, frames aren't highlighted under the cursor, and viewport changes
+//! jump to their destination instead of gliding there.
+//!
+//! By default the mode follows the power source, switching on while the machine runs
+//! on battery. It can also be kept on or off. The power source is checked every so
+//! often in the background, from `/sys/class/power_supply` on Linux and `pmset` on
+//! macOS. Elsewhere the machine is taken to be plugged in.
+
+use gpui::{App, Global};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, process::Command, time::Duration};
+
+/// How often the power source is checked
+const POWER_SOURCE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often an idle canvas is redrawn in low power mode
+pub const IDLE_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// When low power mode is on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LowPowerSetting {
+    /// While running on battery
+    #[default]
+    OnBattery,
+    Always,
+    Never,
+}
+
+impl LowPowerSetting {
+    /// The setting after this one, for switching through them from a single command
+    pub fn next(self) -> Self {
+        match self {
+            LowPowerSetting::OnBattery => LowPowerSetting::Always,
+            LowPowerSetting::Always => LowPowerSetting::Never,
+            LowPowerSetting::Never => LowPowerSetting::OnBattery,
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            LowPowerSetting::OnBattery => "Low power mode is on while running on battery",
+            LowPowerSetting::Always => "Low power mode is always on",
+            LowPowerSetting::Never => "Low power mode is off",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PowerState {
+    pub setting: LowPowerSetting,
+    pub on_battery: bool,
+}
+
+impl PowerState {
+    pub fn is_low_power(&self) -> bool {
+        match self.setting {
+            LowPowerSetting::OnBattery => self.on_battery,
+            LowPowerSetting::Always => true,
+            LowPowerSetting::Never => false,
+        }
+    }
+}
+
+pub struct GlobalPower(pub PowerState);
+
+impl Global for GlobalPower {}
+
+pub trait ActivePower {
+    /// Whether the app should save power right now
+    fn low_power(&self) -> bool;
+}
+
+impl ActivePower for App {
+    fn low_power(&self) -> bool {
+        self.try_global::<GlobalPower>()
+            .is_some_and(|power| power.0.is_low_power())
+    }
+}
+
+/// Start out with `setting` and keep track of the power source from then on
+pub fn init(setting: LowPowerSetting, cx: &mut App) {
+    cx.set_global(GlobalPower(PowerState {
+        setting,
+        on_battery: on_battery(),
+    }));
+
+    let async_cx = cx.to_async();
+    cx.foreground_executor()
+        .spawn(async move {
+            loop {
+                async_cx
+                    .background_executor()
+                    .timer(POWER_SOURCE_INTERVAL)
+                    .await;
+                let on_battery = async_cx
+                    .background_executor()
+                    .spawn(async { on_battery() })
+                    .await;
+                let updated = async_cx.update(|cx| {
+                    let power = &cx.global::<GlobalPower>().0;
+                    if power.on_battery != on_battery {
+                        let power = PowerState {
+                            on_battery,
+                            ..*power
+                        };
+                        set_power(power, cx);
+                    }
+                });
+                // The app is shutting down
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+}
+
+pub fn set_setting(setting: LowPowerSetting, cx: &mut App) {
+    let power = PowerState {
+        setting,
+        ..cx.global::<GlobalPower>().0
+    };
+    set_power(power, cx);
+}
+
+fn set_power(power: PowerState, cx: &mut App) {
+    let was_low_power = cx.low_power();
+    cx.set_global(GlobalPower(power));
+    // Hover highlights and the idle redraws only pick up the change when redrawn
+    if power.is_low_power() != was_low_power {
+        cx.refresh_windows();
+    }
+}
+
+/// Whether the machine is running on battery right now
+fn on_battery() -> bool {
+    if cfg!(target_os = "macos") {
+        Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .is_ok_and(|output| pmset_on_battery(&String::from_utf8_lossy(&output.stdout)))
+    } else if cfg!(target_os = "linux") {
+        sysfs_on_battery(Path::new("/sys/class/power_supply"))
+    } else {
+        false
+    }
+}
+
+/// `pmset -g batt` starts with where power is drawn from, like
+/// `Now drawing from 'Battery Power'`
+fn pmset_on_battery(output: &str) -> bool {
+    output
+        .lines()
+        .next()
+        .is_some_and(|line| line.contains("'Battery Power'"))
+}
+
+/// On battery when no mains supply is online and a battery is discharging
+fn sysfs_on_battery(power_supply: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(power_supply) else {
+        return false;
+    };
+    let read = |dir: &Path, name: &str| {
+        std::fs::read_to_string(dir.join(name))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut discharging = false;
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        match read(&dir, "type").as_str() {
+            "Mains" if read(&dir, "online") == "1" => return false,
+            "Battery" if read(&dir, "status") == "Discharging" => discharging = true,
+            _ => {}
+        }
+    }
+    discharging
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setting_decides_low_power() {
+        let power = |setting, on_battery| PowerState {
+            setting,
+            on_battery,
+        };
+        assert!(power(LowPowerSetting::OnBattery, true).is_low_power());
+        assert!(!power(LowPowerSetting::OnBattery, false).is_low_power());
+        assert!(power(LowPowerSetting::Always, false).is_low_power());
+        assert!(!power(LowPowerSetting::Never, true).is_low_power());
+
+        // Switching through the settings comes back around
+        let setting = LowPowerSetting::default();
+        assert_eq!(setting.next().next().next(), setting);
+    }
+
+    #[test]
+    fn test_pmset_power_source() {
+        let battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1)\t85%; discharging; 4:12 remaining present: true\n";
+        let mains = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=1)\t100%; charged; 0:00 remaining present: true\n";
+        assert!(pmset_on_battery(battery));
+        assert!(!pmset_on_battery(mains));
+        assert!(!pmset_on_battery(""));
+    }
+
+    #[test]
+    fn test_sysfs_power_source() {
+        let root = std::env::temp_dir().join(format!("luna-power-{}", uuid::Uuid::new_v4()));
+        let supply = |name: &str, files: &[(&str, &str)]| {
+            let dir = root.join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            for (file, value) in files {
+                std::fs::write(dir.join(file), format!("{value}\n")).unwrap();
+            }
+        };
+
+        supply("BAT0", &[("type", "Battery"), ("status", "Discharging")]);
+        supply("AC", &[("type", "Mains"), ("online", "0")]);
+        assert!(sysfs_on_battery(&root));
+
+        // Some batteries report discharging for a moment after the charger is plugged in
+        supply("AC", &[("type", "Mains"), ("online", "1")]);
+        assert!(!sysfs_on_battery(&root));
+
+        // Desktops have no battery at all
+        std::fs::remove_dir_all(&root).unwrap();
+        supply("AC", &[("type", "Mains"), ("online", "0")]);
+        assert!(!sysfs_on_battery(&root));
+
+        std::fs::remove_dir_all(&root).unwrap();
+        assert!(!sysfs_on_battery(&root));
+    }
+}
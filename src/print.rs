@@ -0,0 +1,352 @@
+//! # Printing
+//!
+//! Lays frames out onto physical pages and hands the result to the system print spooler.
+//!
+//! Page placement is deliberately independent of any output format: `place_frame` and
+//! `PrintShape` only deal in points and canvas units, and `render_postscript` is just one
+//! consumer of them. A PDF exporter should build on the same placement so printed and
+//! exported pages always agree.
+//!
+//! GPUI does not expose the platform print dialog, so jobs are submitted to the default
+//! printer through CUPS (`lpr`), which is available on both macOS and Linux.
+
+use crate::{
+    canvas::LunaCanvas,
+    node::{NodeCommon, NodeId},
+};
+use gpui::{Bounds, Hsla, Point, Size};
+use std::{fmt::Write as _, io, process::Command};
+
+/// Standard paper sizes, measured in PostScript points (1/72 inch)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaperSize {
+    #[default]
+    A4,
+    Letter,
+}
+
+impl PaperSize {
+    /// Page size in portrait orientation
+    pub fn portrait_size(&self) -> Size<f32> {
+        match self {
+            PaperSize::A4 => Size::new(595.0, 842.0),
+            PaperSize::Letter => Size::new(612.0, 792.0),
+        }
+    }
+}
+
+/// User-facing print options
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageSetup {
+    pub paper: PaperSize,
+    /// Margin applied to every edge of the page, in points
+    pub margin: f32,
+    /// Whether frames smaller than the printable area are scaled up to fill it
+    pub scale_up: bool,
+}
+
+impl Default for PageSetup {
+    fn default() -> Self {
+        Self {
+            paper: PaperSize::default(),
+            margin: 36.0,
+            scale_up: false,
+        }
+    }
+}
+
+/// Where a single frame lands on its page
+#[derive(Debug, Clone, PartialEq)]
+pub struct PagePlacement {
+    /// Oriented page size in points
+    pub page_size: Size<f32>,
+    /// Points per canvas unit
+    pub scale: f32,
+    /// Top-left corner of the frame, in points from the top-left of the page
+    pub origin: Point<f32>,
+}
+
+/// Fit a frame of the given canvas size onto a page
+///
+/// The page is rotated to landscape for frames wider than they are tall, the frame is
+/// scaled uniformly to fit inside the margins and centered in the printable area.
+pub fn place_frame(frame_size: Size<f32>, setup: &PageSetup) -> PagePlacement {
+    let portrait = setup.paper.portrait_size();
+    let page_size = if frame_size.width > frame_size.height {
+        Size::new(portrait.height, portrait.width)
+    } else {
+        portrait
+    };
+
+    let printable = Size::new(
+        (page_size.width - setup.margin * 2.0).max(0.0),
+        (page_size.height - setup.margin * 2.0).max(0.0),
+    );
+
+    let mut scale = if frame_size.width > 0.0 && frame_size.height > 0.0 {
+        (printable.width / frame_size.width).min(printable.height / frame_size.height)
+    } else {
+        1.0
+    };
+    if !setup.scale_up {
+        scale = scale.min(1.0);
+    }
+
+    let printed = Size::new(frame_size.width * scale, frame_size.height * scale);
+    let origin = Point::new(
+        setup.margin + (printable.width - printed.width) / 2.0,
+        setup.margin + (printable.height - printed.height) / 2.0,
+    );
+
+    PagePlacement {
+        page_size,
+        scale,
+        origin,
+    }
+}
+
+/// A node flattened for printing, positioned relative to the printed frame
+///
+/// Children are nested rather than flattened so output formats can clip them to their
+/// parent the same way the canvas does.
+#[derive(Debug, Clone)]
+pub struct PrintShape {
+    pub bounds: Bounds<f32>,
+    pub fill: Option<Hsla>,
+    pub border_color: Option<Hsla>,
+    pub border_width: f32,
+    pub corner_radius: f32,
+    pub children: Vec<PrintShape>,
+}
+
+impl PrintShape {
+    /// Build the shape tree for `node_id`, with `origin` as the node's top-left corner
+    fn from_node(canvas: &LunaCanvas, node_id: NodeId, origin: Point<f32>) -> Option<Self> {
+        let node = canvas.get_node(node_id)?;
+        let layout = node.layout();
+
+        let children = node
+            .children()
+            .iter()
+            .filter_map(|&child_id| {
+                let child = canvas.get_node(child_id).filter(|child| !child.hidden)?;
+                let child_layout = child.layout();
+                let child_origin = Point::new(origin.x + child_layout.x, origin.y + child_layout.y);
+                Self::from_node(canvas, child_id, child_origin)
+            })
+            .collect();
+
+        Some(Self {
+            bounds: Bounds {
+                origin,
+                size: Size::new(layout.width, layout.height),
+            },
+            fill: node.fill(),
+            border_color: node.border_color(),
+            border_width: node.border_width(),
+            corner_radius: node.corner_radius(),
+            children,
+        })
+    }
+}
+
+/// A single printed page holding one frame
+#[derive(Debug, Clone)]
+pub struct PrintPage {
+    pub placement: PagePlacement,
+    pub frame: PrintShape,
+}
+
+impl PrintPage {
+    pub fn for_frame(canvas: &LunaCanvas, frame_id: NodeId, setup: &PageSetup) -> Option<Self> {
+        let frame = PrintShape::from_node(canvas, frame_id, Point::new(0.0, 0.0))?;
+        let placement = place_frame(frame.bounds.size, setup);
+        Some(Self { placement, frame })
+    }
+}
+
+/// Render pages as a DSC-conforming PostScript document
+pub fn render_postscript(pages: &[PrintPage]) -> String {
+    let mut out = String::new();
+    out.push_str("%!PS-Adobe-3.0\n%%Creator: Luna\n");
+    let _ = writeln!(out, "%%Pages: {}", pages.len());
+    out.push_str("%%EndComments\n");
+
+    // x y w h r rrpath -> rounded rectangle path, `arct` degrades to corners at r = 0
+    out.push_str(
+        "/rrpath { 5 dict begin /r exch def /h exch def /w exch def /y exch def /x exch def\n\
+         newpath x r add y moveto\n\
+         x w add y x w add y h add r arct\n\
+         x w add y h add x y h add r arct\n\
+         x y h add x y r arct\n\
+         x y x w add y r arct\n\
+         closepath end } def\n",
+    );
+
+    for (index, page) in pages.iter().enumerate() {
+        let placement = &page.placement;
+        let _ = writeln!(out, "%%Page: {} {}", index + 1, index + 1);
+        let _ = writeln!(
+            out,
+            "<< /PageSize [{} {}] >> setpagedevice",
+            placement.page_size.width, placement.page_size.height
+        );
+        out.push_str("gsave\n");
+        // Flip to a top-left origin so shapes can be written in canvas orientation
+        let _ = writeln!(out, "0 {} translate 1 -1 scale", placement.page_size.height);
+        let _ = writeln!(
+            out,
+            "{} {} translate {} {} scale",
+            placement.origin.x, placement.origin.y, placement.scale, placement.scale
+        );
+        write_shape(&mut out, &page.frame);
+        out.push_str("grestore\nshowpage\n");
+    }
+
+    out.push_str("%%EOF\n");
+    out
+}
+
+fn write_shape(out: &mut String, shape: &PrintShape) {
+    let Bounds { origin, size } = shape.bounds;
+    let radius = shape
+        .corner_radius
+        .min(size.width / 2.0)
+        .min(size.height / 2.0)
+        .max(0.0);
+
+    if let Some(fill) = shape.fill {
+        write_color(out, fill);
+        let _ = writeln!(
+            out,
+            "{} {} {} {} {} rrpath fill",
+            origin.x, origin.y, size.width, size.height, radius
+        );
+    }
+
+    if !shape.children.is_empty() {
+        let _ = writeln!(
+            out,
+            "gsave {} {} {} {} {} rrpath clip",
+            origin.x, origin.y, size.width, size.height, radius
+        );
+        for child in &shape.children {
+            write_shape(out, child);
+        }
+        out.push_str("grestore\n");
+    }
+
+    // Borders sit inside the bounds on the canvas, so inset the stroke by half its width
+    if let Some(border_color) = shape.border_color.filter(|_| shape.border_width > 0.0) {
+        let inset = shape.border_width / 2.0;
+        write_color(out, border_color);
+        let _ = writeln!(
+            out,
+            "{} setlinewidth {} {} {} {} {} rrpath stroke",
+            shape.border_width,
+            origin.x + inset,
+            origin.y + inset,
+            (size.width - shape.border_width).max(0.0),
+            (size.height - shape.border_width).max(0.0),
+            (radius - inset).max(0.0)
+        );
+    }
+}
+
+/// PostScript has no alpha channel, so translucent colors are composited over white paper
+fn write_color(out: &mut String, color: Hsla) {
+    let rgba = gpui::Rgba::from(color);
+    let over_paper = |channel: f32| channel * rgba.a + (1.0 - rgba.a);
+    let _ = writeln!(
+        out,
+        "{:.4} {:.4} {:.4} setrgbcolor",
+        over_paper(rgba.r),
+        over_paper(rgba.g),
+        over_paper(rgba.b)
+    );
+}
+
+/// Submit a PostScript document to the default printer. The job is written to its own
+/// temporary file, which is removed once `lpr` has copied it into the spool.
+pub fn send_to_printer(postscript: &str) -> io::Result<()> {
+    let path = std::env::temp_dir().join(format!("luna-print-{}.ps", uuid::Uuid::new_v4()));
+    std::fs::write(&path, postscript)?;
+
+    let status = Command::new("lpr").arg(&path).status();
+    let _ = std::fs::remove_file(&path);
+    let status = status?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("lpr exited with {status}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_frames_print_at_actual_size() {
+        let setup = PageSetup::default();
+        let placement = place_frame(Size::new(200.0, 300.0), &setup);
+
+        assert_eq!(placement.scale, 1.0);
+        assert_eq!(placement.page_size, PaperSize::A4.portrait_size());
+        // Centered within the page
+        assert_eq!(placement.origin.x, (595.0 - 200.0) / 2.0);
+        assert_eq!(placement.origin.y, (842.0 - 300.0) / 2.0);
+    }
+
+    #[test]
+    fn test_large_frames_shrink_to_fit_margins() {
+        let setup = PageSetup::default();
+        let placement = place_frame(Size::new(1000.0, 2000.0), &setup);
+
+        let printable_height = 842.0 - setup.margin * 2.0;
+        assert!((placement.scale - printable_height / 2000.0).abs() < 1e-5);
+        assert!((placement.origin.y - setup.margin).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_wide_frames_use_landscape() {
+        let placement = place_frame(Size::new(1920.0, 1080.0), &PageSetup::default());
+        assert_eq!(placement.page_size, Size::new(842.0, 595.0));
+    }
+
+    #[test]
+    fn test_scale_up_fills_printable_area() {
+        let setup = PageSetup {
+            scale_up: true,
+            ..Default::default()
+        };
+        let placement = place_frame(Size::new(100.0, 100.0), &setup);
+
+        let printable_width = 595.0 - setup.margin * 2.0;
+        assert!((placement.scale - printable_width / 100.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_postscript_has_one_page_per_frame() {
+        let shape = PrintShape {
+            bounds: Bounds {
+                origin: Point::new(0.0, 0.0),
+                size: Size::new(100.0, 50.0),
+            },
+            fill: Some(Hsla::white()),
+            border_color: Some(Hsla::black()),
+            border_width: 1.0,
+            corner_radius: 4.0,
+            children: Vec::new(),
+        };
+        let page = PrintPage {
+            placement: place_frame(shape.bounds.size, &PageSetup::default()),
+            frame: shape,
+        };
+
+        let postscript = render_postscript(&[page.clone(), page]);
+        assert!(postscript.starts_with("%!PS-Adobe-3.0"));
+        assert!(postscript.contains("%%Pages: 2"));
+        assert_eq!(postscript.matches("showpage").count(), 2);
+    }
+}
@@ -0,0 +1,218 @@
+//! # Settings Profiles
+//!
+//! A profile bundles the settings that make up a Luna setup — theme, key bindings,
+//! panel layout and document preferences — into a single JSON file. Exporting one on
+//! a machine and importing it on another gives both the same setup, which is how a
+//! team standardizes on one.
+//!
+//! The last imported profile is kept in the config directory, and its theme and key
+//! bindings are applied again at every launch. Panels and document preferences are
+//! only applied at import, after that they're remembered with the window state like
+//! any other change.
+
+use crate::{
+    document_settings::DocumentSettings,
+    keymap::init_keymap,
+    notifications::Notification,
+    theme::{GlobalTheme, Theme, ThemeVariant},
+    ui::toasts::Toasts,
+    util::{config_dir, keystroke_source},
+    window_state::PanelState,
+};
+use anyhow::{bail, Context as _};
+use gpui::{App, KeyBinding, KeyBindingContextPredicate, Keymap};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
+
+/// Format version written to new profiles, profiles from newer versions are refused
+pub const PROFILE_VERSION: u32 = 1;
+
+const ACTIVE_PROFILE_FILE_NAME: &str = "profile.json";
+
+/// One key binding, stored the way it would be written in a keymap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyBindingEntry {
+    /// Space-separated keystrokes, like `cmd-k cmd-s`
+    pub keystrokes: String,
+    /// Namespaced action name, like `luna::ToggleGrid`
+    pub action: String,
+    /// Key context the binding is limited to, `None` for bindings that apply everywhere
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub version: u32,
+    pub theme: ThemeVariant,
+    /// Bound on top of the built-in keymap, so actions the profile predates keep
+    /// their default keys
+    pub key_bindings: Vec<KeyBindingEntry>,
+    pub panels: PanelState,
+    pub document_settings: DocumentSettings,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            version: PROFILE_VERSION,
+            theme: ThemeVariant::default(),
+            key_bindings: Vec::new(),
+            panels: PanelState::default(),
+            document_settings: DocumentSettings::default(),
+        }
+    }
+}
+
+impl Profile {
+    fn active_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(ACTIVE_PROFILE_FILE_NAME))
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::parse(&contents).with_context(|| format!("loading {}", path.display()))
+    }
+
+    fn parse(contents: &str) -> anyhow::Result<Self> {
+        let profile: Self = serde_json::from_str(contents)?;
+        if profile.version > PROFILE_VERSION {
+            bail!(
+                "profile version {} is newer than this version of Luna supports",
+                profile.version
+            );
+        }
+        Ok(profile)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// The last imported profile, if there is one that can still be read
+    pub fn load_active(cx: &mut App) -> Option<Self> {
+        let path = Self::active_path().filter(|path| path.exists())?;
+        Self::load(&path)
+            .inspect_err(|err| {
+                Toasts::show(
+                    Notification::error("Couldn't load settings profile, using defaults")
+                        .with_detail(format!("{err:#}")),
+                    cx,
+                )
+            })
+            .ok()
+    }
+
+    /// Keep this profile so it's applied again at the next launch
+    pub fn save_as_active(&self) -> anyhow::Result<()> {
+        let path = Self::active_path().context("no config directory available")?;
+        self.save(&path)
+    }
+
+    /// Every binding in the keymap, in the order they were bound
+    pub fn capture_key_bindings(keymap: &Keymap) -> Vec<KeyBindingEntry> {
+        keymap
+            .bindings()
+            .map(|binding| KeyBindingEntry {
+                keystrokes: binding
+                    .keystrokes()
+                    .iter()
+                    .map(keystroke_source)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                action: binding.action().name().to_string(),
+                context: binding.predicate().map(|predicate| predicate.to_string()),
+            })
+            .collect()
+    }
+
+    /// Apply the parts of the profile that belong to the app rather than a window: the
+    /// theme and the key bindings
+    ///
+    /// Bindings that can't be restored, because their action no longer exists or they
+    /// don't parse, are skipped and reported.
+    pub fn apply_to_app(&self, cx: &mut App) {
+        cx.set_global(GlobalTheme(Arc::new(Theme::from_variant(self.theme))));
+
+        let mut bindings = Vec::new();
+        let mut skipped = Vec::new();
+        for entry in &self.key_bindings {
+            match entry.to_key_binding(cx) {
+                Ok(binding) => bindings.push(binding),
+                Err(err) => skipped.push(format!("{}: {err:#}", entry.keystrokes)),
+            }
+        }
+        if !skipped.is_empty() {
+            Toasts::show(
+                Notification::error(match skipped.len() {
+                    1 => "Skipped 1 key binding from settings profile".to_string(),
+                    count => format!("Skipped {count} key bindings from settings profile"),
+                })
+                .with_detail(skipped.join("\n")),
+                cx,
+            );
+        }
+        cx.clear_key_bindings();
+        init_keymap(cx);
+        cx.bind_keys(bindings);
+    }
+}
+
+impl KeyBindingEntry {
+    fn to_key_binding(&self, cx: &App) -> anyhow::Result<KeyBinding> {
+        let action = cx.build_action(&self.action, None)?;
+        let predicate = self
+            .context
+            .as_deref()
+            .map(KeyBindingContextPredicate::parse)
+            .transpose()?
+            .map(Rc::new);
+        Ok(KeyBinding::load(&self.keystrokes, action, predicate, None)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ColorFormat;
+
+    #[test]
+    fn test_profile_round_trip() {
+        let mut profile = Profile {
+            theme: ThemeVariant::OneLight,
+            key_bindings: vec![KeyBindingEntry {
+                keystrokes: "shift-cmd-g".into(),
+                action: "luna::ToggleGrid".into(),
+                context: None,
+            }],
+            ..Default::default()
+        };
+        profile.panels.sidebar_visible = false;
+        profile.document_settings.color_format = ColorFormat::Rgb;
+
+        let json = serde_json::to_string(&profile).unwrap();
+        assert_eq!(Profile::parse(&json).unwrap(), profile);
+    }
+
+    #[test]
+    fn test_partial_and_future_profiles() {
+        // Missing sections fall back to the defaults
+        let profile = Profile::parse(r#"{ "theme": "one_light" }"#).unwrap();
+        assert_eq!(profile.theme, ThemeVariant::OneLight);
+        assert_eq!(profile.panels, PanelState::default());
+        assert!(profile.key_bindings.is_empty());
+
+        let newer = format!(r#"{{ "version": {} }}"#, PROFILE_VERSION + 1);
+        assert!(Profile::parse(&newer).is_err());
+    }
+}
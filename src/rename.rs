@@ -0,0 +1,93 @@
+//! # Batch Rename
+//!
+//! Naming patterns applied to several nodes at once. A pattern can replace names
+//! outright, find and replace within them, and wrap them in a prefix and suffix. `%n`
+//! anywhere in the pattern stands for the node's position in the batch, so a
+//! selection of buttons can become "Button 1", "Button 2" and so on.
+
+/// Placeholder replaced with each node's number
+pub const NUMBER_PLACEHOLDER: &str = "%n";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenamePattern {
+    /// Replaces the whole name when not empty
+    pub name: String,
+    /// Text to replace in the name, matched case-sensitively
+    pub find: String,
+    pub replace: String,
+    pub prefix: String,
+    pub suffix: String,
+    /// Number given to the first node
+    pub start: usize,
+}
+
+impl Default for RenamePattern {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            find: String::new(),
+            replace: String::new(),
+            prefix: String::new(),
+            suffix: String::new(),
+            start: 1,
+        }
+    }
+}
+
+impl RenamePattern {
+    /// Whether the pattern leaves every name as it is
+    pub fn is_empty(&self) -> bool {
+        self.name.is_empty()
+            && self.find.is_empty()
+            && self.prefix.is_empty()
+            && self.suffix.is_empty()
+    }
+
+    /// The new name for the `index`th node of the batch, currently named `name`
+    pub fn apply(&self, name: &str, index: usize) -> String {
+        let number = (self.start + index).to_string();
+        let expand = |text: &str| text.replace(NUMBER_PLACEHOLDER, &number);
+
+        let mut renamed = if self.name.is_empty() {
+            name.to_string()
+        } else {
+            expand(&self.name)
+        };
+        if !self.find.is_empty() {
+            renamed = renamed.replace(&self.find, &expand(&self.replace));
+        }
+        format!(
+            "{}{}{}",
+            expand(&self.prefix),
+            renamed,
+            expand(&self.suffix)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_pattern() {
+        let mut pattern = RenamePattern::default();
+        assert!(pattern.is_empty());
+        assert_eq!(pattern.apply("Frame 3", 0), "Frame 3");
+
+        pattern.name = "Button %n".into();
+        assert_eq!(pattern.apply("Frame 3", 0), "Button 1");
+        assert_eq!(pattern.apply("Frame 7", 2), "Button 3");
+
+        pattern.name.clear();
+        pattern.find = "Frame".into();
+        pattern.replace = "Card".into();
+        pattern.prefix = "%n. ".into();
+        pattern.suffix = " (old)".into();
+        pattern.start = 0;
+        assert_eq!(pattern.apply("Frame 3", 4), "4. Card 3 (old)");
+
+        // Only the pattern is expanded, not the existing name
+        assert_eq!(pattern.apply("50%n", 0), "0. 50%n (old)");
+    }
+}
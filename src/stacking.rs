@@ -0,0 +1,105 @@
+//! # Stacking Order
+//!
+//! Siblings are painted in order, so the last one in a list is the one on top. These
+//! helpers compute the new order of a sibling list when some of its nodes are moved
+//! to the front, to the back, or one step either way. Moved nodes keep their order
+//! relative to each other, and nodes that aren't moved keep theirs.
+
+use crate::node::NodeId;
+
+/// A change to where nodes sit among their siblings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Restack {
+    BringToFront,
+    SendToBack,
+    /// Move above the next sibling that isn't being moved
+    RaiseOne,
+    /// Move below the previous sibling that isn't being moved
+    LowerOne,
+}
+
+/// `order` with the nodes in `moving` restacked, back to front like `order`
+pub fn restack(order: &[NodeId], moving: &[NodeId], restack: Restack) -> Vec<NodeId> {
+    let is_moving = |node_id: &NodeId| moving.contains(node_id);
+    let mut order = order.to_vec();
+    match restack {
+        Restack::BringToFront => {
+            let (mut rest, front): (Vec<_>, Vec<_>) =
+                order.into_iter().partition(|node_id| !is_moving(node_id));
+            rest.extend(front);
+            return rest;
+        }
+        Restack::SendToBack => {
+            let (mut back, rest): (Vec<_>, Vec<_>) = order.into_iter().partition(is_moving);
+            back.extend(rest);
+            return back;
+        }
+        // Walking from the side the nodes move towards lets a run of moved nodes step
+        // past the same sibling one after the other, so they stay together
+        Restack::RaiseOne => {
+            for index in (1..order.len()).rev() {
+                if is_moving(&order[index - 1]) && !is_moving(&order[index]) {
+                    order.swap(index - 1, index);
+                }
+            }
+        }
+        Restack::LowerOne => {
+            for index in 1..order.len() {
+                if is_moving(&order[index]) && !is_moving(&order[index - 1]) {
+                    order.swap(index - 1, index);
+                }
+            }
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[usize]) -> Vec<NodeId> {
+        ids.iter().copied().map(NodeId::new).collect()
+    }
+
+    #[test]
+    fn test_front_and_back() {
+        let order = ids(&[1, 2, 3, 4, 5]);
+        let moving = ids(&[2, 4]);
+        assert_eq!(
+            restack(&order, &moving, Restack::BringToFront),
+            ids(&[1, 3, 5, 2, 4])
+        );
+        assert_eq!(
+            restack(&order, &moving, Restack::SendToBack),
+            ids(&[2, 4, 1, 3, 5])
+        );
+    }
+
+    #[test]
+    fn test_one_step() {
+        let order = ids(&[1, 2, 3, 4]);
+        assert_eq!(
+            restack(&order, &ids(&[2, 3]), Restack::RaiseOne),
+            ids(&[1, 4, 2, 3])
+        );
+        assert_eq!(
+            restack(&order, &ids(&[2, 3]), Restack::LowerOne),
+            ids(&[2, 3, 1, 4])
+        );
+        // Separate nodes each step past their own neighbour
+        assert_eq!(
+            restack(&order, &ids(&[1, 3]), Restack::RaiseOne),
+            ids(&[2, 1, 4, 3])
+        );
+    }
+
+    #[test]
+    fn test_nodes_already_in_place_stay() {
+        let order = ids(&[1, 2, 3]);
+        assert_eq!(restack(&order, &ids(&[2, 3]), Restack::RaiseOne), order);
+        assert_eq!(restack(&order, &ids(&[1]), Restack::LowerOne), order);
+        assert_eq!(restack(&order, &ids(&[3]), Restack::BringToFront), order);
+        assert_eq!(restack(&order, &[], Restack::SendToBack), order);
+    }
+}
@@ -0,0 +1,488 @@
+//! # Templates
+//!
+//! Starter documents offered by the template gallery. Each template is built from frames
+//! alone: repeated pieces like list rows, feature cards and icon tiles come from a shared
+//! builder function so they stay identical, and every template draws its colors from a
+//! small palette of its own so the result reads as one consistent design.
+//!
+//! Templates are built with ids starting from 1 and are meant to replace the document
+//! they're loaded into.
+
+use crate::node::{
+    frame::{FrameNode, Overflow},
+    NodeCommon, NodeFactory, NodeId, NodeLayout, Shadow,
+};
+use gpui::{hsla, point, Bounds, Hsla, Point, Size};
+use smallvec::smallvec;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    MobileApp,
+    LandingPage,
+    IconSet,
+    FlowDiagram,
+}
+
+impl Template {
+    pub const ALL: [Template; 4] = [
+        Template::MobileApp,
+        Template::LandingPage,
+        Template::IconSet,
+        Template::FlowDiagram,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Template::MobileApp => "Mobile App",
+            Template::LandingPage => "Landing Page",
+            Template::IconSet => "Icon Set",
+            Template::FlowDiagram => "Flow Diagram",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Template::MobileApp => "A phone screen with a scrolling list and a tab bar",
+            Template::LandingPage => "A marketing page with a hero, features and a footer",
+            Template::IconSet => "Twelve icon artboards on a shared keyline grid",
+            Template::FlowDiagram => "Steps, a decision and the connectors between them",
+        }
+    }
+
+    /// Build the template's frames
+    ///
+    /// Parents come before their children, and each frame's layout is relative to its
+    /// parent like the frames of a loaded document.
+    pub fn build(self) -> Vec<FrameNode> {
+        let mut builder = Builder::default();
+        match self {
+            Template::MobileApp => build_mobile_app(&mut builder),
+            Template::LandingPage => build_landing_page(&mut builder),
+            Template::IconSet => build_icon_set(&mut builder),
+            Template::FlowDiagram => build_flow_diagram(&mut builder),
+        }
+        builder.frames
+    }
+}
+
+/// Canvas bounds of each frame, for frames ordered parents first as [`Template::build`]
+/// returns them
+pub fn absolute_bounds(frames: &[FrameNode]) -> Vec<Bounds<f32>> {
+    let mut origins: HashMap<NodeId, Point<f32>> = HashMap::new();
+    for frame in frames {
+        let origin = origins.get(&frame.id).copied().unwrap_or(point(0.0, 0.0));
+        for child_id in &frame.children {
+            origins.insert(
+                *child_id,
+                point(origin.x + frame.layout.x, origin.y + frame.layout.y),
+            );
+        }
+    }
+
+    frames
+        .iter()
+        .map(|frame| {
+            let offset = origins.get(&frame.id).copied().unwrap_or(point(0.0, 0.0));
+            Bounds {
+                origin: point(offset.x + frame.layout.x, offset.y + frame.layout.y),
+                size: Size::new(frame.layout.width, frame.layout.height),
+            }
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Builder {
+    factory: NodeFactory,
+    frames: Vec<FrameNode>,
+}
+
+impl Builder {
+    /// Add a borderless frame, returning it so the caller can style it further
+    fn frame(
+        &mut self,
+        parent: Option<NodeId>,
+        name: &str,
+        (x, y, width, height): (f32, f32, f32, f32),
+        fill: Hsla,
+    ) -> &mut FrameNode {
+        let mut frame = self.factory.create_frame();
+        frame.set_name(Some(name.to_string()));
+        frame.layout = NodeLayout::new(x, y, width, height);
+        frame.set_fill(Some(fill));
+        frame.set_border(None, 0.0);
+
+        if let Some(parent) = parent {
+            if let Some(parent) = self.frames.iter_mut().find(|frame| frame.id == parent) {
+                parent.add_child(frame.id);
+            }
+        }
+        self.frames.push(frame);
+        self.frames.last_mut().unwrap()
+    }
+}
+
+fn soft_shadow() -> Shadow {
+    Shadow {
+        color: hsla(0.0, 0.0, 0.0, 0.12),
+        offset: point(0.0, 4.0),
+        blur_radius: 16.0,
+        spread_radius: 0.0,
+        inset: false,
+    }
+}
+
+/// Stand-in for a line of text, the canvas has no text nodes
+fn text_line(
+    builder: &mut Builder,
+    parent: NodeId,
+    name: &str,
+    bounds: (f32, f32, f32, f32),
+    color: Hsla,
+) {
+    let corner_radius = bounds.3 / 2.0;
+    builder
+        .frame(Some(parent), name, bounds, color)
+        .corner_radius = corner_radius;
+}
+
+fn button(
+    builder: &mut Builder,
+    parent: NodeId,
+    name: &str,
+    bounds: (f32, f32, f32, f32),
+    fill: Hsla,
+    label: Hsla,
+) {
+    let (_, _, width, height) = bounds;
+    let button = builder.frame(Some(parent), name, bounds, fill);
+    button.corner_radius = 8.0;
+    let button = button.id;
+    text_line(
+        builder,
+        button,
+        "Label",
+        (width / 4.0, height / 2.0 - 4.0, width / 2.0, 8.0),
+        label,
+    );
+}
+
+fn build_mobile_app(builder: &mut Builder) {
+    let background = hsla(220.0 / 360.0, 0.20, 0.97, 1.0);
+    let surface = hsla(0.0, 0.0, 1.0, 1.0);
+    let primary = hsla(250.0 / 360.0, 0.65, 0.58, 1.0);
+    let text = hsla(220.0 / 360.0, 0.25, 0.20, 1.0);
+    let muted = hsla(220.0 / 360.0, 0.12, 0.70, 1.0);
+
+    let screen = builder.frame(None, "Home", (0.0, 0.0, 375.0, 812.0), background);
+    screen.corner_radius = 40.0;
+    screen.shadows = smallvec![soft_shadow()];
+    let screen = screen.id;
+
+    let header = builder
+        .frame(Some(screen), "Header", (0.0, 0.0, 375.0, 112.0), primary)
+        .id;
+    text_line(builder, header, "Title", (24.0, 64.0, 120.0, 20.0), surface);
+    builder
+        .frame(Some(header), "Avatar", (315.0, 58.0, 36.0, 36.0), surface)
+        .corner_radius = 18.0;
+
+    let list = builder.frame(Some(screen), "List", (0.0, 112.0, 375.0, 616.0), background);
+    list.overflow = Overflow::Scroll;
+    let list = list.id;
+    for index in 0..8 {
+        let y = 16.0 + index as f32 * 88.0;
+        let row = builder.frame(
+            Some(list),
+            &format!("Row {}", index + 1),
+            (16.0, y, 343.0, 72.0),
+            surface,
+        );
+        row.corner_radius = 12.0;
+        let row = row.id;
+        builder
+            .frame(Some(row), "Thumbnail", (12.0, 12.0, 48.0, 48.0), muted)
+            .corner_radius = 8.0;
+        text_line(builder, row, "Title", (76.0, 20.0, 160.0, 12.0), text);
+        text_line(builder, row, "Subtitle", (76.0, 40.0, 220.0, 10.0), muted);
+    }
+
+    let tab_bar = builder.frame(Some(screen), "Tab Bar", (0.0, 728.0, 375.0, 84.0), surface);
+    tab_bar.border_color = Some(muted);
+    tab_bar.border_width = 1.0;
+    let tab_bar = tab_bar.id;
+    for (index, name) in ["Home", "Search", "Inbox", "Profile"].iter().enumerate() {
+        let color = if index == 0 { primary } else { muted };
+        let x = 36.0 + index as f32 * 88.0;
+        builder
+            .frame(Some(tab_bar), name, (x, 16.0, 28.0, 28.0), color)
+            .corner_radius = 8.0;
+    }
+}
+
+fn build_landing_page(builder: &mut Builder) {
+    let background = hsla(0.0, 0.0, 1.0, 1.0);
+    let tint = hsla(200.0 / 360.0, 0.60, 0.96, 1.0);
+    let primary = hsla(200.0 / 360.0, 0.80, 0.45, 1.0);
+    let text = hsla(210.0 / 360.0, 0.30, 0.15, 1.0);
+    let muted = hsla(210.0 / 360.0, 0.10, 0.75, 1.0);
+    let dark = hsla(210.0 / 360.0, 0.30, 0.12, 1.0);
+
+    let page = builder.frame(None, "Landing Page", (0.0, 0.0, 1280.0, 1440.0), background);
+    page.shadows = smallvec![soft_shadow()];
+    let page = page.id;
+
+    let nav = builder
+        .frame(
+            Some(page),
+            "Navigation",
+            (0.0, 0.0, 1280.0, 72.0),
+            background,
+        )
+        .id;
+    builder
+        .frame(Some(nav), "Logo", (80.0, 20.0, 32.0, 32.0), primary)
+        .corner_radius = 8.0;
+    for (index, name) in ["Product", "Pricing", "About"].iter().enumerate() {
+        let x = 760.0 + index as f32 * 96.0;
+        text_line(builder, nav, name, (x, 32.0, 64.0, 8.0), text);
+    }
+    button(
+        builder,
+        nav,
+        "Sign Up",
+        (1080.0, 16.0, 120.0, 40.0),
+        primary,
+        background,
+    );
+
+    let hero = builder
+        .frame(Some(page), "Hero", (0.0, 72.0, 1280.0, 560.0), tint)
+        .id;
+    text_line(builder, hero, "Headline", (80.0, 160.0, 480.0, 40.0), text);
+    text_line(builder, hero, "Headline", (80.0, 216.0, 360.0, 40.0), text);
+    text_line(
+        builder,
+        hero,
+        "Subheading",
+        (80.0, 288.0, 440.0, 12.0),
+        muted,
+    );
+    text_line(
+        builder,
+        hero,
+        "Subheading",
+        (80.0, 312.0, 400.0, 12.0),
+        muted,
+    );
+    button(
+        builder,
+        hero,
+        "Get Started",
+        (80.0, 360.0, 160.0, 48.0),
+        primary,
+        background,
+    );
+    button(
+        builder,
+        hero,
+        "Learn More",
+        (256.0, 360.0, 160.0, 48.0),
+        background,
+        primary,
+    );
+    let image = builder.frame(Some(hero), "Hero Image", (680.0, 80.0, 520.0, 400.0), muted);
+    image.corner_radius = 24.0;
+    image.shadows = smallvec![soft_shadow()];
+
+    let features = builder
+        .frame(
+            Some(page),
+            "Features",
+            (0.0, 632.0, 1280.0, 528.0),
+            background,
+        )
+        .id;
+    text_line(
+        builder,
+        features,
+        "Heading",
+        (480.0, 96.0, 320.0, 28.0),
+        text,
+    );
+    for index in 0..3 {
+        let x = 80.0 + index as f32 * 384.0;
+        let card = builder.frame(
+            Some(features),
+            &format!("Feature {}", index + 1),
+            (x, 176.0, 352.0, 256.0),
+            background,
+        );
+        card.corner_radius = 16.0;
+        card.border_color = Some(muted);
+        card.border_width = 1.0;
+        let card = card.id;
+        builder
+            .frame(Some(card), "Icon", (32.0, 32.0, 48.0, 48.0), tint)
+            .corner_radius = 12.0;
+        text_line(builder, card, "Title", (32.0, 112.0, 160.0, 16.0), text);
+        text_line(builder, card, "Body", (32.0, 148.0, 280.0, 10.0), muted);
+        text_line(builder, card, "Body", (32.0, 168.0, 240.0, 10.0), muted);
+    }
+
+    let footer = builder
+        .frame(Some(page), "Footer", (0.0, 1160.0, 1280.0, 280.0), dark)
+        .id;
+    builder
+        .frame(Some(footer), "Logo", (80.0, 64.0, 32.0, 32.0), primary)
+        .corner_radius = 8.0;
+    for column in 0..3 {
+        let x = 760.0 + column as f32 * 160.0;
+        for row in 0..4 {
+            let y = 64.0 + row as f32 * 32.0;
+            text_line(builder, footer, "Link", (x, y, 80.0, 8.0), muted);
+        }
+    }
+}
+
+fn build_icon_set(builder: &mut Builder) {
+    let background = hsla(0.0, 0.0, 1.0, 1.0);
+    let keyline = hsla(190.0 / 360.0, 0.70, 0.50, 0.25);
+    let glyph = hsla(230.0 / 360.0, 0.20, 0.20, 1.0);
+
+    let names = [
+        "Home", "Search", "Settings", "User", "Mail", "Calendar", "Camera", "Heart", "Star",
+        "Trash", "Lock", "Bell",
+    ];
+
+    let sheet = builder.frame(None, "Icon Set", (0.0, 0.0, 520.0, 392.0), background);
+    sheet.corner_radius = 16.0;
+    sheet.shadows = smallvec![soft_shadow()];
+    let sheet = sheet.id;
+
+    for (index, name) in names.iter().enumerate() {
+        let x = 40.0 + (index % 4) as f32 * 120.0;
+        let y = 40.0 + (index / 4) as f32 * 112.0;
+        let artboard = builder.frame(
+            Some(sheet),
+            &format!("Icon / {name}"),
+            (x, y, 80.0, 80.0),
+            background,
+        );
+        artboard.border_color = Some(keyline);
+        artboard.border_width = 1.0;
+        let artboard = artboard.id;
+
+        let keylines = builder.frame(Some(artboard), "Keyline", (16.0, 16.0, 48.0, 48.0), keyline);
+        keylines.fill = None;
+        keylines.border_color = Some(keyline);
+        keylines.border_width = 1.0;
+        keylines.corner_radius = 8.0;
+
+        // A simple placeholder glyph in a slightly different shape for each icon
+        let size = 24.0 + (index % 3) as f32 * 4.0;
+        let offset = (80.0 - size) / 2.0;
+        builder
+            .frame(Some(artboard), "Glyph", (offset, offset, size, size), glyph)
+            .corner_radius = [0.0, 6.0, size / 2.0][index % 3];
+    }
+}
+
+fn build_flow_diagram(builder: &mut Builder) {
+    let background = hsla(40.0 / 360.0, 0.30, 0.97, 1.0);
+    let terminal = hsla(150.0 / 360.0, 0.45, 0.55, 1.0);
+    let step = hsla(0.0, 0.0, 1.0, 1.0);
+    let decision = hsla(40.0 / 360.0, 0.90, 0.65, 1.0);
+    let line = hsla(220.0 / 360.0, 0.15, 0.45, 1.0);
+    let text = hsla(220.0 / 360.0, 0.25, 0.20, 1.0);
+
+    let board = builder.frame(None, "Flow", (0.0, 0.0, 880.0, 520.0), background);
+    board.corner_radius = 16.0;
+    let board = board.id;
+
+    let node = |builder: &mut Builder, name: &str, x: f32, y: f32, fill: Hsla, radius: f32| {
+        let shape = builder.frame(Some(board), name, (x, y, 160.0, 64.0), fill);
+        shape.corner_radius = radius;
+        shape.border_color = Some(line);
+        shape.border_width = 1.0;
+        shape.shadows = smallvec![soft_shadow()];
+        let shape = shape.id;
+        text_line(builder, shape, "Label", (40.0, 28.0, 80.0, 8.0), text);
+    };
+    node(builder, "Start", 40.0, 72.0, terminal, 32.0);
+    node(builder, "Sign In", 260.0, 72.0, step, 8.0);
+    node(builder, "Signed In?", 480.0, 72.0, decision, 24.0);
+    node(builder, "Dashboard", 700.0, 72.0, terminal, 32.0);
+    node(builder, "Reset Password", 480.0, 328.0, step, 8.0);
+
+    // Connectors run between the shapes' edges
+    for (name, bounds) in [
+        ("Start → Sign In", (200.0, 103.0, 60.0, 2.0)),
+        ("Sign In → Signed In?", (420.0, 103.0, 60.0, 2.0)),
+        ("Yes", (640.0, 103.0, 60.0, 2.0)),
+        ("No", (559.0, 136.0, 2.0, 192.0)),
+        ("Reset Password → Sign In", (339.0, 359.0, 141.0, 2.0)),
+        ("Back to Sign In", (339.0, 136.0, 2.0, 225.0)),
+    ] {
+        builder.frame(Some(board), name, bounds, line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_templates_are_well_formed() {
+        for template in Template::ALL {
+            let frames = template.build();
+            assert!(!frames.is_empty(), "{} is empty", template.label());
+
+            // Every child follows its parent and, unless the parent scrolls, lies within
+            // its bounds
+            let bounds = absolute_bounds(&frames);
+            for (parent_index, parent) in frames.iter().enumerate() {
+                for child_id in &parent.children {
+                    let child_index = frames.iter().position(|frame| frame.id == *child_id);
+                    let child_index = child_index.expect("child is part of the template");
+                    assert!(child_index > parent_index);
+                    if parent.is_scrollable() {
+                        continue;
+                    }
+
+                    let (parent, child) = (bounds[parent_index], bounds[child_index]);
+                    assert!(
+                        child.origin.x >= parent.origin.x
+                            && child.origin.y >= parent.origin.y
+                            && child.origin.x + child.size.width
+                                <= parent.origin.x + parent.size.width
+                            && child.origin.y + child.size.height
+                                <= parent.origin.y + parent.size.height,
+                        "{} in {} is out of bounds",
+                        frames[child_index].name(),
+                        template.label(),
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_absolute_bounds() {
+        let frames = Template::MobileApp.build();
+        let bounds = absolute_bounds(&frames);
+        let row = frames
+            .iter()
+            .position(|frame| frame.name() == "Row 2")
+            .unwrap();
+        let thumbnail = frames[row].children[0];
+        let thumbnail = frames
+            .iter()
+            .position(|frame| frame.id == thumbnail)
+            .unwrap();
+
+        // List at y 112, second row 104 into it, thumbnail 12 into that
+        assert_eq!(bounds[thumbnail].origin, point(28.0, 228.0));
+    }
+}
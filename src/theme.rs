@@ -17,11 +17,13 @@
 //! allowing for theme variants and potential future theme customization.
 
 use gpui::{hsla, App, Global, Hsla, SharedString};
+use serde::{Deserialize, Serialize};
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
 
 /// Represents the available theme variants
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ThemeVariant {
     OneDark,
     OneLight,
@@ -33,6 +35,31 @@ impl Default for ThemeVariant {
     }
 }
 
+impl ThemeVariant {
+    pub const ALL: [ThemeVariant; 2] = [ThemeVariant::OneDark, ThemeVariant::OneLight];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ThemeVariant::OneDark => "Atom One Dark",
+            ThemeVariant::OneLight => "Atom One Light",
+        }
+    }
+
+    pub fn palette(self) -> Palette {
+        match self {
+            ThemeVariant::OneDark => one_dark(),
+            ThemeVariant::OneLight => one_light(),
+        }
+    }
+
+    /// The variant a theme was built from, themes are identified by name
+    pub fn of(theme: &Theme) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|variant| variant.name() == theme.name.as_ref())
+    }
+}
+
 /// Atom One palette colors for both themes
 #[derive(Debug, Clone)]
 pub struct Palette {
@@ -312,7 +339,11 @@ impl ActiveTheme for App {
 impl Theme {
     /// Create a new theme with the default variant
     pub fn default() -> Self {
-        Self::from_palette("Atom One Dark", one_dark())
+        Self::from_variant(ThemeVariant::default())
+    }
+
+    pub fn from_variant(variant: ThemeVariant) -> Self {
+        Self::from_palette(variant.name(), variant.palette())
     }
 
     pub fn from_palette(name: &str, palette: Palette) -> Self {
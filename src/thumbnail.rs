@@ -0,0 +1,213 @@
+//! # Frame Thumbnails
+//!
+//! Small previews of top-level frames for the frame navigator. A thumbnail is the
+//! frame's subtree scaled down to fit a fixed size, kept as nested shapes so it can be
+//! drawn with children clipped to their frame the same way the canvas draws them.
+//!
+//! Thumbnails are cached per frame along with a fingerprint of everything they show.
+//! A cached thumbnail is only rebuilt once its frame's fingerprint changes, so frames
+//! that weren't touched cost a walk over their nodes and nothing more.
+
+use crate::node::{frame::FrameNode, NodeCommon, NodeId};
+use gpui::{Bounds, Hsla, Point, Size};
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+/// Space a thumbnail is fitted into, in pixels
+pub const THUMBNAIL_SIZE: Size<f32> = Size {
+    width: 96.0,
+    height: 64.0,
+};
+
+/// A node drawn into a thumbnail, already scaled
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThumbnailShape {
+    /// Relative to the parent shape, or to the thumbnail for the frame itself
+    pub bounds: Bounds<f32>,
+    pub fill: Option<Hsla>,
+    /// Only set for visible borders, which thumbnails draw as hairlines
+    pub border_color: Option<Hsla>,
+    pub corner_radius: f32,
+    pub children: Vec<ThumbnailShape>,
+}
+
+/// The subtree of `root` scaled to fit `size` and centered in it
+pub fn build_thumbnail<'a>(
+    root: NodeId,
+    size: Size<f32>,
+    lookup: &impl Fn(NodeId) -> Option<&'a FrameNode>,
+) -> Option<ThumbnailShape> {
+    let frame = lookup(root)?;
+    let frame_size = frame.bounds().size;
+    // Frames squashed flat still get a thumbnail, just an empty looking one
+    let scale =
+        (size.width / frame_size.width.max(1.0)).min(size.height / frame_size.height.max(1.0));
+    let origin = Point::new(
+        (size.width - frame_size.width * scale) / 2.0,
+        (size.height - frame_size.height * scale) / 2.0,
+    );
+    Some(shape(frame, origin, scale, lookup))
+}
+
+fn shape<'a>(
+    node: &FrameNode,
+    origin: Point<f32>,
+    scale: f32,
+    lookup: &impl Fn(NodeId) -> Option<&'a FrameNode>,
+) -> ThumbnailShape {
+    let children = node
+        .children()
+        .iter()
+        .filter_map(|child_id| lookup(*child_id))
+        .filter(|child| !child.hidden)
+        .map(|child| {
+            let layout = child.layout();
+            shape(
+                child,
+                Point::new(layout.x * scale, layout.y * scale),
+                scale,
+                lookup,
+            )
+        })
+        .collect();
+
+    let size = node.bounds().size;
+    ThumbnailShape {
+        bounds: Bounds {
+            origin,
+            size: Size::new(size.width * scale, size.height * scale),
+        },
+        fill: node.fill(),
+        border_color: node.border_color().filter(|_| node.border_width() > 0.0),
+        corner_radius: node.corner_radius() * scale,
+        children,
+    }
+}
+
+/// Hash of everything a thumbnail of `root` shows
+fn fingerprint<'a>(root: NodeId, lookup: &impl Fn(NodeId) -> Option<&'a FrameNode>) -> Option<u64> {
+    fn visit<'a>(
+        node: &FrameNode,
+        hasher: &mut DefaultHasher,
+        lookup: &impl Fn(NodeId) -> Option<&'a FrameNode>,
+    ) {
+        let layout = node.layout();
+        let color = |color: Option<Hsla>| color.map(|c| [c.h, c.s, c.l, c.a].map(f32::to_bits));
+        node.id.0.hash(hasher);
+        [layout.width, layout.height].map(f32::to_bits).hash(hasher);
+        color(node.fill()).hash(hasher);
+        color(node.border_color().filter(|_| node.border_width() > 0.0)).hash(hasher);
+        node.corner_radius().to_bits().hash(hasher);
+        node.children().len().hash(hasher);
+        // Positions are hashed by the parent, so moving a frame around the canvas
+        // leaves its thumbnail alone
+        for child in node
+            .children()
+            .iter()
+            .filter_map(|child_id| lookup(*child_id))
+            .filter(|child| !child.hidden)
+        {
+            [child.layout().x, child.layout().y]
+                .map(f32::to_bits)
+                .hash(hasher);
+            visit(child, hasher, lookup);
+        }
+    }
+
+    let mut hasher = DefaultHasher::new();
+    visit(lookup(root)?, &mut hasher, lookup);
+    Some(hasher.finish())
+}
+
+/// Thumbnails of top-level frames, rebuilt when what they show changes
+#[derive(Debug, Default)]
+pub struct ThumbnailCache {
+    entries: HashMap<NodeId, (u64, ThumbnailShape)>,
+}
+
+impl ThumbnailCache {
+    /// The thumbnail of `root`, built now if there is no up to date one
+    pub fn get<'a>(
+        &mut self,
+        root: NodeId,
+        lookup: &impl Fn(NodeId) -> Option<&'a FrameNode>,
+    ) -> Option<&ThumbnailShape> {
+        let fingerprint = fingerprint(root, lookup)?;
+        let stale = self
+            .entries
+            .get(&root)
+            .is_none_or(|(cached, _)| *cached != fingerprint);
+        if stale {
+            let thumbnail = build_thumbnail(root, THUMBNAIL_SIZE, lookup)?;
+            self.entries.insert(root, (fingerprint, thumbnail));
+        }
+        self.entries.get(&root).map(|(_, thumbnail)| thumbnail)
+    }
+
+    /// Forget the thumbnails of frames that aren't in `roots` anymore
+    pub fn retain(&mut self, roots: &[NodeId]) {
+        self.entries.retain(|node_id, _| roots.contains(node_id));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::hsla;
+
+    fn frames() -> Vec<FrameNode> {
+        let mut page = FrameNode::with_rect(NodeId::new(1), 500.0, 500.0, 384.0, 192.0);
+        page.children = vec![NodeId::new(2)];
+        let card = FrameNode::with_rect(NodeId::new(2), 100.0, 50.0, 80.0, 40.0);
+        vec![page, card]
+    }
+
+    fn lookup<'a>(frames: &'a [FrameNode]) -> impl Fn(NodeId) -> Option<&'a FrameNode> + 'a {
+        move |node_id| frames.iter().find(|frame| frame.id == node_id)
+    }
+
+    #[test]
+    fn test_thumbnail_fits_and_centers() {
+        let frames = frames();
+        let thumbnail = build_thumbnail(NodeId::new(1), THUMBNAIL_SIZE, &lookup(&frames)).unwrap();
+
+        // 384x192 fits the width at a quarter of the size, leaving 8px above and below
+        assert_eq!(thumbnail.bounds.size, Size::new(96.0, 48.0));
+        assert_eq!(thumbnail.bounds.origin, Point::new(0.0, 8.0));
+        let card = &thumbnail.children[0];
+        assert_eq!(card.bounds.origin, Point::new(25.0, 12.5));
+        assert_eq!(card.bounds.size, Size::new(20.0, 10.0));
+    }
+
+    #[test]
+    fn test_cache_rebuilds_changed_frames() {
+        let mut frames = frames();
+        let mut cache = ThumbnailCache::default();
+        let before = cache
+            .get(NodeId::new(1), &lookup(&frames))
+            .cloned()
+            .unwrap();
+
+        // Moving the frame itself on the canvas doesn't change its thumbnail
+        frames[0].layout.x = -300.0;
+        assert_eq!(cache.get(NodeId::new(1), &lookup(&frames)), Some(&before));
+
+        frames[1].fill = Some(hsla(0.3, 0.5, 0.5, 1.0));
+        let after = cache.get(NodeId::new(1), &lookup(&frames)).unwrap();
+        assert_eq!(after.children[0].fill, Some(hsla(0.3, 0.5, 0.5, 1.0)));
+
+        frames[1].hidden = true;
+        let hidden = cache.get(NodeId::new(1), &lookup(&frames)).unwrap();
+        assert!(hidden.children.is_empty());
+
+        cache.retain(&[]);
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(NodeId::new(9), &lookup(&frames)), None);
+    }
+}
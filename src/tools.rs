@@ -16,12 +16,12 @@
 
 #![allow(unused, dead_code)]
 use crate::canvas_element::CanvasElement;
-use crate::{canvas::LunaCanvas, theme::Theme};
+use crate::{canvas::LunaCanvas, color::ColorTarget, theme::Theme, AppState, ShowColorPicker};
 use gpui::{
-    actions, div, hsla, point, prelude::*, px, svg, App, Application, AssetSource, BoxShadow,
+    actions, div, hsla, point, prelude::*, px, svg, App, Application, AssetSource, BoxShadow, Div,
     ElementId, Entity, FocusHandle, Focusable, Global, Hsla, IntoElement, Keystroke, Menu,
-    MenuItem, Modifiers, Pixels, Point, SharedString, TitlebarOptions, UpdateGlobal, WeakEntity,
-    Window, WindowBackgroundAppearance, WindowOptions,
+    MenuItem, Modifiers, Pixels, Point, SharedString, Stateful, TitlebarOptions, UpdateGlobal,
+    WeakEntity, Window, WindowBackgroundAppearance, WindowOptions,
 };
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
@@ -33,6 +33,8 @@ pub enum Tool {
     /// Standard selection tool for clicking, dragging, and manipulating elements
     #[default]
     Selection,
+    /// Tool for picking up the fill of a node as the current color
+    Eyedropper,
     /// Tool for creating and editing connectors between elements
     ///
     /// Creates arrows that can either stand alone or connect elements while
@@ -69,6 +71,7 @@ impl Tool {
         match self {
             Tool::Selection => "svg/arrow_pointer.svg".into(),
             Tool::Arrow => "svg/arrow_tool.svg".into(),
+            Tool::Eyedropper => "svg/pipette.svg".into(),
             Tool::Frame => "svg/frame.svg".into(),
             Tool::Hand => "svg/hand.svg".into(),
             Tool::Image => "svg/image.svg".into(),
@@ -204,12 +207,14 @@ impl RenderOnce for ToolButton {
 ///
 /// This component forms the primary tool selection interface in the application.
 #[derive(IntoElement)]
-pub struct ToolStrip {}
+pub struct ToolStrip {
+    app_state: Entity<AppState>,
+}
 
 impl ToolStrip {
     /// Creates a new ToolStrip with default configuration
-    pub fn new() -> Self {
-        ToolStrip {}
+    pub fn new(app_state: Entity<AppState>) -> Self {
+        ToolStrip { app_state }
     }
 }
 
@@ -251,11 +256,12 @@ impl RenderOnce for ToolStrip {
                     .gap(px(9.))
                     .child(tool_button(Tool::Selection))
                     .child(tool_button(Tool::Hand))
+                    .child(tool_button(Tool::Eyedropper))
                     .child(tool_divider())
                     .child(tool_button(Tool::Prompt).disabled(true))
                     .child(tool_divider())
                     .child(tool_button(Tool::Pencil).disabled(true))
-                    .child(tool_button(Tool::Pen).disabled(true))
+                    .child(tool_button(Tool::Pen))
                     .child(tool_button(Tool::TextCursor).disabled(true))
                     .child(tool_divider())
                     .child(tool_button(Tool::Frame))
@@ -268,7 +274,82 @@ impl RenderOnce for ToolStrip {
                     .child(tool_button(Tool::Arrow).disabled(true)),
             )
             .child(
-                div().w_full().flex().flex_col().items_center(), // .child(CurrentColorTool::new()),
+                div()
+                    .w_full()
+                    .flex()
+                    .flex_col()
+                    .items_center()
+                    .child(CurrentColorTool::new(self.app_state)),
             )
     }
 }
+
+/// Current fill and stroke colors, drawn as two overlapping swatches
+///
+/// The swatch in front is the one picked colors are applied to, clicking it opens the
+/// color picker. Clicking the swatch behind brings it to the front.
+#[derive(IntoElement)]
+pub struct CurrentColorTool {
+    app_state: Entity<AppState>,
+}
+
+impl CurrentColorTool {
+    pub fn new(app_state: Entity<AppState>) -> Self {
+        CurrentColorTool { app_state }
+    }
+}
+
+impl RenderOnce for CurrentColorTool {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+        let state = self.app_state.read(cx);
+        let target = state.color_target;
+
+        const SWATCH_SIZE: f32 = 15.;
+
+        let fill = div()
+            .id("current_fill")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size(px(SWATCH_SIZE))
+            .rounded(px(3.))
+            .border_1()
+            .border_color(theme.tokens.inactive_border)
+            .bg(state.current_background_color);
+        let stroke = div()
+            .id("current_stroke")
+            .absolute()
+            .bottom_0()
+            .right_0()
+            .size(px(SWATCH_SIZE))
+            .rounded(px(3.))
+            .border_4()
+            .border_color(state.current_border_color)
+            .bg(theme.tokens.background_secondary);
+
+        let in_front = |swatch: Stateful<Div>| {
+            swatch.on_click(|_, window, cx| {
+                window.dispatch_action(Box::new(ShowColorPicker), cx);
+            })
+        };
+        let app_state = self.app_state.clone();
+        let behind = move |swatch: Stateful<Div>| {
+            swatch.on_click(move |_, _, cx| {
+                app_state.update(cx, |state, cx| {
+                    state.color_target = state.color_target.toggle();
+                    cx.notify();
+                });
+            })
+        };
+        div()
+            .id("current_color_tool")
+            .relative()
+            .size(px(23.))
+            .my(px(4.))
+            .map(|this| match target {
+                ColorTarget::Fill => this.child(behind(stroke)).child(in_front(fill)),
+                ColorTarget::Stroke => this.child(behind(fill)).child(in_front(stroke)),
+            })
+    }
+}
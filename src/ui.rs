@@ -7,10 +7,26 @@
 //! ## UI Architecture
 //!
 //! Luna's UI is organized into several key components:
+//! - **Batch Rename**: Dialog renaming the selection with a pattern
+//! - **Color Picker**: Popover picking the current fill or stroke, live on the selection
+//! - **Context Menu**: Actions on the selection, opened by right-clicking the canvas
+//! - **Design Lint**: Warnings about inconsistencies in the document, with fixes
+//! - **Document Info**: Summary of the document's node types, colors and hierarchy
+//! - **Frame Navigator**: Thumbnails of the top-level frames for jumping between them
 //! - **Inspector**: Properties panel for viewing and editing element attributes
 //! - **Layer List**: Hierarchical view of elements in the document
+//! - **Library Browser**: Components, styles and color tokens from the shared library
+//! - **Library Review**: Updates to library items the document uses, taken one by one
+//! - **Link Editor**: Dialog attaching a link to the selection, followed in preview
+//! - **Page List**: Pages of the document, switched between and renamed in place
+//! - **Password Prompt**: Dialog asking for the password of a protected document
 //! - **Property**: Reusable property editing components
+//! - **Quick Open**: Fuzzy finder for jumping to nodes by name
+//! - **Shortcuts**: Cheat sheet of the registered keyboard shortcuts
 //! - **Sidebar**: Container for various panels and tools
+//! - **Template Gallery**: Starter documents to begin a new design from
+//! - **Toasts**: Notifications about failures and other events, in the window corner
+//! - **Version Diff**: Changes since the document was last saved, node by node
 //!
 //! The UI system is built on GPUI's component model, with a focus on composability
 //! and reactive updates based on application state changes.
@@ -27,9 +43,26 @@ use gpui::{
 use std::{fs, path::PathBuf};
 use strum::Display;
 
+pub mod batch_rename;
+pub mod color_picker;
+pub mod context_menu;
+pub mod design_lint;
+pub mod detached_panel;
+pub mod document_info;
+pub mod frame_navigator;
 pub mod inspector;
 pub mod layer_list;
+pub mod library_browser;
+pub mod library_review;
+pub mod link_editor;
+pub mod page_list;
+pub mod password_prompt;
 mod property;
+pub mod quick_open;
+pub mod shortcuts;
+pub mod template_gallery;
+pub mod toasts;
+pub mod version_diff;
 pub mod sidebar;
 
 pub struct Titlebar {}
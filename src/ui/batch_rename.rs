@@ -0,0 +1,338 @@
+//! Batch rename dialog for the selected nodes.
+//!
+//! Nodes are numbered in layer order. The dialog previews the new names as the pattern
+//! is typed, and confirming emits all of them at once so the owner can apply them as a
+//! single change.
+
+use gpui::{
+    actions, div, prelude::*, px, App, ElementId, Entity, EventEmitter, FocusHandle, Focusable,
+    IntoElement, KeyDownEvent, MouseButton, SharedString, Window,
+};
+
+use crate::{
+    canvas::LunaCanvas,
+    node::{NodeCommon, NodeId},
+    rename::RenamePattern,
+    theme::Theme,
+};
+
+use super::Titlebar;
+
+actions!(batch_rename, [Confirm, Dismiss, NextField, PreviousField]);
+
+pub enum BatchRenameEvent {
+    /// The pattern was confirmed, with the new name for each node
+    Confirmed(Vec<(NodeId, String)>),
+    Dismissed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    Find,
+    Replace,
+    Prefix,
+    Suffix,
+    Start,
+}
+
+impl Field {
+    const ALL: [Field; 6] = [
+        Field::Name,
+        Field::Find,
+        Field::Replace,
+        Field::Prefix,
+        Field::Suffix,
+        Field::Start,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Field::Name => "Rename to",
+            Field::Find => "Find",
+            Field::Replace => "Replace",
+            Field::Prefix => "Prefix",
+            Field::Suffix => "Suffix",
+            Field::Start => "Start at",
+        }
+    }
+
+    fn placeholder(self) -> &'static str {
+        match self {
+            Field::Name => "Keep current name, %n inserts a number",
+            Field::Find | Field::Replace | Field::Prefix | Field::Suffix => "",
+            Field::Start => "1",
+        }
+    }
+}
+
+pub struct BatchRename {
+    focus_handle: FocusHandle,
+    /// The selected nodes and their current names, in layer order
+    nodes: Vec<(NodeId, String)>,
+    pattern: RenamePattern,
+    /// What has been typed into the start field, kept separately so it can be empty
+    start: String,
+    active_field: Field,
+}
+
+impl BatchRename {
+    const WIDTH: f32 = 420.;
+    /// Nodes past this are renamed but not previewed
+    const MAX_PREVIEW: usize = 8;
+
+    pub fn new(canvas: &Entity<LunaCanvas>, cx: &mut Context<Self>) -> Self {
+        let canvas = canvas.read(cx);
+        let nodes = canvas
+            .nodes()
+            .iter()
+            .filter(|node| canvas.is_node_selected(node.id()))
+            .map(|node| (node.id(), node.name()))
+            .collect();
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            nodes,
+            pattern: RenamePattern::default(),
+            start: String::new(),
+            active_field: Field::Name,
+        }
+    }
+
+    fn field_text(&self, field: Field) -> &str {
+        match field {
+            Field::Name => &self.pattern.name,
+            Field::Find => &self.pattern.find,
+            Field::Replace => &self.pattern.replace,
+            Field::Prefix => &self.pattern.prefix,
+            Field::Suffix => &self.pattern.suffix,
+            Field::Start => &self.start,
+        }
+    }
+
+    fn field_text_mut(&mut self, field: Field) -> &mut String {
+        match field {
+            Field::Name => &mut self.pattern.name,
+            Field::Find => &mut self.pattern.find,
+            Field::Replace => &mut self.pattern.replace,
+            Field::Prefix => &mut self.pattern.prefix,
+            Field::Suffix => &mut self.pattern.suffix,
+            Field::Start => &mut self.start,
+        }
+    }
+
+    fn renamed(&self) -> impl Iterator<Item = (NodeId, &str, String)> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(index, (node_id, name))| {
+                (*node_id, name.as_str(), self.pattern.apply(name, index))
+            })
+    }
+
+    /// Edit the active field from raw key presses, like the quick-open query
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.platform || keystroke.modifiers.control {
+            return;
+        }
+
+        let field = self.active_field;
+        if keystroke.key == "backspace" {
+            self.field_text_mut(field).pop();
+        } else if let Some(text) = keystroke
+            .key_char
+            .as_ref()
+            .filter(|text| !text.chars().any(char::is_control))
+        {
+            if field == Field::Start && !text.chars().all(|c| c.is_ascii_digit()) {
+                return;
+            }
+            self.field_text_mut(field).push_str(text);
+        } else {
+            return;
+        }
+
+        self.pattern.start = self.start.parse().unwrap_or(1);
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn next_field(&mut self, _: &NextField, _window: &mut Window, cx: &mut Context<Self>) {
+        let index = Field::ALL
+            .iter()
+            .position(|f| *f == self.active_field)
+            .unwrap_or(0);
+        self.active_field = Field::ALL[(index + 1) % Field::ALL.len()];
+        cx.notify();
+    }
+
+    fn previous_field(&mut self, _: &PreviousField, _window: &mut Window, cx: &mut Context<Self>) {
+        let index = Field::ALL
+            .iter()
+            .position(|f| *f == self.active_field)
+            .unwrap_or(0);
+        self.active_field = Field::ALL[(index + Field::ALL.len() - 1) % Field::ALL.len()];
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.pattern.is_empty() {
+            cx.emit(BatchRenameEvent::Dismissed);
+            return;
+        }
+
+        let names = self
+            .renamed()
+            .map(|(node_id, _, new_name)| (node_id, new_name))
+            .collect();
+        cx.emit(BatchRenameEvent::Confirmed(names));
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(BatchRenameEvent::Dismissed);
+    }
+}
+
+impl EventEmitter<BatchRenameEvent> for BatchRename {}
+
+impl Focusable for BatchRename {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for BatchRename {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+
+        let fields = Field::ALL.into_iter().map(|field| {
+            let text = self.field_text(field);
+            let is_active = field == self.active_field;
+            div()
+                .id(ElementId::Name(
+                    format!("batch-rename-{}", field.label()).into(),
+                ))
+                .flex()
+                .items_center()
+                .gap_2()
+                .px_2()
+                .py_1()
+                .rounded(px(4.))
+                .when(is_active, |this| this.bg(theme.tokens.surface0))
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.active_field = field;
+                    cx.notify();
+                }))
+                .child(
+                    div()
+                        .w(px(72.))
+                        .text_color(theme.tokens.subtext0)
+                        .child(field.label()),
+                )
+                .child(if text.is_empty() {
+                    div()
+                        .text_color(theme.tokens.foreground_muted)
+                        .child(field.placeholder())
+                } else {
+                    div().text_color(theme.tokens.text).child(text.to_string())
+                })
+                .when(is_active, |this| {
+                    this.child(div().w(px(1.)).h(px(14.)).bg(theme.tokens.cursor))
+                })
+        });
+
+        let preview = self
+            .renamed()
+            .take(Self::MAX_PREVIEW)
+            .map(|(_, name, new_name)| {
+                div()
+                    .flex()
+                    .gap_2()
+                    .px_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_color(theme.tokens.subtext0)
+                            .child(SharedString::from(name.to_string())),
+                    )
+                    .child("→")
+                    .child(div().flex_1().child(new_name))
+            });
+        let hidden_count = self.nodes.len().saturating_sub(Self::MAX_PREVIEW);
+
+        div()
+            .id("batch-rename-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .flex_col()
+            .items_center()
+            .pt(px(Titlebar::HEIGHT * 3.))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(BatchRenameEvent::Dismissed)),
+            )
+            .child(
+                div()
+                    .id("batch-rename")
+                    .key_context("BatchRename")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::confirm))
+                    .on_action(cx.listener(Self::dismiss))
+                    .on_action(cx.listener(Self::next_field))
+                    .on_action(cx.listener(Self::previous_field))
+                    .on_key_down(cx.listener(Self::handle_key_down))
+                    // Keep clicks inside the dialog from reaching the backdrop
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(Self::WIDTH))
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .p_1()
+                    .bg(theme.tokens.background_secondary)
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .rounded(px(8.))
+                    .shadow_lg()
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(theme.tokens.inactive_border)
+                            .child(match self.nodes.len() {
+                                1 => "Rename 1 layer".to_string(),
+                                count => format!("Rename {count} layers"),
+                            }),
+                    )
+                    .children(fields)
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(2.))
+                            .py_1()
+                            .border_t_1()
+                            .border_color(theme.tokens.inactive_border)
+                            .children(preview)
+                            .when(hidden_count > 0, |this| {
+                                this.child(
+                                    div()
+                                        .px_2()
+                                        .text_color(theme.tokens.foreground_muted)
+                                        .child(format!("and {hidden_count} more")),
+                                )
+                            }),
+                    ),
+            )
+    }
+}
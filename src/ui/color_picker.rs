@@ -0,0 +1,787 @@
+//! Popover for picking the current fill or stroke color.
+//!
+//! Saturation and brightness are dragged across the square, hue and opacity along the
+//! sliders under it. The selection takes on the color while it's dragged, and the
+//! whole drag is recorded as one change once it's let go of. Typing replaces the hex
+//! value, which is applied on enter. Recent colors and the eyedropper sit below.
+//!
+//! Everything can be done from the keyboard as well. Tab and shift-tab move between
+//! the controls, the arrow keys step the focused one, ten times as far with shift,
+//! and enter presses it. Steps in a row are recorded as one change, the way a drag
+//! is. Pasting a hex, `rgb()` or `hsl()` value applies it straight away.
+//!
+//! The picker always edits whichever color [`AppState::color_target`] points at, the
+//! same one the swatches in the inspector apply to.
+
+use std::{cell::Cell, rc::Rc, sync::Arc};
+
+use gpui::{
+    actions, anchored, canvas, div, fill, outline, point, prelude::*, px, size, App, BorderStyle,
+    Bounds, Corner, Entity, EventEmitter, FocusHandle, Focusable, Hsla, IntoElement, KeyDownEvent,
+    MouseButton, MouseDownEvent, MouseMoveEvent, Pixels, Point, Subscription, Window,
+};
+
+use crate::{
+    canvas::LunaCanvas,
+    color::{parse_color, parse_pasted_color, ColorFormat, ColorTarget, Hsb},
+    theme::Theme,
+    tools::{GlobalTool, Tool},
+    AppState,
+};
+
+actions!(
+    color_picker,
+    [
+        BigStepDown,
+        BigStepLeft,
+        BigStepRight,
+        BigStepUp,
+        Confirm,
+        Dismiss,
+        NextControl,
+        Paste,
+        PreviousControl,
+        StepDown,
+        StepLeft,
+        StepRight,
+        StepUp
+    ]
+);
+
+pub enum ColorPickerEvent {
+    Dismissed,
+}
+
+/// Part of the picker that's dragged to change the color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    Square,
+    Hue,
+    Alpha,
+}
+
+/// Control of the picker that has keyboard focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Target,
+    Part(Part),
+    Hex,
+    Eyedropper,
+    /// One of the recent colors, by index
+    Recent(usize),
+}
+
+pub struct ColorPicker {
+    focus_handle: FocusHandle,
+    state: Entity<AppState>,
+    canvas: Entity<LunaCanvas>,
+    /// Where the picker was opened, it's laid out next to this point
+    position: Point<Pixels>,
+    color: Hsb,
+    /// Hex value being typed, `None` while the field shows the color
+    hex: Option<String>,
+    dragging: Option<Part>,
+    /// Control the keyboard acts on, `None` until one is tabbed to or clicked
+    focused: Option<Control>,
+    /// Whether the arrow keys have changed the color since it was last recorded
+    stepping: bool,
+    /// Bounds of each part as of the last paint, for mapping drags onto them
+    part_bounds: Rc<Cell<[Bounds<Pixels>; 3]>>,
+    _subscription: Subscription,
+}
+
+impl ColorPicker {
+    const WIDTH: f32 = 220.;
+    const SQUARE_HEIGHT: f32 = 150.;
+    const SLIDER_HEIGHT: f32 = 12.;
+    /// Cells painted along each side of the square, and along each slider
+    const CELLS: usize = 24;
+    /// How far an arrow key moves a part, as a fraction of its range
+    const STEP: f32 = 0.01;
+    /// How far an arrow key moves a part with shift held
+    const BIG_STEP: f32 = 0.1;
+
+    pub fn new(
+        state: Entity<AppState>,
+        canvas: Entity<LunaCanvas>,
+        position: Point<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        // Follow the target color when it changes elsewhere, say when fill and stroke
+        // are swapped, but keep the hue the picker has while it's showing a gray
+        let subscription = cx.observe(&state, |this, state, cx| {
+            let color = state.read(cx).target_color();
+            if this.dragging.is_none() && !this.stepping && this.color.to_hsla() != color {
+                this.color = Hsb::from_hsla(color);
+                this.hex = None;
+            }
+            cx.notify();
+        });
+        let color = Hsb::from_hsla(state.read(cx).target_color());
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            state,
+            canvas,
+            position,
+            color,
+            hex: None,
+            dragging: None,
+            focused: None,
+            stepping: false,
+            part_bounds: Rc::new(Cell::new([Bounds::default(); 3])),
+            _subscription: subscription,
+        }
+    }
+
+    fn begin_drag(&mut self, part: Part, event: &MouseDownEvent, cx: &mut Context<Self>) {
+        self.end_steps(cx);
+        self.dragging = Some(part);
+        self.focused = Some(Control::Part(part));
+        self.canvas
+            .update(cx, |canvas, _| canvas.begin_color_change());
+        self.drag_to(event.position, cx);
+    }
+
+    fn drag_to(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let Some(part) = self.dragging else {
+            return;
+        };
+        let bounds = self.part_bounds.get()[part as usize];
+        let x = ((position.x - bounds.origin.x) / bounds.size.width).clamp(0.0, 1.0);
+        let y = ((position.y - bounds.origin.y) / bounds.size.height).clamp(0.0, 1.0);
+        match part {
+            Part::Square => {
+                self.color.saturation = x;
+                self.color.brightness = 1.0 - y;
+            }
+            Part::Hue => self.color.hue = x,
+            Part::Alpha => self.color.alpha = x,
+        }
+        self.show_color(cx);
+    }
+
+    /// Color the selection with the picker's color without recording a change
+    fn show_color(&mut self, cx: &mut Context<Self>) {
+        let color = self.color.to_hsla();
+        self.hex = None;
+        let target = self.state.update(cx, |state, cx| {
+            state.set_target_color(color);
+            cx.notify();
+            state.color_target
+        });
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.preview_selected_color(target, color, cx)
+        });
+        cx.notify();
+    }
+
+    fn end_drag(&mut self, cx: &mut Context<Self>) {
+        if self.dragging.take().is_some() {
+            self.record_color(cx);
+        }
+    }
+
+    /// Record the color shown since the last arrow key steps as one change
+    fn end_steps(&mut self, cx: &mut Context<Self>) {
+        if std::mem::take(&mut self.stepping) {
+            self.record_color(cx);
+        }
+    }
+
+    /// Record the color shown on the selection as one change, and note it as recent
+    fn record_color(&mut self, cx: &mut Context<Self>) {
+        let color = self.color.to_hsla();
+        self.state.update(cx, |state, cx| {
+            state.note_recent_color(color);
+            cx.notify();
+        });
+        self.canvas
+            .update(cx, |canvas, _| canvas.finish_color_change());
+    }
+
+    /// Apply a color picked in one go, from the hex field or a recent swatch
+    fn pick(&mut self, color: Hsla, cx: &mut Context<Self>) {
+        self.end_steps(cx);
+        self.color = Hsb::from_hsla(color);
+        self.hex = None;
+        self.canvas
+            .update(cx, |canvas, cx| canvas.pick_color(color, cx));
+        cx.notify();
+    }
+
+    fn set_target(&mut self, target: ColorTarget, cx: &mut Context<Self>) {
+        self.end_drag(cx);
+        self.end_steps(cx);
+        self.state.update(cx, |state, cx| {
+            state.color_target = target;
+            cx.notify();
+        });
+    }
+
+    /// Put the picker away and sample the next node clicked on the canvas
+    fn pick_up_eyedropper(&mut self, cx: &mut Context<Self>) {
+        cx.set_global(GlobalTool(Arc::new(Tool::Eyedropper)));
+        self.dismiss(cx);
+    }
+
+    /// Typing replaces the hex value rather than editing it, like the link editor
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.platform || keystroke.modifiers.control {
+            return;
+        }
+
+        if keystroke.key == "backspace" {
+            if let Some(hex) = &mut self.hex {
+                hex.pop();
+            }
+        } else if let Some(text) = keystroke
+            .key_char
+            .as_ref()
+            .filter(|text| !text.chars().any(char::is_control))
+        {
+            self.end_steps(cx);
+            self.focused = Some(Control::Hex);
+            self.hex.get_or_insert_with(String::new).push_str(text);
+        } else {
+            return;
+        }
+
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    /// Apply a pasted color straight away, anything else is put in the hex field to
+    /// show it couldn't be read
+    fn paste(&mut self, _: &Paste, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        match parse_pasted_color(&text) {
+            Some(color) => self.pick(color, cx),
+            None => {
+                self.focused = Some(Control::Hex);
+                self.hex = Some(text.trim().to_string());
+                cx.notify();
+            }
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        self.end_steps(cx);
+        match self.hex.as_deref().map(parse_color) {
+            Some(Some(color)) => self.pick(color, cx),
+            // The field stays as typed so the mistake can be seen
+            Some(None) => {}
+            None => match self.focused {
+                Some(Control::Target) => {
+                    let target = self.state.read(cx).color_target.toggle();
+                    self.set_target(target, cx);
+                }
+                Some(Control::Eyedropper) => self.pick_up_eyedropper(cx),
+                Some(Control::Recent(index)) => {
+                    if let Some(color) = self.state.read(cx).recent_colors.get(index).copied() {
+                        self.pick(color, cx);
+                    }
+                }
+                _ => self.dismiss(cx),
+            },
+        }
+    }
+
+    /// Every control in tab order, with a stop for each recent color
+    fn controls(&self, cx: &App) -> Vec<Control> {
+        let recent_count = self.state.read(cx).recent_colors.len();
+        [
+            Control::Target,
+            Control::Part(Part::Square),
+            Control::Part(Part::Hue),
+            Control::Part(Part::Alpha),
+            Control::Hex,
+            Control::Eyedropper,
+        ]
+        .into_iter()
+        .chain((0..recent_count).map(Control::Recent))
+        .collect()
+    }
+
+    /// Move keyboard focus to the next control, or the previous one, wrapping around
+    fn move_focus(&mut self, forward: bool, cx: &mut Context<Self>) {
+        self.end_steps(cx);
+        let controls = self.controls(cx);
+        let count = controls.len();
+        let index = match self
+            .focused
+            .and_then(|focused| controls.iter().position(|control| *control == focused))
+        {
+            Some(index) if forward => (index + 1) % count,
+            Some(index) => (index + count - 1) % count,
+            None if forward => 0,
+            None => count - 1,
+        };
+        self.focused = Some(controls[index]);
+        cx.notify();
+    }
+
+    fn next_control(&mut self, _: &NextControl, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_focus(true, cx);
+    }
+
+    fn previous_control(
+        &mut self,
+        _: &PreviousControl,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_focus(false, cx);
+    }
+
+    /// Step the focused control by `delta`, with up being positive
+    ///
+    /// With nothing focused the arrow keys act on the square.
+    fn step(&mut self, delta: Point<f32>, cx: &mut Context<Self>) {
+        let control = *self.focused.get_or_insert(Control::Part(Part::Square));
+        let amount = delta.x + delta.y;
+        match control {
+            Control::Part(Part::Square) => {
+                self.color.saturation = (self.color.saturation + delta.x).clamp(0.0, 1.0);
+                self.color.brightness = (self.color.brightness + delta.y).clamp(0.0, 1.0);
+            }
+            // Hue goes round, stepping past red comes back around to it
+            Control::Part(Part::Hue) => {
+                self.color.hue = (self.color.hue + amount).rem_euclid(1.0);
+            }
+            Control::Part(Part::Alpha) => {
+                self.color.alpha = (self.color.alpha + amount).clamp(0.0, 1.0);
+            }
+            Control::Target => {
+                if delta.x != 0.0 {
+                    let target = if delta.x < 0.0 {
+                        ColorTarget::Fill
+                    } else {
+                        ColorTarget::Stroke
+                    };
+                    self.set_target(target, cx);
+                }
+                return;
+            }
+            Control::Recent(index) => {
+                let count = self.state.read(cx).recent_colors.len();
+                let index = if amount < 0.0 {
+                    index.saturating_sub(1)
+                } else {
+                    (index + 1).min(count.saturating_sub(1))
+                };
+                self.focused = Some(Control::Recent(index));
+                cx.notify();
+                return;
+            }
+            Control::Hex | Control::Eyedropper => return,
+        }
+
+        if !self.stepping {
+            self.stepping = true;
+            self.canvas
+                .update(cx, |canvas, _| canvas.begin_color_change());
+        }
+        self.show_color(cx);
+    }
+
+    fn step_up(&mut self, _: &StepUp, _window: &mut Window, cx: &mut Context<Self>) {
+        self.step(point(0.0, Self::STEP), cx);
+    }
+
+    fn step_down(&mut self, _: &StepDown, _window: &mut Window, cx: &mut Context<Self>) {
+        self.step(point(0.0, -Self::STEP), cx);
+    }
+
+    fn step_left(&mut self, _: &StepLeft, _window: &mut Window, cx: &mut Context<Self>) {
+        self.step(point(-Self::STEP, 0.0), cx);
+    }
+
+    fn step_right(&mut self, _: &StepRight, _window: &mut Window, cx: &mut Context<Self>) {
+        self.step(point(Self::STEP, 0.0), cx);
+    }
+
+    fn big_step_up(&mut self, _: &BigStepUp, _window: &mut Window, cx: &mut Context<Self>) {
+        self.step(point(0.0, Self::BIG_STEP), cx);
+    }
+
+    fn big_step_down(&mut self, _: &BigStepDown, _window: &mut Window, cx: &mut Context<Self>) {
+        self.step(point(0.0, -Self::BIG_STEP), cx);
+    }
+
+    fn big_step_left(&mut self, _: &BigStepLeft, _window: &mut Window, cx: &mut Context<Self>) {
+        self.step(point(-Self::BIG_STEP, 0.0), cx);
+    }
+
+    fn big_step_right(&mut self, _: &BigStepRight, _window: &mut Window, cx: &mut Context<Self>) {
+        self.step(point(Self::BIG_STEP, 0.0), cx);
+    }
+
+    fn handle_dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.hex.take().is_some() {
+            cx.notify();
+        } else {
+            self.dismiss(cx);
+        }
+    }
+
+    fn dismiss(&mut self, cx: &mut Context<Self>) {
+        self.end_drag(cx);
+        self.end_steps(cx);
+        cx.emit(ColorPickerEvent::Dismissed);
+    }
+
+    /// A part of the picker painted cell by cell, its bounds noted for dragging
+    fn painted_part(
+        &self,
+        part: Part,
+        paint: impl Fn(Bounds<Pixels>, &mut Window) + 'static,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let part_bounds = self.part_bounds.clone();
+        let focused = self.focused == Some(Control::Part(part));
+        let focus_color = Theme::get_global(cx).tokens.active_border;
+        let height = match part {
+            Part::Square => Self::SQUARE_HEIGHT,
+            Part::Hue | Part::Alpha => Self::SLIDER_HEIGHT,
+        };
+
+        div()
+            .w_full()
+            .h(px(height))
+            .rounded(px(3.))
+            .overflow_hidden()
+            .when(focused, |this| this.border_1().border_color(focus_color))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, event: &MouseDownEvent, _, cx| {
+                    this.begin_drag(part, event, cx)
+                }),
+            )
+            .child(
+                canvas(
+                    move |bounds, _, _| {
+                        let mut parts = part_bounds.get();
+                        parts[part as usize] = bounds;
+                        part_bounds.set(parts);
+                    },
+                    move |bounds, _, window, _| paint(bounds, window),
+                )
+                .size_full(),
+            )
+    }
+}
+
+/// Paint `color_at` across `bounds` in a grid of cells, with a marker at `marker`
+///
+/// Both are given as fractions of the width and height.
+fn paint_cells(
+    bounds: Bounds<Pixels>,
+    columns: usize,
+    rows: usize,
+    color_at: impl Fn(f32, f32) -> Hsla,
+    marker: Point<f32>,
+    window: &mut Window,
+) {
+    let cell = size(
+        bounds.size.width / columns as f32,
+        bounds.size.height / rows as f32,
+    );
+    let fraction = |index: usize, count: usize| index as f32 / (count - 1).max(1) as f32;
+    for row in 0..rows {
+        for column in 0..columns {
+            let origin =
+                bounds.origin + point(cell.width * column as f32, cell.height * row as f32);
+            // A little overlap keeps seams from showing between cells
+            let cell_bounds =
+                Bounds::new(origin, size(cell.width + px(0.5), cell.height + px(0.5)));
+            let color = color_at(fraction(column, columns), fraction(row, rows));
+            window.paint_quad(fill(cell_bounds, color));
+        }
+    }
+
+    // A white ring with a dark edge shows up on light and dark colors alike
+    let center = bounds.origin + point(bounds.size.width * marker.x, bounds.size.height * marker.y);
+    for (marker_size, color) in [(10., gpui::black().opacity(0.5)), (8., gpui::white())] {
+        let marker_bounds = Bounds::centered_at(center, size(px(marker_size), px(marker_size)));
+        window.paint_quad(
+            outline(marker_bounds, color, BorderStyle::Solid).corner_radii(px(marker_size / 2.)),
+        );
+    }
+}
+
+impl EventEmitter<ColorPickerEvent> for ColorPicker {}
+
+impl Focusable for ColorPicker {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ColorPicker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx).clone();
+        let state = self.state.read(cx);
+        let target = state.color_target;
+        let recent_colors = state.recent_colors.clone();
+        let color = self.color;
+        let focused = self.focused;
+
+        // Open away from the nearest window edges, towards the middle of the window
+        let viewport = window.viewport_size();
+        let corner = match (
+            self.position.x > viewport.width / 2.,
+            self.position.y > viewport.height / 2.,
+        ) {
+            (false, false) => Corner::TopLeft,
+            (true, false) => Corner::TopRight,
+            (false, true) => Corner::BottomLeft,
+            (true, true) => Corner::BottomRight,
+        };
+
+        let (hex, hex_valid) = match &self.hex {
+            Some(hex) => (format!("{hex}|"), parse_color(hex).is_some()),
+            None => (ColorFormat::Hex.format(color.to_hsla()), true),
+        };
+
+        let target_button = |option: ColorTarget, label: &'static str, cx: &mut Context<Self>| {
+            div()
+                .id(label)
+                .flex_1()
+                .flex()
+                .justify_center()
+                .py(px(2.))
+                .rounded(px(3.))
+                .text_color(theme.tokens.subtext0)
+                .when(option == target, |this| {
+                    this.bg(theme.tokens.surface2).text_color(theme.tokens.text)
+                })
+                .on_click(cx.listener(move |this, _, _, cx| this.set_target(option, cx)))
+                .child(label)
+        };
+
+        let hue = color.hue;
+        let opaque = color.to_hsla().opacity(1.0);
+        let alpha_background = theme.tokens.background_secondary;
+
+        div()
+            .id("color-picker-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _, _, cx| this.dismiss(cx)),
+            )
+            // Drags keep going when the pointer leaves the picker
+            .on_mouse_move(
+                cx.listener(|this, event: &MouseMoveEvent, _, cx| this.drag_to(event.position, cx)),
+            )
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _, _, cx| this.end_drag(cx)),
+            )
+            .child(
+                anchored()
+                    .position(self.position)
+                    .anchor(corner)
+                    .snap_to_window()
+                    .child(
+                        div()
+                            .id("color-picker")
+                            .key_context("ColorPicker")
+                            .track_focus(&self.focus_handle)
+                            .on_action(cx.listener(Self::confirm))
+                            .on_action(cx.listener(Self::handle_dismiss))
+                            .on_action(cx.listener(Self::paste))
+                            .on_action(cx.listener(Self::next_control))
+                            .on_action(cx.listener(Self::previous_control))
+                            .on_action(cx.listener(Self::step_up))
+                            .on_action(cx.listener(Self::step_down))
+                            .on_action(cx.listener(Self::step_left))
+                            .on_action(cx.listener(Self::step_right))
+                            .on_action(cx.listener(Self::big_step_up))
+                            .on_action(cx.listener(Self::big_step_down))
+                            .on_action(cx.listener(Self::big_step_left))
+                            .on_action(cx.listener(Self::big_step_right))
+                            .on_key_down(cx.listener(Self::handle_key_down))
+                            // Keep clicks inside the picker from reaching the backdrop
+                            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                            .w(px(Self::WIDTH))
+                            .flex()
+                            .flex_col()
+                            .gap(px(8.))
+                            .p(px(8.))
+                            .bg(theme.tokens.background_secondary)
+                            .border_1()
+                            .border_color(theme.tokens.inactive_border)
+                            .rounded(px(8.))
+                            .shadow_lg()
+                            .text_size(px(10.))
+                            .child(
+                                div()
+                                    .flex()
+                                    .p(px(1.))
+                                    .rounded(px(4.))
+                                    .bg(theme.tokens.surface0)
+                                    .when(focused == Some(Control::Target), |this| {
+                                        this.border_1().border_color(theme.tokens.active_border)
+                                    })
+                                    .child(target_button(ColorTarget::Fill, "Fill", cx))
+                                    .child(target_button(ColorTarget::Stroke, "Stroke", cx)),
+                            )
+                            .child(self.painted_part(
+                                Part::Square,
+                                move |bounds, window| {
+                                    paint_cells(
+                                        bounds,
+                                        Self::CELLS,
+                                        Self::CELLS,
+                                        |x, y| {
+                                            Hsb {
+                                                hue,
+                                                saturation: x,
+                                                brightness: 1.0 - y,
+                                                alpha: 1.0,
+                                            }
+                                            .to_hsla()
+                                        },
+                                        point(color.saturation, 1.0 - color.brightness),
+                                        window,
+                                    )
+                                },
+                                cx,
+                            ))
+                            .child(self.painted_part(
+                                Part::Hue,
+                                move |bounds, window| {
+                                    paint_cells(
+                                        bounds,
+                                        Self::CELLS,
+                                        1,
+                                        |x, _| gpui::hsla(x, 1.0, 0.5, 1.0),
+                                        point(hue, 0.5),
+                                        window,
+                                    )
+                                },
+                                cx,
+                            ))
+                            .child(self.painted_part(
+                                Part::Alpha,
+                                move |bounds, window| {
+                                    window.paint_quad(fill(bounds, alpha_background));
+                                    paint_cells(
+                                        bounds,
+                                        Self::CELLS,
+                                        1,
+                                        |x, _| opaque.opacity(x),
+                                        point(color.alpha, 0.5),
+                                        window,
+                                    )
+                                },
+                                cx,
+                            ))
+                            .child(
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap(px(6.))
+                                    .child(
+                                        div()
+                                            .flex_1()
+                                            .flex()
+                                            .items_center()
+                                            .gap(px(4.))
+                                            .px(px(6.))
+                                            .py(px(2.))
+                                            .rounded(px(4.))
+                                            .border_1()
+                                            .border_color(if !hex_valid {
+                                                theme.tokens.error
+                                            } else if focused == Some(Control::Hex) {
+                                                theme.tokens.active_border
+                                            } else {
+                                                gpui::transparent_black()
+                                            })
+                                            .bg(theme.tokens.surface0)
+                                            .text_size(px(11.))
+                                            .child(
+                                                div()
+                                                    .size(px(9.))
+                                                    .rounded(px(2.))
+                                                    .border_1()
+                                                    .border_color(theme.tokens.inactive_border)
+                                                    .bg(color.to_hsla()),
+                                            )
+                                            .child(hex),
+                                    )
+                                    .child(
+                                        div()
+                                            .id("color-picker-eyedropper")
+                                            .size(px(22.))
+                                            .flex()
+                                            .flex_none()
+                                            .items_center()
+                                            .justify_center()
+                                            .rounded(px(3.))
+                                            .hover(|this| this.bg(theme.tokens.surface1))
+                                            .when(focused == Some(Control::Eyedropper), |this| {
+                                                this.border_1()
+                                                    .border_color(theme.tokens.active_border)
+                                            })
+                                            .on_click(cx.listener(|this, _, _, cx| {
+                                                this.pick_up_eyedropper(cx)
+                                            }))
+                                            .child(
+                                                gpui::svg()
+                                                    .path(Tool::Eyedropper.src())
+                                                    .size(px(13.))
+                                                    .text_color(theme.tokens.subtext0),
+                                            ),
+                                    ),
+                            )
+                            .when(!recent_colors.is_empty(), |this| {
+                                this.child(
+                                    div().flex().flex_wrap().gap(px(4.)).children(
+                                        recent_colors.into_iter().enumerate().map(
+                                            |(index, recent)| {
+                                                div()
+                                                    .id(("color-picker-recent", index))
+                                                    .size(px(14.))
+                                                    .rounded(px(3.))
+                                                    .border_1()
+                                                    .border_color(
+                                                        if focused == Some(Control::Recent(index)) {
+                                                            theme.tokens.active_border
+                                                        } else {
+                                                            theme.tokens.inactive_border
+                                                        },
+                                                    )
+                                                    .bg(recent)
+                                                    .hover(|this| {
+                                                        this.border_color(
+                                                            theme.tokens.active_border,
+                                                        )
+                                                    })
+                                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                                        this.pick(recent, cx)
+                                                    }))
+                                            },
+                                        ),
+                                    ),
+                                )
+                            }),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,241 @@
+//! Menu opened by right-clicking the canvas.
+//!
+//! Right-clicking a node selects it first, so every entry acts on the selection the
+//! same way its menu bar item or shortcut would. Picking an entry dispatches its
+//! action to the workspace and closes the menu, as does clicking anywhere else or
+//! pressing escape. Up and down move between the entries that can be picked.
+
+use gpui::{
+    actions, anchored, div, prelude::*, px, Action, App, ElementId, EventEmitter, FocusHandle,
+    Focusable, IntoElement, MouseButton, Pixels, Point, SharedString, Window,
+};
+
+use crate::theme::Theme;
+
+actions!(context_menu, [Confirm, Dismiss, SelectNext, SelectPrevious]);
+
+pub enum ContextMenuEvent {
+    Dismissed,
+}
+
+/// A line of the menu
+pub enum ContextMenuEntry {
+    Action {
+        label: SharedString,
+        action: Box<dyn Action>,
+        /// Disabled entries are shown greyed out so the menu keeps its shape
+        enabled: bool,
+    },
+    Separator,
+}
+
+impl ContextMenuEntry {
+    pub fn action(label: impl Into<SharedString>, action: impl Action) -> Self {
+        Self::Action {
+            label: label.into(),
+            action: Box::new(action),
+            enabled: true,
+        }
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        if let Self::Action {
+            enabled: entry_enabled,
+            ..
+        } = &mut self
+        {
+            *entry_enabled = enabled;
+        }
+        self
+    }
+
+    fn is_enabled(&self) -> bool {
+        matches!(self, Self::Action { enabled: true, .. })
+    }
+}
+
+pub struct ContextMenu {
+    focus_handle: FocusHandle,
+    /// Where focus goes to before an entry's action is dispatched, so the action
+    /// reaches the workspace rather than the menu
+    return_focus: FocusHandle,
+    /// Where the menu was opened, its top left corner sits here
+    position: Point<Pixels>,
+    entries: Vec<ContextMenuEntry>,
+    /// Entry picked by enter, moved with the arrow keys
+    highlighted: Option<usize>,
+}
+
+impl ContextMenu {
+    const MIN_WIDTH: f32 = 160.;
+
+    pub fn new(
+        position: Point<Pixels>,
+        entries: Vec<ContextMenuEntry>,
+        return_focus: FocusHandle,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            return_focus,
+            position,
+            entries,
+            highlighted: None,
+        }
+    }
+
+    /// Dispatch the action of the entry at `index` and close the menu
+    fn pick(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(ContextMenuEntry::Action {
+            action,
+            enabled: true,
+            ..
+        }) = self.entries.get(index)
+        else {
+            return;
+        };
+        window.focus(&self.return_focus);
+        window.dispatch_action(action.boxed_clone(), cx);
+        cx.emit(ContextMenuEvent::Dismissed);
+    }
+
+    /// Highlight the next entry that can be picked going one way, wrapping around
+    fn move_highlight(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let count = self.entries.len();
+        if count == 0 {
+            return;
+        }
+        let start = match (self.highlighted, forward) {
+            (Some(index), _) => index,
+            (None, true) => count - 1,
+            (None, false) => 0,
+        };
+        let next = (1..=count)
+            .map(|step| {
+                if forward {
+                    (start + step) % count
+                } else {
+                    (start + count - step % count) % count
+                }
+            })
+            .find(|index| self.entries[*index].is_enabled());
+        if next.is_some() {
+            self.highlighted = next;
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_highlight(true, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &SelectPrevious,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_highlight(false, cx);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(index) = self.highlighted {
+            self.pick(index, window, cx);
+        }
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(ContextMenuEvent::Dismissed);
+    }
+}
+
+impl EventEmitter<ContextMenuEvent> for ContextMenu {}
+
+impl Focusable for ContextMenu {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ContextMenu {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+
+        let rows = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| match entry {
+                ContextMenuEntry::Action { label, enabled, .. } => div()
+                    .id(ElementId::Name(format!("context-menu-{index}").into()))
+                    .px_2()
+                    .py(px(3.))
+                    .rounded(px(4.))
+                    .whitespace_nowrap()
+                    .text_color(if *enabled {
+                        theme.tokens.text
+                    } else {
+                        theme.tokens.subtext0.opacity(0.6)
+                    })
+                    .when(self.highlighted == Some(index), |div| {
+                        div.bg(theme.tokens.selected)
+                    })
+                    .when(*enabled, |div| {
+                        div.hover(|style| style.bg(theme.tokens.surface2.opacity(0.7)))
+                    })
+                    // Clicking a disabled entry does nothing, it keeps the menu open
+                    .on_click(cx.listener(move |this, _, window, cx| this.pick(index, window, cx)))
+                    .child(label.clone())
+                    .into_any_element(),
+                ContextMenuEntry::Separator => div()
+                    .h(px(1.))
+                    .mx_1()
+                    .my_1()
+                    .bg(theme.tokens.inactive_border)
+                    .into_any_element(),
+            })
+            .collect::<Vec<_>>();
+
+        div()
+            .id("context-menu-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(ContextMenuEvent::Dismissed)),
+            )
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(|_, _, _, cx| cx.emit(ContextMenuEvent::Dismissed)),
+            )
+            .child(
+                anchored().position(self.position).snap_to_window().child(
+                    div()
+                        .id("context-menu")
+                        .key_context("ContextMenu")
+                        .track_focus(&self.focus_handle)
+                        .on_action(cx.listener(Self::confirm))
+                        .on_action(cx.listener(Self::dismiss))
+                        .on_action(cx.listener(Self::select_next))
+                        .on_action(cx.listener(Self::select_previous))
+                        // Keep clicks inside the menu from reaching the backdrop
+                        .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                        .on_mouse_down(MouseButton::Right, |_, _, cx| cx.stop_propagation())
+                        .min_w(px(Self::MIN_WIDTH))
+                        .flex()
+                        .flex_col()
+                        .p_1()
+                        .bg(theme.tokens.background_secondary)
+                        .border_1()
+                        .border_color(theme.tokens.inactive_border)
+                        .rounded(px(6.))
+                        .shadow_lg()
+                        .text_xs()
+                        .children(rows),
+                ),
+            )
+    }
+}
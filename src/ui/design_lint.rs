@@ -0,0 +1,236 @@
+//! Design lint panel listing the issues found in the document.
+//!
+//! Unlike the document info panel the warnings are re-checked on every change to the
+//! canvas, so fixing an issue removes it from the list right away. Clicking a warning
+//! reveals its node and closes the panel.
+
+use gpui::{
+    actions, div, prelude::*, px, App, ElementId, Entity, EventEmitter, FocusHandle, Focusable,
+    IntoElement, MouseButton, SharedString, Subscription, Window,
+};
+
+use crate::{
+    canvas::LunaCanvas,
+    lint::{lint, LintIssue, LintWarning},
+    node::{NodeCommon, NodeId},
+    theme::Theme,
+};
+
+use super::Titlebar;
+
+actions!(design_lint, [Dismiss, FixAll]);
+
+pub enum DesignLintEvent {
+    /// A warning was clicked
+    Reveal(NodeId),
+    Dismissed,
+}
+
+pub struct DesignLint {
+    focus_handle: FocusHandle,
+    canvas: Entity<LunaCanvas>,
+    _observe_canvas: Subscription,
+}
+
+impl DesignLint {
+    const WIDTH: f32 = 360.;
+    const MAX_HEIGHT: f32 = 420.;
+
+    pub fn new(canvas: &Entity<LunaCanvas>, cx: &mut Context<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            canvas: canvas.clone(),
+            _observe_canvas: cx.observe(canvas, |_, _, cx| cx.notify()),
+        }
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DesignLintEvent::Dismissed);
+    }
+
+    fn fix_all(&mut self, _: &FixAll, _window: &mut Window, cx: &mut Context<Self>) {
+        let warnings = lint(self.canvas.read(cx).nodes());
+        self.fix(&warnings, cx);
+    }
+
+    fn fix(&mut self, warnings: &[LintWarning], cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.fix_lint_warnings(warnings, cx);
+        });
+    }
+
+    /// What is wrong, spelled out for the warning's row
+    fn describe(&self, issue: &LintIssue, cx: &App) -> String {
+        let settings = self.canvas.read(cx).app_state().read(cx).document_settings;
+        match issue {
+            LintIssue::OffPixelGrid => "Position or size has a fractional pixel".to_string(),
+            LintIssue::DefaultName => "Still has its generated name".to_string(),
+            LintIssue::NearDuplicateColor { color, closest, .. } => format!(
+                "{} is close to {}",
+                settings.format_color(*color),
+                settings.format_color(*closest)
+            ),
+        }
+    }
+}
+
+impl EventEmitter<DesignLintEvent> for DesignLint {}
+
+impl Focusable for DesignLint {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DesignLint {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx).clone();
+        let canvas = self.canvas.read(cx);
+        let warnings = lint(canvas.nodes());
+        let names: Vec<SharedString> = warnings
+            .iter()
+            .map(|warning| {
+                canvas
+                    .get_node(warning.node_id)
+                    .map(|node| node.name())
+                    .unwrap_or_else(|| warning.node_id.to_string())
+                    .into()
+            })
+            .collect();
+        let fixable_count = warnings
+            .iter()
+            .filter(|warning| warning.issue.can_fix())
+            .count();
+
+        let rows = warnings
+            .iter()
+            .zip(names)
+            .enumerate()
+            .map(|(index, (warning, name))| {
+                let warning = *warning;
+                div()
+                    .id(ElementId::Name(format!("design-lint-{index}").into()))
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .rounded(px(4.))
+                    .hover(|this| this.bg(theme.tokens.surface0))
+                    .on_click(cx.listener(move |_, _, _, cx| {
+                        cx.emit(DesignLintEvent::Reveal(warning.node_id));
+                    }))
+                    .child(
+                        div()
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .child(
+                                div()
+                                    .flex()
+                                    .justify_between()
+                                    .child(warning.issue.title())
+                                    .child(div().text_color(theme.tokens.subtext0).child(name)),
+                            )
+                            .child(
+                                div()
+                                    .text_color(theme.tokens.foreground_muted)
+                                    .child(self.describe(&warning.issue, cx)),
+                            ),
+                    )
+                    .when(warning.issue.can_fix(), |this| {
+                        this.child(
+                            div()
+                                .id(ElementId::Name(format!("design-lint-fix-{index}").into()))
+                                .px_2()
+                                .rounded(px(3.))
+                                .bg(theme.tokens.surface1)
+                                .hover(|this| this.bg(theme.tokens.surface2))
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    // Fixing shouldn't also reveal the node and close the panel
+                                    cx.stop_propagation();
+                                    this.fix(&[warning], cx);
+                                }))
+                                .child("Fix"),
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let header = div()
+            .flex()
+            .items_center()
+            .justify_between()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(theme.tokens.inactive_border)
+            .child(match warnings.len() {
+                0 => "No issues found".to_string(),
+                1 => "1 issue".to_string(),
+                count => format!("{count} issues"),
+            })
+            .when(fixable_count > 0, |this| {
+                this.child(
+                    div()
+                        .id("design-lint-fix-all")
+                        .px_2()
+                        .rounded(px(3.))
+                        .bg(theme.tokens.surface1)
+                        .hover(|this| this.bg(theme.tokens.surface2))
+                        .on_click(
+                            cx.listener(|this, _, window, cx| this.fix_all(&FixAll, window, cx)),
+                        )
+                        .child(format!("Fix {fixable_count}")),
+                )
+            });
+
+        div()
+            .id("design-lint-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .flex_col()
+            .items_center()
+            .pt(px(Titlebar::HEIGHT * 3.))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(DesignLintEvent::Dismissed)),
+            )
+            .child(
+                div()
+                    .id("design-lint")
+                    .key_context("DesignLint")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::dismiss))
+                    .on_action(cx.listener(Self::fix_all))
+                    // Keep clicks inside the panel from reaching the backdrop
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(Self::WIDTH))
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .p_1()
+                    .text_size(px(12.))
+                    .text_color(theme.tokens.text)
+                    .bg(theme.tokens.background_secondary)
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .rounded(px(8.))
+                    .shadow_lg()
+                    .child(header)
+                    .child(
+                        div()
+                            .id("design-lint-warnings")
+                            .max_h(px(Self::MAX_HEIGHT))
+                            .overflow_y_scroll()
+                            .flex()
+                            .flex_col()
+                            .children(rows),
+                    ),
+            )
+    }
+}
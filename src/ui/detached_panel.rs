@@ -0,0 +1,70 @@
+//! Host window for panels popped out of the main window.
+//!
+//! A detached panel renders the very same entity the main window docks, so there is no
+//! state to synchronize: both read the shared canvas entity and GPUI redraws every
+//! window that read an entity when it notifies.
+
+use gpui::{div, prelude::*, px, AnyView, IntoElement, Window};
+
+use crate::theme::Theme;
+
+use super::{inspector::INSPECTOR_WIDTH, sidebar::Sidebar, Titlebar};
+
+/// Panels that can be popped out into their own window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PanelKind {
+    Layers,
+    Inspector,
+}
+
+impl PanelKind {
+    pub fn title(&self) -> &'static str {
+        match self {
+            PanelKind::Layers => "Layers",
+            PanelKind::Inspector => "Inspector",
+        }
+    }
+
+    /// Initial window width, matching the panel's docked width
+    pub fn default_width(&self) -> f32 {
+        match self {
+            PanelKind::Layers => Sidebar::INITIAL_WIDTH,
+            PanelKind::Inspector => INSPECTOR_WIDTH,
+        }
+    }
+}
+
+/// Root view of a detached panel window
+pub struct DetachedPanel {
+    kind: PanelKind,
+    content: AnyView,
+}
+
+impl DetachedPanel {
+    pub fn new(kind: PanelKind, content: AnyView) -> Self {
+        Self { kind, content }
+    }
+
+    pub fn kind(&self) -> PanelKind {
+        self.kind
+    }
+}
+
+impl Render for DetachedPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+
+        div()
+            .id("detached-panel")
+            .relative()
+            .flex()
+            .flex_col()
+            .size_full()
+            .font_family("Berkeley Mono")
+            .text_xs()
+            .bg(theme.tokens.background_secondary)
+            .text_color(theme.tokens.text)
+            .child(div().w_full().h(px(Titlebar::HEIGHT)))
+            .child(div().relative().flex().flex_1().child(self.content.clone()))
+    }
+}
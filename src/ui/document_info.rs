@@ -0,0 +1,221 @@
+//! Document info panel summarizing what the open document is made of.
+//!
+//! Like the quick-open finder the panel shows a snapshot taken when it opens. Nodes
+//! listed in it can be clicked to reveal them, which closes the panel.
+
+use gpui::{
+    actions, div, prelude::*, px, App, ElementId, Entity, EventEmitter, FocusHandle, Focusable,
+    IntoElement, MouseButton, SharedString, Window,
+};
+
+use crate::{
+    canvas::LunaCanvas,
+    document_settings::DocumentSettings,
+    node::{NodeCommon, NodeId},
+    statistics::DocumentStatistics,
+    theme::Theme,
+};
+
+use super::Titlebar;
+
+actions!(document_info, [Dismiss]);
+
+pub enum DocumentInfoEvent {
+    /// A node listed in the panel was clicked
+    Reveal(NodeId),
+    Dismissed,
+}
+
+pub struct DocumentInfo {
+    focus_handle: FocusHandle,
+    statistics: DocumentStatistics,
+    /// Display names of the nodes the statistics refer to
+    names: Vec<(NodeId, SharedString)>,
+    settings: DocumentSettings,
+}
+
+impl DocumentInfo {
+    const WIDTH: f32 = 320.;
+
+    pub fn new(
+        canvas: &Entity<LunaCanvas>,
+        settings: DocumentSettings,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let nodes = canvas.read(cx).nodes();
+        let statistics = DocumentStatistics::collect(nodes);
+        let names = nodes
+            .iter()
+            .map(|node| (node.id(), node.name().into()))
+            .collect();
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            statistics,
+            names,
+            settings,
+        }
+    }
+
+    fn name(&self, node_id: NodeId) -> SharedString {
+        self.names
+            .iter()
+            .find(|(id, _)| *id == node_id)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| node_id.to_string().into())
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DocumentInfoEvent::Dismissed);
+    }
+
+    /// A row naming a node, clicking it reveals the node
+    fn node_row(
+        &self,
+        node_id: NodeId,
+        detail: String,
+        theme: &Theme,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .id(ElementId::Name(
+                format!("document-info-{}", node_id.0).into(),
+            ))
+            .flex()
+            .justify_between()
+            .px_2()
+            .py(px(2.))
+            .rounded(px(4.))
+            .hover(|this| this.bg(theme.tokens.surface0))
+            .on_click(cx.listener(move |_, _, _, cx| {
+                cx.emit(DocumentInfoEvent::Reveal(node_id));
+            }))
+            .child(self.name(node_id))
+            .child(div().text_color(theme.tokens.subtext0).child(detail))
+    }
+}
+
+impl EventEmitter<DocumentInfoEvent> for DocumentInfo {}
+
+impl Focusable for DocumentInfo {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DocumentInfo {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx).clone();
+        let statistics = self.statistics.clone();
+
+        let section = |title: &'static str| {
+            div().flex().flex_col().gap(px(2.)).child(
+                div()
+                    .px_2()
+                    .text_size(px(10.))
+                    .text_color(theme.tokens.subtext0)
+                    .child(title),
+            )
+        };
+        let value_row = |label: SharedString, value: String| {
+            div()
+                .flex()
+                .justify_between()
+                .px_2()
+                .py(px(2.))
+                .child(label)
+                .child(div().text_color(theme.tokens.subtext0).child(value))
+        };
+
+        let types = section("Nodes")
+            .child(value_row("Total".into(), statistics.node_count.to_string()))
+            .children(statistics.counts_by_type.iter().map(|(node_type, count)| {
+                value_row(format!("{node_type:?}").into(), count.to_string())
+            }));
+
+        let colors = section("Most Used Colors").children(statistics.top_colors.iter().map(
+            |(color, count)| {
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(6.))
+                    .px_2()
+                    .py(px(2.))
+                    .child(
+                        div()
+                            .size(px(10.))
+                            .rounded(px(2.))
+                            .border_1()
+                            .border_color(theme.tokens.inactive_border)
+                            .bg(*color),
+                    )
+                    .child(div().flex_1().child(self.settings.format_color(*color)))
+                    .child(
+                        div()
+                            .text_color(theme.tokens.subtext0)
+                            .child(count.to_string()),
+                    )
+            },
+        ));
+
+        let nesting =
+            section("Deepest Nesting").children(statistics.deepest_node.map(|(node_id, depth)| {
+                self.node_row(node_id, format!("{depth} levels"), &theme, cx)
+            }));
+
+        let heaviest = section("Heaviest Frames").children(
+            statistics
+                .heaviest_frames
+                .iter()
+                .map(|(node_id, count)| {
+                    self.node_row(*node_id, format!("{count} nodes"), &theme, cx)
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        div()
+            .id("document-info-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .flex_col()
+            .items_center()
+            .pt(px(Titlebar::HEIGHT * 3.))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(DocumentInfoEvent::Dismissed)),
+            )
+            .child(
+                div()
+                    .id("document-info")
+                    .key_context("DocumentInfo")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::dismiss))
+                    // Keep clicks inside the panel from reaching the backdrop
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(Self::WIDTH))
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .p_2()
+                    .text_size(px(12.))
+                    .text_color(theme.tokens.text)
+                    .bg(theme.tokens.background_secondary)
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .rounded(px(8.))
+                    .shadow_lg()
+                    .child(types)
+                    .when(!statistics.top_colors.is_empty(), |this| this.child(colors))
+                    .when(statistics.deepest_node.is_some(), |this| {
+                        this.child(nesting)
+                    })
+                    .when(!statistics.heaviest_frames.is_empty(), |this| {
+                        this.child(heaviest)
+                    }),
+            )
+    }
+}
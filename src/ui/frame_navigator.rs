@@ -0,0 +1,147 @@
+//! Strip of thumbnails along the bottom of the canvas, one for each top-level frame.
+//!
+//! Clicking a thumbnail selects its frame and scrolls the canvas to it. Thumbnails come
+//! from a [`ThumbnailCache`], so redrawing the strip as the canvas changes only
+//! rebuilds the frames that changed.
+
+use gpui::{
+    div, prelude::*, px, Div, ElementId, Entity, IntoElement, MouseButton, SharedString,
+    Subscription, Window,
+};
+
+use crate::{
+    canvas::LunaCanvas,
+    node::{NodeCommon, NodeId},
+    theme::Theme,
+    thumbnail::{ThumbnailCache, ThumbnailShape, THUMBNAIL_SIZE},
+};
+
+pub struct FrameNavigator {
+    canvas: Entity<LunaCanvas>,
+    thumbnails: ThumbnailCache,
+    /// Redraws thumbnails as frames are edited, added and removed
+    _observe_canvas: Subscription,
+}
+
+impl FrameNavigator {
+    /// Widest the strip gets before it scrolls
+    const MAX_WIDTH: f32 = 640.;
+
+    pub fn new(canvas: Entity<LunaCanvas>, cx: &mut Context<Self>) -> Self {
+        let _observe_canvas = cx.observe(&canvas, |_, _, cx| cx.notify());
+        Self {
+            canvas,
+            thumbnails: ThumbnailCache::default(),
+            _observe_canvas,
+        }
+    }
+
+    fn reveal_frame(&mut self, node_id: NodeId, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.deselect_all_nodes(cx);
+            canvas.select_node(node_id);
+            canvas.center_on_node(node_id, cx);
+        });
+    }
+
+    /// Draw a thumbnail, to be placed in a relatively positioned box of its size
+    pub(super) fn render_shape(shape: &ThumbnailShape) -> Div {
+        div()
+            .absolute()
+            .left(px(shape.bounds.origin.x))
+            .top(px(shape.bounds.origin.y))
+            .w(px(shape.bounds.size.width))
+            .h(px(shape.bounds.size.height))
+            .rounded(px(shape.corner_radius))
+            .overflow_hidden()
+            .when_some(shape.fill, |this, fill| this.bg(fill))
+            .when_some(shape.border_color, |this, color| {
+                this.border_1().border_color(color)
+            })
+            .children(shape.children.iter().map(Self::render_shape))
+    }
+}
+
+impl Render for FrameNavigator {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+        let canvas = self.canvas.read(cx);
+
+        let frames = canvas.top_level_nodes();
+        self.thumbnails.retain(&frames);
+        let lookup = |node_id: NodeId| canvas.get_node(node_id);
+        let cards: Vec<_> = frames
+            .iter()
+            .filter_map(|&node_id| {
+                let thumbnail = Self::render_shape(self.thumbnails.get(node_id, &lookup)?);
+                let name: SharedString = canvas.get_node(node_id)?.name().into();
+                let is_selected = canvas.is_node_selected(node_id);
+                let reveal = cx.listener(move |this, _, _, cx| this.reveal_frame(node_id, cx));
+                Some(
+                    div()
+                        .id(ElementId::Name(
+                            format!("frame-thumbnail-{}", node_id.0).into(),
+                        ))
+                        .flex()
+                        .flex_col()
+                        .flex_none()
+                        .gap_1()
+                        .p_1()
+                        .rounded(px(6.))
+                        .border_1()
+                        .border_color(if is_selected {
+                            theme.tokens.active_border
+                        } else {
+                            gpui::transparent_black()
+                        })
+                        .hover(|this| this.bg(theme.tokens.surface0))
+                        .on_click(reveal)
+                        .child(
+                            div()
+                                .relative()
+                                .w(px(THUMBNAIL_SIZE.width))
+                                .h(px(THUMBNAIL_SIZE.height))
+                                .child(thumbnail),
+                        )
+                        .child(
+                            div()
+                                .w(px(THUMBNAIL_SIZE.width))
+                                .overflow_hidden()
+                                .whitespace_nowrap()
+                                .text_ellipsis()
+                                .text_color(theme.tokens.subtext0)
+                                .child(name),
+                        ),
+                )
+            })
+            .collect();
+
+        div()
+            .absolute()
+            .bottom(px(12.))
+            .left_0()
+            .right_0()
+            .flex()
+            .justify_center()
+            .when(!cards.is_empty(), |this| {
+                this.child(
+                    div()
+                        .id("frame-navigator")
+                        .occlude()
+                        // Clicks between thumbnails shouldn't reach the canvas underneath
+                        .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                        .max_w(px(Self::MAX_WIDTH))
+                        .overflow_x_scroll()
+                        .flex()
+                        .gap_1()
+                        .p_1()
+                        .bg(theme.tokens.background_secondary)
+                        .border_1()
+                        .border_color(theme.tokens.inactive_border)
+                        .rounded(px(8.))
+                        .shadow_lg()
+                        .children(cards),
+                )
+            })
+    }
+}
@@ -2,26 +2,48 @@
 //!
 //! The inspector displays and allows editing of properties
 //! for selected elements in the canvas.
+//!
+//! Clicking a field starts typing into it. Enter applies the value to the whole
+//! selection as one change, escape throws it away, and clicking elsewhere applies it
+//! too. A mixed field starts out empty, and leaving it empty changes nothing.
+//!
+//! While a field is hovered or typed into, the canvas outlines the selected nodes the
+//! edit would change. For a mixed field that narrows to the nodes that don't already
+//! have the value typed so far.
 
 use std::collections::HashSet;
 
 use gpui::{
-    div, prelude::*, px, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled,
-    Window,
+    actions, div, prelude::*, px, ClipboardItem, Context, Corners, Entity, EventEmitter,
+    FocusHandle, Hsla, IntoElement, KeyDownEvent, ParentElement, Render, SharedString, Styled,
+    Subscription, Window,
 };
 use smallvec::SmallVec;
 
 use crate::{
     canvas::LunaCanvas,
-    node::{NodeCommon, NodeId},
-    theme::Theme,
-    AppState,
+    color::{parse_color, ColorFormat, ColorTarget},
+    document_settings::{DimensionUnit, DocumentSettings},
+    grid::GridSettings,
+    history::CanvasCommand,
+    layout::{Axis, Pin, Pins},
+    node::{
+        frame::{FrameNode, NodeStyle, Overflow},
+        BlendMode, NodeCommon, NodeId,
+    },
+    theme::{ActiveTheme, Theme},
+    AppState, ShowColorPicker,
 };
 
-use super::property::{float_input, ColorInput};
+use super::property::{float_input, ColorInput, PropertyInput};
+
+actions!(inspector, [Confirm, Dismiss, CopyValue, PasteValue]);
 
 pub const INSPECTOR_WIDTH: f32 = 200.;
 
+/// Document colors beyond this many are left out of the swatch list
+const MAX_DOCUMENT_SWATCHES: usize = 24;
+
 /// Represents the current selection state in the canvas
 pub enum NodeSelection {
     /// No nodes are selected
@@ -80,6 +102,207 @@ impl Default for InspectorProperties {
     }
 }
 
+/// A property that can be typed into the inspector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectorField {
+    X,
+    Y,
+    Width,
+    Height,
+    BorderWidth,
+    CornerRadius,
+    Opacity,
+    Fill,
+    BorderColor,
+    /// Space between the children of a frame with auto layout
+    Gap,
+    Padding,
+}
+
+/// A value typed into a field, dimensions already in pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldValue {
+    Dimension(f32),
+    /// A 0 to 1 value, typed as a percentage
+    Fraction(f32),
+    Color(Option<Hsla>),
+}
+
+impl InspectorField {
+    fn icon(self) -> &'static str {
+        match self {
+            InspectorField::X => "X",
+            InspectorField::Y => "Y",
+            InspectorField::Width => "W",
+            InspectorField::Height => "H",
+            InspectorField::BorderWidth => "B",
+            InspectorField::CornerRadius => "R",
+            InspectorField::Opacity => "%",
+            InspectorField::Fill => "BG",
+            InspectorField::BorderColor => "BC",
+            InspectorField::Gap => "G",
+            InspectorField::Padding => "P",
+        }
+    }
+
+    /// Read typed text as a value for this field, "none" clears a color
+    fn parse(self, text: &str, settings: &DocumentSettings) -> Option<FieldValue> {
+        let text = text.trim();
+        match self {
+            InspectorField::Fill | InspectorField::BorderColor => {
+                if text.eq_ignore_ascii_case("none") {
+                    Some(FieldValue::Color(None))
+                } else {
+                    parse_color(text).map(|color| FieldValue::Color(Some(color)))
+                }
+            }
+            InspectorField::Opacity => text
+                .trim_end_matches('%')
+                .trim()
+                .parse::<f32>()
+                .ok()
+                .map(|percent| FieldValue::Fraction(percent / 100.0)),
+            _ => settings.parse_dimension(text).map(FieldValue::Dimension),
+        }
+    }
+
+    /// The value as it will be applied, sizes and borders can't go below zero
+    fn clamp(self, value: FieldValue) -> FieldValue {
+        match (self, value) {
+            (
+                InspectorField::Width
+                | InspectorField::Height
+                | InspectorField::BorderWidth
+                | InspectorField::CornerRadius
+                | InspectorField::Gap
+                | InspectorField::Padding,
+                FieldValue::Dimension(value),
+            ) => FieldValue::Dimension(value.max(0.0)),
+            (InspectorField::Opacity, FieldValue::Fraction(value)) => {
+                FieldValue::Fraction(value.clamp(0.0, 1.0))
+            }
+            _ => value,
+        }
+    }
+
+    /// What this field currently reads for `node`
+    fn value_of(self, node: &FrameNode) -> FieldValue {
+        let layout = node.layout();
+        match self {
+            InspectorField::X => FieldValue::Dimension(layout.x),
+            InspectorField::Y => FieldValue::Dimension(layout.y),
+            InspectorField::Width => FieldValue::Dimension(layout.width),
+            InspectorField::Height => FieldValue::Dimension(layout.height),
+            InspectorField::BorderWidth => FieldValue::Dimension(node.border_width()),
+            InspectorField::CornerRadius => FieldValue::Dimension(node.corner_radius()),
+            InspectorField::Opacity => FieldValue::Fraction(node.opacity()),
+            InspectorField::Fill => FieldValue::Color(node.fill()),
+            InspectorField::BorderColor => FieldValue::Color(node.border_color()),
+            InspectorField::Gap => {
+                FieldValue::Dimension(node.auto_layout.map_or(0.0, |auto| auto.gap))
+            }
+            InspectorField::Padding => {
+                FieldValue::Dimension(node.auto_layout.map_or(0.0, |auto| auto.padding))
+            }
+        }
+    }
+
+    /// Whether setting this field to `value` would change anything on `node`
+    fn changes(self, value: FieldValue, node: &FrameNode) -> bool {
+        // Only frames with auto layout have spacing to change
+        let spacing = matches!(self, InspectorField::Gap | InspectorField::Padding);
+        if spacing && node.auto_layout.is_none() {
+            return false;
+        }
+        self.value_of(node) != self.clamp(value)
+    }
+
+    /// The change setting this field to `value` on each of `nodes`
+    fn command(
+        self,
+        value: FieldValue,
+        nodes: &HashSet<NodeId>,
+        canvas: &LunaCanvas,
+    ) -> CanvasCommand {
+        let value = self.clamp(value);
+        let nodes = nodes
+            .iter()
+            .filter_map(|node_id| Some((*node_id, canvas.get_node(*node_id)?)));
+        match (self, value) {
+            (
+                InspectorField::X
+                | InspectorField::Y
+                | InspectorField::Width
+                | InspectorField::Height,
+                FieldValue::Dimension(value),
+            ) => CanvasCommand::SetLayouts(
+                nodes
+                    .map(|(node_id, node)| {
+                        let mut layout = node.layout().clone();
+                        match self {
+                            InspectorField::X => layout.x = value,
+                            InspectorField::Y => layout.y = value,
+                            InspectorField::Width => layout.width = value,
+                            _ => layout.height = value,
+                        }
+                        (node_id, layout)
+                    })
+                    .collect(),
+            ),
+            (InspectorField::Gap | InspectorField::Padding, FieldValue::Dimension(value)) => {
+                CanvasCommand::SetAutoLayout(
+                    nodes
+                        .filter_map(|(node_id, node)| {
+                            let mut auto_layout = node.auto_layout?;
+                            match self {
+                                InspectorField::Gap => auto_layout.gap = value,
+                                _ => auto_layout.padding = value,
+                            }
+                            Some((node_id, Some(auto_layout)))
+                        })
+                        .collect(),
+                )
+            }
+            (field, value) => CanvasCommand::SetStyles(
+                nodes
+                    .map(|(node_id, node)| {
+                        let mut style = NodeStyle::of(node);
+                        match (field, value) {
+                            (InspectorField::BorderWidth, FieldValue::Dimension(width)) => {
+                                style.border_width = width
+                            }
+                            (InspectorField::CornerRadius, FieldValue::Dimension(radius)) => {
+                                style.corner_radii = Corners::all(radius)
+                            }
+                            (InspectorField::Opacity, FieldValue::Fraction(opacity)) => {
+                                style.opacity = opacity
+                            }
+                            (InspectorField::Fill, FieldValue::Color(color)) => style.fill = color,
+                            (InspectorField::BorderColor, FieldValue::Color(color)) => {
+                                style.border_color = color
+                            }
+                            _ => {}
+                        }
+                        (node_id, style)
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Text being typed into a field, and the selection it will be applied to
+struct FieldEdit {
+    field: InspectorField,
+    text: String,
+    nodes: HashSet<NodeId>,
+}
+
+pub enum InspectorEvent {
+    /// The selected nodes the hovered or edited field would change, empty when none is
+    Preview(HashSet<NodeId>),
+}
+
 /// Properties panel for viewing and editing element attributes
 ///
 /// The inspector maintains property values for selected elements and renders
@@ -89,20 +312,70 @@ pub struct Inspector {
     state: Entity<AppState>,
     canvas: Entity<LunaCanvas>,
     properties: InspectorProperties,
+    width: f32,
+    focus_handle: FocusHandle,
+    /// Where focus goes back to once a field is confirmed or dismissed
+    return_focus: FocusHandle,
+    editing: Option<FieldEdit>,
+    hovered_field: Option<InspectorField>,
+    /// The nodes last sent out as [`InspectorEvent::Preview`]
+    preview: HashSet<NodeId>,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl Inspector {
-    pub fn new(state: Entity<AppState>, canvas: Entity<LunaCanvas>) -> Self {
+    pub fn new(
+        state: Entity<AppState>,
+        canvas: Entity<LunaCanvas>,
+        return_focus: FocusHandle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        let _subscriptions = vec![
+            // Clicking away from a field keeps what was typed into it
+            cx.on_blur(&focus_handle, window, |this, _, cx| this.commit_edit(cx)),
+            cx.observe(&canvas, |this, canvas, cx| {
+                // What was typed was meant for the selection it was typed with
+                let selection_changed = this
+                    .editing
+                    .as_ref()
+                    .is_some_and(|edit| &edit.nodes != canvas.read(cx).selected_nodes());
+                if selection_changed {
+                    this.commit_edit(cx);
+                }
+                this.update_preview(cx);
+                cx.notify();
+            }),
+        ];
+
         Self {
             state,
             canvas,
             properties: InspectorProperties::default(),
+            width: INSPECTOR_WIDTH,
+            focus_handle,
+            return_focus,
+            editing: None,
+            hovered_field: None,
+            preview: HashSet::new(),
+            _subscriptions,
         }
     }
 
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn set_width(&mut self, width: f32, cx: &mut Context<Self>) {
+        self.width = width;
+        cx.notify();
+    }
+
     /// Updates the inspector properties based on the currently selected nodes
     pub fn update_selected_node_properties(&mut self, cx: &mut Context<Self>) {
         let canvas = self.canvas.clone();
+        let settings = self.state.read(cx).document_settings;
         let selected_node_set = canvas.read(cx).selected_nodes().clone();
         let selected_nodes = NodeSelection::from(selected_node_set);
 
@@ -131,14 +404,14 @@ impl Inspector {
                     self.properties.border_width.push(node.border_width());
                     self.properties.corner_radius.push(node.corner_radius());
 
-                    // Add color properties with integers instead of decimals
+                    // Colors are shown in the document's preferred format
                     if let Some(border_color) = node.border_color() {
-                        let color_str = self.format_color_string(border_color.to_string());
+                        let color_str = settings.format_color(border_color);
                         self.properties.border_color.push(SharedString::from(color_str));
                     }
 
                     if let Some(fill_color) = node.fill() {
-                        let color_str = self.format_color_string(fill_color.to_string());
+                        let color_str = settings.format_color(fill_color);
                         self.properties.background_color.push(SharedString::from(color_str));
                     }
                 }
@@ -174,11 +447,11 @@ impl Inspector {
 
                         // Collect color values
                         if let Some(border_color) = node.border_color() {
-                            all_border_colors.push(self.format_color_string(border_color.to_string()));
+                            all_border_colors.push(settings.format_color(border_color));
                         }
 
                         if let Some(fill_color) = node.fill() {
-                            all_background_colors.push(self.format_color_string(fill_color.to_string()));
+                            all_background_colors.push(settings.format_color(fill_color));
                         }
                     }
                 }
@@ -283,27 +556,419 @@ impl Inspector {
         cx.notify();
     }
     
-    /// Format a color string to use integers instead of decimals
-    fn format_color_string(&self, color_str: String) -> String {
-        // Replace decimal numbers with integers in color strings
-        // Example: rgba(255.00, 0.00, 0.00, 1.00) -> rgba(255, 0, 0, 1)
-        let mut result = color_str;
-        
-        // Find all decimal numbers and replace them
-        let decimal_regex = regex::Regex::new(r"(\d+)\.\d+").unwrap();
-        result = decimal_regex.replace_all(&result, "$1").to_string();
-        
-        result
+    fn begin_edit(
+        &mut self,
+        field: InspectorField,
+        text: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.commit_edit(cx);
+        let nodes = self.canvas.read(cx).selected_nodes().clone();
+        if nodes.is_empty() {
+            return;
+        }
+        self.editing = Some(FieldEdit { field, text, nodes });
+        self.update_preview(cx);
+        window.focus(&self.focus_handle);
+        cx.notify();
     }
-    
+
+    /// Apply the field being edited to the selection it was started on, as one change
+    fn commit_edit(&mut self, cx: &mut Context<Self>) {
+        let Some(edit) = self.editing.take() else {
+            return;
+        };
+        self.update_preview(cx);
+        cx.notify();
+
+        let settings = self.state.read(cx).document_settings;
+        // Leaving a field empty, or typing something that isn't a value, changes nothing
+        let Some(value) = edit.field.parse(&edit.text, &settings) else {
+            return;
+        };
+        self.canvas.update(cx, |canvas, cx| {
+            let command = edit.field.command(value, &edit.nodes, canvas);
+            canvas.execute(command, cx);
+        });
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.commit_edit(cx);
+        window.focus(&self.return_focus);
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, window: &mut Window, cx: &mut Context<Self>) {
+        self.editing = None;
+        self.update_preview(cx);
+        window.focus(&self.return_focus);
+        cx.notify();
+    }
+
+    fn copy_value(&mut self, _: &CopyValue, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(edit) = &self.editing {
+            cx.write_to_clipboard(ClipboardItem::new_string(edit.text.clone()));
+        }
+    }
+
+    fn paste_value(&mut self, _: &PasteValue, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(edit) = &mut self.editing else {
+            return;
+        };
+        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            edit.text.extend(text.chars().filter(|c| !c.is_control()));
+            self.update_preview(cx);
+            cx.notify();
+        }
+    }
+
+    /// Type into the field being edited from raw key presses, like the link editor
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(edit) = &mut self.editing else {
+            return;
+        };
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.platform || keystroke.modifiers.control {
+            return;
+        }
+
+        if keystroke.key == "backspace" {
+            edit.text.pop();
+        } else if let Some(text) = keystroke
+            .key_char
+            .as_ref()
+            .filter(|text| !text.chars().any(char::is_control))
+        {
+            edit.text.push_str(text);
+        } else {
+            return;
+        }
+
+        self.update_preview(cx);
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn set_hovered_field(&mut self, field: InspectorField, hovered: bool, cx: &mut Context<Self>) {
+        if hovered {
+            self.hovered_field = Some(field);
+        } else if self.hovered_field == Some(field) {
+            self.hovered_field = None;
+        } else {
+            return;
+        }
+        self.update_preview(cx);
+    }
+
+    /// The selected nodes an edit would change, taking the field being typed into
+    /// over the one under the pointer
+    fn previewed_nodes(&self, cx: &Context<Self>) -> HashSet<NodeId> {
+        let canvas = self.canvas.read(cx);
+        let (field, text, nodes) = match (&self.editing, self.hovered_field) {
+            (Some(edit), _) => (edit.field, Some(edit.text.as_str()), &edit.nodes),
+            (None, Some(field)) => (field, None, canvas.selected_nodes()),
+            (None, None) => return HashSet::new(),
+        };
+        // Until something parses, the edit reaches the whole selection
+        let settings = self.state.read(cx).document_settings;
+        let value = text.and_then(|text| field.parse(text, &settings));
+        nodes
+            .iter()
+            .copied()
+            .filter(|node_id| {
+                canvas
+                    .get_node(*node_id)
+                    .is_some_and(|node| value.is_none_or(|value| field.changes(value, node)))
+            })
+            .collect()
+    }
+
+    fn update_preview(&mut self, cx: &mut Context<Self>) {
+        let preview = self.previewed_nodes(cx);
+        if preview != self.preview {
+            self.preview = preview.clone();
+            cx.emit(InspectorEvent::Preview(preview));
+        }
+    }
+
+    fn edit_text(&self, field: InspectorField) -> Option<SharedString> {
+        self.editing
+            .as_ref()
+            .filter(|edit| edit.field == field)
+            .map(|edit| edit.text.clone().into())
+    }
+
+    fn float_field(
+        &self,
+        field: InspectorField,
+        value: Option<Vec<f32>>,
+        cx: &mut Context<Self>,
+    ) -> PropertyInput {
+        // Mixed values start out empty, there's no single value to edit
+        let text = match value.as_deref() {
+            Some([value]) => value.to_string(),
+            _ => String::new(),
+        };
+        float_input(value, field.icon())
+            .editing(self.edit_text(field))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.begin_edit(field, text.clone(), window, cx)
+            }))
+            .on_hover(
+                cx.listener(move |this, hovered, _, cx| {
+                    this.set_hovered_field(field, *hovered, cx)
+                }),
+            )
+    }
+
+    fn color_field(
+        &self,
+        field: InspectorField,
+        value: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) -> ColorInput {
+        let color = value.as_deref().and_then(parse_color);
+        let text = value
+            .as_ref()
+            .filter(|_| color.is_some())
+            .map(|value| value.to_string())
+            .unwrap_or_default();
+        ColorInput::new(value, SharedString::from(field.icon()))
+            .editing(self.edit_text(field))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.begin_edit(field, text.clone(), window, cx)
+            }))
+            .on_swatch_click(cx.listener(move |this, _, window, cx| {
+                if let Some(color) = color {
+                    this.open_color_picker(field, color, window, cx);
+                }
+            }))
+            .on_hover(
+                cx.listener(move |this, hovered, _, cx| {
+                    this.set_hovered_field(field, *hovered, cx)
+                }),
+            )
+    }
+
+    /// Open the color picker on the fill or stroke whose swatch was clicked, starting
+    /// from the selection's color rather than the one new elements get
+    fn open_color_picker(
+        &mut self,
+        field: InspectorField,
+        color: Hsla,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.state.update(cx, |state, cx| {
+            state.color_target = match field {
+                InspectorField::BorderColor => ColorTarget::Stroke,
+                _ => ColorTarget::Fill,
+            };
+            state.set_target_color(color);
+            cx.notify();
+        });
+        window.dispatch_action(Box::new(ShowColorPicker), cx);
+    }
+
+    fn update_document_settings(
+        &mut self,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut DocumentSettings),
+    ) {
+        self.state.update(cx, |state, cx| {
+            update(&mut state.document_settings);
+            cx.notify();
+        });
+        // The grid is part of the settings and drawn by the canvas
+        self.canvas.update(cx, |canvas, cx| canvas.mark_dirty(cx));
+        cx.notify();
+    }
+
+    /// Overflow shared by the selection, a mixed selection reads as visible
+    fn selected_overflow(&self, cx: &Context<Self>) -> Option<Overflow> {
+        let canvas = self.canvas.read(cx);
+        let selected = canvas.selected_nodes();
+        if selected.is_empty() {
+            return None;
+        }
+
+        let all_scroll = selected
+            .iter()
+            .filter_map(|node_id| canvas.get_node(*node_id))
+            .all(|node| node.is_scrollable());
+        Some(if all_scroll {
+            Overflow::Scroll
+        } else {
+            Overflow::Visible
+        })
+    }
+
+    /// Opacity of the selection as whole percentages, one value when they all agree
+    fn selected_opacity(&self, cx: &Context<Self>) -> Option<Vec<f32>> {
+        let canvas = self.canvas.read(cx);
+        let mut percentages: Vec<f32> = canvas
+            .selected_nodes()
+            .iter()
+            .filter_map(|node_id| canvas.get_node(*node_id))
+            .map(|node| (node.opacity() * 100.0).round())
+            .collect();
+        if percentages.windows(2).all(|pair| pair[0] == pair[1]) {
+            percentages.truncate(1);
+        }
+        (!percentages.is_empty()).then_some(percentages)
+    }
+
+    /// Blend mode shared by the selection, a mixed selection reads as normal
+    fn selected_blend_mode(&self, cx: &Context<Self>) -> Option<BlendMode> {
+        let canvas = self.canvas.read(cx);
+        let mut modes = canvas
+            .selected_nodes()
+            .iter()
+            .filter_map(|node_id| canvas.get_node(*node_id))
+            .map(|node| node.blend_mode());
+        let first = modes.next()?;
+        Some(if modes.all(|mode| mode == first) {
+            first
+        } else {
+            BlendMode::Normal
+        })
+    }
+
+    fn set_selected_blend_mode(&mut self, blend_mode: BlendMode, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            let styles = canvas
+                .selected_nodes()
+                .iter()
+                .filter_map(|node_id| {
+                    let mut style = NodeStyle::of(canvas.get_node(*node_id)?);
+                    style.blend_mode = blend_mode;
+                    Some((*node_id, style))
+                })
+                .collect();
+            canvas.execute(CanvasCommand::SetStyles(styles), cx);
+        });
+        cx.notify();
+    }
+
+    fn set_selected_overflow(&mut self, overflow: Overflow, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            let selected: Vec<NodeId> = canvas.selected_nodes().iter().copied().collect();
+            let undo = canvas.styles_of(selected.iter().copied());
+            canvas.record_change(undo);
+            for node_id in selected {
+                if let Some(node) = canvas.get_node_mut(node_id) {
+                    node.overflow = overflow;
+                    node.scroll_offset = Default::default();
+                }
+            }
+            canvas.mark_dirty(cx);
+        });
+        cx.notify();
+    }
+
+    /// Pins shared by the selection, an axis pinned differently reads as the default
+    fn selected_pins(&self, cx: &Context<Self>) -> Option<Pins> {
+        let canvas = self.canvas.read(cx);
+        let mut pins = canvas
+            .selected_nodes()
+            .iter()
+            .filter_map(|node_id| canvas.get_node(*node_id))
+            .map(|node| node.pins);
+        let first = pins.next()?;
+        Some(pins.fold(first, |shared, pins| Pins {
+            horizontal: if pins.horizontal == shared.horizontal {
+                shared.horizontal
+            } else {
+                Pin::default()
+            },
+            vertical: if pins.vertical == shared.vertical {
+                shared.vertical
+            } else {
+                Pin::default()
+            },
+        }))
+    }
+
+    /// Which way the selection stacks its children, `None` inside when any of it doesn't
+    fn selected_auto_layout(&self, cx: &Context<Self>) -> Option<Option<Axis>> {
+        let canvas = self.canvas.read(cx);
+        let mut directions = canvas
+            .selected_nodes()
+            .iter()
+            .filter_map(|node_id| canvas.get_node(*node_id))
+            .map(|node| node.auto_layout.map(|auto_layout| auto_layout.direction));
+        let first = directions.next()?;
+        Some(if directions.all(|direction| direction == first) {
+            first
+        } else {
+            None
+        })
+    }
+
+    /// Gap or padding of the selected frames with auto layout, one value when they all
+    /// agree
+    fn selected_spacing(&self, field: InspectorField, cx: &Context<Self>) -> Option<Vec<f32>> {
+        let canvas = self.canvas.read(cx);
+        let mut values: Vec<f32> = canvas
+            .selected_nodes()
+            .iter()
+            .filter_map(|node_id| canvas.get_node(*node_id))
+            .filter(|node| node.auto_layout.is_some())
+            .filter_map(|node| match field.value_of(node) {
+                FieldValue::Dimension(value) => Some(value),
+                _ => None,
+            })
+            .collect();
+        if values.windows(2).all(|pair| pair[0] == pair[1]) {
+            values.truncate(1);
+        }
+        (!values.is_empty()).then_some(values)
+    }
+
+    fn set_selected_pin(&mut self, axis: Axis, pin: Pin, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            let selected: Vec<NodeId> = canvas.selected_nodes().iter().copied().collect();
+            canvas.set_pins(&selected, axis, pin, cx);
+        });
+        cx.notify();
+    }
+
+    fn set_selected_auto_layout(&mut self, direction: Option<Axis>, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            let selected: Vec<NodeId> = canvas.selected_nodes().iter().copied().collect();
+            canvas.set_auto_layout(&selected, direction, cx);
+        });
+        cx.notify();
+    }
+
+    /// Apply a swatch's color to the selection's fill or stroke, whichever is targeted,
+    /// and use it for new elements
+    fn apply_swatch_color(&mut self, color: Hsla, cx: &mut Context<Self>) {
+        self.canvas
+            .update(cx, |canvas, cx| canvas.pick_color(color, cx));
+        cx.notify();
+    }
+
     /// Converts property data to the format needed by UI components
     /// with visual rounding applied to numerical values
-    fn get_ui_property_values(&self) -> (Option<Vec<f32>>, Option<Vec<f32>>, Option<Vec<f32>>, 
+    ///
+    /// Dimensions are converted into the document's unit first, so rem values keep
+    /// the extra decimals they need.
+    fn get_ui_property_values(&self, settings: &DocumentSettings) -> (Option<Vec<f32>>, Option<Vec<f32>>, Option<Vec<f32>>, 
                                         Option<Vec<f32>>, Option<Vec<f32>>, Option<Vec<f32>>,
                                         Option<SharedString>, Option<SharedString>) {
-        // Helper function to round f32 values to one decimal place
+        // Helper function to round converted values to the unit's precision
+        let scale = 10f32.powi(settings.dimension_precision() as i32);
         let round_values = |values: &[f32]| -> Vec<f32> {
-            values.iter().map(|&v| (v * 10.0).round() / 10.0).collect()
+            values
+                .iter()
+                .map(|&v| (settings.dimension(v) * scale).round() / scale)
+                .collect()
         };
         
         // Convert SmallVec properties to Option<Vec<f32>> with rounding
@@ -364,23 +1029,36 @@ impl Inspector {
     }
 }
 
+impl EventEmitter<InspectorEvent> for Inspector {}
+
 impl Render for Inspector {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let theme = Theme::default();
+        let theme = cx.theme().clone();
 
         // Update properties based on current selection
         self.update_selected_node_properties(cx);
+        let settings = self.state.read(cx).document_settings;
         
         // Get property values formatted for UI display with appropriate rounding
         let (x, y, width, height, border_width, corner_radius, border_color, background_color) = 
-            self.get_ui_property_values();
+            self.get_ui_property_values(&settings);
+        let overflow = self.selected_overflow(cx);
+        let opacity = self.selected_opacity(cx);
+        let blend_mode = self.selected_blend_mode(cx);
+        let pins = self.selected_pins(cx);
+        let auto_layout = self.selected_auto_layout(cx);
+        let gap = self.selected_spacing(InspectorField::Gap, cx);
+        let padding = self.selected_spacing(InspectorField::Padding, cx);
+        let recent_colors = self.state.read(cx).recent_colors.clone();
+        let mut document_colors = self.canvas.read(cx).document_colors();
+        document_colors.truncate(MAX_DOCUMENT_SWATCHES);
 
         let inner = div()
             .id("inspector-inner")
             .flex()
             .flex_col()
             .h_full()
-            .w(px(INSPECTOR_WIDTH))
+            .w(px(self.width))
             .rounded_tr(px(15.))
             .rounded_br(px(15.))
             .on_click(cx.listener(|_, _, _, cx| {
@@ -395,13 +1073,111 @@ impl Render for Inspector {
                     .gap(px(8.))
                     .border_color(theme.tokens.inactive_border)
                     .border_b_1()
-                    .child(float_input(x, "X"))
-                    .child(float_input(y, "Y"))
-                    .child(float_input(width, "W"))
-                    .child(float_input(height, "H"))
-                    .child(float_input(border_width, "B"))
-                    .child(float_input(corner_radius, "R")),
+                    .child(self.float_field(InspectorField::X, x, cx))
+                    .child(self.float_field(InspectorField::Y, y, cx))
+                    .child(self.float_field(InspectorField::Width, width, cx))
+                    .child(self.float_field(InspectorField::Height, height, cx))
+                    .child(self.float_field(InspectorField::BorderWidth, border_width, cx))
+                    .child(self.float_field(InspectorField::CornerRadius, corner_radius, cx))
+                    .child(self.float_field(InspectorField::Opacity, opacity, cx)),
             )
+            .when_some(overflow, |this, overflow| {
+                this.child(
+                    div()
+                        .px(px(8.))
+                        .py(px(10.))
+                        .border_color(theme.tokens.inactive_border)
+                        .border_b_1()
+                        .child(segmented_control(
+                            &theme,
+                            "overflow",
+                            Overflow::ALL.map(|overflow| (overflow, overflow.label())),
+                            overflow,
+                            cx.listener(|this, overflow: &Overflow, _, cx| {
+                                this.set_selected_overflow(*overflow, cx);
+                            }),
+                        )),
+                )
+            })
+            .when_some(pins.zip(auto_layout), |this, (pins, auto_layout)| {
+                let pin_control = |axis: Axis, id: &'static str, cx: &mut Context<Self>| {
+                    segmented_control(
+                        &theme,
+                        id,
+                        Pin::ALL.map(|pin| (pin, pin.label(axis))),
+                        pins.get(axis),
+                        cx.listener(move |this, pin: &Pin, _, cx| {
+                            this.set_selected_pin(axis, *pin, cx);
+                        }),
+                    )
+                };
+                let directions = [None, Some(Axis::Horizontal), Some(Axis::Vertical)]
+                    .map(|direction| (direction, direction.map_or("None", Axis::label)));
+                this.child(
+                    div()
+                        .px(px(8.))
+                        .py(px(10.))
+                        .flex()
+                        .flex_col()
+                        .gap(px(4.))
+                        .border_color(theme.tokens.inactive_border)
+                        .border_b_1()
+                        .child(pin_control(Axis::Horizontal, "pin-horizontal", cx))
+                        .child(pin_control(Axis::Vertical, "pin-vertical", cx))
+                        .child(segmented_control(
+                            &theme,
+                            "auto-layout",
+                            directions,
+                            auto_layout,
+                            cx.listener(|this, direction: &Option<Axis>, _, cx| {
+                                this.set_selected_auto_layout(*direction, cx);
+                            }),
+                        ))
+                        .when(auto_layout.is_some(), |this| {
+                            this.child(
+                                div()
+                                    .flex()
+                                    .gap(px(8.))
+                                    .child(self.float_field(InspectorField::Gap, gap, cx))
+                                    .child(self.float_field(InspectorField::Padding, padding, cx)),
+                            )
+                        }),
+                )
+            })
+            .when_some(blend_mode, |this, blend_mode| {
+                let [normal, multiply, screen, overlay, darken, lighten] =
+                    BlendMode::ALL.map(|mode| (mode, mode.label()));
+                let on_select = |cx: &mut Context<Self>| {
+                    cx.listener(|this, blend_mode: &BlendMode, _, cx| {
+                        this.set_selected_blend_mode(*blend_mode, cx);
+                    })
+                };
+                // Six modes don't fit the width of one row
+                this.child(
+                    div()
+                        .px(px(8.))
+                        .py(px(10.))
+                        .flex()
+                        .flex_col()
+                        .gap(px(4.))
+                        .border_color(theme.tokens.inactive_border)
+                        .border_b_1()
+                        .child(segmented_control(
+                            &theme,
+                            "blend-mode",
+                            [normal, multiply, screen],
+                            blend_mode,
+                            on_select(cx),
+                        ))
+                        .child(segmented_control(
+                            &theme,
+                            "blend-mode-more",
+                            [overlay, darken, lighten],
+                            blend_mode,
+                            on_select(cx),
+                        )),
+                )
+            })
             .child(
                 div()
                     .px(px(8.))
@@ -411,17 +1187,111 @@ impl Render for Inspector {
                     .gap(px(8.))
                     .border_color(theme.tokens.inactive_border)
                     .border_b_1()
-                    .child(ColorInput::new(background_color, SharedString::from("BG")))
-                    .child(ColorInput::new(border_color, SharedString::from("BC"))),
+                    .child(self.color_field(InspectorField::Fill, background_color, cx))
+                    .child(self.color_field(InspectorField::BorderColor, border_color, cx)),
+            )
+            .child(
+                div()
+                    .px(px(8.))
+                    .py(px(10.))
+                    .flex()
+                    .flex_col()
+                    .gap(px(8.))
+                    .border_color(theme.tokens.inactive_border)
+                    .border_b_1()
+                    .when(!recent_colors.is_empty(), |this| {
+                        this.child(swatch_row(
+                            &theme,
+                            "recent-colors",
+                            "Recent",
+                            recent_colors,
+                            cx.listener(|this, color: &Hsla, _, cx| {
+                                this.apply_swatch_color(*color, cx);
+                            }),
+                        ))
+                    })
+                    .when(!document_colors.is_empty(), |this| {
+                        this.child(swatch_row(
+                            &theme,
+                            "document-colors",
+                            "In This Document",
+                            document_colors,
+                            cx.listener(|this, color: &Hsla, _, cx| {
+                                this.apply_swatch_color(*color, cx);
+                            }),
+                        ))
+                    }),
+            )
+            .child(
+                div()
+                    .px(px(8.))
+                    .py(px(10.))
+                    .flex()
+                    .flex_col()
+                    .gap(px(8.))
+                    .child(segmented_control(
+                        &theme,
+                        "color-format",
+                        ColorFormat::ALL.map(|format| (format, format.label())),
+                        settings.color_format,
+                        cx.listener(|this, format: &ColorFormat, _, cx| {
+                            this.update_document_settings(cx, |settings| {
+                                settings.color_format = *format;
+                            });
+                        }),
+                    ))
+                    .child(segmented_control(
+                        &theme,
+                        "dimension-unit",
+                        DimensionUnit::ALL.map(|unit| (unit, unit.label())),
+                        settings.dimension_unit,
+                        cx.listener(|this, unit: &DimensionUnit, _, cx| {
+                            this.update_document_settings(cx, |settings| {
+                                settings.dimension_unit = *unit;
+                            });
+                        }),
+                    ))
+                    .when(settings.grid.visible, |this| {
+                        this.child(segmented_control(
+                            &theme,
+                            "grid-spacing",
+                            GridSettings::SPACING_PRESETS,
+                            settings.grid.spacing,
+                            cx.listener(|this, spacing: &f32, _, cx| {
+                                this.update_document_settings(cx, |settings| {
+                                    settings.grid.spacing = *spacing;
+                                });
+                            }),
+                        ))
+                        .child(segmented_control(
+                            &theme,
+                            "grid-subdivisions",
+                            GridSettings::SUBDIVISION_PRESETS,
+                            settings.grid.subdivisions,
+                            cx.listener(|this, subdivisions: &u32, _, cx| {
+                                this.update_document_settings(cx, |settings| {
+                                    settings.grid.subdivisions = *subdivisions;
+                                });
+                            }),
+                        ))
+                    }),
             );
 
         div()
             .id("inspector")
+            .track_focus(&self.focus_handle)
+            // Only take over keys while a field is being typed into
+            .when(self.editing.is_some(), |this| this.key_context("Inspector"))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::dismiss))
+            .on_action(cx.listener(Self::copy_value))
+            .on_action(cx.listener(Self::paste_value))
+            .on_key_down(cx.listener(Self::handle_key_down))
             .absolute()
             .right_0()
             .top_0()
             .h_full()
-            .w(px(INSPECTOR_WIDTH + 1.))
+            .w(px(self.width + 1.))
             .cursor_default()
             .rounded_tr(px(15.))
             .rounded_br(px(15.))
@@ -434,3 +1304,128 @@ impl Render for Inspector {
             .child(inner)
     }
 }
+
+/// A row of mutually exclusive options with the selected one highlighted
+fn segmented_control<T: Copy + PartialEq + 'static, const N: usize>(
+    theme: &Theme,
+    id: &'static str,
+    options: [(T, &'static str); N],
+    selected: T,
+    on_select: impl Fn(&T, &mut Window, &mut gpui::App) + 'static,
+) -> impl IntoElement {
+    let on_select = std::rc::Rc::new(on_select);
+
+    div()
+        .flex()
+        .p(px(1.))
+        .rounded(px(4.))
+        .bg(theme.tokens.surface0)
+        .text_size(px(10.))
+        .children(
+            options
+                .into_iter()
+                .enumerate()
+                .map(|(index, (option, label))| {
+                    let on_select = on_select.clone();
+                    div()
+                        .id((id, index))
+                        .flex_1()
+                        .flex()
+                        .justify_center()
+                        .py(px(2.))
+                        .rounded(px(3.))
+                        .text_color(theme.tokens.subtext0)
+                        .when(option == selected, |this| {
+                            this.bg(theme.tokens.surface2).text_color(theme.tokens.text)
+                        })
+                        .when(option != selected, |this| {
+                            this.hover(|this| this.text_color(theme.tokens.text))
+                        })
+                        .on_click(move |_, window, cx| on_select(&option, window, cx))
+                        .child(label)
+                }),
+        )
+}
+
+/// A labelled row of color swatches, clicking a swatch picks its color
+fn swatch_row(
+    theme: &Theme,
+    id: &'static str,
+    label: &'static str,
+    colors: Vec<Hsla>,
+    on_pick: impl Fn(&Hsla, &mut Window, &mut gpui::App) + 'static,
+) -> impl IntoElement {
+    let on_pick = std::rc::Rc::new(on_pick);
+
+    div()
+        .flex()
+        .flex_col()
+        .gap(px(4.))
+        .text_size(px(10.))
+        .text_color(theme.tokens.subtext0)
+        .child(label)
+        .child(
+            div()
+                .flex()
+                .flex_wrap()
+                .gap(px(4.))
+                .children(colors.into_iter().enumerate().map(|(index, color)| {
+                    let on_pick = on_pick.clone();
+                    div()
+                        .id((id, index))
+                        .size(px(14.))
+                        .rounded(px(3.))
+                        .border_1()
+                        .border_color(theme.tokens.inactive_border)
+                        .bg(color)
+                        .hover(|this| this.border_color(theme.tokens.active_border))
+                        .on_click(move |_, window, cx| on_pick(&color, window, cx))
+                })),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::same_color, init_globals, keymap::init_keymap, power, window_state::WindowState,
+        Luna,
+    };
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    fn test_typing_into_a_field_keeps_keys_from_the_canvas(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            init_keymap(cx);
+            init_globals(cx);
+            power::init(Default::default(), cx);
+        });
+        let (luna, cx) =
+            cx.add_window_view(|window, cx| Luna::new(WindowState::default(), window, cx));
+        let (canvas, inspector) =
+            luna.read_with(cx, |luna, _| (luna.canvas.clone(), luna.inspector.clone()));
+
+        let node_id = canvas.update(cx, |canvas, cx| {
+            let node_id = canvas.generate_id();
+            let node = FrameNode::with_rect(node_id, 0.0, 0.0, 100.0, 100.0);
+            canvas.add_node(node, None, cx);
+            canvas.select_node(node_id);
+            node_id
+        });
+        cx.update(|window, cx| {
+            inspector.update(cx, |inspector, cx| {
+                inspector.begin_edit(InspectorField::Fill, "#".to_string(), window, cx)
+            })
+        });
+
+        // `f` and `d` pick a tool and reset the current colors on the canvas, backspace
+        // deletes the selection
+        cx.simulate_keystrokes("f f 0 0 0 d backspace 0 enter");
+
+        let fill = canvas.read_with(cx, |canvas, _| {
+            assert!(canvas.selected_nodes().contains(&node_id));
+            canvas.get_node(node_id).and_then(|node| node.fill())
+        });
+        assert!(fill.is_some_and(|fill| same_color(fill, gpui::rgb(0xff0000).into())));
+    }
+}
@@ -2,20 +2,63 @@
 //!
 //! Provides a hierarchical view of elements in the canvas,
 //! showing their selection state and allowing interaction.
+//!
+//! Layers can be dragged to reorder and reparent them. Dragging a selected layer
+//! drags the whole selection, and hovering a collapsed group during a drag opens it
+//! so layers can be dropped among its children.
+//!
+//! Each row can hide its layer or lock it in place on the canvas. Both apply to the
+//! layer's children too, which are dimmed in the list while a parent is hidden.
+//!
+//! Rows can also solo their layer to work on it alone. Everything outside the soloed
+//! layers is dimmed here and left off the canvas until solo is switched off again,
+//! without touching anyone's hidden setting.
+//!
+//! Roots of library component instances are marked with a diamond, and layers inside
+//! an instance whose fill differs from the component's are marked as overridden.
+//!
+//! In a view only document the list is for finding layers: rows select and solo, but
+//! can't be dragged, hidden or locked.
 
 use gpui::{
-    div, prelude::*, px, App, ElementId, Entity, Hsla, IntoElement, List, SharedString, WeakEntity,
-    Window,
+    div, prelude::*, px, App, DragMoveEvent, ElementId, Entity, Hsla, IntoElement, List,
+    SharedString, Subscription, WeakEntity, Window,
 };
 
-use std::collections::HashSet;
+use std::time::{Duration, Instant};
 
 use crate::{
-    canvas::LunaCanvas,
+    canvas::{LayerDrop, LunaCanvas},
     node::{frame::FrameNode, NodeCommon, NodeId, NodeType},
     theme::Theme,
 };
 
+/// How long a collapsed group has to be hovered during a drag before it opens
+const AUTO_EXPAND_DELAY: Duration = Duration::from_millis(500);
+
+/// Layers being dragged in the layer list, and the view shown under the cursor
+#[derive(Clone)]
+struct DraggedLayers {
+    node_ids: Vec<NodeId>,
+    label: SharedString,
+}
+
+impl Render for DraggedLayers {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+
+        div()
+            .px_2()
+            .py(px(2.))
+            .rounded(px(4.))
+            .text_xs()
+            .text_color(theme.tokens.text)
+            .bg(theme.tokens.surface1)
+            .shadow_md()
+            .child(self.label.clone())
+    }
+}
+
 /// Individual item in the layer list representing a canvas element
 #[derive(IntoElement)]
 pub struct LayerListItem {
@@ -24,12 +67,31 @@ pub struct LayerListItem {
     name: SharedString,
     selected: bool,
     nesting_level: usize,
+    /// `None` for nodes without children, otherwise whether they're collapsed
+    collapsed: Option<bool>,
+    hidden: bool,
+    locked: bool,
+    soloed: bool,
+    /// Whether a parent or solo hides this layer, whatever its own setting
+    parent_hidden: bool,
+    /// Where dragged layers would land, when it's relative to this item
+    drop_target: Option<LayerDrop>,
+    /// The nodes dragged when dragging this item
+    dragged: Vec<NodeId>,
+    /// Whether the layer can be dragged, hidden and locked
+    editable: bool,
+    /// Whether the layer is the root of a component instance
+    instance: bool,
+    /// Whether the layer's fill is its own rather than its component's
+    overridden: bool,
     weak_canvas_handle: WeakEntity<LunaCanvas>,
+    weak_layer_list: WeakEntity<LayerList>,
 }
 
 impl LayerListItem {
     pub fn new(
         weak_canvas_handle: WeakEntity<LunaCanvas>,
+        weak_layer_list: WeakEntity<LayerList>,
         node_id: NodeId,
         name: impl Into<SharedString>,
         kind: NodeType,
@@ -40,7 +102,18 @@ impl LayerListItem {
             name: name.into(),
             selected: false,
             nesting_level: 0,
+            collapsed: None,
+            hidden: false,
+            locked: false,
+            soloed: false,
+            parent_hidden: false,
+            drop_target: None,
+            dragged: vec![node_id],
+            editable: true,
+            instance: false,
+            overridden: false,
             weak_canvas_handle,
+            weak_layer_list,
         }
     }
 
@@ -53,6 +126,48 @@ impl LayerListItem {
         self.nesting_level = level;
         self
     }
+
+    pub fn collapsed(mut self, collapsed: Option<bool>) -> Self {
+        self.collapsed = collapsed;
+        self
+    }
+
+    pub fn hidden(mut self, hidden: bool, parent_hidden: bool) -> Self {
+        self.hidden = hidden;
+        self.parent_hidden = parent_hidden;
+        self
+    }
+
+    pub fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    pub fn soloed(mut self, soloed: bool) -> Self {
+        self.soloed = soloed;
+        self
+    }
+
+    pub fn drop_target(mut self, drop_target: Option<LayerDrop>) -> Self {
+        self.drop_target = drop_target;
+        self
+    }
+
+    pub fn component(mut self, instance: bool, overridden: bool) -> Self {
+        self.instance = instance;
+        self.overridden = overridden;
+        self
+    }
+
+    pub fn editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    pub fn dragged(mut self, dragged: Vec<NodeId>) -> Self {
+        self.dragged = dragged;
+        self
+    }
 }
 
 impl RenderOnce for LayerListItem {
@@ -64,42 +179,270 @@ impl RenderOnce for LayerListItem {
         } else {
             theme.tokens.subtext0
         };
+        let text_color = if self.hidden || self.parent_hidden {
+            text_color.alpha(0.45)
+        } else {
+            text_color
+        };
 
         let indentation = px(10.0 + (self.nesting_level as f32 * 10.0));
+        let indicator_color = theme.tokens.active_border;
+        let dragged = DraggedLayers {
+            label: match self.dragged.len() {
+                1 => self.name.clone(),
+                count => format!("{count} layers").into(),
+            },
+            node_ids: self.dragged.clone(),
+        };
+
+        let node_id = self.node_id;
+        let editable = self.editable;
+        let collapsed = self.collapsed;
+        let drop_target = self.drop_target;
+        let weak_layer_list = self.weak_layer_list.clone();
+        let toggle_canvas = self.weak_canvas_handle.clone();
+        let drag_layer_list = self.weak_layer_list.clone();
+        let drop_layer_list = self.weak_layer_list.clone();
+
+        // Toggles show while the row is hovered, and stay once they're switched on
+        let toggle = |name: &str, glyph: &'static str, on: bool| {
+            div()
+                .id(ElementId::Name(
+                    format!("layer-{name}-{}", node_id.0).into(),
+                ))
+                .flex_none()
+                .w(px(14.))
+                .text_color(if on {
+                    text_color
+                } else {
+                    gpui::transparent_black()
+                })
+                .group_hover("layer-row", |style| style.text_color(text_color.alpha(0.6)))
+                .hover(|style| style.text_color(theme.tokens.text))
+                .child(glyph)
+        };
 
         div()
-            .id(ElementId::Name(format!("layer-{}", self.name).into()))
+            .id(ElementId::Name(format!("layer-{}", self.node_id.0).into()))
+            .group("layer-row")
+            .relative()
             .pl(indentation)
             .flex()
             .items_center()
             .rounded_tl(px(4.))
             .rounded_bl(px(4.))
             .when(self.selected, |div| div.bg(theme.tokens.selected))
+            .when(drop_target == Some(LayerDrop::Inside(node_id)), |div| {
+                div.bg(indicator_color.opacity(0.2))
+            })
             .active(|div| div.bg(theme.tokens.surface2.opacity(0.7)))
             .text_color(text_color)
-            .gap(px(10.))
-            .on_click(move |e, _, cx| {
-                let canvas = self
-                    .weak_canvas_handle
-                    .upgrade()
-                    .expect("Canvas handle is dead");
-                canvas.update(cx, |canvas, cx| {
-                    canvas.select_node(self.node_id);
-                });
+            .gap(px(6.))
+            .on_click({
+                let weak_canvas_handle = self.weak_canvas_handle.clone();
+                move |_, window, cx| {
+                    let extend = window.modifiers().shift;
+                    weak_canvas_handle
+                        .update(cx, |canvas, cx| {
+                            // Shift adds to the selection like it does on the canvas
+                            if extend {
+                                canvas.toggle_node_selection(node_id);
+                            } else {
+                                canvas.deselect_all_nodes(cx);
+                                canvas.select_node(node_id);
+                            }
+                            canvas.mark_dirty(cx);
+                        })
+                        .ok();
+                }
+            })
+            .when(editable, |this| {
+                this.on_drag(dragged, |dragged, _, _, cx| cx.new(|_| dragged.clone()))
+                    .on_drag_move::<DraggedLayers>(move |event, _window, cx| {
+                        if !event.bounds.contains(&event.event.position) {
+                            return;
+                        }
+                        let fraction = (event.event.position.y - event.bounds.origin.y)
+                            / event.bounds.size.height;
+                        let drop = drop_zone(node_id, collapsed == Some(false), fraction);
+                        drag_layer_list
+                            .update(cx, |layer_list, cx| {
+                                layer_list.set_drop_target(Some(drop), cx)
+                            })
+                            .ok();
+                    })
+                    .on_drop(move |dragged: &DraggedLayers, _window, cx| {
+                        drop_layer_list
+                            .update(cx, |layer_list, cx| layer_list.drop_layers(dragged, cx))
+                            .ok();
+                    })
+            })
+            .child(
+                div()
+                    .id(ElementId::Name(
+                        format!("layer-disclosure-{}", node_id.0).into(),
+                    ))
+                    .w(px(8.))
+                    .text_color(text_color.alpha(0.6))
+                    .when_some(collapsed, |div, collapsed| {
+                        div.child(if collapsed { "▸" } else { "▾" })
+                            .on_click(move |_, _, cx| {
+                                // Opening a group shouldn't also select it
+                                cx.stop_propagation();
+                                weak_layer_list
+                                    .update(cx, |layer_list, cx| {
+                                        layer_list.toggle_collapsed(node_id, cx)
+                                    })
+                                    .ok();
+                            })
+                    }),
+            )
+            .child(
+                div()
+                    .text_color(text_color.alpha(0.8))
+                    .child(if self.instance { "◇" } else { "□" }),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .child(self.name),
+            )
+            .when(self.overridden, |this| {
+                this.child(div().text_color(text_color.alpha(0.6)).child("•"))
+            })
+            .child(
+                toggle("solo", "◎", self.soloed)
+                    .when(!editable, |toggle| toggle.mr_1())
+                    .on_click({
+                        let canvas = toggle_canvas.clone();
+                        move |_, _, cx| {
+                            cx.stop_propagation();
+                            canvas
+                                .update(cx, |canvas, cx| canvas.toggle_solo(node_id, cx))
+                                .ok();
+                        }
+                    }),
+            )
+            .when(editable, |this| {
+                this.child(toggle("lock", "⊘", self.locked).on_click({
+                    let canvas = toggle_canvas.clone();
+                    let locked = self.locked;
+                    move |_, _, cx| {
+                        cx.stop_propagation();
+                        canvas
+                            .update(cx, |canvas, cx| canvas.set_locked(&[node_id], !locked, cx))
+                            .ok();
+                    }
+                }))
+                .child(toggle("visibility", "◌", self.hidden).mr_1().on_click({
+                    let hidden = self.hidden;
+                    move |_, _, cx| {
+                        cx.stop_propagation();
+                        toggle_canvas
+                            .update(cx, |canvas, cx| canvas.set_hidden(&[node_id], !hidden, cx))
+                            .ok();
+                    }
+                }))
+            })
+            .map(|this| match drop_target {
+                Some(LayerDrop::Before(_)) => {
+                    this.child(drop_line(indentation, indicator_color).top_0())
+                }
+                Some(LayerDrop::After(_)) => {
+                    this.child(drop_line(indentation, indicator_color).bottom_0())
+                }
+                _ => this,
             })
-            .child(div().text_color(text_color.alpha(0.8)).child("□"))
-            .child(self.name)
     }
 }
 
+/// Where a drop `fraction` of the way down a node's row would land
+fn drop_zone(node_id: NodeId, is_open_group: bool, fraction: f32) -> LayerDrop {
+    // An open group's children are listed right below it, so its lower edge is the
+    // start of its children rather than a gap after it
+    if fraction < 0.25 {
+        LayerDrop::Before(node_id)
+    } else if fraction > 0.75 && !is_open_group {
+        LayerDrop::After(node_id)
+    } else {
+        LayerDrop::Inside(node_id)
+    }
+}
+
+/// The line showing where dragged layers will be inserted between two rows
+fn drop_line(indentation: gpui::Pixels, color: Hsla) -> gpui::Div {
+    div()
+        .absolute()
+        .left(indentation)
+        .right_0()
+        .h(px(2.))
+        .bg(color)
+}
+
 /// Container for the list of layer items representing canvas elements
 pub struct LayerList {
     canvas: Entity<LunaCanvas>,
+    /// Where the layers being dragged would be dropped
+    drop_target: Option<LayerDrop>,
+    /// A collapsed group hovered during a drag, and when the hover started
+    pending_expand: Option<(NodeId, Instant)>,
+    /// Keeps the list in step with selection and edits made on the canvas
+    _observe_canvas: Subscription,
 }
 
 impl LayerList {
     pub fn new(canvas: Entity<LunaCanvas>, cx: &mut Context<Self>) -> Self {
-        Self { canvas }
+        let _observe_canvas = cx.observe(&canvas, |_, _, cx| cx.notify());
+        Self {
+            canvas,
+            drop_target: None,
+            pending_expand: None,
+            _observe_canvas,
+        }
+    }
+
+    pub fn toggle_collapsed(&mut self, node_id: NodeId, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            let collapsed = canvas.get_node(node_id).is_some_and(|node| node.collapsed);
+            canvas.set_layer_collapsed(node_id, !collapsed, cx);
+        });
+        cx.notify();
+    }
+
+    fn is_collapsed(&self, node_id: NodeId, cx: &App) -> bool {
+        self.canvas
+            .read(cx)
+            .get_node(node_id)
+            .is_some_and(|node| node.collapsed)
+    }
+
+    fn set_drop_target(&mut self, drop_target: Option<LayerDrop>, cx: &mut Context<Self>) {
+        match drop_target {
+            Some(LayerDrop::Inside(node_id)) if self.is_collapsed(node_id, cx) => {
+                if self.pending_expand.map(|(pending, _)| pending) != Some(node_id) {
+                    self.pending_expand = Some((node_id, Instant::now()));
+                }
+            }
+            _ => self.pending_expand = None,
+        }
+
+        if self.drop_target != drop_target {
+            self.drop_target = drop_target;
+            cx.notify();
+        }
+    }
+
+    fn drop_layers(&mut self, dragged: &DraggedLayers, cx: &mut Context<Self>) {
+        if let Some(drop) = self.drop_target.take() {
+            self.canvas.update(cx, |canvas, cx| {
+                canvas.move_layers(&dragged.node_ids, drop, cx);
+            });
+        }
+        self.pending_expand = None;
+        cx.notify();
     }
 
     // Helper method to find the parent of a node
@@ -115,19 +458,30 @@ impl LayerList {
     // Build the layer list items with hierarchy
     fn build_items(
         &self,
+        canvas: &LunaCanvas,
         weak_canvas_handle: WeakEntity<LunaCanvas>,
-        nodes: &[FrameNode],
+        weak_layer_list: WeakEntity<LayerList>,
         parent_id: Option<NodeId>,
         nesting_level: usize,
-        selected_nodes: &HashSet<NodeId>,
+        parent_hidden: bool,
     ) -> Vec<LayerListItem> {
         let mut items = Vec::new();
+        let nodes = canvas.nodes();
+        let selected_nodes = canvas.selected_nodes();
 
+        // Children are listed in the order their parent paints them
         let children = if let Some(parent) = parent_id {
             nodes
                 .iter()
-                .filter(|node| self.find_parent(nodes, node.id()) == Some(parent))
-                .collect::<Vec<_>>()
+                .find(|node| node.id() == parent)
+                .map(|parent| {
+                    parent
+                        .children()
+                        .iter()
+                        .filter_map(|child| nodes.iter().find(|node| node.id() == *child))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
         } else {
             // root nodes
             nodes
@@ -138,23 +492,53 @@ impl LayerList {
 
         for node in children {
             let node_id = node.id();
-            let name = format!("Frame {}", node_id.0);
+            let name = node.name();
             let selected = selected_nodes.contains(&node_id);
+            let has_children = !node.children().is_empty();
+            let collapsed = node.collapsed;
+            // Dragging a selected layer takes the rest of the selection along
+            let dragged = if selected {
+                selected_nodes.iter().copied().collect()
+            } else {
+                vec![node_id]
+            };
 
             items.push(
-                LayerListItem::new(weak_canvas_handle.clone(), node_id, name, NodeType::Frame)
-                    .selected(selected)
-                    .nesting_level(nesting_level),
+                LayerListItem::new(
+                    weak_canvas_handle.clone(),
+                    weak_layer_list.clone(),
+                    node_id,
+                    name,
+                    node.node_type(),
+                )
+                .selected(selected)
+                .nesting_level(nesting_level)
+                .collapsed(has_children.then_some(collapsed))
+                .hidden(
+                    node.hidden,
+                    parent_hidden || canvas.is_hidden_by_solo(node_id),
+                )
+                .locked(node.locked)
+                .component(
+                    node.component.is_some(),
+                    node.component_source
+                        .as_ref()
+                        .is_some_and(|source| source.overrides_fill(node)),
+                )
+                .soloed(canvas.is_soloed(node_id))
+                .drop_target(self.drop_target.filter(|drop| drop.node_id() == node_id))
+                .dragged(dragged),
             );
 
             // Add children
-            if !node.children().is_empty() {
+            if has_children && !collapsed {
                 let child_items = self.build_items(
+                    canvas,
                     weak_canvas_handle.clone(),
-                    nodes,
+                    weak_layer_list.clone(),
                     Some(node_id),
                     nesting_level + 1,
-                    selected_nodes,
+                    parent_hidden || node.hidden,
                 );
                 items.extend(child_items);
             }
@@ -165,24 +549,50 @@ impl LayerList {
 }
 
 impl Render for LayerList {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // Open a collapsed group once it has been hovered long enough, checking again
+        // each frame until then since the cursor may be resting on it
+        if let Some((node_id, since)) = self.pending_expand {
+            if since.elapsed() >= AUTO_EXPAND_DELAY {
+                self.canvas.update(cx, |canvas, cx| {
+                    canvas.set_layer_collapsed(node_id, false, cx);
+                });
+                self.pending_expand = None;
+            } else {
+                window.request_animation_frame();
+            }
+        }
+
         let mut layers = div()
             .id("layer-list")
             .key_context("LayerList")
             .flex()
             .flex_col()
             .flex_1()
-            .pt_1();
+            .pt_1()
+            // Rows set the drop target as the cursor moves over them, it's only cleared
+            // once the cursor leaves the list
+            .on_drag_move::<DraggedLayers>(cx.listener(
+                |this, event: &DragMoveEvent<DraggedLayers>, _, cx| {
+                    if !event.bounds.contains(&event.event.position) {
+                        this.set_drop_target(None, cx);
+                    }
+                },
+            ))
+            // Dropping in the space below the rows uses the last target shown
+            .on_drop(cx.listener(|this, dragged: &DraggedLayers, _, cx| {
+                this.drop_layers(dragged, cx);
+            }));
 
-        let canvas = self.canvas.read(cx);
-        let nodes = canvas.nodes().clone();
-        let selected_nodes = canvas.selected_nodes().clone();
         let weak_canvas_handle = self.canvas.clone().downgrade();
+        let weak_layer_list = cx.entity().downgrade();
+        let canvas = self.canvas.read(cx);
+        let editable = !canvas.is_view_only(cx);
 
-        let items = self.build_items(weak_canvas_handle, &nodes, None, 0, &selected_nodes);
+        let items = self.build_items(canvas, weak_canvas_handle, weak_layer_list, None, 0, false);
 
         for item in items {
-            layers = layers.child(item);
+            layers = layers.child(item.editable(editable));
         }
 
         layers
@@ -0,0 +1,305 @@
+//! Library browser listing what's been published to the shared library.
+//!
+//! Like the document info panel it shows the library as it was when the panel opened.
+//! Clicking a component inserts an instance of it, clicking a style or color token
+//! applies it to the selection. Either way the panel closes.
+//!
+//! Color tokens can also be given a new color here, typed in any format the color
+//! fields take. The change is published like any other and the open document updates
+//! every layer using the token.
+
+use gpui::{
+    actions, div, prelude::*, px, App, ElementId, EventEmitter, FocusHandle, Focusable, Hsla,
+    IntoElement, KeyDownEvent, MouseButton, Window,
+};
+
+use crate::{
+    color::parse_color,
+    library::{Library, LibraryContent, LibraryItem, LibraryKind},
+    theme::Theme,
+};
+
+use super::Titlebar;
+
+actions!(library_browser, [Confirm, Dismiss, Paste]);
+
+pub enum LibraryBrowserEvent {
+    /// An item was clicked
+    Insert(LibraryItem),
+    /// A color token was given a new color
+    Recolor(LibraryItem, Hsla),
+    Dismissed,
+}
+
+pub struct LibraryBrowser {
+    focus_handle: FocusHandle,
+    library: Library,
+    /// The id of the color token being recolored and the color typed for it so far
+    recoloring: Option<(String, String)>,
+}
+
+impl LibraryBrowser {
+    const WIDTH: f32 = 320.;
+    const MAX_HEIGHT: f32 = 420.;
+
+    pub fn new(library: Library, cx: &mut Context<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            library,
+            recoloring: None,
+        }
+    }
+
+    /// Leaving a recolor only cancels it, the panel stays open
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.recoloring.take().is_some() {
+            cx.notify();
+        } else {
+            cx.emit(LibraryBrowserEvent::Dismissed);
+        }
+    }
+
+    fn start_recoloring(&mut self, item: &LibraryItem, cx: &mut Context<Self>) {
+        let LibraryContent::ColorToken { color } = &item.content else {
+            return;
+        };
+        self.recoloring = Some((item.id.clone(), color.clone()));
+        cx.notify();
+    }
+
+    /// Edit the color being typed from raw key presses, like the link editor
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let keystroke = &event.keystroke;
+        let Some((_, text)) = &mut self.recoloring else {
+            return;
+        };
+        if keystroke.modifiers.platform || keystroke.modifiers.control {
+            return;
+        }
+
+        if keystroke.key == "backspace" {
+            text.pop();
+        } else if let Some(typed) = keystroke
+            .key_char
+            .as_ref()
+            .filter(|typed| !typed.chars().any(char::is_control))
+        {
+            text.push_str(typed);
+        } else {
+            return;
+        }
+
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn paste(&mut self, _: &Paste, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some((_, text)) = &mut self.recoloring else {
+            return;
+        };
+        if let Some(pasted) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            *text = pasted.trim().to_string();
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some((id, text)) = &self.recoloring else {
+            return;
+        };
+        // An unreadable color stays as typed so the mistake can be seen
+        let Some(color) = parse_color(text) else {
+            return;
+        };
+        if let Some(item) = self.library.item(id) {
+            cx.emit(LibraryBrowserEvent::Recolor(item.clone(), color));
+        }
+    }
+
+    fn item_row(
+        &self,
+        item: &LibraryItem,
+        theme: &Theme,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let (swatch, detail) = match &item.content {
+            LibraryContent::Component { nodes } => {
+                let size = nodes
+                    .first()
+                    .map(|root| format!("{} × {}", root.width, root.height))
+                    .unwrap_or_default();
+                (None, size)
+            }
+            LibraryContent::Style { style } => {
+                let fill = style.fill.as_deref().and_then(parse_color);
+                (fill, String::new())
+            }
+            LibraryContent::ColorToken { color } => (parse_color(color), color.clone()),
+        };
+        let recoloring = self
+            .recoloring
+            .as_ref()
+            .filter(|(id, _)| *id == item.id)
+            .map(|(_, text)| text.clone());
+        let is_token = item.content.kind() == LibraryKind::ColorToken;
+
+        let clicked = item.clone();
+        let recolored = item.clone();
+        let row = div()
+            .id(ElementId::Name(format!("library-item-{}", item.id).into()))
+            .group("library-item")
+            .flex()
+            .items_center()
+            .gap(px(6.))
+            .px_2()
+            .py(px(2.))
+            .rounded(px(4.))
+            .hover(|this| this.bg(theme.tokens.surface0))
+            .on_click(cx.listener(move |_, _, _, cx| {
+                cx.emit(LibraryBrowserEvent::Insert(clicked.clone()));
+            }))
+            .child(
+                div()
+                    .size(px(10.))
+                    .rounded(px(2.))
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .when_some(
+                        recoloring.as_deref().and_then(parse_color).or(swatch),
+                        |this, color: Hsla| this.bg(color),
+                    ),
+            )
+            .child(div().flex_1().child(item.name.clone()));
+
+        match recoloring {
+            Some(text) => row.child(
+                div()
+                    .px_1()
+                    .rounded(px(3.))
+                    .border_1()
+                    .border_color(if parse_color(&text).is_some() {
+                        theme.tokens.inactive_border
+                    } else {
+                        theme.tokens.error
+                    })
+                    .bg(theme.tokens.surface0)
+                    .child(format!("{text}|")),
+            ),
+            None => row
+                .child(div().text_color(theme.tokens.subtext0).child(detail))
+                .when(is_token, |row| {
+                    row.child(
+                        div()
+                            .id(ElementId::Name(
+                                format!("library-recolor-{}", item.id).into(),
+                            ))
+                            .text_color(gpui::transparent_black())
+                            .group_hover("library-item", |style| {
+                                style.text_color(theme.tokens.subtext0)
+                            })
+                            .hover(|style| style.text_color(theme.tokens.text))
+                            .child("✎")
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                // Recoloring a token shouldn't also apply it
+                                cx.stop_propagation();
+                                this.start_recoloring(&recolored, cx);
+                            })),
+                    )
+                }),
+        }
+    }
+}
+
+impl EventEmitter<LibraryBrowserEvent> for LibraryBrowser {}
+
+impl Focusable for LibraryBrowser {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LibraryBrowser {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx).clone();
+
+        let section = |title: &'static str| {
+            div().flex().flex_col().gap(px(2.)).child(
+                div()
+                    .px_2()
+                    .text_size(px(10.))
+                    .text_color(theme.tokens.subtext0)
+                    .child(title),
+            )
+        };
+
+        let mut sections = Vec::new();
+        for kind in LibraryKind::ALL {
+            let rows = self
+                .library
+                .items
+                .iter()
+                .filter(|item| item.content.kind() == kind)
+                .map(|item| self.item_row(item, &theme, cx))
+                .collect::<Vec<_>>();
+            if !rows.is_empty() {
+                sections.push(section(kind.plural_label()).children(rows));
+            }
+        }
+
+        div()
+            .id("library-browser-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .flex_col()
+            .items_center()
+            .pt(px(Titlebar::HEIGHT * 3.))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(LibraryBrowserEvent::Dismissed)),
+            )
+            .child(
+                div()
+                    .id("library-browser")
+                    .key_context("LibraryBrowser")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::confirm))
+                    .on_action(cx.listener(Self::dismiss))
+                    .on_action(cx.listener(Self::paste))
+                    .on_key_down(cx.listener(Self::handle_key_down))
+                    // Keep clicks inside the panel from reaching the backdrop
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(Self::WIDTH))
+                    .max_h(px(Self::MAX_HEIGHT))
+                    .overflow_y_scroll()
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .p_2()
+                    .text_size(px(12.))
+                    .text_color(theme.tokens.text)
+                    .bg(theme.tokens.background_secondary)
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .rounded(px(8.))
+                    .shadow_lg()
+                    .when(sections.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .px_2()
+                                .text_color(theme.tokens.subtext0)
+                                .child("Nothing has been published to the library yet"),
+                        )
+                    })
+                    .children(sections),
+            )
+    }
+}
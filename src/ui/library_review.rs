@@ -0,0 +1,344 @@
+//! Library review panel for bringing layers up to date with the library.
+//!
+//! Every layer linked to an item that was published again gets a row, with thumbnails
+//! of the layer as it is and as the update would leave it. Each update can be taken or
+//! skipped on its own. Skipped layers stay linked to the revision they're at, so they
+//! come up again the next time the library changes.
+//!
+//! Like the document info panel the rows are worked out when the panel opens.
+
+use gpui::{
+    actions, div, prelude::*, px, App, ElementId, Entity, EventEmitter, FocusHandle, Focusable,
+    IntoElement, MouseButton, Point, SharedString, Window,
+};
+
+use crate::{
+    canvas::LunaCanvas,
+    color::ColorTarget,
+    library::{LibraryUpdate, LinkedAs},
+    node::{
+        frame::{FrameNode, NodeStyle},
+        NodeCommon, NodeId,
+    },
+    theme::Theme,
+    thumbnail::{build_thumbnail, ThumbnailShape, THUMBNAIL_SIZE},
+};
+
+use super::{frame_navigator::FrameNavigator, Titlebar};
+
+actions!(library_review, [Confirm, Dismiss]);
+
+pub enum LibraryReviewEvent {
+    /// The updates that were taken, in the order they were listed
+    Apply(Vec<LibraryUpdate>),
+    Dismissed,
+}
+
+struct ReviewRow {
+    update: LibraryUpdate,
+    name: SharedString,
+    before: Option<ThumbnailShape>,
+    after: Option<ThumbnailShape>,
+    accepted: bool,
+}
+
+pub struct LibraryReview {
+    focus_handle: FocusHandle,
+    rows: Vec<ReviewRow>,
+}
+
+impl LibraryReview {
+    const WIDTH: f32 = 460.;
+    const MAX_HEIGHT: f32 = 420.;
+
+    pub fn new(
+        updates: Vec<LibraryUpdate>,
+        canvas: &Entity<LunaCanvas>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let canvas = canvas.read(cx);
+        let rows = updates
+            .into_iter()
+            .map(|update| {
+                let lookup = |node_id: NodeId| canvas.get_node(node_id);
+                let name = canvas
+                    .get_node(update.node_id)
+                    .map_or_else(|| update.node_id.to_string(), |node| node.name());
+                ReviewRow {
+                    name: name.into(),
+                    before: build_thumbnail(update.node_id, THUMBNAIL_SIZE, &lookup),
+                    after: updated_thumbnail(&update, canvas),
+                    update,
+                    accepted: true,
+                }
+            })
+            .collect();
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            rows,
+        }
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(LibraryReviewEvent::Dismissed);
+    }
+
+    fn confirm(&mut self, _: &Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        let accepted = self
+            .rows
+            .iter()
+            .filter(|row| row.accepted)
+            .map(|row| row.update.clone())
+            .collect();
+        cx.emit(LibraryReviewEvent::Apply(accepted));
+    }
+
+    fn set_all_accepted(&mut self, accepted: bool, cx: &mut Context<Self>) {
+        for row in &mut self.rows {
+            row.accepted = accepted;
+        }
+        cx.notify();
+    }
+
+    fn thumbnail(shape: Option<&ThumbnailShape>, theme: &Theme) -> impl IntoElement {
+        div()
+            .relative()
+            .flex_none()
+            .w(px(THUMBNAIL_SIZE.width))
+            .h(px(THUMBNAIL_SIZE.height))
+            .rounded(px(4.))
+            .bg(theme.tokens.surface0)
+            .when_some(shape, |this, shape| {
+                this.child(FrameNavigator::render_shape(shape))
+            })
+    }
+
+    fn render_row(&self, index: usize, theme: &Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let row = &self.rows[index];
+        let update = &row.update;
+        let choice = |label: &'static str, accepted: bool| {
+            let is_chosen = row.accepted == accepted;
+            div()
+                .id(ElementId::Name(
+                    format!("library-review-{label}-{index}").into(),
+                ))
+                .px_2()
+                .rounded(px(3.))
+                .when(is_chosen, |this| this.bg(theme.tokens.surface2))
+                .when(!is_chosen, |this| {
+                    this.text_color(theme.tokens.subtext0)
+                        .hover(|this| this.bg(theme.tokens.surface1))
+                })
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.rows[index].accepted = accepted;
+                    cx.notify();
+                }))
+                .child(label)
+        };
+
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .p_1()
+            .child(Self::thumbnail(row.before.as_ref(), theme))
+            .child(div().text_color(theme.tokens.subtext0).child("→"))
+            .child(Self::thumbnail(row.after.as_ref(), theme))
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(div().truncate().child(row.name.clone()))
+                    .child(
+                        div()
+                            .truncate()
+                            .text_color(theme.tokens.foreground_muted)
+                            .child(format!(
+                                "{} {}, revision {} → {}",
+                                update.linked_as.label(),
+                                update.item.name,
+                                update.revision,
+                                update.item.revision
+                            )),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_1()
+                            .child(choice("Update", true))
+                            .child(choice("Skip", false)),
+                    ),
+            )
+    }
+}
+
+/// Thumbnail of the layer as `update` would leave it
+fn updated_thumbnail(update: &LibraryUpdate, canvas: &LunaCanvas) -> Option<ThumbnailShape> {
+    let target = match update.linked_as {
+        LinkedAs::Component => {
+            // An instance is rebuilt from the component, so it's previewed on its own
+            let mut next_id = 0;
+            let instance = update
+                .item
+                .instantiate(Point::default(), None, || {
+                    next_id += 1;
+                    NodeId::new(next_id)
+                })
+                .ok()?;
+            let root = instance.first()?.id;
+            let lookup = |node_id: NodeId| instance.iter().find(|node| node.id == node_id);
+            return build_thumbnail(root, THUMBNAIL_SIZE, &lookup);
+        }
+        LinkedAs::Style => ColorTarget::Fill,
+        LinkedAs::Token(target) => target,
+    };
+
+    let mut node: FrameNode = canvas.get_node(update.node_id)?.clone();
+    let mut style = NodeStyle::of(&node);
+    update.item.apply_to_style(&mut style, target).ok()?;
+    style.apply_to(&mut node);
+    let lookup = |node_id: NodeId| {
+        if node_id == node.id {
+            Some(&node)
+        } else {
+            canvas.get_node(node_id)
+        }
+    };
+    build_thumbnail(update.node_id, THUMBNAIL_SIZE, &lookup)
+}
+
+impl EventEmitter<LibraryReviewEvent> for LibraryReview {}
+
+impl Focusable for LibraryReview {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LibraryReview {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx).clone();
+        let accepted_count = self.rows.iter().filter(|row| row.accepted).count();
+
+        let button = |id: &'static str, label: String| {
+            div()
+                .id(id)
+                .px_2()
+                .rounded(px(3.))
+                .bg(theme.tokens.surface1)
+                .hover(|this| this.bg(theme.tokens.surface2))
+                .child(label)
+        };
+
+        let header = div()
+            .flex()
+            .items_center()
+            .justify_between()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(theme.tokens.inactive_border)
+            .child(match self.rows.len() {
+                1 => "1 library update".to_string(),
+                count => format!("{count} library updates"),
+            })
+            .child(
+                div()
+                    .flex()
+                    .gap_1()
+                    .child(
+                        button("library-review-update-all", "Update All".into()).on_click(
+                            cx.listener(|this, _, _, cx| this.set_all_accepted(true, cx)),
+                        ),
+                    )
+                    .child(
+                        button("library-review-skip-all", "Skip All".into()).on_click(
+                            cx.listener(|this, _, _, cx| this.set_all_accepted(false, cx)),
+                        ),
+                    ),
+            );
+
+        let rows = (0..self.rows.len())
+            .map(|index| self.render_row(index, &theme, cx))
+            .collect::<Vec<_>>();
+
+        let footer = div()
+            .flex()
+            .justify_end()
+            .gap_1()
+            .px_2()
+            .py_1()
+            .border_t_1()
+            .border_color(theme.tokens.inactive_border)
+            .child(
+                button("library-review-cancel", "Cancel".into()).on_click(
+                    cx.listener(|this, _, window, cx| this.dismiss(&Dismiss, window, cx)),
+                ),
+            )
+            .child(
+                button(
+                    "library-review-confirm",
+                    match accepted_count {
+                        0 => "Skip".to_string(),
+                        1 => "Update 1 Layer".to_string(),
+                        count => format!("Update {count} Layers"),
+                    },
+                )
+                .on_click(cx.listener(|this, _, window, cx| this.confirm(&Confirm, window, cx))),
+            );
+
+        div()
+            .id("library-review-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .flex_col()
+            .items_center()
+            .pt(px(Titlebar::HEIGHT * 3.))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(LibraryReviewEvent::Dismissed)),
+            )
+            .child(
+                div()
+                    .id("library-review")
+                    .key_context("LibraryReview")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::dismiss))
+                    .on_action(cx.listener(Self::confirm))
+                    // Keep clicks inside the panel from reaching the backdrop
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(Self::WIDTH))
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .p_1()
+                    .text_size(px(12.))
+                    .text_color(theme.tokens.text)
+                    .bg(theme.tokens.background_secondary)
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .rounded(px(8.))
+                    .shadow_lg()
+                    .child(header)
+                    .child(
+                        div()
+                            .id("library-review-updates")
+                            .max_h(px(Self::MAX_HEIGHT))
+                            .overflow_y_scroll()
+                            .flex()
+                            .flex_col()
+                            .gap_1()
+                            .children(rows),
+                    )
+                    .child(footer),
+            )
+    }
+}
@@ -0,0 +1,177 @@
+//! Dialog for attaching a link to the selected nodes.
+//!
+//! The field starts out with the link the selection already shares, if any. Confirming
+//! an empty field removes the link, anything else is normalized into an address that
+//! can be opened when the node is clicked in preview.
+
+use gpui::{
+    actions, div, prelude::*, px, App, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
+    KeyDownEvent, MouseButton, Window,
+};
+
+use crate::{canvas::LunaCanvas, node::frame::normalize_link, theme::Theme};
+
+use super::Titlebar;
+
+actions!(link_editor, [Confirm, Dismiss, Paste]);
+
+pub enum LinkEditorEvent {
+    /// The link was confirmed, `None` when it was cleared
+    Confirmed(Option<String>),
+    Dismissed,
+}
+
+pub struct LinkEditor {
+    focus_handle: FocusHandle,
+    text: String,
+    node_count: usize,
+}
+
+impl LinkEditor {
+    const WIDTH: f32 = 420.;
+
+    pub fn new(canvas: &Entity<LunaCanvas>, cx: &mut Context<Self>) -> Self {
+        let canvas = canvas.read(cx);
+        let selection = canvas.top_level_selection();
+        let mut links = selection
+            .iter()
+            .map(|node_id| canvas.get_node(*node_id).and_then(|node| node.link.clone()));
+        let first = links.next().flatten();
+        let text = if links.all(|link| link == first) {
+            first.unwrap_or_default()
+        } else {
+            String::new()
+        };
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            text,
+            node_count: selection.len(),
+        }
+    }
+
+    /// Edit the address from raw key presses, like the batch rename fields
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.platform || keystroke.modifiers.control {
+            return;
+        }
+
+        if keystroke.key == "backspace" {
+            self.text.pop();
+        } else if let Some(text) = keystroke
+            .key_char
+            .as_ref()
+            .filter(|text| !text.chars().any(char::is_control))
+        {
+            self.text.push_str(text);
+        } else {
+            return;
+        }
+
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn paste(&mut self, _: &Paste, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            // Addresses are a single line, drop anything a copied snippet brought along
+            self.text.extend(text.chars().filter(|c| !c.is_control()));
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(LinkEditorEvent::Confirmed(normalize_link(&self.text)));
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(LinkEditorEvent::Dismissed);
+    }
+}
+
+impl EventEmitter<LinkEditorEvent> for LinkEditor {}
+
+impl Focusable for LinkEditor {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LinkEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+
+        div()
+            .id("link-editor-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .flex_col()
+            .items_center()
+            .pt(px(Titlebar::HEIGHT * 3.))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(LinkEditorEvent::Dismissed)),
+            )
+            .child(
+                div()
+                    .id("link-editor")
+                    .key_context("LinkEditor")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::confirm))
+                    .on_action(cx.listener(Self::dismiss))
+                    .on_action(cx.listener(Self::paste))
+                    .on_key_down(cx.listener(Self::handle_key_down))
+                    // Keep clicks inside the dialog from reaching the backdrop
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(Self::WIDTH))
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .p_1()
+                    .bg(theme.tokens.background_secondary)
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .rounded(px(8.))
+                    .shadow_lg()
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(theme.tokens.inactive_border)
+                            .child(match self.node_count {
+                                1 => "Link 1 layer".to_string(),
+                                count => format!("Link {count} layers"),
+                            }),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .px_2()
+                            .py_1()
+                            .rounded(px(4.))
+                            .bg(theme.tokens.surface0)
+                            .child(if self.text.is_empty() {
+                                div()
+                                    .text_color(theme.tokens.foreground_muted)
+                                    .child("https://, mailto: or leave empty to remove")
+                            } else {
+                                div().text_color(theme.tokens.text).child(self.text.clone())
+                            })
+                            .child(div().w(px(1.)).h(px(14.)).bg(theme.tokens.cursor)),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,268 @@
+//! List of the document's pages, above the layer list.
+//!
+//! Clicking a page puts it on the canvas, scrolled and zoomed the way it was left.
+//! Double-clicking one renames it in place: enter keeps the new name, escape the old
+//! one. The plus button adds an empty page after the last.
+//!
+//! In a view only document pages can still be switched between, but not added to or
+//! renamed.
+
+use gpui::{
+    actions, div, prelude::*, px, ClickEvent, ElementId, Entity, FocusHandle, IntoElement,
+    KeyDownEvent, SharedString, Subscription, Window,
+};
+
+use crate::{canvas::LunaCanvas, theme::Theme};
+
+actions!(page_list, [Confirm, Dismiss]);
+
+/// A page being renamed, and the name typed for it so far
+struct PageRename {
+    index: usize,
+    text: String,
+}
+
+pub struct PageList {
+    canvas: Entity<LunaCanvas>,
+    focus_handle: FocusHandle,
+    /// Where focus goes back to once a rename is confirmed or dismissed
+    return_focus: FocusHandle,
+    renaming: Option<PageRename>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl PageList {
+    pub fn new(
+        canvas: Entity<LunaCanvas>,
+        return_focus: FocusHandle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        let _subscriptions = vec![
+            // Clicking away from a page being renamed keeps what was typed for it
+            cx.on_blur(&focus_handle, window, |this, _, cx| this.commit_rename(cx)),
+            cx.observe(&canvas, |_, _, cx| cx.notify()),
+        ];
+
+        Self {
+            canvas,
+            focus_handle,
+            return_focus,
+            renaming: None,
+            _subscriptions,
+        }
+    }
+
+    /// Type a new name for the page at `index` in place of its current one
+    pub fn start_renaming(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.commit_rename(cx);
+        let Some(name) = self.canvas.read(cx).page_names().nth(index) else {
+            return;
+        };
+        self.renaming = Some(PageRename {
+            index,
+            text: name.to_string(),
+        });
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Give the page being renamed what was typed, an empty name keeps the old one
+    fn commit_rename(&mut self, cx: &mut Context<Self>) {
+        let Some(rename) = self.renaming.take() else {
+            return;
+        };
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.rename_page(rename.index, &rename.text, cx)
+        });
+        cx.notify();
+    }
+
+    fn show_page(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.commit_rename(cx);
+        self.canvas
+            .update(cx, |canvas, cx| canvas.show_page(index, cx));
+    }
+
+    /// Type into the name being edited from raw key presses, like the inspector fields
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(rename) = &mut self.renaming else {
+            return;
+        };
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.platform || keystroke.modifiers.control {
+            return;
+        }
+
+        if keystroke.key == "backspace" {
+            rename.text.pop();
+        } else if let Some(text) = keystroke
+            .key_char
+            .as_ref()
+            .filter(|text| !text.chars().any(char::is_control))
+        {
+            rename.text.push_str(text);
+        } else {
+            return;
+        }
+
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.commit_rename(cx);
+        window.focus(&self.return_focus);
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, window: &mut Window, cx: &mut Context<Self>) {
+        self.renaming = None;
+        window.focus(&self.return_focus);
+        cx.notify();
+    }
+}
+
+impl Render for PageList {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+        let canvas = self.canvas.read(cx);
+        let editable = !canvas.is_view_only(cx);
+        let current = canvas.current_page();
+        let names: Vec<SharedString> = canvas
+            .page_names()
+            .map(|name| SharedString::from(name.to_string()))
+            .collect();
+
+        let header = div()
+            .flex()
+            .items_center()
+            .justify_between()
+            .px_2()
+            .py_1()
+            .text_xs()
+            .text_color(theme.tokens.subtext0)
+            .child("Pages")
+            .when(editable, |div| {
+                div.child(
+                    gpui::div()
+                        .id("add-page")
+                        .px_1()
+                        .rounded(px(4.))
+                        .hover(|style| style.text_color(theme.tokens.text))
+                        .child("+")
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.commit_rename(cx);
+                            this.canvas.update(cx, |canvas, cx| canvas.add_page(cx));
+                        })),
+                )
+            });
+
+        let mut list = div()
+            .id("page-list")
+            .key_context("PageList")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::dismiss))
+            .on_key_down(cx.listener(Self::handle_key_down))
+            .flex()
+            .flex_col()
+            .pb_1()
+            .border_b_1()
+            .border_color(theme.tokens.inactive_border)
+            .child(header);
+
+        for (index, name) in names.into_iter().enumerate() {
+            let renaming = self
+                .renaming
+                .as_ref()
+                .filter(|rename| rename.index == index);
+            let label: SharedString = match renaming {
+                Some(rename) => format!("{}|", rename.text).into(),
+                None => name,
+            };
+            let selected = index == current;
+
+            list = list.child(
+                div()
+                    .id(ElementId::Name(format!("page-{index}").into()))
+                    .mx_1()
+                    .px_2()
+                    .py(px(2.))
+                    .rounded(px(4.))
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_xs()
+                    .text_color(if selected {
+                        theme.tokens.text
+                    } else {
+                        theme.tokens.subtext0
+                    })
+                    .when(selected, |div| div.bg(theme.tokens.selected))
+                    .when(renaming.is_some(), |div| {
+                        div.border_1().border_color(theme.tokens.active_border)
+                    })
+                    .active(|div| div.bg(theme.tokens.surface2.opacity(0.7)))
+                    .child(label)
+                    .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                        if editable && event.down.click_count == 2 {
+                            this.start_renaming(index, window, cx);
+                        } else {
+                            this.show_page(index, cx);
+                        }
+                    })),
+            );
+        }
+
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        init_globals, keymap::init_keymap, node::frame::FrameNode, power,
+        window_state::WindowState, Luna,
+    };
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    fn test_typing_a_page_name_keeps_keys_from_the_canvas(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            init_keymap(cx);
+            init_globals(cx);
+            power::init(Default::default(), cx);
+        });
+        let (luna, cx) =
+            cx.add_window_view(|window, cx| Luna::new(WindowState::default(), window, cx));
+        let (canvas, sidebar) =
+            luna.read_with(cx, |luna, _| (luna.canvas.clone(), luna.sidebar.clone()));
+        let page_list = sidebar.read_with(cx, |sidebar, _| sidebar.page_list().clone());
+
+        let node_id = canvas.update(cx, |canvas, cx| {
+            let node_id = canvas.generate_id();
+            let node = FrameNode::with_rect(node_id, 0.0, 0.0, 100.0, 100.0);
+            canvas.add_node(node, None, cx);
+            canvas.select_node(node_id);
+            node_id
+        });
+        cx.update(|window, cx| {
+            page_list.update(cx, |page_list, cx| page_list.start_renaming(0, window, cx))
+        });
+
+        // Backspace deletes the selection on the canvas and `h` and `r` pick tools
+        cx.simulate_keystrokes("backspace backspace backspace backspace backspace backspace");
+        cx.simulate_keystrokes("h e r o enter");
+
+        canvas.read_with(cx, |canvas, _| {
+            assert!(canvas.get_node(node_id).is_some());
+            assert_eq!(canvas.page_names().next(), Some("hero"));
+        });
+    }
+}
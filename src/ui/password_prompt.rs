@@ -0,0 +1,223 @@
+//! Dialog asking for a document's password.
+//!
+//! It's shown when a protected document is opened and when a password is set. A new
+//! password is asked for twice, since a typo in it would lock the document for good.
+//! Typed characters are only ever shown as dots.
+
+use std::path::PathBuf;
+
+use gpui::{
+    actions, div, prelude::*, px, App, EventEmitter, FocusHandle, Focusable, IntoElement,
+    KeyDownEvent, MouseButton, SharedString, Window,
+};
+
+use crate::theme::Theme;
+
+use super::Titlebar;
+
+actions!(password_prompt, [Confirm, Dismiss, Paste]);
+
+/// What the password is asked for
+#[derive(Debug, Clone, PartialEq)]
+pub enum PasswordPurpose {
+    /// Opening the protected document at the path
+    Open(PathBuf),
+    /// Choosing a new password, `protected` when the document already has one
+    Protect { protected: bool },
+    /// Repeating the new password that was just entered
+    Repeat(String),
+}
+
+pub enum PasswordPromptEvent {
+    Confirmed(String),
+    Dismissed,
+}
+
+pub struct PasswordPrompt {
+    focus_handle: FocusHandle,
+    purpose: PasswordPurpose,
+    text: String,
+    /// Why the previous attempt didn't work
+    error: Option<SharedString>,
+}
+
+impl PasswordPrompt {
+    const WIDTH: f32 = 420.;
+
+    pub fn new(
+        purpose: PasswordPurpose,
+        error: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            purpose,
+            text: String::new(),
+            error,
+        }
+    }
+
+    pub fn purpose(&self) -> &PasswordPurpose {
+        &self.purpose
+    }
+
+    fn title(&self) -> String {
+        match &self.purpose {
+            PasswordPurpose::Open(path) => {
+                let name = path
+                    .file_stem()
+                    .map_or("Untitled".into(), |name| name.to_string_lossy());
+                format!("“{name}” is password protected")
+            }
+            PasswordPurpose::Protect { protected: false } => "Protect with a password".into(),
+            PasswordPurpose::Protect { protected: true } => "Change the password".into(),
+            PasswordPurpose::Repeat(_) => "Enter the password again".into(),
+        }
+    }
+
+    fn placeholder(&self) -> &'static str {
+        match self.purpose {
+            PasswordPurpose::Protect { protected: true } => {
+                "New password, or leave empty to remove it"
+            }
+            _ => "Password",
+        }
+    }
+
+    /// Edit the password from raw key presses, like the link editor
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.platform || keystroke.modifiers.control {
+            return;
+        }
+
+        if keystroke.key == "backspace" {
+            self.text.pop();
+        } else if let Some(text) = keystroke
+            .key_char
+            .as_ref()
+            .filter(|text| !text.chars().any(char::is_control))
+        {
+            self.text.push_str(text);
+        } else {
+            return;
+        }
+
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn paste(&mut self, _: &Paste, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            // A password copied from a manager may end in a newline
+            self.text.extend(text.chars().filter(|c| !c.is_control()));
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        // Only a password being changed can be left empty, which removes it
+        let removing = self.purpose == PasswordPurpose::Protect { protected: true };
+        if self.text.is_empty() && !removing {
+            return;
+        }
+        cx.emit(PasswordPromptEvent::Confirmed(self.text.clone()));
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(PasswordPromptEvent::Dismissed);
+    }
+}
+
+impl EventEmitter<PasswordPromptEvent> for PasswordPrompt {}
+
+impl Focusable for PasswordPrompt {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for PasswordPrompt {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+
+        div()
+            .id("password-prompt-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .flex_col()
+            .items_center()
+            .pt(px(Titlebar::HEIGHT * 3.))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(PasswordPromptEvent::Dismissed)),
+            )
+            .child(
+                div()
+                    .id("password-prompt")
+                    .key_context("PasswordPrompt")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::confirm))
+                    .on_action(cx.listener(Self::dismiss))
+                    .on_action(cx.listener(Self::paste))
+                    .on_key_down(cx.listener(Self::handle_key_down))
+                    // Keep clicks inside the dialog from reaching the backdrop
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(Self::WIDTH))
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .p_1()
+                    .bg(theme.tokens.background_secondary)
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .rounded(px(8.))
+                    .shadow_lg()
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(theme.tokens.inactive_border)
+                            .child(self.title()),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .px_2()
+                            .py_1()
+                            .rounded(px(4.))
+                            .bg(theme.tokens.surface0)
+                            .child(if self.text.is_empty() {
+                                div()
+                                    .text_color(theme.tokens.foreground_muted)
+                                    .child(self.placeholder())
+                            } else {
+                                div()
+                                    .text_color(theme.tokens.text)
+                                    .child("•".repeat(self.text.chars().count()))
+                            })
+                            .child(div().w(px(1.)).h(px(14.)).bg(theme.tokens.cursor)),
+                    )
+                    .when_some(self.error.clone(), |div, error| {
+                        div.child(
+                            gpui::div()
+                                .px_2()
+                                .text_color(theme.tokens.error)
+                                .child(error),
+                        )
+                    }),
+            )
+    }
+}
@@ -2,19 +2,18 @@
 //!
 //! Provides reusable UI components for displaying and editing
 //! element properties, with support for mixed-value states.
+//!
+//! Fields don't edit text themselves. The inspector owns the text being typed and
+//! hands it to the field it belongs to, which shows it in place of the value.
 
 use std::str::FromStr;
 
 use gpui::{
-    div, prelude::*, px, Context, Entity, Hsla, IntoElement, ParentElement, Render, Rgba,
-    SharedString, Styled, Window,
+    div, prelude::*, px, App, ClickEvent, ClipboardItem, Context, ElementId, Entity, Hsla,
+    IntoElement, ParentElement, Render, Rgba, SharedString, Styled, Window,
 };
 
-use crate::{
-    canvas::LunaCanvas,
-    theme::{ActiveTheme, Theme},
-    AppState,
-};
+use crate::{canvas::LunaCanvas, theme::ActiveTheme, AppState};
 
 /// Creates a new property input field with the given value and icon
 pub fn float_input(value: Option<Vec<f32>>, icon: impl Into<SharedString>) -> PropertyInput {
@@ -31,6 +30,10 @@ pub fn float_input(value: Option<Vec<f32>>, icon: impl Into<SharedString>) -> Pr
 pub struct PropertyInput {
     value: Option<Vec<f32>>,
     icon: SharedString,
+    /// Text being typed into the field, shown instead of the value
+    editing: Option<SharedString>,
+    on_click: Option<Box<dyn Fn(&ClickEvent, &mut Window, &mut App)>>,
+    on_hover: Option<Box<dyn Fn(&bool, &mut Window, &mut App)>>,
 }
 
 impl PropertyInput {
@@ -38,13 +41,35 @@ impl PropertyInput {
         Self {
             value,
             icon: icon.into(),
+            editing: None,
+            on_click: None,
+            on_hover: None,
         }
     }
+
+    pub fn editing(mut self, text: Option<SharedString>) -> Self {
+        self.editing = text;
+        self
+    }
+
+    pub fn on_click(
+        mut self,
+        handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_click = Some(Box::new(handler));
+        self
+    }
+
+    /// Called with true when the pointer enters the field and false when it leaves
+    pub fn on_hover(mut self, handler: impl Fn(&bool, &mut Window, &mut App) + 'static) -> Self {
+        self.on_hover = Some(Box::new(handler));
+        self
+    }
 }
 
 impl RenderOnce for PropertyInput {
     fn render(self, window: &mut Window, cx: &mut gpui::App) -> impl IntoElement {
-        let theme = Theme::default();
+        let theme = cx.theme();
 
         // Convert Option<Vec<f32>> to display string
         let display_value = match &self.value {
@@ -56,9 +81,17 @@ impl RenderOnce for PropertyInput {
 
         let no_value = display_value.is_empty();
         let mixed = display_value == "Mixed";
+        let editing = self.editing.is_some();
+        let display_value = match self.editing {
+            Some(text) => SharedString::from(format!("{text}|")),
+            None => display_value,
+        };
 
         div().flex().flex_row().child(
             div()
+                .id(ElementId::Name(format!("float-input-{}", self.icon).into()))
+                .when_some(self.on_click, |this, on_click| this.on_click(on_click))
+                .when_some(self.on_hover, |this, on_hover| this.on_hover(on_hover))
                 .flex()
                 .items_center()
                 .flex_none()
@@ -66,9 +99,15 @@ impl RenderOnce for PropertyInput {
                 .pr(px(4.))
                 .w(px(84.))
                 .rounded(px(4.))
+                .border_1()
+                .border_color(if editing {
+                    theme.tokens.active_border
+                } else {
+                    gpui::transparent_black()
+                })
                 .bg(theme.tokens.surface0)
                 .text_color(theme.tokens.text)
-                .when(no_value || mixed, |this| {
+                .when((no_value || mixed) && !editing, |this| {
                     this.text_color(theme.tokens.text.alpha(0.5))
                 })
                 .text_size(px(11.))
@@ -87,11 +126,21 @@ impl RenderOnce for PropertyInput {
     }
 }
 
+/// Color field showing a swatch and the color as text
+///
+/// The value is displayed as given, so it should already be in the document's color
+/// format. Clicking the field copies that text, unless the field has its own click
+/// handler.
 #[derive(IntoElement)]
 pub struct ColorInput {
     value: Option<SharedString>,
     opacity: Option<f32>,
     icon: SharedString,
+    /// Text being typed into the field, shown instead of the value
+    editing: Option<SharedString>,
+    on_click: Option<Box<dyn Fn(&ClickEvent, &mut Window, &mut App)>>,
+    on_swatch_click: Option<Box<dyn Fn(&ClickEvent, &mut Window, &mut App)>>,
+    on_hover: Option<Box<dyn Fn(&bool, &mut Window, &mut App)>>,
 }
 
 impl ColorInput {
@@ -100,9 +149,41 @@ impl ColorInput {
             value,
             opacity: None,
             icon,
+            editing: None,
+            on_click: None,
+            on_swatch_click: None,
+            on_hover: None,
         }
     }
 
+    pub fn editing(mut self, text: Option<SharedString>) -> Self {
+        self.editing = text;
+        self
+    }
+
+    pub fn on_click(
+        mut self,
+        handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_click = Some(Box::new(handler));
+        self
+    }
+
+    /// Called when the swatch is clicked, in place of the field's own click handler
+    pub fn on_swatch_click(
+        mut self,
+        handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_swatch_click = Some(Box::new(handler));
+        self
+    }
+
+    /// Called with true when the pointer enters the field and false when it leaves
+    pub fn on_hover(mut self, handler: impl Fn(&bool, &mut Window, &mut App) + 'static) -> Self {
+        self.on_hover = Some(Box::new(handler));
+        self
+    }
+
     pub fn parse_color(&self) -> Option<Hsla> {
         if let Some(color_str) = &self.value {
             crate::color::parse_color(color_str)
@@ -117,13 +198,32 @@ impl RenderOnce for ColorInput {
         let theme = cx.theme();
 
         let parsed_color = self.parse_color();
-        let mut display_value = String::new();
-        if let Some(color) = parsed_color {
-            display_value = color.to_string();
+        let display_value = self
+            .value
+            .clone()
+            .filter(|_| parsed_color.is_some())
+            .unwrap_or_default();
+        let editing = self.editing.is_some();
+        // The swatch keeps showing the current color while a new one is typed
+        let display_value = match self.editing {
+            Some(text) => SharedString::from(format!("{text}|")),
+            None => display_value,
         };
 
         div().flex().flex_row().child(
             div()
+                .id(ElementId::Name(format!("color-input-{}", self.icon).into()))
+                .map(|this| match self.on_click {
+                    Some(on_click) => this.on_click(on_click),
+                    None if !display_value.is_empty() => {
+                        let value = display_value.clone();
+                        this.on_click(move |_, _, cx| {
+                            cx.write_to_clipboard(ClipboardItem::new_string(value.to_string()))
+                        })
+                    }
+                    None => this,
+                })
+                .when_some(self.on_hover, |this, on_hover| this.on_hover(on_hover))
                 .flex()
                 .items_center()
                 .flex_none()
@@ -131,17 +231,32 @@ impl RenderOnce for ColorInput {
                 .pr(px(4.))
                 .w_full()
                 .rounded(px(4.))
+                .border_1()
+                .border_color(if editing {
+                    theme.tokens.active_border
+                } else {
+                    gpui::transparent_black()
+                })
                 .bg(theme.tokens.surface0)
                 .text_color(theme.tokens.text)
                 .text_size(px(11.))
                 .when_some(parsed_color, |this, color| {
                     this.child(
                         div()
+                            .id(ElementId::Name(
+                                format!("color-swatch-{}", self.icon).into(),
+                            ))
                             .size(px(9.))
                             .border_color(cx.theme().tokens.inactive_border)
                             .border_1()
                             .rounded(px(2.))
-                            .bg(color),
+                            .bg(color)
+                            .when_some(self.on_swatch_click, |this, on_swatch_click| {
+                                this.on_click(move |event, window, cx| {
+                                    cx.stop_propagation();
+                                    on_swatch_click(event, window, cx);
+                                })
+                            }),
                     )
                 })
                 .when(!display_value.is_empty(), |this| {
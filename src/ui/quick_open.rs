@@ -0,0 +1,287 @@
+//! Quick-open finder for jumping to nodes by name.
+//!
+//! The candidate list is a snapshot of the canvas taken when the finder opens, so
+//! results don't shift under the cursor while typing. Picking a result only emits an
+//! event, the owner decides what selecting and revealing a node means.
+
+use gpui::{
+    actions, div, prelude::*, px, App, ElementId, Entity, EventEmitter, FocusHandle, Focusable,
+    Hsla, IntoElement, KeyDownEvent, MouseButton, SharedString, Window,
+};
+
+use crate::{
+    canvas::LunaCanvas,
+    fuzzy::{self, FuzzyMatch},
+    node::{NodeCommon, NodeId},
+    theme::Theme,
+};
+
+use super::Titlebar;
+
+actions!(quick_open, [Confirm, Dismiss, SelectNext, SelectPrevious]);
+
+pub enum QuickOpenEvent {
+    /// A node was picked
+    Confirmed(NodeId),
+    /// The finder was closed without picking anything
+    Dismissed,
+}
+
+struct Candidate {
+    node_id: NodeId,
+    name: SharedString,
+}
+
+pub struct QuickOpen {
+    focus_handle: FocusHandle,
+    candidates: Vec<Candidate>,
+    query: String,
+    matches: Vec<FuzzyMatch>,
+    selected_index: usize,
+}
+
+impl QuickOpen {
+    const WIDTH: f32 = 420.;
+    /// Results past this are dropped, large documents are narrowed down by typing
+    const MAX_RESULTS: usize = 12;
+
+    pub fn new(canvas: &Entity<LunaCanvas>, cx: &mut Context<Self>) -> Self {
+        let candidates = canvas
+            .read(cx)
+            .nodes()
+            .iter()
+            .map(|node| Candidate {
+                node_id: node.id(),
+                name: node.name().into(),
+            })
+            .collect();
+
+        let mut quick_open = Self {
+            focus_handle: cx.focus_handle(),
+            candidates,
+            query: String::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        quick_open.update_matches();
+        quick_open
+    }
+
+    fn update_matches(&mut self) {
+        let names = self
+            .candidates
+            .iter()
+            .map(|candidate| candidate.name.as_ref());
+        self.matches = fuzzy::match_candidates(&self.query, names);
+        self.matches.truncate(Self::MAX_RESULTS);
+        self.selected_index = 0;
+    }
+
+    /// Edit the query from raw key presses
+    ///
+    /// Editor shortcuts are bound to bare letters, but their handlers live on the
+    /// workspace, which isn't an ancestor of the finder. Those actions go unhandled and
+    /// the keystroke falls through to here as text.
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.platform || keystroke.modifiers.control {
+            return;
+        }
+
+        if keystroke.key == "backspace" {
+            self.query.pop();
+        } else if let Some(text) = keystroke
+            .key_char
+            .as_ref()
+            .filter(|text| !text.chars().any(char::is_control))
+        {
+            self.query.push_str(text);
+        } else {
+            return;
+        }
+
+        self.update_matches();
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        if !self.matches.is_empty() {
+            self.selected_index = (self.selected_index + 1) % self.matches.len();
+            cx.notify();
+        }
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &SelectPrevious,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.matches.is_empty() {
+            self.selected_index = self
+                .selected_index
+                .checked_sub(1)
+                .unwrap_or(self.matches.len() - 1);
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        self.confirm_match(self.selected_index, cx);
+    }
+
+    fn confirm_match(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(fuzzy_match) = self.matches.get(index) {
+            let node_id = self.candidates[fuzzy_match.candidate_index].node_id;
+            cx.emit(QuickOpenEvent::Confirmed(node_id));
+        }
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(QuickOpenEvent::Dismissed);
+    }
+}
+
+impl EventEmitter<QuickOpenEvent> for QuickOpen {}
+
+impl Focusable for QuickOpen {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for QuickOpen {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+
+        let query = if self.query.is_empty() {
+            div()
+                .text_color(theme.tokens.foreground_muted)
+                .child("Go to node…")
+        } else {
+            div()
+                .text_color(theme.tokens.text)
+                .child(self.query.clone())
+        };
+
+        let results = self
+            .matches
+            .iter()
+            .enumerate()
+            .map(|(index, fuzzy_match)| {
+                let candidate = &self.candidates[fuzzy_match.candidate_index];
+                div()
+                    .id(ElementId::Name(
+                        format!("quick-open-{}", candidate.node_id.0).into(),
+                    ))
+                    .px_2()
+                    .py_1()
+                    .rounded(px(4.))
+                    .when(index == self.selected_index, |div| {
+                        div.bg(theme.tokens.selected)
+                    })
+                    .on_click(cx.listener(move |this, _, _, cx| this.confirm_match(index, cx)))
+                    .child(highlighted_name(
+                        &candidate.name,
+                        &fuzzy_match.positions,
+                        theme.tokens.text,
+                        theme.tokens.subtext0,
+                    ))
+            })
+            .collect::<Vec<_>>();
+
+        div()
+            .id("quick-open-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .flex_col()
+            .items_center()
+            .pt(px(Titlebar::HEIGHT * 3.))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(QuickOpenEvent::Dismissed)),
+            )
+            .child(
+                div()
+                    .id("quick-open")
+                    .key_context("QuickOpen")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::confirm))
+                    .on_action(cx.listener(Self::dismiss))
+                    .on_action(cx.listener(Self::select_next))
+                    .on_action(cx.listener(Self::select_previous))
+                    .on_key_down(cx.listener(Self::handle_key_down))
+                    // Keep clicks inside the finder from reaching the backdrop
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(Self::WIDTH))
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .p_1()
+                    .bg(theme.tokens.background_secondary)
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .rounded(px(8.))
+                    .shadow_lg()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .px_2()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(theme.tokens.inactive_border)
+                            .child(query)
+                            .child(div().w(px(1.)).h(px(14.)).bg(theme.tokens.cursor)),
+                    )
+                    .children(results)
+                    .when(self.matches.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .px_2()
+                                .py_1()
+                                .text_color(theme.tokens.foreground_muted)
+                                .child("No matching nodes"),
+                        )
+                    }),
+            )
+    }
+}
+
+/// Render a name with the characters the query matched emphasized
+fn highlighted_name(
+    name: &str,
+    positions: &[usize],
+    matched_color: Hsla,
+    unmatched_color: Hsla,
+) -> impl IntoElement {
+    let mut runs: Vec<(bool, String)> = Vec::new();
+    for (index, c) in name.chars().enumerate() {
+        let is_match = positions.contains(&index);
+        match runs.last_mut() {
+            Some((run_is_match, text)) if *run_is_match == is_match => text.push(c),
+            _ => runs.push((is_match, c.to_string())),
+        }
+    }
+
+    div()
+        .flex()
+        .children(runs.into_iter().map(|(is_match, text)| {
+            let color = if is_match {
+                matched_color
+            } else {
+                unmatched_color
+            };
+            div().text_color(color).child(text)
+        }))
+}
@@ -0,0 +1,166 @@
+//! Keyboard shortcut cheat sheet.
+//!
+//! The sheet is built from the keymap registered with GPUI when it opens rather than
+//! from a list of its own, so a binding added or changed in `keymap.rs` shows up here
+//! without anything else to update.
+
+use gpui::{
+    actions, div, prelude::*, px, App, EventEmitter, FocusHandle, Focusable, IntoElement,
+    MouseButton, SharedString, Window,
+};
+
+use crate::{
+    theme::Theme,
+    util::{format_keystroke, humanize_action_name},
+};
+
+use super::Titlebar;
+
+actions!(shortcuts, [Dismiss]);
+
+pub enum ShortcutsEvent {
+    Dismissed,
+}
+
+/// Bindings sharing a key context
+struct ShortcutGroup {
+    title: SharedString,
+    /// Each action with every key sequence bound to it
+    shortcuts: Vec<(SharedString, Vec<String>)>,
+}
+
+pub struct Shortcuts {
+    focus_handle: FocusHandle,
+    groups: Vec<ShortcutGroup>,
+}
+
+impl Shortcuts {
+    const WIDTH: f32 = 600.;
+    const MAX_HEIGHT: f32 = 520.;
+
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let mut groups: Vec<ShortcutGroup> = Vec::new();
+        let keymap = cx.key_bindings();
+        for binding in keymap.borrow().bindings() {
+            // Bindings without a context are handled by the workspace around the canvas
+            let title: SharedString = binding
+                .predicate()
+                .map(|predicate| humanize_action_name(&predicate.to_string()))
+                .unwrap_or_else(|| "Canvas".to_string())
+                .into();
+            let action = humanize_action_name(binding.action().name()).into();
+            let keys = binding
+                .keystrokes()
+                .iter()
+                .map(format_keystroke)
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let group = match groups.iter().position(|group| group.title == title) {
+                Some(index) => &mut groups[index],
+                None => {
+                    groups.push(ShortcutGroup {
+                        title,
+                        shortcuts: Vec::new(),
+                    });
+                    groups.last_mut().unwrap()
+                }
+            };
+            match group.shortcuts.iter_mut().find(|(name, _)| *name == action) {
+                Some((_, bound_keys)) if !bound_keys.contains(&keys) => bound_keys.push(keys),
+                Some(_) => {}
+                None => group.shortcuts.push((action, vec![keys])),
+            }
+        }
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            groups,
+        }
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(ShortcutsEvent::Dismissed);
+    }
+}
+
+impl EventEmitter<ShortcutsEvent> for Shortcuts {}
+
+impl Focusable for Shortcuts {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for Shortcuts {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+
+        let groups = self.groups.iter().map(|group| {
+            div()
+                .w(px(Self::WIDTH / 2. - 16.))
+                .flex()
+                .flex_col()
+                .gap(px(2.))
+                .child(
+                    div()
+                        .pb_1()
+                        .text_size(px(10.))
+                        .text_color(theme.tokens.subtext0)
+                        .child(group.title.clone()),
+                )
+                .children(group.shortcuts.iter().map(|(action, keys)| {
+                    div()
+                        .flex()
+                        .justify_between()
+                        .gap_2()
+                        .child(action.clone())
+                        .child(
+                            div()
+                                .text_color(theme.tokens.subtext0)
+                                .child(keys.join(", ")),
+                        )
+                }))
+        });
+
+        div()
+            .id("shortcuts-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .flex_col()
+            .items_center()
+            .pt(px(Titlebar::HEIGHT * 3.))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(ShortcutsEvent::Dismissed)),
+            )
+            .child(
+                div()
+                    .id("shortcuts")
+                    .key_context("Shortcuts")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::dismiss))
+                    // Keep clicks inside the sheet from reaching the backdrop
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(Self::WIDTH))
+                    .max_h(px(Self::MAX_HEIGHT))
+                    .overflow_y_scroll()
+                    .flex()
+                    .flex_wrap()
+                    .gap_4()
+                    .p_3()
+                    .text_size(px(12.))
+                    .text_color(theme.tokens.text)
+                    .bg(theme.tokens.background_secondary)
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .rounded(px(8.))
+                    .shadow_lg()
+                    .children(groups),
+            )
+    }
+}
@@ -7,11 +7,11 @@ use crate::{canvas::LunaCanvas, theme::Theme, tools::ToolStrip};
 use gpui::{
     actions, div, hsla, point, prelude::*, px, svg, App, Application, AssetSource, BoxShadow,
     ElementId, Entity, FocusHandle, Focusable, Global, Hsla, IntoElement, Keystroke, Menu,
-    MenuItem, Modifiers, Pixels, Point, SharedString, TitlebarOptions, UpdateGlobal, WeakEntity,
-    Window, WindowBackgroundAppearance, WindowOptions,
+    MenuItem, Modifiers, Pixels, Point, SharedString, Subscription, TitlebarOptions, UpdateGlobal,
+    WeakEntity, Window, WindowBackgroundAppearance, WindowOptions,
 };
 
-use super::{layer_list::LayerList, Titlebar};
+use super::{layer_list::LayerList, page_list::PageList, Titlebar};
 
 /// Container for tool selection and other canvas controls
 ///
@@ -20,39 +20,126 @@ use super::{layer_list::LayerList, Titlebar};
 pub struct Sidebar {
     canvas: Entity<LunaCanvas>,
     layer_list: Entity<LayerList>,
+    page_list: Entity<PageList>,
+    width: f32,
+    /// Whether the layer list is currently shown in its own window instead
+    layers_detached: bool,
+    /// Keeps the document title and its edited marker current
+    _observe_canvas: Subscription,
 }
 
 impl Sidebar {
-    pub fn new(canvas: Entity<LunaCanvas>, cx: &mut Context<Self>) -> Self {
+    pub fn new(
+        canvas: Entity<LunaCanvas>,
+        return_focus: FocusHandle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
         let layer_list = cx.new(|cx| LayerList::new(canvas.clone(), cx));
-        Self { canvas, layer_list }
+        let page_list = cx.new(|cx| PageList::new(canvas.clone(), return_focus, window, cx));
+        let _observe_canvas = cx.observe(&canvas, |_, _, cx| cx.notify());
+        Self {
+            canvas,
+            layer_list,
+            page_list,
+            width: Self::INITIAL_WIDTH,
+            layers_detached: false,
+            _observe_canvas,
+        }
+    }
+
+    pub fn layer_list(&self) -> &Entity<LayerList> {
+        &self.layer_list
+    }
+
+    pub fn page_list(&self) -> &Entity<PageList> {
+        &self.page_list
+    }
+
+    pub fn set_layers_detached(&mut self, detached: bool, cx: &mut Context<Self>) {
+        self.layers_detached = detached;
+        cx.notify();
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn set_width(&mut self, width: f32, cx: &mut Context<Self>) {
+        self.width = width;
+        cx.notify();
     }
 }
 
 impl Sidebar {
     pub const INITIAL_WIDTH: f32 = 220.;
+    /// Room left for the window controls at the start of the titlebar
+    const TRAFFIC_LIGHTS_WIDTH: f32 = 72.;
 }
 
 impl Render for Sidebar {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let token = &Theme::get_global(cx).tokens;
 
+        let canvas = self.canvas.read(cx);
+        let view_only = canvas.is_view_only(cx);
+        let title: SharedString = canvas
+            .document_path()
+            .and_then(|path| path.file_stem())
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| "Untitled".into());
+        let titlebar = div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .w_full()
+            .h(px(Titlebar::HEIGHT))
+            .pl(px(Self::TRAFFIC_LIGHTS_WIDTH))
+            .pr_2()
+            .overflow_hidden()
+            .whitespace_nowrap()
+            .text_xs()
+            .text_color(token.text)
+            .child(title)
+            .when(canvas.is_edited(), |this| {
+                this.child(div().text_color(token.foreground_muted).child("— Edited"))
+            })
+            .when(view_only, |this| {
+                this.child(
+                    div()
+                        .text_color(token.foreground_muted)
+                        .child("— View Only"),
+                )
+            });
+
         let inner = div()
             .id("sidebar-inner")
             .flex()
             .flex_col()
             .h_full()
-            .w(px(Self::INITIAL_WIDTH))
+            .w(px(self.width))
             .rounded_tl(px(15.))
             .rounded_bl(px(15.))
-            .child(div().w_full().h(px(Titlebar::HEIGHT)))
+            .child(titlebar)
             .child(
                 div()
                     .flex()
                     .flex_1()
                     .w_full()
-                    .child(ToolStrip::new())
-                    .child(self.layer_list.clone()),
+                    // There's nothing to draw or color in a view only document
+                    .when(!view_only, |div| {
+                        div.child(ToolStrip::new(self.canvas.read(cx).app_state().clone()))
+                    })
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .flex_1()
+                            .child(self.page_list.clone())
+                            .when(!self.layers_detached, |div| {
+                                div.child(self.layer_list.clone())
+                            }),
+                    ),
             );
 
         div()
@@ -62,7 +149,7 @@ impl Render for Sidebar {
             .top_0()
             .left_0()
             .h_full()
-            .w(px(Self::INITIAL_WIDTH + 1.))
+            .w(px(self.width + 1.))
             .bg(token.background_secondary)
             .border_r_1()
             .border_color(token.inactive_border)
@@ -0,0 +1,247 @@
+//! Template gallery for starting a new document from one of the built-in templates.
+//!
+//! Each card shows a thumbnail drawn from the template's own frames, so the preview is
+//! exactly what choosing it will put on the canvas.
+
+use gpui::{
+    actions, div, prelude::*, px, App, Bounds, ElementId, EventEmitter, FocusHandle, Focusable,
+    IntoElement, MouseButton, Window,
+};
+
+use crate::{
+    node::{frame::FrameNode, NodeCommon},
+    template::{absolute_bounds, Template},
+    theme::Theme,
+};
+
+use super::Titlebar;
+
+actions!(
+    template_gallery,
+    [Confirm, Dismiss, SelectNext, SelectPrevious]
+);
+
+pub enum TemplateGalleryEvent {
+    Confirmed(Template),
+    Dismissed,
+}
+
+/// A template's frames with their canvas bounds, for drawing its thumbnail
+struct Preview {
+    template: Template,
+    frames: Vec<(FrameNode, Bounds<f32>)>,
+    content: Bounds<f32>,
+}
+
+pub struct TemplateGallery {
+    focus_handle: FocusHandle,
+    previews: Vec<Preview>,
+    selected_index: usize,
+    /// Whether the gallery was opened at first launch rather than from the menu
+    onboarding: bool,
+}
+
+impl TemplateGallery {
+    const THUMBNAIL_WIDTH: f32 = 200.;
+    const THUMBNAIL_HEIGHT: f32 = 140.;
+
+    pub fn new(onboarding: bool, cx: &mut Context<Self>) -> Self {
+        let previews = Template::ALL
+            .into_iter()
+            .map(|template| {
+                let frames = template.build();
+                let bounds = absolute_bounds(&frames);
+                let content = bounds
+                    .iter()
+                    .copied()
+                    .reduce(|content, bounds| content.union(&bounds))
+                    .unwrap_or_default();
+                Preview {
+                    template,
+                    frames: frames.into_iter().zip(bounds).collect(),
+                    content,
+                }
+            })
+            .collect();
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            previews,
+            selected_index: 0,
+            onboarding,
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_index = (self.selected_index + 1) % self.previews.len();
+        cx.notify();
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &SelectPrevious,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.selected_index = (self.selected_index + self.previews.len() - 1) % self.previews.len();
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        let template = self.previews[self.selected_index].template;
+        cx.emit(TemplateGalleryEvent::Confirmed(template));
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(TemplateGalleryEvent::Dismissed);
+    }
+
+    /// Draw the frames scaled down to fit the thumbnail, centered
+    fn render_thumbnail(preview: &Preview) -> impl IntoElement {
+        let content = preview.content;
+        let scale = (Self::THUMBNAIL_WIDTH / content.size.width)
+            .min(Self::THUMBNAIL_HEIGHT / content.size.height);
+        let offset_x = (Self::THUMBNAIL_WIDTH - content.size.width * scale) / 2.;
+        let offset_y = (Self::THUMBNAIL_HEIGHT - content.size.height * scale) / 2.;
+
+        div()
+            .relative()
+            .w(px(Self::THUMBNAIL_WIDTH))
+            .h(px(Self::THUMBNAIL_HEIGHT))
+            .overflow_hidden()
+            .children(preview.frames.iter().map(|(frame, bounds)| {
+                div()
+                    .absolute()
+                    .left(px(offset_x + (bounds.origin.x - content.origin.x) * scale))
+                    .top(px(offset_y + (bounds.origin.y - content.origin.y) * scale))
+                    .w(px(bounds.size.width * scale))
+                    .h(px(bounds.size.height * scale))
+                    .rounded(px(frame.corner_radius() * scale))
+                    .when_some(frame.fill(), |this, fill| this.bg(fill))
+                    // Borders are drawn as hairlines, scaled down they'd disappear
+                    .when_some(
+                        frame.border_color().filter(|_| frame.border_width() > 0.),
+                        |this, color| this.border_1().border_color(color),
+                    )
+            }))
+    }
+}
+
+impl EventEmitter<TemplateGalleryEvent> for TemplateGallery {}
+
+impl Focusable for TemplateGallery {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TemplateGallery {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+
+        let cards = self.previews.iter().enumerate().map(|(index, preview)| {
+            let template = preview.template;
+            let is_selected = index == self.selected_index;
+            div()
+                .id(ElementId::Name(
+                    format!("template-{}", template.label()).into(),
+                ))
+                .flex()
+                .flex_col()
+                .gap_1()
+                .p_2()
+                .rounded(px(6.))
+                .border_1()
+                .border_color(if is_selected {
+                    theme.tokens.active_border
+                } else {
+                    theme.tokens.inactive_border
+                })
+                .when(is_selected, |this| this.bg(theme.tokens.surface0))
+                .hover(|this| this.bg(theme.tokens.surface0))
+                .on_click(cx.listener(move |_, _, _, cx| {
+                    cx.emit(TemplateGalleryEvent::Confirmed(template));
+                }))
+                .child(
+                    div()
+                        .rounded(px(4.))
+                        .bg(theme.tokens.background)
+                        .child(Self::render_thumbnail(preview)),
+                )
+                .child(div().text_color(theme.tokens.text).child(template.label()))
+                .child(
+                    div()
+                        .w(px(Self::THUMBNAIL_WIDTH))
+                        .text_size(px(10.))
+                        .text_color(theme.tokens.subtext0)
+                        .child(template.description()),
+                )
+        });
+
+        div()
+            .id("template-gallery-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .flex_col()
+            .items_center()
+            .pt(px(Titlebar::HEIGHT * 3.))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(TemplateGalleryEvent::Dismissed)),
+            )
+            .child(
+                div()
+                    .id("template-gallery")
+                    .key_context("TemplateGallery")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::confirm))
+                    .on_action(cx.listener(Self::dismiss))
+                    .on_action(cx.listener(Self::select_next))
+                    .on_action(cx.listener(Self::select_previous))
+                    // Keep clicks inside the gallery from reaching the backdrop
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .p_3()
+                    .text_size(px(12.))
+                    .bg(theme.tokens.background_secondary)
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .rounded(px(8.))
+                    .shadow_lg()
+                    .child(
+                        div()
+                            .text_color(theme.tokens.text)
+                            .child(if self.onboarding {
+                                "Welcome to Luna — start from a template"
+                            } else {
+                                "New from Template"
+                            }),
+                    )
+                    .child(div().flex().gap_2().children(cards))
+                    .child(
+                        div()
+                            .id("template-gallery-dismiss")
+                            .self_end()
+                            .px_2()
+                            .py_1()
+                            .rounded(px(4.))
+                            .text_color(theme.tokens.subtext0)
+                            .hover(|this| this.bg(theme.tokens.surface0))
+                            .on_click(
+                                cx.listener(|_, _, _, cx| cx.emit(TemplateGalleryEvent::Dismissed)),
+                            )
+                            .child(if self.onboarding {
+                                "Continue with the sample document"
+                            } else {
+                                "Cancel"
+                            }),
+                    ),
+            )
+    }
+}
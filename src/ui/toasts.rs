@@ -0,0 +1,181 @@
+//! Toasts stacked in the corner of the window for [`Notification`]s.
+//!
+//! There is one set of toasts for the app, kept in a global so anything holding an
+//! `App` can post to it, including code that runs before the window is open. Each
+//! toast can be dismissed, and ones with an action dispatch it from a button.
+
+use std::time::Instant;
+
+use gpui::{
+    div, prelude::*, px, App, ElementId, Entity, Global, IntoElement, MouseButton, Task, Window,
+};
+
+use crate::{
+    notifications::{Notification, NotificationLevel, Notifications},
+    theme::Theme,
+};
+
+use super::Titlebar;
+
+/// The app's toasts, posted to with [`Toasts::show`]
+pub struct GlobalToasts(pub Entity<Toasts>);
+
+impl Global for GlobalToasts {}
+
+pub struct Toasts {
+    notifications: Notifications,
+    /// Waits for the next toast to expire, replaced whenever toasts change
+    expiry: Option<Task<()>>,
+}
+
+impl Toasts {
+    const WIDTH: f32 = 320.;
+
+    pub fn init(cx: &mut App) {
+        let toasts = cx.new(|_| Self {
+            notifications: Notifications::default(),
+            expiry: None,
+        });
+        cx.set_global(GlobalToasts(toasts));
+    }
+
+    pub fn global(cx: &App) -> Entity<Toasts> {
+        cx.global::<GlobalToasts>().0.clone()
+    }
+
+    /// Post a notification to the app's toasts
+    pub fn show(notification: Notification, cx: &mut App) {
+        Self::global(cx).update(cx, |toasts, cx| toasts.push(notification, cx));
+    }
+
+    pub fn push(&mut self, notification: Notification, cx: &mut Context<Self>) {
+        self.notifications.push(notification, Instant::now());
+        self.schedule_expiry(cx);
+        cx.notify();
+    }
+
+    fn dismiss(&mut self, id: usize, cx: &mut Context<Self>) {
+        if self.notifications.dismiss(id).is_some() {
+            self.schedule_expiry(cx);
+            cx.notify();
+        }
+    }
+
+    fn schedule_expiry(&mut self, cx: &mut Context<Self>) {
+        let Some(next_expiry) = self.notifications.next_expiry() else {
+            self.expiry = None;
+            return;
+        };
+
+        let timer = cx
+            .background_executor()
+            .timer(next_expiry.saturating_duration_since(Instant::now()));
+        let this = cx.entity().downgrade();
+        let mut async_cx = cx.to_async();
+        self.expiry = Some(cx.foreground_executor().spawn(async move {
+            timer.await;
+            this.update(&mut async_cx, |toasts, cx| {
+                if toasts.notifications.expire(Instant::now()) {
+                    cx.notify();
+                }
+                toasts.schedule_expiry(cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn render_toast(
+        &self,
+        id: usize,
+        notification: &Notification,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let theme = Theme::get_global(cx);
+        let accent = match notification.level {
+            NotificationLevel::Info => theme.tokens.active_border,
+            NotificationLevel::Error => theme.tokens.error,
+        };
+
+        let button = |label: &str| {
+            div()
+                .id(ElementId::Name(format!("toast-{id}-{label}").into()))
+                .flex_none()
+                .px_1()
+                .rounded(px(4.))
+                .text_color(theme.tokens.subtext0)
+                .hover(|this| this.bg(theme.tokens.surface0).text_color(theme.tokens.text))
+                .child(label.to_string())
+        };
+
+        div()
+            .id(ElementId::Name(format!("toast-{id}").into()))
+            .occlude()
+            // Clicks on a toast shouldn't reach the canvas underneath
+            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .w(px(Self::WIDTH))
+            .flex()
+            .items_start()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .bg(theme.tokens.background_secondary)
+            .border_1()
+            .border_color(theme.tokens.inactive_border)
+            .rounded(px(6.))
+            .shadow_lg()
+            .child(
+                div()
+                    .flex_none()
+                    .mt(px(5.))
+                    .size(px(6.))
+                    .rounded_full()
+                    .bg(accent),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .gap_0p5()
+                    .child(notification.message.clone())
+                    .when_some(notification.detail.clone(), |this, detail| {
+                        this.child(
+                            div()
+                                .text_color(theme.tokens.foreground_muted)
+                                .child(detail),
+                        )
+                    }),
+            )
+            .when_some(notification.action.clone(), |this, action| {
+                this.child(button(&action.label).on_click(cx.listener(
+                    move |this, _, window, cx| {
+                        window.dispatch_action(action.action.boxed_clone(), cx);
+                        this.dismiss(id, cx);
+                    },
+                )))
+            })
+            .child(
+                button("Dismiss").on_click(cx.listener(move |this, _, _, cx| this.dismiss(id, cx))),
+            )
+    }
+}
+
+impl Render for Toasts {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let toasts: Vec<_> = self
+            .notifications
+            .iter()
+            .map(|(id, notification)| self.render_toast(id, notification, cx))
+            .collect();
+
+        // Newest on top, just under the titlebar
+        div()
+            .absolute()
+            .top(px(Titlebar::HEIGHT + 8.))
+            .right(px(8.))
+            .flex()
+            .flex_col_reverse()
+            .gap_1()
+            .children(toasts)
+    }
+}
@@ -0,0 +1,252 @@
+//! Version diff panel listing how the document differs from its saved version.
+//!
+//! The canvas marks the same differences over the nodes while the panel is open. Both
+//! show the comparison as it was when the panel opened. Clicking an added or changed
+//! node reveals it and leaves the panel open, so the list can be walked through.
+
+use gpui::{
+    actions, div, prelude::*, px, App, ElementId, Entity, EventEmitter, FocusHandle, Focusable,
+    Hsla, IntoElement, MouseButton, SharedString, Window,
+};
+
+use crate::{
+    canvas::LunaCanvas,
+    document_diff::DocumentDiff,
+    node::{NodeCommon, NodeId},
+    theme::Theme,
+};
+
+use super::Titlebar;
+
+actions!(version_diff, [Dismiss]);
+
+pub enum VersionDiffEvent {
+    /// An added or changed node was clicked
+    Reveal(NodeId),
+    Dismissed,
+}
+
+pub struct VersionDiff {
+    focus_handle: FocusHandle,
+    diff: DocumentDiff,
+    /// Names of the added nodes, which the diff only has ids for
+    added_names: Vec<(NodeId, SharedString)>,
+}
+
+impl VersionDiff {
+    const WIDTH: f32 = 420.;
+    const MAX_HEIGHT: f32 = 480.;
+
+    pub fn new(diff: DocumentDiff, canvas: &Entity<LunaCanvas>, cx: &mut Context<Self>) -> Self {
+        let canvas = canvas.read(cx);
+        let added_names = diff
+            .added
+            .iter()
+            .map(|&node_id| {
+                let name = canvas
+                    .get_node(node_id)
+                    .map_or_else(|| node_id.to_string(), |node| node.name().to_string());
+                (node_id, name.into())
+            })
+            .collect();
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            diff,
+            added_names,
+        }
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(VersionDiffEvent::Dismissed);
+    }
+
+    /// A row naming a node still in the document, clicking it reveals the node
+    fn node_row(
+        &self,
+        node_id: NodeId,
+        name: SharedString,
+        color: Hsla,
+        theme: &Theme,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .id(ElementId::Name(
+                format!("version-diff-{}", node_id.0).into(),
+            ))
+            .flex()
+            .items_center()
+            .gap(px(6.))
+            .px_2()
+            .py(px(2.))
+            .rounded(px(4.))
+            .hover(|this| this.bg(theme.tokens.surface0))
+            .on_click(cx.listener(move |_, _, _, cx| {
+                cx.emit(VersionDiffEvent::Reveal(node_id));
+            }))
+            .child(
+                div()
+                    .size(px(8.))
+                    .rounded(px(2.))
+                    .border_1()
+                    .border_color(color),
+            )
+            .child(name)
+    }
+}
+
+impl EventEmitter<VersionDiffEvent> for VersionDiff {}
+
+impl Focusable for VersionDiff {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for VersionDiff {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = Theme::get_global(cx).clone();
+        let palette = theme.palette.clone();
+
+        let section = |title: String| {
+            div().flex().flex_col().gap(px(2.)).child(
+                div()
+                    .px_2()
+                    .text_size(px(10.))
+                    .text_color(theme.tokens.subtext0)
+                    .child(title),
+            )
+        };
+
+        let added = section(format!("Added ({})", self.added_names.len())).children(
+            self.added_names
+                .iter()
+                .map(|(node_id, name)| {
+                    self.node_row(*node_id, name.clone(), palette.green, &theme, cx)
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        // Removed nodes are gone from the canvas, so there's nothing to reveal
+        let removed = section(format!("Removed ({})", self.diff.removed.len())).children(
+            self.diff.removed.iter().map(|removed| {
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(6.))
+                    .px_2()
+                    .py(px(2.))
+                    .child(
+                        div()
+                            .size(px(8.))
+                            .rounded(px(2.))
+                            .bg(palette.red.opacity(0.12))
+                            .border_1()
+                            .border_dashed()
+                            .border_color(palette.red),
+                    )
+                    .child(
+                        div()
+                            .text_color(theme.tokens.subtext0)
+                            .child(removed.name.clone()),
+                    )
+            }),
+        );
+
+        let changed = section(format!("Changed ({})", self.diff.changed.len())).children(
+            self.diff
+                .changed
+                .iter()
+                .map(|change| {
+                    // Property, saved value and current value side by side
+                    let cell = |text: String| div().flex_1().min_w_0().truncate().child(text);
+                    let properties = change.properties.iter().map(|property| {
+                        div()
+                            .flex()
+                            .gap_2()
+                            .pl(px(22.))
+                            .pr_2()
+                            .child(
+                                cell(property.property.to_string())
+                                    .text_color(theme.tokens.subtext0),
+                            )
+                            .child(cell(property.before.clone()).text_color(palette.red))
+                            .child(cell(property.after.clone()).text_color(palette.green))
+                    });
+
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap(px(2.))
+                        .pb_1()
+                        .child(self.node_row(
+                            change.node_id,
+                            change.name.clone().into(),
+                            palette.yellow,
+                            &theme,
+                            cx,
+                        ))
+                        .children(properties)
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        div()
+            .id("version-diff-backdrop")
+            .occlude()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .flex_col()
+            .items_center()
+            .pt(px(Titlebar::HEIGHT * 3.))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| cx.emit(VersionDiffEvent::Dismissed)),
+            )
+            .child(
+                div()
+                    .id("version-diff")
+                    .key_context("VersionDiff")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::dismiss))
+                    // Keep clicks inside the panel from reaching the backdrop
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(Self::WIDTH))
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .p_1()
+                    .text_size(px(12.))
+                    .text_color(theme.tokens.text)
+                    .bg(theme.tokens.background_secondary)
+                    .border_1()
+                    .border_color(theme.tokens.inactive_border)
+                    .rounded(px(8.))
+                    .shadow_lg()
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(theme.tokens.inactive_border)
+                            .child("Changes since the saved version"),
+                    )
+                    .child(
+                        div()
+                            .id("version-diff-changes")
+                            .max_h(px(Self::MAX_HEIGHT))
+                            .overflow_y_scroll()
+                            .flex()
+                            .flex_col()
+                            .gap_3()
+                            .p_1()
+                            .when(!self.diff.added.is_empty(), |this| this.child(added))
+                            .when(!self.diff.removed.is_empty(), |this| this.child(removed))
+                            .when(!self.diff.changed.is_empty(), |this| this.child(changed)),
+                    ),
+            )
+    }
+}
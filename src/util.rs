@@ -6,12 +6,13 @@
 //!
 //! The utilities fall into several categories:
 //! - Pixel coordinate management (rounding, point creation)
-//! - Input processing (keystroke parsing and creation)
+//! - Input processing (keystroke parsing, creation, display and serialization)
 //! - General-purpose helpers that are shared across multiple components
 
 #![allow(unused, dead_code)]
 
 use gpui::{Keystroke, Modifiers, Pixels, Point};
+use std::path::PathBuf;
 
 /// Rounds a floating-point pixel value to the nearest integer pixel
 ///
@@ -100,3 +101,111 @@ pub fn keystroke_builder(str: &str) -> Keystroke {
         key_char,
     }
 }
+
+/// Writes a keystroke in the `ctrl-alt-shift-cmd-fn-key` form key bindings are parsed from
+pub fn keystroke_source(keystroke: &Keystroke) -> String {
+    let modifiers = &keystroke.modifiers;
+    let mut source = String::new();
+    for (held, name) in [
+        (modifiers.control, "ctrl-"),
+        (modifiers.alt, "alt-"),
+        (modifiers.shift, "shift-"),
+        (modifiers.platform, "cmd-"),
+        (modifiers.function, "fn-"),
+    ] {
+        if held {
+            source.push_str(name);
+        }
+    }
+    source.push_str(&keystroke.key);
+    source
+}
+
+/// Formats a keystroke for display, using the symbols macOS menus use for modifiers
+///
+/// Modifiers are listed in the platform's conventional order (⌃⌥⇧⌘) followed by the
+/// key, so `shift-cmd-p` reads as `⇧⌘P`.
+pub fn format_keystroke(keystroke: &Keystroke) -> String {
+    let modifiers = &keystroke.modifiers;
+    let mut formatted = String::new();
+    for (held, symbol) in [
+        (modifiers.function, "fn "),
+        (modifiers.control, "⌃"),
+        (modifiers.alt, "⌥"),
+        (modifiers.shift, "⇧"),
+        (modifiers.platform, "⌘"),
+    ] {
+        if held {
+            formatted.push_str(symbol);
+        }
+    }
+
+    let key = match keystroke.key.as_str() {
+        "escape" => "Esc".to_string(),
+        "enter" => "↩".to_string(),
+        "tab" => "⇥".to_string(),
+        "backspace" => "⌫".to_string(),
+        "delete" => "⌦".to_string(),
+        "space" => "Space".to_string(),
+        "up" => "↑".to_string(),
+        "down" => "↓".to_string(),
+        "left" => "←".to_string(),
+        "right" => "→".to_string(),
+        key => key.to_uppercase(),
+    };
+    formatted.push_str(&key);
+    formatted
+}
+
+/// Turns a namespaced action name like `luna::ToggleSnapToGrid` into `Toggle snap to grid`
+///
+/// Acronyms are kept as they are, so `ToggleUI` becomes `Toggle UI`.
+pub fn humanize_action_name(name: &str) -> String {
+    let name = name.rsplit("::").next().unwrap_or(name);
+    let chars: Vec<char> = name.chars().collect();
+
+    let mut words: Vec<String> = Vec::new();
+    for (index, &c) in chars.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|index| chars[index]);
+        let next = chars.get(index + 1);
+        // A word starts at an uppercase letter after a lowercase one, or at the last
+        // letter of an acronym that runs into the next word
+        let starts_word = c.is_uppercase()
+            && previous.is_some_and(|previous| {
+                previous.is_lowercase()
+                    || previous.is_uppercase() && next.is_some_and(|next| next.is_lowercase())
+            });
+        match words.last_mut() {
+            Some(word) if !starts_word => word.push(c),
+            _ => words.push(c.to_string()),
+        }
+    }
+
+    words
+        .iter()
+        .enumerate()
+        .map(|(index, word)| {
+            let is_acronym = word.chars().count() > 1 && word.chars().all(char::is_uppercase);
+            if index == 0 || is_acronym {
+                word.clone()
+            } else {
+                word.to_lowercase()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Directory Luna keeps its configuration and persisted state in
+///
+/// Honors `$XDG_CONFIG_HOME` when it is set to an absolute path and otherwise uses
+/// `~/.config/luna` on every platform, so the location is the same wherever users
+/// sync their dotfiles from.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(base.join("luna"))
+}
@@ -0,0 +1,315 @@
+//! # Window State Persistence
+//!
+//! Remembers how the window was left — its frame, the panel layout, the open document
+//! and where the user was looking on each document's canvas — so the next launch picks
+//! up in the same place instead of a default window.
+//!
+//! State is stored as JSON in the config directory. A missing or unreadable file is
+//! never an error, it only means starting from defaults.
+//!
+//! Reopening the last document, with its viewport and selection, can be turned off.
+//! The window frame and panels are restored either way, since they describe the
+//! workspace rather than the work.
+
+use crate::{
+    document::ViewportState,
+    document_settings::DocumentSettings,
+    power::LowPowerSetting,
+    ui::{inspector::INSPECTOR_WIDTH, sidebar::Sidebar},
+    util::config_dir,
+};
+use anyhow::Context as _;
+use gpui::{point, px, size, Bounds, WindowBounds};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf};
+
+const STATE_FILE_NAME: &str = "window_state.json";
+
+/// Viewport key used for documents that have never been saved to disk
+pub const UNTITLED_DOCUMENT: &str = "untitled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowMode {
+    #[default]
+    Windowed,
+    Maximized,
+    Fullscreen,
+}
+
+/// Serializable mirror of [`WindowBounds`]
+///
+/// For maximized and fullscreen windows the bounds are the restore size, matching
+/// what GPUI reports and expects.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedWindowBounds {
+    pub mode: WindowMode,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl SavedWindowBounds {
+    pub fn from_window_bounds(window_bounds: WindowBounds) -> Self {
+        let (mode, bounds) = match window_bounds {
+            WindowBounds::Windowed(bounds) => (WindowMode::Windowed, bounds),
+            WindowBounds::Maximized(bounds) => (WindowMode::Maximized, bounds),
+            WindowBounds::Fullscreen(bounds) => (WindowMode::Fullscreen, bounds),
+        };
+
+        Self {
+            mode,
+            x: bounds.origin.x.0,
+            y: bounds.origin.y.0,
+            width: bounds.size.width.0,
+            height: bounds.size.height.0,
+        }
+    }
+
+    pub fn to_window_bounds(&self) -> WindowBounds {
+        let bounds = Bounds {
+            origin: point(px(self.x), px(self.y)),
+            size: size(px(self.width), px(self.height)),
+        };
+
+        match self.mode {
+            WindowMode::Windowed => WindowBounds::Windowed(bounds),
+            WindowMode::Maximized => WindowBounds::Maximized(bounds),
+            WindowMode::Fullscreen => WindowBounds::Fullscreen(bounds),
+        }
+    }
+}
+
+/// Visibility and width of the panels surrounding the canvas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanelState {
+    pub sidebar_visible: bool,
+    pub sidebar_width: f32,
+    pub inspector_visible: bool,
+    pub inspector_width: f32,
+    pub navigator_visible: bool,
+}
+
+impl Default for PanelState {
+    fn default() -> Self {
+        Self {
+            sidebar_visible: true,
+            sidebar_width: Sidebar::INITIAL_WIDTH,
+            inspector_visible: true,
+            inspector_width: INSPECTOR_WIDTH,
+            navigator_visible: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowState {
+    pub bounds: Option<SavedWindowBounds>,
+    /// The document that was open when the window was last closed
+    pub document: Option<PathBuf>,
+    pub panels: PanelState,
+    /// Last viewport per document, keyed by [`Self::document_key`]
+    pub viewports: HashMap<String, ViewportState>,
+    /// Unit and color format preferences per document, keyed like `viewports`
+    pub document_settings: HashMap<String, DocumentSettings>,
+    /// Selected node ids per saved document, keyed like `viewports`
+    pub selections: HashMap<String, Vec<usize>>,
+    /// Scroll to keep the selection in view when it's moved from the keyboard
+    pub follow_selection: bool,
+    /// Pull dragged layers onto the edges and centers of the layers around them
+    pub snap_to_layers: bool,
+    /// Show rulers along the top and left of the canvas
+    pub show_rulers: bool,
+    /// Reopen the last document at launch, rather than starting an untitled one
+    pub restore_session: bool,
+    /// When to save power at the cost of hover highlights and smooth transitions
+    pub low_power: LowPowerSetting,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            bounds: None,
+            document: None,
+            panels: PanelState::default(),
+            viewports: HashMap::new(),
+            document_settings: HashMap::new(),
+            selections: HashMap::new(),
+            follow_selection: true,
+            snap_to_layers: true,
+            show_rulers: false,
+            restore_session: true,
+            low_power: LowPowerSetting::default(),
+        }
+    }
+}
+
+impl WindowState {
+    fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(STATE_FILE_NAME))
+    }
+
+    /// Whether any state has been saved yet, false on the first launch
+    pub fn exists() -> bool {
+        Self::path().is_some_and(|path| path.exists())
+    }
+
+    /// Load the persisted state, falling back to defaults if there is none
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path().context("no config directory available")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// Viewports are keyed by document path so reopening a file restores its view
+    pub fn document_key(&self) -> String {
+        self.document
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_else(|| UNTITLED_DOCUMENT.to_string())
+    }
+
+    pub fn viewport(&self) -> Option<ViewportState> {
+        self.viewports.get(&self.document_key()).copied()
+    }
+
+    pub fn set_viewport(&mut self, viewport: ViewportState) {
+        self.viewports.insert(self.document_key(), viewport);
+    }
+
+    /// The selection the document was left with, empty for untitled documents
+    pub fn selection(&self) -> Vec<usize> {
+        self.selections
+            .get(&self.document_key())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Remember the selection of a saved document, untitled ones aren't reopened so
+    /// there's nothing to select in them
+    pub fn set_selection(&mut self, mut selection: Vec<usize>) {
+        if self.document.is_none() {
+            return;
+        }
+        selection.sort_unstable();
+        if selection.is_empty() {
+            self.selections.remove(&self.document_key());
+        } else {
+            self.selections.insert(self.document_key(), selection);
+        }
+    }
+
+    pub fn document_settings(&self) -> DocumentSettings {
+        self.document_settings
+            .get(&self.document_key())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_document_settings(&mut self, settings: DocumentSettings) {
+        self.document_settings.insert(self.document_key(), settings);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ColorFormat;
+
+    #[test]
+    fn test_window_bounds_round_trip() {
+        let saved = SavedWindowBounds {
+            mode: WindowMode::Maximized,
+            x: 10.0,
+            y: 20.0,
+            width: 1280.0,
+            height: 800.0,
+        };
+
+        let restored = SavedWindowBounds::from_window_bounds(saved.to_window_bounds());
+        assert_eq!(restored, saved);
+    }
+
+    #[test]
+    fn test_viewports_are_per_document() {
+        let mut state = WindowState::default();
+        let untitled = ViewportState {
+            scroll_x: 0.0,
+            scroll_y: 0.0,
+            zoom: 1.0,
+        };
+        state.set_viewport(untitled);
+
+        state.document = Some(PathBuf::from("/tmp/poster.luna"));
+        assert_eq!(state.viewport(), None);
+
+        let poster = ViewportState {
+            scroll_x: 120.0,
+            scroll_y: -40.0,
+            zoom: 2.0,
+        };
+        state.set_viewport(poster);
+        assert_eq!(state.viewport(), Some(poster));
+
+        state.document = None;
+        assert_eq!(state.viewport(), Some(untitled));
+    }
+
+    #[test]
+    fn test_document_settings_are_per_document() {
+        let mut state = WindowState::default();
+        state.set_document_settings(DocumentSettings {
+            color_format: ColorFormat::Hsl,
+            ..Default::default()
+        });
+
+        state.document = Some(PathBuf::from("/tmp/poster.luna"));
+        assert_eq!(state.document_settings(), DocumentSettings::default());
+
+        state.document = None;
+        assert_eq!(state.document_settings().color_format, ColorFormat::Hsl);
+    }
+
+    #[test]
+    fn test_selection_is_kept_for_saved_documents() {
+        let mut state = WindowState::default();
+        state.set_selection(vec![4, 2]);
+        assert!(state.selections.is_empty());
+
+        state.document = Some(PathBuf::from("/tmp/poster.luna"));
+        state.set_selection(vec![4, 2]);
+        assert_eq!(state.selection(), vec![2, 4]);
+
+        state.set_selection(Vec::new());
+        assert!(state.selections.is_empty());
+    }
+
+    #[test]
+    fn test_partial_state_uses_defaults() {
+        let state: WindowState =
+            serde_json::from_str(r#"{ "panels": { "sidebar_visible": false } }"#).unwrap();
+
+        assert_eq!(state.bounds, None);
+        assert!(!state.panels.sidebar_visible);
+        assert!(state.panels.inspector_visible);
+        assert_eq!(state.panels.sidebar_width, Sidebar::INITIAL_WIDTH);
+        assert!(state.follow_selection);
+        assert!(state.snap_to_layers);
+        assert!(!state.show_rulers);
+        assert!(state.restore_session);
+        assert_eq!(state.low_power, LowPowerSetting::OnBattery);
+    }
+}